@@ -0,0 +1,138 @@
+-- Schema as of the first versioned release. Databases created before migrations existed are brought
+-- up to this shape first, so every statement tolerates existing objects.
+-- {serial_primary_key} is replaced with the backend's auto-incrementing key type.
+
+CREATE TABLE IF NOT EXISTS opportunities (
+    id TEXT PRIMARY KEY,
+    pair TEXT NOT NULL,
+    buy_exchange TEXT NOT NULL,
+    sell_exchange TEXT NOT NULL,
+    buy_pair TEXT,
+    sell_pair TEXT,
+    sell_quote_rate TEXT NOT NULL DEFAULT '1',
+    buy_price TEXT NOT NULL,
+    sell_price TEXT NOT NULL,
+    profit_percentage TEXT NOT NULL,
+    profit_amount TEXT NOT NULL,
+    max_trade_size TEXT NOT NULL,
+    gross_profit TEXT NOT NULL DEFAULT '0',
+    buy_fee_cost TEXT NOT NULL DEFAULT '0',
+    sell_fee_cost TEXT NOT NULL DEFAULT '0',
+    gas_cost TEXT NOT NULL DEFAULT '0',
+    transfer_cost TEXT NOT NULL DEFAULT '0',
+    adjusted_profit_amount TEXT,
+    timestamp TEXT NOT NULL,
+    status TEXT NOT NULL,
+    closed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_opportunities_timestamp ON opportunities (timestamp);
+
+CREATE TABLE IF NOT EXISTS trades (
+    id TEXT PRIMARY KEY,
+    opportunity_id TEXT NOT NULL REFERENCES opportunities (id),
+    exchange TEXT NOT NULL,
+    pair TEXT NOT NULL,
+    side TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    filled_amount TEXT NOT NULL DEFAULT '0',
+    price TEXT NOT NULL,
+    fee_amount TEXT NOT NULL DEFAULT '0',
+    fee_asset TEXT,
+    status TEXT NOT NULL,
+    order_id TEXT,
+    tx_hash TEXT,
+    created_at TEXT NOT NULL,
+    executed_at TEXT,
+    simulated BIGINT NOT NULL DEFAULT 0,
+    failure_reason TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_trades_created_at ON trades (created_at);
+
+CREATE INDEX IF NOT EXISTS idx_trades_opportunity_id ON trades (opportunity_id);
+
+CREATE TABLE IF NOT EXISTS price_snapshots (
+    id {serial_primary_key},
+    exchange TEXT NOT NULL,
+    pair TEXT NOT NULL,
+    bid TEXT NOT NULL,
+    ask TEXT NOT NULL,
+    volume_24h TEXT,
+    timestamp TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_snapshots_timestamp ON price_snapshots (timestamp);
+
+CREATE TABLE IF NOT EXISTS order_book_snapshots (
+    id {serial_primary_key},
+    exchange TEXT NOT NULL,
+    pair TEXT NOT NULL,
+    bids TEXT NOT NULL,
+    asks TEXT NOT NULL,
+    timestamp TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_book_snapshots_timestamp ON order_book_snapshots (timestamp);
+
+CREATE TABLE IF NOT EXISTS hedge_positions (
+    id TEXT PRIMARY KEY,
+    opportunity_id TEXT NOT NULL REFERENCES opportunities (id),
+    exchange TEXT NOT NULL,
+    pair TEXT NOT NULL,
+    side TEXT NOT NULL,
+    size TEXT NOT NULL,
+    entry_price TEXT NOT NULL,
+    order_id TEXT,
+    status TEXT NOT NULL,
+    opened_at TEXT NOT NULL,
+    closed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_hedge_positions_status ON hedge_positions (status);
+
+CREATE TABLE IF NOT EXISTS portfolio_snapshots (
+    id {serial_primary_key},
+    total_value_usd TEXT NOT NULL,
+    balances TEXT NOT NULL,
+    timestamp TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_portfolio_snapshots_timestamp ON portfolio_snapshots (timestamp);
+
+CREATE TABLE IF NOT EXISTS trading_halts (
+    id TEXT PRIMARY KEY,
+    reason TEXT NOT NULL,
+    halted_at TEXT NOT NULL,
+    halted_until TEXT NOT NULL,
+    cleared_at TEXT
+);
+
+CREATE TABLE IF NOT EXISTS cross_chain_opportunities (
+    id TEXT PRIMARY KEY,
+    pair TEXT NOT NULL,
+    buy_exchange TEXT NOT NULL,
+    sell_exchange TEXT NOT NULL,
+    source_chain TEXT NOT NULL,
+    target_chain TEXT NOT NULL,
+    token_address TEXT NOT NULL,
+    buy_price TEXT NOT NULL,
+    sell_price TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    buy_swap_fee TEXT NOT NULL,
+    buy_gas_cost TEXT NOT NULL,
+    bridge_fees TEXT NOT NULL,
+    sell_swap_fee TEXT NOT NULL,
+    sell_gas_cost TEXT NOT NULL,
+    profit_estimate TEXT NOT NULL,
+    estimated_time_minutes BIGINT NOT NULL,
+    timestamp TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS nonces (
+    chain_id BIGINT NOT NULL,
+    address TEXT NOT NULL,
+    nonce BIGINT NOT NULL,
+    updated_at TEXT NOT NULL,
+    PRIMARY KEY (chain_id, address)
+);
@@ -0,0 +1,21 @@
+-- Recorded prices move to price_history, with 1-minute mid-price candles for pruned rows.
+
+ALTER TABLE price_snapshots RENAME TO price_history;
+
+DROP INDEX IF EXISTS idx_price_snapshots_timestamp;
+
+CREATE INDEX IF NOT EXISTS idx_price_history_timestamp ON price_history (timestamp);
+
+CREATE INDEX IF NOT EXISTS idx_price_history_pair_exchange ON price_history (pair, exchange, timestamp);
+
+CREATE TABLE IF NOT EXISTS price_candles (
+    exchange TEXT NOT NULL,
+    pair TEXT NOT NULL,
+    minute TEXT NOT NULL,
+    open TEXT NOT NULL,
+    high TEXT NOT NULL,
+    low TEXT NOT NULL,
+    close TEXT NOT NULL,
+    samples BIGINT NOT NULL,
+    PRIMARY KEY (exchange, pair, minute)
+);
@@ -0,0 +1,32 @@
+-- At most one active opportunity per route: improvements update it in place and are counted in
+-- updates, and a new row is only started once the previous one reached a terminal status.
+
+ALTER TABLE opportunities ADD COLUMN updates BIGINT NOT NULL DEFAULT 0;
+
+ALTER TABLE opportunities ADD COLUMN first_seen_at TEXT;
+
+UPDATE opportunities SET first_seen_at = timestamp;
+
+-- Rows superseded by a later active row on the same route were never closed; they end when the
+-- next one was found
+UPDATE opportunities SET
+    status = 'expired',
+    closed_at = (
+        SELECT MIN(newer.timestamp) FROM opportunities newer
+        WHERE newer.pair = opportunities.pair
+            AND newer.buy_exchange = opportunities.buy_exchange
+            AND newer.sell_exchange = opportunities.sell_exchange
+            AND (newer.timestamp > opportunities.timestamp
+                OR (newer.timestamp = opportunities.timestamp AND newer.id > opportunities.id))
+    )
+WHERE status = 'active' AND EXISTS (
+    SELECT 1 FROM opportunities newer
+    WHERE newer.status = 'active' AND newer.pair = opportunities.pair
+        AND newer.buy_exchange = opportunities.buy_exchange
+        AND newer.sell_exchange = opportunities.sell_exchange
+        AND (newer.timestamp > opportunities.timestamp
+            OR (newer.timestamp = opportunities.timestamp AND newer.id > opportunities.id))
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_opportunities_active_route
+    ON opportunities (pair, buy_exchange, sell_exchange) WHERE status = 'active';
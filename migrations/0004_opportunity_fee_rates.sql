@@ -0,0 +1,6 @@
+-- Taker fee rates (fractions) each leg was priced with, so fee-tier changes are visible per opportunity.
+-- Rows recorded before this migration were priced with rates that are no longer known.
+
+ALTER TABLE opportunities ADD COLUMN buy_fee_rate TEXT NOT NULL DEFAULT '0';
+
+ALTER TABLE opportunities ADD COLUMN sell_fee_rate TEXT NOT NULL DEFAULT '0';
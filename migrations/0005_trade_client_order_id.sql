@@ -0,0 +1,3 @@
+-- Client order id each leg was placed under, so an order whose placement timed out can be found again.
+
+ALTER TABLE trades ADD COLUMN client_order_id TEXT;
@@ -0,0 +1,9 @@
+-- Consecutive execution failures per route (the pair-buy-sell key of active opportunities), kept so a
+-- restart neither forgets a cooldown nor resets the backoff of a problem route
+CREATE TABLE IF NOT EXISTS route_failures (
+    route TEXT PRIMARY KEY,
+    consecutive_failures BIGINT NOT NULL,
+    cooled_until TEXT,
+    last_error TEXT,
+    updated_at TEXT NOT NULL
+);
@@ -0,0 +1,6 @@
+-- Opportunities above trading.max_profit_threshold are recorded but only executed once approved
+-- from the CLI (`approve <id>`), which stamps approved_at.
+
+ALTER TABLE opportunities ADD COLUMN requires_approval BIGINT NOT NULL DEFAULT 0;
+
+ALTER TABLE opportunities ADD COLUMN approved_at TEXT;
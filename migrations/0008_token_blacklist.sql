@@ -0,0 +1,10 @@
+-- Tokens that failed the pre-trade safety probe on a chain (fee-on-transfer above the limit, or not
+-- sellable), kept so they stay excluded from opportunity generation after a restart
+CREATE TABLE IF NOT EXISTS token_blacklist (
+    chain_id BIGINT NOT NULL,
+    token TEXT NOT NULL,
+    exchange TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    blacklisted_at TEXT NOT NULL,
+    PRIMARY KEY (chain_id, token)
+);
@@ -0,0 +1,4 @@
+-- Profit one more unit of base would have added at the chosen size, per unit in the buy quote. Positive
+-- when a balance, config or risk cap rather than the books bounded the size.
+
+ALTER TABLE opportunities ADD COLUMN marginal_profit TEXT NOT NULL DEFAULT '0';
@@ -0,0 +1,16 @@
+-- Net spread of every scanned route per cycle, in percent. Each row holds sums so that old samples can be
+-- rolled up into one downsampled row per route and hour without losing the mean or deviation.
+
+CREATE TABLE IF NOT EXISTS spread_history (
+    pair TEXT NOT NULL,
+    buy_exchange TEXT NOT NULL,
+    sell_exchange TEXT NOT NULL,
+    timestamp TEXT NOT NULL,
+    samples BIGINT NOT NULL,
+    net_sum DOUBLE PRECISION NOT NULL,
+    net_sum_squares DOUBLE PRECISION NOT NULL,
+    above_threshold BIGINT NOT NULL,
+    downsampled BIGINT NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_spread_history_timestamp ON spread_history (timestamp);
@@ -0,0 +1,9 @@
+-- Errors the bot recorded per component and UTC hour, with the latest message, so daily reports can be
+-- regenerated after the fact
+CREATE TABLE IF NOT EXISTS error_counts (
+    hour TEXT NOT NULL,
+    context TEXT NOT NULL,
+    count BIGINT NOT NULL,
+    last_message TEXT NOT NULL,
+    PRIMARY KEY (hour, context)
+);
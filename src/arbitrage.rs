@@ -1,403 +1,3239 @@
 use anyhow::Result;
 use chrono::Utc;
 use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
 use std::collections::HashMap;
-use std::time::Duration;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
 use tokio::time;
-use tracing::{info, warn, error, debug};
+use tracing::{info, warn, error, debug, debug_span, info_span, Instrument, Span};
 
-use crate::config::Config;
-use crate::exchanges::{ExchangeManager, Exchange};
-use crate::models::{ArbitrageOpportunity, OpportunityStatus, TradingPair, Price};
+use crate::config::{section_changed, Config};
+use crate::control::{self, ControlCommand, ControlHandle, ControlRequest, RuntimeSettings, CONTROL_CHANNEL_CAPACITY};
+use crate::exchanges::{self, AssetAliases, ExchangeManager, Exchange, OrderAmount, OrderOptions, OrderRequest, SymbolRules, TradingFees};
+use crate::models::{ArbitrageOpportunity, BalanceReport, CancellationReport, CrossChainArbitrage, HedgePosition, HedgeStatus, OpenOrdersReport, OpportunityStatus, OrderCancellation, PairScan, Portfolio, ScanReport, ScanRoute, SpreadAlert, SpreadCause, SpreadSample, Trade, TradingHalt, TradingPair, TradeSide, TradeStatus, Price, VenueQuote};
+use crate::utils;
 use crate::database::Database;
+use crate::balances::BalanceManager;
+use crate::blockchain::across::AcrossBridge;
+use crate::blockchain::atomic::{AtomicExecutor, AtomicPlan};
+use crate::blockchain::blocks::{ChainHead, HeadReceiver};
 use crate::blockchain::BlockchainManager;
+use crate::cooldown::RouteCooldowns;
+use crate::notifications::NotificationService;
+use crate::orders::{self, OrderTracker};
+use crate::paper::PaperPortfolio;
+use crate::pnl;
+use crate::profit::{self, Bps, Leg, ProfitBreakdown};
+use crate::config::{LegOrderType, PairSettings, ResidualQuote};
+use crate::crosschain::CrossChainScanner;
+use crate::equivalence::QuoteEquivalence;
+use crate::errors::BotError;
+use crate::rebalance::{RebalancePlan, Rebalancer, Transfer};
+use crate::recorder::MarketRecorder;
+use crate::report;
+use crate::status::{Heartbeat, StatusState};
+use crate::risk::{self, RiskManager};
+use crate::sanity::PriceSanity;
+use crate::scoring::{self, ScoreInputs};
+use crate::sizing::{Liquidity, Route};
+use crate::spreads::{self, SpreadTracker};
+use crate::token_safety::{TokenSafety, TokenStatus};
+use crate::valuation::ValuationService;
+
+const ORDER_POLL_INTERVAL: Duration = Duration::from_secs(1);
+// While the venue's account stream is up, fills are awaited from it and REST polls become a fallback
+const STREAMED_ORDER_POLL_INTERVAL: Duration = Duration::from_secs(5);
+// A leg is placed again only after its lookup by client id showed the earlier attempt never arrived
+const LEG_PLACEMENT_ATTEMPTS: usize = 2;
+const ACCOUNT_CHANNEL_CAPACITY: usize = 1024;
+const PRICE_CHANNEL_CAPACITY: usize = 1024;
+const REVALIDATION_DEPTH: usize = 20;
+// How long in-flight executions may keep polling their orders after a shutdown signal
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(30);
+const PRICE_HISTORY_PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+// How often the scheduler looks whether the reporting day has ended
+const DAILY_REPORT_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+const FILL_COUNT_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+// What expected execution latency is scored against when max_execution_latency_ms is unset
+const DEFAULT_LATENCY_BUDGET: Duration = Duration::from_secs(2);
 
 pub struct ArbitrageBot {
     config: Config,
     exchange_manager: ExchangeManager,
     blockchain_manager: BlockchainManager,
     database: Database,
+    notifications: NotificationService,
+    paper_portfolio: Mutex<PaperPortfolio>,
+    recorder: Option<MarketRecorder>,
+    valuation: ValuationService,
+    risk: RiskManager,
+    // Shared with the status server, which lists the routes cooling down
+    route_cooldowns: Arc<RouteCooldowns>,
+    token_safety: TokenSafety,
+    spreads: SpreadTracker,
+    balances: Arc<BalanceManager>,
+    order_tracker: Arc<OrderTracker>,
+    rebalancer: Rebalancer,
+    cross_chain: CrossChainScanner,
+    // Latest valuation, the base for the daily loss limit
+    portfolio: Option<Portfolio>,
     dry_run: bool,
-    active_opportunities: HashMap<String, ArbitrageOpportunity>,
+    // Set through the control API; while paused neither scanning nor execution runs
+    paused: bool,
+    // Published after every change to the settings above for the status server
+    settings: watch::Sender<RuntimeSettings>,
+    // Shared with the status server, which serves it as is; never held across an await
+    active_opportunities: Arc<RwLock<HashMap<String, ArbitrageOpportunity>>>,
+    latest_prices: HashMap<String, HashMap<String, Price>>,
+    price_sanity: PriceSanity,
+    quote_equivalence: QuoteEquivalence,
+    // Live block subscriptions by chain id; on-chain quotes are refreshed per block while these are up
+    chain_heads: HashMap<u64, HeadReceiver>,
+    stats: ScanStats,
+    // Set to the time the first shutdown signal arrived
+    shutdown: Arc<watch::Sender<Option<Instant>>>,
+    // Set from the control server; while engaged nothing is executed
+    kill_switch: Arc<watch::Sender<bool>>,
+    heartbeat: Arc<Heartbeat>,
+    started_at: Instant,
+    // Orders placed by this process that have not reached a terminal state, keyed by order id
+    open_orders: Mutex<HashMap<String, Trade>>,
+    // File re-read on SIGHUP; without one reloading is disabled
+    config_path: Option<String>,
+    // Executed and failed executions per route key, with when they were read from the database
+    fill_counts: Option<(Instant, HashMap<String, (u64, u64)>)>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ScanStats {
+    pub cycles: u64,
+    pub opportunities_found: u64,
+    pub opportunities_persisted: u64,
+    pub prices_rejected: u64,
+    pub rejections_by_kind: HashMap<&'static str, u64>,
+    pub opportunities_skipped: u64,
+    pub skips_by_reason: HashMap<&'static str, u64>,
+}
+
+// What one leg filled across its requotes; `unfilled` is the rest the venue would still have traded and
+// `open` the rest of an order that could not be cancelled, which is still on the book and may yet fill
+#[derive(Debug, Default)]
+struct LegFill {
+    trades: Vec<Trade>,
+    filled: Decimal,
+    unfilled: Decimal,
+    open: Decimal,
+}
+
+impl LegFill {
+    fn record(&mut self, trade: Trade) {
+        if matches!(trade.status, TradeStatus::Executed) {
+            self.filled += trade.amount;
+        }
+        self.trades.push(trade);
+    }
+    
+    fn executed(&self) -> impl Iterator<Item = &Trade> {
+        self.trades.iter().filter(|trade| matches!(trade.status, TradeStatus::Executed))
+    }
+    
+    fn average_price(&self) -> Decimal {
+        if self.filled.is_zero() {
+            return Decimal::ZERO;
+        }
+        self.executed().map(|trade| trade.amount * trade.price).sum::<Decimal>() / self.filled
+    }
+    
+    // Quote received for what was sold, net of fees charged in that quote
+    fn proceeds(&self, quote: &str) -> Decimal {
+        self.executed()
+            .map(|trade| {
+                let fee = if trade.fee_asset.as_deref() == Some(quote) { trade.fee_amount } else { Decimal::ZERO };
+                trade.amount * trade.price - fee
+            })
+            .sum()
+    }
+    
+    fn last_status(&self) -> Option<&TradeStatus> {
+        self.trades.last().map(|trade| &trade.status)
+    }
 }
 
 impl ArbitrageBot {
     pub async fn new(config: Config) -> Result<Self> {
         let mut exchange_manager = ExchangeManager::new();
+        // On-chain connectors take their nonce managers from here, so it comes before the exchanges
+        let database = Database::new(&config.database_url).await?;
+        database.migrate().await?;
+        let blockchain_manager = BlockchainManager::new(&config.blockchain).await?
+            .with_database(database.clone());
         
         for (name, exchange_config) in &config.exchanges {
             if exchange_config.enabled {
                 match name.as_str() {
                     "binance" => {
-                        let exchange = Box::new(crate::exchanges::binance::BinanceExchange::new(exchange_config.clone()));
+                        let exchange = Box::new(crate::exchanges::binance::BinanceExchange::new(exchange_config.clone())?);
                         exchange_manager.add_exchange(exchange);
                         info!("Initialized Binance exchange");
                     },
-                    "uniswap" => {
-                        let exchange = Box::new(crate::exchanges::uniswap::UniswapExchange::new(exchange_config.clone()).await?);
+                    "bitfinex" => {
+                        let exchange = Box::new(crate::exchanges::bitfinex::BitfinexExchange::new(exchange_config.clone())?);
+                        exchange_manager.add_exchange(exchange);
+                        info!("Initialized Bitfinex exchange");
+                    },
+                    "bybit" => {
+                        let exchange = Box::new(crate::exchanges::bybit::BybitExchange::new(exchange_config.clone())?);
+                        exchange_manager.add_exchange(exchange);
+                        info!("Initialized Bybit exchange");
+                    },
+                    "coinbase" => {
+                        let exchange = Box::new(crate::exchanges::coinbase::CoinbaseExchange::new(exchange_config.clone())?);
+                        exchange_manager.add_exchange(exchange);
+                        info!("Initialized Coinbase exchange");
+                    },
+                    "curve" => {
+                        let gas_policy = config.venue_chain(name, exchange_config)?
+                            .map(|(_, chain)| chain.gas_policy())
+                            .unwrap_or_default();
+                        let exchange = Box::new(crate::exchanges::curve::CurveExchange::new(exchange_config.clone(), config.trading.max_slippage, gas_policy).await?);
+                        exchange_manager.add_exchange(exchange);
+                        info!("Initialized Curve exchange");
+                    },
+                    "gateio" => {
+                        let exchange = Box::new(crate::exchanges::gateio::GateIoExchange::new(exchange_config.clone())?);
+                        exchange_manager.add_exchange(exchange);
+                        info!("Initialized Gate.io exchange");
+                    },
+                    "hyperliquid" => {
+                        let exchange = Box::new(crate::exchanges::hyperliquid::HyperliquidExchange::new(exchange_config.clone(), config.trading.max_slippage).await?);
+                        exchange_manager.add_exchange(exchange);
+                        info!("Initialized Hyperliquid exchange");
+                    },
+                    "kraken" => {
+                        let exchange = Box::new(crate::exchanges::kraken::KrakenExchange::new(exchange_config.clone())?);
+                        exchange_manager.add_exchange(exchange);
+                        info!("Initialized Kraken exchange");
+                    },
+                    "kucoin" => {
+                        let exchange = Box::new(crate::exchanges::kucoin::KucoinExchange::new(exchange_config.clone())?);
+                        exchange_manager.add_exchange(exchange);
+                        info!("Initialized KuCoin exchange");
+                    },
+                    "mexc" => {
+                        let exchange = Box::new(crate::exchanges::mexc::MexcExchange::new(exchange_config.clone())?);
+                        exchange_manager.add_exchange(exchange);
+                        info!("Initialized MEXC exchange");
+                    },
+                    "okx" => {
+                        let exchange = Box::new(crate::exchanges::okx::OkxExchange::new(exchange_config.clone())?);
+                        exchange_manager.add_exchange(exchange);
+                        info!("Initialized OKX exchange");
+                    },
+                    "uniswap" | "sushiswap" => {
+                        let params = match name.as_str() {
+                            "uniswap" => crate::exchanges::univ2::UniV2Params::uniswap()?,
+                            _ => crate::exchanges::univ2::UniV2Params::sushiswap()?,
+                        };
+                        // Without a chain key the entry's own RPC URL and api_secret key are used
+                        let exchange = Box::new(if exchange_config.chain.is_some() {
+                            let chain = config.enabled_venue_chain(name, exchange_config)?;
+                            crate::exchanges::univ2::UniV2StyleExchange::with_chain(exchange_config.clone(), chain, config.trading.max_slippage, params).await?
+                        } else {
+                            crate::exchanges::univ2::UniV2StyleExchange::new(exchange_config.clone(), config.trading.max_slippage, params).await?
+                        });
+                        exchange_manager.add_exchange(exchange);
+                        info!("Initialized {} exchange", name);
+                    },
+                    "0x" => {
+                        let chain = config.enabled_venue_chain(name, exchange_config)?;
+                        let exchange = Box::new(crate::exchanges::zerox::ZeroExExchange::new(exchange_config.clone(), chain, config.trading.max_slippage)?);
+                        exchange_manager.add_exchange(exchange);
+                        info!("Initialized 0x exchange");
+                    },
+                    "1inch" => {
+                        let chain = config.enabled_venue_chain(name, exchange_config)?;
+                        let exchange = Box::new(crate::exchanges::oneinch::OneInchExchange::new(exchange_config.clone(), chain, config.trading.max_slippage).await?);
+                        exchange_manager.add_exchange(exchange);
+                        info!("Initialized 1inch exchange");
+                    },
+                    "pancakeswap" => {
+                        let chain = config.enabled_venue_chain(name, exchange_config)?;
+                        let params = crate::exchanges::univ2::UniV2Params::pancakeswap()?;
+                        let exchange = Box::new(crate::exchanges::univ2::UniV2StyleExchange::with_chain(exchange_config.clone(), chain, config.trading.max_slippage, params).await?);
+                        exchange_manager.add_exchange(exchange);
+                        info!("Initialized PancakeSwap exchange");
+                    },
+                    "quickswap" => {
+                        let chain = config.enabled_venue_chain(name, exchange_config)?;
+                        let params = crate::exchanges::univ2::UniV2Params::quickswap()?;
+                        let exchange = Box::new(crate::exchanges::univ2::UniV2StyleExchange::with_chain(exchange_config.clone(), chain, config.trading.max_slippage, params).await?);
+                        exchange_manager.add_exchange(exchange);
+                        info!("Initialized QuickSwap exchange");
+                    },
+                    "uniswap_v3" => {
+                        let chain = config.venue_chain(name, exchange_config)?.map(|(_, chain)| chain);
+                        let exchange = Box::new(crate::exchanges::uniswap_v3::UniswapV3Exchange::new(exchange_config.clone(), config.trading.max_slippage, chain, &blockchain_manager).await?);
                         exchange_manager.add_exchange(exchange);
-                        info!("Initialized Uniswap exchange");
+                        info!("Initialized Uniswap V3 exchange");
                     },
                     _ => {
                         warn!("Unknown exchange: {}", name);
                     }
                 }
+                
+                if let Some(fees) = &exchange_config.fees {
+                    exchange_manager.set_fee_override(name, TradingFees {
+                        maker_fee: fees.maker_fee,
+                        taker_fee: fees.taker_fee,
+                    });
+                }
+                if !exchange_config.asset_aliases.is_empty() {
+                    let aliases = AssetAliases::new(&exchange_config.asset_aliases)
+                        .map_err(|e| anyhow::anyhow!("Invalid asset_aliases for {}: {}", name, e))?;
+                    exchange_manager.set_aliases(name, aliases);
+                }
+                
+                if exchange_config.mode == crate::config::ExchangeMode::DataOnly {
+                    exchange_manager.set_mode(name, exchange_config.mode);
+                    info!("{} is data-only: used for price discovery, never for execution", name);
+                }
             }
         }
         
-        let blockchain_manager = BlockchainManager::new(&config.blockchain).await?;
-        let database = Database::new(&config.database_url).await?;
+        Self::check_hedge_exchange(&config, &exchange_manager)?;
+        
+        Ok(Self::with_components(config, exchange_manager, blockchain_manager, database))
+    }
+    
+    fn check_hedge_exchange(config: &Config, exchange_manager: &ExchangeManager) -> Result<()> {
+        if config.trading.hedge_on_leg_failure {
+            let hedge_exchange = config.trading.hedge_exchange.as_deref()
+                .ok_or_else(|| anyhow::anyhow!("hedge_on_leg_failure requires hedge_exchange to be configured"))?;
+            let exchange = exchange_manager.get_exchange(hedge_exchange)
+                .ok_or_else(|| anyhow::anyhow!("Hedge exchange {} is not enabled", hedge_exchange))?;
+            if exchange.hedge_venue().is_none() {
+                anyhow::bail!("Exchange {} cannot open hedge positions", hedge_exchange);
+            }
+            if !exchange_manager.can_execute(hedge_exchange) {
+                anyhow::bail!("Hedge exchange {} is configured as data-only", hedge_exchange);
+            }
+        }
+        Ok(())
+    }
+    
+    pub fn with_components(
+        config: Config,
+        mut exchange_manager: ExchangeManager,
+        blockchain_manager: BlockchainManager,
+        database: Database,
+    ) -> Self {
+        exchange_manager.set_timeouts(config.trading.timeouts.clone());
+        exchange_manager.set_health_config(config.trading.health.clone());
+        let notifications = NotificationService::new(config.notifications.as_ref());
+        let recorder = config.trading.record_market_data
+            .then(|| MarketRecorder::spawn(database.clone()));
         
-        Ok(Self {
+        let valuation = ValuationService::new(&config.trading.valuation);
+        let risk = RiskManager::new(config.trading.risk_management.clone());
+        let route_cooldowns = RouteCooldowns::new(config.trading.route_cooldown.clone());
+        let token_safety = TokenSafety::new(config.trading.token_safety.clone());
+        let spreads = SpreadTracker::new(config.trading.spread_history.clone());
+        let buffers = config.exchanges.iter()
+            .map(|(name, exchange)| (name.clone(), exchange.reserve_balance.clone()))
+            .collect();
+        let rebalancer = Rebalancer::new(config.rebalancing.clone());
+        let bridge = AcrossBridge::new(&config.cross_chain.bridge_api_url, &blockchain_manager);
+        let cross_chain = CrossChainScanner::new(config.cross_chain.clone(), Box::new(bridge));
+        let balances = BalanceManager::new(Duration::from_millis(config.trading.balance_cache_ttl_ms), buffers);
+        let price_sanity = PriceSanity::new(config.trading.price_sanity.clone());
+        let quote_equivalence = QuoteEquivalence::new(config.trading.quote_equivalence.clone());
+        let settings = watch::channel(RuntimeSettings {
+            paused: false,
+            dry_run: false,
+            min_profit_threshold: config.trading.min_profit_threshold,
+            max_concurrent_trades: config.trading.max_concurrent_trades,
+        }).0;
+        
+        let chain_heads = blockchain_manager.chain_heads().clone();
+        for exchange in exchange_manager.get_all_exchanges() {
+            if let Some(heads) = exchange.chain_id().and_then(|chain_id| chain_heads.get(&chain_id)) {
+                exchange.watch_chain_head(heads.clone());
+            }
+        }
+        
+        Self {
             config,
             exchange_manager,
             blockchain_manager,
             database,
+            notifications,
+            paper_portfolio: Mutex::new(PaperPortfolio::default()),
+            recorder,
+            valuation,
+            risk,
+            route_cooldowns: Arc::new(route_cooldowns),
+            token_safety,
+            spreads,
+            balances: Arc::new(balances),
+            order_tracker: Arc::new(OrderTracker::default()),
+            rebalancer,
+            cross_chain,
+            portfolio: None,
             dry_run: false,
-            active_opportunities: HashMap::new(),
+            paused: false,
+            settings,
+            active_opportunities: Arc::new(RwLock::new(HashMap::new())),
+            latest_prices: HashMap::new(),
+            price_sanity,
+            quote_equivalence,
+            chain_heads,
+            stats: ScanStats::default(),
+            shutdown: Arc::new(watch::channel(None).0),
+            kill_switch: Arc::new(watch::channel(false).0),
+            heartbeat: Arc::new(Heartbeat::default()),
+            started_at: Instant::now(),
+            open_orders: Mutex::new(HashMap::new()),
+            config_path: None,
+            fill_counts: None,
+        }
+    }
+    
+    pub fn stats(&self) -> &ScanStats {
+        &self.stats
+    }
+    
+    pub fn database(&self) -> &Database {
+        &self.database
+    }
+    
+    pub fn exchange_manager(&self) -> &ExchangeManager {
+        &self.exchange_manager
+    }
+    
+    // Everything the status server reads, shared rather than copied
+    pub fn status_state(&self) -> StatusState {
+        StatusState {
+            started_at: self.started_at,
+            settings: self.settings.subscribe(),
+            max_tick_age: Duration::from_secs(self.config.status.max_tick_age_seconds),
+            heartbeat: self.heartbeat.clone(),
+            kill_switch: self.kill_switch.clone(),
+            health: self.exchange_manager.health_tracker(),
+            active_opportunities: self.active_opportunities.clone(),
+            route_cooldowns: self.route_cooldowns.clone(),
+            database: self.database.clone(),
+            control: None,
+        }
+    }
+    
+    fn publish_settings(&self) {
+        self.settings.send_replace(RuntimeSettings {
+            paused: self.paused,
+            dry_run: self.dry_run,
+            min_profit_threshold: self.config.trading.min_profit_threshold,
+            max_concurrent_trades: self.config.trading.max_concurrent_trades,
+        });
+    }
+    
+    pub fn active_opportunity_count(&self) -> usize {
+        self.active_opportunities.read().unwrap().len()
+    }
+    
+    pub fn set_config_path(&mut self, path: &str) {
+        self.config_path = Some(path.to_string());
+    }
+    
+    // Re-reads the config file and applies what can change under a running loop. Nothing is touched
+    // until the new file has loaded and validated, so a failed reload leaves the old config in effect.
+    fn reload_config(&mut self) -> Result<()> {
+        let path = self.config_path.as_deref()
+            .ok_or_else(|| anyhow::anyhow!("Bot was not started from a config file"))?;
+        let loaded = Config::load(path)?;
+        let (new, restart_required) = self.config.merge_reload(loaded);
+        // Checked again against the running exchanges, which is what the new settings will apply to
+        new.validate()?;
+        Self::check_hedge_exchange(&new, &self.exchange_manager)?;
+        
+        for section in &restart_required {
+            warn!("Config section {} changed on disk; restart required to apply it, keeping the running values", section);
+        }
+        
+        let old = &self.config.trading;
+        let trading = &new.trading;
+        self.exchange_manager.set_timeouts(trading.timeouts.clone());
+        if section_changed(&old.health, &trading.health) {
+            self.exchange_manager.set_health_config(trading.health.clone());
+        }
+        self.price_sanity.set_config(trading.price_sanity.clone());
+        self.quote_equivalence = QuoteEquivalence::new(trading.quote_equivalence.clone());
+        self.valuation = ValuationService::new(&trading.valuation);
+        self.risk.set_limits(trading.risk_management.clone());
+        self.route_cooldowns.set_config(trading.route_cooldown.clone());
+        self.token_safety.set_config(trading.token_safety.clone());
+        self.spreads.set_config(trading.spread_history.clone());
+        self.balances.set_ttl(Duration::from_millis(trading.balance_cache_ttl_ms));
+        if trading.record_market_data != old.record_market_data {
+            self.recorder = trading.record_market_data
+                .then(|| MarketRecorder::spawn(self.database.clone()));
+        }
+        if section_changed(&self.config.notifications, &new.notifications) {
+            self.notifications = NotificationService::new(new.notifications.as_ref());
+        }
+        
+        self.config = new;
+        self.publish_settings();
+        info!("Config reloaded from {}", path);
+        Ok(())
+    }
+    
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+        self.publish_settings();
+        if dry_run {
+            info!("Bot running in DRY RUN mode - no actual trades will be executed");
+        }
+    }
+    
+    // Called by the main loop between cycles. Trading changes go through the same validation as the
+    // config file and last until the next reload.
+    pub fn apply_control(&mut self, command: ControlCommand) -> Result<String> {
+        let outcome = match &command {
+            ControlCommand::Pause if self.paused => "already paused".to_string(),
+            ControlCommand::Pause => {
+                self.paused = true;
+                "paused: scanning and execution stopped".to_string()
+            },
+            ControlCommand::Resume if !self.paused => "already running".to_string(),
+            ControlCommand::Resume => {
+                self.paused = false;
+                "resumed: scanning and execution restarted".to_string()
+            },
+            ControlCommand::DryRun(dry_run) if *dry_run == self.dry_run => {
+                format!("dry-run already {}", if *dry_run { "on" } else { "off" })
+            },
+            ControlCommand::DryRun(dry_run) => {
+                self.set_dry_run(*dry_run);
+                if *dry_run { "dry-run on: no trades will be executed".to_string() } else { "dry-run off: live trading".to_string() }
+            },
+            ControlCommand::Trading(adjustment) => {
+                if adjustment.min_profit_threshold.is_none() && adjustment.max_concurrent_trades.is_none() {
+                    anyhow::bail!("no trading settings given");
+                }
+                let mut new = self.config.clone();
+                if let Some(threshold) = adjustment.min_profit_threshold {
+                    new.trading.min_profit_threshold = threshold;
+                }
+                if let Some(max) = adjustment.max_concurrent_trades {
+                    new.trading.max_concurrent_trades = max;
+                }
+                new.validate()?;
+                
+                self.config = new;
+                format!("min_profit_threshold {}%, max_concurrent_trades {}",
+                        self.config.trading.min_profit_threshold, self.config.trading.max_concurrent_trades)
+            },
+        };
+        self.publish_settings();
+        
+        info!("Control action {}: {}", command, outcome);
+        if self.config.control.notify {
+            self.notifications.control_action(&command.to_string(), &outcome);
+        }
+        Ok(outcome)
+    }
+    
+    fn on_control_request(&mut self, request: ControlRequest) {
+        let command = request.command.clone();
+        let outcome = self.apply_control(request.command).map_err(|e| {
+            warn!("Control action {} rejected: {}", command, e);
+            e.to_string()
+        });
+        let _ = request.reply.send(outcome);
+    }
+    
+    pub async fn run(&mut self) -> Result<()> {
+        info!("Starting arbitrage bot main loop");
+        self.notifications.bot_started(self.dry_run);
+        self.check_connectivity().await;
+        
+        if let Err(e) = self.recover_in_flight_state().await {
+            error!("Failed to recover in-flight state: {}", e);
+        }
+        if let Err(e) = self.reconcile_hedge_positions().await {
+            error!("Failed to reconcile open hedge positions: {}", e);
+        }
+        if let Err(e) = self.restore_route_cooldowns().await {
+            error!("Failed to restore route cooldowns: {}", e);
+        }
+        if let Err(e) = self.restore_token_blacklist().await {
+            error!("Failed to restore the token blacklist: {}", e);
+        }
+        if let Err(e) = self.restore_spread_history().await {
+            error!("Failed to restore spread history: {}", e);
+        }
+        
+        let (price_tx, mut price_rx) = mpsc::channel(PRICE_CHANNEL_CAPACITY);
+        for exchange in self.exchange_manager.get_all_exchanges() {
+            exchange.subscribe_prices(price_tx.clone());
+        }
+        drop(price_tx);
+        
+        let (account_tx, account_rx) = mpsc::channel(ACCOUNT_CHANNEL_CAPACITY);
+        for exchange in self.exchange_manager.get_all_exchanges() {
+            exchange.subscribe_account(account_tx.clone());
+        }
+        drop(account_tx);
+        tokio::spawn(orders::track_account_events(account_rx, self.order_tracker.clone(), self.balances.clone()));
+        
+        // The timer below keeps refreshing every venue, which covers chains whose subscription is down
+        let (head_tx, mut head_rx) = mpsc::channel(PRICE_CHANNEL_CAPACITY);
+        for (&chain_id, heads) in &self.chain_heads {
+            let mut heads = heads.clone();
+            let head_tx = head_tx.clone();
+            tokio::spawn(async move {
+                while heads.changed().await.is_ok() {
+                    let head = *heads.borrow_and_update();
+                    if let Some(head) = head {
+                        if head_tx.send((chain_id, head)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            });
+        }
+        drop(head_tx);
+        
+        let mut interval = time::interval(Duration::from_secs(self.config.trading.check_interval_seconds));
+        let mut debounce = Duration::from_millis(self.config.trading.event_debounce_ms);
+        let mut dirty_pairs: HashMap<String, TradingPair> = HashMap::new();
+        let mut debounce_deadline: Option<time::Instant> = None;
+        
+        let mut snapshot_interval_seconds = self.config.trading.valuation.snapshot_interval_seconds;
+        let mut snapshot_interval = time::interval(Duration::from_secs(snapshot_interval_seconds.max(1)));
+        let auto_rebalance = self.config.rebalancing.auto;
+        let mut rebalance_interval = time::interval(Duration::from_secs(self.config.rebalancing.interval_seconds.max(1)));
+        let scan_cross_chain = self.config.cross_chain.enabled;
+        let mut cross_chain_interval = time::interval(Duration::from_secs(self.config.cross_chain.scan_interval_seconds.max(1)));
+        let mut prune_interval = time::interval(PRICE_HISTORY_PRUNE_INTERVAL);
+        let mut report_interval = time::interval(DAILY_REPORT_CHECK_INTERVAL);
+        // The day under way at startup is reported when it ends; earlier days only through `report`
+        let mut report_day = None;
+        
+        let mut shutdown = self.shutdown.subscribe();
+        spawn_signal_listener(self.shutdown.clone());
+        let mut reload_rx = spawn_reload_listener();
+        
+        let mut kill_switch = self.kill_switch.subscribe();
+        let (control_tx, mut control_rx) = mpsc::channel(CONTROL_CHANNEL_CAPACITY);
+        if self.config.control.enabled {
+            match tokio::net::TcpListener::bind(&self.config.control.listen_addr).await {
+                Ok(listener) => {
+                    info!("Control server listening on {}", self.config.control.listen_addr);
+                    tokio::spawn(crate::control::serve(listener, self.kill_switch.clone()));
+                },
+                Err(e) => warn!("Control server disabled, cannot bind {}: {}", self.config.control.listen_addr, e),
+            }
+        }
+        if self.config.status.enabled {
+            match tokio::net::TcpListener::bind(&self.config.status.listen_addr).await {
+                Ok(listener) => {
+                    info!("Status server listening on {}", self.config.status.listen_addr);
+                    let control = self.config.control.api_token.clone()
+                        .map(|token| ControlHandle { token, commands: control_tx.clone() });
+                    if control.is_some() {
+                        info!("Control API enabled on the status server");
+                    }
+                    tokio::spawn(crate::status::serve(listener, StatusState { control, ..self.status_state() }));
+                },
+                Err(e) => warn!("Status server disabled, cannot bind {}: {}", self.config.status.listen_addr, e),
+            }
+        }
+        
+        while !self.shutdown_requested() {
+            tokio::select! {
+                _ = interval.tick() => {
+                    self.heartbeat.tick();
+                    if self.paused {
+                        continue;
+                    }
+                    match self.run_cycle().await {
+                        Ok(()) => self.notifications.clear_error("main loop"),
+                        Err(e) => {
+                            error!("Error in main loop: {}", e);
+                            self.record_error("main loop", &e);
+                            tokio::time::sleep(Duration::from_secs(5)).await;
+                        }
+                    }
+                },
+                _ = snapshot_interval.tick(), if snapshot_interval_seconds > 0 => {
+                    if let Err(e) = self.snapshot_portfolio().await {
+                        warn!("Failed to snapshot portfolio: {}", e);
+                    }
+                },
+                _ = cross_chain_interval.tick(), if scan_cross_chain => {
+                    match self.scan_cross_chain().await {
+                        Ok(routes) => for route in &routes {
+                            info!("Cross-chain route: buy {} on {} ({}), bridge to {}, sell on {}: ${:.2} estimated profit, ~{} min",
+                                  route.pair.base, route.buy_exchange, route.source_chain, route.target_chain,
+                                  route.sell_exchange, route.profit_estimate, route.estimated_time_minutes);
+                        },
+                        Err(e) => warn!("Cross-chain scan failed: {}", e),
+                    }
+                },
+                _ = prune_interval.tick() => {
+                    self.prune_price_history();
+                    self.prune_spread_history();
+                },
+                _ = report_interval.tick() => self.check_daily_report(&mut report_day).await,
+                _ = rebalance_interval.tick(), if auto_rebalance => {
+                    if let Err(e) = self.auto_rebalance().await {
+                        warn!("Automatic rebalancing failed: {}", e);
+                    }
+                },
+                Some(price) = price_rx.recv() => {
+                    let pair = self.exchange_manager.canonical_pair(&price.exchange, &price.pair);
+                    self.record_price(price);
+                    
+                    if debounce_deadline.is_none() {
+                        debounce_deadline = Some(time::Instant::now() + debounce);
+                    }
+                    dirty_pairs.insert(pair.symbol.clone(), pair);
+                },
+                Some((chain_id, head)) = head_rx.recv() => {
+                    debug!("New head on chain {}: block {}", chain_id, head.number);
+                    let pairs = self.refresh_chain_prices(chain_id).await;
+                    
+                    if !pairs.is_empty() && debounce_deadline.is_none() {
+                        debounce_deadline = Some(time::Instant::now() + debounce);
+                    }
+                    for pair in pairs {
+                        dirty_pairs.insert(pair.symbol.clone(), pair);
+                    }
+                },
+                _ = time::sleep_until(debounce_deadline.unwrap_or_else(time::Instant::now)), if debounce_deadline.is_some() => {
+                    debounce_deadline = None;
+                    let pairs: Vec<_> = dirty_pairs.drain().map(|(_, pair)| pair).collect();
+                    
+                    if self.paused {
+                        continue;
+                    }
+                    if let Err(e) = self.evaluate_updated_pairs(&pairs).await {
+                        error!("Error evaluating price updates: {}", e);
+                    }
+                },
+                Some(request) = control_rx.recv() => self.on_control_request(request),
+                Ok(()) = kill_switch.changed() => {
+                    let engaged = *kill_switch.borrow_and_update();
+                    self.on_kill_switch(engaged).await;
+                },
+                Some(()) = reload_rx.recv() => {
+                    let check_interval_seconds = self.config.trading.check_interval_seconds;
+                    let previous_snapshot_seconds = snapshot_interval_seconds;
+                    match self.reload_config() {
+                        Ok(()) => {
+                            let trading = &self.config.trading;
+                            if trading.check_interval_seconds != check_interval_seconds {
+                                interval = time::interval(Duration::from_secs(trading.check_interval_seconds));
+                            }
+                            debounce = Duration::from_millis(trading.event_debounce_ms);
+                            snapshot_interval_seconds = trading.valuation.snapshot_interval_seconds;
+                            if snapshot_interval_seconds != previous_snapshot_seconds {
+                                snapshot_interval = time::interval(Duration::from_secs(snapshot_interval_seconds.max(1)));
+                            }
+                        },
+                        Err(e) => error!("Config reload failed, keeping the previous config: {}", e),
+                    }
+                },
+                _ = shutdown.changed() => {}
+            }
+        }
+        
+        info!("Main loop stopped, cleaning up before exit");
+        self.shutdown_cleanup().await;
+        self.notifications.bot_stopped().await;
+        Ok(())
+    }
+    
+    async fn on_kill_switch(&self, engaged: bool) {
+        if engaged {
+            warn!("Kill switch engaged: execution stopped, cancelling open orders; scanning continues");
+            self.cancel_open_orders().await;
+        } else {
+            info!("Kill switch released: execution resumed");
+        }
+        self.notifications.kill_switch(engaged);
+    }
+    
+    pub fn kill_switch_engaged(&self) -> bool {
+        *self.kill_switch.borrow()
+    }
+    
+    fn shutdown_requested(&self) -> bool {
+        self.shutdown.borrow().is_some()
+    }
+    
+    fn shutdown_grace_expired(&self) -> bool {
+        self.shutdown.borrow()
+            .map(|requested_at| requested_at.elapsed() >= SHUTDOWN_GRACE_PERIOD)
+            .unwrap_or(false)
+    }
+    
+    async fn shutdown_cleanup(&mut self) {
+        self.cancel_open_orders().await;
+        
+        let keys: Vec<_> = self.active_opportunities.read().unwrap().keys().cloned().collect();
+        if let Err(e) = self.expire_opportunities(&keys).await {
+            warn!("Failed to expire active opportunities on shutdown: {}", e);
+        }
+        
+        if let Some(recorder) = self.recorder.take() {
+            recorder.flush().await;
+        }
+        self.database.close().await;
+    }
+    
+    async fn cancel_open_orders(&self) {
+        let orders: Vec<_> = self.open_orders.lock().unwrap().drain().map(|(_, trade)| trade).collect();
+        if !orders.is_empty() {
+            info!("Cancelling {} open orders", orders.len());
+        }
+        
+        for mut trade in orders {
+            let (Some(exchange), Some(order_id)) = (self.exchange_manager.get_exchange(&trade.exchange), trade.order_id.clone()) else {
+                continue;
+            };
+            
+            let cancel = exchange.cancel_order(&order_id);
+            match self.exchange_manager.call(exchange.name(), "cancel_order", self.exchange_manager.timeouts().order(), cancel).await {
+                Ok(()) => {
+                    trade.status = TradeStatus::Cancelled;
+                    if let Err(e) = self.database.update_trade_status(&trade).await {
+                        warn!("Failed to record cancellation of order {}: {}", order_id, e);
+                    }
+                    info!("Cancelled open order {} on {}", order_id, trade.exchange);
+                },
+                Err(e) => error!("Failed to cancel order {} on {}, it may still be open: {}", order_id, trade.exchange, e),
+            }
+        }
+    }
+    
+    async fn snapshot_portfolio(&mut self) -> Result<()> {
+        let portfolio = self.valuation.value_portfolio(&self.exchange_manager).await?;
+        self.database.save_portfolio_snapshot(&portfolio).await?;
+        info!("Portfolio value: ${:.2} across {} assets", portfolio.total_value_usd, portfolio.balances.len());
+        self.portfolio = Some(portfolio);
+        Ok(())
+    }
+    
+    // Runs in the background: a large first rollup must not hold up the main loop
+    fn prune_price_history(&self) {
+        let Some(days) = self.config.trading.keep_price_history_days else {
+            return;
+        };
+        let before = chrono::Utc::now() - chrono::Duration::days(days as i64);
+        let roll_up = self.config.trading.roll_up_price_history;
+        let database = self.database.clone();
+        
+        tokio::spawn(async move {
+            match database.prune_price_history(before, roll_up).await {
+                Ok(pruning) if pruning.deleted > 0 => {
+                    info!("Pruned {} recorded prices older than {} days into {} candles", pruning.deleted, days, pruning.candles);
+                },
+                Ok(_) => {},
+                Err(e) => warn!("Failed to prune price history: {}", e),
+            }
+        });
+    }
+    
+    async fn check_daily_report(&self, report_day: &mut Option<chrono::NaiveDate>) {
+        let offset = match self.config.reports.offset() {
+            Ok(offset) => offset,
+            Err(e) => {
+                warn!("Daily report skipped: {}", e);
+                return;
+            }
+        };
+        let today = report::local_date(Utc::now(), offset);
+        
+        match report_day.replace(today) {
+            Some(day) if day < today && self.config.reports.daily_summary => {
+                if let Err(e) = self.send_daily_report(day, offset).await {
+                    warn!("Failed to produce the daily report for {}: {}", day, e);
+                }
+            },
+            Some(day) if day > today => *report_day = Some(day),
+            _ => {},
+        }
+    }
+    
+    async fn send_daily_report(&self, day: chrono::NaiveDate, offset: chrono::FixedOffset) -> Result<()> {
+        let report = report::daily(&self.database, day, offset, self.dry_run).await?;
+        let paths = report::write(&report, std::path::Path::new(&self.config.reports.directory))?;
+        info!("Daily report for {} written to {}", day,
+              paths.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join(" and "));
+        self.notifications.daily_summary(&report);
+        Ok(())
+    }
+    
+    // Counted per hour for the daily report as well as towards the repeated-error notification
+    fn record_error(&self, context: &str, error: &anyhow::Error) {
+        self.notifications.record_error(context, error);
+        let database = self.database.clone();
+        let (context, message) = (context.to_string(), error.to_string());
+        tokio::spawn(async move {
+            if let Err(e) = database.record_error(&context, &message, Utc::now()).await {
+                warn!("Failed to record the {} error: {}", context, e);
+            }
+        });
+    }
+    
+    fn prune_spread_history(&self) {
+        let config = &self.config.trading.spread_history;
+        let now = chrono::Utc::now();
+        let downsample_before = now - chrono::Duration::hours(config.raw_retention_hours as i64);
+        let delete_before = now - chrono::Duration::days(config.retention_days as i64);
+        let database = self.database.clone();
+        
+        tokio::spawn(async move {
+            match database.prune_spread_history(downsample_before, delete_before).await {
+                Ok(pruning) if pruning.downsampled > 0 || pruning.deleted > 0 => {
+                    info!("Downsampled {} spread samples to hourly rows and deleted {} expired rows", pruning.downsampled, pruning.deleted);
+                },
+                Ok(_) => {},
+                Err(e) => warn!("Failed to prune spread history: {}", e),
+            }
+        });
+    }
+    
+    // Routes are persisted as plans only; nothing is swapped or bridged
+    pub async fn scan_cross_chain(&self) -> Result<Vec<CrossChainArbitrage>> {
+        let routes = self.cross_chain.scan(&self.exchange_manager, &self.blockchain_manager).await?;
+        for route in &routes {
+            self.database.save_cross_chain_arbitrage(route).await?;
+        }
+        Ok(routes)
+    }
+    
+    pub async fn execute_cross_chain_bridge(&self, route: &CrossChainArbitrage) -> Result<String> {
+        self.cross_chain.execute_bridge(route, &self.blockchain_manager).await
+    }
+    
+    // Every exchange with account access, or only `exchange`, which may also be a data-only one
+    fn account_exchanges(&self, exchange: Option<&str>) -> Result<Vec<&dyn Exchange>> {
+        Ok(match exchange {
+            Some(name) => vec![self.exchange_manager.get_exchange(name)
+                .ok_or_else(|| anyhow::anyhow!("Exchange {} is not enabled", name))?],
+            None => self.exchange_manager.get_all_exchanges().into_iter()
+                .filter(|exchange| self.exchange_manager.can_execute(exchange.name()))
+                .collect(),
         })
     }
     
-    pub fn set_dry_run(&mut self, dry_run: bool) {
-        self.dry_run = dry_run;
-        if dry_run {
-            info!("Bot running in DRY RUN mode - no actual trades will be executed");
+    pub async fn balance_report(&self, exchange: Option<&str>) -> Result<BalanceReport> {
+        let exchanges = self.account_exchanges(exchange)?;
+        Ok(self.valuation.balance_report(&self.exchange_manager, &exchanges).await)
+    }
+    
+    // Reads the venues directly, so it also sees orders placed by another running bot or by hand
+    pub async fn list_open_orders(&self, exchange: Option<&str>) -> Result<OpenOrdersReport> {
+        let exchanges = self.account_exchanges(exchange)?;
+        let fetches = exchanges.iter().map(|exchange| async move {
+            let result = self.exchange_manager.call(
+                exchange.name(), "get_open_orders", self.exchange_manager.timeouts().order_status(),
+                exchange.get_open_orders(None),
+            ).await;
+            (exchange.name().to_string(), result)
+        });
+        
+        let mut report = OpenOrdersReport::default();
+        for (name, result) in futures::future::join_all(fetches).await {
+            match result {
+                Ok(orders) => report.orders.extend(orders),
+                Err(e) => {
+                    report.failures.insert(name, e.to_string());
+                }
+            }
+        }
+        report.orders.sort_by(|a, b| a.exchange.cmp(&b.exchange).then(a.created_at.cmp(&b.created_at)));
+        Ok(report)
+    }
+    
+    pub async fn cancel_exchange_order(&self, exchange: &str, order_id: &str) -> Result<()> {
+        let venue = self.exchange_manager.get_exchange(exchange)
+            .ok_or_else(|| anyhow::anyhow!("Exchange {} is not enabled", exchange))?;
+        self.exchange_manager.call(
+            exchange, "cancel_order", self.exchange_manager.timeouts().order(),
+            venue.cancel_order(order_id),
+        ).await
+    }
+    
+    // One failed cancellation does not stop the rest
+    pub async fn cancel_all_orders(&self, exchange: Option<&str>) -> Result<CancellationReport> {
+        let open = self.list_open_orders(exchange).await?;
+        let mut report = CancellationReport {
+            failures: open.failures,
+            ..Default::default()
+        };
+        
+        for order in open.orders {
+            let error = self.cancel_exchange_order(&order.exchange, &order.order_id).await.err();
+            match &error {
+                Some(e) => warn!("Failed to cancel order {} on {}: {}", order.order_id, order.exchange, e),
+                None => info!("Cancelled order {} on {}", order.order_id, order.exchange),
+            }
+            report.cancellations.push(OrderCancellation {
+                exchange: order.exchange,
+                order_id: order.order_id,
+                error: error.map(|e| e.to_string()),
+            });
+        }
+        Ok(report)
+    }
+    
+    pub async fn plan_rebalance(&self) -> Result<RebalancePlan> {
+        self.rebalancer.plan(&self.exchange_manager, &self.database).await
+    }
+    
+    pub async fn execute_rebalance(&self, plan: &RebalancePlan) -> Vec<(Transfer, Result<String>)> {
+        let mut results = Vec::new();
+        for transfer in &plan.transfers {
+            let result = self.rebalancer.execute(&self.exchange_manager, transfer).await;
+            self.balances.invalidate(&transfer.from);
+            results.push((transfer.clone(), result));
+        }
+        results
+    }
+    
+    async fn auto_rebalance(&self) -> Result<()> {
+        let plan = self.plan_rebalance().await?;
+        for reason in &plan.skipped {
+            debug!("Rebalancing skipped: {}", reason);
+        }
+        if plan.transfers.is_empty() {
+            return Ok(());
+        }
+        
+        // Withdrawals move real funds, so they follow the same switches as trading
+        if self.dry_run || self.kill_switch_engaged() {
+            for transfer in &plan.transfers {
+                info!("Rebalancing not executed: would move {} {} from {} to {}",
+                      transfer.amount, transfer.asset, transfer.from, transfer.to);
+            }
+            return Ok(());
+        }
+        
+        for (transfer, result) in self.execute_rebalance(&plan).await {
+            if let Ok(id) = result {
+                info!("Rebalancing {} {} from {} to {} submitted as withdrawal {}",
+                      transfer.amount, transfer.asset, transfer.from, transfer.to, id);
+            }
+        }
+        Ok(())
+    }
+    
+    async fn evaluate_updated_pairs(&mut self, pairs: &[TradingPair]) -> Result<()> {
+        // A new quote can also be the sell leg of a route bought under an equivalent quote
+        let mut expanded: Vec<TradingPair> = Vec::new();
+        for pair in pairs {
+            for pair in std::iter::once(pair.clone()).chain(self.quote_equivalence.equivalent_pairs(pair)) {
+                if !expanded.contains(&pair) {
+                    expanded.push(pair);
+                }
+            }
+        }
+        
+        for pair in &expanded {
+            if let Err(e) = self.evaluate_pair(pair).instrument(info_span!("pair", pair = %pair.symbol)).await {
+                warn!("Error evaluating pair {}: {}", pair.symbol, e);
+            }
+        }
+        
+        self.execute_opportunities().await
+    }
+    
+    pub async fn run_cycle(&mut self) -> Result<()> {
+        self.stats.cycles += 1;
+        self.scan_and_execute().await
+    }
+    
+    async fn scan_and_execute(&mut self) -> Result<()> {
+        debug!("Scanning for arbitrage opportunities");
+        
+        let enabled_exchanges = self.config.get_enabled_exchanges();
+        
+        if enabled_exchanges.len() < 2 {
+            warn!("Need at least 2 exchanges for arbitrage");
+            return Ok(());
+        }
+        
+        let configured = self.configured_pairs();
+        let mut all_pairs = configured.clone();
+        // Conversion rates between equivalent quotes come from whichever venues list those pairs
+        all_pairs.extend(self.quote_equivalence.conversion_pairs());
+        
+        let scan_started = std::time::Instant::now();
+        let all_pairs: Vec<TradingPair> = all_pairs.into_iter().collect();
+        
+        let fetched = futures::future::join_all(
+            all_pairs.iter().map(|pair| self.exchange_manager.get_all_prices(pair).instrument(info_span!("pair", pair = %pair.symbol)))
+        ).await;
+        let fetch_duration = scan_started.elapsed();
+        
+        for prices in fetched {
+            for price in prices? {
+                self.record_price(price);
+            }
+        }
+        
+        if self.spreads.config().enabled {
+            self.record_spreads(&configured).await;
+        }
+        
+        for pair in &all_pairs {
+            if let Err(e) = self.evaluate_pair(pair).instrument(info_span!("pair", pair = %pair.symbol)).await {
+                warn!("Error scanning pair {}: {}", pair.symbol, e);
+            }
+        }
+        
+        info!("Scanned {} pairs in {:?} (price fetch {:?})",
+              all_pairs.len(), scan_started.elapsed(), fetch_duration);
+        self.heartbeat.scanned();
+        
+        self.execute_opportunities().await?;
+        
+        self.cleanup_expired_opportunities().await?;
+        
+        Ok(())
+    }
+    
+    async fn taker_fee(&self, exchange: &str, pair: &TradingPair) -> Result<Bps> {
+        let manager = &self.exchange_manager;
+        let venue = manager.get_exchange(exchange)
+            .ok_or_else(|| anyhow::anyhow!("Exchange {} is not enabled", exchange))?;
+        let native = manager.native_pair(exchange, pair);
+        let fees = manager.trading_fees(venue, &native).await?;
+        Ok(Bps::from_fraction(fees.taker_fee))
+    }
+    
+    async fn scan_route(&self, pair: &TradingPair, buy: &Price, sell: &Price) -> ScanRoute {
+        let fees = tokio::try_join!(self.taker_fee(&buy.exchange, pair), self.taker_fee(&sell.exchange, pair));
+        let net_percentage = match fees {
+            Ok((buy_fee, sell_fee)) => Some(profit::unit_margin(Leg::new(buy.ask, buy_fee), Leg::new(sell.bid, sell_fee)).as_percent()),
+            Err(e) => {
+                debug!("No fees to net the {} {} -> {} spread with: {}", pair.symbol, buy.exchange, sell.exchange, e);
+                None
+            }
+        };
+        
+        ScanRoute {
+            buy_exchange: buy.exchange.clone(),
+            sell_exchange: sell.exchange.clone(),
+            gross_percentage: (sell.bid - buy.ask) / buy.ask * Decimal::ONE_HUNDRED,
+            net_percentage,
+        }
+    }
+    
+    // Every route's net spread is recorded each cycle, whether or not it clears the threshold, and checked against
+    // its rolling history
+    async fn record_spreads(&self, pairs: &std::collections::HashSet<TradingPair>) {
+        let now = Utc::now();
+        let mut samples = Vec::new();
+        
+        for pair in pairs.iter().filter(|pair| self.config.pair_settings(&pair.symbol).enabled) {
+            let prices = self.comparable_prices(pair);
+            let min_profit_threshold = self.config.pair_settings(&pair.symbol).min_profit_threshold;
+            
+            for buy in prices.iter().filter(|price| price.pair == *pair) {
+                for sell in prices.iter().filter(|price| price.exchange != buy.exchange) {
+                    let Some(sell_rate) = self.quote_rate(&sell.exchange, &sell.pair.quote, &pair.quote) else {
+                        continue;
+                    };
+                    let (buy_fee, sell_fee) = match tokio::try_join!(self.taker_fee(&buy.exchange, pair), self.taker_fee(&sell.exchange, &sell.pair)) {
+                        Ok(fees) => fees,
+                        Err(e) => {
+                            debug!("No fees to net the {} {} -> {} spread with: {}", pair.symbol, buy.exchange, sell.exchange, e);
+                            continue;
+                        }
+                    };
+                    let net_spread = profit::unit_margin(Leg::new(buy.ask, buy_fee), Leg::new(sell.bid * sell_rate, sell_fee)).as_percent();
+                    
+                    let route = (pair.symbol.clone(), buy.exchange.clone(), sell.exchange.clone());
+                    let net = net_spread.to_f64().unwrap_or_default();
+                    if let Some(baseline) = self.spreads.observe(&route, net, now) {
+                        let alert = SpreadAlert {
+                            pair: pair.symbol.clone(),
+                            buy_exchange: buy.exchange.clone(),
+                            sell_exchange: sell.exchange.clone(),
+                            net_spread: net,
+                            mean: baseline.mean(),
+                            stddev: baseline.stddev(),
+                            samples: baseline.samples,
+                            window_hours: self.config.trading.spread_history.window_hours,
+                            cause: self.spread_cause(pair, buy, sell, &prices, net - baseline.mean()),
+                        };
+                        warn!("{} {} -> {} net spread {:.3}% is {:.1} standard deviations above its {:.3}% mean: {}",
+                              alert.pair, alert.buy_exchange, alert.sell_exchange, alert.net_spread, alert.deviations(),
+                              alert.mean, alert.diagnosis());
+                        self.notifications.spread_anomaly(&alert);
+                    }
+                    
+                    samples.push(SpreadSample {
+                        pair: pair.symbol.clone(),
+                        buy_exchange: buy.exchange.clone(),
+                        sell_exchange: sell.exchange.clone(),
+                        net_spread,
+                        above_threshold: net_spread > min_profit_threshold,
+                        timestamp: now,
+                    });
+                }
+            }
+        }
+        
+        if let Err(e) = self.database.save_spread_samples(&samples).await {
+            warn!("Failed to record spread history: {}", e);
+        }
+    }
+    
+    // Mids are restated in the pair's quote so venues quoting an equivalent asset count as witnesses
+    fn spread_cause(&self, pair: &TradingPair, buy: &Price, sell: &Price, prices: &[Price], excess: f64) -> SpreadCause {
+        let mid = |price: &Price| {
+            let rate = self.quote_rate(&price.exchange, &price.pair.quote, &pair.quote)?;
+            (price.mid() * rate).to_f64()
+        };
+        let others: Vec<f64> = prices.iter()
+            .filter(|price| price.exchange != buy.exchange && price.exchange != sell.exchange)
+            .filter_map(mid)
+            .collect();
+        spreads::diagnose((&buy.exchange, mid(buy)), (&sell.exchange, mid(sell)), &others, excess)
+    }
+    
+    // Venues are compared under canonical symbols, so WETH/USDC on a DEX meets ETH/USDC on a CEX
+    fn configured_pairs(&self) -> std::collections::HashSet<TradingPair> {
+        let mut pairs = std::collections::HashSet::new();
+        for (name, exchange_config) in &self.config.get_enabled_exchanges() {
+            for pair_str in &exchange_config.trading_pairs {
+                if let Some(pair) = self.parse_trading_pair(pair_str) {
+                    pairs.insert(self.exchange_manager.canonical_pair(name, &pair));
+                }
+            }
+        }
+        pairs.retain(|pair| self.config.pair_settings(&pair.symbol).enabled);
+        pairs
+    }
+    
+    fn record_price(&mut self, mut price: Price) {
+        price.pair = self.exchange_manager.canonical_pair(&price.exchange, &price.pair);
+        debug!("Got price from {}: {} bid, {} ask", price.exchange, price.bid, price.ask);
+        
+        let others = self.latest_prices.get(&price.pair.symbol).into_iter().flat_map(|prices| prices.values());
+        if let Err(rejection) = self.price_sanity.check(&price, others) {
+            warn!("Rejected {} {} price ({} bid, {} ask): {}", price.exchange, price.pair.symbol, price.bid, price.ask, rejection);
+            self.stats.prices_rejected += 1;
+            *self.stats.rejections_by_kind.entry(rejection.kind()).or_default() += 1;
+            return;
+        }
+        
+        if let Some(recorder) = &self.recorder {
+            recorder.record_price(&price);
+        }
+        self.latest_prices
+            .entry(price.pair.symbol.clone())
+            .or_default()
+            .insert(price.exchange.clone(), price);
+    }
+    
+    // Re-reads every on-chain quote on the chain that produced a new block, returning the pairs that changed
+    async fn refresh_chain_prices(&mut self, chain_id: u64) -> Vec<TradingPair> {
+        let manager = &self.exchange_manager;
+        let budget = manager.timeouts().price();
+        let mut requests = Vec::new();
+        
+        for exchange in manager.get_all_exchanges() {
+            if exchange.chain_id() != Some(chain_id) || !manager.is_available(exchange.name()) {
+                continue;
+            }
+            let Some(exchange_config) = self.config.exchanges.get(exchange.name()) else {
+                continue;
+            };
+            for pair in exchange_config.trading_pairs.iter().filter_map(|pair| self.parse_trading_pair(pair)) {
+                requests.push(async move {
+                    manager.fetch_price(exchange, &pair, budget).await
+                });
+            }
+        }
+        
+        let mut pairs = Vec::new();
+        for result in futures::future::join_all(requests).await {
+            match result {
+                Ok(price) => {
+                    pairs.push(self.exchange_manager.canonical_pair(&price.exchange, &price.pair));
+                    self.record_price(price);
+                },
+                Err(e) => debug!("Block refresh on chain {} failed: {}", chain_id, e),
+            }
+        }
+        pairs
+    }
+    
+    fn chain_head(&self, exchange: &str) -> Option<ChainHead> {
+        let chain_id = self.exchange_manager.get_exchange(exchange)?.chain_id()?;
+        *self.chain_heads.get(&chain_id)?.borrow()
+    }
+    
+    fn cached_prices(&self, pair: &TradingPair) -> Vec<Price> {
+        let max_age = chrono::Duration::seconds(self.config.trading.check_interval_seconds as i64 * 2);
+        let now = Utc::now();
+        
+        // With a live head an on-chain quote is only current if it was read at the latest block
+        let is_fresh = |price: &Price| match self.chain_head(&price.exchange) {
+            Some(head) => price.timestamp >= head.timestamp,
+            None => now.signed_duration_since(price.timestamp) <= max_age,
+        };
+        
+        self.latest_prices.get(&pair.symbol)
+            .map(|prices| prices.values()
+                .filter(|price| is_fresh(price))
+                .cloned()
+                .collect())
+            .unwrap_or_default()
+    }
+    
+    // The pair's own quotes plus those of the same base under equivalent quotes
+    fn comparable_prices(&self, pair: &TradingPair) -> Vec<Price> {
+        let mut prices = self.cached_prices(pair);
+        for equivalent in self.quote_equivalence.equivalent_pairs(pair) {
+            prices.extend(self.cached_prices(&equivalent));
+        }
+        prices
+    }
+    
+    // What one unit of `from` received on `venue` is worth in `to`; None when the two are not
+    // equivalent or no usable rate is quoted
+    fn quote_rate(&self, venue: &str, from: &str, to: &str) -> Option<Decimal> {
+        if from == to {
+            return Some(Decimal::ONE);
+        }
+        let quotes: Vec<Price> = [TradingPair::new(from, to), TradingPair::new(to, from)].iter()
+            .flat_map(|pair| self.cached_prices(pair))
+            .collect();
+        self.quote_equivalence.rate(from, to, venue, &quotes)
+    }
+    
+    // The pair `exchange` lists between two equivalent quotes, natively named, and the side that turns `from` into `to`
+    fn conversion_pair(&self, exchange: &str, from: &str, to: &str) -> Option<(TradingPair, TradeSide)> {
+        let venue = self.exchange_manager.get_exchange(exchange)?;
+        [(TradingPair::new(from, to), TradeSide::Sell), (TradingPair::new(to, from), TradeSide::Buy)].into_iter()
+            .map(|(pair, side)| (self.exchange_manager.native_pair(exchange, &pair), side))
+            .find(|(pair, _)| venue.supports_pair(pair))
+    }
+    
+    async fn evaluate_pair(&mut self, pair: &TradingPair) -> Result<()> {
+        if !self.config.pair_settings(&pair.symbol).enabled {
+            return Ok(());
+        }
+        let prices = self.comparable_prices(pair);
+        
+        // A fresh quote showing the spread has closed retires the opportunity without waiting for its TTL
+        let closed: Vec<_> = self.active_opportunities.read().unwrap().iter()
+            .filter(|(_, opportunity)| opportunity.pair.symbol == pair.symbol)
+            .filter(|(_, opportunity)| {
+                let sell_pair = self.exchange_manager.canonical_pair(&opportunity.sell_exchange, &opportunity.sell_pair);
+                let buy = prices.iter().find(|price| price.exchange == opportunity.buy_exchange && price.pair == opportunity.pair);
+                let sell = prices.iter().find(|price| price.exchange == opportunity.sell_exchange && price.pair == sell_pair);
+                matches!((buy, sell), (Some(buy), Some(sell)) if buy.ask >= sell.bid * opportunity.sell_quote_rate)
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        self.expire_opportunities(&closed).await?;
+        
+        for opportunity in self.find_opportunities(pair, &prices).await? {
+            if let Some(reason) = self.latency_skip(&opportunity, &prices) {
+                self.stats.opportunities_skipped += 1;
+                *self.stats.skips_by_reason.entry(reason).or_default() += 1;
+                continue;
+            }
+            self.add_opportunity(opportunity).await?;
+        }
+        
+        Ok(())
+    }
+    
+    // Live scanning only: recorded quotes replayed by the backtester are old by design.
+    // A leg without a latency estimate yet counts as instant, so one known leg can still exceed the budget.
+    fn latency_skip(&self, opportunity: &ArbitrageOpportunity, prices: &[Price]) -> Option<&'static str> {
+        let sell_pair = self.exchange_manager.canonical_pair(&opportunity.sell_exchange, &opportunity.sell_pair);
+        let buy = prices.iter().find(|price| price.exchange == opportunity.buy_exchange && price.pair == opportunity.pair)?;
+        let sell = prices.iter().find(|price| price.exchange == opportunity.sell_exchange && price.pair == sell_pair)?;
+        let trading = &self.config.trading;
+        
+        if let Some(max_quote_age_ms) = trading.max_quote_age_ms {
+            let age = Utc::now().signed_duration_since(buy.timestamp.min(sell.timestamp));
+            if age > chrono::Duration::milliseconds(max_quote_age_ms as i64) {
+                debug!("Skipping {} {} -> {}: quote is {}ms old", opportunity.pair.symbol, opportunity.buy_exchange,
+                       opportunity.sell_exchange, age.num_milliseconds());
+                return Some("stale_quote");
+            }
+        }
+        
+        if let Some(max_execution_latency_ms) = trading.max_execution_latency_ms {
+            let health = self.exchange_manager.health_tracker();
+            let leg_latency = |price: &Price| health.order_latency(&price.exchange).or(price.fetch_latency);
+            let expected = leg_latency(buy).unwrap_or_default() + leg_latency(sell).unwrap_or_default();
+            if expected > Duration::from_millis(max_execution_latency_ms) {
+                debug!("Skipping {} {} -> {}: expected to take {:?} to execute", opportunity.pair.symbol,
+                       opportunity.buy_exchange, opportunity.sell_exchange, expected);
+                return Some("latency_budget");
+            }
+        }
+        
+        None
+    }
+    
+    // Shared by the live scanner and the backtester, which feed it recorded prices instead.
+    // Routes buy `pair` itself; the sell leg may be quoted in an equivalent asset.
+    pub async fn find_opportunities(&self, pair: &TradingPair, prices: &[Price]) -> Result<Vec<ArbitrageOpportunity>> {
+        let mut opportunities = Vec::new();
+        
+        for buy in prices.iter().filter(|price| price.pair == *pair) {
+            for sell in prices.iter().filter(|price| price.exchange != buy.exchange) {
+                let Some(sell_rate) = self.quote_rate(&sell.exchange, &sell.pair.quote, &pair.quote) else {
+                    debug!("No {} -> {} rate on {} to compare {} against", sell.pair.quote, pair.quote, sell.exchange, pair.symbol);
+                    continue;
+                };
+                
+                // A slow or failing venue only costs the routes through it
+                let span = debug_span!("route", route = %format!("{}->{}", buy.exchange, sell.exchange));
+                match self.calculate_arbitrage_opportunity(buy, sell, sell_rate).instrument(span).await {
+                    Ok(Some(opportunity)) => opportunities.push(opportunity),
+                    Ok(None) => {},
+                    Err(e) => warn!("Skipping {} {} -> {} {}: {}", pair.symbol, buy.exchange, sell.pair.symbol, sell.exchange, e),
+                }
+            }
+        }
+        
+        Ok(opportunities)
+    }
+    
+    // `sell_rate` restates the sell venue's quote in the buy venue's; 1 when they match
+    async fn calculate_arbitrage_opportunity(
+        &self,
+        buy: &Price,
+        sell: &Price,
+        sell_rate: Decimal,
+    ) -> Result<Option<ArbitrageOpportunity>> {
+        let pair = &buy.pair;
+        let buy_exchange = buy.exchange.as_str();
+        let sell_exchange = sell.exchange.as_str();
+        let buy_price = buy.ask;
+        let sell_price = sell.bid * sell_rate;
+        let settings = self.config.pair_settings(&pair.symbol);
+        let min_margin = Bps::from_percent(settings.min_profit_threshold);
+        
+        // Fees only shrink the margin, so quotes that fall short before them never cost a fee lookup
+        if profit::unit_margin(Leg::new(buy_price, Bps::ZERO), Leg::new(sell_price, Bps::ZERO)) <= min_margin {
+            return Ok(None);
+        }
+        
+        // Cached streaming prices can still reference a venue that has since been put in cooldown
+        if !self.exchange_manager.is_available(buy_exchange) || !self.exchange_manager.is_available(sell_exchange) {
+            return Ok(None);
+        }
+        
+        let buy_exchange_obj = self.exchange_manager.get_exchange(buy_exchange)
+            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", buy_exchange))?;
+        let sell_exchange_obj = self.exchange_manager.get_exchange(sell_exchange)
+            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", sell_exchange))?;
+        
+        let manager = &self.exchange_manager;
+        let timeouts = manager.timeouts();
+        // Everything sent to a venue uses its own symbols for the pair
+        let buy_pair = manager.native_pair(buy_exchange, pair);
+        let sell_pair = manager.native_pair(sell_exchange, &sell.pair);
+        
+        for (exchange, venue_pair) in [(buy_exchange_obj, &buy_pair), (sell_exchange_obj, &sell_pair)] {
+            if let Some(reason) = self.unsafe_token(exchange, venue_pair).await? {
+                debug!("Skipping {} {} -> {}: {}", pair.symbol, buy_exchange, sell_exchange, reason);
+                return Ok(None);
+            }
+        }
+        
+        let (buy_fees, sell_fees) = tokio::try_join!(
+            manager.trading_fees(buy_exchange_obj, &buy_pair),
+            manager.trading_fees(sell_exchange_obj, &sell_pair),
+        )?;
+        
+        let buy_fee = Bps::from_fraction(buy_fees.taker_fee);
+        let sell_fee = Bps::from_fraction(sell_fees.taker_fee);
+        
+        // Swapping the proceeds back is a third trade, so its fee comes out of the rate
+        let cross_quote = sell.pair.quote != pair.quote;
+        let sell_rate = if cross_quote && self.quote_equivalence.residual() == ResidualQuote::Convert {
+            let (conversion_pair, _) = self.conversion_pair(sell_exchange, &sell.pair.quote, &pair.quote)
+                .ok_or_else(|| anyhow::anyhow!("{} cannot convert {} to {}", sell_exchange, sell.pair.quote, pair.quote))?;
+            let conversion_fees = manager.trading_fees(sell_exchange_obj, &conversion_pair).await?;
+            sell_rate * (Decimal::ONE - conversion_fees.taker_fee)
+        } else {
+            sell_rate
+        };
+        let sell_price = sell.bid * sell_rate;
+        
+        if profit::unit_margin(Leg::new(buy_price, buy_fee), Leg::new(sell_price, sell_fee)) <= min_margin {
+            return Ok(None);
+        }
+        
+        let (buy_order_book, sell_order_book, buy_pool, sell_pool) = tokio::try_join!(
+            manager.call(buy_exchange, "get_order_book", timeouts.order_book(), buy_exchange_obj.get_order_book(&buy_pair, 20)),
+            manager.call(sell_exchange, "get_order_book", timeouts.order_book(), sell_exchange_obj.get_order_book(&sell_pair, 20)),
+            manager.call(buy_exchange, "get_pool_reserves", timeouts.order_book(), buy_exchange_obj.get_pool_reserves(&buy_pair)),
+            manager.call(sell_exchange, "get_pool_reserves", timeouts.order_book(), sell_exchange_obj.get_pool_reserves(&sell_pair)),
+        )?;
+        
+        if let Some(recorder) = &self.recorder {
+            recorder.record_order_book(&buy_order_book);
+            recorder.record_order_book(&sell_order_book);
+        }
+        let sell_order_book = if cross_quote { sell_order_book.converted(sell_rate) } else { sell_order_book };
+        let sell_pool = if cross_quote { sell_pool.map(|pool| pool.converted(sell_rate)) } else { sell_pool };
+        
+        // Pools are sized on their exact curve rather than the sampled book
+        let route = Route {
+            buy: buy_pool.map_or(Liquidity::Book(&buy_order_book), Liquidity::Pool),
+            sell: sell_pool.map_or(Liquidity::Book(&sell_order_book), Liquidity::Pool),
+            buy_fee,
+            sell_fee,
+        };
+        
+        let max_trade_size = self.calculate_max_trade_size(&route, buy_exchange, buy_price, sell_price, &settings);
+        
+        // Sizes past the position limit would only be cut back at execution
+        let max_trade_size = match self.valuation.usd_price(manager, &pair.quote).await {
+            Some(quote_usd) => self.risk.cap_size(max_trade_size, buy_price * quote_usd),
+            None => max_trade_size,
+        };
+        
+        // Data-only venues have no account to size against; such opportunities are only reported
+        let max_trade_size = if manager.can_execute(buy_exchange) && manager.can_execute(sell_exchange) {
+            let unit_cost = buy_price * (Decimal::ONE + settings.max_slippage + buy_fees.taker_fee);
+            let affordable = self.affordable_size(buy_exchange_obj, sell_exchange_obj, &buy_pair, &sell_pair, unit_cost).await?;
+            max_trade_size.min(affordable)
+        } else {
+            max_trade_size
+        };
+        
+        if max_trade_size <= Decimal::ZERO {
+            return Ok(None);
+        }
+        
+        // Gas hardly moves with size, so it is priced once at the largest size and solved against as a fixed cost
+        let (buy_gas_cost, sell_gas_cost) = tokio::try_join!(
+            manager.call(buy_exchange, "get_execution_cost", timeouts.order_book(), buy_exchange_obj.get_execution_cost(&buy_pair, max_trade_size)),
+            manager.call(sell_exchange, "get_execution_cost", timeouts.order_book(), sell_exchange_obj.get_execution_cost(&sell_pair, max_trade_size)),
+        )?;
+        let gas_cost = buy_gas_cost + sell_gas_cost;
+        
+        let Some(sizing) = route.solve(max_trade_size, gas_cost, min_margin) else {
+            debug!("Skipping {} {} -> {}: no size up to {} clears the margin and ${:.2} gas",
+                   pair.symbol, buy_exchange, sell_exchange, max_trade_size, gas_cost);
+            return Ok(None);
+        };
+        
+        let (buy_rules, sell_rules) = tokio::try_join!(
+            manager.call(buy_exchange, "get_symbol_rules", timeouts.account(), buy_exchange_obj.get_symbol_rules(&buy_pair)),
+            manager.call(sell_exchange, "get_symbol_rules", timeouts.account(), sell_exchange_obj.get_symbol_rules(&sell_pair)),
+        )?;
+        let trade_size = sell_rules.round_quantity(buy_rules.round_quantity(sizing.size));
+        
+        // Rounding only shrinks the size, so both sides can still fill it
+        let effective_buy_price = route.buy.average_price(&TradeSide::Buy, trade_size).unwrap_or(sizing.buy_price);
+        let effective_sell_price = route.sell.average_price(&TradeSide::Sell, trade_size).unwrap_or(sizing.sell_price);
+        let marginal_profit = route.marginal_profit(trade_size).unwrap_or(sizing.marginal_profit);
+        
+        let tradable = buy_rules.check_order(trade_size, effective_buy_price)
+            .and_then(|_| sell_rules.check_order(trade_size, effective_sell_price));
+        if let Err(e) = tradable {
+            debug!("Skipping {} {} -> {}: {}", pair.symbol, buy_exchange, sell_exchange, e);
+            return Ok(None);
+        }
+        
+        let buy_leg = Leg::new(effective_buy_price, buy_fee);
+        let sell_leg = Leg::new(effective_sell_price, sell_fee);
+        let trading = ProfitBreakdown::compute(trade_size, buy_leg, sell_leg, Decimal::ZERO, Decimal::ZERO);
+        
+        if trading.net_margin() <= min_margin {
+            return Ok(None);
+        }
+        
+        let gross_edge = trading.net;
+        
+        if gas_cost >= gross_edge {
+            debug!("Skipping {} {} -> {}: gas cost {:.2} exceeds edge {:.2}",
+                   pair.symbol, buy_exchange, sell_exchange, gas_cost, gross_edge);
+            return Ok(None);
+        }
+        
+        let transfer_cost = self.amortized_transfer_cost(buy_exchange_obj, sell_exchange_obj, &buy_pair, &sell_pair, effective_buy_price).await;
+        let breakdown = ProfitBreakdown::compute(trade_size, buy_leg, sell_leg, gas_cost, transfer_cost);
+        
+        if breakdown.net <= Decimal::ZERO {
+            info!("{} {} -> {} only pays before transfers: ${:.2} raw, ${:.2} after ${:.2} amortized withdrawal fees",
+                  pair.symbol, buy_exchange, sell_exchange, breakdown.net_before_transfers(), breakdown.net, transfer_cost);
+            if self.config.trading.require_transfer_profit {
+                return Ok(None);
+            }
+        }
+        
+        let opportunity = ArbitrageOpportunity {
+            id: uuid::Uuid::new_v4(),
+            pair: pair.clone(),
+            buy_exchange: buy_exchange.to_string(),
+            sell_exchange: sell_exchange.to_string(),
+            buy_pair,
+            sell_pair,
+            sell_quote_rate: sell_rate,
+            buy_price: effective_buy_price,
+            sell_price: effective_sell_price,
+            profit_percentage: trading.net_margin().as_percent(),
+            profit_amount: breakdown.net_before_transfers(),
+            max_trade_size: trade_size,
+            gross_profit: breakdown.gross,
+            buy_fee_cost: breakdown.buy_fee,
+            sell_fee_cost: breakdown.sell_fee,
+            buy_fee_rate: buy_fees.taker_fee,
+            sell_fee_rate: sell_fees.taker_fee,
+            gas_cost,
+            transfer_cost,
+            adjusted_profit_amount: breakdown.net,
+            marginal_profit,
+            timestamp: Utc::now(),
+            status: OpportunityStatus::Active,
+            requires_approval: self.config.trading.max_profit_threshold
+                .map_or(false, |max_profit| trading.net_margin().as_percent() > max_profit),
+            approved_at: None,
+        };
+        
+        if opportunity.requires_approval {
+            warn!("{} {} -> {} pays {:.2}%, above max_profit_threshold; holding it for manual approval",
+                  pair.symbol, buy_exchange, sell_exchange, opportunity.profit_percentage);
+        }
+        
+        info!("Found arbitrage opportunity {} {} ({}) -> {} ({}): {:.2}% after fees, {}, {:.4} per further unit",
+              pair.symbol, buy_exchange, opportunity.buy_pair.symbol, sell_exchange, opportunity.sell_pair.symbol,
+              opportunity.profit_percentage, breakdown, marginal_profit);
+        
+        Ok(Some(opportunity))
+    }
+    
+    // Restoring inventory after a fill means moving base from the buy venue to the sell venue
+    // and quote back the other way; each opportunity carries the configured share of both fees
+    async fn amortized_transfer_cost(
+        &self,
+        buy_exchange: &dyn Exchange,
+        sell_exchange: &dyn Exchange,
+        buy_pair: &TradingPair,
+        sell_pair: &TradingPair,
+        buy_price: Decimal,
+    ) -> Decimal {
+        let amortization = self.config.trading.transfer_cost_amortization;
+        if amortization <= Decimal::ZERO {
+            return Decimal::ZERO;
+        }
+        
+        // Not routed through the health tracker: most venues simply don't support withdrawals
+        let timeout = self.exchange_manager.timeouts().account();
+        let (base_fee, quote_fee) = tokio::join!(
+            time::timeout(timeout, buy_exchange.get_withdrawal_fee(&buy_pair.base)),
+            time::timeout(timeout, sell_exchange.get_withdrawal_fee(&sell_pair.quote)),
+        );
+        let base_fee = base_fee.map_err(anyhow::Error::from).and_then(|fee| fee.map_err(anyhow::Error::from));
+        let quote_fee = quote_fee.map_err(anyhow::Error::from).and_then(|fee| fee.map_err(anyhow::Error::from));
+        
+        // Venues that cannot withdraw are never rebalanced from, so they add nothing
+        let base_fee = base_fee.unwrap_or_else(|e| {
+            debug!("No {} withdrawal fee for {}: {}", buy_pair.base, buy_exchange.name(), e);
+            Decimal::ZERO
+        });
+        let quote_fee = quote_fee.unwrap_or_else(|e| {
+            debug!("No {} withdrawal fee for {}: {}", sell_pair.quote, sell_exchange.name(), e);
+            Decimal::ZERO
+        });
+        
+        (base_fee * buy_price + quote_fee) * amortization
+    }
+    
+    async fn available_balance(&self, exchange: &dyn Exchange, asset: &str) -> Result<Decimal> {
+        let free = if self.dry_run {
+            self.seed_paper_balances(exchange).await;
+            self.paper_portfolio.lock().unwrap().balance(exchange.name(), asset)
+        } else {
+            self.balances.free_balance(&self.exchange_manager, exchange, asset).await?
+        };
+        Ok(self.balances.unreserved(exchange.name(), asset, free))
+    }
+    
+    // Largest size the unreserved quote on the buy venue and base on the sell venue can cover, with
+    // `unit_cost` the quote spent per unit of base including slippage and fees
+    async fn affordable_size(
+        &self,
+        buy_exchange: &dyn Exchange,
+        sell_exchange: &dyn Exchange,
+        buy_pair: &TradingPair,
+        sell_pair: &TradingPair,
+        unit_cost: Decimal,
+    ) -> Result<Decimal> {
+        let (quote_available, base_available) = tokio::try_join!(
+            self.available_balance(buy_exchange, &buy_pair.quote),
+            self.available_balance(sell_exchange, &sell_pair.base),
+        )?;
+        
+        if unit_cost <= Decimal::ZERO {
+            return Ok(Decimal::ZERO);
+        }
+        Ok((quote_available / unit_cost).min(base_available))
+    }
+    
+    fn calculate_max_trade_size(
+        &self,
+        route: &Route,
+        buy_exchange: &str,
+        buy_price: Decimal,
+        sell_price: Decimal,
+        settings: &PairSettings,
+    ) -> Decimal {
+        let buy_liquidity = route.buy.depth_within(&TradeSide::Buy, buy_price, settings.max_slippage);
+        let sell_liquidity = route.sell.depth_within(&TradeSide::Sell, sell_price, settings.max_slippage);
+        
+        let max_size = buy_liquidity.min(sell_liquidity);
+        
+        let config_max = self.config.exchanges.get(buy_exchange)
+            .map(|c| c.max_trade_amount)
+            .unwrap_or(Decimal::from(1000));
+        let pair_max = settings.max_trade_size.unwrap_or(config_max);
+        
+        max_size.min(config_max).min(pair_max)
+    }
+    
+    fn opportunity_key(opportunity: &ArbitrageOpportunity) -> String {
+        format!("{}-{}-{}", 
+                opportunity.pair.symbol, 
+                opportunity.buy_exchange, 
+                opportunity.sell_exchange)
+    }
+    
+    async fn add_opportunity(&mut self, mut opportunity: ArbitrageOpportunity) -> Result<()> {
+        if self.shutdown_requested() {
+            return Ok(());
+        }
+        
+        self.stats.opportunities_found += 1;
+        
+        let key = Self::opportunity_key(&opportunity);
+        
+        // The database keeps one active row per route, so an improvement takes over its id and any approval
+        let existing = self.active_opportunities.read().unwrap()
+            .get(&key)
+            .map(|existing| (existing.profit_percentage, existing.approved_at));
+        if let Some((existing_profit, approved_at)) = existing {
+            if opportunity.profit_percentage > existing_profit {
+                opportunity.approved_at = approved_at;
+                opportunity.id = self.database.save_opportunity(&opportunity).await?;
+                self.active_opportunities.write().unwrap().insert(key.clone(), opportunity.clone());
+                self.stats.opportunities_persisted += 1;
+                self.announce_opportunity(&opportunity);
+                info!("Updated opportunity: {}", key);
+            }
+        } else {
+            opportunity.id = self.database.save_opportunity(&opportunity).await?;
+            self.active_opportunities.write().unwrap().insert(key.clone(), opportunity.clone());
+            self.stats.opportunities_persisted += 1;
+            self.announce_opportunity(&opportunity);
+            info!("Added new opportunity: {}", key);
+        }
+        
+        Ok(())
+    }
+    
+    fn announce_opportunity(&self, opportunity: &ArbitrageOpportunity) {
+        if opportunity.requires_approval && opportunity.approved_at.is_none() {
+            self.notifications.approval_needed(opportunity);
+        } else {
+            self.notifications.opportunity_found(opportunity);
+        }
+    }
+    
+    // Approvals are given from the CLI, which only reaches the database
+    async fn refresh_approvals(&self) -> Result<()> {
+        let pending: Vec<_> = self.active_opportunities.read().unwrap().iter()
+            .filter(|(_, opportunity)| opportunity.requires_approval && opportunity.approved_at.is_none())
+            .map(|(key, opportunity)| (key.clone(), opportunity.id))
+            .collect();
+        
+        for (key, id) in pending {
+            let Some(approved_at) = self.database.get_opportunity(id).await?.and_then(|stored| stored.approved_at) else {
+                continue;
+            };
+            info!("Opportunity {} was approved at {}", id, approved_at);
+            if let Some(opportunity) = self.active_opportunities.write().unwrap().get_mut(&key).filter(|opportunity| opportunity.id == id) {
+                opportunity.approved_at = Some(approved_at);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    // Never buy what cannot be moved: suspended deposits or withdrawals are how halts and delistings start
+    async fn check_transfer_status(&self, opportunity: &ArbitrageOpportunity) -> Result<()> {
+        let manager = &self.exchange_manager;
+        let timeout = manager.timeouts().account();
+        let buy_exchange = manager.get_exchange(&opportunity.buy_exchange)
+            .ok_or_else(|| anyhow::anyhow!("Exchange {} is not enabled", opportunity.buy_exchange))?;
+        let sell_exchange = manager.get_exchange(&opportunity.sell_exchange)
+            .ok_or_else(|| anyhow::anyhow!("Exchange {} is not enabled", opportunity.sell_exchange))?;
+        let buy_asset = &opportunity.buy_pair.base;
+        let sell_asset = &opportunity.sell_pair.base;
+        
+        let (bought, sold) = tokio::try_join!(
+            manager.call(buy_exchange.name(), "get_transfer_status", timeout, buy_exchange.get_transfer_status(buy_asset)),
+            manager.call(sell_exchange.name(), "get_transfer_status", timeout, sell_exchange.get_transfer_status(sell_asset)),
+        )?;
+        if !bought.withdrawals_enabled {
+            anyhow::bail!("{} withdrawals of {} are suspended", buy_exchange.name(), buy_asset);
+        }
+        if !sold.deposits_enabled {
+            anyhow::bail!("{} deposits of {} are suspended", sell_exchange.name(), sell_asset);
+        }
+        
+        Ok(())
+    }
+    
+    async fn execute_opportunities(&mut self) -> Result<()> {
+        if self.kill_switch_engaged() {
+            debug!("Kill switch engaged, skipping execution");
+            return Ok(());
+        }
+        
+        if let Some(halt) = self.check_trading_halt().await? {
+            debug!("Trading halted until {}, skipping execution", halt.halted_until);
+            return Ok(());
+        }
+        
+        self.refresh_approvals().await?;
+        if let Err(e) = self.refresh_fill_counts().await {
+            warn!("Failed to read route fill rates, scoring with the last ones read: {}", e);
+        }
+        
+        let now = Utc::now();
+        let candidates: Vec<_> = self.active_opportunities.read().unwrap().values()
+            .filter(|opportunity| {
+                (!opportunity.requires_approval || opportunity.approved_at.is_some())
+                    && self.exchange_manager.can_execute(&opportunity.buy_exchange)
+                    && self.exchange_manager.can_execute(&opportunity.sell_exchange)
+                    && !self.route_cooldowns.is_cooling(&Self::opportunity_key(opportunity), now)
+            })
+            .cloned()
+            .collect();
+        
+        let mut scored = Vec::with_capacity(candidates.len());
+        for opportunity in candidates {
+            let inputs = self.score_inputs(&opportunity).await;
+            scored.push((opportunity, inputs));
+        }
+        let trading = &self.config.trading;
+        let quote_horizon = trading.max_quote_age_ms.map(Duration::from_millis)
+            .unwrap_or(Duration::from_secs(trading.check_interval_seconds * 2));
+        let latency_budget = trading.max_execution_latency_ms.map(Duration::from_millis).unwrap_or(DEFAULT_LATENCY_BUDGET);
+        let ranked = scoring::rank(scored, &trading.scoring, quote_horizon, latency_budget);
+        
+        // The whole batch is awaited before the next cycle scans, so taking at most max_concurrent_trades
+        // executions here bounds what is ever in flight. Each check awaits prices or balances before its
+        // reservation is made, so sizing and reserving go one opportunity at a time and each sees what the
+        // ones before it claimed; an opportunity whose reservation fails leaves its place to the next one.
+        let max_concurrent = self.config.trading.max_concurrent_trades;
+        let mut reserved = Vec::new();
+        for (opportunity, score) in ranked {
+            if self.shutdown_requested() || self.kill_switch_engaged() {
+                break;
+            }
+            if reserved.len() >= max_concurrent {
+                debug!("{} executions already in this batch, {} {} -> {} waits for the next cycle",
+                       max_concurrent, opportunity.pair.symbol, opportunity.buy_exchange, opportunity.sell_exchange);
+                break;
+            }
+            let span = opportunity_span(&opportunity);
+            if let Some(opportunity) = self.reserve_execution(opportunity).instrument(span.clone()).await {
+                info!("Executing {} {} -> {} (opportunity {}), {}",
+                      opportunity.pair.symbol, opportunity.buy_exchange, opportunity.sell_exchange, opportunity.id, score);
+                reserved.push((opportunity, span));
+            }
+        }
+        
+        let executions = reserved.into_iter().map(|(opportunity, span)| {
+            self.execute_active_opportunity(opportunity).instrument(span)
+        });
+        for result in futures::future::join_all(executions).await {
+            result?;
+        }
+        
+        Ok(())
+    }
+    
+    async fn refresh_fill_counts(&mut self) -> Result<()> {
+        if self.fill_counts.as_ref().map_or(false, |(read_at, _)| read_at.elapsed() < FILL_COUNT_REFRESH_INTERVAL) {
+            return Ok(());
+        }
+        
+        let since = Utc::now() - chrono::Duration::days(self.config.trading.scoring.fill_rate_days as i64);
+        let counts = self.database.get_route_fill_counts(since).await?.into_iter()
+            .map(|count| (
+                format!("{}-{}-{}", count.pair, count.buy_exchange, count.sell_exchange),
+                (count.executed as u64, count.failed as u64),
+            ))
+            .collect();
+        self.fill_counts = Some((Instant::now(), counts));
+        Ok(())
+    }
+    
+    async fn score_inputs(&self, opportunity: &ArbitrageOpportunity) -> ScoreInputs {
+        let quote_usd = self.valuation.usd_price(&self.exchange_manager, &opportunity.pair.quote).await
+            .unwrap_or_default();
+        let (executed, failed) = self.fill_counts.as_ref()
+            .and_then(|(_, counts)| counts.get(&Self::opportunity_key(opportunity)).copied())
+            .unwrap_or_default();
+        
+        let sell_pair = self.exchange_manager.canonical_pair(&opportunity.sell_exchange, &opportunity.sell_pair);
+        let quote = |exchange: &str, pair: &TradingPair| self.latest_prices.get(&pair.symbol)
+            .and_then(|prices| prices.get(exchange));
+        let buy = quote(&opportunity.buy_exchange, &opportunity.pair);
+        let sell = quote(&opportunity.sell_exchange, &sell_pair);
+        
+        let quote_age = match (buy, sell) {
+            (Some(buy), Some(sell)) => Some(Utc::now().signed_duration_since(buy.timestamp.min(sell.timestamp))
+                .to_std()
+                .unwrap_or_default()),
+            _ => None,
+        };
+        let health = self.exchange_manager.health_tracker();
+        let leg_latency = |price: Option<&Price>| price
+            .and_then(|price| health.order_latency(&price.exchange).or(price.fetch_latency));
+        let latency = match (leg_latency(buy), leg_latency(sell)) {
+            (Some(buy_latency), Some(sell_latency)) => Some(buy_latency + sell_latency),
+            _ => None,
+        };
+        
+        ScoreInputs {
+            profit_usd: opportunity.adjusted_profit_amount * quote_usd,
+            executed,
+            failed,
+            quote_age,
+            latency,
+        }
+    }
+    
+    // Sizes the opportunity within the risk limits and unclaimed balances and claims both; None when it
+    // is skipped. Must not run alongside another reservation: each check is only valid until its claim.
+    async fn reserve_execution(&self, mut opportunity: ArbitrageOpportunity) -> Option<ArbitrageOpportunity> {
+        if let Err(e) = self.check_transfer_status(&opportunity).await {
+            warn!("Skipping opportunity {}: {}", opportunity.id, e);
+            return None;
+        }
+        
+        let trade_usd = match self.apply_position_limits(&mut opportunity).await {
+            Ok(trade_usd) => trade_usd,
+            Err(e) => {
+                warn!("Risk limits blocked opportunity {}: {}", opportunity.id, e);
+                return None;
+            }
+        };
+        
+        if let Err(e) = self.reserve_balances(&mut opportunity).await {
+            warn!("Skipping opportunity {}: {}", opportunity.id, e);
+            return None;
+        }
+        
+        self.risk.reserve(opportunity.id, trade_usd);
+        Some(opportunity)
+    }
+    
+    // Runs an opportunity reserved by `reserve_execution` and releases its reservations. Everything
+    // logged from here through the order fills carries the opportunity's span
+    async fn execute_active_opportunity(&self, mut opportunity: ArbitrageOpportunity) -> Result<()> {
+        let result = self.execute_opportunity(&opportunity).await;
+        self.risk.release(opportunity.id);
+        self.balances.release(opportunity.id);
+        self.balances.invalidate(&opportunity.buy_exchange);
+        self.balances.invalidate(&opportunity.sell_exchange);
+        
+        let key = Self::opportunity_key(&opportunity);
+        opportunity.status = match result {
+            Ok(()) => {
+                if !self.dry_run {
+                    self.notifications.trade_executed(&opportunity);
+                }
+                self.notifications.clear_error("execution");
+                if self.route_cooldowns.record_success(&key) {
+                    self.database.clear_route_failure(&key).await?;
+                }
+                OpportunityStatus::Executed
+            },
+            Err(e) => {
+                error!("Failed to execute opportunity {}: {}", opportunity.id, e);
+                if !self.dry_run {
+                    self.notifications.trade_failed(&opportunity, &e);
+                }
+                self.record_error("execution", &e);
+                match BotError::find(&e) {
+                    // Nothing wrong with the route: balances are re-read above and the venue's limit recovers
+                    // on its own, so neither counts towards a route cooldown
+                    Some(BotError::InsufficientBalance { .. } | BotError::RateLimited { .. }) => {},
+                    // Every later order on the venue would be refused as well
+                    Some(BotError::AuthFailed { exchange, .. }) => {
+                        error!("{} rejected the API credentials, engaging the kill switch", exchange);
+                        control::set_engaged(&self.kill_switch, true);
+                    },
+                    _ => {
+                        let failure = self.route_cooldowns.record_failure(&key, &e, Utc::now());
+                        self.database.save_route_failure(&failure).await?;
+                    },
+                }
+                OpportunityStatus::Failed
+            }
+        };
+        
+        self.active_opportunities.write().unwrap().remove(&key);
+        self.database.update_opportunity_status(&opportunity).await?;
+        
+        Ok(())
+    }
+    
+    // Re-sizes against balances not yet claimed by other executions, then claims what this one needs
+    async fn reserve_balances(&self, opportunity: &mut ArbitrageOpportunity) -> Result<()> {
+        let buy_exchange = self.exchange_manager.get_exchange(&opportunity.buy_exchange)
+            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", opportunity.buy_exchange))?;
+        let sell_exchange = self.exchange_manager.get_exchange(&opportunity.sell_exchange)
+            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", opportunity.sell_exchange))?;
+        
+        let buy_fee = self.exchange_manager.trading_fees(buy_exchange, &opportunity.buy_pair).await?.taker_fee;
+        let max_slippage = self.config.pair_settings(&opportunity.pair.symbol).max_slippage;
+        let unit_cost = opportunity.buy_price * (Decimal::ONE + max_slippage + buy_fee);
+        let affordable = self.affordable_size(
+            buy_exchange, sell_exchange, &opportunity.buy_pair, &opportunity.sell_pair, unit_cost,
+        ).await?;
+        if affordable <= Decimal::ZERO {
+            anyhow::bail!("No unreserved {} on {} or {} on {}",
+                          opportunity.buy_pair.quote, opportunity.buy_exchange, opportunity.sell_pair.base, opportunity.sell_exchange);
+        }
+        if affordable < opportunity.max_trade_size {
+            info!("Reducing opportunity {} from {} to {} {} to fit available balances",
+                  opportunity.id, opportunity.max_trade_size, affordable, opportunity.pair.base);
+            opportunity.max_trade_size = affordable;
+        }
+        
+        let quote_needed = opportunity.max_trade_size * unit_cost;
+        self.balances.reserve(opportunity.id, &opportunity.buy_exchange, &opportunity.buy_pair.quote, quote_needed);
+        self.balances.reserve(opportunity.id, &opportunity.sell_exchange, &opportunity.sell_pair.base, opportunity.max_trade_size);
+        Ok(())
+    }
+    
+    // Returns the active halt, creating one when today's realized loss has reached the daily limit.
+    // Losses from before a manual `resume` do not count again.
+    async fn check_trading_halt(&self) -> Result<Option<TradingHalt>> {
+        let now = Utc::now();
+        if let Some(halt) = self.database.get_active_trading_halt(now).await? {
+            return Ok(Some(halt));
+        }
+        
+        // Until the first snapshot the portfolio is valued here, so the limit applies from the first cycle
+        let valued;
+        let portfolio = match &self.portfolio {
+            Some(portfolio) => portfolio,
+            None => {
+                valued = self.valuation.value_portfolio(&self.exchange_manager).await?;
+                &valued
+            }
+        };
+        
+        let day_start = risk::start_of_day(now);
+        let since = self.database.get_last_halt_clearance(day_start).await?
+            .map(|cleared_at| cleared_at.max(day_start))
+            .unwrap_or(day_start);
+        let realized = self.database.get_pnl_summary(since, now, self.dry_run).await?.total_realized;
+        
+        if !self.risk.loss_limit_breached(portfolio, realized) {
+            return Ok(None);
+        }
+        
+        let halt = TradingHalt {
+            id: uuid::Uuid::new_v4(),
+            reason: format!("Realized loss of ${:.2} today reached the daily limit of ${:.2}",
+                            -realized, self.risk.daily_loss_limit(portfolio)),
+            halted_at: now,
+            halted_until: risk::end_of_day(now),
+            cleared_at: None,
+        };
+        self.database.save_trading_halt(&halt).await?;
+        error!("Trading halted until {}: {}", halt.halted_until, halt.reason);
+        self.notifications.trading_halted(&halt);
+        
+        Ok(Some(halt))
+    }
+    
+    // Caps the size at the per-trade USD limit and checks total exposure; returns the trade's USD value
+    async fn apply_position_limits(&self, opportunity: &mut ArbitrageOpportunity) -> Result<Decimal> {
+        let quote_usd = self.valuation.usd_price(&self.exchange_manager, &opportunity.pair.quote).await
+            .ok_or_else(|| anyhow::anyhow!("No USD price for {}", opportunity.pair.quote))?;
+        let unit_usd = opportunity.buy_price * quote_usd;
+        
+        let capped = self.risk.cap_size(opportunity.max_trade_size, unit_usd);
+        if capped < opportunity.max_trade_size {
+            info!("Capping opportunity {} from {} to {} {} by the position size limit",
+                  opportunity.id, opportunity.max_trade_size, capped, opportunity.pair.base);
+            opportunity.max_trade_size = capped;
+        }
+        
+        let trade_usd = opportunity.max_trade_size * unit_usd;
+        let held_usd = self.held_inventory_usd().await?;
+        self.risk.check_exposure(trade_usd, held_usd)?;
+        
+        Ok(trade_usd)
+    }
+    
+    // Inventory bought by executions whose sells did not match it, whether or not it was hedged or is
+    // being accumulated, valued at today's prices
+    async fn held_inventory_usd(&self) -> Result<Decimal> {
+        let trades = self.database.get_executed_trades(self.dry_run).await?;
+        let now = Utc::now();
+        let mut held = Decimal::ZERO;
+        
+        for (asset, inventory) in pnl::summarize(now, now, &trades).inventory {
+            if inventory.quantity <= Decimal::ZERO {
+                continue;
+            }
+            held += match self.valuation.usd_price(&self.exchange_manager, &asset).await {
+                Some(asset_usd) => inventory.quantity * asset_usd,
+                // The cost basis is in the quote, which the limit treats as USD when it has no price
+                None => inventory.cost_basis,
+            };
+        }
+        
+        Ok(held)
+    }
+    
+    async fn execute_opportunity(&self, opportunity: &ArbitrageOpportunity) -> Result<()> {
+        if self.dry_run {
+            return self.simulate_opportunity(opportunity).await;
+        }
+        
+        info!("Executing arbitrage opportunity: {} -> {}, {:.2}% profit",
+              opportunity.buy_exchange, opportunity.sell_exchange, opportunity.profit_percentage);
+        
+        let buy_exchange = self.exchange_manager.get_exchange(&opportunity.buy_exchange)
+            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", opportunity.buy_exchange))?;
+        let sell_exchange = self.exchange_manager.get_exchange(&opportunity.sell_exchange)
+            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", opportunity.sell_exchange))?;
+        
+        self.revalidate_opportunity(buy_exchange, sell_exchange, opportunity).await?;
+        
+        // A flash loan is repaid in the asset it lent, so routes across equivalent quotes run leg by leg
+        let sell_quote = self.exchange_manager.canonical_pair(&opportunity.sell_exchange, &opportunity.sell_pair).quote;
+        let cross_quote = sell_quote != opportunity.pair.quote;
+        if let Some(executor) = self.atomic_route(buy_exchange, sell_exchange)?.filter(|_| !cross_quote) {
+            return self.execute_atomic(&executor, buy_exchange, sell_exchange, opportunity).await;
+        }
+        
+        let timeouts = self.exchange_manager.timeouts();
+        let buy_rules = self.exchange_manager.call(
+            buy_exchange.name(), "get_symbol_rules", timeouts.account(),
+            buy_exchange.get_symbol_rules(&opportunity.buy_pair),
+        ).await?;
+        let buy_amount = buy_rules.round_quantity(opportunity.max_trade_size);
+        buy_rules.check_order(buy_amount, opportunity.buy_price)?;
+        
+        // A quote-sized buy spends what the rounded size costs at the quoted price; its fill reports the base bought
+        let mut buy_request = self.leg_order(opportunity, TradeSide::Buy, buy_amount);
+        if buy_request.price.is_none() && self.config.trading.quote_sized_buys && buy_exchange.supports_quote_orders() {
+            buy_request.amount = OrderAmount::Quote(buy_amount * opportunity.buy_price);
+        }
+        let bought = self.work_leg(buy_exchange, opportunity, "b", &buy_rules, buy_request).await?;
+        if bought.filled.is_zero() {
+            anyhow::bail!("Buy leg on {} ended as {:?}", opportunity.buy_exchange, bought.last_status());
+        }
+        if bought.unfilled > Decimal::ZERO || bought.open > Decimal::ZERO {
+            info!("Buy leg of opportunity {} filled {} of {} ({} still open), selling only what was bought",
+                  opportunity.id, bought.filled, bought.filled + bought.unfilled + bought.open, bought.open);
+        }
+        
+        // Whatever the sell leg could not place or fill is left over from the buy; what is still open on
+        // the sell venue may yet fill, so only the unfilled rest is unwound
+        let sold = match self.execute_sell_leg(sell_exchange, opportunity, bought.filled).await {
+            Ok(sold) if sold.unfilled.is_zero() && sold.open.is_zero() => sold,
+            Ok(sold) => {
+                self.resolve_stranded_inventory(opportunity, sold.unfilled).await;
+                anyhow::bail!("Sell leg on {} sold {} of {} with {} still open and ended as {:?}",
+                              opportunity.sell_exchange, sold.filled, bought.filled, sold.open, sold.last_status());
+            },
+            Err(e) if exchanges::outcome_unknown(&e) => {
+                self.report_unknown_sell(sell_exchange, opportunity, bought.filled, &e).await;
+                return Err(e);
+            },
+            Err(e) => {
+                self.resolve_stranded_inventory(opportunity, bought.filled).await;
+                return Err(e);
+            }
+        };
+        
+        info!("Trade execution completed for opportunity {}: bought {} at {}, sold {} at {}",
+              opportunity.id, bought.filled, bought.average_price(), sold.filled, sold.average_price());
+        
+        if cross_quote {
+            self.settle_quote_residual(sell_exchange, opportunity, &sell_quote, sold.proceeds(&opportunity.sell_pair.quote)).await;
+        }
+        Ok(())
+    }
+    
+    // The sell leg paid out in an equivalent quote; swap it back to the buy leg's quote or keep it as configured.
+    // The arbitrage itself is complete either way, so a failed conversion is reported rather than returned.
+    async fn settle_quote_residual(&self, sell_exchange: &dyn Exchange, opportunity: &ArbitrageOpportunity, sell_quote: &str, proceeds: Decimal) {
+        if self.quote_equivalence.residual() == ResidualQuote::Hold {
+            info!("Holding {} {} on {} from opportunity {} instead of converting to {}",
+                  proceeds, opportunity.sell_pair.quote, opportunity.sell_exchange, opportunity.id, opportunity.pair.quote);
+            return;
+        }
+        
+        match self.convert_proceeds(sell_exchange, opportunity, sell_quote, proceeds).await {
+            Ok(trade) if matches!(trade.status, TradeStatus::Executed) => {
+                info!("Converted {} {} proceeds of opportunity {} on {}: {:?} {} {} at {}",
+                      proceeds, opportunity.sell_pair.quote, opportunity.id, opportunity.sell_exchange,
+                      trade.side, trade.amount, trade.pair.symbol, trade.price);
+            },
+            Ok(trade) => {
+                self.cancel_pending_order(sell_exchange, &trade).await;
+                warn!("Conversion of {} {} for opportunity {} ended as {:?}; the residual stays on {}",
+                      proceeds, opportunity.sell_pair.quote, opportunity.id, trade.status, opportunity.sell_exchange);
+            },
+            Err(e) => {
+                error!("Failed to convert {} {} for opportunity {}: {}", proceeds, opportunity.sell_pair.quote, opportunity.id, e);
+                self.record_error("quote_conversion", &e);
+            }
+        }
+    }
+    
+    async fn convert_proceeds(&self, sell_exchange: &dyn Exchange, opportunity: &ArbitrageOpportunity, from: &str, proceeds: Decimal) -> Result<Trade> {
+        let to = &opportunity.pair.quote;
+        let (pair, side) = self.conversion_pair(sell_exchange.name(), from, to)
+            .ok_or_else(|| anyhow::anyhow!("{} cannot convert {} to {}", sell_exchange.name(), from, to))?;
+        
+        // Buying the target asset is sized in it, at the venue's current ask
+        let amount = match side {
+            TradeSide::Sell => proceeds,
+            TradeSide::Buy => {
+                let ask = self.latest_prices.get(&TradingPair::new(to, from).symbol)
+                    .and_then(|prices| prices.get(sell_exchange.name()))
+                    .map(|price| price.ask)
+                    .filter(|ask| *ask > Decimal::ZERO)
+                    .ok_or_else(|| anyhow::anyhow!("No {} price on {}", pair.symbol, sell_exchange.name()))?;
+                proceeds / ask
+            },
+        };
+        
+        let timeouts = self.exchange_manager.timeouts();
+        let rules = self.exchange_manager.call(
+            sell_exchange.name(), "get_symbol_rules", timeouts.account(), sell_exchange.get_symbol_rules(&pair),
+        ).await?;
+        let amount = rules.round_quantity(amount);
+        
+        // The proceeds are a quote amount, so venues that can spend one exactly do so
+        let amount = match side {
+            TradeSide::Buy if sell_exchange.supports_quote_orders() => OrderAmount::Quote(proceeds),
+            _ => OrderAmount::Base(amount),
+        };
+        let request = OrderRequest { pair, side, amount, price: None, options: OrderOptions::default() };
+        let trade = self.place_leg(sell_exchange, opportunity.id, "c", request).await?;
+        
+        self.wait_for_fill(sell_exchange, trade).await
+    }
+    
+    // Same-chain DEX routes skip leg risk entirely when the chain has an executor contract
+    fn atomic_route(&self, buy_exchange: &dyn Exchange, sell_exchange: &dyn Exchange) -> Result<Option<AtomicExecutor>> {
+        match (buy_exchange.chain_id(), sell_exchange.chain_id()) {
+            (Some(buy_chain), Some(sell_chain))
+                if buy_chain == sell_chain && buy_exchange.atomic_venue().is_some() && sell_exchange.atomic_venue().is_some() => {
+                Ok(self.blockchain_manager.atomic_executor(buy_chain)?)
+            },
+            _ => Ok(None),
+        }
+    }
+    
+    // Borrows the quote asset, buys on one venue, sells on the other and repays in a single transaction
+    async fn execute_atomic(
+        &self,
+        executor: &AtomicExecutor,
+        buy_exchange: &dyn Exchange,
+        sell_exchange: &dyn Exchange,
+        opportunity: &ArbitrageOpportunity,
+    ) -> Result<()> {
+        let (Some(buy_venue), Some(sell_venue)) = (buy_exchange.atomic_venue(), sell_exchange.atomic_venue()) else {
+            anyhow::bail!("{} and {} cannot be executed atomically", buy_exchange.name(), sell_exchange.name());
+        };
+        
+        // Both legs are on one chain, so the buy venue's token addresses serve the sell venue too
+        let pair = &opportunity.buy_pair;
+        let base = buy_venue.token_address(&pair.base)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.base))?;
+        let quote = buy_venue.token_address(&pair.quote)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.quote))?;
+        let base_decimals = buy_venue.token_decimals(base).await?;
+        let quote_decimals = buy_venue.token_decimals(quote).await?;
+        
+        let notional = opportunity.max_trade_size * opportunity.buy_price;
+        let loan_amount = utils::decimal_to_u256(notional, quote_decimals)?;
+        let buy = buy_venue.encode_swap(quote, base, loan_amount, executor.address()).await?;
+        // Only the first swap's guaranteed output is committed to the second; any surplus stays in the contract
+        let sell = sell_venue.encode_swap(base, quote, buy.step.min_amount_out, executor.address()).await?;
+        let premium = executor.flash_loan_premium(loan_amount).await?;
+        let plan = AtomicPlan::new(quote, loan_amount, premium, buy, sell)?;
+        
+        let prepared = executor.prepare(&plan).await?;
+        info!("Atomic arbitrage for opportunity {} simulated at {} {} profit via {:?}",
+              opportunity.id, utils::u256_to_decimal(prepared.simulated_profit, quote_decimals)?, pair.quote, prepared.call);
+        
+        let private = executor.transactions().private_relay().map_or(false, |relay| relay.should_route(notional));
+        let tx_hash = executor.submit(prepared, private).await?;
+        let receipt = executor.transactions().confirm(tx_hash).await?;
+        let fill = executor.settle(&plan, &receipt);
+        
+        let (status, failure_reason) = if fill.success {
+            (TradeStatus::Executed, None)
+        } else {
+            (TradeStatus::Failed, executor.revert_reason(tx_hash).await)
+        };
+        let tx_hash = format!("{:?}", tx_hash);
+        let native_symbol = buy_exchange.chain_id()
+            .and_then(|chain_id| self.config.blockchain.chain_by_id(chain_id))
+            .map(|chain| chain.native_symbol.clone())
+            .filter(|symbol| !symbol.is_empty());
+        let price_or = |quote_amount: Decimal, base_amount: Decimal, fallback: Decimal| {
+            if base_amount > Decimal::ZERO { quote_amount / base_amount } else { fallback }
+        };
+        
+        let base_bought = utils::u256_to_decimal(fill.base_bought, base_decimals)?;
+        let base_sold = utils::u256_to_decimal(plan.sell.step.amount_in, base_decimals)?;
+        let quote_received = utils::u256_to_decimal(fill.quote_received, quote_decimals)?;
+        let leg = |exchange: &dyn Exchange, pair: &TradingPair, side, amount, price, fee_amount, fee_asset| Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: opportunity.id,
+            exchange: exchange.name().to_string(),
+            pair: pair.clone(),
+            side,
+            amount,
+            price,
+            fee_amount,
+            fee_asset,
+            status: status.clone(),
+            created_at: Utc::now(),
+            executed_at: Some(Utc::now()),
+            order_id: Some(tx_hash.clone()),
+            client_order_id: None,
+            tx_hash: Some(tx_hash.clone()),
+            simulated: false,
+            failure_reason: failure_reason.clone(),
+        };
+        
+        // The flash loan premium is charged to the buy leg and the gas of the whole transaction to the sell leg
+        let buy_trade = leg(
+            buy_exchange, &opportunity.buy_pair, TradeSide::Buy, base_bought,
+            price_or(notional, base_bought, opportunity.buy_price),
+            utils::u256_to_decimal(plan.premium, quote_decimals)?, Some(pair.quote.clone()),
+        );
+        let sell_trade = leg(
+            sell_exchange, &opportunity.sell_pair, TradeSide::Sell, base_sold,
+            price_or(quote_received, base_sold, opportunity.sell_price),
+            utils::u256_to_decimal(fill.gas_native, 18)?, native_symbol,
+        );
+        self.database.save_trade(&buy_trade).await?;
+        self.database.save_trade(&sell_trade).await?;
+        
+        if !fill.success {
+            anyhow::bail!("Atomic arbitrage {} reverted on chain: {}", tx_hash, failure_reason.as_deref().unwrap_or("unknown reason"));
+        }
+        
+        info!("Atomic arbitrage completed for opportunity {} in {}: bought {} at {}, sold {} at {}",
+              opportunity.id, tx_hash, buy_trade.amount, buy_trade.price, sell_trade.amount, sell_trade.price);
+        Ok(())
+    }
+    
+    async fn cancel_pending_order(&self, exchange: &dyn Exchange, trade: &Trade) {
+        let Some(order_id) = trade.order_id.as_deref().filter(|_| matches!(trade.status, TradeStatus::Pending)) else {
+            return;
+        };
+        
+        let cancel = exchange.cancel_order(order_id);
+        match self.exchange_manager.call(exchange.name(), "cancel_order", self.exchange_manager.timeouts().order(), cancel).await {
+            Ok(()) => {
+                self.open_orders.lock().unwrap().remove(order_id);
+            },
+            Err(e) => warn!("Failed to cancel unfilled {:?} order {} on {}: {}", trade.side, order_id, exchange.name(), e),
+        }
+    }
+    
+    async fn execute_sell_leg(&self, sell_exchange: &dyn Exchange, opportunity: &ArbitrageOpportunity, amount: Decimal) -> Result<LegFill> {
+        let sell_rules = self.exchange_manager.call(
+            sell_exchange.name(), "get_symbol_rules", self.exchange_manager.timeouts().account(),
+            sell_exchange.get_symbol_rules(&opportunity.sell_pair),
+        ).await?;
+        // Whatever is below the sell venue's step stays behind as dust
+        let amount = sell_rules.round_quantity(amount);
+        sell_rules.check_order(amount, opportunity.native_sell_price())?;
+        
+        let sell_request = self.leg_order(opportunity, TradeSide::Sell, amount);
+        self.work_leg(sell_exchange, opportunity, "s", &sell_rules, sell_request).await
+    }
+    
+    // Works a leg until it is filled or out of requotes. An order still open after order_timeout_ms is
+    // cancelled and what it filled is kept; the rest is requoted off the venue's current price. Once
+    // something has filled, a failed requote ends the leg instead of losing track of those fills
+    async fn work_leg(
+        &self,
+        exchange: &dyn Exchange,
+        opportunity: &ArbitrageOpportunity,
+        leg: &str,
+        rules: &SymbolRules,
+        mut request: OrderRequest,
+    ) -> Result<LegFill> {
+        let mut fill = LegFill::default();
+        let mut attempt: u32 = 1;
+        loop {
+            let tag = if attempt == 1 { leg.to_string() } else { format!("{}{}", leg, attempt) };
+            let trade = match self.place_leg(exchange, opportunity.id, &tag, request.clone()).await {
+                Ok(trade) => trade,
+                Err(e) if fill.trades.is_empty() => return Err(e),
+                Err(e) => {
+                    warn!("Requote {} of {:?} leg for opportunity {} on {} failed: {}", attempt - 1, request.side, opportunity.id, exchange.name(), e);
+                    break;
+                }
+            };
+            let trade = self.settle_order(exchange, trade).await?;
+            let (status, price) = (trade.status.clone(), request.price.unwrap_or(trade.price));
+            let filled = if matches!(status, TradeStatus::Executed) { trade.amount } else { Decimal::ZERO };
+            fill.record(trade);
+            
+            // A quote-sized buy spends its quote in one go, so there is no base left to requote
+            let OrderAmount::Base(amount) = request.amount else {
+                break;
+            };
+            // The order stays in open_orders for cleanup, and neither requoting nor unwinding its rest is safe
+            if matches!(status, TradeStatus::Pending) {
+                fill.unfilled = Decimal::ZERO;
+                fill.open = amount - filled;
+                break;
+            }
+            let remaining = rules.round_quantity(amount - filled);
+            fill.unfilled = if rules.check_order(remaining, price).is_ok() { remaining } else { Decimal::ZERO };
+            if fill.unfilled.is_zero() || !matches!(status, TradeStatus::Executed | TradeStatus::Cancelled) {
+                break;
+            }
+            if attempt > self.config.trading.max_requotes {
+                break;
+            }
+            
+            match self.requote(exchange, opportunity, &request, fill.unfilled).await {
+                Ok(Some(requote)) => request = requote,
+                Ok(None) => break,
+                Err(e) => {
+                    warn!("Could not requote {:?} leg for opportunity {} on {}: {}", request.side, opportunity.id, exchange.name(), e);
+                    break;
+                }
+            }
+            attempt += 1;
+            info!("Requoting {} {} of opportunity {} on {} at {:?} (attempt {})",
+                  fill.unfilled, request.pair.base, opportunity.id, exchange.name(), request.price, attempt);
+        }
+        Ok(fill)
+    }
+    
+    // The unfilled rest of a leg, priced off the venue's current quote the way leg_order prices the first order
+    async fn requote(&self, exchange: &dyn Exchange, opportunity: &ArbitrageOpportunity, request: &OrderRequest, remaining: Decimal) -> Result<Option<OrderRequest>> {
+        let quote = self.exchange_manager.call(
+            exchange.name(), "get_price", self.exchange_manager.timeouts().price(), exchange.get_price(&request.pair),
+        ).await?;
+        let max_slippage = self.config.pair_settings(&opportunity.pair.symbol).max_slippage;
+        let price = match request.side {
+            TradeSide::Buy => quote.ask * (Decimal::ONE + max_slippage),
+            TradeSide::Sell => quote.bid * (Decimal::ONE - max_slippage),
+        };
+        
+        // Buying more at or above what the sell leg is expected to fetch only adds to a loss
+        if matches!(request.side, TradeSide::Buy) && price >= opportunity.sell_price {
+            info!("Not requoting the buy leg of opportunity {}: {} now asks {}", opportunity.id, exchange.name(), quote.ask);
+            return Ok(None);
         }
+        
+        Ok(Some(OrderRequest {
+            amount: OrderAmount::Base(remaining),
+            price: request.price.map(|_| price),
+            ..request.clone()
+        }))
     }
     
-    pub async fn run(&mut self) -> Result<()> {
-        info!("Starting arbitrage bot main loop");
+    // Waits up to order_timeout_ms for the order to fill, then cancels it and reads back what it filled
+    async fn settle_order(&self, exchange: &dyn Exchange, trade: Trade) -> Result<Trade> {
+        let mut trade = self.wait_for_fill(exchange, trade).await?;
+        let Some(order_id) = trade.order_id.clone().filter(|_| matches!(trade.status, TradeStatus::Pending)) else {
+            return Ok(trade);
+        };
         
-        let mut interval = time::interval(Duration::from_secs(self.config.trading.check_interval_seconds));
+        info!("Order {} on {} not filled within {}ms, cancelling it", order_id, exchange.name(), self.config.trading.order_timeout_ms);
+        self.cancel_pending_order(exchange, &trade).await;
+        match self.exchange_manager.call(
+            exchange.name(), "get_order_status", self.exchange_manager.timeouts().order_status(),
+            exchange.get_order_status(&order_id),
+        ).await {
+            Ok(update) => {
+                apply_order_update(&mut trade, update);
+                self.database.update_trade_status(&trade).await?;
+            },
+            Err(e) => warn!("Failed to read back cancelled order {} on {}: {}", order_id, exchange.name(), e),
+        }
         
-        loop {
-            interval.tick().await;
+        if matches!(trade.status, TradeStatus::Pending) {
+            warn!("Order {} on {} is still open after cancelling; its leg is not requoted", order_id, exchange.name());
+        } else {
+            self.open_orders.lock().unwrap().remove(&order_id);
+        }
+        Ok(trade)
+    }
+    
+    // Places and records one leg of `opportunity_id`. A placement that failed without a definite answer may
+    // still have reached the venue, so it is looked up by its client id and only sent again if it never
+    // arrived; venues without client ids fail the leg instead, since a blind retry could double the position
+    async fn place_leg(&self, exchange: &dyn Exchange, opportunity_id: uuid::Uuid, leg: &str, mut request: OrderRequest) -> Result<Trade> {
+        if exchange.supports_client_order_ids() {
+            request.options.client_order_id = Some(client_order_id(opportunity_id, leg));
+        }
+        let operation = match request.side {
+            TradeSide::Buy => "place_buy_order",
+            TradeSide::Sell => "place_sell_order",
+        };
+        let timeouts = self.exchange_manager.timeouts();
+        
+        let mut attempt = 1;
+        let mut trade = loop {
+            let error = match self.exchange_manager.call(exchange.name(), operation, timeouts.order(), exchange.submit_order(&request)).await {
+                Ok(trade) => break trade,
+                Err(e) if !exchanges::outcome_unknown(&e) => return Err(e),
+                Err(e) => e,
+            };
+            let Some(client_id) = request.options.client_order_id.as_deref() else {
+                return Err(error.context(format!("{} cannot look orders up by client id, so the {} leg is not retried", exchange.name(), leg)));
+            };
             
-            if let Err(e) = self.scan_and_execute().await {
-                error!("Error in main loop: {}", e);
-                tokio::time::sleep(Duration::from_secs(5)).await;
+            warn!("Placing order {} on {} may have failed, looking it up: {}", client_id, exchange.name(), error);
+            let lookup = self.exchange_manager.call(
+                exchange.name(), "get_order_by_client_id", timeouts.order_status(),
+                exchange.get_order_by_client_id(&request.pair, client_id),
+            ).await;
+            match lookup {
+                Ok(Some(trade)) => {
+                    info!("Order {} reached {} despite the failed placement", client_id, exchange.name());
+                    break trade;
+                },
+                Ok(None) if attempt < LEG_PLACEMENT_ATTEMPTS => {
+                    info!("Order {} never reached {}, placing it again", client_id, exchange.name());
+                    attempt += 1;
+                },
+                Ok(None) => return Err(error),
+                Err(lookup_error) => {
+                    return Err(error.context(format!("order {} could not be looked up either: {}", client_id, lookup_error)));
+                },
             }
+        };
+        
+        trade.opportunity_id = opportunity_id;
+        if trade.client_order_id.is_none() {
+            trade.client_order_id = request.options.client_order_id;
         }
+        self.database.save_trade(&trade).await?;
+        Ok(trade)
     }
     
-    async fn scan_and_execute(&mut self) -> Result<()> {
-        debug!("Scanning for arbitrage opportunities");
+    // Limit legs are priced at the detected price moved against us by the slippage allowance, so they
+    // fill wherever the opportunity still holds and, as IOC by default, never rest once it is gone
+    fn leg_order(&self, opportunity: &ArbitrageOpportunity, side: TradeSide, amount: Decimal) -> OrderRequest {
+        let settings = self.config.pair_settings(&opportunity.pair.symbol);
+        let (pair, price) = match side {
+            TradeSide::Buy => (&opportunity.buy_pair, opportunity.buy_price * (Decimal::ONE + settings.max_slippage)),
+            TradeSide::Sell => (&opportunity.sell_pair, opportunity.native_sell_price() * (Decimal::ONE - settings.max_slippage)),
+        };
         
-        let enabled_exchanges = self.config.get_enabled_exchanges();
+        OrderRequest {
+            pair: pair.clone(),
+            side,
+            amount: OrderAmount::Base(amount),
+            price: (settings.order_type == LegOrderType::Limit).then_some(price),
+            options: OrderOptions {
+                time_in_force: settings.time_in_force,
+                ..OrderOptions::default()
+            },
+        }
+    }
+    
+    // The sell leg left `size` of the bought asset unsold. Accumulated assets are kept; anything else is sold
+    // back on the buy exchange at the market, recorded under the opportunity so its PnL carries the loss, and
+    // what that could not sell is shorted on the hedge venue to lock in its value
+    async fn resolve_stranded_inventory(&self, opportunity: &ArbitrageOpportunity, size: Decimal) {
+        if size <= Decimal::ZERO {
+            return;
+        }
+        let asset = &opportunity.buy_pair.base;
         
-        if enabled_exchanges.len() < 2 {
-            warn!("Need at least 2 exchanges for arbitrage");
-            return Ok(());
+        if self.config.trading.accumulate_assets.iter().any(|held| held.eq_ignore_ascii_case(asset)) {
+            let outcome = format!("Holding {} {} on {}, {} is being accumulated", size, asset, opportunity.buy_exchange, asset);
+            warn!("{} (opportunity {})", outcome, opportunity.id);
+            self.notifications.stranded_inventory(opportunity, &outcome);
+            return;
         }
         
-        let mut all_pairs = std::collections::HashSet::new();
-        for exchange_config in enabled_exchanges.values() {
-            for pair_str in &exchange_config.trading_pairs {
-                if let Some(pair) = self.parse_trading_pair(pair_str) {
-                    all_pairs.insert(pair);
+        let mut outcome = Vec::new();
+        let mut remaining = size;
+        if self.config.trading.unwind_on_leg_failure {
+            match self.unwind_inventory(opportunity, size).await {
+                Ok(unwound) => {
+                    warn!("Unwound {} {} stranded by opportunity {} on {} at {}",
+                          unwound.filled, asset, opportunity.id, opportunity.buy_exchange, unwound.average_price());
+                    outcome.push(format!("Sold {} {} back on {} at {}", unwound.filled, asset, opportunity.buy_exchange, unwound.average_price()));
+                    if unwound.open > Decimal::ZERO {
+                        outcome.push(format!("{} {} still on the book of {} after a failed cancel", unwound.open, asset, opportunity.buy_exchange));
+                    }
+                    remaining = unwound.unfilled;
+                },
+                Err(e) => {
+                    error!("Failed to unwind {} {} stranded by opportunity {}: {}", size, asset, opportunity.id, e);
+                    self.record_error("unwind", &e);
+                    outcome.push(format!("Selling {} {} back on {} failed: {}", size, asset, opportunity.buy_exchange, e));
                 }
             }
         }
         
-        for pair in all_pairs {
-            if let Err(e) = self.scan_pair_for_opportunities(&pair).await {
-                warn!("Error scanning pair {}: {}", pair.symbol, e);
+        if remaining > Decimal::ZERO && self.config.trading.hedge_on_leg_failure {
+            match self.open_hedge(opportunity, remaining).await {
+                Ok(()) => outcome.push(format!("Hedged {} {}", remaining, asset)),
+                Err(e) => {
+                    error!("Failed to hedge {} {} stranded by opportunity {}: {}", remaining, asset, opportunity.id, e);
+                    self.record_error("hedge", &e);
+                    outcome.push(format!("Hedging {} {} failed: {}", remaining, asset, e));
+                }
             }
+        } else if remaining > Decimal::ZERO {
+            outcome.push(format!("{} {} left unhedged on {}", remaining, asset, opportunity.buy_exchange));
         }
+        self.notifications.stranded_inventory(opportunity, &outcome.join("\n"));
+    }
+    
+    // The sell may have reached the venue without an answer, so unwinding as well could sell the inventory
+    // twice. Nothing is unwound; what the venue shows for the pair goes out with the alert instead
+    async fn report_unknown_sell(&self, sell_exchange: &dyn Exchange, opportunity: &ArbitrageOpportunity, size: Decimal, error: &anyhow::Error) {
+        let pair = &opportunity.sell_pair;
+        let timeouts = self.exchange_manager.timeouts();
+        error!("Sell of {} {} for opportunity {} on {} has an unknown outcome, leaving it unresolved: {}",
+               size, pair.base, opportunity.id, opportunity.sell_exchange, error);
+        let mut outcome = vec![format!("Unresolved: selling {} {} on {} may have gone through ({}); nothing was unwound",
+                                       size, pair.base, opportunity.sell_exchange, error)];
         
-        self.execute_opportunities().await?;
+        let orders = self.exchange_manager.call(
+            sell_exchange.name(), "get_open_orders", timeouts.order_status(), sell_exchange.get_open_orders(Some(pair)),
+        ).await;
+        match orders {
+            Ok(orders) => {
+                let sells: Vec<_> = orders.iter()
+                    .filter(|order| matches!(order.side, TradeSide::Sell))
+                    .map(|order| format!("{} ({} of {} filled)", order.order_id, order.filled, order.amount))
+                    .collect();
+                if sells.is_empty() {
+                    outcome.push(format!("No open {} sell orders on {}", pair.symbol, opportunity.sell_exchange));
+                } else {
+                    outcome.push(format!("Open {} sell orders on {}: {}", pair.symbol, opportunity.sell_exchange, sells.join(", ")));
+                }
+            },
+            Err(e) => outcome.push(format!("Open orders on {} could not be read: {}", opportunity.sell_exchange, e)),
+        }
         
-        self.cleanup_expired_opportunities().await?;
+        let balances = self.exchange_manager.call(
+            sell_exchange.name(), "get_balances", timeouts.account(), sell_exchange.get_balances(),
+        ).await;
+        match balances {
+            Ok(balances) => {
+                let held = balances.get(&pair.base).map(|balance| balance.total).unwrap_or_default();
+                outcome.push(format!("{} holds {} {}", opportunity.sell_exchange, held, pair.base));
+            },
+            Err(e) => outcome.push(format!("Balances on {} could not be read: {}", opportunity.sell_exchange, e)),
+        }
+        self.notifications.stranded_inventory(opportunity, &outcome.join("\n"));
+    }
+    
+    // A market sell, requoted like any leg if it does not fill, so the unwind is worked until the inventory is gone
+    async fn unwind_inventory(&self, opportunity: &ArbitrageOpportunity, size: Decimal) -> Result<LegFill> {
+        let exchange = self.exchange_manager.get_exchange(&opportunity.buy_exchange)
+            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", opportunity.buy_exchange))?;
+        let rules = self.exchange_manager.call(
+            exchange.name(), "get_symbol_rules", self.exchange_manager.timeouts().account(),
+            exchange.get_symbol_rules(&opportunity.buy_pair),
+        ).await?;
+        let amount = rules.round_quantity(size);
+        rules.check_order(amount, opportunity.buy_price)?;
+        
+        let request = OrderRequest {
+            pair: opportunity.buy_pair.clone(),
+            side: TradeSide::Sell,
+            amount: OrderAmount::Base(amount),
+            price: None,
+            options: OrderOptions::default(),
+        };
+        self.work_leg(exchange, opportunity, "u", &rules, request).await
+    }
+    
+    async fn open_hedge(&self, opportunity: &ArbitrageOpportunity, size: Decimal) -> Result<()> {
+        let hedge_exchange = self.config.trading.hedge_exchange.as_deref()
+            .ok_or_else(|| anyhow::anyhow!("No hedge exchange configured"))?;
+        let exchange = self.exchange_manager.get_exchange(hedge_exchange)
+            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", hedge_exchange))?;
+        let venue = exchange.hedge_venue()
+            .ok_or_else(|| anyhow::anyhow!("Exchange {} cannot open hedge positions", hedge_exchange))?;
         
+        let pair = self.exchange_manager.native_pair(hedge_exchange, &opportunity.pair);
+        let trade = self.exchange_manager.call(
+            hedge_exchange, "hedge", self.exchange_manager.timeouts().order(),
+            venue.hedge(&pair, size, TradeSide::Sell),
+        ).await?;
+        
+        let position = HedgePosition {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: opportunity.id,
+            exchange: hedge_exchange.to_string(),
+            pair,
+            side: TradeSide::Sell,
+            size: trade.amount,
+            entry_price: trade.price,
+            order_id: trade.order_id,
+            status: HedgeStatus::Open,
+            opened_at: Utc::now(),
+            closed_at: None,
+        };
+        self.database.save_hedge_position(&position).await?;
+        
+        warn!("Opened hedge {} on {}: short {} {} at {} for opportunity {}",
+              position.id, hedge_exchange, position.size, position.pair.base, position.entry_price, opportunity.id);
         Ok(())
     }
     
-    async fn scan_pair_for_opportunities(&mut self, pair: &TradingPair) -> Result<()> {
-        let mut prices = Vec::new();
+    // One request per enabled exchange to its API root over that exchange's own client settings, logging the
+    // egress path. Any HTTP answer counts as reachable; failures are only logged, health tracking takes over
+    // once scanning starts
+    async fn check_connectivity(&self) {
+        let checks = self.config.exchanges.iter()
+            .filter(|(_, exchange)| exchange.enabled)
+            .map(|(name, exchange)| async move {
+                let http = exchange.http();
+                let started = Instant::now();
+                let result = match utils::http_client_with(&http) {
+                    Ok(client) => client.get(&exchange.api_url).send().await.map_err(anyhow::Error::from),
+                    Err(e) => Err(e),
+                };
+                (name, utils::egress(&http), started.elapsed(), result)
+            });
         
-        for exchange in self.exchange_manager.get_all_exchanges() {
-            if exchange.supports_pair(pair) {
-                match exchange.get_price(pair).await {
-                    Ok(price) => {
-                        prices.push(price);
-                        debug!("Got price from {}: {} bid, {} ask", 
-                               exchange.name(), price.bid, price.ask);
-                    },
-                    Err(e) => {
-                        warn!("Failed to get price from {} for {}: {}", 
-                              exchange.name(), pair.symbol, e);
-                    }
-                }
+        for (name, egress, elapsed, result) in futures::future::join_all(checks).await {
+            match result {
+                Ok(response) => info!("{} reachable via {} in {:?} (HTTP {})", name, egress, elapsed, response.status().as_u16()),
+                Err(e) => warn!("{} unreachable via {}: {}", name, egress, e),
             }
         }
-        
-        if prices.len() < 2 {
-            return Ok(());
+    }
+    
+    // Failure counts survive restarts, so a route that was cooling down stays benched until its cooldown ends
+    pub async fn restore_route_cooldowns(&self) -> Result<()> {
+        let failures = self.database.get_route_failures().await?;
+        let now = Utc::now();
+        for failure in failures.iter().filter(|failure| failure.cooled_until.map_or(false, |until| until > now)) {
+            info!("Route {} is cooling down until {} after {} consecutive failed executions",
+                  failure.route, failure.cooled_until.unwrap_or(now), failure.consecutive_failures);
         }
-        
-        for i in 0..prices.len() {
-            for j in (i+1)..prices.len() {
-                let price1 = &prices[i];
-                let price2 = &prices[j];
-                
-                if let Some(opportunity) = self.calculate_arbitrage_opportunity(
-                    pair,
-                    &price1.exchange,
-                    &price2.exchange,
-                    price1.ask,
-                    price2.bid,
-                ).await? {
-                    self.add_opportunity(opportunity).await?;
-                }
-                
-                if let Some(opportunity) = self.calculate_arbitrage_opportunity(
-                    pair,
-                    &price2.exchange,
-                    &price1.exchange,
-                    price2.ask,
-                    price1.bid,
-                ).await? {
-                    self.add_opportunity(opportunity).await?;
-                }
-            }
+        self.route_cooldowns.restore(failures);
+        Ok(())
+    }
+    
+    // The alert window picks up where it left off, so a restart does not wait out `min_samples` again
+    pub async fn restore_spread_history(&self) -> Result<()> {
+        let now = Utc::now();
+        let window_hours = self.config.trading.spread_history.window_hours as i64;
+        let buckets = self.database.get_spread_buckets(now - chrono::Duration::hours(window_hours), now).await?;
+        self.spreads.restore(&buckets);
+        Ok(())
+    }
+    
+    pub async fn restore_token_blacklist(&self) -> Result<()> {
+        let tokens = self.database.get_blacklisted_tokens().await?;
+        if !tokens.is_empty() {
+            info!("{} tokens are blacklisted: {}", tokens.len(),
+                  tokens.iter().map(|token| format!("{} (chain {})", token.token, token.chain_id)).collect::<Vec<_>>().join(", "));
         }
-        
+        self.token_safety.restore(tokens);
         Ok(())
     }
     
-    async fn calculate_arbitrage_opportunity(
-        &self,
-        pair: &TradingPair,
-        buy_exchange: &str,
-        sell_exchange: &str,
-        buy_price: Decimal,
-        sell_price: Decimal,
-    ) -> Result<Option<ArbitrageOpportunity>> {
-        let gross_profit_pct = (sell_price - buy_price) / buy_price * Decimal::from(100);
-        
-        if gross_profit_pct <= self.config.trading.min_profit_threshold {
+    // The base token of an on-chain leg is probed the first time it comes up; returns why it must not be traded.
+    // A probe that errors is not cached, so the token is tried again on the next scan
+    async fn unsafe_token(&self, exchange: &dyn Exchange, pair: &TradingPair) -> Result<Option<String>> {
+        let Some(chain_id) = exchange.chain_id() else {
             return Ok(None);
+        };
+        match self.token_safety.status(chain_id, &pair.base) {
+            TokenStatus::Safe => return Ok(None),
+            TokenStatus::Blacklisted(reason) => return Ok(Some(format!("{} is blacklisted on chain {}: {}", pair.base, chain_id, reason))),
+            TokenStatus::Unknown => {},
         }
         
-        let buy_exchange_obj = self.exchange_manager.get_exchange(buy_exchange)
-            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", buy_exchange))?;
-        let sell_exchange_obj = self.exchange_manager.get_exchange(sell_exchange)
-            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", sell_exchange))?;
+        let probe = self.exchange_manager.call(
+            exchange.name(),
+            "probe_token",
+            self.exchange_manager.timeouts().order_book(),
+            exchange.probe_token(pair, self.token_safety.probe_amount()),
+        ).await?;
         
-        let buy_fees = buy_exchange_obj.get_trading_fees(pair).await?;
-        let sell_fees = sell_exchange_obj.get_trading_fees(pair).await?;
+        match self.token_safety.record(chain_id, &pair.base, exchange.name(), probe.as_ref(), Utc::now()) {
+            Some(blacklisted) => {
+                self.database.save_blacklisted_token(&blacklisted).await?;
+                Ok(Some(format!("{} is blacklisted on chain {}: {}", pair.base, chain_id, blacklisted.reason)))
+            },
+            None => Ok(None),
+        }
+    }
+    
+    // Picks up where a previous process left off: refreshes orders it never saw complete, finishes or
+    // fails opportunities that were mid-execution and puts untouched ones back into the active set
+    async fn recover_in_flight_state(&mut self) -> Result<()> {
+        for mut trade in self.database.get_pending_trades().await? {
+            let (Some(exchange), Some(order_id)) = (self.exchange_manager.get_exchange(&trade.exchange), trade.order_id.clone()) else {
+                warn!("Pending {:?} trade {} on {} cannot be checked", trade.side, trade.id, trade.exchange);
+                continue;
+            };
+            
+            let update = self.exchange_manager.call(
+                exchange.name(), "get_order_status", self.exchange_manager.timeouts().order_status(),
+                exchange.get_order_status(&order_id),
+            ).await;
+            match update {
+                Ok(update) => {
+                    apply_order_update(&mut trade, update);
+                    self.database.update_trade_status(&trade).await?;
+                    info!("Recovered order {} on {}: {:?}", order_id, trade.exchange, trade.status);
+                },
+                Err(e) => warn!("Failed to query recovered order {} on {}: {}", order_id, trade.exchange, e),
+            }
+            
+            if matches!(trade.status, TradeStatus::Pending) {
+                self.open_orders.lock().unwrap().insert(order_id, trade);
+            }
+        }
         
-        let total_fee_pct = buy_fees.taker_fee + sell_fees.taker_fee;
-        let net_profit_pct = gross_profit_pct - (total_fee_pct * Decimal::from(100));
+        let mut stranded: HashMap<(String, String), Decimal> = HashMap::new();
+        let mut rehydrated = 0;
         
-        if net_profit_pct <= self.config.trading.min_profit_threshold {
-            return Ok(None);
+        for mut opportunity in self.database.get_opportunities_with_status(&OpportunityStatus::Active).await? {
+            let trades = self.database.get_trades_for_opportunity(opportunity.id).await?;
+            if trades.is_empty() {
+                self.active_opportunities.write().unwrap().insert(Self::opportunity_key(&opportunity), opportunity);
+                rehydrated += 1;
+                continue;
+            }
+            
+            let status = match self.recover_opportunity(&opportunity, &trades).instrument(opportunity_span(&opportunity)).await {
+                Ok(status) => status,
+                Err(e) => {
+                    error!("Could not recover opportunity {}: {}", opportunity.id, e);
+                    self.notifications.trade_failed(&opportunity, &e);
+                    OpportunityStatus::Failed
+                }
+            };
+            
+            let buy = trades.iter().find(|trade| matches!(trade.side, TradeSide::Buy));
+            if let (OpportunityStatus::Failed, Some(buy)) = (&status, buy) {
+                if matches!(buy.status, TradeStatus::Executed) {
+                    *stranded.entry((buy.exchange.clone(), opportunity.buy_pair.base.clone())).or_default() += buy.amount;
+                }
+            }
+            
+            opportunity.status = status;
+            self.database.update_opportunity_status(&opportunity).await?;
         }
         
-        let max_trade_size = self.calculate_max_trade_size(
-            buy_exchange_obj,
-            sell_exchange_obj,
-            pair,
-            buy_price,
-            sell_price,
-        ).await?;
-        
-        if max_trade_size <= Decimal::ZERO {
-            return Ok(None);
+        if rehydrated > 0 {
+            info!("Restored {} active opportunities from the database", rehydrated);
         }
+        self.report_stranded_inventory(&stranded).await;
         
-        let profit_amount = max_trade_size * net_profit_pct / Decimal::from(100);
+        Ok(())
+    }
+    
+    async fn recover_opportunity(&self, opportunity: &ArbitrageOpportunity, trades: &[Trade]) -> Result<OpportunityStatus> {
+        let buy = trades.iter().find(|trade| matches!(trade.side, TradeSide::Buy));
+        let sell = trades.iter().find(|trade| matches!(trade.side, TradeSide::Sell));
         
-        let opportunity = ArbitrageOpportunity {
-            id: uuid::Uuid::new_v4(),
-            pair: pair.clone(),
-            buy_exchange: buy_exchange.to_string(),
-            sell_exchange: sell_exchange.to_string(),
-            buy_price,
-            sell_price,
-            profit_percentage: net_profit_pct,
-            profit_amount,
-            max_trade_size,
-            timestamp: Utc::now(),
-            status: OpportunityStatus::Active,
+        let buy = match buy {
+            Some(buy) if matches!(buy.status, TradeStatus::Executed) => buy,
+            Some(buy) if matches!(buy.status, TradeStatus::Pending) => {
+                anyhow::bail!("Buy order {} on {} is still open after restart",
+                              buy.order_id.as_deref().unwrap_or("?"), buy.exchange);
+            },
+            Some(_) => return Ok(OpportunityStatus::Failed),
+            None => return Ok(OpportunityStatus::Failed),
         };
         
-        info!("Found arbitrage opportunity: {:.2}% profit, ${:.2} potential profit",
-              net_profit_pct, profit_amount);
+        let sell_exchange = self.exchange_manager.get_exchange(&opportunity.sell_exchange)
+            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", opportunity.sell_exchange))?;
         
-        Ok(Some(opportunity))
+        let sold = match sell {
+            Some(sell) if matches!(sell.status, TradeStatus::Executed) => return Ok(OpportunityStatus::Executed),
+            Some(sell) if matches!(sell.status, TradeStatus::Pending) => {
+                let mut sold = LegFill::default();
+                sold.record(self.settle_order(sell_exchange, sell.clone()).await?);
+                // An order that would not cancel may still fill, so its rest is left open rather than unwound
+                let rest = (sell.amount - sold.filled).max(Decimal::ZERO);
+                if matches!(sold.last_status(), Some(TradeStatus::Pending)) {
+                    sold.open = rest;
+                } else {
+                    sold.unfilled = rest;
+                }
+                sold
+            },
+            _ if self.dry_run || buy.simulated || !self.exchange_manager.can_execute(sell_exchange.name()) => {
+                anyhow::bail!("Holding {} {} on {} from an unfinished buy leg; the sell leg was not resumed",
+                              buy.amount, opportunity.buy_pair.base, buy.exchange);
+            },
+            _ => {
+                info!("Resuming sell leg of opportunity {} on {}", opportunity.id, opportunity.sell_exchange);
+                match self.execute_sell_leg(sell_exchange, opportunity, buy.amount).await {
+                    Ok(sold) => sold,
+                    Err(e) => {
+                        if exchanges::outcome_unknown(&e) {
+                            self.report_unknown_sell(sell_exchange, opportunity, buy.amount, &e).await;
+                        }
+                        return Err(e);
+                    }
+                }
+            }
+        };
+        
+        if sold.unfilled.is_zero() && sold.open.is_zero() {
+            info!("Opportunity {} completed after restart", opportunity.id);
+            let sell_quote = self.exchange_manager.canonical_pair(&opportunity.sell_exchange, &opportunity.sell_pair).quote;
+            if sell_quote != opportunity.pair.quote {
+                self.settle_quote_residual(sell_exchange, opportunity, &sell_quote, sold.proceeds(&opportunity.sell_pair.quote)).await;
+            }
+            return Ok(OpportunityStatus::Executed);
+        }
+        
+        self.resolve_stranded_inventory(opportunity, sold.unfilled).await;
+        anyhow::bail!("Sell leg on {} sold {} of {} with {} still open and ended as {:?} after restart",
+                      opportunity.sell_exchange, sold.filled, buy.amount, sold.open, sold.last_status())
     }
     
-    async fn calculate_max_trade_size(
-        &self,
-        buy_exchange: &dyn Exchange,
-        sell_exchange: &dyn Exchange,
-        pair: &TradingPair,
-        buy_price: Decimal,
-        sell_price: Decimal,
-    ) -> Result<Decimal> {
-        let buy_order_book = buy_exchange.get_order_book(pair, 20).await?;
-        let sell_order_book = sell_exchange.get_order_book(pair, 20).await?;
+    // Unfinished buys leave base asset behind; check the venues actually hold at least that much
+    async fn report_stranded_inventory(&self, stranded: &HashMap<(String, String), Decimal>) {
+        if stranded.is_empty() {
+            info!("Inventory reconciliation: no unsold inventory from previous runs");
+            return;
+        }
         
-        let mut buy_liquidity = Decimal::ZERO;
-        for ask in &buy_order_book.asks {
-            if ask.price <= buy_price * (Decimal::ONE + self.config.trading.max_slippage) {
-                buy_liquidity += ask.quantity;
-            } else {
-                break;
+        for ((exchange_name, asset), expected) in stranded {
+            let Some(exchange) = self.exchange_manager.get_exchange(exchange_name) else {
+                warn!("Inventory reconciliation: expected {} {} on {}, exchange is not enabled", expected, asset, exchange_name);
+                continue;
+            };
+            
+            let balances = self.exchange_manager.call(
+                exchange_name, "get_balances", self.exchange_manager.timeouts().account(),
+                exchange.get_balances(),
+            ).await;
+            match balances {
+                Ok(balances) => {
+                    let actual = balances.get(asset).map(|balance| balance.total).unwrap_or_default();
+                    if actual < *expected {
+                        warn!("Inventory reconciliation: {} {} expected on {} from unsold buys, only {} held",
+                              expected, asset, exchange_name, actual);
+                    } else {
+                        info!("Inventory reconciliation: {} holds {} {} (at least {} from unsold buys)",
+                              exchange_name, actual, asset, expected);
+                    }
+                },
+                Err(e) => warn!("Inventory reconciliation: failed to fetch {} balances: {}", exchange_name, e),
             }
         }
-        
-        let mut sell_liquidity = Decimal::ZERO;
-        for bid in &sell_order_book.bids {
-            if bid.price >= sell_price * (Decimal::ONE - self.config.trading.max_slippage) {
-                sell_liquidity += bid.quantity;
+    }
+    
+    // Hedges outlive the process, so on startup check each tracked one against the venue and
+    // close out records whose position is already gone
+    async fn reconcile_hedge_positions(&self) -> Result<()> {
+        for position in self.database.get_open_hedge_positions().await? {
+            let venue = match self.exchange_manager.get_exchange(&position.exchange).and_then(|e| e.hedge_venue()) {
+                Some(venue) => venue,
+                None => {
+                    warn!("Open hedge {} on {} cannot be checked: exchange is not enabled", position.id, position.exchange);
+                    continue;
+                }
+            };
+            
+            let current = self.exchange_manager.call(
+                &position.exchange, "get_position", self.exchange_manager.timeouts().account(),
+                venue.get_position(&position.pair),
+            ).await?;
+            if current.is_zero() {
+                self.database.close_hedge_position(position.id, Utc::now()).await?;
+                info!("Hedge {} on {} for {} is no longer open, marked closed", position.id, position.exchange, position.pair.symbol);
             } else {
-                break;
+                warn!("Hedge {} on {} still open: {:?} {} {} (venue position {})",
+                      position.id, position.exchange, position.side, position.size, position.pair.base, current);
             }
         }
         
-        let max_size = buy_liquidity.min(sell_liquidity);
-        
-        let config_max = self.config.exchanges.get(&buy_exchange.name().to_string())
-            .map(|c| c.max_trade_amount)
-            .unwrap_or(Decimal::from(1000));
-        
-        Ok(max_size.min(config_max))
+        Ok(())
     }
     
-    async fn add_opportunity(&mut self, opportunity: ArbitrageOpportunity) -> Result<()> {
-        let key = format!("{}-{}-{}", 
-                         opportunity.pair.symbol, 
-                         opportunity.buy_exchange, 
-                         opportunity.sell_exchange);
+    // Quotes (especially RFQ-style DEX aggregator quotes) go stale fast, so re-quote both legs right
+    // before execution and abort if either moved past the slippage budget
+    async fn revalidate_opportunity(
+        &self,
+        buy_exchange: &dyn Exchange,
+        sell_exchange: &dyn Exchange,
+        opportunity: &ArbitrageOpportunity,
+    ) -> Result<()> {
+        let manager = &self.exchange_manager;
+        let budget = manager.timeouts().order_book();
+        let (buy_book, sell_book, buy_pool, sell_pool) = tokio::try_join!(
+            manager.call(buy_exchange.name(), "get_order_book", budget, buy_exchange.get_order_book(&opportunity.buy_pair, REVALIDATION_DEPTH)),
+            manager.call(sell_exchange.name(), "get_order_book", budget, sell_exchange.get_order_book(&opportunity.sell_pair, REVALIDATION_DEPTH)),
+            manager.call(buy_exchange.name(), "get_pool_reserves", budget, buy_exchange.get_pool_reserves(&opportunity.buy_pair)),
+            manager.call(sell_exchange.name(), "get_pool_reserves", budget, sell_exchange.get_pool_reserves(&opportunity.sell_pair)),
+        )?;
+        // Priced the way the opportunity was sized
+        let buy_liquidity = buy_pool.map_or(Liquidity::Book(&buy_book), Liquidity::Pool);
+        let sell_liquidity = sell_pool.map_or(Liquidity::Book(&sell_book), Liquidity::Pool);
         
-        if let Some(existing) = self.active_opportunities.get(&key) {
-            if opportunity.profit_percentage > existing.profit_percentage {
-                self.active_opportunities.insert(key.clone(), opportunity.clone());
-                self.database.save_opportunity(&opportunity).await?;
-                info!("Updated opportunity: {}", key);
-            }
-        } else {
-            self.active_opportunities.insert(key.clone(), opportunity.clone());
-            self.database.save_opportunity(&opportunity).await?;
-            info!("Added new opportunity: {}", key);
+        let buy_price = buy_liquidity.average_price(&TradeSide::Buy, opportunity.max_trade_size)
+            .ok_or_else(|| anyhow::anyhow!("Not enough depth on {} to buy {}", opportunity.buy_exchange, opportunity.max_trade_size))?;
+        let sell_price = sell_liquidity.average_price(&TradeSide::Sell, opportunity.max_trade_size)
+            .ok_or_else(|| anyhow::anyhow!("Not enough depth on {} to sell {}", opportunity.sell_exchange, opportunity.max_trade_size))?
+            * opportunity.sell_quote_rate;
+        
+        let max_slippage = self.config.pair_settings(&opportunity.pair.symbol).max_slippage;
+        if buy_price > opportunity.buy_price * (Decimal::ONE + max_slippage) {
+            anyhow::bail!("Buy quote on {} degraded to {} from {}", opportunity.buy_exchange, buy_price, opportunity.buy_price);
+        }
+        if sell_price < opportunity.sell_price * (Decimal::ONE - max_slippage) {
+            anyhow::bail!("Sell quote on {} degraded to {} from {}", opportunity.sell_exchange, sell_price, opportunity.sell_price);
+        }
+        
+        // On-chain legs fail here when gas is above the chain's max_fee_gwei cap, before any order goes out
+        let (buy_gas_cost, sell_gas_cost) = tokio::try_join!(
+            manager.call(buy_exchange.name(), "get_execution_cost", budget, buy_exchange.get_execution_cost(&opportunity.buy_pair, opportunity.max_trade_size)),
+            manager.call(sell_exchange.name(), "get_execution_cost", budget, sell_exchange.get_execution_cost(&opportunity.sell_pair, opportunity.max_trade_size)),
+        )?;
+        let gross_edge = opportunity.profit_amount + opportunity.gas_cost;
+        if buy_gas_cost + sell_gas_cost >= gross_edge {
+            anyhow::bail!("Gas cost rose to {:.2}, above the {:.2} edge", buy_gas_cost + sell_gas_cost, gross_edge);
         }
         
         Ok(())
     }
     
-    async fn execute_opportunities(&mut self) -> Result<()> {
-        let opportunities: Vec<_> = self.active_opportunities.values().cloned().collect();
+    async fn simulate_opportunity(&self, opportunity: &ArbitrageOpportunity) -> Result<()> {
+        let buy_exchange = self.exchange_manager.get_exchange(&opportunity.buy_exchange)
+            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", opportunity.buy_exchange))?;
+        let sell_exchange = self.exchange_manager.get_exchange(&opportunity.sell_exchange)
+            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", opportunity.sell_exchange))?;
         
-        let mut sorted_opportunities = opportunities;
-        sorted_opportunities.sort_by(|a, b| b.profit_percentage.cmp(&a.profit_percentage));
+        self.seed_paper_balances(buy_exchange).await;
+        self.seed_paper_balances(sell_exchange).await;
         
-        let max_concurrent = self.config.trading.max_concurrent_trades;
-        let to_execute = sorted_opportunities.into_iter()
-            .take(max_concurrent)
-            .collect::<Vec<_>>();
+        let manager = &self.exchange_manager;
+        let timeouts = manager.timeouts();
+        let (buy_book, sell_book, buy_fees, sell_fees) = tokio::try_join!(
+            manager.call(buy_exchange.name(), "get_order_book", timeouts.order_book(), buy_exchange.get_order_book(&opportunity.buy_pair, 20)),
+            manager.call(sell_exchange.name(), "get_order_book", timeouts.order_book(), sell_exchange.get_order_book(&opportunity.sell_pair, 20)),
+            manager.trading_fees(buy_exchange, &opportunity.buy_pair),
+            manager.trading_fees(sell_exchange, &opportunity.sell_pair),
+        )?;
         
-        for opportunity in to_execute {
-            if let Err(e) = self.execute_opportunity(&opportunity).await {
-                error!("Failed to execute opportunity {}: {}", opportunity.id, e);
-            }
-        }
+        let (mut buy_trade, mut sell_trade, pnl, cumulative) = {
+            let mut portfolio = self.paper_portfolio.lock().unwrap();
+            let buy_trade = portfolio.simulate_fill(
+                buy_exchange.name(), &buy_book, TradeSide::Buy, opportunity.max_trade_size, &buy_fees)?;
+            let sell_trade = portfolio.simulate_fill(
+                sell_exchange.name(), &sell_book, TradeSide::Sell, buy_trade.amount, &sell_fees)?;
+            let pnl = portfolio.record_round_trip(&buy_trade, &sell_trade);
+            (buy_trade, sell_trade, pnl, portfolio.realized_pnl())
+        };
+        
+        buy_trade.opportunity_id = opportunity.id;
+        sell_trade.opportunity_id = opportunity.id;
+        self.database.save_trade(&buy_trade).await?;
+        self.database.save_trade(&sell_trade).await?;
         
+        info!("DRY RUN: Simulated {} -> {} for {} {}: bought at {}, sold at {}, PnL {:.4} (cumulative {:.4})",
+              opportunity.buy_exchange, opportunity.sell_exchange, buy_trade.amount, opportunity.pair.base,
+              buy_trade.price, sell_trade.price, pnl, cumulative);
         Ok(())
     }
     
-    async fn execute_opportunity(&mut self, opportunity: &ArbitrageOpportunity) -> Result<()> {
-        if self.dry_run {
-            info!("DRY RUN: Would execute arbitrage opportunity: {:.2}% profit, ${:.2}",
-                  opportunity.profit_percentage, opportunity.profit_amount);
-            return Ok(());
+    async fn seed_paper_balances(&self, exchange: &dyn Exchange) {
+        if self.paper_portfolio.lock().unwrap().is_seeded(exchange.name()) {
+            return;
         }
         
-        info!("Executing arbitrage opportunity: {} -> {}, {:.2}% profit",
-              opportunity.buy_exchange, opportunity.sell_exchange, opportunity.profit_percentage);
+        let balances = match self.config.trading.paper_balances.get(exchange.name()) {
+            Some(balances) => balances.clone(),
+            None => match self.exchange_manager.call(exchange.name(), "get_balances", self.exchange_manager.timeouts().account(), exchange.get_balances()).await {
+                Ok(balances) => balances.into_iter()
+                    .map(|(asset, balance)| (asset, balance.free))
+                    .collect(),
+                Err(e) => {
+                    warn!("Could not fetch balances from {} for dry run, starting empty: {}", exchange.name(), e);
+                    HashMap::new()
+                }
+            },
+        };
         
-        // TODO: Implement actual trade execution
-        // This would involve:
-        // 1. Check account balances
-        // 2. Place buy order on buy exchange
-        // 3. Wait for fill
-        // 4. Place sell order on sell exchange
-        // 5. Monitor execution
-        // 6. Handle partial fills and errors
-        // 7. Update database with results
-        
-        info!("Trade execution completed for opportunity {}", opportunity.id);
-        Ok(())
+        info!("DRY RUN: Seeded simulated balances for {}: {:?}", exchange.name(), balances);
+        self.paper_portfolio.lock().unwrap().seed(exchange.name(), balances);
+    }
+    
+    async fn wait_for_fill(&self, exchange: &dyn Exchange, mut trade: Trade) -> Result<Trade> {
+        let order_id = match &trade.order_id {
+            Some(order_id) => order_id.clone(),
+            None => return Ok(trade),
+        };
+        
+        let deadline = time::Instant::now() + Duration::from_millis(self.config.trading.order_timeout_ms);
+        while matches!(trade.status, TradeStatus::Pending) {
+            self.open_orders.lock().unwrap().insert(order_id.clone(), trade.clone());
+            
+            if self.shutdown_grace_expired() {
+                warn!("Shutdown grace period elapsed, stopped waiting for order {} on {}", order_id, exchange.name());
+                break;
+            }
+            let now = time::Instant::now();
+            if now >= deadline {
+                break;
+            }
+            
+            let poll_interval = if self.order_tracker.is_streaming(exchange.name()) {
+                STREAMED_ORDER_POLL_INTERVAL
+            } else {
+                ORDER_POLL_INTERVAL
+            };
+            let streamed = self.order_tracker
+                .wait_for_update(exchange.name(), &order_id, poll_interval.min(deadline - now))
+                .await;
+            
+            // A slow status query is retried on the next poll rather than abandoning the order
+            let update = match streamed {
+                Some(update) => update,
+                None => match self.exchange_manager.call(
+                    exchange.name(), "get_order_status", self.exchange_manager.timeouts().order_status(),
+                    exchange.get_order_status(&order_id),
+                ).await {
+                    Ok(update) => update,
+                    Err(e) => {
+                        warn!("Failed to poll order {} on {}: {}", order_id, exchange.name(), e);
+                        continue;
+                    }
+                },
+            };
+            apply_order_update(&mut trade, update);
+            self.database.update_trade_status(&trade).await?;
+        }
+        
+        if !matches!(trade.status, TradeStatus::Pending) {
+            self.open_orders.lock().unwrap().remove(&order_id);
+        }
+        Ok(trade)
     }
     
     async fn cleanup_expired_opportunities(&mut self) -> Result<()> {
         let now = Utc::now();
-        let expiry_threshold = chrono::Duration::minutes(5);
+        let expiry_threshold = chrono::Duration::seconds(self.config.trading.opportunity_ttl_seconds as i64);
         
-        let expired_keys: Vec<_> = self.active_opportunities.iter()
+        let expired_keys: Vec<_> = self.active_opportunities.read().unwrap().iter()
             .filter(|(_, opp)| now.signed_duration_since(opp.timestamp) > expiry_threshold)
             .map(|(key, _)| key.clone())
             .collect();
         
-        for key in expired_keys {
-            if let Some(mut opportunity) = self.active_opportunities.remove(&key) {
-                opportunity.status = OpportunityStatus::Expired;
-                self.database.update_opportunity_status(&opportunity).await?;
+        self.expire_opportunities(&expired_keys).await
+    }
+    
+    async fn expire_opportunities(&mut self, keys: &[String]) -> Result<()> {
+        let mut ids = Vec::new();
+        let mut active_opportunities = self.active_opportunities.write().unwrap();
+        for key in keys {
+            if let Some(opportunity) = active_opportunities.remove(key) {
+                ids.push(opportunity.id);
                 debug!("Expired opportunity: {}", key);
             }
         }
+        drop(active_opportunities);
         
-        Ok(())
+        self.database.update_opportunity_statuses(&ids, &OpportunityStatus::Expired).await
     }
     
-    pub async fn scan_pair(&self, pair_str: &str) -> Result<()> {
-        if let Some(pair) = self.parse_trading_pair(pair_str) {
-            let prices = self.exchange_manager.get_all_prices(&pair).await?;
-            
-            println!("Prices for {}:", pair.symbol);
-            for price in prices {
-                println!("  {}: bid={}, ask={}, spread={:.4}%",
-                        price.exchange,
-                        price.bid,
-                        price.ask,
-                        (price.ask - price.bid) / price.bid * Decimal::from(100));
-            }
-            
-            let best_buy = self.exchange_manager.find_best_buy_price(&pair).await?;
-            let best_sell = self.exchange_manager.find_best_sell_price(&pair).await?;
-            
-            if let (Some(buy), Some(sell)) = (best_buy, best_sell) {
-                if buy.exchange != sell.exchange {
-                    let profit_pct = (sell.bid - buy.ask) / buy.ask * Decimal::from(100);
-                    println!("\nArbitrage opportunity:");
-                    println!("  Buy on {} at {}", buy.exchange, buy.ask);
-                    println!("  Sell on {} at {}", sell.exchange, sell.bid);
-                    println!("  Gross profit: {:.4}%", profit_pct);
-                }
-            }
-        } else {
-            anyhow::bail!("Invalid trading pair format: {}", pair_str);
-        }
+    // Quotes `pair` everywhere and evaluates it like the live scanner would, without recording or trading
+    pub async fn scan_pair(&self, pair_str: &str) -> Result<PairScan> {
+        let pair = self.parse_trading_pair(pair_str)
+            .ok_or_else(|| anyhow::anyhow!("Invalid trading pair format: {}", pair_str))?;
+        Ok(self.scan(&pair).await)
+    }
+    
+    pub async fn scan_all(&self) -> ScanReport {
+        let mut pairs: Vec<TradingPair> = self.configured_pairs().into_iter().collect();
+        pairs.sort_by(|a, b| a.symbol.cmp(&b.symbol));
         
-        Ok(())
+        ScanReport {
+            pairs: futures::future::join_all(pairs.iter().map(|pair| self.scan(pair))).await,
+        }
     }
     
-    pub async fn scan_all(&self) -> Result<()> {
-        let exchanges = self.exchange_manager.get_all_exchanges();
+    async fn scan(&self, pair: &TradingPair) -> PairScan {
+        let mut scan = PairScan {
+            pair: pair.symbol.clone(),
+            quotes: Vec::new(),
+            best_route: None,
+            min_profit_threshold: self.config.pair_settings(&pair.symbol).min_profit_threshold,
+            opportunities: Vec::new(),
+            error: None,
+        };
         
-        for exchange in exchanges {
-            let pairs = exchange.get_supported_pairs().await?;
-            println!("\n{} supported pairs:", exchange.name());
-            
-            for pair in pairs.iter().take(5) {
-                if let Ok(price) = exchange.get_price(pair).await {
-                    println!("  {}: bid={}, ask={}", pair.symbol, price.bid, price.ask);
-                }
+        let prices = match self.exchange_manager.get_all_prices(pair).await {
+            Ok(prices) => prices,
+            Err(e) => {
+                scan.error = Some(e.to_string());
+                return scan;
             }
+        };
+        
+        let best = prices.iter()
+            .filter(|buy| buy.ask > Decimal::ZERO)
+            .flat_map(|buy| prices.iter()
+                .filter(move |sell| sell.exchange != buy.exchange)
+                .map(move |sell| (buy, sell)))
+            .max_by_key(|(buy, sell)| (sell.bid - buy.ask) / buy.ask);
+        if let Some((buy, sell)) = best {
+            scan.best_route = Some(self.scan_route(pair, buy, sell).await);
         }
         
-        Ok(())
+        match self.find_opportunities(pair, &prices).await {
+            Ok(opportunities) => scan.opportunities = opportunities,
+            Err(e) => scan.error = Some(e.to_string()),
+        }
+        scan.quotes = prices.into_iter().map(VenueQuote::new).collect();
+        scan
     }
     
     fn parse_trading_pair(&self, pair_str: &str) -> Option<TradingPair> {
@@ -408,4 +3244,96 @@ impl ArbitrageBot {
             None
         }
     }
-}
\ No newline at end of file
+}
+
+fn opportunity_span(opportunity: &ArbitrageOpportunity) -> Span {
+    info_span!(
+        "opportunity",
+        opportunity_id = %opportunity.id,
+        pair = %opportunity.pair.symbol,
+        route = %format!("{}->{}", opportunity.buy_exchange, opportunity.sell_exchange),
+    )
+}
+
+// Derived from the opportunity and leg so a retried placement is recognised as the same order; 32 hex
+// digits and a short leg tag stay within Binance's 36-character limit
+pub(crate) fn client_order_id(opportunity_id: uuid::Uuid, leg: &str) -> String {
+    format!("{}-{}", opportunity_id.simple(), leg)
+}
+
+fn apply_order_update(trade: &mut Trade, update: Trade) {
+    trade.status = update.status;
+    trade.amount = update.amount;
+    trade.price = update.price;
+    trade.executed_at = update.executed_at;
+    trade.tx_hash = update.tx_hash.or(trade.tx_hash.take());
+    trade.failure_reason = update.failure_reason.or(trade.failure_reason.take());
+    if update.fee_amount > Decimal::ZERO {
+        trade.fee_amount = update.fee_amount;
+        trade.fee_asset = update.fee_asset;
+    }
+}
+
+async fn wait_for_shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {},
+                    _ = terminate.recv() => {},
+                }
+            },
+            Err(e) => {
+                warn!("Failed to install SIGTERM handler: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+// Each SIGHUP asks the main loop to reload the config; signals arriving while one is pending are merged
+fn spawn_reload_listener() -> mpsc::Receiver<()> {
+    let (reload_tx, reload_rx) = mpsc::channel(1);
+    
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::hangup()) {
+            Ok(mut hangup) => {
+                tokio::spawn(async move {
+                    while hangup.recv().await.is_some() {
+                        info!("SIGHUP received, reloading config");
+                        let _ = reload_tx.try_send(());
+                    }
+                });
+            },
+            Err(e) => warn!("Failed to install SIGHUP handler, config reload disabled: {}", e),
+        }
+    }
+    
+    #[cfg(not(unix))]
+    drop(reload_tx);
+    
+    reload_rx
+}
+
+// First signal starts a graceful shutdown, a second one exits without cleaning up
+fn spawn_signal_listener(shutdown: Arc<watch::Sender<Option<Instant>>>) {
+    tokio::spawn(async move {
+        wait_for_shutdown_signal().await;
+        info!("Shutdown signal received, finishing in-flight executions (signal again to force exit)");
+        shutdown.send_replace(Some(Instant::now()));
+        
+        wait_for_shutdown_signal().await;
+        warn!("Second shutdown signal received, exiting immediately");
+        std::process::exit(130);
+    });
+}
@@ -0,0 +1,265 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{debug, info};
+
+use crate::arbitrage::ArbitrageBot;
+use crate::blockchain::BlockchainManager;
+use crate::config::Config;
+use crate::database::Database;
+use crate::errors::BotResult;
+use crate::exchanges::{Exchange, ExchangeManager, TradingFees};
+use crate::models::{ArbitrageOpportunity, Balance, OrderBook, Price, Trade, TradeSide, TradingPair};
+use crate::profit::{Bps, Leg, ProfitBreakdown};
+
+#[derive(Debug, Clone)]
+pub struct BacktestConfig {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub latency: Duration,
+    pub taker_fee: Decimal,
+}
+
+#[derive(Debug, Default)]
+pub struct BacktestReport {
+    pub ticks: u64,
+    pub opportunities: u64,
+    pub executed: u64,
+    pub profitable: u64,
+    pub missed: u64,
+    pub total_pnl: Decimal,
+    pub max_drawdown: Decimal,
+}
+
+impl BacktestReport {
+    pub fn hit_rate(&self) -> Decimal {
+        if self.executed == 0 {
+            return Decimal::ZERO;
+        }
+        Decimal::from(self.profitable) / Decimal::from(self.executed) * Decimal::from(100)
+    }
+}
+
+// Serves recorded snapshots as of a shared replay clock, so the scanner runs unchanged against history
+struct ReplayExchange {
+    name: String,
+    clock: Arc<RwLock<DateTime<Utc>>>,
+    prices: HashMap<String, Vec<Price>>,
+    order_books: HashMap<String, Vec<OrderBook>>,
+    taker_fee: Decimal,
+}
+
+impl ReplayExchange {
+    fn now(&self) -> DateTime<Utc> {
+        *self.clock.read().unwrap()
+    }
+
+    fn latest<'a, T>(snapshots: Option<&'a Vec<T>>, now: DateTime<Utc>, timestamp: impl Fn(&T) -> DateTime<Utc>) -> Option<&'a T> {
+        let snapshots = snapshots?;
+        let index = snapshots.partition_point(|snapshot| timestamp(snapshot) <= now);
+        index.checked_sub(1).map(|i| &snapshots[i])
+    }
+}
+
+#[async_trait]
+impl Exchange for ReplayExchange {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        Self::latest(self.prices.get(&pair.symbol), self.now(), |price| price.timestamp)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No recorded price for {} on {}", pair.symbol, self.name).into())
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        let mut order_book = Self::latest(self.order_books.get(&pair.symbol), self.now(), |book| book.timestamp)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No recorded order book for {} on {}", pair.symbol, self.name))?;
+        
+        order_book.bids.truncate(depth);
+        order_book.asks.truncate(depth);
+        Ok(order_book)
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        Ok(HashMap::new())
+    }
+
+    async fn place_buy_order(&self, _pair: &TradingPair, _amount: Decimal, _price: Option<Decimal>) -> BotResult<Trade> {
+        Err(anyhow::anyhow!("Replay exchange {} does not execute orders", self.name).into())
+    }
+
+    async fn place_sell_order(&self, _pair: &TradingPair, _amount: Decimal, _price: Option<Decimal>) -> BotResult<Trade> {
+        Err(anyhow::anyhow!("Replay exchange {} does not execute orders", self.name).into())
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        Err(anyhow::anyhow!("Replay exchange does not track orders: {}", order_id).into())
+    }
+
+    async fn cancel_order(&self, _order_id: &str) -> BotResult<()> {
+        Ok(())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.prices.contains_key(&pair.symbol)
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        Ok(self.prices.values()
+            .filter_map(|prices| prices.first())
+            .map(|price| price.pair.clone())
+            .collect())
+    }
+
+    async fn get_trading_fees(&self, _pair: &TradingPair) -> BotResult<TradingFees> {
+        Ok(TradingFees {
+            maker_fee: self.taker_fee,
+            taker_fee: self.taker_fee,
+        })
+    }
+}
+
+fn route_key(opportunity: &ArbitrageOpportunity) -> String {
+    format!("{}-{}-{}", opportunity.pair.symbol, opportunity.buy_exchange, opportunity.sell_exchange)
+}
+
+pub async fn run(mut config: Config, backtest: BacktestConfig) -> Result<BacktestReport> {
+    let database = Database::new(&config.database_url).await?;
+    let prices = database.get_price_history(None, None, backtest.from, backtest.to).await?;
+    let order_books = database.get_order_book_snapshots(backtest.from, backtest.to).await?;
+
+    if prices.is_empty() {
+        anyhow::bail!("No recorded prices between {} and {}", backtest.from, backtest.to);
+    }
+    info!("Loaded {} price and {} order book snapshots", prices.len(), order_books.len());
+
+    let clock = Arc::new(RwLock::new(backtest.from));
+    let mut venues: HashMap<String, ReplayExchange> = HashMap::new();
+    let mut pairs: HashMap<String, TradingPair> = HashMap::new();
+
+    for price in prices {
+        pairs.entry(price.pair.symbol.clone()).or_insert_with(|| price.pair.clone());
+        venues.entry(price.exchange.clone())
+            .or_insert_with(|| ReplayExchange {
+                name: price.exchange.clone(),
+                clock: clock.clone(),
+                prices: HashMap::new(),
+                order_books: HashMap::new(),
+                taker_fee: backtest.taker_fee,
+            })
+            .prices.entry(price.pair.symbol.clone()).or_default().push(price);
+    }
+
+    for order_book in order_books {
+        if let Some(venue) = venues.get_mut(&order_book.exchange) {
+            venue.order_books.entry(order_book.pair.symbol.clone()).or_default().push(order_book);
+        }
+    }
+
+    let venue_names: Vec<String> = venues.keys().cloned().collect();
+    let mut exchange_manager = ExchangeManager::new();
+    for (_, venue) in venues {
+        exchange_manager.add_exchange(Box::new(venue));
+    }
+
+    // The replayed history must not be recorded a second time
+    config.trading.record_market_data = false;
+    let step = chrono::Duration::seconds(config.trading.check_interval_seconds.max(1) as i64);
+    let max_age = step * 2;
+    let latency = chrono::Duration::from_std(backtest.latency)?;
+
+    let blockchain_manager = BlockchainManager::new(&config.blockchain).await?;
+    let bot = ArbitrageBot::with_components(config, exchange_manager, blockchain_manager, database);
+
+    let mut report = BacktestReport::default();
+    let mut open_routes: HashSet<String> = HashSet::new();
+    let mut peak_pnl = Decimal::ZERO;
+    let mut now = backtest.from;
+
+    while now < backtest.to {
+        *clock.write().unwrap() = now;
+        report.ticks += 1;
+        
+        let mut current_routes = HashSet::new();
+        
+        for pair in pairs.values() {
+            let mut pair_prices = Vec::new();
+            for name in &venue_names {
+                let exchange = bot.exchange_manager().get_exchange(name).unwrap();
+                if let Ok(price) = exchange.get_price(pair).await {
+                    if now.signed_duration_since(price.timestamp) <= max_age {
+                        pair_prices.push(price);
+                    }
+                }
+            }
+            
+            for opportunity in bot.find_opportunities(pair, &pair_prices).await? {
+                let key = route_key(&opportunity);
+                
+                // A spread that persists across ticks is a single opportunity, not one per tick
+                if open_routes.contains(&key) {
+                    current_routes.insert(key);
+                    continue;
+                }
+                current_routes.insert(key);
+                report.opportunities += 1;
+                
+                *clock.write().unwrap() = now + latency;
+                let pnl = simulate_execution(&bot, &opportunity).await;
+                *clock.write().unwrap() = now;
+                
+                match pnl {
+                    Some(pnl) => {
+                        report.executed += 1;
+                        if pnl > Decimal::ZERO {
+                            report.profitable += 1;
+                        }
+                        report.total_pnl += pnl;
+                        peak_pnl = peak_pnl.max(report.total_pnl);
+                        report.max_drawdown = report.max_drawdown.max(peak_pnl - report.total_pnl);
+                    },
+                    None => report.missed += 1,
+                }
+            }
+        }
+        
+        open_routes = current_routes;
+        now += step;
+    }
+
+    Ok(report)
+}
+
+// Fills both legs against the books as they stood after the configured latency
+async fn simulate_execution(bot: &ArbitrageBot, opportunity: &ArbitrageOpportunity) -> Option<Decimal> {
+    let buy_exchange = bot.exchange_manager().get_exchange(&opportunity.buy_exchange)?;
+    let sell_exchange = bot.exchange_manager().get_exchange(&opportunity.sell_exchange)?;
+    let size = opportunity.max_trade_size;
+
+    let buy_book = buy_exchange.get_order_book(&opportunity.buy_pair, 20).await.ok()?;
+    let sell_book = sell_exchange.get_order_book(&opportunity.sell_pair, 20).await.ok()?;
+    let buy_fees = bot.exchange_manager().trading_fees(buy_exchange, &opportunity.buy_pair).await.ok()?;
+    let sell_fees = bot.exchange_manager().trading_fees(sell_exchange, &opportunity.sell_pair).await.ok()?;
+
+    let buy_price = buy_book.effective_price(&TradeSide::Buy, size)?;
+    let sell_price = sell_book.effective_price(&TradeSide::Sell, size)? * opportunity.sell_quote_rate;
+
+    let breakdown = ProfitBreakdown::compute(
+        size,
+        Leg::new(buy_price, Bps::from_fraction(buy_fees.taker_fee)),
+        Leg::new(sell_price, Bps::from_fraction(sell_fees.taker_fee)),
+        opportunity.gas_cost,
+        Decimal::ZERO,
+    );
+
+    debug!("Backtest fill {}: bought {} at {}, sold at {}, {}",
+           route_key(opportunity), size, buy_price, sell_price, breakdown);
+    Some(breakdown.net)
+}
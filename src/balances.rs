@@ -0,0 +1,111 @@
+use anyhow::Result;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::exchanges::{Exchange, ExchangeManager};
+
+struct CachedBalances {
+    fetched_at: Instant,
+    free: HashMap<String, Decimal>,
+}
+
+struct Reservation {
+    exchange: String,
+    asset: String,
+    amount: Decimal,
+}
+
+// Free balances per exchange, cached for a short TTL, minus the configured buffers and whatever
+// executions in progress have already claimed
+pub struct BalanceManager {
+    ttl: Mutex<Duration>,
+    buffers: HashMap<String, HashMap<String, Decimal>>,
+    cache: Mutex<HashMap<String, CachedBalances>>,
+    reservations: Mutex<HashMap<uuid::Uuid, Vec<Reservation>>>,
+}
+
+impl BalanceManager {
+    pub fn new(ttl: Duration, buffers: HashMap<String, HashMap<String, Decimal>>) -> Self {
+        Self {
+            ttl: Mutex::new(ttl),
+            buffers,
+            cache: Mutex::new(HashMap::new()),
+            reservations: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn set_ttl(&self, ttl: Duration) {
+        *self.ttl.lock().unwrap() = ttl;
+    }
+
+    pub async fn free_balance(&self, manager: &ExchangeManager, exchange: &dyn Exchange, asset: &str) -> Result<Decimal> {
+        {
+            let ttl = *self.ttl.lock().unwrap();
+            let cache = self.cache.lock().unwrap();
+            if let Some(cached) = cache.get(exchange.name()).filter(|cached| cached.fetched_at.elapsed() < ttl) {
+                return Ok(cached.free.get(asset).copied().unwrap_or_default());
+            }
+        }
+        
+        let balances = manager.call(
+            exchange.name(), "get_balances", manager.timeouts().account(),
+            exchange.get_balances(),
+        ).await?;
+        let free: HashMap<String, Decimal> = balances.into_iter()
+            .map(|(asset, balance)| (asset, balance.free))
+            .collect();
+        let balance = free.get(asset).copied().unwrap_or_default();
+        
+        self.cache.lock().unwrap().insert(exchange.name().to_string(), CachedBalances {
+            fetched_at: Instant::now(),
+            free,
+        });
+        Ok(balance)
+    }
+
+    // What is left of `free` after the exchange's buffer and outstanding reservations
+    pub fn unreserved(&self, exchange: &str, asset: &str, free: Decimal) -> Decimal {
+        let buffer = self.buffers.get(exchange)
+            .and_then(|buffers| buffers.get(asset))
+            .copied()
+            .unwrap_or_default();
+        let reserved: Decimal = self.reservations.lock().unwrap().values()
+            .flatten()
+            .filter(|reservation| reservation.exchange == exchange && reservation.asset == asset)
+            .map(|reservation| reservation.amount)
+            .sum();
+        
+        (free - buffer - reserved).max(Decimal::ZERO)
+    }
+
+    pub fn reserve(&self, opportunity_id: uuid::Uuid, exchange: &str, asset: &str, amount: Decimal) {
+        self.reservations.lock().unwrap()
+            .entry(opportunity_id)
+            .or_default()
+            .push(Reservation {
+                exchange: exchange.to_string(),
+                asset: asset.to_string(),
+                amount,
+            });
+    }
+
+    pub fn release(&self, opportunity_id: uuid::Uuid) {
+        self.reservations.lock().unwrap().remove(&opportunity_id);
+    }
+
+    // Balances moved, so the next lookup goes back to the exchange
+    pub fn invalidate(&self, exchange: &str) {
+        self.cache.lock().unwrap().remove(exchange);
+    }
+
+    // Streamed changes for some assets. The stream reports every asset that changes, so a cached
+    // snapshot it has kept up to date counts as freshly fetched; without one there is nothing to patch.
+    pub fn apply_update(&self, exchange: &str, free: HashMap<String, Decimal>) {
+        if let Some(cached) = self.cache.lock().unwrap().get_mut(exchange) {
+            cached.free.extend(free);
+            cached.fetched_at = Instant::now();
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use ethers::prelude::*;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::blockchain::bridge::{Bridge, BridgeQuote};
+use crate::blockchain::failover::RpcProvider;
+use crate::blockchain::gas::GasOracle;
+use crate::blockchain::simulate;
+use crate::blockchain::BlockchainManager;
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::erc20::{self, SignerClient, TokenApprovals, ERC20};
+use crate::utils;
+
+pub const DEFAULT_API_URL: &str = "https://app.across.to/api";
+// Relayers that have not filled by then let the deposit be refunded on the source chain
+const FILL_DEADLINE_SECONDS: u64 = 6 * 60 * 60;
+
+abigen!(
+    AcrossSpokePool,
+    r#"[
+        function depositV3(address depositor, address recipient, address inputToken, address outputToken, uint256 inputAmount, uint256 outputAmount, uint256 destinationChainId, address exclusiveRelayer, uint32 quoteTimestamp, uint32 fillDeadline, uint32 exclusivityDeadline, bytes message) external payable
+    ]"#
+);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SuggestedFees {
+    total_relay_fee: RelayFee,
+    estimated_fill_time_sec: u64,
+    timestamp: String,
+    spoke_pool_address: String,
+    limits: DepositLimits,
+    #[serde(default)]
+    is_amount_too_low: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct RelayFee {
+    total: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DepositLimits {
+    min_deposit: String,
+}
+
+// Quotes from Across' suggested-fees endpoint and deposits into the source chain's SpokePool
+pub struct AcrossBridge {
+    client: Client,
+    api_url: String,
+    providers: HashMap<u64, Arc<RpcProvider>>,
+    signers: HashMap<u64, Arc<SignerClient>>,
+    gas_oracles: HashMap<u64, Arc<GasOracle>>,
+    decimals: Mutex<HashMap<(u64, Address), u8>>,
+}
+
+impl AcrossBridge {
+    pub fn new(api_url: &str, blockchain: &BlockchainManager) -> Self {
+        let mut providers = HashMap::new();
+        let mut signers = HashMap::new();
+        let mut gas_oracles = HashMap::new();
+        
+        for chain in blockchain.enabled_chains() {
+            let Some(config) = blockchain.get_chain_config(chain) else {
+                continue;
+            };
+            if let Some(provider) = blockchain.get_provider(chain) {
+                providers.insert(config.chain_id, provider);
+            }
+            if let Some(signer) = blockchain.get_signer(chain) {
+                signers.insert(config.chain_id, signer);
+            }
+            if let Some(gas_oracle) = blockchain.get_gas_oracle(chain) {
+                gas_oracles.insert(config.chain_id, gas_oracle);
+            }
+        }
+        
+        Self {
+            client: utils::http_client(),
+            api_url: api_url.trim_end_matches('/').to_string(),
+            providers,
+            signers,
+            gas_oracles,
+            decimals: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn token(chain_id: u64, symbol: &str) -> Result<Address> {
+        erc20::token_address(chain_id, symbol)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported on chain {}: {}", chain_id, symbol))
+    }
+
+    // The same token can use different decimals per chain (e.g. USDC on BSC)
+    async fn token_decimals(&self, chain_id: u64, token: Address) -> Result<u8> {
+        if let Some(decimals) = self.decimals.lock().unwrap().get(&(chain_id, token)) {
+            return Ok(*decimals);
+        }
+        
+        let provider = self.providers.get(&chain_id)
+            .ok_or_else(|| anyhow::anyhow!("Chain {} is not enabled", chain_id))?;
+        let decimals = ERC20::new(token, provider.clone()).decimals().call().await?;
+        
+        self.decimals.lock().unwrap().insert((chain_id, token), decimals);
+        Ok(decimals)
+    }
+
+    fn from_units(units: &str, decimals: u8) -> Result<Decimal> {
+        utils::u256_to_decimal(U256::from_dec_str(units)?, decimals)
+    }
+}
+
+#[async_trait]
+impl Bridge for AcrossBridge {
+    fn name(&self) -> &str {
+        "across"
+    }
+
+    async fn quote(&self, token: &str, amount: Decimal, from_chain_id: u64, to_chain_id: u64) -> BotResult<BridgeQuote> {
+        let input_token = Self::token(from_chain_id, token)?;
+        let output_token = Self::token(to_chain_id, token)?;
+        let decimals = self.token_decimals(from_chain_id, input_token).await?;
+        
+        let url = format!("{}/suggested-fees", self.api_url);
+        let response = self.client
+            .get(&url)
+            .query(&[
+                ("inputToken", format!("{:?}", input_token)),
+                ("outputToken", format!("{:?}", output_token)),
+                ("originChainId", from_chain_id.to_string()),
+                ("destinationChainId", to_chain_id.to_string()),
+                ("amount", utils::decimal_to_u256(amount, decimals)?.to_string()),
+            ])
+            .send()
+            .await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Across", response).await);
+        }
+        
+        let fees: SuggestedFees = response.json().await?;
+        if fees.is_amount_too_low {
+            return Err(anyhow::anyhow!("Across rejects {} {} as too small to relay", amount, token).into());
+        }
+        
+        Ok(BridgeQuote {
+            token: token.to_string(),
+            amount,
+            from_chain_id,
+            to_chain_id,
+            fee: Self::from_units(&fees.total_relay_fee.total, decimals)?,
+            // Round partial minutes up so a plan never looks faster than the relayer promised
+            estimated_minutes: fees.estimated_fill_time_sec.div_ceil(60) as u32,
+            min_amount: Self::from_units(&fees.limits.min_deposit, decimals)?,
+            contract: fees.spoke_pool_address,
+            quote_timestamp: fees.timestamp.parse()?,
+        })
+    }
+
+    async fn execute(&self, quote: &BridgeQuote) -> BotResult<String> {
+        let client = self.signers.get(&quote.from_chain_id)
+            .ok_or_else(|| anyhow::anyhow!("No private key configured for chain {}", quote.from_chain_id))?;
+        
+        let input_token = Self::token(quote.from_chain_id, &quote.token)?;
+        let output_token = Self::token(quote.to_chain_id, &quote.token)?;
+        let input_amount = utils::decimal_to_u256(quote.amount, self.token_decimals(quote.from_chain_id, input_token).await?)?;
+        let output_amount = utils::decimal_to_u256(
+            quote.amount - quote.fee,
+            self.token_decimals(quote.to_chain_id, output_token).await?,
+        )?;
+        
+        let gas_oracle = self.gas_oracles.get(&quote.from_chain_id)
+            .ok_or_else(|| anyhow::anyhow!("Chain {} is not enabled", quote.from_chain_id))?;
+        let fees = gas_oracle.fees().await?;
+        
+        let spoke_pool = quote.contract.parse::<Address>()
+            .map_err(|e| anyhow::anyhow!("Invalid Across spoke pool {}: {}", quote.contract, e))?;
+        TokenApprovals::new(spoke_pool, false)
+            .with_gas_oracle(gas_oracle.clone())
+            .ensure(client, input_token, input_amount)
+            .await?;
+        
+        let depositor = client.address();
+        let mut call = AcrossSpokePool::new(spoke_pool, client.clone())
+            .deposit_v3(
+                depositor,
+                depositor,
+                input_token,
+                output_token,
+                input_amount,
+                output_amount,
+                U256::from(quote.to_chain_id),
+                Address::zero(),
+                quote.quote_timestamp as u32,
+                (quote.quote_timestamp + FILL_DEADLINE_SECONDS) as u32,
+                0,
+                Bytes::new(),
+            );
+        fees.apply(&mut call.tx);
+        simulate::simulate(client, &call.tx).await?;
+        let pending_tx = call.send().await?;
+        let tx_hash = format!("{:?}", pending_tx.tx_hash());
+        
+        tracing::info!("Submitted Across deposit {} of {} {} from chain {} to {}",
+                       tx_hash, quote.amount, quote.token, quote.from_chain_id, quote.to_chain_id);
+        Ok(tx_hash)
+    }
+}
@@ -0,0 +1,206 @@
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use std::sync::Arc;
+
+use crate::blockchain::nonce::TransactionManager;
+use crate::blockchain::simulate;
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::erc20::{self, SignerClient};
+use crate::models::SmartContractCall;
+
+// Executor contract deployed by the operator. `execute` flash-borrows `loanAmount` of `loanAsset` from
+// the Aave V3 pool, runs each step (approve `target` for `amountIn` of `tokenIn`, call it with `data`),
+// then reverts unless it holds the loan, the premium and `minProfit`; the profit goes to the caller
+abigen!(
+    ArbitrageExecutor,
+    r#"[
+        struct SwapStep { address target; address tokenIn; address tokenOut; uint256 amountIn; uint256 minAmountOut; bytes data; }
+        function execute(address pool, address loanAsset, uint256 loanAmount, SwapStep[] calldata steps, uint256 minProfit) external returns (uint256 profit)
+        error InsufficientProfit(uint256 balance, uint256 required)
+        error StepFailed(uint256 index, bytes reason)
+    ]"#
+);
+
+abigen!(
+    AaveV3Pool,
+    r#"[
+        function FLASHLOAN_PREMIUM_TOTAL() external view returns (uint128)
+    ]"#
+);
+
+const AAVE_V3_POOL_ETHEREUM: &str = "0x87870Bca3F3fD6335C3F4ce8392D69350B4fA4E2";
+const AAVE_V3_POOL_POLYGON: &str = "0x794a61358D6845594F94dc1DB02A252b5b4814aD";
+
+// Covers the flash loan callback and approvals on top of the two swaps, used when estimation fails
+const DEFAULT_ATOMIC_GAS: u64 = 450_000;
+
+pub fn aave_v3_pool(chain_id: u64) -> Option<Address> {
+    let address = match chain_id {
+        erc20::ETHEREUM_CHAIN_ID => AAVE_V3_POOL_ETHEREUM,
+        erc20::POLYGON_CHAIN_ID => AAVE_V3_POOL_POLYGON,
+        _ => return None,
+    };
+    address.parse().ok()
+}
+
+// One swap as the executor runs it, plus what the venue quoted for it
+#[derive(Debug, Clone)]
+pub struct EncodedSwap {
+    pub step: SwapStep,
+    pub expected_out: U256,
+}
+
+#[derive(Debug, Clone)]
+pub struct AtomicPlan {
+    pub loan_asset: Address,
+    pub loan_amount: U256,
+    pub premium: U256,
+    pub buy: EncodedSwap,
+    pub sell: EncodedSwap,
+    // Below this the contract reverts; the worst case the slippage limits allow
+    pub min_profit: U256,
+}
+
+impl AtomicPlan {
+    pub fn new(loan_asset: Address, loan_amount: U256, premium: U256, buy: EncodedSwap, sell: EncodedSwap) -> BotResult<Self> {
+        let repayment = loan_amount + premium;
+        if sell.expected_out <= repayment {
+            return Err(anyhow::anyhow!("Atomic route returns {} against a repayment of {}", sell.expected_out, repayment).into());
+        }
+        
+        Ok(Self {
+            loan_asset,
+            loan_amount,
+            premium,
+            min_profit: sell.step.min_amount_out.saturating_sub(repayment),
+            buy,
+            sell,
+        })
+    }
+
+    pub fn expected_profit(&self) -> U256 {
+        self.sell.expected_out.saturating_sub(self.loan_amount + self.premium)
+    }
+}
+
+// A simulated call ready to broadcast
+pub struct PreparedCall {
+    pub call: SmartContractCall,
+    pub tx: TypedTransaction,
+    pub simulated_profit: U256,
+}
+
+// Token movements of a mined atomic arbitrage, in token units
+#[derive(Debug, Clone)]
+pub struct AtomicFill {
+    pub success: bool,
+    pub base_bought: U256,
+    pub quote_received: U256,
+    pub gas_native: U256,
+}
+
+pub struct AtomicExecutor {
+    contract: ArbitrageExecutor<SignerClient>,
+    pool: Address,
+    chain_id: u64,
+    transactions: Arc<TransactionManager>,
+}
+
+impl AtomicExecutor {
+    pub fn new(address: Address, pool: Address, client: Arc<SignerClient>, transactions: Arc<TransactionManager>) -> Self {
+        let chain_id = client.signer().chain_id();
+        Self {
+            contract: ArbitrageExecutor::new(address, client),
+            pool,
+            chain_id,
+            transactions,
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        self.contract.address()
+    }
+
+    pub fn transactions(&self) -> &Arc<TransactionManager> {
+        &self.transactions
+    }
+
+    pub async fn flash_loan_premium(&self, amount: U256) -> BotResult<U256> {
+        let pool = AaveV3Pool::new(self.pool, self.contract.client());
+        let premium_bps = pool.flashloan_premium_total().call().await?;
+        Ok(amount * U256::from(premium_bps) / U256::from(10_000))
+    }
+
+    fn call(&self, plan: &AtomicPlan) -> ContractCall<SignerClient, U256> {
+        self.contract.execute(
+            self.pool,
+            plan.loan_asset,
+            plan.loan_amount,
+            vec![plan.buy.step.clone(), plan.sell.step.clone()],
+            plan.min_profit,
+        )
+    }
+
+    // Runs the whole sequence through eth_call first, so a route that would revert never costs gas
+    pub async fn prepare(&self, plan: &AtomicPlan) -> BotResult<PreparedCall> {
+        let call = self.call(plan);
+        let simulated_profit = call.call().await
+            .map_err(|e| BotError::chain(format!("Atomic arbitrage simulation reverted: {}", e)))?;
+        
+        let gas_limit = call.estimate_gas().await
+            .unwrap_or_else(|_| U256::from(DEFAULT_ATOMIC_GAS));
+        let mut tx = call.tx.clone();
+        tx.set_gas(gas_limit);
+        
+        let gas_price = self.transactions.gas_oracle().fees().await?.expected_price();
+        let contract_call = SmartContractCall {
+            contract_address: format!("{:?}", self.address()),
+            function_name: "execute".to_string(),
+            parameters: serde_json::json!({
+                "pool": self.pool,
+                "loan_asset": plan.loan_asset,
+                "loan_amount": plan.loan_amount.to_string(),
+                "steps": [plan.buy.step.target, plan.sell.step.target],
+                "min_profit": plan.min_profit.to_string(),
+            }),
+            gas_limit: gas_limit.as_u64(),
+            gas_price: gas_price.as_u64(),
+            chain_id: self.chain_id,
+        };
+        
+        Ok(PreparedCall {
+            call: contract_call,
+            tx,
+            simulated_profit,
+        })
+    }
+
+    pub async fn submit(&self, prepared: PreparedCall, private: bool) -> BotResult<H256> {
+        if private {
+            self.transactions.send_private(prepared.tx).await
+        } else {
+            self.transactions.send(prepared.tx).await
+        }
+    }
+
+    pub async fn revert_reason(&self, tx_hash: H256) -> Option<String> {
+        simulate::replay_revert_reason(self.contract.client().inner(), tx_hash).await
+    }
+
+    pub fn settle(&self, plan: &AtomicPlan, receipt: &TransactionReceipt) -> AtomicFill {
+        let executor = Some(self.address());
+        let quote_in = erc20::sum_transfers(&receipt.logs, plan.loan_asset, None, executor);
+        let gas_native = match (receipt.gas_used, receipt.effective_gas_price) {
+            (Some(gas_used), Some(gas_price)) => gas_used * gas_price,
+            _ => U256::zero(),
+        };
+        
+        AtomicFill {
+            success: receipt.status == Some(U64::from(1)),
+            base_bought: erc20::sum_transfers(&receipt.logs, plan.buy.step.token_out, None, executor),
+            // The flash loan itself also lands in the executor
+            quote_received: quote_in.saturating_sub(plan.loan_amount),
+            gas_native,
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use ethers::prelude::*;
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::blockchain::failover::RpcProvider;
+use crate::errors::BotResult;
+use crate::models::Price;
+
+const RECONNECT_MIN_DELAY: Duration = Duration::from_secs(1);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChainHead {
+    pub number: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+// None while the subscription is down, which tells consumers to fall back to polling
+pub type HeadReceiver = watch::Receiver<Option<ChainHead>>;
+
+pub fn spawn_head_watcher(chain: String, ws_url: String) -> HeadReceiver {
+    let (sender, receiver) = watch::channel(None);
+
+    tokio::spawn(async move {
+        let mut delay = RECONNECT_MIN_DELAY;
+        loop {
+            match follow_heads(&ws_url, &sender).await {
+                Ok(()) => tracing::warn!("{} block subscription ended", chain),
+                Err(e) => tracing::warn!("{} block subscription failed: {}", chain, e),
+            }
+            
+            // A subscription that delivered blocks was healthy, so the next reconnect starts fresh
+            if sender.send_replace(None).is_some() {
+                delay = RECONNECT_MIN_DELAY;
+            }
+            if sender.is_closed() {
+                return;
+            }
+            
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+        }
+    });
+
+    receiver
+}
+
+async fn follow_heads(ws_url: &str, sender: &watch::Sender<Option<ChainHead>>) -> Result<()> {
+    let provider = Provider::<Ws>::connect(ws_url).await?;
+    let mut blocks = provider.subscribe_blocks().await?;
+
+    while let Some(block) = blocks.next().await {
+        let Some(number) = block.number else {
+            continue;
+        };
+        let timestamp = DateTime::from_timestamp(block.timestamp.as_u64() as i64, 0).unwrap_or_else(Utc::now);
+        sender.send_replace(Some(ChainHead { number: number.as_u64(), timestamp }));
+    }
+
+    Ok(())
+}
+
+// Time of the block on-chain reads are answered from: the subscribed head, or the node's latest block
+// when there is no subscription
+pub async fn quote_time(head: Option<ChainHead>, provider: &RpcProvider) -> BotResult<DateTime<Utc>> {
+    if let Some(head) = head {
+        return Ok(head.timestamp);
+    }
+    let latest = provider.get_block(BlockNumber::Latest).await?;
+    Ok(latest
+        .and_then(|block| DateTime::from_timestamp(block.timestamp.as_u64() as i64, 0))
+        .unwrap_or_else(Utc::now))
+}
+
+// On-chain quotes only change with a new block, so while a head subscription is live a quote is reused
+// until the next block; without one every lookup goes to the node as before
+#[derive(Default)]
+pub struct BlockPriceCache {
+    heads: RwLock<Option<HeadReceiver>>,
+    // Pair symbol to the block the quote was read at
+    prices: Mutex<HashMap<String, (u64, Price)>>,
+}
+
+impl BlockPriceCache {
+    pub fn attach(&self, heads: HeadReceiver) {
+        *self.heads.write().unwrap() = Some(heads);
+    }
+
+    pub fn head(&self) -> Option<ChainHead> {
+        self.heads.read().unwrap().as_ref().and_then(|heads| *heads.borrow())
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<Price> {
+        let head = self.head()?;
+        self.prices.lock().unwrap().get(symbol)
+            .filter(|(block, _)| *block == head.number)
+            .map(|(_, price)| price.clone())
+    }
+
+    // Stamps the quote with the block time of the head it was read at, the authoritative age of an on-chain price
+    pub fn store(&self, head: Option<ChainHead>, mut price: Price) -> Price {
+        if let Some(head) = head {
+            price.timestamp = head.timestamp;
+            self.prices.lock().unwrap().insert(price.pair.symbol.clone(), (head.number, price.clone()));
+        }
+        price
+    }
+}
@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+
+use crate::errors::BotResult;
+
+#[derive(Debug, Clone)]
+pub struct BridgeQuote {
+    pub token: String,
+    pub amount: Decimal,
+    pub from_chain_id: u64,
+    pub to_chain_id: u64,
+    // In units of the bridged token, deducted from what arrives on the destination chain
+    pub fee: Decimal,
+    pub estimated_minutes: u32,
+    pub min_amount: Decimal,
+    // Contract the deposit is sent to and the time the fee was quoted at; both bind the deposit
+    pub contract: String,
+    pub quote_timestamp: u64,
+}
+
+#[async_trait]
+pub trait Bridge: Send + Sync {
+    fn name(&self) -> &str;
+    
+    async fn quote(&self, token: &str, amount: Decimal, from_chain_id: u64, to_chain_id: u64) -> BotResult<BridgeQuote>;
+    
+    // Deposits on the source chain and returns the transaction hash
+    async fn execute(&self, quote: &BridgeQuote) -> BotResult<String>;
+}
+
+// Fees drift between planning and execution; a fresh quote whose fee grew more than `tolerance`
+// (a fraction of the planned fee) aborts instead of depositing. Cheaper quotes always pass
+pub async fn execute_requoted(bridge: &dyn Bridge, planned: &BridgeQuote, tolerance: Decimal) -> BotResult<String> {
+    let fresh = bridge.quote(&planned.token, planned.amount, planned.from_chain_id, planned.to_chain_id).await?;
+    
+    if fresh.fee > planned.fee * (Decimal::ONE + tolerance) {
+        return Err(anyhow::anyhow!(
+            "{} fee for {} {} moved from {} to {}, beyond the {}% tolerance",
+            bridge.name(), planned.amount, planned.token, planned.fee, fresh.fee, tolerance * Decimal::from(100)
+        ).into());
+    }
+    if fresh.amount < fresh.min_amount {
+        return Err(anyhow::anyhow!("{} requires at least {} {}", bridge.name(), fresh.min_amount, fresh.token).into());
+    }
+    
+    bridge.execute(&fresh).await
+}
@@ -0,0 +1,201 @@
+use async_trait::async_trait;
+use ethers::providers::{Http, HttpClientError, JsonRpcClient, JsonRpcError, Provider};
+use ethers::types::U64;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::errors::BotResult;
+use crate::utils;
+
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+pub const DEFAULT_MAX_BLOCK_LAG: u64 = 3;
+
+pub type RpcProvider = Provider<FailoverTransport>;
+
+pub fn rpc_provider(urls: &[String], max_block_lag: u64, http: &utils::HttpSettings) -> BotResult<RpcProvider> {
+    Ok(Provider::new(FailoverTransport::new(urls, max_block_lag, http)?))
+}
+
+// Hosted providers answer with a JSON-RPC error rather than an HTTP status when throttling
+fn is_rate_limited(error: &JsonRpcError) -> bool {
+    error.code == -32005 || error.code == 429 || error.message.to_lowercase().contains("rate limit")
+}
+
+#[derive(Debug, Default)]
+struct EndpointHealth {
+    latest_block: Option<u64>,
+    latency: Option<Duration>,
+    consecutive_failures: u32,
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    url: String,
+    transport: Http,
+    health: Mutex<EndpointHealth>,
+}
+
+impl Endpoint {
+    fn record_failure(&self) {
+        self.health.lock().unwrap().consecutive_failures += 1;
+    }
+}
+
+// Spreads one chain's JSON-RPC traffic over several endpoints: requests go to the active endpoint and
+// move on to the next one on transport errors or throttling, and a periodic probe re-selects the
+// healthiest endpoint by block height and latency
+#[derive(Debug)]
+pub struct FailoverTransport {
+    endpoints: Vec<Endpoint>,
+    active: AtomicUsize,
+    max_block_lag: u64,
+    last_check: Mutex<Option<Instant>>,
+}
+
+impl FailoverTransport {
+    pub fn new(urls: &[String], max_block_lag: u64, http: &utils::HttpSettings) -> BotResult<Self> {
+        if urls.is_empty() {
+            return Err(anyhow::anyhow!("At least one RPC URL is required").into());
+        }
+        let client = utils::http_client_with(http)?;
+        
+        let endpoints = urls.iter()
+            .map(|url| {
+                let parsed = reqwest::Url::parse(url)
+                    .map_err(|e| anyhow::anyhow!("Invalid RPC URL {}: {}", url, e))?;
+                Ok(Endpoint {
+                    url: url.clone(),
+                    transport: Http::new_with_client(parsed, client.clone()),
+                    health: Mutex::new(EndpointHealth::default()),
+                })
+            })
+            .collect::<BotResult<Vec<_>>>()?;
+        
+        Ok(Self {
+            endpoints,
+            active: AtomicUsize::new(0),
+            max_block_lag,
+            last_check: Mutex::new(None),
+        })
+    }
+
+    pub fn active_url(&self) -> &str {
+        &self.endpoints[self.active.load(Ordering::Relaxed)].url
+    }
+
+    // Endpoints lagging the highest block seen by more than max_block_lag are only used as a last resort
+    pub async fn check_health(&self) {
+        let probes = self.endpoints.iter().map(|endpoint| async move {
+            let started = Instant::now();
+            let result = endpoint.transport.request::<_, U64>("eth_blockNumber", ()).await;
+            (endpoint, result.map(|block| (block.as_u64(), started.elapsed())))
+        });
+        
+        for (endpoint, result) in futures::future::join_all(probes).await {
+            let mut health = endpoint.health.lock().unwrap();
+            match result {
+                Ok((block, latency)) => {
+                    health.latest_block = Some(block);
+                    health.latency = Some(latency);
+                    health.consecutive_failures = 0;
+                },
+                Err(e) => {
+                    health.consecutive_failures += 1;
+                    tracing::debug!("RPC endpoint {} failed its health check: {}", endpoint.url, e);
+                },
+            }
+        }
+        
+        let best = self.healthiest();
+        let previous = self.active.swap(best, Ordering::Relaxed);
+        if previous != best {
+            tracing::warn!("Switching RPC endpoint from {} to {}", self.endpoints[previous].url, self.endpoints[best].url);
+        }
+    }
+
+    fn healthiest(&self) -> usize {
+        let snapshots: Vec<_> = self.endpoints.iter()
+            .map(|endpoint| {
+                let health = endpoint.health.lock().unwrap();
+                (health.latest_block, health.latency, health.consecutive_failures)
+            })
+            .collect();
+        let highest = snapshots.iter().filter_map(|(block, _, _)| *block).max().unwrap_or(0);
+        
+        (0..snapshots.len())
+            .min_by_key(|&index| {
+                let (block, latency, failures) = snapshots[index];
+                let lagging = block.map_or(true, |block| highest.saturating_sub(block) > self.max_block_lag);
+                (lagging, failures, latency.unwrap_or(Duration::MAX))
+            })
+            .unwrap_or(0)
+    }
+
+    async fn check_health_if_due(&self) {
+        if self.endpoints.len() < 2 {
+            return;
+        }
+        
+        let due = {
+            let mut last_check = self.last_check.lock().unwrap();
+            let due = last_check.map_or(true, |checked| checked.elapsed() >= HEALTH_CHECK_INTERVAL);
+            if due {
+                *last_check = Some(Instant::now());
+            }
+            due
+        };
+        if due {
+            self.check_health().await;
+        }
+    }
+}
+
+#[async_trait]
+impl JsonRpcClient for FailoverTransport {
+    type Error = HttpClientError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: std::fmt::Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        self.check_health_if_due().await;
+        
+        // Serialized once so the same request can be replayed against each endpoint
+        let params = serde_json::to_value(params)
+            .map_err(|err| HttpClientError::SerdeJson { err, text: String::new() })?;
+        
+        let start = self.active.load(Ordering::Relaxed);
+        let mut last_error = None;
+        for offset in 0..self.endpoints.len() {
+            let index = (start + offset) % self.endpoints.len();
+            let endpoint = &self.endpoints[index];
+            
+            match endpoint.transport.request::<_, R>(method, &params).await {
+                Ok(response) => {
+                    endpoint.health.lock().unwrap().consecutive_failures = 0;
+                    if index != start {
+                        self.active.store(index, Ordering::Relaxed);
+                        tracing::warn!("Failed over to RPC endpoint {}", endpoint.url);
+                    }
+                    return Ok(response);
+                },
+                // The node did answer; a revert or invalid request fails the same way everywhere
+                Err(HttpClientError::JsonRpcError(e)) if !is_rate_limited(&e) => {
+                    return Err(HttpClientError::JsonRpcError(e));
+                },
+                Err(e) => {
+                    endpoint.record_failure();
+                    tracing::warn!("RPC endpoint {} failed {}: {}", endpoint.url, method, e);
+                    last_error = Some(e);
+                },
+            }
+        }
+        
+        Err(last_error.expect("the transport has at least one endpoint"))
+    }
+}
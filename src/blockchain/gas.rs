@@ -0,0 +1,181 @@
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::blockchain::failover::RpcProvider;
+use crate::errors::BotResult;
+
+// Recent blocks whose priority fees the estimate averages
+const FEE_HISTORY_BLOCKS: u64 = 10;
+pub const DEFAULT_MAX_FEE_GWEI: u64 = 300;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriorityFeeStrategy {
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl PriorityFeeStrategy {
+    // Percentile of the tips paid in recent blocks
+    fn percentile(&self) -> f64 {
+        match self {
+            PriorityFeeStrategy::Slow => 10.0,
+            PriorityFeeStrategy::Normal => 50.0,
+            PriorityFeeStrategy::Fast => 90.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GasPolicy {
+    pub priority_fee: PriorityFeeStrategy,
+    // Hard cap in wei on what a transaction may pay per gas
+    pub max_fee: U256,
+    // Fixed price for chains without EIP-1559; zero asks the node
+    pub legacy_gas_price: U256,
+}
+
+// For connectors configured with their own RPC URL rather than a chain section
+impl Default for GasPolicy {
+    fn default() -> Self {
+        Self {
+            priority_fee: PriorityFeeStrategy::default(),
+            max_fee: U256::from(DEFAULT_MAX_FEE_GWEI) * U256::exp10(9),
+            legacy_gas_price: U256::zero(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum GasFees {
+    Eip1559 {
+        base_fee: U256,
+        max_fee_per_gas: U256,
+        max_priority_fee_per_gas: U256,
+    },
+    Legacy {
+        gas_price: U256,
+    },
+}
+
+impl GasFees {
+    // What a transaction included in the next block is expected to pay per gas
+    pub fn expected_price(&self) -> U256 {
+        match *self {
+            GasFees::Eip1559 { base_fee, max_priority_fee_per_gas, .. } => base_fee + max_priority_fee_per_gas,
+            GasFees::Legacy { gas_price } => gas_price,
+        }
+    }
+
+    // Rewrites the transaction as the matching type, keeping everything but its fee fields
+    pub fn apply(&self, tx: &mut TypedTransaction) {
+        let from = tx.from().copied();
+        let to = tx.to().cloned();
+        let gas = tx.gas().copied();
+        let value = tx.value().copied();
+        let data = tx.data().cloned();
+        let nonce = tx.nonce().copied();
+        let chain_id = tx.chain_id();
+        
+        *tx = match *self {
+            GasFees::Eip1559 { max_fee_per_gas, max_priority_fee_per_gas, .. } => TypedTransaction::Eip1559(Eip1559TransactionRequest {
+                from,
+                to,
+                gas,
+                value,
+                data,
+                nonce,
+                chain_id,
+                max_fee_per_gas: Some(max_fee_per_gas),
+                max_priority_fee_per_gas: Some(max_priority_fee_per_gas),
+                ..Default::default()
+            }),
+            GasFees::Legacy { gas_price } => TypedTransaction::Legacy(TransactionRequest {
+                from,
+                to,
+                gas,
+                gas_price: Some(gas_price),
+                value,
+                data,
+                nonce,
+                chain_id,
+                ..Default::default()
+            }),
+        };
+    }
+}
+
+pub struct GasOracle {
+    provider: Arc<RpcProvider>,
+    policy: GasPolicy,
+}
+
+impl GasOracle {
+    pub fn new(provider: Arc<RpcProvider>, policy: GasPolicy) -> Self {
+        Self { provider, policy }
+    }
+
+    // Fails instead of pricing above the cap, so callers skip the transaction rather than overpay
+    pub async fn fees(&self) -> BotResult<GasFees> {
+        let history = match self.provider
+            .fee_history(FEE_HISTORY_BLOCKS, BlockNumber::Latest, &[self.policy.priority_fee.percentile()])
+            .await
+        {
+            Ok(history) => history,
+            Err(e) => {
+                tracing::debug!("eth_feeHistory unavailable, using legacy gas pricing: {}", e);
+                return self.legacy_fees().await;
+            }
+        };
+        
+        // The last entry is the base fee of the block after the newest one in the history
+        let base_fee = history.base_fee_per_gas.last().copied().unwrap_or_default();
+        if base_fee.is_zero() {
+            return self.legacy_fees().await;
+        }
+        
+        let tips: Vec<U256> = history.reward.iter()
+            .filter_map(|rewards| rewards.first().copied())
+            .collect();
+        let priority_fee = if tips.is_empty() {
+            U256::zero()
+        } else {
+            tips.iter().fold(U256::zero(), |sum, tip| sum + tip) / U256::from(tips.len())
+        };
+        
+        self.check_cap(base_fee + priority_fee)?;
+        
+        // Room for the base fee to double before inclusion, but never past the cap
+        Ok(GasFees::Eip1559 {
+            base_fee,
+            max_fee_per_gas: (base_fee * 2 + priority_fee).min(self.policy.max_fee),
+            max_priority_fee_per_gas: priority_fee,
+        })
+    }
+
+    async fn legacy_fees(&self) -> BotResult<GasFees> {
+        let gas_price = if self.policy.legacy_gas_price.is_zero() {
+            self.provider.get_gas_price().await?
+        } else {
+            self.policy.legacy_gas_price
+        };
+        
+        self.check_cap(gas_price)?;
+        Ok(GasFees::Legacy { gas_price })
+    }
+
+    pub fn check_cap(&self, price: U256) -> BotResult<()> {
+        if price > self.policy.max_fee {
+            return Err(anyhow::anyhow!(
+                "Gas price {} gwei exceeds the max_fee_gwei cap of {} gwei",
+                ethers::utils::format_units(price, "gwei")?,
+                ethers::utils::format_units(self.policy.max_fee, "gwei")?
+            ).into());
+        }
+        Ok(())
+    }
+}
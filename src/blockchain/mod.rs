@@ -0,0 +1,160 @@
+use ethers::prelude::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::config::{BlockchainConfig, ChainConfig};
+use crate::database::Database;
+use crate::errors::BotResult;
+use crate::exchanges::erc20::SignerClient;
+
+pub mod across;
+pub mod atomic;
+pub mod blocks;
+pub mod bridge;
+pub mod failover;
+pub mod gas;
+pub mod multicall;
+pub mod nonce;
+pub mod private;
+pub mod simulate;
+
+use atomic::AtomicExecutor;
+use blocks::HeadReceiver;
+use failover::RpcProvider;
+use gas::GasOracle;
+use nonce::TransactionManager;
+use private::PrivateRelay;
+
+pub struct BlockchainManager {
+    chains: HashMap<String, ChainConfig>,
+    providers: HashMap<String, Arc<RpcProvider>>,
+    // Only chains with a private key configured can send transactions
+    signers: HashMap<String, Arc<SignerClient>>,
+    gas_oracles: HashMap<String, Arc<GasOracle>>,
+    // Keyed by chain id; only chains with a websocket URL have one
+    heads: HashMap<u64, HeadReceiver>,
+    // One per wallet and chain, shared by every connector sending from that wallet
+    transaction_managers: Mutex<HashMap<(u64, Address), Arc<TransactionManager>>>,
+    database: Option<Database>,
+}
+
+impl BlockchainManager {
+    pub async fn new(config: &BlockchainConfig) -> BotResult<Self> {
+        let mut chains = HashMap::new();
+        let mut providers = HashMap::new();
+        let mut signers = HashMap::new();
+        let mut gas_oracles = HashMap::new();
+        let mut heads = HashMap::new();
+        
+        for (name, chain) in config.iter() {
+            if !chain.enabled {
+                continue;
+            }
+            
+            let provider = Arc::new(failover::rpc_provider(&chain.rpc_urls, chain.max_block_lag, &chain.http())?);
+            if !chain.private_key.is_empty() {
+                let wallet = chain.private_key.parse::<LocalWallet>()?.with_chain_id(chain.chain_id);
+                signers.insert(name.to_string(), Arc::new(SignerMiddleware::new(provider.clone(), wallet)));
+            }
+            gas_oracles.insert(name.to_string(), Arc::new(GasOracle::new(provider.clone(), chain.gas_policy())));
+            if let Some(ws_url) = &chain.ws_url {
+                heads.insert(chain.chain_id, blocks::spawn_head_watcher(name.to_string(), ws_url.clone()));
+            }
+            providers.insert(name.to_string(), provider);
+            chains.insert(name.to_string(), chain.clone());
+            tracing::info!("Initialized {} provider (chain id {})", chain.name, chain.chain_id);
+        }
+        
+        Ok(Self {
+            chains,
+            providers,
+            signers,
+            gas_oracles,
+            heads,
+            transaction_managers: Mutex::new(HashMap::new()),
+            database: None,
+        })
+    }
+    
+    // Persists the last nonce used by each wallet so a restart never reuses one
+    pub fn with_database(mut self, database: Database) -> Self {
+        self.database = Some(database);
+        self
+    }
+    
+    pub fn transaction_manager(&self, client: Arc<SignerClient>, gas_oracle: Arc<GasOracle>) -> BotResult<Arc<TransactionManager>> {
+        let chain_id = client.signer().chain_id();
+        let key = (chain_id, client.address());
+        
+        let mut managers = self.transaction_managers.lock().unwrap();
+        if let Some(manager) = managers.get(&key) {
+            return Ok(manager.clone());
+        }
+        
+        let chain = self.chains.values().find(|chain| chain.chain_id == chain_id);
+        let policy = chain.map(|chain| chain.replacement_policy()).unwrap_or_default();
+        let private_relay = chain.and_then(|chain| chain.private_tx_policy())
+            .map(PrivateRelay::new)
+            .transpose()?;
+        
+        let manager = Arc::new(TransactionManager::new(client, gas_oracle, policy, self.database.clone(), private_relay));
+        managers.insert(key, manager.clone());
+        Ok(manager)
+    }
+    
+    // None when the chain has no executor contract configured
+    pub fn atomic_executor(&self, chain_id: u64) -> BotResult<Option<AtomicExecutor>> {
+        let Some((name, chain)) = self.chains.iter().find(|(_, chain)| chain.chain_id == chain_id) else {
+            return Ok(None);
+        };
+        let Some(executor) = &chain.executor_contract else {
+            return Ok(None);
+        };
+        
+        let client = self.get_signer(name)
+            .ok_or_else(|| anyhow::anyhow!("Atomic execution on {} requires a private key", name))?;
+        let gas_oracle = self.get_gas_oracle(name)
+            .ok_or_else(|| anyhow::anyhow!("No gas oracle for {}", name))?;
+        let pool = match &chain.flash_loan_pool {
+            Some(pool) => pool.parse::<Address>()
+                .map_err(|e| anyhow::anyhow!("Invalid flash_loan_pool {} for {}: {}", pool, name, e))?,
+            None => atomic::aave_v3_pool(chain_id)
+                .ok_or_else(|| anyhow::anyhow!("No flash loan pool known for {}; set flash_loan_pool", name))?,
+        };
+        
+        let executor = executor.parse::<Address>()
+            .map_err(|e| anyhow::anyhow!("Invalid executor_contract {} for {}: {}", executor, name, e))?;
+        let transactions = self.transaction_manager(client.clone(), gas_oracle)?;
+        Ok(Some(AtomicExecutor::new(executor, pool, client, transactions)))
+    }
+    
+    pub fn chain_heads(&self) -> &HashMap<u64, HeadReceiver> {
+        &self.heads
+    }
+    
+    pub fn get_provider(&self, chain: &str) -> Option<Arc<RpcProvider>> {
+        self.providers.get(chain).cloned()
+    }
+    
+    pub fn get_signer(&self, chain: &str) -> Option<Arc<SignerClient>> {
+        self.signers.get(chain).cloned()
+    }
+    
+    pub fn get_gas_oracle(&self, chain: &str) -> Option<Arc<GasOracle>> {
+        self.gas_oracles.get(chain).cloned()
+    }
+    
+    pub fn get_chain_config(&self, chain: &str) -> Option<&ChainConfig> {
+        self.chains.get(chain)
+    }
+    
+    pub fn chain_name(&self, chain_id: u64) -> Option<&str> {
+        self.chains.iter()
+            .find(|(_, chain)| chain.chain_id == chain_id)
+            .map(|(name, _)| name.as_str())
+    }
+    
+    pub fn enabled_chains(&self) -> Vec<&str> {
+        self.chains.keys().map(|name| name.as_str()).collect()
+    }
+}
@@ -0,0 +1,168 @@
+use ethers::abi::Token;
+use ethers::contract::{Multicall, MulticallVersion, MULTICALL_ADDRESS};
+use ethers::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::blockchain::failover::RpcProvider;
+use crate::errors::BotResult;
+use crate::exchanges::erc20::ERC20;
+
+#[derive(Debug, Clone, Default)]
+pub struct WalletSnapshot {
+    pub native: U256,
+    pub balances: HashMap<Address, U256>,
+    pub allowances: HashMap<Address, U256>,
+    pub decimals: HashMap<Address, u8>,
+    pub symbols: HashMap<Address, String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Read {
+    Native(Address),
+    Balance(Address, Address),
+    Allowance(Address, Address, Address),
+    Decimals(Address),
+    Symbol(Address),
+}
+
+// Batches ERC20 reads through Multicall3; decimals and symbols never change, so they are read once per token
+pub struct TokenReader {
+    provider: Arc<RpcProvider>,
+    decimals: Mutex<HashMap<Address, u8>>,
+    symbols: Mutex<HashMap<Address, String>>,
+    reads: AtomicU64,
+    rpc_calls: AtomicU64,
+}
+
+impl TokenReader {
+    pub fn new(provider: Arc<RpcProvider>) -> Self {
+        Self {
+            provider,
+            decimals: Mutex::new(HashMap::new()),
+            symbols: Mutex::new(HashMap::new()),
+            reads: AtomicU64::new(0),
+            rpc_calls: AtomicU64::new(0),
+        }
+    }
+
+    pub async fn decimals(&self, token: Address) -> BotResult<u8> {
+        Ok(self.decimals_of(&[token]).await?[&token])
+    }
+
+    pub async fn decimals_of(&self, tokens: &[Address]) -> BotResult<HashMap<Address, u8>> {
+        let missing: Vec<_> = {
+            let cached = self.decimals.lock().unwrap();
+            tokens.iter().filter(|token| !cached.contains_key(token)).map(|token| Read::Decimals(*token)).collect()
+        };
+        self.execute(&missing).await?;
+        
+        let cached = self.decimals.lock().unwrap();
+        tokens.iter()
+            .map(|token| cached.get(token)
+                .map(|decimals| (*token, *decimals))
+                .ok_or_else(|| anyhow::anyhow!("decimals() failed for token {:?}", token).into()))
+            .collect()
+    }
+
+    // Native balance, token balances, allowances for the spender and any uncached metadata in one eth_call
+    pub async fn snapshot(&self, owner: Address, tokens: &[Address], spender: Option<Address>) -> BotResult<WalletSnapshot> {
+        let mut reads = vec![Read::Native(owner)];
+        {
+            let decimals = self.decimals.lock().unwrap();
+            let symbols = self.symbols.lock().unwrap();
+            for &token in tokens {
+                reads.push(Read::Balance(token, owner));
+                if let Some(spender) = spender {
+                    reads.push(Read::Allowance(token, owner, spender));
+                }
+                if !decimals.contains_key(&token) {
+                    reads.push(Read::Decimals(token));
+                }
+                if !symbols.contains_key(&token) {
+                    reads.push(Read::Symbol(token));
+                }
+            }
+        }
+        
+        let mut snapshot = self.execute(&reads).await?;
+        // Some older tokens have no string symbol(); they are simply left out
+        let decimals = self.decimals.lock().unwrap();
+        let symbols = self.symbols.lock().unwrap();
+        for token in tokens {
+            if let Some(value) = decimals.get(token) {
+                snapshot.decimals.insert(*token, *value);
+            }
+            if let Some(symbol) = symbols.get(token) {
+                snapshot.symbols.insert(*token, symbol.clone());
+            }
+        }
+        Ok(snapshot)
+    }
+
+    async fn execute(&self, reads: &[Read]) -> BotResult<WalletSnapshot> {
+        let mut snapshot = WalletSnapshot::default();
+        if reads.is_empty() {
+            return Ok(snapshot);
+        }
+        
+        let mut multicall = Multicall::new(self.provider.clone(), Some(MULTICALL_ADDRESS)).await?
+            .version(MulticallVersion::Multicall3);
+        for read in reads {
+            // Every sub-call may fail on its own so one odd token cannot sink the whole batch
+            match *read {
+                Read::Native(owner) => {
+                    multicall.add_get_eth_balance(owner, true);
+                },
+                Read::Balance(token, owner) => {
+                    multicall.add_call(ERC20::new(token, self.provider.clone()).balance_of(owner), true);
+                },
+                Read::Allowance(token, owner, spender) => {
+                    multicall.add_call(ERC20::new(token, self.provider.clone()).allowance(owner, spender), true);
+                },
+                Read::Decimals(token) => {
+                    multicall.add_call(ERC20::new(token, self.provider.clone()).decimals(), true);
+                },
+                Read::Symbol(token) => {
+                    multicall.add_call(ERC20::new(token, self.provider.clone()).symbol(), true);
+                },
+            }
+        }
+        
+        let results = multicall.call_raw().await?;
+        self.record_batch(reads.len());
+        
+        for (read, result) in reads.iter().zip(results) {
+            let Ok(value) = result else {
+                tracing::debug!("Multicall read {:?} reverted", read);
+                continue;
+            };
+            match (*read, value) {
+                (Read::Native(_), Token::Uint(balance)) => snapshot.native = balance,
+                (Read::Balance(token, _), Token::Uint(balance)) => {
+                    snapshot.balances.insert(token, balance);
+                },
+                (Read::Allowance(token, ..), Token::Uint(allowance)) => {
+                    snapshot.allowances.insert(token, allowance);
+                },
+                (Read::Decimals(token), Token::Uint(decimals)) => {
+                    self.decimals.lock().unwrap().insert(token, decimals.low_u32() as u8);
+                },
+                (Read::Symbol(token), Token::String(symbol)) => {
+                    self.symbols.lock().unwrap().insert(token, symbol);
+                },
+                (read, value) => tracing::debug!("Unexpected multicall result {:?} for {:?}", value, read),
+            }
+        }
+        
+        Ok(snapshot)
+    }
+
+    fn record_batch(&self, reads: usize) {
+        let total_reads = self.reads.fetch_add(reads as u64, Ordering::Relaxed) + reads as u64;
+        let total_calls = self.rpc_calls.fetch_add(1, Ordering::Relaxed) + 1;
+        tracing::debug!("Batched {} token reads into one RPC call; {} round trips saved so far ({} reads over {} calls)",
+                        reads, total_reads - total_calls, total_reads, total_calls);
+    }
+}
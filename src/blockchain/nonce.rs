@@ -0,0 +1,284 @@
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::blockchain::gas::{GasFees, GasOracle};
+use crate::blockchain::private::PrivateRelay;
+use crate::blockchain::simulate;
+use crate::database::Database;
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::erc20::SignerClient;
+
+// Replacements must outbid the stuck transaction by at least 10% to be accepted by most nodes
+const FEE_BUMP_PERCENT: u64 = 20;
+
+pub const DEFAULT_STUCK_BLOCKS: u64 = 5;
+pub const DEFAULT_MAX_BUMPS: u32 = 3;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ReplacementPolicy {
+    // Blocks a transaction may stay unmined before it is rebroadcast with a higher fee
+    pub stuck_blocks: u64,
+    pub max_bumps: u32,
+}
+
+// For wallets on chains without a chain section
+impl Default for ReplacementPolicy {
+    fn default() -> Self {
+        Self {
+            stuck_blocks: DEFAULT_STUCK_BLOCKS,
+            max_bumps: DEFAULT_MAX_BUMPS,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TxStatus {
+    Pending,
+    Mined(TransactionReceipt),
+    // Still unmined after every allowed replacement; the reason is meant for the failed trade
+    Abandoned(String),
+}
+
+#[derive(Debug, Clone)]
+struct PendingTx {
+    tx: TypedTransaction,
+    fees: GasFees,
+    // Every hash broadcast for this nonce, original first; any of them may be the one that mines
+    hashes: Vec<H256>,
+    broadcast_block: U64,
+    bumps: u32,
+    // Last block the private relay will try; private transactions are never fee-bumped
+    private_deadline: Option<U64>,
+}
+
+// Hands out nonces for one wallet on one chain, so concurrent sends never collide, and replaces
+// transactions that get stuck behind a rising base fee
+pub struct TransactionManager {
+    client: Arc<SignerClient>,
+    gas_oracle: Arc<GasOracle>,
+    policy: ReplacementPolicy,
+    database: Option<Database>,
+    private_relay: Option<PrivateRelay>,
+    // None until the first send, and again after a failed send, so it is re-read from the node
+    next_nonce: tokio::sync::Mutex<Option<U256>>,
+    // Keyed by the original hash, which callers keep as the order id
+    pending: Mutex<HashMap<H256, PendingTx>>,
+}
+
+impl TransactionManager {
+    pub fn new(
+        client: Arc<SignerClient>,
+        gas_oracle: Arc<GasOracle>,
+        policy: ReplacementPolicy,
+        database: Option<Database>,
+        private_relay: Option<PrivateRelay>,
+    ) -> Self {
+        Self {
+            client,
+            gas_oracle,
+            policy,
+            database,
+            private_relay,
+            next_nonce: tokio::sync::Mutex::new(None),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        self.client.address()
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.client.signer().chain_id()
+    }
+
+    // The node's pending count misses transactions it has already dropped, the persisted nonce
+    // misses those sent by other tools; the larger of the two is safe
+    async fn resync_nonce(&self) -> BotResult<U256> {
+        let on_chain = self.client.get_transaction_count(self.address(), Some(BlockNumber::Pending.into())).await?;
+        let persisted = match &self.database {
+            Some(database) => database.get_last_nonce(self.chain_id(), &format!("{:?}", self.address())).await?
+                .map(|nonce| U256::from(nonce) + 1),
+            None => None,
+        };
+        Ok(persisted.map_or(on_chain, |persisted| persisted.max(on_chain)))
+    }
+
+    pub fn gas_oracle(&self) -> &Arc<GasOracle> {
+        &self.gas_oracle
+    }
+
+    pub fn private_relay(&self) -> Option<&PrivateRelay> {
+        self.private_relay.as_ref()
+    }
+
+    // Returns the hash to track the transaction by, even if it is later replaced
+    pub async fn send(&self, tx: TypedTransaction) -> BotResult<H256> {
+        self.broadcast(tx, false).await
+    }
+
+    pub async fn send_private(&self, tx: TypedTransaction) -> BotResult<H256> {
+        if self.private_relay.is_none() {
+            return Err(anyhow::anyhow!("No private relay configured for chain {}", self.chain_id()).into());
+        }
+        self.broadcast(tx, true).await
+    }
+
+    async fn broadcast(&self, mut tx: TypedTransaction, private: bool) -> BotResult<H256> {
+        let fees = self.gas_oracle.fees().await?;
+        fees.apply(&mut tx);
+        tx.set_from(self.address());
+        simulate::simulate(&self.client, &tx).await?;
+        if tx.gas().is_none() {
+            let gas = self.client.estimate_gas(&tx, None).await?;
+            tx.set_gas(gas);
+        }
+        
+        let mut next_nonce = self.next_nonce.lock().await;
+        let nonce = match *next_nonce {
+            Some(nonce) => nonce,
+            None => self.resync_nonce().await?,
+        };
+        tx.set_nonce(nonce);
+        
+        let broadcast_block = self.client.get_block_number().await?;
+        let private_deadline = self.private_relay.as_ref()
+            .filter(|_| private)
+            .map(|relay| broadcast_block + relay.policy().deadline_blocks);
+        
+        let sent = match (&self.private_relay, private_deadline) {
+            (Some(relay), Some(deadline)) => self.submit_private(relay, &tx, deadline).await,
+            _ => self.client.send_transaction(tx.clone(), None).await
+                .map(|pending_tx| pending_tx.tx_hash())
+                .map_err(Into::into),
+        };
+        let hash = match sent {
+            Ok(hash) => hash,
+            Err(e) => {
+                *next_nonce = None;
+                return Err(e);
+            }
+        };
+        *next_nonce = Some(nonce + 1);
+        drop(next_nonce);
+        
+        if let Some(database) = &self.database {
+            database.save_last_nonce(self.chain_id(), &format!("{:?}", self.address()), nonce.as_u64()).await?;
+        }
+        
+        self.pending.lock().unwrap().insert(hash, PendingTx {
+            tx,
+            fees,
+            hashes: vec![hash],
+            broadcast_block,
+            bumps: 0,
+            private_deadline,
+        });
+        
+        Ok(hash)
+    }
+
+    async fn submit_private(&self, relay: &PrivateRelay, tx: &TypedTransaction, deadline: U64) -> BotResult<H256> {
+        let signature = self.client.signer().sign_transaction(tx).await?;
+        relay.submit(tx.rlp_signed(&signature), deadline).await
+    }
+
+    // Checks every broadcast of the transaction and replaces it if it has been stuck too long
+    pub async fn poll(&self, hash: H256) -> BotResult<TxStatus> {
+        let pending = self.pending.lock().unwrap().get(&hash).cloned();
+        let Some(mut pending) = pending else {
+            // Sent before a restart or by another process; all that can be done is look for it
+            return Ok(match self.client.get_transaction_receipt(hash).await? {
+                Some(receipt) => TxStatus::Mined(receipt),
+                None => TxStatus::Pending,
+            });
+        };
+        
+        for broadcast in &pending.hashes {
+            if let Some(receipt) = self.client.get_transaction_receipt(*broadcast).await? {
+                self.pending.lock().unwrap().remove(&hash);
+                return Ok(TxStatus::Mined(receipt));
+            }
+        }
+        
+        let current_block = self.client.get_block_number().await?;
+        // The relay drops what it cannot include without telling anyone, so silence past the deadline is failure
+        if let Some(deadline) = pending.private_deadline {
+            if current_block <= deadline {
+                return Ok(TxStatus::Pending);
+            }
+            self.pending.lock().unwrap().remove(&hash);
+            *self.next_nonce.lock().await = None;
+            return Ok(TxStatus::Abandoned(format!(
+                "Private transaction {:?} was not included by block {}", hash, deadline
+            )));
+        }
+        
+        if current_block.saturating_sub(pending.broadcast_block).as_u64() < self.policy.stuck_blocks {
+            return Ok(TxStatus::Pending);
+        }
+        
+        if pending.bumps >= self.policy.max_bumps {
+            self.pending.lock().unwrap().remove(&hash);
+            // The nonce may still be occupied by the stuck transaction
+            *self.next_nonce.lock().await = None;
+            return Ok(TxStatus::Abandoned(format!(
+                "Transaction {:?} not mined after {} fee bumps", hash, pending.bumps
+            )));
+        }
+        
+        let fees = self.bumped_fees(&pending.fees).await?;
+        fees.apply(&mut pending.tx);
+        let replacement = self.client.send_transaction(pending.tx.clone(), None).await?.tx_hash();
+        
+        pending.bumps += 1;
+        pending.fees = fees;
+        pending.hashes.push(replacement);
+        pending.broadcast_block = current_block;
+        tracing::warn!("Replaced stuck transaction {:?} with {:?} (bump {} of {})",
+                       hash, replacement, pending.bumps, self.policy.max_bumps);
+        self.pending.lock().unwrap().insert(hash, pending);
+        
+        Ok(TxStatus::Pending)
+    }
+
+    // The current market fee or the previous fee plus the minimum bump, whichever is higher;
+    // the oracle still refuses anything above the chain's cap
+    async fn bumped_fees(&self, previous: &GasFees) -> BotResult<GasFees> {
+        let bump = |fee: U256| fee * (100 + FEE_BUMP_PERCENT) / 100;
+        let current = self.gas_oracle.fees().await?;
+        
+        Ok(match (current, *previous) {
+            (
+                GasFees::Eip1559 { base_fee, max_fee_per_gas, max_priority_fee_per_gas },
+                GasFees::Eip1559 { max_fee_per_gas: previous_max, max_priority_fee_per_gas: previous_priority, .. },
+            ) => {
+                let max_priority_fee_per_gas = max_priority_fee_per_gas.max(bump(previous_priority));
+                let max_fee_per_gas = max_fee_per_gas.max(bump(previous_max));
+                self.gas_oracle.check_cap(max_fee_per_gas)?;
+                GasFees::Eip1559 { base_fee, max_fee_per_gas, max_priority_fee_per_gas }
+            },
+            (GasFees::Legacy { gas_price }, GasFees::Legacy { gas_price: previous_price }) => {
+                let gas_price = gas_price.max(bump(previous_price));
+                self.gas_oracle.check_cap(gas_price)?;
+                GasFees::Legacy { gas_price }
+            },
+            // A replacement must keep the transaction type, so the node's view of the chain changed
+            _ => return Err(BotError::chain("Chain fee model changed since the transaction was sent")),
+        })
+    }
+
+    // Polls until the transaction mines, replacing it as needed; for sends that must land before
+    // the caller can continue (e.g. approvals)
+    pub async fn confirm(&self, hash: H256) -> BotResult<TransactionReceipt> {
+        loop {
+            match self.poll(hash).await? {
+                TxStatus::Mined(receipt) => return Ok(receipt),
+                TxStatus::Abandoned(reason) => return Err(BotError::chain(reason)),
+                TxStatus::Pending => tokio::time::sleep(std::time::Duration::from_secs(3)).await,
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+
+use crate::errors::{BotError, BotResult};
+use crate::utils;
+
+pub const FLASHBOTS_PROTECT_URL: &str = "https://rpc.flashbots.net/fast";
+
+#[derive(Debug, Clone)]
+pub struct PrivateTxPolicy {
+    pub relay_url: String,
+    // The relay stops trying to include the transaction after this many blocks
+    pub deadline_blocks: u64,
+    // Trades worth less than this in the quote asset go to the public mempool, where inclusion is faster
+    pub min_notional: Decimal,
+    pub http: utils::HttpSettings,
+}
+
+// Submits signed transactions to a private relay (Flashbots Protect, MEV-Share) so they are never
+// visible in the public mempool to sandwich bots
+pub struct PrivateRelay {
+    relay: Provider<Http>,
+    policy: PrivateTxPolicy,
+}
+
+impl PrivateRelay {
+    pub fn new(policy: PrivateTxPolicy) -> BotResult<Self> {
+        Ok(Self {
+            relay: utils::http_provider(&policy.relay_url, &policy.http)?,
+            policy,
+        })
+    }
+
+    pub fn policy(&self) -> &PrivateTxPolicy {
+        &self.policy
+    }
+
+    pub fn should_route(&self, notional: Decimal) -> bool {
+        notional >= self.policy.min_notional
+    }
+
+    // Returns the transaction hash; a relay that drops the transaction never reports back, so callers
+    // must stop waiting at max_block
+    pub async fn submit(&self, raw_tx: Bytes, max_block: U64) -> BotResult<H256> {
+        let params = serde_json::json!({
+            "tx": raw_tx,
+            "maxBlockNumber": max_block,
+            "preferences": { "fast": true },
+        });
+        
+        let hash: H256 = self.relay.request("eth_sendPrivateTransaction", [params]).await
+            .map_err(|e| BotError::chain(format!("Private relay {} rejected the transaction: {}", self.policy.relay_url, e)))?;
+        Ok(hash)
+    }
+}
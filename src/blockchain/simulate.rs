@@ -0,0 +1,192 @@
+use ethers::abi::{self, ParamType, Token};
+use ethers::prelude::*;
+use ethers::providers::MiddlewareError;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use std::fmt;
+
+use crate::blockchain::failover::RpcProvider;
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::erc20::SignerClient;
+
+// Error(string) and Panic(uint256), the two encodings solc emits on its own
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+// Custom errors of contracts the bot sends to; the V2/V3 routers predate custom errors and revert with strings
+const KNOWN_ERRORS: &[&str] = &[
+    // Arbitrage executor
+    "error InsufficientProfit(uint256 balance, uint256 required)",
+    "error StepFailed(uint256 index, bytes reason)",
+    // Uniswap Universal Router
+    "error V2TooLittleReceived()",
+    "error V3TooLittleReceived()",
+    "error TransactionDeadlinePassed()",
+    // 1inch aggregation router v5
+    "error ReturnAmountIsNotEnough()",
+    "error SwapWithZeroAmount()",
+    // Across spoke pool
+    "error InvalidQuoteTimestamp()",
+    "error InvalidFillDeadline()",
+    "error DisabledRoute()",
+    "error MsgValueDoesNotMatchInputAmount()",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RevertReason {
+    Message(String),
+    Panic(U256),
+    Custom { name: String, args: Vec<String> },
+    Unknown(Bytes),
+    Empty,
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevertReason::Message(message) => write!(f, "{}", message),
+            RevertReason::Panic(code) => write!(f, "panic 0x{:02x} ({})", code, panic_description(*code)),
+            RevertReason::Custom { name, args } => write!(f, "{}({})", name, args.join(", ")),
+            RevertReason::Unknown(data) => write!(f, "unrecognized revert data {}", data),
+            RevertReason::Empty => write!(f, "reverted without a reason"),
+        }
+    }
+}
+
+fn panic_description(code: U256) -> &'static str {
+    match code.low_u64() {
+        0x01 => "assertion failed",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "invalid enum value",
+        0x22 => "corrupted storage byte array",
+        0x31 => "pop on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to uninitialized function",
+        _ => "unknown panic code",
+    }
+}
+
+pub fn decode_revert(data: &[u8]) -> RevertReason {
+    if data.is_empty() {
+        return RevertReason::Empty;
+    }
+    if data.len() < 4 {
+        return RevertReason::Unknown(Bytes::from(data.to_vec()));
+    }
+
+    let (selector, payload) = data.split_at(4);
+    if selector == ERROR_SELECTOR {
+        if let Ok(tokens) = abi::decode(&[ParamType::String], payload) {
+            if let Some(Token::String(message)) = tokens.into_iter().next() {
+                return RevertReason::Message(message);
+            }
+        }
+    }
+    if selector == PANIC_SELECTOR {
+        if let Ok(tokens) = abi::decode(&[ParamType::Uint(256)], payload) {
+            if let Some(Token::Uint(code)) = tokens.into_iter().next() {
+                return RevertReason::Panic(code);
+            }
+        }
+    }
+
+    if let Ok(known) = abi::parse_abi(KNOWN_ERRORS) {
+        for error in known.errors() {
+            if &error.signature().as_bytes()[..4] != selector {
+                continue;
+            }
+            if let Ok(tokens) = error.decode(payload) {
+                return RevertReason::Custom {
+                    name: error.name.clone(),
+                    args: tokens.iter().map(|token| token.to_string()).collect(),
+                };
+            }
+        }
+    }
+
+    RevertReason::Unknown(Bytes::from(data.to_vec()))
+}
+
+// Runs the transaction through eth_call at the latest block with the sender's address, so a swap,
+// approval or bridge deposit that would revert fails here instead of on chain
+pub async fn simulate(client: &SignerClient, tx: &TypedTransaction) -> BotResult<()> {
+    let mut tx = tx.clone();
+    tx.set_from(client.address());
+
+    match client.call(&tx, Some(BlockNumber::Latest.into())).await {
+        Ok(_) => Ok(()),
+        Err(e) => match e.as_error_response().and_then(|response| response.as_revert_data()) {
+            Some(data) => Err(BotError::chain(format!("Transaction to {:?} would revert: {}", tx.to_addr(), decode_revert(&data)))),
+            None => Err(BotError::chain(format!("Transaction simulation failed: {}", e))),
+        },
+    }
+}
+
+// Re-runs a transaction that reverted on chain against the state before its block; transactions
+// ahead of it in the same block are not replayed, so the reason can differ when it was front-run
+pub async fn replay_revert_reason(provider: &RpcProvider, tx_hash: H256) -> Option<String> {
+    let tx = provider.get_transaction(tx_hash).await.ok()??;
+    let block = tx.block_number?.saturating_sub(U64::one());
+
+    let mut request = TransactionRequest::new()
+        .from(tx.from)
+        .data(tx.input.clone())
+        .value(tx.value)
+        .gas(tx.gas);
+    if let Some(to) = tx.to {
+        request = request.to(to);
+    }
+
+    match provider.call(&request.into(), Some(block.into())).await {
+        Ok(_) => None,
+        Err(e) => Some(match e.as_error_response().and_then(|response| response.as_revert_data()) {
+            Some(data) => decode_revert(&data).to_string(),
+            None => e.to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hand-encoded revert payloads for each form the decoder understands
+    #[test]
+    fn decodes_each_revert_form() {
+        let encode = |signature: &str, tokens: &[Token]| -> Vec<u8> {
+            let mut data = ethers::utils::id(signature).to_vec();
+            data.extend(abi::encode(tokens));
+            data
+        };
+        let message = decode_revert(&encode("Error(string)", &[Token::String("Too little received".to_string())]));
+        assert!(message == RevertReason::Message("Too little received".to_string()),
+               "Error(string) decoded as {:?}", message);
+        
+        let panic = decode_revert(&encode("Panic(uint256)", &[Token::Uint(U256::from(0x11))]));
+        assert!(panic == RevertReason::Panic(U256::from(0x11)),
+               "Panic(0x11) decoded as {:?}", panic);
+        assert!(panic.to_string().contains("overflow"),
+               "Panic(0x11) described as {}", panic);
+        
+        let custom = decode_revert(&encode(
+            "InsufficientProfit(uint256,uint256)",
+            &[Token::Uint(U256::from(995)), Token::Uint(U256::from(1000))],
+        ));
+        let expected = RevertReason::Custom {
+            name: "InsufficientProfit".to_string(),
+            args: vec!["995".to_string(), "1000".to_string()],
+        };
+        assert!(custom == expected, "InsufficientProfit decoded as {:?}", custom);
+        
+        let no_args = decode_revert(&encode("V3TooLittleReceived()", &[]));
+        assert!(matches!(&no_args, RevertReason::Custom { name, args } if name == "V3TooLittleReceived" && args.is_empty()),
+               "V3TooLittleReceived() decoded as {:?}", no_args);
+        
+        let unknown = decode_revert(&encode("NotARealError(uint256)", &[Token::Uint(U256::one())]));
+        assert!(matches!(unknown, RevertReason::Unknown(_)),
+               "unregistered custom error decoded as {:?}", unknown);
+        assert!(decode_revert(&[]) == RevertReason::Empty,
+               "empty revert data was not reported as a bare revert");
+    }
+}
@@ -0,0 +1,163 @@
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot, watch};
+use tracing::{debug, warn};
+
+pub const CONTROL_CHANNEL_CAPACITY: usize = 16;
+
+// Sent by the REST control endpoints and applied by the main loop between scan cycles
+#[derive(Debug, Clone)]
+pub enum ControlCommand {
+    Pause,
+    Resume,
+    DryRun(bool),
+    Trading(TradingAdjustment),
+}
+
+impl fmt::Display for ControlCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ControlCommand::Pause => write!(f, "pause"),
+            ControlCommand::Resume => write!(f, "resume"),
+            ControlCommand::DryRun(true) => write!(f, "dry-run on"),
+            ControlCommand::DryRun(false) => write!(f, "dry-run off"),
+            ControlCommand::Trading(adjustment) => write!(f, "trading {}", adjustment),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TradingAdjustment {
+    pub min_profit_threshold: Option<Decimal>,
+    pub max_concurrent_trades: Option<usize>,
+}
+
+impl fmt::Display for TradingAdjustment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut changes = Vec::new();
+        if let Some(threshold) = self.min_profit_threshold {
+            changes.push(format!("min_profit_threshold={}", threshold));
+        }
+        if let Some(max) = self.max_concurrent_trades {
+            changes.push(format!("max_concurrent_trades={}", max));
+        }
+        write!(f, "{}", changes.join(" "))
+    }
+}
+
+pub struct ControlRequest {
+    pub command: ControlCommand,
+    // The outcome as applied, or why it was rejected
+    pub reply: oneshot::Sender<Result<String, String>>,
+}
+
+// What the control endpoints can change, published by the main loop for the status server
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeSettings {
+    pub paused: bool,
+    pub dry_run: bool,
+    pub min_profit_threshold: Decimal,
+    pub max_concurrent_trades: usize,
+}
+
+#[derive(Clone)]
+pub struct ControlHandle {
+    pub token: String,
+    pub commands: mpsc::Sender<ControlRequest>,
+}
+
+impl ControlHandle {
+    // Compares every byte so the time taken does not reveal how much of the token matched. A blank
+    // token authorizes nothing, even a bare "Bearer "
+    pub fn authorized(&self, authorization: Option<&str>) -> bool {
+        if self.token.trim().is_empty() {
+            return false;
+        }
+        let Some(presented) = authorization.and_then(|value| value.strip_prefix("Bearer ")) else {
+            return false;
+        };
+        let (presented, expected) = (presented.trim().as_bytes(), self.token.as_bytes());
+        presented.len() == expected.len()
+            && presented.iter().zip(expected).fold(0u8, |difference, (a, b)| difference | (a ^ b)) == 0
+    }
+    
+    pub async fn send(&self, command: ControlCommand) -> Result<String, String> {
+        let (reply, outcome) = oneshot::channel();
+        self.commands.send(ControlRequest { command, reply }).await
+            .map_err(|_| "the main loop is not running".to_string())?;
+        outcome.await.map_err(|_| "the main loop stopped before applying the command".to_string())?
+    }
+}
+
+// Line protocol on a loopback socket: the client sends one command ("halt", "resume" or "status")
+// and reads back a one-line reply. There is no authentication, so keep it bound to localhost.
+pub async fn serve(listener: TcpListener, kill_switch: Arc<watch::Sender<bool>>) {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                warn!("Control server failed to accept a connection: {}", e);
+                continue;
+            }
+        };
+        
+        let kill_switch = kill_switch.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &kill_switch).await {
+                debug!("Control connection from {} failed: {}", peer, e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(stream: TcpStream, kill_switch: &watch::Sender<bool>) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    BufReader::new(reader).read_line(&mut line).await?;
+
+    let reply = match line.trim() {
+        "halt" => {
+            if set_engaged(kill_switch, true) { "halted" } else { "already halted" }
+        },
+        "resume" => {
+            if set_engaged(kill_switch, false) { "resumed" } else { "already trading" }
+        },
+        "status" => {
+            if *kill_switch.borrow() { "halted" } else { "trading" }
+        },
+        other => {
+            writer.write_all(format!("unknown command: {}\n", other).as_bytes()).await?;
+            return Ok(());
+        }
+    };
+
+    writer.write_all(format!("{}\n", reply).as_bytes()).await?;
+    Ok(())
+}
+
+// Only notifies subscribers on an actual change, so repeated halts do not re-run the engage handling
+pub fn set_engaged(kill_switch: &watch::Sender<bool>, engaged: bool) -> bool {
+    kill_switch.send_if_modified(|current| {
+        let changed = *current != engaged;
+        *current = engaged;
+        changed
+    })
+}
+
+pub async fn send_command(listen_addr: &str, command: &str) -> Result<String> {
+    let stream = TcpStream::connect(listen_addr).await
+        .map_err(|e| anyhow::anyhow!("No running bot is listening on {}: {}", listen_addr, e))?;
+    let (reader, mut writer) = stream.into_split();
+
+    writer.write_all(format!("{}\n", command).as_bytes()).await?;
+
+    let mut reply = String::new();
+    BufReader::new(reader).read_line(&mut reply).await?;
+    Ok(reply.trim().to_string())
+}
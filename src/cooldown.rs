@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use tracing::{info, warn};
+
+use crate::config::RouteCooldownConfig;
+use crate::models::RouteFailure;
+
+// Routes whose executions keep failing are benched with a backoff, as the health tracker does with exchanges.
+// Times are wall-clock so the records can be persisted and picked up again after a restart.
+pub struct RouteCooldowns {
+    config: RwLock<RouteCooldownConfig>,
+    routes: Mutex<HashMap<String, RouteFailure>>,
+}
+
+impl RouteCooldowns {
+    pub fn new(config: RouteCooldownConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            routes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Cooldowns already running keep their end; the next failure uses the new settings
+    pub fn set_config(&self, config: RouteCooldownConfig) {
+        *self.config.write().unwrap() = config;
+    }
+
+    pub fn restore(&self, failures: Vec<RouteFailure>) {
+        let mut routes = self.routes.lock().unwrap();
+        for failure in failures {
+            routes.insert(failure.route.clone(), failure);
+        }
+    }
+
+    // Doubles with every failure past the threshold
+    fn cooldown(&self, consecutive_failures: u32) -> chrono::Duration {
+        let config = self.config.read().unwrap();
+        let base = config.cooldown_seconds.max(1);
+        let doublings = consecutive_failures.saturating_sub(config.failure_threshold).min(16);
+        let backoff = base.saturating_mul(1_u64 << doublings);
+        chrono::Duration::seconds(backoff.min(config.max_cooldown_seconds.max(base)) as i64)
+    }
+
+    // A cooldown that has run out is logged as lifted once; the count stays, so another failure backs off further
+    pub fn is_cooling(&self, route: &str, now: DateTime<Utc>) -> bool {
+        let mut routes = self.routes.lock().unwrap();
+        let Some(failure) = routes.get_mut(route) else {
+            return false;
+        };
+
+        match failure.cooled_until {
+            Some(until) if now < until => true,
+            Some(_) => {
+                info!("Route {} is back in rotation after its cooldown ({} consecutive failed executions)",
+                      route, failure.consecutive_failures);
+                failure.cooled_until = None;
+                false
+            },
+            None => false,
+        }
+    }
+
+    // Returns the updated record for persisting
+    pub fn record_failure(&self, route: &str, error: &anyhow::Error, now: DateTime<Utc>) -> RouteFailure {
+        let failure_threshold = self.config.read().unwrap().failure_threshold;
+        let mut routes = self.routes.lock().unwrap();
+        let failure = routes.entry(route.to_string()).or_insert_with(|| RouteFailure {
+            route: route.to_string(),
+            consecutive_failures: 0,
+            cooled_until: None,
+            last_error: None,
+            updated_at: now,
+        });
+
+        failure.consecutive_failures += 1;
+        failure.last_error = Some(error.to_string());
+        failure.updated_at = now;
+        if failure.consecutive_failures >= failure_threshold {
+            let cooldown = self.cooldown(failure.consecutive_failures);
+            failure.cooled_until = Some(now + cooldown);
+            warn!("Route {} cooling down for {}s after {} consecutive failed executions (last: {})",
+                  route, cooldown.num_seconds(), failure.consecutive_failures, error);
+        }
+        failure.clone()
+    }
+
+    // True when the route had failures on record, which then need clearing from the database too
+    pub fn record_success(&self, route: &str) -> bool {
+        self.routes.lock().unwrap().remove(route).is_some()
+    }
+
+    // Routes sitting out a cooldown right now, the soonest to lift first
+    pub fn cooling(&self, now: DateTime<Utc>) -> Vec<RouteFailure> {
+        let mut cooling: Vec<_> = self.routes.lock().unwrap().values()
+            .filter(|failure| failure.cooled_until.map_or(false, |until| until > now))
+            .cloned()
+            .collect();
+        cooling.sort_by_key(|failure| failure.cooled_until);
+        cooling
+    }
+}
@@ -0,0 +1,170 @@
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use tracing::debug;
+
+use crate::blockchain::bridge::{self, Bridge, BridgeQuote};
+use crate::blockchain::BlockchainManager;
+use crate::config::CrossChainConfig;
+use crate::exchanges::erc20;
+use crate::exchanges::{Exchange, ExchangeManager};
+use crate::models::{CrossChainArbitrage, Price, TradingPair};
+
+// Compares the same pair on DEXes of different enabled chains and prices the swap -> bridge -> swap
+// route, bridging the base token
+pub struct CrossChainScanner {
+    config: CrossChainConfig,
+    bridge: Box<dyn Bridge>,
+}
+
+struct Venue<'a> {
+    exchange: &'a dyn Exchange,
+    chain_id: u64,
+    chain: String,
+    price: Price,
+}
+
+impl CrossChainScanner {
+    pub fn new(config: CrossChainConfig, bridge: Box<dyn Bridge>) -> Self {
+        Self { config, bridge }
+    }
+
+    pub async fn scan(&self, manager: &ExchangeManager, blockchain: &BlockchainManager) -> Result<Vec<CrossChainArbitrage>> {
+        let mut venues: BTreeMap<String, Vec<Venue>> = BTreeMap::new();
+        
+        for exchange in manager.get_all_exchanges() {
+            let Some(chain_id) = exchange.chain_id() else {
+                continue;
+            };
+            // Venues on chains without a configured provider cannot be bridged to or from
+            let Some(chain) = blockchain.chain_name(chain_id) else {
+                continue;
+            };
+            if !manager.is_available(exchange.name()) {
+                continue;
+            }
+            
+            for pair in exchange.get_supported_pairs().await? {
+                match manager.fetch_price(exchange, &pair, manager.timeouts().order_book()).await {
+                    Ok(price) => venues.entry(manager.canonical_pair(exchange.name(), &pair).symbol).or_default().push(Venue {
+                        exchange,
+                        chain_id,
+                        chain: chain.to_string(),
+                        price,
+                    }),
+                    Err(e) => debug!("No {} price from {}: {}", pair.symbol, exchange.name(), e),
+                }
+            }
+        }
+        
+        let mut routes = Vec::new();
+        for venues in venues.values() {
+            for buy in venues {
+                for sell in venues {
+                    if buy.chain_id == sell.chain_id || buy.price.ask >= sell.price.bid {
+                        continue;
+                    }
+                    
+                    match self.evaluate(manager, buy, sell).await {
+                        Ok(Some(route)) => routes.push(route),
+                        Ok(None) => {},
+                        Err(e) => debug!("Skipping {} {} ({}) -> {} ({}): {}",
+                                         buy.price.pair.symbol, buy.exchange.name(), buy.chain,
+                                         sell.exchange.name(), sell.chain, e),
+                    }
+                }
+            }
+        }
+        
+        routes.sort_by(|a, b| b.profit_estimate.cmp(&a.profit_estimate));
+        Ok(routes)
+    }
+
+    // Moves the route's base tokens, already held on the source chain, to the target chain.
+    // The fee planned with the route is the reference the fresh quote is checked against
+    pub async fn execute_bridge(&self, route: &CrossChainArbitrage, blockchain: &BlockchainManager) -> Result<String> {
+        let chain_id = |name: &str| blockchain.get_chain_config(name)
+            .map(|chain| chain.chain_id)
+            .ok_or_else(|| anyhow::anyhow!("Chain {} is not enabled", name));
+        
+        let planned = BridgeQuote {
+            token: route.pair.base.clone(),
+            amount: route.amount,
+            from_chain_id: chain_id(&route.source_chain)?,
+            to_chain_id: chain_id(&route.target_chain)?,
+            fee: route.bridge_fees / route.buy_price,
+            estimated_minutes: route.estimated_time_minutes,
+            min_amount: Decimal::ZERO,
+            contract: String::new(),
+            quote_timestamp: 0,
+        };
+        
+        Ok(bridge::execute_requoted(self.bridge.as_ref(), &planned, self.config.bridge_fee_tolerance).await?)
+    }
+
+    async fn evaluate(
+        &self,
+        manager: &ExchangeManager,
+        buy: &Venue<'_>,
+        sell: &Venue<'_>,
+    ) -> Result<Option<CrossChainArbitrage>> {
+        let pair: &TradingPair = &buy.price.pair;
+        let timeouts = manager.timeouts();
+        let trade_size = self.config.trade_size;
+        
+        let (buy_fees, sell_fees) = tokio::try_join!(
+            manager.trading_fees(buy.exchange, pair),
+            manager.trading_fees(sell.exchange, pair),
+        )?;
+        
+        let buy_swap_fee = trade_size * buy_fees.taker_fee;
+        let amount = (trade_size - buy_swap_fee) / buy.price.ask;
+        
+        let token_address = erc20::token_address(buy.chain_id, &pair.base)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported on {}: {}", buy.chain, pair.base))?;
+        
+        let quote = self.bridge.quote(&pair.base, amount, buy.chain_id, sell.chain_id).await?;
+        if amount < quote.min_amount {
+            anyhow::bail!("{} {} is below the {} minimum of {}", amount, pair.base, self.bridge.name(), quote.min_amount);
+        }
+        let arriving = amount - quote.fee;
+        if arriving <= Decimal::ZERO {
+            return Ok(None);
+        }
+        
+        let (buy_gas_cost, sell_gas_cost) = tokio::try_join!(
+            manager.call(buy.exchange.name(), "get_execution_cost", timeouts.order_book(), buy.exchange.get_execution_cost(pair, amount)),
+            manager.call(sell.exchange.name(), "get_execution_cost", timeouts.order_book(), sell.exchange.get_execution_cost(pair, arriving)),
+        )?;
+        
+        let proceeds = arriving * sell.price.bid;
+        let sell_swap_fee = proceeds * sell_fees.taker_fee;
+        let profit_estimate = proceeds - sell_swap_fee - sell_gas_cost - trade_size - buy_gas_cost;
+        if profit_estimate < self.config.min_profit {
+            return Ok(None);
+        }
+        
+        Ok(Some(CrossChainArbitrage {
+            id: uuid::Uuid::new_v4(),
+            pair: pair.clone(),
+            buy_exchange: buy.exchange.name().to_string(),
+            sell_exchange: sell.exchange.name().to_string(),
+            source_chain: buy.chain.clone(),
+            target_chain: sell.chain.clone(),
+            token_address: format!("{:?}", token_address),
+            buy_price: buy.price.ask,
+            sell_price: sell.price.bid,
+            amount,
+            buy_swap_fee,
+            buy_gas_cost,
+            // Valued at the source-chain price the bridged tokens were bought at
+            bridge_fees: quote.fee * buy.price.ask,
+            sell_swap_fee,
+            sell_gas_cost,
+            profit_estimate,
+            estimated_time_minutes: quote.estimated_minutes,
+            timestamp: Utc::now(),
+        }))
+    }
+}
@@ -0,0 +1,1969 @@
+use anyhow::Result;
+use chrono::{DateTime, DurationRound, Utc};
+use futures::stream::{BoxStream, StreamExt};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use sqlx::any::{AnyPool, AnyPoolOptions, AnyRow};
+use sqlx::Row;
+use std::str::FromStr;
+use tracing::info;
+
+use crate::pnl::{self, PnlSummary};
+use crate::models::{ArbitrageOpportunity, BlacklistedToken, CrossChainArbitrage, ErrorCount, HedgePosition, HedgeStatus, OpportunityCount, OpportunityStats, OpportunityStatus, OrderBook, Portfolio, Price, PriceCandle, ProfitBucket, RouteFailure, RouteFillCount, SpreadBucket, SpreadSample, Trade, TradeSide, TradeStatus, TradingHalt, TradingPair};
+
+// Rows per multi-row INSERT, well below SQLite's bound parameter limit
+const PRICE_HISTORY_BATCH_SIZE: usize = 100;
+
+// Upper bounds, in percent, of the profit buckets `stats` reports
+const PROFIT_BUCKETS: [f64; 5] = [0.5, 1.0, 2.0, 5.0, 10.0];
+
+// What one pruning run removed and kept
+#[derive(Debug, Clone, Copy)]
+pub struct PriceHistoryPruning {
+    pub deleted: u64,
+    pub candles: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SpreadHistoryPruning {
+    pub downsampled: u64,
+    pub deleted: u64,
+}
+
+// Chosen by the database_url scheme. Both store decimals and timestamps as text (RFC 3339 in UTC, so
+// they also sort correctly); queries use $N placeholders, which SQLite accepts too
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Sqlite,
+    Postgres,
+}
+
+impl Backend {
+    pub fn from_url(database_url: &str) -> Result<Self> {
+        if database_url.starts_with("sqlite:") {
+            Ok(Backend::Sqlite)
+        } else if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+            Ok(Backend::Postgres)
+        } else {
+            anyhow::bail!("Unsupported database_url scheme, expected sqlite: or postgres://")
+        }
+    }
+    
+    fn serial_primary_key(self) -> &'static str {
+        match self {
+            Backend::Sqlite => "INTEGER PRIMARY KEY AUTOINCREMENT",
+            Backend::Postgres => "BIGSERIAL PRIMARY KEY",
+        }
+    }
+    
+    // Seconds between two RFC 3339 text columns
+    fn seconds_between(self, from: &str, to: &str) -> String {
+        match self {
+            Backend::Sqlite => format!("(julianday({}) - julianday({})) * 86400.0", to, from),
+            Backend::Postgres => format!("EXTRACT(EPOCH FROM (CAST({} AS TIMESTAMPTZ) - CAST({} AS TIMESTAMPTZ)))", to, from),
+        }
+    }
+}
+
+// Embedded in the binary and applied in version order; a schema change is always a new file, never an
+// edit to one that has shipped
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    sql: &'static str,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, name: "initial_schema", sql: include_str!("../migrations/0001_initial_schema.sql") },
+    Migration { version: 2, name: "price_history", sql: include_str!("../migrations/0002_price_history.sql") },
+    Migration { version: 3, name: "opportunity_route_key", sql: include_str!("../migrations/0003_opportunity_route_key.sql") },
+    Migration { version: 4, name: "opportunity_fee_rates", sql: include_str!("../migrations/0004_opportunity_fee_rates.sql") },
+    Migration { version: 5, name: "trade_client_order_id", sql: include_str!("../migrations/0005_trade_client_order_id.sql") },
+    Migration { version: 6, name: "route_failures", sql: include_str!("../migrations/0006_route_failures.sql") },
+    Migration { version: 7, name: "opportunity_approval", sql: include_str!("../migrations/0007_opportunity_approval.sql") },
+    Migration { version: 8, name: "token_blacklist", sql: include_str!("../migrations/0008_token_blacklist.sql") },
+    Migration { version: 9, name: "opportunity_marginal_profit", sql: include_str!("../migrations/0009_opportunity_marginal_profit.sql") },
+    Migration { version: 10, name: "spread_history", sql: include_str!("../migrations/0010_spread_history.sql") },
+    Migration { version: 11, name: "error_counts", sql: include_str!("../migrations/0011_error_counts.sql") },
+];
+
+impl Migration {
+    // Comment lines are dropped first, so they may contain semicolons
+    fn statements(&self, backend: Backend) -> Vec<String> {
+        self.sql
+            .replace("{serial_primary_key}", backend.serial_primary_key())
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("--"))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .split(';')
+            .map(|statement| statement.trim().to_string())
+            .filter(|statement| !statement.is_empty())
+            .collect()
+    }
+}
+
+#[derive(Clone)]
+pub struct Database {
+    pool: AnyPool,
+    backend: Backend,
+}
+
+pub async fn init_database(database_url: &str) -> Result<()> {
+    let database = Database::new(database_url).await?;
+    database.migrate().await?;
+    Ok(())
+}
+
+impl Database {
+    pub async fn new(database_url: &str) -> Result<Self> {
+        sqlx::any::install_default_drivers();
+        let backend = Backend::from_url(database_url)?;
+        
+        // SQLite files are created on first use; foreign keys are enforced by default on both
+        let in_memory = database_url.contains(":memory:");
+        let url = if backend == Backend::Sqlite && !in_memory && !database_url.contains("mode=") {
+            let separator = if database_url.contains('?') { '&' } else { '?' };
+            format!("{}{}mode=rwc", database_url, separator)
+        } else {
+            database_url.to_string()
+        };
+        
+        // Every connection to an in-memory database gets its own empty database
+        let max_connections = if in_memory { 1 } else { 5 };
+        
+        let pool = AnyPoolOptions::new()
+            .max_connections(max_connections)
+            .connect(&url)
+            .await?;
+        
+        Ok(Self { pool, backend })
+    }
+    
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+    
+    pub async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    // Waits for in-flight queries to finish and closes every connection
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+
+    // Applies every pending migration, each in its own transaction, and returns the ones applied
+    pub async fn migrate(&self) -> Result<Vec<&'static Migration>> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS schema_migrations (
+                version BIGINT PRIMARY KEY,
+                name TEXT NOT NULL,
+                applied_at TEXT NOT NULL
+            )"
+        )
+        .execute(&self.pool)
+        .await?;
+        
+        let pending = self.pending_migrations().await?;
+        if pending.first().map_or(false, |migration| migration.version == 1) {
+            self.upgrade_legacy_schema().await?;
+        }
+        
+        for migration in &pending {
+            let mut transaction = self.pool.begin().await?;
+            for statement in migration.statements(self.backend) {
+                sqlx::query(&statement)
+                    .execute(&mut *transaction)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Migration {} ({}) failed: {}", migration.version, migration.name, e))?;
+            }
+            sqlx::query("INSERT INTO schema_migrations (version, name, applied_at) VALUES ($1, $2, $3)")
+                .bind(migration.version)
+                .bind(migration.name)
+                .bind(Utc::now().to_rfc3339())
+                .execute(&mut *transaction)
+                .await?;
+            transaction.commit().await?;
+            
+            info!("Applied migration {} ({})", migration.version, migration.name);
+        }
+        
+        Ok(pending)
+    }
+    
+    // None until the first migration has been applied
+    pub async fn schema_version(&self) -> Result<Option<i64>> {
+        if self.table_columns("schema_migrations").await?.is_empty() {
+            return Ok(None);
+        }
+        
+        let row = sqlx::query("SELECT MAX(version) AS version FROM schema_migrations")
+            .fetch_one(&self.pool)
+            .await?;
+        
+        Ok(row.try_get("version")?)
+    }
+    
+    pub async fn pending_migrations(&self) -> Result<Vec<&'static Migration>> {
+        let version = self.schema_version().await?.unwrap_or(0);
+        Ok(MIGRATIONS.iter().filter(|migration| migration.version > version).collect())
+    }
+    
+    // Databases created before versioned migrations may predate some of the columns in the first
+    // migration; they are added here so it can be recorded as applied
+    async fn upgrade_legacy_schema(&self) -> Result<()> {
+        let columns = self.table_columns("opportunities").await?;
+        if !columns.is_empty() {
+            let has_transfer_cost = columns.iter()
+                .any(|name| name == "transfer_cost");
+            if !has_transfer_cost {
+                sqlx::query("ALTER TABLE opportunities ADD COLUMN transfer_cost TEXT NOT NULL DEFAULT '0'")
+                    .execute(&self.pool)
+                    .await?;
+                sqlx::query("ALTER TABLE opportunities ADD COLUMN adjusted_profit_amount TEXT")
+                    .execute(&self.pool)
+                    .await?;
+            }
+            let has_profit_breakdown = columns.iter()
+                .any(|name| name == "gross_profit");
+            if !has_profit_breakdown {
+                for column in ["gross_profit", "buy_fee_cost", "sell_fee_cost"] {
+                    sqlx::query(&format!("ALTER TABLE opportunities ADD COLUMN {} TEXT NOT NULL DEFAULT '0'", column))
+                        .execute(&self.pool)
+                        .await?;
+                }
+            }
+            let has_native_pairs = columns.iter()
+                .any(|name| name == "buy_pair");
+            if !has_native_pairs {
+                for column in ["buy_pair", "sell_pair"] {
+                    sqlx::query(&format!("ALTER TABLE opportunities ADD COLUMN {} TEXT", column))
+                        .execute(&self.pool)
+                        .await?;
+                }
+            }
+            let has_sell_quote_rate = columns.iter()
+                .any(|name| name == "sell_quote_rate");
+            if !has_sell_quote_rate {
+                sqlx::query("ALTER TABLE opportunities ADD COLUMN sell_quote_rate TEXT NOT NULL DEFAULT '1'")
+                    .execute(&self.pool)
+                    .await?;
+            }
+            // When an opportunity stopped being active; rows closed before this was tracked have no lifetime
+            let has_closed_at = columns.iter()
+                .any(|name| name == "closed_at");
+            if !has_closed_at {
+                sqlx::query("ALTER TABLE opportunities ADD COLUMN closed_at TEXT")
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+        
+        let columns = self.table_columns("trades").await?;
+        if !columns.is_empty() {
+            let has_simulated = columns.iter()
+                .any(|name| name == "simulated");
+            if !has_simulated {
+                sqlx::query("ALTER TABLE trades ADD COLUMN simulated BIGINT NOT NULL DEFAULT 0")
+                    .execute(&self.pool)
+                    .await?;
+            }
+            let has_failure_reason = columns.iter()
+                .any(|name| name == "failure_reason");
+            if !has_failure_reason {
+                sqlx::query("ALTER TABLE trades ADD COLUMN failure_reason TEXT")
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+        
+        Ok(())
+    }
+
+    async fn table_columns(&self, table: &str) -> Result<Vec<String>> {
+        let sql = match self.backend {
+            Backend::Sqlite => "SELECT name FROM pragma_table_info($1)",
+            Backend::Postgres => "SELECT CAST(column_name AS TEXT) AS name FROM information_schema.columns WHERE table_name = $1",
+        };
+        let rows = sqlx::query(sql)
+            .bind(table)
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(|row| Ok(row.try_get("name")?)).collect()
+    }
+    
+    // An active opportunity is upserted on its route: if the route already has an active row, that
+    // row takes the new prices and timestamp, its update count goes up and its id is returned, which
+    // the caller should adopt. Anything else is upserted on its id
+    pub async fn save_opportunity(&self, opportunity: &ArbitrageOpportunity) -> Result<uuid::Uuid> {
+        let conflict = if matches!(opportunity.status, OpportunityStatus::Active) {
+            "ON CONFLICT (pair, buy_exchange, sell_exchange) WHERE status = 'active' DO UPDATE SET
+                 buy_pair = excluded.buy_pair, sell_pair = excluded.sell_pair,
+                 sell_quote_rate = excluded.sell_quote_rate,
+                 buy_price = excluded.buy_price, sell_price = excluded.sell_price,
+                 profit_percentage = excluded.profit_percentage, profit_amount = excluded.profit_amount,
+                 max_trade_size = excluded.max_trade_size, gross_profit = excluded.gross_profit,
+                 buy_fee_cost = excluded.buy_fee_cost, sell_fee_cost = excluded.sell_fee_cost,
+                 buy_fee_rate = excluded.buy_fee_rate, sell_fee_rate = excluded.sell_fee_rate,
+                 gas_cost = excluded.gas_cost, transfer_cost = excluded.transfer_cost,
+                 adjusted_profit_amount = excluded.adjusted_profit_amount, timestamp = excluded.timestamp,
+                 marginal_profit = excluded.marginal_profit,
+                 requires_approval = excluded.requires_approval, updates = opportunities.updates + 1"
+        } else {
+            "ON CONFLICT (id) DO UPDATE SET
+                 pair = excluded.pair, buy_exchange = excluded.buy_exchange,
+                 sell_exchange = excluded.sell_exchange, buy_pair = excluded.buy_pair,
+                 sell_pair = excluded.sell_pair, sell_quote_rate = excluded.sell_quote_rate,
+                 buy_price = excluded.buy_price, sell_price = excluded.sell_price,
+                 profit_percentage = excluded.profit_percentage, profit_amount = excluded.profit_amount,
+                 max_trade_size = excluded.max_trade_size, gross_profit = excluded.gross_profit,
+                 buy_fee_cost = excluded.buy_fee_cost, sell_fee_cost = excluded.sell_fee_cost,
+                 buy_fee_rate = excluded.buy_fee_rate, sell_fee_rate = excluded.sell_fee_rate,
+                 gas_cost = excluded.gas_cost, transfer_cost = excluded.transfer_cost,
+                 adjusted_profit_amount = excluded.adjusted_profit_amount, timestamp = excluded.timestamp,
+                 marginal_profit = excluded.marginal_profit,
+                 status = excluded.status, closed_at = excluded.closed_at,
+                 requires_approval = excluded.requires_approval, approved_at = excluded.approved_at"
+        };
+        let sql = format!(
+            "INSERT INTO opportunities
+                (id, pair, buy_exchange, sell_exchange, buy_pair, sell_pair, sell_quote_rate, buy_price, sell_price,
+                 profit_percentage, profit_amount, max_trade_size, gross_profit, buy_fee_cost, sell_fee_cost,
+                 buy_fee_rate, sell_fee_rate, gas_cost, transfer_cost, adjusted_profit_amount, timestamp, status,
+                 closed_at, first_seen_at, requires_approval, approved_at, marginal_profit)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21,
+                 $22, $23, $21, $24, $25, $26)
+             {}
+             RETURNING id",
+            conflict,
+        );
+        
+        let row = sqlx::query(&sql)
+            .bind(opportunity.id.to_string())
+            .bind(&opportunity.pair.symbol)
+            .bind(&opportunity.buy_exchange)
+            .bind(&opportunity.sell_exchange)
+            .bind(&opportunity.buy_pair.symbol)
+            .bind(&opportunity.sell_pair.symbol)
+            .bind(opportunity.sell_quote_rate.to_string())
+            .bind(opportunity.buy_price.to_string())
+            .bind(opportunity.sell_price.to_string())
+            .bind(opportunity.profit_percentage.to_string())
+            .bind(opportunity.profit_amount.to_string())
+            .bind(opportunity.max_trade_size.to_string())
+            .bind(opportunity.gross_profit.to_string())
+            .bind(opportunity.buy_fee_cost.to_string())
+            .bind(opportunity.sell_fee_cost.to_string())
+            .bind(opportunity.buy_fee_rate.to_string())
+            .bind(opportunity.sell_fee_rate.to_string())
+            .bind(opportunity.gas_cost.to_string())
+            .bind(opportunity.transfer_cost.to_string())
+            .bind(opportunity.adjusted_profit_amount.to_string())
+            .bind(opportunity.timestamp.to_rfc3339())
+            .bind(status_to_str(&opportunity.status))
+            .bind(closed_at(&opportunity.status))
+            .bind(opportunity.requires_approval as i64)
+            .bind(opportunity.approved_at.map(|t| t.to_rfc3339()))
+            .bind(opportunity.marginal_profit.to_string())
+            .fetch_one(&self.pool)
+            .await?;
+        
+        Ok(uuid::Uuid::parse_str(&row.try_get::<String, _>("id")?)?)
+    }
+
+    pub async fn update_opportunity_status(&self, opportunity: &ArbitrageOpportunity) -> Result<()> {
+        sqlx::query("UPDATE opportunities SET status = $1, closed_at = COALESCE(closed_at, $2) WHERE id = $3")
+            .bind(status_to_str(&opportunity.status))
+            .bind(closed_at(&opportunity.status))
+            .bind(opportunity.id.to_string())
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+
+    pub async fn update_opportunity_statuses(&self, ids: &[uuid::Uuid], status: &OpportunityStatus) -> Result<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        
+        let placeholders = (3..ids.len() + 3).map(|index| format!("${}", index)).collect::<Vec<_>>().join(", ");
+        let sql = format!("UPDATE opportunities SET status = $1, closed_at = COALESCE(closed_at, $2) WHERE id IN ({})", placeholders);
+        let mut query = sqlx::query(&sql).bind(status_to_str(status)).bind(closed_at(status));
+        for id in ids {
+            query = query.bind(id.to_string());
+        }
+        query.execute(&self.pool).await?;
+        
+        Ok(())
+    }
+
+    // Only an active opportunity waiting for approval can be approved; returns whether this one was
+    pub async fn approve_opportunity(&self, id: uuid::Uuid, now: DateTime<Utc>) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE opportunities SET approved_at = $1
+             WHERE id = $2 AND status = 'active' AND requires_approval = 1 AND approved_at IS NULL"
+        )
+            .bind(now.to_rfc3339())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn get_opportunity(&self, id: uuid::Uuid) -> Result<Option<ArbitrageOpportunity>> {
+        let row = sqlx::query("SELECT * FROM opportunities WHERE id = $1")
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        row.map(|row| opportunity_from_row(&row)).transpose()
+    }
+
+    pub async fn get_opportunities_with_status(&self, status: &OpportunityStatus) -> Result<Vec<ArbitrageOpportunity>> {
+        let rows = sqlx::query("SELECT * FROM opportunities WHERE status = $1 ORDER BY timestamp")
+            .bind(status_to_str(status))
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(opportunity_from_row).collect()
+    }
+
+    pub async fn save_trade(&self, trade: &Trade) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO trades
+                (id, opportunity_id, exchange, pair, side, amount, filled_amount, price,
+                 fee_amount, fee_asset, status, order_id, tx_hash, created_at, executed_at, simulated, failure_reason,
+                 client_order_id)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)"
+        )
+        .bind(trade.id.to_string())
+        .bind(trade.opportunity_id.to_string())
+        .bind(&trade.exchange)
+        .bind(&trade.pair.symbol)
+        .bind(side_to_str(&trade.side))
+        .bind(trade.amount.to_string())
+        .bind(filled_amount(trade).to_string())
+        .bind(trade.price.to_string())
+        .bind(trade.fee_amount.to_string())
+        .bind(&trade.fee_asset)
+        .bind(trade_status_to_str(&trade.status))
+        .bind(&trade.order_id)
+        .bind(&trade.tx_hash)
+        .bind(trade.created_at.to_rfc3339())
+        .bind(trade.executed_at.map(|t| t.to_rfc3339()))
+        .bind(trade.simulated as i64)
+        .bind(&trade.failure_reason)
+        .bind(&trade.client_order_id)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    pub async fn update_trade_status(&self, trade: &Trade) -> Result<()> {
+        sqlx::query(
+            "UPDATE trades
+             SET status = $1, amount = $2, filled_amount = $3, price = $4, fee_amount = $5, fee_asset = $6,
+                 order_id = $7, tx_hash = $8, executed_at = $9, failure_reason = $10,
+                 client_order_id = COALESCE($11, client_order_id)
+             WHERE id = $12"
+        )
+        .bind(trade_status_to_str(&trade.status))
+        .bind(trade.amount.to_string())
+        .bind(filled_amount(trade).to_string())
+        .bind(trade.price.to_string())
+        .bind(trade.fee_amount.to_string())
+        .bind(&trade.fee_asset)
+        .bind(&trade.order_id)
+        .bind(&trade.tx_hash)
+        .bind(trade.executed_at.map(|t| t.to_rfc3339()))
+        .bind(&trade.failure_reason)
+        .bind(&trade.client_order_id)
+        .bind(trade.id.to_string())
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    pub async fn get_trades_for_opportunity(&self, opportunity_id: uuid::Uuid) -> Result<Vec<Trade>> {
+        let rows = sqlx::query("SELECT * FROM trades WHERE opportunity_id = $1 ORDER BY created_at")
+            .bind(opportunity_id.to_string())
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(trade_from_row).collect()
+    }
+    
+    pub async fn get_pending_trades(&self) -> Result<Vec<Trade>> {
+        let rows = sqlx::query("SELECT * FROM trades WHERE status = 'pending' AND simulated = 0 ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(trade_from_row).collect()
+    }
+    
+    pub async fn get_executed_trades(&self, simulated: bool) -> Result<Vec<Trade>> {
+        let rows = sqlx::query("SELECT * FROM trades WHERE status = 'executed' AND simulated = $1 ORDER BY created_at")
+            .bind(simulated as i64)
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(trade_from_row).collect()
+    }
+    
+    pub async fn get_trades_between(&self, start: DateTime<Utc>, end: DateTime<Utc>, simulated: bool) -> Result<Vec<Trade>> {
+        let rows = sqlx::query(
+            "SELECT * FROM trades WHERE created_at >= $1 AND created_at < $2 AND simulated = $3 ORDER BY created_at"
+        )
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339())
+            .bind(simulated as i64)
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(trade_from_row).collect()
+    }
+    
+    pub async fn get_pnl_summary(&self, start: DateTime<Utc>, end: DateTime<Utc>, simulated: bool) -> Result<PnlSummary> {
+        let trades = self.get_trades_between(start, end, simulated).await?;
+        Ok(pnl::summarize(start, end, &trades))
+    }
+    
+    pub async fn save_price_history(&self, prices: &[Price]) -> Result<()> {
+        if prices.is_empty() {
+            return Ok(());
+        }
+        
+        let mut transaction = self.pool.begin().await?;
+        for batch in prices.chunks(PRICE_HISTORY_BATCH_SIZE) {
+            let values: Vec<String> = (0..batch.len())
+                .map(|row| {
+                    let first = row * 6;
+                    format!("(${}, ${}, ${}, ${}, ${}, ${})", first + 1, first + 2, first + 3, first + 4, first + 5, first + 6)
+                })
+                .collect();
+            let sql = format!(
+                "INSERT INTO price_history (exchange, pair, bid, ask, volume_24h, timestamp) VALUES {}",
+                values.join(", "),
+            );
+            
+            let mut query = sqlx::query(&sql);
+            for price in batch {
+                query = query
+                    .bind(price.exchange.clone())
+                    .bind(price.pair.symbol.clone())
+                    .bind(price.bid.to_string())
+                    .bind(price.ask.to_string())
+                    .bind(price.volume_24h.map(|v| v.to_string()))
+                    .bind(price.timestamp.to_rfc3339());
+            }
+            query.execute(&mut *transaction).await?;
+        }
+        transaction.commit().await?;
+        
+        Ok(())
+    }
+    
+    pub async fn save_order_book_snapshot(&self, order_book: &OrderBook) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO order_book_snapshots (exchange, pair, bids, asks, timestamp)
+             VALUES ($1, $2, $3, $4, $5)"
+        )
+        .bind(&order_book.exchange)
+        .bind(&order_book.pair.symbol)
+        .bind(serde_json::to_string(&order_book.bids)?)
+        .bind(serde_json::to_string(&order_book.asks)?)
+        .bind(order_book.timestamp.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    // Raw recorded prices in [start, end), optionally for one pair and exchange
+    pub async fn get_price_history(
+        &self,
+        pair: Option<&TradingPair>,
+        exchange: Option<&str>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<Price>> {
+        let (sql, filters) = history_query("price_history", "timestamp", pair, exchange);
+        let mut query = sqlx::query(&sql)
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339());
+        for filter in filters {
+            query = query.bind(filter);
+        }
+        let rows = query.fetch_all(&self.pool).await?;
+        
+        rows.iter().map(price_from_row).collect()
+    }
+    
+    #[cfg(test)]
+    pub async fn get_price_candles(
+        &self,
+        pair: Option<&TradingPair>,
+        exchange: Option<&str>,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<PriceCandle>> {
+        let (sql, filters) = history_query("price_candles", "minute", pair, exchange);
+        let mut query = sqlx::query(&sql)
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339());
+        for filter in filters {
+            query = query.bind(filter);
+        }
+        let rows = query.fetch_all(&self.pool).await?;
+        
+        rows.iter().map(candle_from_row).collect()
+    }
+    
+    // Deletes recorded prices before the minute `before` falls in, after rolling them up into candles
+    // when `roll_up` is set. Whole minutes are cut so a later run never sees half of one; prices that
+    // arrive late for a minute already rolled up are dropped
+    pub async fn prune_price_history(&self, before: DateTime<Utc>, roll_up: bool) -> Result<PriceHistoryPruning> {
+        let cutoff = before.duration_trunc(chrono::Duration::minutes(1))?.to_rfc3339();
+        
+        let mut candles = Vec::new();
+        if roll_up {
+            // Dropped before the transaction below, which may need the same connection
+            let mut rows = sqlx::query("SELECT * FROM price_history WHERE timestamp < $1 ORDER BY exchange, pair, timestamp")
+                .bind(&cutoff)
+                .fetch(&self.pool);
+            while let Some(row) = rows.next().await {
+                PriceCandle::roll_up(&mut candles, &price_from_row(&row?)?);
+            }
+        }
+        
+        let mut transaction = self.pool.begin().await?;
+        for candle in &candles {
+            sqlx::query(
+                "INSERT INTO price_candles (exchange, pair, minute, open, high, low, close, samples)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                 ON CONFLICT (exchange, pair, minute) DO NOTHING"
+            )
+            .bind(&candle.exchange)
+            .bind(&candle.pair.symbol)
+            .bind(candle.minute.to_rfc3339())
+            .bind(candle.open.to_string())
+            .bind(candle.high.to_string())
+            .bind(candle.low.to_string())
+            .bind(candle.close.to_string())
+            .bind(candle.samples)
+            .execute(&mut *transaction)
+            .await?;
+        }
+        let deleted = sqlx::query("DELETE FROM price_history WHERE timestamp < $1")
+            .bind(&cutoff)
+            .execute(&mut *transaction)
+            .await?
+            .rows_affected();
+        transaction.commit().await?;
+        
+        Ok(PriceHistoryPruning { deleted, candles: candles.len() as u64 })
+    }
+    
+    pub async fn save_spread_samples(&self, samples: &[SpreadSample]) -> Result<()> {
+        if samples.is_empty() {
+            return Ok(());
+        }
+        
+        let mut transaction = self.pool.begin().await?;
+        for batch in samples.chunks(PRICE_HISTORY_BATCH_SIZE) {
+            let values: Vec<String> = (0..batch.len())
+                .map(|row| {
+                    let first = row * 8;
+                    format!("(${}, ${}, ${}, ${}, ${}, ${}, ${}, ${})",
+                            first + 1, first + 2, first + 3, first + 4, first + 5, first + 6, first + 7, first + 8)
+                })
+                .collect();
+            let sql = format!(
+                "INSERT INTO spread_history
+                    (pair, buy_exchange, sell_exchange, timestamp, samples, net_sum, net_sum_squares, above_threshold)
+                 VALUES {}",
+                values.join(", "),
+            );
+            
+            let mut query = sqlx::query(&sql);
+            for sample in batch {
+                let net_spread = sample.net_spread.to_f64().unwrap_or_default();
+                query = query
+                    .bind(sample.pair.clone())
+                    .bind(sample.buy_exchange.clone())
+                    .bind(sample.sell_exchange.clone())
+                    .bind(sample.timestamp.to_rfc3339())
+                    .bind(1i64)
+                    .bind(net_spread)
+                    .bind(net_spread * net_spread)
+                    .bind(sample.above_threshold as i64);
+            }
+            query.execute(&mut *transaction).await?;
+        }
+        transaction.commit().await?;
+        
+        Ok(())
+    }
+    
+    // Recorded spreads in [start, end) merged per route and UTC hour, raw and downsampled rows alike
+    pub async fn get_spread_buckets(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<SpreadBucket>> {
+        let rows = sqlx::query(
+            "SELECT pair, buy_exchange, sell_exchange, substr(timestamp, 1, 13) AS hour,
+                    CAST(SUM(samples) AS BIGINT) AS samples, SUM(net_sum) AS net_sum,
+                    SUM(net_sum_squares) AS net_sum_squares, CAST(SUM(above_threshold) AS BIGINT) AS above_threshold
+             FROM spread_history WHERE timestamp >= $1 AND timestamp < $2
+             GROUP BY pair, buy_exchange, sell_exchange, substr(timestamp, 1, 13)
+             ORDER BY hour"
+        )
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339())
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter()
+            .map(|row| {
+                let hour: String = row.try_get("hour")?;
+                Ok(SpreadBucket {
+                    pair: row.try_get("pair")?,
+                    buy_exchange: row.try_get("buy_exchange")?,
+                    sell_exchange: row.try_get("sell_exchange")?,
+                    hour: chrono::NaiveDateTime::parse_from_str(&format!("{}:00", hour), "%Y-%m-%dT%H:%M")?.and_utc(),
+                    samples: row.try_get("samples")?,
+                    net_sum: row.try_get("net_sum")?,
+                    net_sum_squares: row.try_get("net_sum_squares")?,
+                    above_threshold: row.try_get("above_threshold")?,
+                })
+            })
+            .collect()
+    }
+    
+    // Rolls samples before the hour `downsample_before` falls in up into one row per route and hour, then
+    // deletes every row before `delete_before`
+    pub async fn prune_spread_history(&self, downsample_before: DateTime<Utc>, delete_before: DateTime<Utc>) -> Result<SpreadHistoryPruning> {
+        let cutoff = downsample_before.duration_trunc(chrono::Duration::hours(1))?.to_rfc3339();
+        
+        let mut transaction = self.pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO spread_history
+                (pair, buy_exchange, sell_exchange, timestamp, samples, net_sum, net_sum_squares, above_threshold, downsampled)
+             SELECT pair, buy_exchange, sell_exchange, substr(timestamp, 1, 13) || ':00:00+00:00',
+                    CAST(SUM(samples) AS BIGINT), SUM(net_sum), SUM(net_sum_squares), CAST(SUM(above_threshold) AS BIGINT), 1
+             FROM spread_history WHERE downsampled = 0 AND timestamp < $1
+             GROUP BY pair, buy_exchange, sell_exchange, substr(timestamp, 1, 13)"
+        )
+            .bind(&cutoff)
+            .execute(&mut *transaction)
+            .await?;
+        let downsampled = sqlx::query("DELETE FROM spread_history WHERE downsampled = 0 AND timestamp < $1")
+            .bind(&cutoff)
+            .execute(&mut *transaction)
+            .await?
+            .rows_affected();
+        let deleted = sqlx::query("DELETE FROM spread_history WHERE timestamp < $1")
+            .bind(delete_before.to_rfc3339())
+            .execute(&mut *transaction)
+            .await?
+            .rows_affected();
+        transaction.commit().await?;
+        
+        Ok(SpreadHistoryPruning { downsampled, deleted })
+    }
+    
+    pub async fn get_order_book_snapshots(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<OrderBook>> {
+        let rows = sqlx::query("SELECT * FROM order_book_snapshots WHERE timestamp >= $1 AND timestamp < $2 ORDER BY timestamp")
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339())
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(order_book_from_row).collect()
+    }
+    
+    pub async fn save_hedge_position(&self, position: &HedgePosition) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO hedge_positions
+                (id, opportunity_id, exchange, pair, side, size, entry_price, order_id, status, opened_at, closed_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+             ON CONFLICT (id) DO UPDATE SET
+                 opportunity_id = excluded.opportunity_id, exchange = excluded.exchange, pair = excluded.pair,
+                 side = excluded.side, size = excluded.size, entry_price = excluded.entry_price,
+                 order_id = excluded.order_id, status = excluded.status, opened_at = excluded.opened_at,
+                 closed_at = excluded.closed_at"
+        )
+        .bind(position.id.to_string())
+        .bind(position.opportunity_id.to_string())
+        .bind(&position.exchange)
+        .bind(&position.pair.symbol)
+        .bind(side_to_str(&position.side))
+        .bind(position.size.to_string())
+        .bind(position.entry_price.to_string())
+        .bind(&position.order_id)
+        .bind(hedge_status_to_str(&position.status))
+        .bind(position.opened_at.to_rfc3339())
+        .bind(position.closed_at.map(|t| t.to_rfc3339()))
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    pub async fn get_open_hedge_positions(&self) -> Result<Vec<HedgePosition>> {
+        let rows = sqlx::query("SELECT * FROM hedge_positions WHERE status = 'open' ORDER BY opened_at")
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(hedge_position_from_row).collect()
+    }
+    
+    pub async fn close_hedge_position(&self, id: uuid::Uuid, closed_at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE hedge_positions SET status = 'closed', closed_at = $1 WHERE id = $2")
+            .bind(closed_at.to_rfc3339())
+            .bind(id.to_string())
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+    
+    // Balances are stored as JSON keyed by asset; the total is kept in its own column for charting
+    pub async fn save_portfolio_snapshot(&self, portfolio: &Portfolio) -> Result<()> {
+        sqlx::query("INSERT INTO portfolio_snapshots (total_value_usd, balances, timestamp) VALUES ($1, $2, $3)")
+            .bind(portfolio.total_value_usd.to_string())
+            .bind(serde_json::to_string(&portfolio.balances)?)
+            .bind(portfolio.updated_at.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+    
+    // Total of the latest snapshot taken before `at`
+    pub async fn get_portfolio_value_before(&self, at: DateTime<Utc>) -> Result<Option<Decimal>> {
+        let row = sqlx::query("SELECT total_value_usd FROM portfolio_snapshots WHERE timestamp < $1 ORDER BY timestamp DESC LIMIT 1")
+            .bind(at.to_rfc3339())
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        row.map(|row| decimal_column(&row, "total_value_usd")).transpose()
+    }
+    
+    pub async fn save_cross_chain_arbitrage(&self, arbitrage: &CrossChainArbitrage) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO cross_chain_opportunities
+                (id, pair, buy_exchange, sell_exchange, source_chain, target_chain, token_address,
+                 buy_price, sell_price, amount, buy_swap_fee, buy_gas_cost, bridge_fees,
+                 sell_swap_fee, sell_gas_cost, profit_estimate, estimated_time_minutes, timestamp)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)
+             ON CONFLICT (id) DO UPDATE SET
+                 pair = excluded.pair, buy_exchange = excluded.buy_exchange,
+                 sell_exchange = excluded.sell_exchange, source_chain = excluded.source_chain,
+                 target_chain = excluded.target_chain, token_address = excluded.token_address,
+                 buy_price = excluded.buy_price, sell_price = excluded.sell_price, amount = excluded.amount,
+                 buy_swap_fee = excluded.buy_swap_fee, buy_gas_cost = excluded.buy_gas_cost,
+                 bridge_fees = excluded.bridge_fees, sell_swap_fee = excluded.sell_swap_fee,
+                 sell_gas_cost = excluded.sell_gas_cost, profit_estimate = excluded.profit_estimate,
+                 estimated_time_minutes = excluded.estimated_time_minutes, timestamp = excluded.timestamp"
+        )
+        .bind(arbitrage.id.to_string())
+        .bind(&arbitrage.pair.symbol)
+        .bind(&arbitrage.buy_exchange)
+        .bind(&arbitrage.sell_exchange)
+        .bind(&arbitrage.source_chain)
+        .bind(&arbitrage.target_chain)
+        .bind(&arbitrage.token_address)
+        .bind(arbitrage.buy_price.to_string())
+        .bind(arbitrage.sell_price.to_string())
+        .bind(arbitrage.amount.to_string())
+        .bind(arbitrage.buy_swap_fee.to_string())
+        .bind(arbitrage.buy_gas_cost.to_string())
+        .bind(arbitrage.bridge_fees.to_string())
+        .bind(arbitrage.sell_swap_fee.to_string())
+        .bind(arbitrage.sell_gas_cost.to_string())
+        .bind(arbitrage.profit_estimate.to_string())
+        .bind(arbitrage.estimated_time_minutes as i64)
+        .bind(arbitrage.timestamp.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    pub async fn save_trading_halt(&self, halt: &TradingHalt) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO trading_halts (id, reason, halted_at, halted_until, cleared_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (id) DO UPDATE SET
+                 reason = excluded.reason, halted_at = excluded.halted_at, halted_until = excluded.halted_until,
+                 cleared_at = excluded.cleared_at"
+        )
+        .bind(halt.id.to_string())
+        .bind(&halt.reason)
+        .bind(halt.halted_at.to_rfc3339())
+        .bind(halt.halted_until.to_rfc3339())
+        .bind(halt.cleared_at.map(|t| t.to_rfc3339()))
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    pub async fn save_route_failure(&self, failure: &RouteFailure) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO route_failures (route, consecutive_failures, cooled_until, last_error, updated_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (route) DO UPDATE SET
+                 consecutive_failures = excluded.consecutive_failures, cooled_until = excluded.cooled_until,
+                 last_error = excluded.last_error, updated_at = excluded.updated_at"
+        )
+        .bind(&failure.route)
+        .bind(failure.consecutive_failures as i64)
+        .bind(failure.cooled_until.map(|t| t.to_rfc3339()))
+        .bind(&failure.last_error)
+        .bind(failure.updated_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    pub async fn clear_route_failure(&self, route: &str) -> Result<()> {
+        sqlx::query("DELETE FROM route_failures WHERE route = $1")
+            .bind(route)
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+    
+    pub async fn get_route_failures(&self) -> Result<Vec<RouteFailure>> {
+        let rows = sqlx::query("SELECT * FROM route_failures")
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(route_failure_from_row).collect()
+    }
+    
+    pub async fn save_blacklisted_token(&self, token: &BlacklistedToken) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO token_blacklist (chain_id, token, exchange, reason, blacklisted_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (chain_id, token) DO UPDATE SET
+                 exchange = excluded.exchange, reason = excluded.reason, blacklisted_at = excluded.blacklisted_at"
+        )
+        .bind(token.chain_id as i64)
+        .bind(&token.token)
+        .bind(&token.exchange)
+        .bind(&token.reason)
+        .bind(token.blacklisted_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    pub async fn get_blacklisted_tokens(&self) -> Result<Vec<BlacklistedToken>> {
+        let rows = sqlx::query("SELECT * FROM token_blacklist")
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(blacklisted_token_from_row).collect()
+    }
+    
+    pub async fn get_active_trading_halt(&self, now: DateTime<Utc>) -> Result<Option<TradingHalt>> {
+        let row = sqlx::query(
+            "SELECT * FROM trading_halts WHERE cleared_at IS NULL AND halted_until > $1 ORDER BY halted_until DESC LIMIT 1"
+        )
+            .bind(now.to_rfc3339())
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        row.map(|row| trading_halt_from_row(&row)).transpose()
+    }
+    
+    // Returns how many active halts were lifted
+    pub async fn clear_trading_halts(&self, now: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("UPDATE trading_halts SET cleared_at = $1 WHERE cleared_at IS NULL AND halted_until > $2")
+            .bind(now.to_rfc3339())
+            .bind(now.to_rfc3339())
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(result.rows_affected())
+    }
+    
+    pub async fn get_trading_halts_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<TradingHalt>> {
+        let rows = sqlx::query("SELECT * FROM trading_halts WHERE halted_at >= $1 AND halted_at < $2 ORDER BY halted_at")
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339())
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(trading_halt_from_row).collect()
+    }
+    
+    // Counted per UTC hour, which is as finely as reports can split them
+    pub async fn record_error(&self, context: &str, message: &str, at: DateTime<Utc>) -> Result<()> {
+        let hour = at.duration_trunc(chrono::Duration::hours(1))?;
+        sqlx::query(
+            "INSERT INTO error_counts (hour, context, count, last_message)
+             VALUES ($1, $2, 1, $3)
+             ON CONFLICT (hour, context) DO UPDATE SET
+                 count = error_counts.count + 1, last_message = excluded.last_message"
+        )
+        .bind(hour.to_rfc3339())
+        .bind(context)
+        .bind(message)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    // Most frequent first
+    pub async fn get_error_counts(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<ErrorCount>> {
+        let rows = sqlx::query("SELECT * FROM error_counts WHERE hour >= $1 AND hour < $2 ORDER BY hour")
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339())
+            .fetch_all(&self.pool)
+            .await?;
+        
+        let mut counts: Vec<ErrorCount> = Vec::new();
+        for row in &rows {
+            let context: String = row.try_get("context")?;
+            let count: i64 = row.try_get("count")?;
+            let last_message: String = row.try_get("last_message")?;
+            match counts.iter_mut().find(|error| error.context == context) {
+                Some(error) => {
+                    error.count += count;
+                    error.last_message = last_message;
+                },
+                None => counts.push(ErrorCount { context, count, last_message }),
+            }
+        }
+        counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.context.cmp(&b.context)));
+        Ok(counts)
+    }
+    
+    // Estimated gas of the executed opportunities in [start, end), dated like get_opportunity_stats
+    pub async fn get_executed_gas_cost(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Decimal> {
+        let rows = sqlx::query("SELECT gas_cost FROM opportunities WHERE status = 'executed' AND timestamp >= $1 AND timestamp < $2")
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339())
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(|row| decimal_column(row, "gas_cost")).sum()
+    }
+    
+    pub async fn get_last_halt_clearance(&self, since: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+        let row = sqlx::query("SELECT MAX(cleared_at) AS cleared_at FROM trading_halts WHERE cleared_at >= $1")
+            .bind(since.to_rfc3339())
+            .fetch_one(&self.pool)
+            .await?;
+        
+        let cleared_at: Option<String> = row.try_get("cleared_at")?;
+        Ok(cleared_at
+            .map(|value| DateTime::parse_from_rfc3339(&value).map(|t| t.with_timezone(&Utc)))
+            .transpose()?)
+    }
+    
+    pub async fn save_last_nonce(&self, chain_id: u64, address: &str, nonce: u64) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO nonces (chain_id, address, nonce, updated_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (chain_id, address) DO UPDATE SET nonce = excluded.nonce, updated_at = excluded.updated_at"
+        )
+        .bind(chain_id as i64)
+        .bind(address)
+        .bind(nonce as i64)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    pub async fn get_last_nonce(&self, chain_id: u64, address: &str) -> Result<Option<u64>> {
+        let row = sqlx::query("SELECT nonce FROM nonces WHERE chain_id = $1 AND address = $2")
+            .bind(chain_id as i64)
+            .bind(address)
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        Ok(row.map(|row| row.try_get::<i64, _>("nonce")).transpose()?.map(|nonce| nonce as u64))
+    }
+    
+    // Aggregated in SQL; profit percentages are stored as text and compared as doubles
+    pub async fn get_opportunity_stats(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<OpportunityStats> {
+        let (start, end) = (start.to_rfc3339(), end.to_rfc3339());
+        
+        let row = sqlx::query(&format!(
+            "SELECT COUNT(*) AS total,
+                    COALESCE(SUM(CASE WHEN status = 'active' THEN 1 ELSE 0 END), 0) AS active,
+                    COALESCE(SUM(CASE WHEN status = 'executed' THEN 1 ELSE 0 END), 0) AS executed,
+                    COALESCE(SUM(CASE WHEN status = 'expired' THEN 1 ELSE 0 END), 0) AS expired,
+                    COALESCE(SUM(CASE WHEN status = 'failed' THEN 1 ELSE 0 END), 0) AS failed,
+                    AVG(CAST(profit_percentage AS DOUBLE PRECISION)) AS average_profit,
+                    MAX(CAST(profit_percentage AS DOUBLE PRECISION)) AS max_profit,
+                    CAST(AVG({}) AS DOUBLE PRECISION) AS average_lifetime,
+                    COALESCE(SUM(updates), 0) AS updates
+             FROM opportunities WHERE timestamp >= $1 AND timestamp < $2",
+            self.backend.seconds_between("COALESCE(first_seen_at, timestamp)", "closed_at"),
+        ))
+            .bind(&start)
+            .bind(&end)
+            .fetch_one(&self.pool)
+            .await?;
+        
+        let mut stats = OpportunityStats {
+            total: row.try_get("total")?,
+            active: row.try_get("active")?,
+            executed: row.try_get("executed")?,
+            expired: row.try_get("expired")?,
+            failed: row.try_get("failed")?,
+            average_profit_percentage: row.try_get("average_profit")?,
+            max_profit_percentage: row.try_get("max_profit")?,
+            average_lifetime_seconds: row.try_get("average_lifetime")?,
+            updates: row.try_get("updates")?,
+            ..Default::default()
+        };
+        
+        stats.by_day = self.count_opportunities_by("substr(timestamp, 1, 10)", "key", &start, &end).await?;
+        stats.by_pair = self.count_opportunities_by("pair", "count DESC, key", &start, &end).await?;
+        stats.by_route = self.count_opportunities_by("buy_exchange || ' -> ' || sell_exchange", "count DESC, key", &start, &end).await?;
+        
+        // Buckets are [bound, next bound), the last one open-ended
+        let cases: String = PROFIT_BUCKETS.iter().enumerate()
+            .map(|(index, bound)| format!("WHEN CAST(profit_percentage AS DOUBLE PRECISION) < {} THEN {} ", bound, index))
+            .collect();
+        let sql = format!(
+            "SELECT CAST(CASE {}ELSE {} END AS BIGINT) AS bucket, COUNT(*) AS count
+             FROM opportunities WHERE timestamp >= $1 AND timestamp < $2
+             GROUP BY bucket",
+            cases, PROFIT_BUCKETS.len(),
+        );
+        let rows = sqlx::query(&sql)
+            .bind(&start)
+            .bind(&end)
+            .fetch_all(&self.pool)
+            .await?;
+        
+        let mut counts = vec![0; PROFIT_BUCKETS.len() + 1];
+        for row in rows {
+            let bucket: i64 = row.try_get("bucket")?;
+            counts[bucket as usize] = row.try_get("count")?;
+        }
+        stats.profit_distribution = counts.into_iter().enumerate()
+            .map(|(index, count)| ProfitBucket {
+                from: index.checked_sub(1).map(|lower| PROFIT_BUCKETS[lower]),
+                to: PROFIT_BUCKETS.get(index).copied(),
+                count,
+            })
+            .collect();
+        
+        Ok(stats)
+    }
+    
+    // `key` is an SQL expression over opportunity columns, never user input
+    pub async fn get_route_fill_counts(&self, start: DateTime<Utc>) -> Result<Vec<RouteFillCount>> {
+        let rows = sqlx::query(
+            "SELECT pair, buy_exchange, sell_exchange,
+                    COALESCE(SUM(CASE WHEN status = 'executed' THEN 1 ELSE 0 END), 0) AS executed,
+                    COALESCE(SUM(CASE WHEN status = 'failed' THEN 1 ELSE 0 END), 0) AS failed
+             FROM opportunities WHERE timestamp >= $1 AND status IN ('executed', 'failed')
+             GROUP BY pair, buy_exchange, sell_exchange"
+        )
+            .bind(start.to_rfc3339())
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter()
+            .map(|row| Ok(RouteFillCount {
+                pair: row.try_get("pair")?,
+                buy_exchange: row.try_get("buy_exchange")?,
+                sell_exchange: row.try_get("sell_exchange")?,
+                executed: row.try_get("executed")?,
+                failed: row.try_get("failed")?,
+            }))
+            .collect()
+    }
+    
+    async fn count_opportunities_by(&self, key: &str, order: &str, start: &str, end: &str) -> Result<Vec<OpportunityCount>> {
+        let sql = format!(
+            "SELECT {} AS key, COUNT(*) AS count,
+                    COALESCE(SUM(CASE WHEN status = 'executed' THEN 1 ELSE 0 END), 0) AS executed,
+                    AVG(CAST(profit_percentage AS DOUBLE PRECISION)) AS average_profit
+             FROM opportunities WHERE timestamp >= $1 AND timestamp < $2
+             GROUP BY key ORDER BY {}",
+            key, order,
+        );
+        let rows = sqlx::query(&sql)
+            .bind(start)
+            .bind(end)
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter()
+            .map(|row| Ok(OpportunityCount {
+                key: row.try_get("key")?,
+                count: row.try_get("count")?,
+                executed: row.try_get("executed")?,
+                average_profit_percentage: row.try_get("average_profit")?,
+            }))
+            .collect()
+    }
+    
+    // Exports stream their rows so a long range never has to fit in memory
+    pub fn stream_opportunities_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> BoxStream<'_, Result<ArbitrageOpportunity>> {
+        sqlx::query("SELECT * FROM opportunities WHERE timestamp >= $1 AND timestamp < $2 ORDER BY timestamp")
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339())
+            .fetch(&self.pool)
+            .map(|row| opportunity_from_row(&row?))
+            .boxed()
+    }
+    
+    // Live and simulated trades alike; the simulated flag is part of each trade
+    pub fn stream_trades_between(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> BoxStream<'_, Result<Trade>> {
+        sqlx::query("SELECT * FROM trades WHERE created_at >= $1 AND created_at < $2 ORDER BY created_at")
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339())
+            .fetch(&self.pool)
+            .map(|row| trade_from_row(&row?))
+            .boxed()
+    }
+    
+    pub fn stream_price_history(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> BoxStream<'_, Result<Price>> {
+        sqlx::query("SELECT * FROM price_history WHERE timestamp >= $1 AND timestamp < $2 ORDER BY timestamp")
+            .bind(start.to_rfc3339())
+            .bind(end.to_rfc3339())
+            .fetch(&self.pool)
+            .map(|row| price_from_row(&row?))
+            .boxed()
+    }
+    
+    pub async fn count_opportunities(&self) -> Result<i64> {
+        let row = sqlx::query("SELECT COUNT(*) AS count FROM opportunities")
+            .fetch_one(&self.pool)
+            .await?;
+        
+        Ok(row.try_get("count")?)
+    }
+    
+    // Improvements folded into existing rows by the route upsert
+    pub async fn count_opportunity_updates(&self) -> Result<i64> {
+        let row = sqlx::query("SELECT COALESCE(SUM(updates), 0) AS count FROM opportunities")
+            .fetch_one(&self.pool)
+            .await?;
+        
+        Ok(row.try_get("count")?)
+    }
+}
+
+pub fn status_to_str(status: &OpportunityStatus) -> &'static str {
+    match status {
+        OpportunityStatus::Active => "active",
+        OpportunityStatus::Executed => "executed",
+        OpportunityStatus::Expired => "expired",
+        OpportunityStatus::Failed => "failed",
+    }
+}
+
+fn status_from_str(status: &str) -> Result<OpportunityStatus> {
+    match status {
+        "active" => Ok(OpportunityStatus::Active),
+        "executed" => Ok(OpportunityStatus::Executed),
+        "expired" => Ok(OpportunityStatus::Expired),
+        "failed" => Ok(OpportunityStatus::Failed),
+        _ => anyhow::bail!("Unknown opportunity status: {}", status),
+    }
+}
+
+pub fn side_to_str(side: &TradeSide) -> &'static str {
+    match side {
+        TradeSide::Buy => "buy",
+        TradeSide::Sell => "sell",
+    }
+}
+
+fn side_from_str(side: &str) -> Result<TradeSide> {
+    match side {
+        "buy" => Ok(TradeSide::Buy),
+        "sell" => Ok(TradeSide::Sell),
+        _ => anyhow::bail!("Unknown trade side: {}", side),
+    }
+}
+
+pub fn trade_status_to_str(status: &TradeStatus) -> &'static str {
+    match status {
+        TradeStatus::Pending => "pending",
+        TradeStatus::Executed => "executed",
+        TradeStatus::Failed => "failed",
+        TradeStatus::Cancelled => "cancelled",
+    }
+}
+
+fn trade_status_from_str(status: &str) -> Result<TradeStatus> {
+    match status {
+        "pending" => Ok(TradeStatus::Pending),
+        "executed" => Ok(TradeStatus::Executed),
+        "failed" => Ok(TradeStatus::Failed),
+        "cancelled" => Ok(TradeStatus::Cancelled),
+        _ => anyhow::bail!("Unknown trade status: {}", status),
+    }
+}
+
+fn hedge_status_to_str(status: &HedgeStatus) -> &'static str {
+    match status {
+        HedgeStatus::Open => "open",
+        HedgeStatus::Closed => "closed",
+    }
+}
+
+fn hedge_status_from_str(status: &str) -> Result<HedgeStatus> {
+    match status {
+        "open" => Ok(HedgeStatus::Open),
+        "closed" => Ok(HedgeStatus::Closed),
+        _ => anyhow::bail!("Unknown hedge status: {}", status),
+    }
+}
+
+fn closed_at(status: &OpportunityStatus) -> Option<String> {
+    match status {
+        OpportunityStatus::Active => None,
+        _ => Some(Utc::now().to_rfc3339()),
+    }
+}
+
+fn filled_amount(trade: &Trade) -> Decimal {
+    match trade.status {
+        TradeStatus::Executed => trade.amount,
+        _ => Decimal::ZERO,
+    }
+}
+
+fn parse_pair(symbol: &str) -> Result<TradingPair> {
+    let parts: Vec<&str> = symbol.split('/').collect();
+    if parts.len() != 2 {
+        anyhow::bail!("Invalid trading pair in database: {}", symbol);
+    }
+    Ok(TradingPair::new(parts[0], parts[1]))
+}
+
+fn decimal_column(row: &AnyRow, column: &str) -> Result<Decimal> {
+    let value: String = row.try_get(column)?;
+    Ok(Decimal::from_str(&value)?)
+}
+
+fn timestamp_column(row: &AnyRow, column: &str) -> Result<DateTime<Utc>> {
+    let value: String = row.try_get(column)?;
+    Ok(DateTime::parse_from_rfc3339(&value)?.with_timezone(&Utc))
+}
+
+fn opportunity_from_row(row: &AnyRow) -> Result<ArbitrageOpportunity> {
+    let id: String = row.try_get("id")?;
+    let pair = parse_pair(&row.try_get::<String, _>("pair")?)?;
+    // Rows saved before aliasing traded the canonical pair on both venues
+    let native_pair = |column: &str| -> Result<TradingPair> {
+        match row.try_get::<Option<String>, _>(column)? {
+            Some(symbol) => parse_pair(&symbol),
+            None => Ok(pair.clone()),
+        }
+    };
+    let buy_pair = native_pair("buy_pair")?;
+    let sell_pair = native_pair("sell_pair")?;
+    let status: String = row.try_get("status")?;
+    let profit_amount = decimal_column(row, "profit_amount")?;
+    // Rows saved before transfer costs were tracked paid none
+    let adjusted_profit_amount: Option<String> = row.try_get("adjusted_profit_amount")?;
+    let adjusted_profit_amount = match adjusted_profit_amount {
+        Some(value) => Decimal::from_str(&value)?,
+        None => profit_amount,
+    };
+    let requires_approval: i64 = row.try_get("requires_approval")?;
+    let approved_at: Option<String> = row.try_get("approved_at")?;
+
+    Ok(ArbitrageOpportunity {
+        id: uuid::Uuid::parse_str(&id)?,
+        pair,
+        buy_exchange: row.try_get("buy_exchange")?,
+        sell_exchange: row.try_get("sell_exchange")?,
+        buy_pair,
+        sell_pair,
+        sell_quote_rate: decimal_column(row, "sell_quote_rate")?,
+        buy_price: decimal_column(row, "buy_price")?,
+        sell_price: decimal_column(row, "sell_price")?,
+        profit_percentage: decimal_column(row, "profit_percentage")?,
+        profit_amount,
+        max_trade_size: decimal_column(row, "max_trade_size")?,
+        gross_profit: decimal_column(row, "gross_profit")?,
+        buy_fee_cost: decimal_column(row, "buy_fee_cost")?,
+        sell_fee_cost: decimal_column(row, "sell_fee_cost")?,
+        buy_fee_rate: decimal_column(row, "buy_fee_rate")?,
+        sell_fee_rate: decimal_column(row, "sell_fee_rate")?,
+        gas_cost: decimal_column(row, "gas_cost")?,
+        transfer_cost: decimal_column(row, "transfer_cost")?,
+        adjusted_profit_amount,
+        marginal_profit: decimal_column(row, "marginal_profit")?,
+        timestamp: timestamp_column(row, "timestamp")?,
+        status: status_from_str(&status)?,
+        requires_approval: requires_approval != 0,
+        approved_at: approved_at
+            .map(|value| DateTime::parse_from_rfc3339(&value).map(|t| t.with_timezone(&Utc)))
+            .transpose()?,
+    })
+}
+
+fn trade_from_row(row: &AnyRow) -> Result<Trade> {
+    let id: String = row.try_get("id")?;
+    let opportunity_id: String = row.try_get("opportunity_id")?;
+    let pair: String = row.try_get("pair")?;
+    let side: String = row.try_get("side")?;
+    let status: String = row.try_get("status")?;
+    let executed_at: Option<String> = row.try_get("executed_at")?;
+    
+    Ok(Trade {
+        id: uuid::Uuid::parse_str(&id)?,
+        opportunity_id: uuid::Uuid::parse_str(&opportunity_id)?,
+        exchange: row.try_get("exchange")?,
+        pair: parse_pair(&pair)?,
+        side: side_from_str(&side)?,
+        amount: decimal_column(row, "amount")?,
+        price: decimal_column(row, "price")?,
+        fee_amount: decimal_column(row, "fee_amount")?,
+        fee_asset: row.try_get("fee_asset")?,
+        status: trade_status_from_str(&status)?,
+        created_at: timestamp_column(row, "created_at")?,
+        executed_at: executed_at
+            .map(|value| DateTime::parse_from_rfc3339(&value).map(|t| t.with_timezone(&Utc)))
+            .transpose()?,
+        order_id: row.try_get("order_id")?,
+        client_order_id: row.try_get("client_order_id")?,
+        tx_hash: row.try_get("tx_hash")?,
+        simulated: row.try_get::<i64, _>("simulated")? != 0,
+        failure_reason: row.try_get("failure_reason")?,
+    })
+}
+
+fn hedge_position_from_row(row: &AnyRow) -> Result<HedgePosition> {
+    let id: String = row.try_get("id")?;
+    let opportunity_id: String = row.try_get("opportunity_id")?;
+    let pair: String = row.try_get("pair")?;
+    let side: String = row.try_get("side")?;
+    let status: String = row.try_get("status")?;
+    let closed_at: Option<String> = row.try_get("closed_at")?;
+    
+    Ok(HedgePosition {
+        id: uuid::Uuid::parse_str(&id)?,
+        opportunity_id: uuid::Uuid::parse_str(&opportunity_id)?,
+        exchange: row.try_get("exchange")?,
+        pair: parse_pair(&pair)?,
+        side: side_from_str(&side)?,
+        size: decimal_column(row, "size")?,
+        entry_price: decimal_column(row, "entry_price")?,
+        order_id: row.try_get("order_id")?,
+        status: hedge_status_from_str(&status)?,
+        opened_at: timestamp_column(row, "opened_at")?,
+        closed_at: closed_at
+            .map(|value| DateTime::parse_from_rfc3339(&value).map(|t| t.with_timezone(&Utc)))
+            .transpose()?,
+    })
+}
+
+fn price_from_row(row: &AnyRow) -> Result<Price> {
+    let pair: String = row.try_get("pair")?;
+    let volume_24h: Option<String> = row.try_get("volume_24h")?;
+    
+    Ok(Price {
+        exchange: row.try_get("exchange")?,
+        pair: parse_pair(&pair)?,
+        bid: decimal_column(row, "bid")?,
+        ask: decimal_column(row, "ask")?,
+        timestamp: timestamp_column(row, "timestamp")?,
+        volume_24h: volume_24h.map(|v| Decimal::from_str(&v)).transpose()?,
+        fetch_latency: None,
+    })
+}
+
+#[cfg(test)]
+fn candle_from_row(row: &AnyRow) -> Result<PriceCandle> {
+    let pair: String = row.try_get("pair")?;
+    
+    Ok(PriceCandle {
+        exchange: row.try_get("exchange")?,
+        pair: parse_pair(&pair)?,
+        minute: timestamp_column(row, "minute")?,
+        open: decimal_column(row, "open")?,
+        high: decimal_column(row, "high")?,
+        low: decimal_column(row, "low")?,
+        close: decimal_column(row, "close")?,
+        samples: row.try_get("samples")?,
+    })
+}
+
+// `time` in [$1, $2) plus optional pair and exchange filters, whose values are returned for binding
+fn history_query(table: &str, time: &str, pair: Option<&TradingPair>, exchange: Option<&str>) -> (String, Vec<String>) {
+    let mut sql = format!("SELECT * FROM {} WHERE {} >= $1 AND {} < $2", table, time, time);
+    let mut filters = Vec::new();
+    if let Some(pair) = pair {
+        filters.push(pair.symbol.clone());
+        sql.push_str(&format!(" AND pair = ${}", filters.len() + 2));
+    }
+    if let Some(exchange) = exchange {
+        filters.push(exchange.to_string());
+        sql.push_str(&format!(" AND exchange = ${}", filters.len() + 2));
+    }
+    sql.push_str(&format!(" ORDER BY {}", time));
+    
+    (sql, filters)
+}
+
+fn order_book_from_row(row: &AnyRow) -> Result<OrderBook> {
+    let pair: String = row.try_get("pair")?;
+    let bids: String = row.try_get("bids")?;
+    let asks: String = row.try_get("asks")?;
+    
+    Ok(OrderBook {
+        exchange: row.try_get("exchange")?,
+        pair: parse_pair(&pair)?,
+        bids: serde_json::from_str(&bids)?,
+        asks: serde_json::from_str(&asks)?,
+        timestamp: timestamp_column(row, "timestamp")?,
+    })
+}
+
+fn route_failure_from_row(row: &AnyRow) -> Result<RouteFailure> {
+    let consecutive_failures: i64 = row.try_get("consecutive_failures")?;
+    let cooled_until: Option<String> = row.try_get("cooled_until")?;
+    
+    Ok(RouteFailure {
+        route: row.try_get("route")?,
+        consecutive_failures: consecutive_failures as u32,
+        cooled_until: cooled_until
+            .map(|value| DateTime::parse_from_rfc3339(&value).map(|t| t.with_timezone(&Utc)))
+            .transpose()?,
+        last_error: row.try_get("last_error")?,
+        updated_at: timestamp_column(row, "updated_at")?,
+    })
+}
+
+fn blacklisted_token_from_row(row: &AnyRow) -> Result<BlacklistedToken> {
+    let chain_id: i64 = row.try_get("chain_id")?;
+    
+    Ok(BlacklistedToken {
+        chain_id: chain_id as u64,
+        token: row.try_get("token")?,
+        exchange: row.try_get("exchange")?,
+        reason: row.try_get("reason")?,
+        blacklisted_at: timestamp_column(row, "blacklisted_at")?,
+    })
+}
+
+fn trading_halt_from_row(row: &AnyRow) -> Result<TradingHalt> {
+    let id: String = row.try_get("id")?;
+    let cleared_at: Option<String> = row.try_get("cleared_at")?;
+    
+    Ok(TradingHalt {
+        id: uuid::Uuid::parse_str(&id)?,
+        reason: row.try_get("reason")?,
+        halted_at: timestamp_column(row, "halted_at")?,
+        halted_until: timestamp_column(row, "halted_until")?,
+        cleared_at: cleared_at
+            .map(|value| DateTime::parse_from_rfc3339(&value).map(|t| t.with_timezone(&Utc)))
+            .transpose()?,
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    use crate::recorder::MarketRecorder;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    // An opportunity on `pair` detected `age` ago, with everything the stats do not look at held fixed
+    pub(crate) fn stats_opportunity(pair: &TradingPair, buy: &str, sell: &str, profit_percentage: &str, age: chrono::Duration) -> ArbitrageOpportunity {
+        ArbitrageOpportunity {
+            id: uuid::Uuid::new_v4(),
+            pair: pair.clone(),
+            buy_exchange: buy.to_string(),
+            sell_exchange: sell.to_string(),
+            buy_pair: pair.clone(),
+            sell_pair: pair.clone(),
+            sell_quote_rate: Decimal::ONE,
+            buy_price: dec("100"),
+            sell_price: dec("101"),
+            profit_percentage: dec(profit_percentage),
+            profit_amount: dec("1"),
+            max_trade_size: dec("1"),
+            gross_profit: dec("1"),
+            buy_fee_cost: Decimal::ZERO,
+            sell_fee_cost: Decimal::ZERO,
+            buy_fee_rate: Decimal::ZERO,
+            sell_fee_rate: Decimal::ZERO,
+            gas_cost: Decimal::ZERO,
+            transfer_cost: Decimal::ZERO,
+            adjusted_profit_amount: dec("1"),
+            marginal_profit: Decimal::ZERO,
+            timestamp: chrono::Utc::now() - age,
+            status: OpportunityStatus::Active,
+            requires_approval: false,
+            approved_at: None,
+        }
+    }
+
+    // Three opportunities over two days, closed 10 seconds, 30 seconds and a day after detection, plus
+    // one outside the range
+    #[tokio::test]
+    async fn aggregates_opportunity_stats() -> Result<()> {
+        let eth = TradingPair::new("ETH", "USDT");
+        let btc = TradingPair::new("BTC", "USDT");
+        
+        let database = Database::new("sqlite::memory:").await?;
+        database.migrate().await?;
+        
+        let mut executed = stats_opportunity(&eth, "alpha", "beta", "0.3", chrono::Duration::seconds(10));
+        let expired = stats_opportunity(&eth, "alpha", "beta", "1.5", chrono::Duration::seconds(30));
+        let stale = stats_opportunity(&btc, "beta", "alpha", "12", chrono::Duration::days(1));
+        let outside = stats_opportunity(&btc, "beta", "alpha", "0.7", chrono::Duration::days(10));
+        // Closed before the other opportunity on its route is saved, which would otherwise update it
+        database.save_opportunity(&executed).await?;
+        executed.status = OpportunityStatus::Executed;
+        database.update_opportunity_status(&executed).await?;
+        for opportunity in [&expired, &stale, &outside] {
+            database.save_opportunity(opportunity).await?;
+        }
+        database.update_opportunity_statuses(&[expired.id, stale.id], &OpportunityStatus::Expired).await?;
+        
+        let now = chrono::Utc::now();
+        let stats = database.get_opportunity_stats(now - chrono::Duration::days(2), now + chrono::Duration::hours(1)).await?;
+        assert!(stats.total == 3 && stats.executed == 1 && stats.expired == 2 && stats.active == 0,
+               "counted {} total, {} executed, {} expired, {} active", stats.total, stats.executed, stats.expired, stats.active);
+        assert!(stats.execution_rate().map_or(false, |rate| (rate - 1.0 / 3.0).abs() < 1e-9),
+               "execution rate was {:?}", stats.execution_rate());
+        assert!(stats.max_profit_percentage == Some(12.0),
+               "best profit was {:?}", stats.max_profit_percentage);
+        // (10 + 30 + 86400) / 3 seconds, give or take the time the scenario takes
+        assert!(stats.average_lifetime_seconds.map_or(false, |seconds| (28810.0..28830.0).contains(&seconds)),
+               "average lifetime was {:?}", stats.average_lifetime_seconds);
+        
+        assert!(stats.by_day.len() == 2 && stats.by_day.iter().map(|day| day.count).sum::<i64>() == 3,
+               "per-day counts were {:?}", stats.by_day);
+        let route = stats.by_route.first();
+        assert!(route.map_or(false, |route| route.key == "alpha -> beta" && route.count == 2 && route.executed == 1),
+               "the busiest route was {:?}", route);
+        assert!(stats.by_pair.iter().any(|pair| pair.key == "BTC/USDT" && pair.count == 1),
+               "per-pair counts were {:?}", stats.by_pair);
+        
+        let counts: Vec<i64> = stats.profit_distribution.iter().map(|bucket| bucket.count).collect();
+        assert!(counts == [1, 0, 1, 0, 0, 1], "profit distribution was {:?}", counts);
+        assert!(stats.profit_distribution.first().map_or(false, |bucket| bucket.from.is_none() && bucket.to == Some(0.5)),
+               "the lowest bucket was {:?}", stats.profit_distribution.first());
+        
+        let empty = database.get_opportunity_stats(now + chrono::Duration::days(1), now + chrono::Duration::days(2)).await?;
+        assert!(empty.total == 0 && empty.average_lifetime_seconds.is_none() && empty.by_route.is_empty(),
+               "an empty range reported {:?}", empty);
+        
+        Ok(())
+    }
+
+    // Save/load round trips against every backend; Postgres only when POSTGRES_TEST_URL points at a
+    // scratch database
+    #[tokio::test]
+    async fn round_trips_every_backend() -> Result<()> {
+        let mut urls = vec!["sqlite::memory:".to_string()];
+        match std::env::var("POSTGRES_TEST_URL") {
+            Ok(url) => urls.push(url),
+            Err(_) => println!("POSTGRES_TEST_URL is not set, skipping Postgres"),
+        }
+        
+        for url in urls {
+            let database = Database::new(&url).await?;
+            database.migrate().await?;
+            // A second run must find the schema in place
+            database.migrate().await?;
+            let backend = format!("{:?}", database.backend());
+            
+            let pair = TradingPair::new("ETH", "USDT");
+            let mut opportunity = stats_opportunity(&pair, "alpha", "beta", "1.23456789", chrono::Duration::seconds(5));
+            opportunity.buy_price = dec("0.00000001");
+            opportunity.sell_price = dec("123456789.123456789");
+            opportunity.sell_quote_rate = dec("0.9999");
+            database.save_opportunity(&opportunity).await?;
+            opportunity.status = OpportunityStatus::Executed;
+            database.update_opportunity_status(&opportunity).await?;
+            
+            let loaded = database.get_opportunity(opportunity.id).await?;
+            assert!(loaded.as_ref().map(serde_json::to_value).transpose()? == Some(serde_json::to_value(&opportunity)?),
+                   "{}: opportunity loaded as {:?}", backend, loaded);
+            
+            let mut trade = Trade {
+                id: uuid::Uuid::new_v4(),
+                opportunity_id: opportunity.id,
+                exchange: "alpha".to_string(),
+                pair: pair.clone(),
+                side: TradeSide::Buy,
+                amount: dec("0.12345678"),
+                price: dec("0.00000001"),
+                fee_amount: dec("0.000001"),
+                fee_asset: Some("ETH".to_string()),
+                status: TradeStatus::Pending,
+                created_at: chrono::Utc::now(),
+                executed_at: None,
+                order_id: Some("42".to_string()),
+                client_order_id: None,
+                tx_hash: None,
+                simulated: true,
+                failure_reason: None,
+            };
+            database.save_trade(&trade).await?;
+            trade.status = TradeStatus::Executed;
+            trade.executed_at = Some(chrono::Utc::now());
+            database.update_trade_status(&trade).await?;
+            
+            let trades = database.get_trades_for_opportunity(opportunity.id).await?;
+            let expected = vec![serde_json::to_value(&trade)?];
+            let loaded = trades.iter().map(serde_json::to_value).collect::<Result<Vec<_>, _>>()?;
+            assert!(loaded == expected, "{}: trades loaded as {:?}", backend, trades);
+            
+            let stats = database.get_opportunity_stats(
+                opportunity.timestamp - chrono::Duration::milliseconds(1),
+                opportunity.timestamp + chrono::Duration::milliseconds(1),
+            ).await?;
+            assert!(stats.total == 1 && stats.executed == 1
+                      && stats.average_lifetime_seconds.map_or(false, |seconds| (4.0..60.0).contains(&seconds)),
+                   "{}: stats were {:?}", backend, stats);
+        }
+        
+        Ok(())
+    }
+
+    // A fresh database migrates to the latest version once, and a pre-migrations database keeps its
+    // rows while gaining the columns it predates
+    #[tokio::test]
+    async fn migrates_fresh_and_legacy_databases() -> Result<()> {
+        let database = Database::new("sqlite::memory:").await?;
+        assert!(database.schema_version().await?.is_none(), "a fresh database had a schema version");
+        let pending = database.pending_migrations().await?;
+        assert!(pending.first().map(|migration| migration.version) == Some(1),
+               "a fresh database had {} pending migrations", pending.len());
+        assert!(database.schema_version().await?.is_none(), "listing pending migrations applied them");
+        
+        let applied = database.migrate().await?;
+        let latest = applied.last().map(|migration| migration.version);
+        assert!(applied.len() == pending.len() && database.schema_version().await? == latest,
+               "applied {} migrations, now at {:?}", applied.len(), database.schema_version().await?);
+        assert!(database.migrate().await?.is_empty() && database.pending_migrations().await?.is_empty(),
+               "a migrated database still had pending migrations");
+        
+        let path = std::env::temp_dir().join(format!("legacy-{}.db", uuid::Uuid::new_v4()));
+        let url = format!("sqlite://{}", path.display());
+        sqlx::any::install_default_drivers();
+        let legacy = sqlx::any::AnyPoolOptions::new()
+            .max_connections(1)
+            .connect(&format!("{}?mode=rwc", url))
+            .await?;
+        sqlx::query(
+            "CREATE TABLE opportunities (id TEXT PRIMARY KEY, pair TEXT NOT NULL, buy_exchange TEXT NOT NULL,
+                sell_exchange TEXT NOT NULL, buy_price TEXT NOT NULL, sell_price TEXT NOT NULL,
+                profit_percentage TEXT NOT NULL, profit_amount TEXT NOT NULL, max_trade_size TEXT NOT NULL,
+                gas_cost TEXT NOT NULL DEFAULT '0', timestamp TEXT NOT NULL, status TEXT NOT NULL)"
+        )
+        .execute(&legacy)
+        .await?;
+        let id = uuid::Uuid::new_v4();
+        sqlx::query("INSERT INTO opportunities VALUES ($1, 'ETH/USDT', 'alpha', 'beta', '100', '101', '1', '1', '1', '0', $2, 'expired')")
+            .bind(id.to_string())
+            .bind(chrono::Utc::now().to_rfc3339())
+            .execute(&legacy)
+            .await?;
+        legacy.close().await;
+        
+        let result = async {
+            let database = Database::new(&url).await?;
+            database.migrate().await?;
+            let opportunity = database.get_opportunity(id).await?;
+            let version = database.schema_version().await?;
+            database.close().await;
+            anyhow::Ok((opportunity, version))
+        }.await;
+        let _ = std::fs::remove_file(&path);
+        let (opportunity, version) = result?;
+        
+        assert!(opportunity.as_ref().map_or(false, |opportunity| opportunity.sell_quote_rate == Decimal::ONE
+                  && matches!(opportunity.status, OpportunityStatus::Expired)),
+               "the legacy opportunity loaded as {:?}", opportunity);
+        assert!(version == latest, "the legacy database ended at version {:?}", version);
+        
+        Ok(())
+    }
+
+    // 250 prices per venue a second apart, two hours old, go through the recorder; pruning an hour
+    // later rolls the whole minutes up and leaves the newer rows alone
+    #[tokio::test]
+    async fn records_and_prunes_price_history() -> Result<()> {
+        let pair = TradingPair::new("ETH", "USDT");
+        let database = Database::new("sqlite::memory:").await?;
+        database.migrate().await?;
+        
+        let start = chrono::Utc::now().duration_trunc(chrono::Duration::minutes(1))? - chrono::Duration::hours(2);
+        let recorder = MarketRecorder::spawn(database.clone());
+        for second in 0..250 {
+            for (exchange, offset) in [("alpha", Decimal::ZERO), ("beta", dec("10"))] {
+                let bid = dec("1000") + Decimal::from(second % 60) + offset;
+                recorder.record_price(&Price {
+                    exchange: exchange.to_string(),
+                    pair: pair.clone(),
+                    bid,
+                    ask: bid + Decimal::TWO,
+                    timestamp: start + chrono::Duration::seconds(second),
+                    volume_24h: None,
+                    fetch_latency: None,
+                });
+            }
+        }
+        recorder.flush().await;
+        
+        let end = start + chrono::Duration::hours(1);
+        
+        let all = database.get_price_history(None, None, start, end).await?;
+        assert!(all.len() == 500, "recorded {} prices", all.len());
+        let beta = database.get_price_history(Some(&pair), Some("beta"), start, end).await?;
+        assert!(beta.len() == 250 && beta.iter().all(|price| price.exchange == "beta"),
+               "{} prices were filtered to beta", beta.len());
+        assert!(beta.windows(2).all(|window| window[0].timestamp < window[1].timestamp),
+               "price history was not in time order");
+        let other = database.get_price_history(Some(&TradingPair::new("BTC", "USDT")), None, start, end).await?;
+        assert!(other.is_empty(), "{} prices matched another pair", other.len());
+        
+        // Cuts at start + 3m30s, so the fourth minute and what follows stay raw
+        let pruning = database.prune_price_history(start + chrono::Duration::seconds(210), true).await?;
+        assert!(pruning.deleted == 360 && pruning.candles == 6, "pruning reported {:?}", pruning);
+        
+        let remaining = database.get_price_history(None, None, start, end).await?;
+        assert!(remaining.len() == 140 && remaining.iter().all(|price| price.timestamp >= start + chrono::Duration::minutes(3)),
+               "{} prices remained after pruning", remaining.len());
+        
+        let candles = database.get_price_candles(Some(&pair), Some("alpha"), start, end).await?;
+        let first = candles.first();
+        assert!(candles.len() == 3 && first.map_or(false, |candle| candle.minute == start
+                  && candle.open == dec("1001") && candle.high == dec("1060") && candle.low == dec("1001")
+                  && candle.close == dec("1060") && candle.samples == 60),
+               "alpha candles were {:?}", candles);
+        
+        let pruning = database.prune_price_history(start + chrono::Duration::seconds(210), true).await?;
+        assert!(pruning.deleted == 0 && pruning.candles == 0, "pruning again reported {:?}", pruning);
+        
+        let pruning = database.prune_price_history(end, false).await?;
+        let candles = database.get_price_candles(None, None, start, end).await?;
+        assert!(pruning.deleted == 140 && candles.len() == 6,
+               "deleting without a rollup reported {:?} and left {} candles", pruning, candles.len());
+        
+        Ok(())
+    }
+
+    // A persistent spread that keeps improving stays one row with a growing update count; a new row
+    // only starts once that one is closed
+    #[tokio::test]
+    async fn upserts_an_improving_route() -> Result<()> {
+        let pair = TradingPair::new("ETH", "USDT");
+        let database = Database::new("sqlite::memory:").await?;
+        database.migrate().await?;
+        
+        let first = stats_opportunity(&pair, "alpha", "beta", "0.6", chrono::Duration::seconds(30));
+        let first_id = database.save_opportunity(&first).await?;
+        assert!(first_id == first.id, "a new route did not keep its own id");
+        
+        let mut latest = first.clone();
+        for (profit, age) in [("0.8", 20), ("1.1", 10)] {
+            latest = stats_opportunity(&pair, "alpha", "beta", profit, chrono::Duration::seconds(age));
+            let id = database.save_opportunity(&latest).await?;
+            assert!(id == first_id, "improving to {}% saved under a new id", profit);
+        }
+        
+        // The reverse route is a different opportunity
+        let reverse = stats_opportunity(&pair, "beta", "alpha", "0.2", chrono::Duration::seconds(10));
+        assert!(database.save_opportunity(&reverse).await? == reverse.id,
+               "the reverse route was folded into the forward one");
+        
+        let stored = database.get_opportunity(first_id).await?;
+        assert!(stored.as_ref().map_or(false, |stored| stored.profit_percentage == dec("1.1")
+                  && stored.timestamp == latest.timestamp),
+               "the route's row was {:?}", stored);
+        assert!(database.count_opportunities().await? == 2 && database.count_opportunity_updates().await? == 2,
+               "{} rows with {} updates", database.count_opportunities().await?, database.count_opportunity_updates().await?);
+        
+        let mut closed = latest.clone();
+        closed.id = first_id;
+        closed.status = OpportunityStatus::Expired;
+        database.update_opportunity_status(&closed).await?;
+        
+        let next = stats_opportunity(&pair, "alpha", "beta", "0.7", chrono::Duration::zero());
+        let next_id = database.save_opportunity(&next).await?;
+        assert!(next_id == next.id && database.count_opportunities().await? == 3,
+               "a route whose row had expired did not start a new one");
+        
+        let now = chrono::Utc::now();
+        let stats = database.get_opportunity_stats(now - chrono::Duration::hours(1), now + chrono::Duration::hours(1)).await?;
+        // Measured from the first detection 30 seconds ago, not the last improvement
+        assert!(stats.updates == 2 && stats.average_lifetime_seconds.map_or(false, |seconds| (29.0..60.0).contains(&seconds)),
+               "stats reported {} updates and a lifetime of {:?}", stats.updates, stats.average_lifetime_seconds);
+        
+        Ok(())
+    }
+
+    // Five samples over two past hours and one now; downsampling must keep every hour's sums
+    #[tokio::test]
+    async fn downsamples_spread_history() -> Result<()> {
+        let close = |actual: f64, expected: f64| (actual - expected).abs() < 1e-9;
+        let database = Database::new("sqlite::memory:").await?;
+        database.migrate().await?;
+        let now = chrono::Utc::now();
+        let base = now.duration_trunc(chrono::Duration::hours(1))? - chrono::Duration::hours(5);
+        let sample = |minutes: i64, net_spread: &str| SpreadSample {
+            pair: "ETH/USDT".to_string(),
+            buy_exchange: "alpha".to_string(),
+            sell_exchange: "beta".to_string(),
+            net_spread: dec(net_spread),
+            above_threshold: dec(net_spread) > dec("0.25"),
+            timestamp: base + chrono::Duration::minutes(minutes),
+        };
+        let samples = vec![sample(5, "0.1"), sample(20, "0.2"), sample(40, "0.3"), sample(65, "0.4"), sample(70, "0.6"),
+                           SpreadSample { timestamp: now, ..sample(0, "0.5") }];
+        database.save_spread_samples(&samples).await?;
+        
+        let (from, to) = (base - chrono::Duration::hours(1), now + chrono::Duration::hours(1));
+        let recorded = database.get_spread_buckets(from, to).await?;
+        assert!(recorded.len() == 3 && recorded[0].hour == base && recorded[0].samples == 3
+                  && close(recorded[0].net_sum, 0.6) && recorded[0].above_threshold == 1,
+               "recorded spreads bucketed as {:?}", recorded);
+        
+        let pruning = database.prune_spread_history(now - chrono::Duration::hours(1), base - chrono::Duration::hours(1)).await?;
+        assert!(pruning.downsampled == 5 && pruning.deleted == 0, "downsampling reported {:?}", pruning);
+        let downsampled = database.get_spread_buckets(from, to).await?;
+        let same = downsampled.len() == recorded.len() && downsampled.iter().zip(&recorded).all(|(after, before)| {
+            after.hour == before.hour && after.samples == before.samples && after.above_threshold == before.above_threshold
+                && close(after.net_sum, before.net_sum) && close(after.net_sum_squares, before.net_sum_squares)
+        });
+        assert!(same, "downsampling changed the buckets to {:?}", downsampled);
+        let again = database.prune_spread_history(now - chrono::Duration::hours(1), base - chrono::Duration::hours(1)).await?;
+        assert!(again.downsampled == 0, "downsampled rows were downsampled again: {:?}", again);
+        
+        let expiry = database.prune_spread_history(now - chrono::Duration::hours(1), base + chrono::Duration::minutes(90)).await?;
+        let remaining = database.get_spread_buckets(from, to).await?;
+        assert!(expiry.deleted == 2 && remaining.len() == 1 && remaining[0].samples == 1,
+               "expiring the first two hours reported {:?}, leaving {:?}", expiry, remaining);
+        
+        Ok(())
+    }
+}
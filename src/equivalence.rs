@@ -0,0 +1,166 @@
+use rust_decimal::Decimal;
+
+use crate::config::{QuoteEquivalenceConfig, ResidualQuote};
+use crate::models::{Price, TradingPair};
+
+// Groups of quote assets worth roughly the same, e.g. dollar stables. Prices quoted in one member
+// are restated in another at the rate the two actually trade at, never at an assumed 1:1.
+pub struct QuoteEquivalence {
+    config: QuoteEquivalenceConfig,
+}
+
+impl QuoteEquivalence {
+    pub fn new(mut config: QuoteEquivalenceConfig) -> Self {
+        for group in &mut config.groups {
+            for asset in group.iter_mut() {
+                *asset = asset.to_uppercase();
+            }
+        }
+        Self { config }
+    }
+
+    pub fn residual(&self) -> ResidualQuote {
+        self.config.residual
+    }
+
+    fn group(&self, asset: &str) -> Option<&Vec<String>> {
+        self.config.groups.iter().find(|group| group.iter().any(|member| member == asset))
+    }
+
+    pub fn equivalent(&self, a: &str, b: &str) -> bool {
+        a != b && self.group(a).map_or(false, |group| group.iter().any(|member| member == b))
+    }
+
+    // The same base under every other quote in its group, e.g. ETH/USDC and ETH/DAI for ETH/USDT
+    pub fn equivalent_pairs(&self, pair: &TradingPair) -> Vec<TradingPair> {
+        self.group(&pair.quote)
+            .map(|group| group.iter()
+                .filter(|quote| **quote != pair.quote && **quote != pair.base)
+                .map(|quote| TradingPair::new(&pair.base, quote))
+                .collect())
+            .unwrap_or_default()
+    }
+
+    // Every pair between two members of a group, in both orientations; venues list whichever they trade
+    pub fn conversion_pairs(&self) -> Vec<TradingPair> {
+        let mut pairs = Vec::new();
+        for group in &self.config.groups {
+            for from in group {
+                for to in group.iter().filter(|to| *to != from) {
+                    pairs.push(TradingPair::new(from, to));
+                }
+            }
+        }
+        pairs
+    }
+
+    // Units of `to` one unit of `from` is worth, from current `quotes` of FROM/TO and TO/FROM.
+    // Converting proceeds means selling them on `venue`, so only its quotes count and they are taken
+    // at the side the swap would hit; held proceeds are valued at the mid across all venues.
+    pub fn rate(&self, from: &str, to: &str, venue: &str, quotes: &[Price]) -> Option<Decimal> {
+        if from == to {
+            return Some(Decimal::ONE);
+        }
+        if !self.equivalent(from, to) {
+            return None;
+        }
+        
+        let convert = self.config.residual == ResidualQuote::Convert;
+        let rates: Vec<Decimal> = quotes.iter()
+            .filter(|quote| !convert || quote.exchange == venue)
+            .filter(|quote| quote.bid > Decimal::ZERO && quote.ask > Decimal::ZERO)
+            .filter_map(|quote| {
+                let mid = (quote.bid + quote.ask) / Decimal::TWO;
+                if quote.pair.base == from && quote.pair.quote == to {
+                    Some(if convert { quote.bid } else { mid })
+                } else if quote.pair.base == to && quote.pair.quote == from {
+                    Some(Decimal::ONE / if convert { quote.ask } else { mid })
+                } else {
+                    None
+                }
+            })
+            .collect();
+        
+        let rate = if convert {
+            rates.into_iter().max()?
+        } else if rates.is_empty() {
+            return None;
+        } else {
+            rates.iter().sum::<Decimal>() / Decimal::from(rates.len())
+        };
+        
+        if (rate - Decimal::ONE).abs() > self.config.max_depeg {
+            tracing::debug!("{} -> {} rate {} is off peg by more than {}", from, to, rate, self.config.max_depeg);
+            return None;
+        }
+        Some(rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use crate::models::{OrderBook, OrderBookLevel, TradeSide};
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    // Stable quotes are compared at the rate they trade at, and a depegged one is not compared at all
+    #[test]
+    fn compares_stable_quotes_at_their_rate() {
+        let config = |residual| QuoteEquivalenceConfig {
+            groups: vec![vec!["usdt".to_string(), "usdc".to_string(), "dai".to_string()]],
+            residual,
+            ..QuoteEquivalenceConfig::default()
+        };
+        let quote = |exchange: &str, base: &str, quote: &str, bid: &str, ask: &str| Price {
+            exchange: exchange.to_string(),
+            pair: TradingPair::new(base, quote),
+            bid: dec(bid),
+            ask: dec(ask),
+            timestamp: chrono::Utc::now(),
+            volume_24h: None,
+            fetch_latency: None,
+        };
+        let convert = QuoteEquivalence::new(config(ResidualQuote::Convert));
+        let hold = QuoteEquivalence::new(config(ResidualQuote::Hold));
+        
+        let pairs: Vec<String> = convert.equivalent_pairs(&TradingPair::new("ETH", "USDT")).into_iter().map(|pair| pair.symbol).collect();
+        assert!(pairs == ["ETH/USDC", "ETH/DAI"], "ETH/USDT was compared against {:?}", pairs);
+        assert!(!convert.equivalent("ETH", "USDT"), "ETH and USDT were treated as equivalent");
+        
+        let quotes = [quote("alpha", "USDC", "USDT", "0.9995", "0.9997"), quote("beta", "USDC", "USDT", "0.9990", "1.0010")];
+        let rate = convert.rate("USDC", "USDT", "alpha", &quotes);
+        assert!(rate == Some(dec("0.9995")),
+               "converting USDC on alpha was priced at {:?} rather than its bid", rate);
+        let rate = convert.rate("USDC", "USDT", "gamma", &quotes);
+        assert!(rate.is_none(), "a venue without a USDC market converted at {:?}", rate);
+        
+        let inverted = [quote("alpha", "USDT", "USDC", "0.9990", "1.0005")];
+        let rate = convert.rate("USDC", "USDT", "alpha", &inverted);
+        assert!(rate == Some(Decimal::ONE / dec("1.0005")),
+               "buying USDT with USDC was priced at {:?} rather than the inverse ask", rate);
+        
+        let rate = hold.rate("USDC", "USDT", "gamma", &quotes);
+        assert!(rate == Some(dec("0.99980")),
+               "held USDC was valued at {:?} rather than the cross-venue mid", rate);
+        
+        let depegged = [quote("alpha", "USDC", "USDT", "0.95", "0.96")];
+        let rate = convert.rate("USDC", "USDT", "alpha", &depegged);
+        assert!(rate.is_none(), "a 5% depeg still converted at {:?}", rate);
+        
+        let book = OrderBook {
+            exchange: "alpha".to_string(),
+            pair: TradingPair::new("ETH", "USDC"),
+            bids: vec![OrderBookLevel { price: dec("2000"), quantity: dec("1") }],
+            asks: vec![OrderBookLevel { price: dec("2002"), quantity: dec("1") }],
+            timestamp: chrono::Utc::now(),
+        };
+        let converted = book.converted(dec("0.999"));
+        let bid = converted.effective_price(&TradeSide::Sell, Decimal::ONE);
+        assert!(bid == Some(dec("1998.000")),
+               "a 2000 USDC bid restated at 0.999 came to {:?}", bid);
+    }
+}
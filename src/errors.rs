@@ -0,0 +1,283 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+// Lowercase fragments of venue error messages, checked in this order; a venue's own code is kept but not
+// interpreted, since every venue numbers its errors differently
+const RATE_LIMIT_HINTS: &[&str] = &["rate limit", "too many requests", "too many visits", "too frequent", "request limit"];
+const AUTH_HINTS: &[&str] = &[
+    "api key", "api-key", "apikey", "access-key", "invalid key", "invalid sign", "signature", "unauthorized",
+    "permission denied", "permissions for action",
+];
+const INVALID_SYMBOL_HINTS: &[&str] = &[
+    "invalid symbol", "unknown symbol", "unknown asset pair", "unknown pair", "symbol not", "invalid instrument",
+    "instrument id does not exist", "invalid_currency", "invalid currency pair", "market not found", "unknown product",
+];
+const INSUFFICIENT_BALANCE_HINTS: &[&str] = &[
+    "not enough balance", "not enough funds", "not enough exchange balance", "balance not enough",
+    "balance is not enough", "balance_not_enough", "balance insufficient",
+];
+// Nouns that make an "insufficient" about the account; a bare "insufficient" also covers pool liquidity
+const BALANCE_NOUNS: &[&str] = &["balance", "fund", "margin"];
+
+// Failures the retry, health and execution layers act on differently. The `Exchange` trait and the blockchain
+// module return these; anywhere else they travel inside anyhow and find() gets them back out of the chain
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum BotError {
+    #[error("{exchange} rate limit hit{}: {message}", .retry_after.map(|delay| format!(", retry after {:?}", delay)).unwrap_or_default())]
+    RateLimited { exchange: String, retry_after: Option<Duration>, message: String },
+    #[error("{exchange} rejected the API credentials: {message}")]
+    AuthFailed { exchange: String, message: String },
+    #[error("Insufficient balance on {exchange}: {message}")]
+    InsufficientBalance { exchange: String, message: String },
+    #[error("{exchange} does not know the symbol: {message}")]
+    InvalidSymbol { exchange: String, message: String },
+    #[error("{exchange} timed out: {message}")]
+    NetworkTimeout { exchange: String, message: String },
+    // Anything else the venue refused or failed on, including 5xx responses
+    #[error("{exchange} API error{}: {message}", .code.as_ref().map(|code| format!(" {}", code)).unwrap_or_default())]
+    ExchangeRejected { exchange: String, code: Option<String>, message: String },
+    // A simulation, submission or confirmation that failed on chain
+    #[error("{message}")]
+    ChainError { message: String },
+    // Everything without a category of its own: transport, parsing and local failures, kept whole so their
+    // causes can still be inspected
+    #[error("{0:#}")]
+    Other(Arc<anyhow::Error>),
+}
+
+pub type BotResult<T> = std::result::Result<T, BotError>;
+
+impl BotError {
+    // Sorts an error the venue reported in a response body, e.g. a Kraken error list or an order ack
+    pub fn rejected(exchange: &str, code: Option<String>, message: impl Into<String>) -> Self {
+        let message = message.into();
+        let text = message.to_lowercase();
+        let mentions = |hints: &[&str]| hints.iter().any(|hint| text.contains(hint));
+        let exchange = exchange.to_string();
+
+        if mentions(RATE_LIMIT_HINTS) {
+            BotError::RateLimited { exchange, retry_after: None, message }
+        } else if mentions(AUTH_HINTS) {
+            BotError::AuthFailed { exchange, message }
+        } else if mentions(INVALID_SYMBOL_HINTS) {
+            BotError::InvalidSymbol { exchange, message }
+        } else if mentions(INSUFFICIENT_BALANCE_HINTS) || insufficient_balance(&text) {
+            BotError::InsufficientBalance { exchange, message }
+        } else {
+            BotError::ExchangeRejected { exchange, code, message }
+        }
+    }
+
+    // Consumes a non-success response. The status decides rate limits and credentials; otherwise the body
+    // is sorted like any other rejection
+    pub async fn from_response(exchange: &str, response: reqwest::Response) -> Self {
+        let status = response.status();
+        let retry_after = crate::utils::retry_after(&response);
+        let body = response.text().await.unwrap_or_default();
+        Self::from_status(exchange, status, retry_after, body)
+    }
+
+    pub fn from_status(exchange: &str, status: reqwest::StatusCode, retry_after: Option<Duration>, body: String) -> Self {
+        match status.as_u16() {
+            // 418 is Binance's IP ban for ignoring earlier 429s
+            429 | 418 => BotError::RateLimited { exchange: exchange.to_string(), retry_after, message: body },
+            401 | 403 => BotError::AuthFailed { exchange: exchange.to_string(), message: body },
+            _ => match Self::rejected(exchange, body_code(&body), body) {
+                BotError::RateLimited { exchange, message, .. } => BotError::RateLimited { exchange, retry_after, message },
+                error => error,
+            },
+        }
+    }
+
+    pub fn chain(message: impl Into<String>) -> Self {
+        BotError::ChainError { message: message.into() }
+    }
+
+    pub fn find(error: &anyhow::Error) -> Option<&BotError> {
+        // Attached as context, the error is only reachable through anyhow's own downcast
+        let found = error.downcast_ref::<BotError>()
+            .or_else(|| error.chain().find_map(|cause| cause.downcast_ref::<BotError>()));
+        match found {
+            Some(BotError::Other(inner)) => Self::find(inner),
+            found => found,
+        }
+    }
+
+    // The error and its causes, continuing into the error an `Other` carries
+    pub fn causes(error: &anyhow::Error) -> Vec<&(dyn std::error::Error + 'static)> {
+        let mut causes = Vec::new();
+        for cause in error.chain() {
+            causes.push(cause);
+            if let Some(BotError::Other(inner)) = cause.downcast_ref::<BotError>() {
+                causes.extend(Self::causes(inner));
+            }
+        }
+        causes
+    }
+
+    pub fn exchange(&self) -> Option<&str> {
+        match self {
+            BotError::RateLimited { exchange, .. }
+            | BotError::AuthFailed { exchange, .. }
+            | BotError::InsufficientBalance { exchange, .. }
+            | BotError::InvalidSymbol { exchange, .. }
+            | BotError::NetworkTimeout { exchange, .. }
+            | BotError::ExchangeRejected { exchange, .. } => Some(exchange),
+            BotError::ChainError { .. } | BotError::Other(_) => None,
+        }
+    }
+
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            BotError::ExchangeRejected { code, .. } => code.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+// A BotError raised directly comes back out as itself; anything else, including a BotError with context
+// attached, is kept whole under Other
+impl From<anyhow::Error> for BotError {
+    fn from(error: anyhow::Error) -> Self {
+        if error.chain().nth(1).is_none() {
+            if let Some(error) = error.downcast_ref::<BotError>() {
+                return error.clone();
+            }
+        }
+        BotError::Other(Arc::new(error))
+    }
+}
+
+// Errors `?` meets in connector and chain code that have no category of their own
+macro_rules! unclassified {
+    ($($error:ty),* $(,)?) => {
+        $(
+            impl From<$error> for BotError {
+                fn from(error: $error) -> Self {
+                    BotError::Other(Arc::new(error.into()))
+                }
+            }
+        )*
+    };
+}
+
+unclassified!(
+    reqwest::Error,
+    serde_json::Error,
+    rust_decimal::Error,
+    std::num::ParseIntError,
+    std::num::ParseFloatError,
+    ethers::providers::ProviderError,
+    ethers::abi::Error,
+    ethers::signers::WalletError,
+    ethers::utils::ConversionError,
+);
+
+impl<M: ethers::providers::Middleware + 'static> From<ethers::contract::ContractError<M>> for BotError {
+    fn from(error: ethers::contract::ContractError<M>) -> Self {
+        BotError::Other(Arc::new(error.into()))
+    }
+}
+
+impl<M: ethers::providers::Middleware + 'static> From<ethers::contract::MulticallError<M>> for BotError {
+    fn from(error: ethers::contract::MulticallError<M>) -> Self {
+        BotError::Other(Arc::new(error.into()))
+    }
+}
+
+impl<M, S> From<ethers::middleware::signer::SignerMiddlewareError<M, S>> for BotError
+where
+    M: ethers::providers::Middleware + 'static,
+    S: ethers::signers::Signer + 'static,
+{
+    fn from(error: ethers::middleware::signer::SignerMiddlewareError<M, S>) -> Self {
+        BotError::Other(Arc::new(error.into()))
+    }
+}
+
+// "insufficient" followed within a few words by a balance, funds or margin, as in "Insufficient USDT balance"
+fn insufficient_balance(text: &str) -> bool {
+    let words: Vec<&str> = text.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()).collect();
+    words.iter().enumerate().any(|(i, word)| {
+        *word == "insufficient"
+            && words[i + 1..].iter().take(3).any(|next| BALANCE_NOUNS.iter().any(|noun| next.starts_with(noun)))
+    })
+}
+
+// The venue's own error code, under whichever key it uses
+fn body_code(body: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(body).ok()?;
+    ["code", "retCode", "label"].iter()
+        .filter_map(|key| value.get(*key))
+        .find_map(|code| match code {
+            serde_json::Value::Number(number) => Some(number.to_string()),
+            serde_json::Value::String(text) if !text.is_empty() => Some(text.clone()),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Venue failures sort by status, venue code and message, and stay findable behind context and conversions
+    #[tokio::test]
+    async fn sorts_venue_failures() {
+        let status = |code: u16| reqwest::StatusCode::from_u16(code).unwrap();
+        
+        let cases = [
+            (BotError::from_status("Binance", status(429), Some(Duration::from_secs(2)), "{}".to_string()), "rate limited"),
+            (BotError::from_status("Coinbase", status(401), None, "Unauthorized".to_string()), "auth"),
+            (BotError::from_status("Binance", status(400), None,
+                                   r#"{"code":-2010,"msg":"Account has insufficient balance for requested action."}"#.to_string()), "balance"),
+            (BotError::from_status("Binance", status(400), None, r#"{"code":-1121,"msg":"Invalid symbol."}"#.to_string()), "symbol"),
+            (BotError::from_status("Binance", status(400), None, r#"{"code":-2013,"msg":"Order does not exist."}"#.to_string()), "rejected"),
+            (BotError::from_status("Binance", status(502), None, "<html>502 Bad Gateway</html>".to_string()), "rejected"),
+            (BotError::rejected("Kraken", None, "EOrder:Insufficient funds"), "balance"),
+            (BotError::rejected("Kraken", None, "EAPI:Invalid key"), "auth"),
+            (BotError::rejected("Kraken", None, "EQuery:Unknown asset pair"), "symbol"),
+            (BotError::rejected("Bybit", Some("10006".to_string()), "Too many visits!"), "rate limited"),
+            (BotError::rejected("OKX", Some("51008".to_string()), "Order failed. Insufficient USDT balance in account"), "balance"),
+            (BotError::rejected("Gate.io", Some("BALANCE_NOT_ENOUGH".to_string()), "Not enough balance"), "balance"),
+            // A pool without depth is the route's problem, not the account's
+            (BotError::from_status("1inch", status(400), None,
+                                   r#"{"statusCode":400,"error":"Bad Request","description":"insufficient liquidity"}"#.to_string()), "rejected"),
+            (BotError::from_status("0x", status(400), None,
+                                   r#"{"code":100,"reason":"Validation Failed","validationErrors":[{"field":"buyAmount","code":1004,"reason":"INSUFFICIENT_ASSET_LIQUIDITY"}]}"#.to_string()), "rejected"),
+        ];
+        for (error, expected) in &cases {
+            let sorted = match error {
+                BotError::RateLimited { .. } => "rate limited",
+                BotError::AuthFailed { .. } => "auth",
+                BotError::InsufficientBalance { .. } => "balance",
+                BotError::InvalidSymbol { .. } => "symbol",
+                BotError::ExchangeRejected { .. } => "rejected",
+                BotError::NetworkTimeout { .. } | BotError::ChainError { .. } | BotError::Other(_) => "other",
+            };
+            assert!(sorted == *expected, "{:?} was sorted as {} instead of {}", error, sorted, expected);
+        }
+        assert!(matches!(&cases[0].0, BotError::RateLimited { retry_after: Some(delay), .. } if *delay == Duration::from_secs(2)),
+               "the 429 lost its Retry-After: {:?}", cases[0].0);
+        assert!(cases[4].0.code() == Some("-2013") && cases[4].0.to_string().starts_with("Binance API error -2013: "),
+               "the unknown order reads {:?} with code {:?}", cases[4].0.to_string(), cases[4].0.code());
+        
+        // Found again behind context, and a timed-out call is both a timeout and of unknown outcome
+        let wrapped = anyhow::Error::new(crate::exchanges::OutcomeUnknown).context(cases[5].0.clone());
+        assert!(BotError::find(&wrapped).map(ToString::to_string) == Some(cases[5].0.to_string())
+                  && crate::exchanges::outcome_unknown(&wrapped),
+               "a 5xx placement came back as {:?}", BotError::find(&wrapped));
+        // Converted back from anyhow, a bare BotError is itself again and one with context keeps it reachable
+        let bare = BotError::from(anyhow::Error::new(cases[2].0.clone()));
+        let kept = anyhow::Error::new(BotError::from(anyhow::Error::new(cases[2].0.clone()).context("placing the buy leg")));
+        assert!(matches!(bare, BotError::InsufficientBalance { .. })
+                  && matches!(BotError::find(&kept), Some(BotError::InsufficientBalance { .. })),
+               "converted errors came back as {:?} and {:?}", bare, BotError::find(&kept));
+        let timed_out = crate::exchanges::ExchangeManager::new().call("alpha", "get_price", Duration::from_millis(10), async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            Ok(())
+        }).await.unwrap_err();
+        assert!(matches!(BotError::find(&timed_out), Some(BotError::NetworkTimeout { exchange, .. }) if exchange == "alpha")
+                  && crate::exchanges::outcome_unknown(&timed_out),
+               "a timed-out call came back as {:?}", BotError::find(&timed_out));
+    }
+}
@@ -4,16 +4,191 @@ use chrono::Utc;
 use reqwest::Client;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
 
-use crate::config::ExchangeConfig;
-use crate::exchanges::{Exchange, TradingFees};
-use crate::models::{Balance, OrderBook, OrderBookLevel, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::config::{ExchangeConfig, TimeInForce};
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::{AccountEvent, Exchange, OrderAmount, OrderOptions, OrderRequest, OutcomeUnknown, SymbolRules, TradingFees, TransferStatus, WithdrawalLimits};
+use crate::models::{Balance, OpenOrder, OrderBook, OrderBookLevel, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::utils::{self, RateLimiter};
+
+const MAX_STREAM_BACKOFF: Duration = Duration::from_secs(60);
+// REQUEST_WEIGHT limit per IP; the header below reports how much of it is already used
+const REQUEST_WEIGHT_PER_MINUTE: u32 = 6000;
+const USED_WEIGHT_HEADER: &str = "X-MBX-USED-WEIGHT-1M";
+const BOOK_TICKER_WEIGHT: u32 = 2;
+const ACCOUNT_WEIGHT: u32 = 20;
+const NEW_ORDER_WEIGHT: u32 = 1;
+const QUERY_ORDER_WEIGHT: u32 = 4;
+const CANCEL_ORDER_WEIGHT: u32 = 1;
+// Listing open orders costs far more when it spans every symbol
+const OPEN_ORDERS_WEIGHT: u32 = 6;
+const ALL_OPEN_ORDERS_WEIGHT: u32 = 80;
+const EXCHANGE_INFO_WEIGHT: u32 = 20;
+const DEPOSIT_ADDRESS_WEIGHT: u32 = 10;
+const COIN_CONFIG_WEIGHT: u32 = 10;
+const WITHDRAW_WEIGHT: u32 = 1;
+const LISTEN_KEY_WEIGHT: u32 = 2;
+const SERVER_TIME_WEIGHT: u32 = 1;
+const COMMISSION_WEIGHT: u32 = 20;
+// Fee tiers move with 30-day volume and BNB holdings, so an hour old rate is still accurate
+const TRADING_FEE_TTL: Duration = Duration::from_secs(3600);
+// The clock offset is measured before the first signed request and refreshed after this long
+const CLOCK_SYNC_INTERVAL: Duration = Duration::from_secs(600);
+// "Timestamp for this request is outside of the recvWindow"
+const TIMESTAMP_ERROR_CODE: i64 = -1021;
+// "Order does not exist"
+const UNKNOWN_ORDER_CODE: i64 = -2013;
+// Listen keys expire after 60 minutes without a keepalive
+const LISTEN_KEY_KEEPALIVE: Duration = Duration::from_secs(30 * 60);
+// Withdrawal fees change rarely, and the coin config endpoint is expensive
+const WITHDRAWAL_FEE_TTL: Duration = Duration::from_secs(600);
+// Suspended deposits and withdrawals are the first sign of a halt, so they are re-read more often than fees
+const TRANSFER_STATUS_TTL: Duration = Duration::from_secs(60);
+// Share of malformed depth levels above which the whole book is rejected rather than trimmed
+const MAX_MALFORMED_LEVEL_RATIO: f64 = 0.05;
+
+fn depth_weight(limit: usize) -> u32 {
+    match limit {
+        0..=100 => 5,
+        101..=500 => 25,
+        501..=1000 => 50,
+        _ => 250,
+    }
+}
+
+// Hex HMAC-SHA256 over the query string, shared with venues that copied Binance's signing scheme
+pub(crate) fn sign_query(secret: &str, query_string: &str) -> String {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    type HmacSha256 = Hmac<Sha256>;
+
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .expect("HMAC can take key of any size");
+    mac.update(query_string.as_bytes());
+
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn parse_depth_level(level: &[String; 2]) -> Result<OrderBookLevel> {
+    let price = Decimal::from_str(&level[0])?;
+    let quantity = Decimal::from_str(&level[1])?;
+    if price <= Decimal::ZERO || quantity <= Decimal::ZERO {
+        anyhow::bail!("non-positive price or quantity");
+    }
+    Ok(OrderBookLevel { price, quantity })
+}
+
+// Malformed levels are dropped and counted; a zeroed level would otherwise pass as free liquidity
+pub(crate) fn parse_depth_levels(side: &str, levels: &[[String; 2]]) -> Result<Vec<OrderBookLevel>> {
+    let mut parsed = Vec::with_capacity(levels.len());
+    let mut malformed = 0;
+    
+    for level in levels {
+        match parse_depth_level(level) {
+            Ok(level) => parsed.push(level),
+            Err(e) => {
+                malformed += 1;
+                tracing::debug!("Skipping malformed Binance {} level {:?}: {}", side, level, e);
+            }
+        }
+    }
+    
+    if malformed > 0 {
+        if malformed as f64 > levels.len() as f64 * MAX_MALFORMED_LEVEL_RATIO {
+            anyhow::bail!("Binance order book has {} malformed {} levels out of {}", malformed, side, levels.len());
+        }
+        warn!("Skipped {} malformed Binance {} levels out of {}", malformed, side, levels.len());
+    }
+    
+    Ok(parsed)
+}
+
+// Unlike depth levels a bad balance is never skipped: a missing asset would read as an empty wallet
+pub(crate) fn parse_balances(balances: Vec<BinanceBalance>) -> Result<HashMap<String, Balance>> {
+    let mut parsed = HashMap::new();
+    
+    for balance in balances {
+        let free = Decimal::from_str(&balance.free)
+            .map_err(|e| anyhow::anyhow!("Invalid Binance free balance {:?} for {}: {}", balance.free, balance.asset, e))?;
+        let locked = Decimal::from_str(&balance.locked)
+            .map_err(|e| anyhow::anyhow!("Invalid Binance locked balance {:?} for {}: {}", balance.locked, balance.asset, e))?;
+        if free < Decimal::ZERO || locked < Decimal::ZERO {
+            anyhow::bail!("Negative Binance balance for {}: {} free, {} locked", balance.asset, free, locked);
+        }
+        let total = free + locked;
+        
+        if total > Decimal::ZERO {
+            parsed.insert(balance.asset.clone(), Balance {
+                asset: balance.asset,
+                free,
+                locked,
+                total,
+                usd_value: Decimal::ZERO,
+            });
+        }
+    }
+    
+    Ok(parsed)
+}
+
+// Offset between Binance's clock and ours, added to the timestamp of every signed request
+#[derive(Debug, Default)]
+pub(crate) struct ServerClock {
+    offset_ms: AtomicI64,
+    last_sync: Mutex<Option<Instant>>,
+}
+
+impl ServerClock {
+    // Assumes the server read its clock halfway through the round trip
+    pub(crate) fn record(&self, sent_at_ms: i64, server_time_ms: i64, received_at_ms: i64) -> i64 {
+        let offset = server_time_ms - (sent_at_ms + received_at_ms) / 2;
+        self.offset_ms.store(offset, Ordering::Relaxed);
+        offset
+    }
+    
+    pub(crate) fn timestamp(&self, local_ms: i64) -> i64 {
+        local_ms + self.offset_ms.load(Ordering::Relaxed)
+    }
+    
+    // Failed attempts count too, so an unreachable time endpoint is not retried before every request
+    pub(crate) fn needs_sync(&self) -> bool {
+        self.last_sync.lock().unwrap().map_or(true, |at| at.elapsed() >= CLOCK_SYNC_INTERVAL)
+    }
+    
+    fn attempted(&self) {
+        *self.last_sync.lock().unwrap() = Some(Instant::now());
+    }
+}
+
+pub(crate) fn error_code(error_text: &str) -> Option<i64> {
+    serde_json::from_str::<BinanceError>(error_text).ok().map(|error| error.code)
+}
 
 pub struct BinanceExchange {
     config: ExchangeConfig,
     client: Client,
+    clock: Arc<ServerClock>,
+    price_cache: Arc<RwLock<HashMap<String, Price>>>,
+    price_listeners: Arc<RwLock<Vec<tokio::sync::mpsc::Sender<Price>>>>,
+    stream_task: Option<tokio::task::JoinHandle<()>>,
+    rate_limiter: Arc<RateLimiter>,
+    symbol_rules: RwLock<HashMap<String, SymbolRules>>,
+    withdrawal_fees: RwLock<HashMap<String, (Instant, Decimal)>>,
+    transfer_statuses: RwLock<HashMap<String, (Instant, TransferStatus)>>,
+    trading_fees: RwLock<HashMap<String, (Instant, TradingFees)>>,
+    account_listeners: Arc<RwLock<Vec<mpsc::Sender<AccountEvent>>>>,
+    user_stream_task: Mutex<Option<tokio::task::JoinHandle<()>>>,
+    // Orders placed while the user data stream runs that have not reached a terminal state; their
+    // status is re-read after every reconnect
+    pending_orders: Arc<Mutex<HashSet<String>>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -26,6 +201,142 @@ struct BinanceTicker {
     volume: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct BinanceStreamMessage {
+    data: BinanceBookTickerEvent,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceBookTickerEvent {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "b")]
+    bid_price: String,
+    #[serde(rename = "a")]
+    ask_price: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "e")]
+enum BinanceUserEvent {
+    #[serde(rename = "executionReport")]
+    ExecutionReport(BinanceExecutionReport),
+    #[serde(rename = "outboundAccountPosition")]
+    AccountPosition(BinanceAccountPosition),
+    #[serde(rename = "listenKeyExpired")]
+    ListenKeyExpired,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceExecutionReport {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "S")]
+    side: String,
+    #[serde(rename = "q")]
+    quantity: String,
+    #[serde(rename = "p")]
+    price: String,
+    #[serde(rename = "X")]
+    status: String,
+    #[serde(rename = "i")]
+    order_id: u64,
+    #[serde(rename = "z")]
+    executed_qty: String,
+    #[serde(rename = "Z")]
+    cummulative_quote_qty: String,
+    // Commission of this execution only, not of the whole order
+    #[serde(rename = "n", default)]
+    commission: Option<String>,
+    #[serde(rename = "N", default)]
+    commission_asset: Option<String>,
+    #[serde(rename = "T")]
+    transaction_time: i64,
+    #[serde(rename = "c", default)]
+    client_order_id: String,
+    // Set on cancellations, where `c` is the cancel request's own id
+    #[serde(rename = "C", default)]
+    original_client_order_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceAccountPosition {
+    #[serde(rename = "B")]
+    balances: Vec<BinanceStreamBalance>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceStreamBalance {
+    #[serde(rename = "a")]
+    asset: String,
+    #[serde(rename = "f")]
+    free: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BinanceCommissionRates {
+    standard_commission: BinanceCommission,
+    tax_commission: BinanceCommission,
+    discount: BinanceCommissionDiscount,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceCommission {
+    maker: String,
+    taker: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceCommissionDiscount {
+    enabled_for_account: bool,
+    enabled_for_symbol: bool,
+    discount: String,
+}
+
+// The BNB discount only scales the standard commission, and only when paying fees in BNB is
+// switched on for the account and allowed on the symbol
+pub(crate) fn commission_fees(rates: &BinanceCommissionRates) -> Result<TradingFees> {
+    let rate = |value: &str| Decimal::from_str(value)
+        .map_err(|e| anyhow::anyhow!("Invalid Binance commission rate {:?}: {}", value, e));
+    let discount = if rates.discount.enabled_for_account && rates.discount.enabled_for_symbol {
+        rate(&rates.discount.discount)?
+    } else {
+        Decimal::ONE
+    };
+    
+    Ok(TradingFees {
+        maker_fee: rate(&rates.standard_commission.maker)? * discount + rate(&rates.tax_commission.maker)?,
+        taker_fee: rate(&rates.standard_commission.taker)? * discount + rate(&rates.tax_commission.taker)?,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceError {
+    code: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceServerTime {
+    server_time: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceListenKey {
+    listen_key: String,
+}
+
+pub(crate) enum UserStreamMessage {
+    Event(AccountEvent),
+    ListenKeyExpired,
+    Ignored,
+}
+
 #[derive(Debug, Deserialize)]
 struct BinanceOrderBook {
     bids: Vec<[String; 2]>,
@@ -33,7 +344,7 @@ struct BinanceOrderBook {
 }
 
 #[derive(Debug, Deserialize)]
-struct BinanceBalance {
+pub(crate) struct BinanceBalance {
     asset: String,
     free: String,
     locked: String,
@@ -50,11 +361,19 @@ struct BinanceOrderRequest {
     side: String,
     #[serde(rename = "type")]
     order_type: String,
-    quantity: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quantity: Option<String>,
+    // Market orders only: spend this much of the quote asset instead of trading `quantity`
+    #[serde(rename = "quoteOrderQty", skip_serializing_if = "Option::is_none")]
+    quote_order_qty: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     price: Option<String>,
-    #[serde(rename = "timeInForce")]
+    #[serde(rename = "timeInForce", skip_serializing_if = "Option::is_none")]
     time_in_force: Option<String>,
-    timestamp: u64,
+    #[serde(rename = "newClientOrderId", skip_serializing_if = "Option::is_none")]
+    client_order_id: Option<String>,
+    #[serde(rename = "newOrderRespType")]
+    response_type: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -63,65 +382,725 @@ struct BinanceOrderResponse {
     order_id: u64,
     symbol: String,
     status: String,
+    #[serde(rename = "origQty")]
+    orig_qty: String,
     #[serde(rename = "executedQty")]
     executed_qty: String,
+    #[serde(rename = "cummulativeQuoteQty", default)]
+    cummulative_quote_qty: Option<String>,
     price: String,
     side: String,
+    #[serde(rename = "transactTime", default)]
+    transact_time: Option<i64>,
+    // Creation time, only reported when orders are queried
+    #[serde(default)]
+    time: Option<i64>,
+    #[serde(default)]
+    fills: Vec<BinanceFill>,
+    #[serde(rename = "clientOrderId", default)]
+    client_order_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceExchangeInfo {
+    symbols: Vec<BinanceSymbolInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceSymbolInfo {
+    symbol: String,
+    filters: Vec<BinanceSymbolFilter>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "filterType")]
+enum BinanceSymbolFilter {
+    #[serde(rename = "PRICE_FILTER", rename_all = "camelCase")]
+    Price { tick_size: String },
+    #[serde(rename = "LOT_SIZE", rename_all = "camelCase")]
+    LotSize { min_qty: String, max_qty: String, step_size: String },
+    // Older symbols still report MIN_NOTIONAL, newer ones NOTIONAL
+    #[serde(rename = "MIN_NOTIONAL", rename_all = "camelCase")]
+    MinNotional { min_notional: String },
+    #[serde(rename = "NOTIONAL", rename_all = "camelCase")]
+    Notional { min_notional: String },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceDepositAddress {
+    address: String,
+    #[serde(default)]
+    tag: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceCoinConfig {
+    coin: String,
+    deposit_all_enable: bool,
+    withdraw_all_enable: bool,
+    network_list: Vec<BinanceCoinNetwork>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceCoinNetwork {
+    is_default: bool,
+    withdraw_enable: bool,
+    withdraw_fee: String,
+    withdraw_min: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceWithdrawResponse {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceFill {
+    commission: String,
+    #[serde(rename = "commissionAsset")]
+    commission_asset: String,
 }
 
 impl BinanceExchange {
-    pub fn new(config: ExchangeConfig) -> Self {
-        Self {
+    pub fn new(config: ExchangeConfig) -> Result<Self> {
+        let mut exchange = Self {
+            client: utils::http_client_with(&config.http())?,
             config,
-            client: Client::new(),
+            clock: Arc::new(ServerClock::default()),
+            price_cache: Arc::new(RwLock::new(HashMap::new())),
+            price_listeners: Arc::new(RwLock::new(Vec::new())),
+            stream_task: None,
+            rate_limiter: utils::rate_limiter("binance", REQUEST_WEIGHT_PER_MINUTE, Duration::from_secs(60)),
+            symbol_rules: RwLock::new(HashMap::new()),
+            withdrawal_fees: RwLock::new(HashMap::new()),
+            transfer_statuses: RwLock::new(HashMap::new()),
+            trading_fees: RwLock::new(HashMap::new()),
+            account_listeners: Arc::new(RwLock::new(Vec::new())),
+            user_stream_task: Mutex::new(None),
+            pending_orders: Arc::new(Mutex::new(HashSet::new())),
+        };
+        exchange.stream_task = exchange.start_price_stream();
+        Ok(exchange)
+    }
+
+    // Shares the client, rate limiter and account state but runs no streams of its own; the user data
+    // stream task owns one for its REST calls
+    fn rest_handle(&self) -> Self {
+        Self {
+            config: self.config.clone(),
+            client: self.client.clone(),
+            clock: self.clock.clone(),
+            price_cache: self.price_cache.clone(),
+            price_listeners: self.price_listeners.clone(),
+            stream_task: None,
+            rate_limiter: self.rate_limiter.clone(),
+            symbol_rules: RwLock::new(HashMap::new()),
+            withdrawal_fees: RwLock::new(HashMap::new()),
+            transfer_statuses: RwLock::new(HashMap::new()),
+            trading_fees: RwLock::new(HashMap::new()),
+            account_listeners: self.account_listeners.clone(),
+            user_stream_task: Mutex::new(None),
+            pending_orders: self.pending_orders.clone(),
+        }
+    }
+
+    fn start_user_stream(&self) {
+        let Some(websocket_url) = self.config.websocket_url.clone() else {
+            return;
+        };
+        if !self.config.user_data_stream || self.config.api_key.is_empty() {
+            return;
+        }
+        
+        let mut task = self.user_stream_task.lock().unwrap();
+        if task.is_none() {
+            *task = Some(tokio::spawn(Self::run_user_stream(Arc::new(self.rest_handle()), websocket_url)));
+        }
+    }
+
+    // A listen key, kept alive every 30 minutes, names the account's websocket. Every (re)connect
+    // re-reads the pending orders before announcing itself, so fills during the gap are not lost.
+    async fn run_user_stream(rest: Arc<Self>, websocket_url: String) {
+        use futures::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+        
+        let mut backoff = Duration::from_secs(1);
+        let mut commissions = HashMap::new();
+        
+        loop {
+            let connection = match rest.create_listen_key().await {
+                Ok(listen_key) => {
+                    let url = format!("{}/ws/{}", websocket_url.trim_end_matches('/'), listen_key);
+                    tokio_tungstenite::connect_async(&url).await
+                        .map(|(stream, _)| (stream, listen_key))
+                        .map_err(anyhow::Error::from)
+                },
+                Err(e) => Err(e),
+            };
+            
+            match connection {
+                Ok((mut stream, listen_key)) => {
+                    info!("Connected to Binance user data stream");
+                    backoff = Duration::from_secs(1);
+                    rest.resync_pending_orders().await;
+                    rest.publish_account(AccountEvent::Connected { exchange: rest.name().to_string() }).await;
+                    
+                    let mut keepalive = tokio::time::interval_at(tokio::time::Instant::now() + LISTEN_KEY_KEEPALIVE, LISTEN_KEY_KEEPALIVE);
+                    loop {
+                        tokio::select! {
+                            message = stream.next() => match message {
+                                Some(Ok(Message::Text(text))) => match rest.user_stream_message(&text, &mut commissions) {
+                                    Ok(UserStreamMessage::Event(event)) => rest.publish_account(event).await,
+                                    Ok(UserStreamMessage::ListenKeyExpired) => {
+                                        warn!("Binance listen key expired");
+                                        break;
+                                    },
+                                    Ok(UserStreamMessage::Ignored) => {},
+                                    Err(e) => warn!("Unexpected Binance user stream message: {}", e),
+                                },
+                                Some(Ok(Message::Ping(payload))) => {
+                                    let _ = stream.send(Message::Pong(payload)).await;
+                                },
+                                Some(Ok(Message::Close(_))) | None => break,
+                                Some(Ok(_)) => {},
+                                Some(Err(e)) => {
+                                    warn!("Binance user data stream error: {}", e);
+                                    break;
+                                }
+                            },
+                            _ = keepalive.tick() => {
+                                if let Err(e) = rest.keep_alive_listen_key(&listen_key).await {
+                                    warn!("Failed to keep the Binance listen key alive: {}", e);
+                                    break;
+                                }
+                            },
+                        }
+                    }
+                    
+                    rest.publish_account(AccountEvent::Disconnected { exchange: rest.name().to_string() }).await;
+                    warn!("Binance user data stream disconnected, reconnecting in {:?}", backoff);
+                },
+                Err(e) => {
+                    warn!("Failed to connect to Binance user data stream, retrying in {:?}: {}", backoff, e);
+                }
+            }
+            
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_STREAM_BACKOFF);
+        }
+    }
+
+    async fn create_listen_key(&self) -> Result<String> {
+        let url = format!("{}/api/v3/userDataStream", self.config.api_url);
+        let response = self.send(self.client.post(&url).header("X-MBX-APIKEY", &self.config.api_key), LISTEN_KEY_WEIGHT).await?;
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Binance", response).await.into());
+        }
+        let key: BinanceListenKey = response.json().await?;
+        Ok(key.listen_key)
+    }
+
+    async fn keep_alive_listen_key(&self, listen_key: &str) -> Result<()> {
+        let url = format!("{}/api/v3/userDataStream?listenKey={}", self.config.api_url, listen_key);
+        let response = self.send(self.client.put(&url).header("X-MBX-APIKEY", &self.config.api_key), LISTEN_KEY_WEIGHT).await?;
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Binance", response).await.into());
+        }
+        Ok(())
+    }
+
+    async fn resync_pending_orders(&self) {
+        let pending: Vec<String> = self.pending_orders.lock().unwrap().iter().cloned().collect();
+        
+        for order_id in pending {
+            match self.get_order_status(&order_id).await {
+                Ok(trade) => {
+                    if !matches!(trade.status, TradeStatus::Pending) {
+                        self.pending_orders.lock().unwrap().remove(&order_id);
+                    }
+                    self.publish_account(AccountEvent::Order(trade)).await;
+                },
+                Err(e) => warn!("Failed to re-read Binance order {} after reconnecting: {}", order_id, e),
+            }
+        }
+    }
+
+    async fn publish_account(&self, event: AccountEvent) {
+        let listeners = self.account_listeners.read().unwrap().clone();
+        for listener in listeners {
+            let _ = listener.send(event.clone()).await;
+        }
+    }
+
+    // `commissions` accumulates the per-execution commission of each order until it is final
+    pub(crate) fn user_stream_message(&self, text: &str, commissions: &mut HashMap<String, (Decimal, String)>) -> Result<UserStreamMessage> {
+        let report = match serde_json::from_str::<BinanceUserEvent>(text)? {
+            BinanceUserEvent::ExecutionReport(report) => report,
+            BinanceUserEvent::AccountPosition(position) => {
+                let mut free = HashMap::new();
+                for balance in position.balances {
+                    let amount = Decimal::from_str(&balance.free)
+                        .map_err(|e| anyhow::anyhow!("Invalid Binance free balance {:?} for {}: {}", balance.free, balance.asset, e))?;
+                    free.insert(balance.asset, amount);
+                }
+                return Ok(UserStreamMessage::Event(AccountEvent::Balances { exchange: self.name().to_string(), free }));
+            },
+            BinanceUserEvent::ListenKeyExpired => return Ok(UserStreamMessage::ListenKeyExpired),
+            BinanceUserEvent::Other => return Ok(UserStreamMessage::Ignored),
+        };
+        // Orders on symbols this bot does not trade are someone else's
+        if self.pair_from_symbol(&report.symbol).is_none() {
+            return Ok(UserStreamMessage::Ignored);
+        }
+        
+        let order_id = format!("{}:{}", report.symbol, report.order_id);
+        if let (Some(commission), Some(asset)) = (&report.commission, &report.commission_asset) {
+            let commission = Decimal::from_str(commission)?;
+            if commission > Decimal::ZERO {
+                let entry = commissions.entry(order_id.clone()).or_insert((Decimal::ZERO, asset.clone()));
+                entry.0 += commission;
+            }
+        }
+        
+        let mut trade = self.order_to_trade(BinanceOrderResponse {
+            order_id: report.order_id,
+            symbol: report.symbol,
+            status: report.status,
+            orig_qty: report.quantity,
+            executed_qty: report.executed_qty,
+            cummulative_quote_qty: Some(report.cummulative_quote_qty),
+            price: report.price,
+            side: report.side,
+            transact_time: Some(report.transaction_time),
+            time: None,
+            fills: Vec::new(),
+            client_order_id: Some(report.original_client_order_id)
+                .filter(|id| !id.is_empty())
+                .or(Some(report.client_order_id).filter(|id| !id.is_empty())),
+        })?;
+        if let Some((fee, asset)) = commissions.get(&order_id) {
+            trade.fee_amount = *fee;
+            trade.fee_asset = Some(asset.clone());
+        }
+        if !matches!(trade.status, TradeStatus::Pending) {
+            commissions.remove(&order_id);
+            self.pending_orders.lock().unwrap().remove(&order_id);
+        }
+        
+        Ok(UserStreamMessage::Event(AccountEvent::Order(trade)))
+    }
+
+    fn start_price_stream(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let websocket_url = self.config.websocket_url.clone()?;
+        
+        let symbols: HashMap<String, TradingPair> = self.config.configured_pairs().into_iter()
+            .map(|pair| (self.convert_symbol(&pair), pair))
+            .collect();
+        
+        if symbols.is_empty() {
+            return None;
+        }
+        
+        let streams = symbols.keys()
+            .map(|symbol| format!("{}@bookTicker", symbol.to_lowercase()))
+            .collect::<Vec<_>>()
+            .join("/");
+        let url = format!("{}/stream?streams={}", websocket_url.trim_end_matches('/'), streams);
+        
+        let cache = self.price_cache.clone();
+        let listeners = self.price_listeners.clone();
+        Some(tokio::spawn(Self::run_price_stream(url, symbols, cache, listeners)))
+    }
+
+    async fn run_price_stream(
+        url: String,
+        symbols: HashMap<String, TradingPair>,
+        cache: Arc<RwLock<HashMap<String, Price>>>,
+        listeners: Arc<RwLock<Vec<tokio::sync::mpsc::Sender<Price>>>>,
+    ) {
+        use futures::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+        
+        let mut backoff = Duration::from_secs(1);
+        
+        loop {
+            match tokio_tungstenite::connect_async(&url).await {
+                Ok((mut stream, _)) => {
+                    info!("Connected to Binance bookTicker stream for {} pairs", symbols.len());
+                    backoff = Duration::from_secs(1);
+                    
+                    while let Some(message) = stream.next().await {
+                        match message {
+                            Ok(Message::Text(text)) => Self::handle_book_ticker(&text, &symbols, &cache, &listeners),
+                            Ok(Message::Ping(payload)) => {
+                                let _ = stream.send(Message::Pong(payload)).await;
+                            },
+                            Ok(Message::Close(_)) => break,
+                            Ok(_) => {},
+                            Err(e) => {
+                                warn!("Binance price stream error: {}", e);
+                                break;
+                            }
+                        }
+                    }
+                    
+                    warn!("Binance price stream disconnected, reconnecting in {:?}", backoff);
+                },
+                Err(e) => {
+                    warn!("Failed to connect to Binance price stream, retrying in {:?}: {}", backoff, e);
+                }
+            }
+            
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_STREAM_BACKOFF);
+        }
+    }
+
+    fn handle_book_ticker(
+        text: &str,
+        symbols: &HashMap<String, TradingPair>,
+        cache: &RwLock<HashMap<String, Price>>,
+        listeners: &RwLock<Vec<tokio::sync::mpsc::Sender<Price>>>,
+    ) {
+        let event = match serde_json::from_str::<BinanceStreamMessage>(text) {
+            Ok(message) => message.data,
+            Err(e) => {
+                warn!("Unexpected Binance stream message: {}", e);
+                return;
+            }
+        };
+        
+        let pair = match symbols.get(&event.symbol) {
+            Some(pair) => pair,
+            None => return,
+        };
+        
+        let (bid, ask) = match (Decimal::from_str(&event.bid_price), Decimal::from_str(&event.ask_price)) {
+            (Ok(bid), Ok(ask)) => (bid, ask),
+            _ => return,
+        };
+        
+        let price = Price {
+            exchange: "binance".to_string(),
+            pair: pair.clone(),
+            bid,
+            ask,
+            timestamp: Utc::now(),
+            volume_24h: None,
+            fetch_latency: None,
+        };
+        
+        // A full channel only means the scanner is behind; it re-reads the cache on the next sweep
+        for listener in listeners.read().unwrap().iter() {
+            let _ = listener.try_send(price.clone());
+        }
+        
+        cache.write().unwrap().insert(pair.symbol.clone(), price);
+    }
+
+    fn cached_price(&self, pair: &TradingPair) -> Option<Price> {
+        let price = self.price_cache.read().unwrap().get(&pair.symbol).cloned()?;
+        let age = Utc::now().signed_duration_since(price.timestamp);
+        
+        if age <= chrono::Duration::milliseconds(self.config.stream_staleness_ms as i64) {
+            Some(price)
+        } else {
+            None
         }
     }
 
     fn create_signature(&self, query_string: &str) -> String {
-        use hmac::{Hmac, Mac};
-        use sha2::Sha256;
+        sign_query(&self.config.api_secret, query_string)
+    }
+
+    // Every REST call goes through here so request weight is accounted before sending and
+    // corrected from the venue's own count afterwards
+    async fn send(&self, request: reqwest::RequestBuilder, weight: u32) -> Result<reqwest::Response> {
+        self.rate_limiter.acquire(weight).await;
+        
+        let response = request.send().await?;
         
-        type HmacSha256 = Hmac<Sha256>;
+        let used_weight = response.headers().get(USED_WEIGHT_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u32>().ok());
+        if let Some(used_weight) = used_weight {
+            self.rate_limiter.sync_used(used_weight);
+        }
         
-        let mut mac = HmacSha256::new_from_slice(self.config.api_secret.as_bytes())
-            .expect("HMAC can take key of any size");
-        mac.update(query_string.as_bytes());
+        // 418 means the IP is already banned; retrying would only extend the ban
+        if response.status().as_u16() == 418 {
+            return Err(BotError::RateLimited {
+                exchange: self.name().to_string(),
+                retry_after: utils::retry_after(&response),
+                message: "IP banned for exceeding rate limits".to_string(),
+            }.into());
+        }
         
-        let result = mac.finalize();
-        hex::encode(result.into_bytes())
+        Ok(response)
+    }
+
+    // For idempotent reads only. `build` runs once per attempt so signed requests get a fresh timestamp.
+    async fn send_with_retry<F>(&self, build: F, weight: u32) -> Result<reqwest::Response>
+    where
+        F: Fn() -> Result<reqwest::RequestBuilder>,
+    {
+        let build = &build;
+        utils::with_retry(self.name(), &self.config.retry, move || async move {
+            self.send(build()?, weight).await
+        }).await
     }
 
-    async fn make_signed_request<T>(&self, endpoint: &str, params: &HashMap<String, String>) -> Result<T>
+    async fn make_signed_request<T>(&self, endpoint: &str, params: &HashMap<String, String>, weight: u32) -> Result<T>
     where
         T: for<'de> Deserialize<'de>,
     {
-        let timestamp = chrono::Utc::now().timestamp_millis();
-        let mut query_params = params.clone();
-        query_params.insert("timestamp".to_string(), timestamp.to_string());
+        self.make_signed_request_with_method(reqwest::Method::GET, endpoint, params, weight).await
+    }
+
+    async fn sync_clock(&self) -> Result<()> {
+        self.clock.attempted();
+        let url = format!("{}/api/v3/time", self.config.api_url);
         
-        let query_string = serde_urlencoded::to_string(&query_params)?;
-        let signature = self.create_signature(&query_string);
+        let sent_at = Utc::now().timestamp_millis();
+        let response = self.send(self.client.get(&url), SERVER_TIME_WEIGHT).await?;
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Binance", response).await.into());
+        }
+        let server_time: BinanceServerTime = response.json().await?;
+        let received_at = Utc::now().timestamp_millis();
         
-        let url = format!("{}{}?{}&signature={}", self.config.api_url, endpoint, query_string, signature);
+        let offset = self.clock.record(sent_at, server_time.server_time, received_at);
+        if offset.abs() > 1000 {
+            warn!("Binance server time is {}ms ahead of the local clock; signed requests are corrected", offset);
+        }
+        Ok(())
+    }
+
+    async fn make_signed_request_with_method<T>(&self, method: reqwest::Method, endpoint: &str, params: &HashMap<String, String>, weight: u32) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if self.clock.needs_sync() {
+            if let Err(e) = self.sync_clock().await {
+                warn!("Failed to sync with Binance server time, signing with the previous offset: {}", e);
+            }
+        }
         
-        let response = self.client
-            .get(&url)
-            .header("X-MBX-APIKEY", &self.config.api_key)
-            .send()
-            .await?;
+        let build = || -> Result<reqwest::RequestBuilder> {
+            let timestamp = self.clock.timestamp(Utc::now().timestamp_millis());
+            let mut query_params = params.clone();
+            query_params.insert("timestamp".to_string(), timestamp.to_string());
+            if let Some(recv_window) = self.config.recv_window_ms {
+                query_params.insert("recvWindow".to_string(), recv_window.to_string());
+            }
+            
+            let query_string = serde_urlencoded::to_string(&query_params)?;
+            let signature = self.create_signature(&query_string);
+            
+            let url = format!("{}{}?{}&signature={}", self.config.api_url, endpoint, query_string, signature);
+            
+            Ok(self.client
+                .request(method.clone(), &url)
+                .header("X-MBX-APIKEY", &self.config.api_key))
+        };
         
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            anyhow::bail!("Binance API error: {}", error_text);
+        let mut resynced = false;
+        loop {
+            // Order placement and cancellation are sent exactly once; only status and account reads are retried
+            let response = if method == reqwest::Method::GET {
+                self.send_with_retry(build, weight).await?
+            } else {
+                self.send(build()?, weight).await?
+            };
+            
+            if !response.status().is_success() {
+                let status = response.status();
+                let retry_after = utils::retry_after(&response);
+                let error_text = response.text().await?;
+                // Rejected before it was processed, so even an order is safe to send again
+                if !resynced && error_code(&error_text) == Some(TIMESTAMP_ERROR_CODE) {
+                    warn!("Binance rejected the request timestamp, resyncing the clock and retrying: {}", error_text);
+                    self.sync_clock().await?;
+                    resynced = true;
+                    continue;
+                }
+                if resynced && error_code(&error_text) == Some(TIMESTAMP_ERROR_CODE) {
+                    anyhow::bail!("Binance rejected the request timestamp even after resyncing the clock: {}", error_text);
+                }
+                let error = BotError::from_status(self.name(), status, retry_after, error_text);
+                // On a 5xx Binance itself does not know whether the order or cancel went through
+                if status.is_server_error() && method != reqwest::Method::GET {
+                    return Err(anyhow::Error::new(OutcomeUnknown).context(error));
+                }
+                return Err(error.into());
+            }
+            
+            let result = response.json::<T>().await?;
+            return Ok(result);
         }
-        
-        let result = response.json::<T>().await?;
-        Ok(result)
     }
 
     fn convert_symbol(&self, pair: &TradingPair) -> String {
         format!("{}{}", pair.base, pair.quote)
     }
+
+    fn pair_from_symbol(&self, symbol: &str) -> Option<TradingPair> {
+        self.config.configured_pairs().into_iter()
+            .find(|pair| self.convert_symbol(pair) == symbol)
+    }
+
+    // Binance order ids are only unique per symbol, so both are encoded in the order id we hand out
+    fn split_order_id(order_id: &str) -> Result<(&str, &str)> {
+        order_id.split_once(':')
+            .ok_or_else(|| anyhow::anyhow!("Invalid Binance order id: {}", order_id))
+    }
+
+    fn order_to_trade(&self, order: BinanceOrderResponse) -> Result<Trade> {
+        let pair = self.pair_from_symbol(&order.symbol)
+            .ok_or_else(|| anyhow::anyhow!("Unknown Binance symbol: {}", order.symbol))?;
+        
+        let executed_qty = Decimal::from_str(&order.executed_qty)?;
+        let quote_qty = order.cummulative_quote_qty.as_deref()
+            .map(Decimal::from_str)
+            .transpose()?
+            .unwrap_or_default();
+        
+        let price = if executed_qty > Decimal::ZERO && quote_qty > Decimal::ZERO {
+            quote_qty / executed_qty
+        } else {
+            Decimal::from_str(&order.price)?
+        };
+        
+        let status = match order.status.as_str() {
+            "FILLED" => TradeStatus::Executed,
+            // An IOC limit that partially filled expires, but what it bought is as settled as a fill
+            "CANCELED" | "EXPIRED" | "EXPIRED_IN_MATCH" if executed_qty > Decimal::ZERO => TradeStatus::Executed,
+            "CANCELED" | "EXPIRED" | "EXPIRED_IN_MATCH" => TradeStatus::Cancelled,
+            "REJECTED" => TradeStatus::Failed,
+            _ => TradeStatus::Pending,
+        };
+        
+        let side = if order.side == "BUY" { TradeSide::Buy } else { TradeSide::Sell };
+        
+        // Commission is only reported per fill, which the order status endpoint does not return
+        let mut fee_amount = Decimal::ZERO;
+        for fill in &order.fills {
+            fee_amount += Decimal::from_str(&fill.commission)?;
+        }
+        let fee_asset = order.fills.first().map(|fill| fill.commission_asset.clone());
+        
+        let amount = if executed_qty > Decimal::ZERO {
+            executed_qty
+        } else {
+            Decimal::from_str(&order.orig_qty)?
+        };
+        
+        let executed_at = match status {
+            TradeStatus::Executed => Some(order.transact_time
+                .and_then(chrono::DateTime::from_timestamp_millis)
+                .unwrap_or_else(Utc::now)),
+            _ => None,
+        };
+        
+        Ok(Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            pair,
+            side,
+            amount,
+            price,
+            fee_amount,
+            fee_asset,
+            status,
+            created_at: Utc::now(),
+            executed_at,
+            order_id: Some(format!("{}:{}", order.symbol, order.order_id)),
+            client_order_id: order.client_order_id,
+            tx_hash: None,
+            simulated: false,
+            failure_reason: None,
+        })
+    }
+
+    fn symbol_rules_from_filters(filters: &[BinanceSymbolFilter]) -> Result<SymbolRules> {
+        let mut rules = SymbolRules::unconstrained();
+        
+        for filter in filters {
+            match filter {
+                BinanceSymbolFilter::Price { tick_size } => {
+                    rules.price_tick = Decimal::from_str(tick_size)?.normalize();
+                },
+                BinanceSymbolFilter::LotSize { min_qty, max_qty, step_size } => {
+                    rules.min_quantity = Decimal::from_str(min_qty)?;
+                    rules.quantity_step = Decimal::from_str(step_size)?.normalize();
+                    let max_quantity = Decimal::from_str(max_qty)?;
+                    rules.max_quantity = (max_quantity > Decimal::ZERO).then_some(max_quantity);
+                },
+                BinanceSymbolFilter::MinNotional { min_notional } | BinanceSymbolFilter::Notional { min_notional } => {
+                    rules.min_notional = rules.min_notional.max(Decimal::from_str(min_notional)?);
+                },
+                BinanceSymbolFilter::Other => {},
+            }
+        }
+        
+        Ok(rules)
+    }
+
+    async fn place_order(&self, pair: &TradingPair, side: TradeSide, amount: OrderAmount, price: Option<Decimal>, options: &OrderOptions) -> Result<Trade> {
+        let price = match price {
+            Some(price) => Some(self.get_symbol_rules(pair).await?.round_price(price, &side)),
+            None => None,
+        };
+        
+        let request = BinanceOrderRequest {
+            symbol: self.convert_symbol(pair),
+            side: match side {
+                TradeSide::Buy => "BUY".to_string(),
+                TradeSide::Sell => "SELL".to_string(),
+            },
+            // Post-only limits are LIMIT_MAKER orders, which take no time in force
+            order_type: match (price, options.post_only) {
+                (Some(_), true) => "LIMIT_MAKER".to_string(),
+                (Some(_), false) => "LIMIT".to_string(),
+                (None, _) => "MARKET".to_string(),
+            },
+            quantity: match amount {
+                OrderAmount::Base(quantity) => Some(quantity.normalize().to_string()),
+                OrderAmount::Quote(_) => None,
+            },
+            // Quote amounts are accepted to the quote asset's precision, which is 8 decimals on spot
+            quote_order_qty: match amount {
+                OrderAmount::Base(_) => None,
+                OrderAmount::Quote(spend) => Some(spend.round_dp_with_strategy(8, rust_decimal::RoundingStrategy::ToZero).normalize().to_string()),
+            },
+            price: price.map(|p| p.normalize().to_string()),
+            time_in_force: price.filter(|_| !options.post_only).map(|_| match options.time_in_force {
+                TimeInForce::Gtc => "GTC".to_string(),
+                TimeInForce::Ioc => "IOC".to_string(),
+                TimeInForce::Fok => "FOK".to_string(),
+            }),
+            client_order_id: options.client_order_id.clone(),
+            response_type: "FULL".to_string(),
+        };
+        
+        let params: HashMap<String, String> = serde_urlencoded::from_str(&serde_urlencoded::to_string(&request)?)?;
+        let order: BinanceOrderResponse = self
+            .make_signed_request_with_method(reqwest::Method::POST, "/api/v3/order", &params, NEW_ORDER_WEIGHT)
+            .await?;
+        
+        let trade = self.order_to_trade(order)?;
+        if matches!(trade.status, TradeStatus::Pending) && self.user_stream_task.lock().unwrap().is_some() {
+            if let Some(order_id) = &trade.order_id {
+                self.pending_orders.lock().unwrap().insert(order_id.clone());
+            }
+        }
+        Ok(trade)
+    }
 }
 
 #[async_trait]
@@ -130,11 +1109,18 @@ impl Exchange for BinanceExchange {
         "binance"
     }
 
-    async fn get_price(&self, pair: &TradingPair) -> Result<Price> {
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        if let Some(price) = self.cached_price(pair) {
+            return Ok(price);
+        }
+        
         let symbol = self.convert_symbol(pair);
         let url = format!("{}/api/v3/ticker/bookTicker?symbol={}", self.config.api_url, symbol);
         
-        let response = self.client.get(&url).send().await?;
+        let response = self.send_with_retry(|| Ok(self.client.get(&url)), BOOK_TICKER_WEIGHT).await?;
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Binance", response).await);
+        }
         let ticker: BinanceTicker = response.json().await?;
         
         Ok(Price {
@@ -144,29 +1130,22 @@ impl Exchange for BinanceExchange {
             ask: Decimal::from_str(&ticker.ask_price)?,
             timestamp: Utc::now(),
             volume_24h: Some(Decimal::from_str(&ticker.volume)?),
+            fetch_latency: None,
         })
     }
 
-    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> Result<OrderBook> {
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
         let symbol = self.convert_symbol(pair);
         let url = format!("{}/api/v3/depth?symbol={}&limit={}", self.config.api_url, symbol, depth);
         
-        let response = self.client.get(&url).send().await?;
+        let response = self.send_with_retry(|| Ok(self.client.get(&url)), depth_weight(depth)).await?;
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Binance", response).await);
+        }
         let order_book: BinanceOrderBook = response.json().await?;
         
-        let bids = order_book.bids.iter()
-            .map(|level| OrderBookLevel {
-                price: Decimal::from_str(&level[0]).unwrap_or_default(),
-                quantity: Decimal::from_str(&level[1]).unwrap_or_default(),
-            })
-            .collect();
-            
-        let asks = order_book.asks.iter()
-            .map(|level| OrderBookLevel {
-                price: Decimal::from_str(&level[0]).unwrap_or_default(),
-                quantity: Decimal::from_str(&level[1]).unwrap_or_default(),
-            })
-            .collect();
+        let bids = parse_depth_levels("bid", &order_book.bids)?;
+        let asks = parse_depth_levels("ask", &order_book.asks)?;
         
         Ok(OrderBook {
             exchange: self.name().to_string(),
@@ -177,70 +1156,425 @@ impl Exchange for BinanceExchange {
         })
     }
 
-    async fn get_balances(&self) -> Result<HashMap<String, Balance>> {
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
         let params = HashMap::new();
-        let account_info: BinanceAccountInfo = self.make_signed_request("/api/v3/account", &params).await?;
+        let account_info: BinanceAccountInfo = self.make_signed_request("/api/v3/account", &params, ACCOUNT_WEIGHT).await?;
         
-        let mut balances = HashMap::new();
+        Ok(parse_balances(account_info.balances)?)
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Buy, OrderAmount::Base(amount), price, &OrderOptions::default()).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Sell, OrderAmount::Base(amount), price, &OrderOptions::default()).await?)
+    }
+
+    async fn submit_order(&self, request: &OrderRequest) -> BotResult<Trade> {
+        Ok(self.place_order(&request.pair, request.side.clone(), request.amount, request.price, &request.options).await?)
+    }
+
+    fn supports_client_order_ids(&self) -> bool {
+        true
+    }
+
+    async fn get_order_by_client_id(&self, pair: &TradingPair, client_order_id: &str) -> BotResult<Option<Trade>> {
+        let mut params = HashMap::new();
+        params.insert("symbol".to_string(), self.convert_symbol(pair));
+        params.insert("origClientOrderId".to_string(), client_order_id.to_string());
         
-        for balance in account_info.balances {
-            let free = Decimal::from_str(&balance.free).unwrap_or_default();
-            let locked = Decimal::from_str(&balance.locked).unwrap_or_default();
-            let total = free + locked;
-            
-            if total > Decimal::ZERO {
-                balances.insert(balance.asset.clone(), Balance {
-                    asset: balance.asset,
-                    free,
-                    locked,
-                    total,
-                    usd_value: Decimal::ZERO,
-                });
-            }
+        match self.make_signed_request::<BinanceOrderResponse>("/api/v3/order", &params, QUERY_ORDER_WEIGHT).await {
+            Ok(order) => Ok(Some(self.order_to_trade(order)?)),
+            Err(e) if BotError::find(&e).and_then(BotError::code).and_then(|code| code.parse::<i64>().ok()) == Some(UNKNOWN_ORDER_CODE) => Ok(None),
+            Err(e) => Err(e.into()),
         }
-        
-        Ok(balances)
     }
 
-    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> Result<Trade> {
-        todo!("Implement buy order placement")
+    fn supports_quote_orders(&self) -> bool {
+        true
     }
 
-    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> Result<Trade> {
-        todo!("Implement sell order placement")
+    // The reply's executedQty is in base units, so the trade carries the base actually bought
+    async fn place_market_buy_quote(&self, pair: &TradingPair, quote_amount: Decimal) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Buy, OrderAmount::Quote(quote_amount), None, &OrderOptions::default()).await?)
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        let (symbol, id) = Self::split_order_id(order_id)?;
+        
+        let mut params = HashMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+        params.insert("orderId".to_string(), id.to_string());
+        
+        let order: BinanceOrderResponse = self.make_signed_request("/api/v3/order", &params, QUERY_ORDER_WEIGHT).await?;
+        Ok(self.order_to_trade(order)?)
     }
 
-    async fn get_order_status(&self, order_id: &str) -> Result<Trade> {
-        todo!("Implement order status check")
+    async fn cancel_order(&self, order_id: &str) -> BotResult<()> {
+        let (symbol, id) = Self::split_order_id(order_id)?;
+        
+        let mut params = HashMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+        params.insert("orderId".to_string(), id.to_string());
+        
+        let _: serde_json::Value = self
+            .make_signed_request_with_method(reqwest::Method::DELETE, "/api/v3/order", &params, CANCEL_ORDER_WEIGHT)
+            .await?;
+        Ok(())
     }
 
-    async fn cancel_order(&self, order_id: &str) -> Result<()> {
-        todo!("Implement order cancellation")
+    async fn get_open_orders(&self, pair: Option<&TradingPair>) -> BotResult<Vec<OpenOrder>> {
+        let mut params = HashMap::new();
+        let weight = match pair {
+            Some(pair) => {
+                params.insert("symbol".to_string(), self.convert_symbol(pair));
+                OPEN_ORDERS_WEIGHT
+            },
+            None => ALL_OPEN_ORDERS_WEIGHT,
+        };
+        
+        let orders: Vec<BinanceOrderResponse> = self.make_signed_request("/api/v3/openOrders", &params, weight).await?;
+        
+        let mut open_orders = Vec::with_capacity(orders.len());
+        for order in orders {
+            let pair = self.pair_from_symbol(&order.symbol)
+                .map(|pair| pair.symbol)
+                .unwrap_or_else(|| order.symbol.clone());
+            
+            open_orders.push(OpenOrder {
+                exchange: self.name().to_string(),
+                order_id: format!("{}:{}", order.symbol, order.order_id),
+                pair,
+                side: if order.side == "BUY" { TradeSide::Buy } else { TradeSide::Sell },
+                price: Decimal::from_str(&order.price)?,
+                filled: Decimal::from_str(&order.executed_qty)?,
+                amount: Decimal::from_str(&order.orig_qty)?,
+                created_at: order.time
+                    .and_then(chrono::DateTime::from_timestamp_millis)
+                    .unwrap_or_else(Utc::now),
+            });
+        }
+        Ok(open_orders)
     }
 
     fn supports_pair(&self, pair: &TradingPair) -> bool {
         self.config.trading_pairs.contains(&pair.symbol)
     }
 
-    async fn get_supported_pairs(&self) -> Result<Vec<TradingPair>> {
-        let pairs = self.config.trading_pairs.iter()
-            .filter_map(|symbol| {
-                let parts: Vec<&str> = symbol.split('/').collect();
-                if parts.len() == 2 {
-                    Some(TradingPair::new(parts[0], parts[1]))
-                } else {
-                    None
-                }
-            })
-            .collect();
+    fn subscribe_prices(&self, sender: tokio::sync::mpsc::Sender<Price>) {
+        self.price_listeners.write().unwrap().push(sender);
+    }
+
+    // The stream starts with the first subscriber, so one-off commands never open a listen key
+    fn subscribe_account(&self, sender: mpsc::Sender<AccountEvent>) {
+        self.account_listeners.write().unwrap().push(sender);
+        self.start_user_stream();
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        Ok(self.config.configured_pairs())
+    }
+
+    // The account's own rates per symbol. Without an API key there is no account to ask, so the
+    // base tier is assumed; a failed refresh keeps using the last rates fetched.
+    async fn get_trading_fees(&self, pair: &TradingPair) -> BotResult<TradingFees> {
+        if self.config.api_key.is_empty() {
+            return Ok(TradingFees {
+                maker_fee: Decimal::new(1, 3),
+                taker_fee: Decimal::new(1, 3),
+            });
+        }
+        
+        let symbol = self.convert_symbol(pair);
+        let cached = self.trading_fees.read().unwrap().get(&symbol).cloned();
+        if let Some((fetched_at, fees)) = &cached {
+            if fetched_at.elapsed() < TRADING_FEE_TTL {
+                return Ok(fees.clone());
+            }
+        }
         
-        Ok(pairs)
+        let mut params = HashMap::new();
+        params.insert("symbol".to_string(), symbol.clone());
+        let fees = match self.make_signed_request::<BinanceCommissionRates>("/api/v3/account/commission", &params, COMMISSION_WEIGHT).await {
+            Ok(rates) => commission_fees(&rates)?,
+            Err(e) => match cached {
+                Some((_, fees)) => {
+                    warn!("Failed to refresh Binance fees for {}, keeping the previous rates: {}", symbol, e);
+                    return Ok(fees);
+                },
+                None => return Err(e.into()),
+            },
+        };
+        
+        self.trading_fees.write().unwrap().insert(symbol, (Instant::now(), fees.clone()));
+        Ok(fees)
     }
 
-    async fn get_trading_fees(&self, _pair: &TradingPair) -> Result<TradingFees> {
-        Ok(TradingFees {
-            maker_fee: Decimal::from_str("0.001")?,
-            taker_fee: Decimal::from_str("0.001")?,
+    // Deposits and withdrawals use each coin's default network
+    async fn get_deposit_address(&self, asset: &str) -> BotResult<String> {
+        let mut params = HashMap::new();
+        params.insert("coin".to_string(), asset.to_string());
+        
+        let deposit: BinanceDepositAddress = self
+            .make_signed_request("/sapi/v1/capital/deposit/address", &params, DEPOSIT_ADDRESS_WEIGHT)
+            .await?;
+        // Sending without the memo would lose the funds
+        if !deposit.tag.is_empty() {
+            return Err(anyhow::anyhow!("Binance deposits of {} require a memo, which is not supported", asset).into());
+        }
+        Ok(deposit.address)
+    }
+
+    async fn get_withdrawal_limits(&self, asset: &str) -> BotResult<WithdrawalLimits> {
+        let params = HashMap::new();
+        let coins: Vec<BinanceCoinConfig> = self
+            .make_signed_request("/sapi/v1/capital/config/getall", &params, COIN_CONFIG_WEIGHT)
+            .await?;
+        
+        let network = coins.into_iter()
+            .find(|coin| coin.coin == asset)
+            .and_then(|coin| coin.network_list.into_iter().find(|network| network.is_default))
+            .ok_or_else(|| anyhow::anyhow!("Binance has no default network for {}", asset))?;
+        if !network.withdraw_enable {
+            return Err(anyhow::anyhow!("Binance withdrawals of {} are currently disabled", asset).into());
+        }
+        
+        Ok(WithdrawalLimits {
+            fee: Decimal::from_str(&network.withdraw_fee)?,
+            minimum: Decimal::from_str(&network.withdraw_min)?,
         })
     }
-}
\ No newline at end of file
+
+    // One coin config read refreshes every asset's status
+    async fn get_transfer_status(&self, asset: &str) -> BotResult<TransferStatus> {
+        if let Some((fetched_at, status)) = self.transfer_statuses.read().unwrap().get(asset) {
+            if fetched_at.elapsed() < TRANSFER_STATUS_TTL {
+                return Ok(*status);
+            }
+        }
+        
+        let params = HashMap::new();
+        let coins: Vec<BinanceCoinConfig> = self
+            .make_signed_request("/sapi/v1/capital/config/getall", &params, COIN_CONFIG_WEIGHT)
+            .await?;
+        
+        let fetched_at = Instant::now();
+        let mut statuses = self.transfer_statuses.write().unwrap();
+        for coin in coins {
+            statuses.insert(coin.coin, (fetched_at, TransferStatus {
+                deposits_enabled: coin.deposit_all_enable,
+                withdrawals_enabled: coin.withdraw_all_enable,
+            }));
+        }
+        statuses.get(asset)
+            .map(|(_, status)| *status)
+            .ok_or_else(|| anyhow::anyhow!("Binance lists no coin {}", asset).into())
+    }
+
+    async fn get_withdrawal_fee(&self, asset: &str) -> BotResult<Decimal> {
+        if let Some((fetched_at, fee)) = self.withdrawal_fees.read().unwrap().get(asset) {
+            if fetched_at.elapsed() < WITHDRAWAL_FEE_TTL {
+                return Ok(*fee);
+            }
+        }
+        
+        let fee = self.get_withdrawal_limits(asset).await?.fee;
+        self.withdrawal_fees.write().unwrap().insert(asset.to_string(), (Instant::now(), fee));
+        Ok(fee)
+    }
+
+    async fn withdraw(&self, asset: &str, amount: Decimal, address: &str) -> BotResult<String> {
+        let mut params = HashMap::new();
+        params.insert("coin".to_string(), asset.to_string());
+        params.insert("address".to_string(), address.to_string());
+        params.insert("amount".to_string(), amount.normalize().to_string());
+        
+        let response: BinanceWithdrawResponse = self
+            .make_signed_request_with_method(reqwest::Method::POST, "/sapi/v1/capital/withdraw/apply", &params, WITHDRAW_WEIGHT)
+            .await?;
+        Ok(response.id)
+    }
+
+    async fn get_symbol_rules(&self, pair: &TradingPair) -> BotResult<SymbolRules> {
+        let symbol = self.convert_symbol(pair);
+        if let Some(rules) = self.symbol_rules.read().unwrap().get(&symbol) {
+            return Ok(rules.clone());
+        }
+        
+        let url = format!("{}/api/v3/exchangeInfo?symbol={}", self.config.api_url, symbol);
+        let response = self.send_with_retry(|| Ok(self.client.get(&url)), EXCHANGE_INFO_WEIGHT).await?;
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Binance", response).await);
+        }
+        let info: BinanceExchangeInfo = response.json().await?;
+        
+        let symbol_info = info.symbols.into_iter()
+            .find(|info| info.symbol == symbol)
+            .ok_or_else(|| anyhow::anyhow!("Binance exchangeInfo has no entry for {}", symbol))?;
+        let rules = Self::symbol_rules_from_filters(&symbol_info.filters)?;
+        
+        self.symbol_rules.write().unwrap().insert(symbol, rules.clone());
+        Ok(rules)
+    }
+}
+
+impl Drop for BinanceExchange {
+    fn drop(&mut self) {
+        if let Some(task) = self.stream_task.take() {
+            task.abort();
+        }
+        if let Some(task) = self.user_stream_task.lock().unwrap().take() {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    // Corrupted Binance payloads must never turn into zero-priced liquidity or silently empty balances
+    #[test]
+    fn rejects_malformed_levels_and_balances() -> Result<()> {
+        // Twenty good levels followed by the given corrupted prices
+        let levels = |bad: &[&str]| -> Vec<[String; 2]> {
+            (0..20)
+                .map(|i| [format!("{}.50", 1000 + i), "1.25".to_string()])
+                .chain(bad.iter().map(|price| [price.to_string(), "1.0".to_string()]))
+                .collect()
+        };
+        
+        let one_bad = parse_depth_levels("ask", &levels(&["not-a-price"]))?;
+        assert!(one_bad.len() == 20,
+               "one malformed level in 21 left {} levels instead of 20", one_bad.len());
+        assert!(one_bad.iter().all(|level| level.price > Decimal::ZERO && level.quantity > Decimal::ZERO),
+               "a malformed level was kept with a zero price or quantity");
+        
+        let zero_price = parse_depth_levels("ask", &levels(&["0.00000000"]))?;
+        assert!(zero_price.iter().all(|level| level.price > Decimal::ZERO),
+               "a zero-priced ask survived parsing");
+        
+        let two_bad = parse_depth_levels("bid", &levels(&["", "1e"]));
+        assert!(two_bad.is_err(),
+               "an order book with two malformed levels in 22 was accepted");
+        
+        let balances: Vec<BinanceBalance> = serde_json::from_str(r#"[
+            {"asset": "BTC", "free": "0.50000000", "locked": "0.10000000"},
+            {"asset": "ETH", "free": "0.00000000", "locked": "0.00000000"}
+        ]"#)?;
+        let parsed = parse_balances(balances)?;
+        assert!(parsed.len() == 1 && parsed.get("BTC").map(|balance| balance.total) == Some(Decimal::from_str("0.6")?),
+               "well-formed balances parsed as {:?}", parsed);
+        
+        let corrupted: Vec<BinanceBalance> = serde_json::from_str(r#"[
+            {"asset": "BTC", "free": "0.5", "locked": "0.1"},
+            {"asset": "USDT", "free": "12,000.00", "locked": "0"}
+        ]"#)?;
+        assert!(parse_balances(corrupted).is_err(),
+               "a malformed balance was read as zero instead of failing");
+        
+        Ok(())
+    }
+
+    // User data stream messages become order and balance events; anything else is ignored or flagged
+    #[test]
+    fn reads_user_stream_messages() -> Result<()> {
+        let exchange_config: ExchangeConfig = toml::from_str(r#"
+            name = "binance"
+            api_url = "http://localhost"
+            enabled = true
+            trading_pairs = ["ETH/USDT"]
+            min_trade_amount = "0"
+            max_trade_amount = "10"
+        "#)?;
+        let exchange = BinanceExchange::new(exchange_config)?;
+        let mut commissions = HashMap::new();
+        let report = |status: &str, executed: &str, quote: &str, commission: &str| format!(
+            r#"{{"e":"executionReport","E":1,"s":"ETHUSDT","S":"BUY","o":"LIMIT","q":"2.00000000","p":"1000.00000000","X":"{}","i":42,"z":"{}","Z":"{}","n":"{}","N":"USDT","T":1700000000000}}"#,
+            status, executed, quote, commission,
+        );
+        
+        let streamed_order = |message: Result<UserStreamMessage>| match message {
+            Ok(UserStreamMessage::Event(AccountEvent::Order(trade))) => Some(trade),
+            _ => None,
+        };
+        let partial = streamed_order(exchange.user_stream_message(&report("PARTIALLY_FILLED", "1.00000000", "999.00000000", "0.999"), &mut commissions));
+        assert!(partial.as_ref().map_or(false, |trade| matches!(trade.status, TradeStatus::Pending)
+                  && trade.order_id.as_deref() == Some("ETHUSDT:42") && trade.fee_amount == dec("0.999")),
+               "a partial fill was streamed as {:?}", partial);
+        let filled = streamed_order(exchange.user_stream_message(&report("FILLED", "2.00000000", "1999.00000000", "1.0"), &mut commissions));
+        assert!(filled.as_ref().map_or(false, |trade| matches!(trade.status, TradeStatus::Executed)
+                  && trade.amount == dec("2") && trade.price == dec("999.5") && trade.fee_amount == dec("1.999")
+                  && trade.fee_asset.as_deref() == Some("USDT")),
+               "the final fill was streamed as {:?}", filled);
+        assert!(commissions.is_empty(), "commissions of a finished order were kept: {:?}", commissions);
+        
+        let balances = exchange.user_stream_message(
+            r#"{"e":"outboundAccountPosition","E":1,"u":1,"B":[{"a":"ETH","f":"3.5","l":"0"},{"a":"USDT","f":"1000","l":"0"}]}"#,
+            &mut commissions,
+        );
+        assert!(matches!(&balances, Ok(UserStreamMessage::Event(AccountEvent::Balances { free, .. }))
+                  if free.get("ETH") == Some(&dec("3.5")) && free.len() == 2),
+               "an account position update was not read as a balance change");
+        let foreign = report("FILLED", "2", "2000", "0").replace("ETHUSDT", "BTCUSDT");
+        assert!(matches!(exchange.user_stream_message(&foreign, &mut commissions), Ok(UserStreamMessage::Ignored)),
+               "an order on an untraded symbol was not ignored");
+        assert!(matches!(exchange.user_stream_message(r#"{"e":"listenKeyExpired","E":1}"#, &mut commissions),
+                  Ok(UserStreamMessage::ListenKeyExpired)),
+               "an expired listen key was not reported");
+        assert!(matches!(exchange.user_stream_message(r#"{"e":"balanceUpdate","E":1,"a":"ETH","d":"1"}"#, &mut commissions),
+                  Ok(UserStreamMessage::Ignored)),
+               "an unhandled event type was not ignored");
+        
+        Ok(())
+    }
+
+    // Offset math against fixed clock readings, so no real clock or venue is involved
+    #[test]
+    fn offsets_signed_timestamps_by_the_server_clock() {
+        let clock = ServerClock::default();
+        
+        assert!(clock.needs_sync(), "a clock that never synced did not ask for a sync");
+        assert!(clock.timestamp(1_000) == 1_000, "an unsynced clock changed the timestamp");
+        
+        // Sent at 1000, answered at 1200: the server read 1600 at our 1100, so it runs 500ms ahead
+        let ahead = clock.record(1_000, 1_600, 1_200);
+        assert!(ahead == 500, "a server 500ms ahead gave an offset of {}", ahead);
+        assert!(clock.timestamp(2_000) == 2_500, "a local 2000 was signed as {}", clock.timestamp(2_000));
+        
+        // A local clock running fast gives a negative offset, and a slow round trip does not skew it
+        let behind = clock.record(10_000, 9_000, 10_400);
+        assert!(behind == -1_200, "a server 1200ms behind gave an offset of {}", behind);
+        assert!(clock.timestamp(20_000) == 18_800, "a local 20000 was signed as {}", clock.timestamp(20_000));
+        
+        assert!(error_code(r#"{"code":-1021,"msg":"Timestamp for this request is outside of the recvWindow."}"#) == Some(-1021),
+               "a -1021 response was not recognised");
+        assert!(error_code("<html>502 Bad Gateway</html>").is_none(),
+               "a non-JSON error produced an error code");
+    }
+
+    // Commission rates come back as the venue's standard rate plus tax, less the discount when it applies
+    #[test]
+    fn reads_commission_rates() -> Result<()> {
+        // Response shape of /api/v3/account/commission: 0.1% standard, no tax, 25% off when paying in BNB
+        let commission = |account: bool| format!(
+            r#"{{"symbol":"ETHUSDT","standardCommission":{{"maker":"0.00100000","taker":"0.00100000","buyer":"0.00000000","seller":"0.00000000"}},"taxCommission":{{"maker":"0.00000000","taker":"0.00010000","buyer":"0.00000000","seller":"0.00000000"}},"discount":{{"enabledForAccount":{},"enabledForSymbol":true,"discountAsset":"BNB","discount":"0.75000000"}}}}"#,
+            account,
+        );
+        
+        let discounted = commission_fees(&serde_json::from_str(&commission(true))?)?;
+        assert!(discounted.maker_fee == dec("0.00075"), "discounted maker fee was {}", discounted.maker_fee);
+        assert!(discounted.taker_fee == dec("0.00085"), "discounted taker fee (plus tax) was {}", discounted.taker_fee);
+        
+        let standard = commission_fees(&serde_json::from_str(&commission(false))?)?;
+        assert!(standard.maker_fee == dec("0.001"), "undiscounted maker fee was {}", standard.maker_fee);
+        assert!(standard.taker_fee == dec("0.0011"), "undiscounted taker fee (plus tax) was {}", standard.taker_fee);
+        
+        Ok(())
+    }
+}
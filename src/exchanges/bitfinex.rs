@@ -0,0 +1,468 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::config::ExchangeConfig;
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::{Exchange, TradingFees};
+use crate::models::{Balance, OrderBook, OrderBookLevel, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::utils::{self, RateLimiter};
+
+// Bitfinex keeps legacy names for some assets; (bitfinex, standard)
+const ASSET_ALIASES: &[(&str, &str)] = &[
+    ("UST", "USDT"),
+    ("UDC", "USDC"),
+    ("DSH", "DASH"),
+    ("QTM", "QTUM"),
+    ("IOT", "IOTA"),
+    ("MNA", "MANA"),
+    ("DAT", "DATA"),
+    ("YYW", "YOYOW"),
+    ("ALG", "ALGO"),
+];
+
+// Book lengths accepted by the v2 book endpoint
+const BOOK_LENGTHS: [usize; 3] = [1, 25, 100];
+
+// Indices into the v2 order array
+const ORDER_ID: usize = 0;
+const ORDER_SYMBOL: usize = 3;
+const ORDER_MTS_CREATE: usize = 4;
+const ORDER_MTS_UPDATE: usize = 5;
+const ORDER_AMOUNT: usize = 6;
+const ORDER_AMOUNT_ORIG: usize = 7;
+const ORDER_STATUS: usize = 13;
+const ORDER_PRICE: usize = 16;
+const ORDER_PRICE_AVG: usize = 17;
+
+// Indices into the v2 order trade array
+const TRADE_FEE: usize = 9;
+const TRADE_FEE_CURRENCY: usize = 10;
+
+pub struct BitfinexExchange {
+    config: ExchangeConfig,
+    client: Client,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    last_nonce: AtomicU64,
+}
+
+impl BitfinexExchange {
+    pub fn new(config: ExchangeConfig) -> Result<Self> {
+        Ok(Self {
+            rate_limiter: utils::request_rate_limiter("bitfinex", config.rate_limit, None),
+            client: utils::http_client_with(&config.http())?,
+            config,
+            last_nonce: AtomicU64::new(0),
+        })
+    }
+
+    pub fn normalize_asset(asset: &str) -> String {
+        ASSET_ALIASES.iter()
+            .find(|(bitfinex, _)| *bitfinex == asset)
+            .map(|(_, symbol)| symbol.to_string())
+            .unwrap_or_else(|| asset.to_string())
+    }
+
+    fn bitfinex_asset(symbol: &str) -> Option<String> {
+        if let Some((bitfinex, _)) = ASSET_ALIASES.iter().find(|(_, standard)| *standard == symbol) {
+            return Some(bitfinex.to_string());
+        }
+        
+        // Anything without an alias must already be a valid Bitfinex currency code
+        let valid = !symbol.is_empty() && symbol.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+        valid.then(|| symbol.to_string())
+    }
+
+    fn try_convert_symbol(pair: &TradingPair) -> Option<String> {
+        let base = Self::bitfinex_asset(&pair.base)?;
+        let quote = Self::bitfinex_asset(&pair.quote)?;
+        
+        // Currencies longer than three characters are separated with a colon
+        if base.len() > 3 || quote.len() > 3 {
+            Some(format!("t{}:{}", base, quote))
+        } else {
+            Some(format!("t{}{}", base, quote))
+        }
+    }
+
+    fn convert_symbol(&self, pair: &TradingPair) -> Result<String> {
+        Self::try_convert_symbol(pair)
+            .ok_or_else(|| anyhow::anyhow!("Pair has no Bitfinex symbol: {}", pair.symbol))
+    }
+
+    fn pair_from_symbol(&self, symbol: &str) -> Option<TradingPair> {
+        self.config.configured_pairs().into_iter()
+            .find(|pair| Self::try_convert_symbol(pair).as_deref() == Some(symbol))
+    }
+
+    // Order lookups are per symbol, so both are encoded in the order id we hand out
+    fn split_order_id(order_id: &str) -> Result<(&str, i64)> {
+        let (symbol, id) = order_id.rsplit_once('@')
+            .ok_or_else(|| anyhow::anyhow!("Invalid Bitfinex order id: {}", order_id))?;
+        Ok((symbol, id.parse()?))
+    }
+
+    // Authenticated requests must carry a strictly increasing nonce
+    fn next_nonce(&self) -> u64 {
+        let now = Utc::now().timestamp_micros() as u64;
+        let mut last = self.last_nonce.load(Ordering::SeqCst);
+        loop {
+            let nonce = now.max(last + 1);
+            match self.last_nonce.compare_exchange(last, nonce, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => return nonce,
+                Err(current) => last = current,
+            }
+        }
+    }
+
+    fn create_signature(&self, path: &str, nonce: u64, body: &str) -> String {
+        use hmac::{Hmac, Mac};
+        use sha2::Sha384;
+        
+        type HmacSha384 = Hmac<Sha384>;
+        
+        let message = format!("/api/{}{}{}", path, nonce, body);
+        let mut mac = HmacSha384::new_from_slice(self.config.api_secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(message.as_bytes());
+        
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    async fn make_public_request<T>(&self, path: &str) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let url = format!("{}/{}", self.config.api_url, path);
+        let response = self.client.get(&url).send().await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Bitfinex", response).await.into());
+        }
+        
+        Ok(response.json().await?)
+    }
+
+    async fn make_signed_request<T>(&self, path: &str, body: Value) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let nonce = self.next_nonce();
+        let body = body.to_string();
+        let signature = self.create_signature(path, nonce, &body);
+        
+        let response = self.client
+            .post(format!("{}/{}", self.config.api_url, path))
+            .header("bfx-nonce", nonce.to_string())
+            .header("bfx-apikey", &self.config.api_key)
+            .header("bfx-signature", signature)
+            .header("Content-Type", "application/json")
+            .body(body)
+            .send()
+            .await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Bitfinex", response).await.into());
+        }
+        
+        Ok(response.json().await?)
+    }
+
+    // The v2 API returns bare JSON numbers, sometimes in exponent notation
+    fn value_decimal(value: &Value) -> Result<Decimal> {
+        match value {
+            Value::Null => Ok(Decimal::ZERO),
+            Value::String(s) => Ok(Decimal::from_str(s)?),
+            other => {
+                let text = other.to_string();
+                Decimal::from_str(&text)
+                    .or_else(|_| Decimal::from_scientific(&text))
+                    .map_err(|e| anyhow::anyhow!("Invalid Bitfinex number {}: {}", text, e))
+            }
+        }
+    }
+
+    fn field<'a>(array: &'a [Value], index: usize) -> &'a Value {
+        array.get(index).unwrap_or(&Value::Null)
+    }
+
+    fn order_to_trade(&self, order: &[Value]) -> Result<Trade> {
+        let symbol = Self::field(order, ORDER_SYMBOL).as_str().unwrap_or_default();
+        let pair = self.pair_from_symbol(symbol)
+            .ok_or_else(|| anyhow::anyhow!("Unknown Bitfinex symbol: {}", symbol))?;
+        let id = Self::field(order, ORDER_ID).as_i64()
+            .ok_or_else(|| anyhow::anyhow!("Bitfinex order without id"))?;
+        
+        // Amounts are signed: positive for buys, negative for sells; AMOUNT is what remains open
+        let original = Self::value_decimal(Self::field(order, ORDER_AMOUNT_ORIG))?;
+        let remaining = Self::value_decimal(Self::field(order, ORDER_AMOUNT))?;
+        let filled = (original - remaining).abs();
+        let side = if original >= Decimal::ZERO { TradeSide::Buy } else { TradeSide::Sell };
+        
+        let price = if filled > Decimal::ZERO {
+            Self::value_decimal(Self::field(order, ORDER_PRICE_AVG))?
+        } else {
+            Self::value_decimal(Self::field(order, ORDER_PRICE))?
+        };
+        
+        // e.g. "ACTIVE", "EXECUTED @ 107.6(-0.2)", "PARTIALLY FILLED @ ...", "CANCELED"
+        let status_text = Self::field(order, ORDER_STATUS).as_str().unwrap_or_default();
+        let status = if status_text.starts_with("EXECUTED") {
+            TradeStatus::Executed
+        } else if status_text.contains("CANCELED") {
+            TradeStatus::Cancelled
+        } else if status_text.starts_with("RSN_") || status_text.starts_with("INSUFFICIENT") {
+            TradeStatus::Failed
+        } else {
+            TradeStatus::Pending
+        };
+        
+        let parse_time = |value: &Value| value.as_i64().and_then(chrono::DateTime::from_timestamp_millis);
+        let executed_at = match status {
+            TradeStatus::Executed => parse_time(Self::field(order, ORDER_MTS_UPDATE)).or_else(|| Some(Utc::now())),
+            _ => None,
+        };
+        
+        Ok(Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            pair,
+            side,
+            amount: if filled > Decimal::ZERO { filled } else { original.abs() },
+            price,
+            fee_amount: Decimal::ZERO,
+            fee_asset: None,
+            status,
+            created_at: parse_time(Self::field(order, ORDER_MTS_CREATE)).unwrap_or_else(Utc::now),
+            executed_at,
+            order_id: Some(format!("{}@{}", symbol, id)),
+            client_order_id: None,
+            tx_hash: None,
+            simulated: false,
+            failure_reason: None,
+        })
+    }
+
+    // Fees are only reported on the fills, so sum them from the order's trades
+    async fn order_fees(&self, symbol: &str, id: i64) -> Result<(Decimal, Option<String>)> {
+        let trades: Vec<Vec<Value>> = self
+            .make_signed_request(&format!("v2/auth/r/order/{}:{}/trades", symbol, id), serde_json::json!({}))
+            .await?;
+        
+        let mut fee = Decimal::ZERO;
+        let mut fee_asset = None;
+        for trade in &trades {
+            // Fees are reported as negative amounts
+            fee += Self::value_decimal(Self::field(trade, TRADE_FEE))?.abs();
+            if let Some(currency) = Self::field(trade, TRADE_FEE_CURRENCY).as_str() {
+                fee_asset = Some(Self::normalize_asset(currency));
+            }
+        }
+        
+        Ok((fee, fee_asset))
+    }
+
+    async fn place_order(&self, pair: &TradingPair, side: TradeSide, amount: Decimal, price: Option<Decimal>) -> Result<Trade> {
+        let symbol = self.convert_symbol(pair)?;
+        let signed_amount = match side {
+            TradeSide::Buy => amount,
+            TradeSide::Sell => -amount,
+        };
+        
+        let mut body = serde_json::json!({
+            "type": if price.is_some() { "EXCHANGE LIMIT" } else { "EXCHANGE MARKET" },
+            "symbol": symbol,
+            "amount": signed_amount.normalize().to_string(),
+        });
+        if let Some(price) = price {
+            body["price"] = Value::String(price.normalize().to_string());
+        }
+        
+        // [MTS, TYPE, MESSAGE_ID, null, [ORDER...], CODE, STATUS, TEXT]
+        let notification: Vec<Value> = self.make_signed_request("v2/auth/w/order/submit", body).await?;
+        let status = Self::field(&notification, 6).as_str().unwrap_or_default();
+        if status != "SUCCESS" {
+            let text = Self::field(&notification, 7).as_str().unwrap_or_default();
+            return Err(BotError::rejected("Bitfinex", Some(status.to_string()), text).into());
+        }
+        
+        let order = Self::field(&notification, 4).as_array()
+            .and_then(|orders| orders.first())
+            .and_then(|order| order.as_array())
+            .ok_or_else(|| anyhow::anyhow!("Bitfinex order response without order"))?;
+        
+        self.order_to_trade(order)
+    }
+}
+
+#[async_trait]
+impl Exchange for BitfinexExchange {
+    fn name(&self) -> &str {
+        "bitfinex"
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        // [BID, BID_SIZE, ASK, ASK_SIZE, DAILY_CHANGE, DAILY_CHANGE_RELATIVE, LAST_PRICE, VOLUME, HIGH, LOW]
+        let ticker: Vec<Value> = self
+            .make_public_request(&format!("v2/ticker/{}", self.convert_symbol(pair)?))
+            .await?;
+        
+        Ok(Price {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bid: Self::value_decimal(Self::field(&ticker, 0))?,
+            ask: Self::value_decimal(Self::field(&ticker, 2))?,
+            timestamp: Utc::now(),
+            volume_24h: Self::value_decimal(Self::field(&ticker, 7)).ok(),
+            fetch_latency: None,
+        })
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        let len = BOOK_LENGTHS.iter().copied()
+            .find(|len| *len >= depth)
+            .unwrap_or(BOOK_LENGTHS[BOOK_LENGTHS.len() - 1]);
+        
+        // [[PRICE, COUNT, AMOUNT]], positive amounts are bids and negative amounts are asks
+        let entries: Vec<Vec<Value>> = self
+            .make_public_request(&format!("v2/book/{}/P0?len={}", self.convert_symbol(pair)?, len))
+            .await?;
+        
+        let mut bids = Vec::new();
+        let mut asks = Vec::new();
+        
+        for entry in &entries {
+            let price = Self::value_decimal(Self::field(entry, 0))?;
+            let amount = Self::value_decimal(Self::field(entry, 2))?;
+            let level = OrderBookLevel {
+                price,
+                quantity: amount.abs(),
+            };
+            if amount > Decimal::ZERO {
+                bids.push(level);
+            } else {
+                asks.push(level);
+            }
+        }
+        
+        bids.truncate(depth);
+        asks.truncate(depth);
+        
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bids,
+            asks,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        // [[WALLET_TYPE, CURRENCY, BALANCE, UNSETTLED_INTEREST, AVAILABLE_BALANCE, ...]]
+        let wallets: Vec<Vec<Value>> = self.make_signed_request("v2/auth/r/wallets", serde_json::json!({})).await?;
+        
+        let mut balances = HashMap::new();
+        
+        for wallet in wallets.iter().filter(|wallet| Self::field(wallet, 0).as_str() == Some("exchange")) {
+            let asset = Self::normalize_asset(Self::field(wallet, 1).as_str().unwrap_or_default());
+            let total = Self::value_decimal(Self::field(wallet, 2)).unwrap_or_default();
+            // Available balance is null until Bitfinex has calculated it
+            let free = match Self::field(wallet, 4) {
+                Value::Null => total,
+                available => Self::value_decimal(available).unwrap_or(total),
+            };
+            
+            if total > Decimal::ZERO {
+                balances.insert(asset.clone(), Balance {
+                    asset,
+                    free,
+                    locked: total - free,
+                    total,
+                    usd_value: Decimal::ZERO,
+                });
+            }
+        }
+        
+        Ok(balances)
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Buy, amount, price).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Sell, amount, price).await?)
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        let (symbol, id) = Self::split_order_id(order_id)?;
+        let body = serde_json::json!({ "id": [id] });
+        
+        // Open orders are listed as active; filled and cancelled ones only in history
+        let active: Vec<Vec<Value>> = self
+            .make_signed_request(&format!("v2/auth/r/orders/{}", symbol), body.clone())
+            .await?;
+        let order = match active.into_iter().next() {
+            Some(order) => order,
+            None => {
+                let history: Vec<Vec<Value>> = self
+                    .make_signed_request(&format!("v2/auth/r/orders/{}/hist", symbol), body)
+                    .await?;
+                history.into_iter().next()
+                    .ok_or_else(|| anyhow::anyhow!("Bitfinex order not found: {}", order_id))?
+            }
+        };
+        
+        let mut trade = self.order_to_trade(&order)?;
+        if matches!(trade.status, TradeStatus::Executed) {
+            let (fee_amount, fee_asset) = self.order_fees(symbol, id).await?;
+            trade.fee_amount = fee_amount;
+            trade.fee_asset = fee_asset;
+        }
+        
+        Ok(trade)
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> BotResult<()> {
+        let (_, id) = Self::split_order_id(order_id)?;
+        
+        let _: Value = self
+            .make_signed_request("v2/auth/w/order/cancel", serde_json::json!({ "id": id }))
+            .await?;
+        Ok(())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.config.trading_pairs.contains(&pair.symbol) && Self::try_convert_symbol(pair).is_some()
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        Ok(self.config.configured_pairs().into_iter()
+            .filter(|pair| self.supports_pair(pair))
+            .collect())
+    }
+
+    async fn get_trading_fees(&self, _pair: &TradingPair) -> BotResult<TradingFees> {
+        Ok(TradingFees {
+            maker_fee: Decimal::from_str("0.001")?,
+            taker_fee: Decimal::from_str("0.002")?,
+        })
+    }
+}
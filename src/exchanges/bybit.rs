@@ -0,0 +1,459 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use crate::config::ExchangeConfig;
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::{Exchange, TradingFees};
+use crate::models::{Balance, OrderBook, OrderBookLevel, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::utils::{self, RateLimiter};
+
+const RECV_WINDOW: &str = "5000";
+
+pub struct BybitExchange {
+    config: ExchangeConfig,
+    client: Client,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    fee_cache: RwLock<HashMap<String, TradingFees>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitResponse<T> {
+    ret_code: i64,
+    ret_msg: String,
+    result: Option<T>,
+    time: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitList<T> {
+    list: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitTicker {
+    bid1_price: String,
+    ask1_price: String,
+    volume24h: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitOrderBook {
+    b: Vec<[String; 2]>,
+    a: Vec<[String; 2]>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitWallet {
+    coin: Vec<BybitCoinBalance>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitCoinBalance {
+    coin: String,
+    wallet_balance: String,
+    #[serde(default)]
+    locked: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitOrderRequest {
+    category: String,
+    symbol: String,
+    side: String,
+    order_type: String,
+    qty: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_in_force: Option<String>,
+    // Spot market buys are sized in the quote coin unless told otherwise
+    #[serde(skip_serializing_if = "Option::is_none")]
+    market_unit: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitOrderAck {
+    order_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitOrder {
+    order_id: String,
+    symbol: String,
+    side: String,
+    order_status: String,
+    qty: String,
+    price: String,
+    cum_exec_qty: String,
+    avg_price: String,
+    cum_exec_fee: String,
+    created_time: String,
+    updated_time: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitFeeRate {
+    taker_fee_rate: String,
+    maker_fee_rate: String,
+}
+
+impl BybitExchange {
+    pub fn new(config: ExchangeConfig) -> Result<Self> {
+        Ok(Self {
+            rate_limiter: utils::request_rate_limiter("bybit", config.rate_limit, None),
+            client: utils::http_client_with(&config.http())?,
+            config,
+            fee_cache: RwLock::new(HashMap::new()),
+        })
+    }
+
+    fn convert_symbol(&self, pair: &TradingPair) -> String {
+        format!("{}{}", pair.base, pair.quote)
+    }
+
+    fn pair_from_symbol(&self, symbol: &str) -> Option<TradingPair> {
+        self.config.configured_pairs().into_iter()
+            .find(|pair| self.convert_symbol(pair) == symbol)
+    }
+
+    // Cancelling needs the symbol as well, so both are encoded in the order id we hand out
+    fn split_order_id(order_id: &str) -> Result<(&str, &str)> {
+        order_id.split_once(':')
+            .ok_or_else(|| anyhow::anyhow!("Invalid Bybit order id: {}", order_id))
+    }
+
+    fn create_signature(&self, timestamp: &str, payload: &str) -> String {
+        use hmac::{Hmac, Mac};
+        use sha2::Sha256;
+        
+        type HmacSha256 = Hmac<Sha256>;
+        
+        let message = format!("{}{}{}{}", timestamp, self.config.api_key, RECV_WINDOW, payload);
+        let mut mac = HmacSha256::new_from_slice(self.config.api_secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(message.as_bytes());
+        
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn unwrap_response<T>(response: BybitResponse<T>) -> Result<T> {
+        if response.ret_code != 0 {
+            return Err(BotError::rejected("Bybit", Some(response.ret_code.to_string()), response.ret_msg).into());
+        }
+        response.result.ok_or_else(|| anyhow::anyhow!("Bybit API returned no result"))
+    }
+
+    async fn make_public_request<T>(&self, endpoint: &str, query: &str) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        Ok(self.make_timed_public_request(endpoint, query).await?.0)
+    }
+
+    // The result along with the server time the response was produced at
+    async fn make_timed_public_request<T>(&self, endpoint: &str, query: &str) -> Result<(T, chrono::DateTime<Utc>)>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let url = format!("{}{}?{}", self.config.api_url, endpoint, query);
+        let response: BybitResponse<T> = self.client.get(&url).send().await?.json().await?;
+        let time = response.time.and_then(chrono::DateTime::from_timestamp_millis).unwrap_or_else(Utc::now);
+        Ok((Self::unwrap_response(response)?, time))
+    }
+
+    // GET requests sign the query string, POST requests sign the JSON body
+    async fn make_signed_request<T>(&self, method: reqwest::Method, endpoint: &str, query: &str, body: Option<String>) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let timestamp = Utc::now().timestamp_millis().to_string();
+        let body = body.unwrap_or_default();
+        let payload = if method == reqwest::Method::GET { query } else { body.as_str() };
+        let signature = self.create_signature(&timestamp, payload);
+        
+        let url = if query.is_empty() {
+            format!("{}{}", self.config.api_url, endpoint)
+        } else {
+            format!("{}{}?{}", self.config.api_url, endpoint, query)
+        };
+        
+        let mut request = self.client
+            .request(method, &url)
+            .header("X-BAPI-API-KEY", &self.config.api_key)
+            .header("X-BAPI-TIMESTAMP", &timestamp)
+            .header("X-BAPI-RECV-WINDOW", RECV_WINDOW)
+            .header("X-BAPI-SIGN", signature);
+        if !body.is_empty() {
+            request = request.header("Content-Type", "application/json").body(body);
+        }
+        
+        let response = request.send().await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Bybit", response).await.into());
+        }
+        
+        Self::unwrap_response(response.json().await?)
+    }
+
+    fn order_to_trade(&self, order: BybitOrder) -> Result<Trade> {
+        let pair = self.pair_from_symbol(&order.symbol)
+            .ok_or_else(|| anyhow::anyhow!("Unknown Bybit symbol: {}", order.symbol))?;
+        
+        let parse = |value: &str| -> Result<Decimal> {
+            if value.is_empty() {
+                Ok(Decimal::ZERO)
+            } else {
+                Ok(Decimal::from_str(value)?)
+            }
+        };
+        let parse_time = |value: &str| value.parse::<i64>().ok()
+            .and_then(chrono::DateTime::from_timestamp_millis);
+        
+        let filled = parse(&order.cum_exec_qty)?;
+        let price = if filled > Decimal::ZERO { parse(&order.avg_price)? } else { parse(&order.price)? };
+        
+        let status = match order.order_status.as_str() {
+            "Filled" => TradeStatus::Executed,
+            "Cancelled" | "PartiallyFilledCanceled" | "Deactivated" => TradeStatus::Cancelled,
+            "Rejected" => TradeStatus::Failed,
+            _ => TradeStatus::Pending,
+        };
+        
+        let side = if order.side == "Buy" { TradeSide::Buy } else { TradeSide::Sell };
+        
+        // Spot fees are taken from whatever the order receives
+        let fee_asset = match side {
+            TradeSide::Buy => pair.base.clone(),
+            TradeSide::Sell => pair.quote.clone(),
+        };
+        
+        let executed_at = match status {
+            TradeStatus::Executed => parse_time(&order.updated_time).or_else(|| Some(Utc::now())),
+            _ => None,
+        };
+        
+        Ok(Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            pair,
+            side,
+            amount: if filled > Decimal::ZERO { filled } else { parse(&order.qty)? },
+            price,
+            fee_amount: parse(&order.cum_exec_fee)?,
+            fee_asset: Some(fee_asset),
+            status,
+            created_at: parse_time(&order.created_time).unwrap_or_else(Utc::now),
+            executed_at,
+            order_id: Some(format!("{}:{}", order.symbol, order.order_id)),
+            client_order_id: None,
+            tx_hash: None,
+            simulated: false,
+            failure_reason: None,
+        })
+    }
+
+    async fn place_order(&self, pair: &TradingPair, side: TradeSide, amount: Decimal, price: Option<Decimal>) -> Result<Trade> {
+        if amount < self.config.min_trade_amount || amount > self.config.max_trade_amount {
+            anyhow::bail!("Order size {} outside configured Bybit limits [{}, {}]",
+                          amount, self.config.min_trade_amount, self.config.max_trade_amount);
+        }
+        
+        let symbol = self.convert_symbol(pair);
+        let request = BybitOrderRequest {
+            category: "spot".to_string(),
+            symbol: symbol.clone(),
+            side: match side {
+                TradeSide::Buy => "Buy".to_string(),
+                TradeSide::Sell => "Sell".to_string(),
+            },
+            order_type: if price.is_some() { "Limit".to_string() } else { "Market".to_string() },
+            qty: amount.normalize().to_string(),
+            price: price.map(|p| p.normalize().to_string()),
+            time_in_force: price.map(|_| "GTC".to_string()),
+            market_unit: price.is_none().then(|| "baseCoin".to_string()),
+        };
+        
+        let ack: BybitOrderAck = self
+            .make_signed_request(reqwest::Method::POST, "/v5/order/create", "", Some(serde_json::to_string(&request)?))
+            .await?;
+        
+        Ok(self.get_order_status(&format!("{}:{}", symbol, ack.order_id)).await?)
+    }
+}
+
+#[async_trait]
+impl Exchange for BybitExchange {
+    fn name(&self) -> &str {
+        "bybit"
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        let (tickers, time): (BybitList<BybitTicker>, _) = self
+            .make_timed_public_request("/v5/market/tickers", &format!("category=spot&symbol={}", self.convert_symbol(pair)))
+            .await?;
+        let ticker = tickers.list.into_iter().next()
+            .ok_or_else(|| anyhow::anyhow!("Bybit returned no ticker for {}", pair.symbol))?;
+        
+        Ok(Price {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bid: Decimal::from_str(&ticker.bid1_price)?,
+            ask: Decimal::from_str(&ticker.ask1_price)?,
+            timestamp: time,
+            volume_24h: Decimal::from_str(&ticker.volume24h).ok(),
+            fetch_latency: None,
+        })
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        let book: BybitOrderBook = self
+            .make_public_request(
+                "/v5/market/orderbook",
+                &format!("category=spot&symbol={}&limit={}", self.convert_symbol(pair), depth),
+            )
+            .await?;
+        
+        let to_levels = |levels: &[[String; 2]]| -> Result<Vec<OrderBookLevel>> {
+            levels.iter()
+                .map(|level| Ok(OrderBookLevel {
+                    price: Decimal::from_str(&level[0])?,
+                    quantity: Decimal::from_str(&level[1])?,
+                }))
+                .collect()
+        };
+        
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bids: to_levels(&book.b)?,
+            asks: to_levels(&book.a)?,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        let wallets: BybitList<BybitWallet> = self
+            .make_signed_request(reqwest::Method::GET, "/v5/account/wallet-balance", "accountType=UNIFIED", None)
+            .await?;
+        
+        let mut balances = HashMap::new();
+        
+        for coin in wallets.list.into_iter().flat_map(|wallet| wallet.coin) {
+            let total = Decimal::from_str(&coin.wallet_balance).unwrap_or_default();
+            let locked = Decimal::from_str(&coin.locked).unwrap_or_default();
+            
+            if total > Decimal::ZERO {
+                balances.insert(coin.coin.clone(), Balance {
+                    asset: coin.coin,
+                    free: total - locked,
+                    locked,
+                    total,
+                    usd_value: Decimal::ZERO,
+                });
+            }
+        }
+        
+        Ok(balances)
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Buy, amount, price).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Sell, amount, price).await?)
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        let (symbol, id) = Self::split_order_id(order_id)?;
+        let query = format!("category=spot&symbol={}&orderId={}", symbol, id);
+        
+        // Open and recently closed orders live in realtime; older ones only in history
+        let realtime: BybitList<BybitOrder> = self
+            .make_signed_request(reqwest::Method::GET, "/v5/order/realtime", &query, None)
+            .await?;
+        let order = match realtime.list.into_iter().next() {
+            Some(order) => order,
+            None => {
+                let history: BybitList<BybitOrder> = self
+                    .make_signed_request(reqwest::Method::GET, "/v5/order/history", &query, None)
+                    .await?;
+                history.list.into_iter().next()
+                    .ok_or_else(|| anyhow::anyhow!("Bybit order not found: {}", order_id))?
+            }
+        };
+        
+        Ok(self.order_to_trade(order)?)
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> BotResult<()> {
+        let (symbol, id) = Self::split_order_id(order_id)?;
+        let body = serde_json::json!({ "category": "spot", "symbol": symbol, "orderId": id }).to_string();
+        
+        let _: serde_json::Value = self
+            .make_signed_request(reqwest::Method::POST, "/v5/order/cancel", "", Some(body))
+            .await?;
+        Ok(())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.config.trading_pairs.contains(&pair.symbol)
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        Ok(self.config.configured_pairs())
+    }
+
+    async fn get_trading_fees(&self, pair: &TradingPair) -> BotResult<TradingFees> {
+        // The fee-rate endpoint needs credentials; data-only venues assume the VIP 0 spot rate
+        if self.config.api_key.is_empty() {
+            return Ok(TradingFees {
+                maker_fee: Decimal::new(1, 3),
+                taker_fee: Decimal::new(1, 3),
+            });
+        }
+        
+        let symbol = self.convert_symbol(pair);
+        if let Some(fees) = self.fee_cache.read().unwrap().get(&symbol) {
+            return Ok(fees.clone());
+        }
+        
+        let rates: BybitList<BybitFeeRate> = self
+            .make_signed_request(reqwest::Method::GET, "/v5/account/fee-rate", &format!("category=spot&symbol={}", symbol), None)
+            .await?;
+        let rate = rates.list.into_iter().next()
+            .ok_or_else(|| anyhow::anyhow!("Bybit returned no fee rate for {}", pair.symbol))?;
+        
+        let fees = TradingFees {
+            maker_fee: Decimal::from_str(&rate.maker_fee_rate)?,
+            taker_fee: Decimal::from_str(&rate.taker_fee_rate)?,
+        };
+        
+        self.fee_cache.write().unwrap().insert(symbol, fees.clone());
+        Ok(fees)
+    }
+}
@@ -0,0 +1,422 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::config::ExchangeConfig;
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::{Exchange, TradingFees};
+use crate::models::{Balance, OrderBook, OrderBookLevel, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::utils::{self, RateLimiter};
+
+pub struct CoinbaseExchange {
+    config: ExchangeConfig,
+    client: Client,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinbaseTicker {
+    best_bid: String,
+    best_ask: String,
+    #[serde(default)]
+    trades: Vec<CoinbaseTickerTrade>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinbaseTickerTrade {
+    time: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinbaseProductBookResponse {
+    pricebook: CoinbasePriceBook,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinbasePriceBook {
+    bids: Vec<CoinbaseBookLevel>,
+    asks: Vec<CoinbaseBookLevel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinbaseBookLevel {
+    price: String,
+    size: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinbaseAccountsResponse {
+    accounts: Vec<CoinbaseAccount>,
+    #[serde(default)]
+    has_next: bool,
+    #[serde(default)]
+    cursor: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinbaseAccount {
+    currency: String,
+    available_balance: CoinbaseAmount,
+    hold: Option<CoinbaseAmount>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinbaseAmount {
+    value: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CoinbaseOrderRequest {
+    client_order_id: String,
+    product_id: String,
+    side: String,
+    order_configuration: CoinbaseOrderConfiguration,
+}
+
+#[derive(Debug, Serialize)]
+enum CoinbaseOrderConfiguration {
+    #[serde(rename = "market_market_ioc")]
+    Market { base_size: String },
+    #[serde(rename = "limit_limit_gtc")]
+    Limit { base_size: String, limit_price: String },
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinbaseCreateOrderResponse {
+    success: bool,
+    success_response: Option<CoinbaseOrderId>,
+    error_response: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinbaseOrderId {
+    order_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinbaseOrderResponse {
+    order: CoinbaseOrder,
+}
+
+#[derive(Debug, Deserialize)]
+struct CoinbaseOrder {
+    order_id: String,
+    product_id: String,
+    side: String,
+    status: String,
+    filled_size: Option<String>,
+    average_filled_price: Option<String>,
+    total_fees: Option<String>,
+    created_time: Option<String>,
+    last_fill_time: Option<String>,
+}
+
+impl CoinbaseExchange {
+    pub fn new(config: ExchangeConfig) -> Result<Self> {
+        Ok(Self {
+            rate_limiter: utils::request_rate_limiter("coinbase", config.rate_limit, None),
+            client: utils::http_client_with(&config.http())?,
+            config,
+        })
+    }
+
+    fn convert_symbol(&self, pair: &TradingPair) -> String {
+        format!("{}-{}", pair.base, pair.quote)
+    }
+
+    fn pair_from_symbol(&self, symbol: &str) -> Option<TradingPair> {
+        self.config.configured_pairs().into_iter()
+            .find(|pair| self.convert_symbol(pair) == symbol)
+    }
+
+    fn create_signature(&self, timestamp: i64, method: &reqwest::Method, path: &str, body: &str) -> String {
+        use hmac::{Hmac, Mac};
+        use sha2::Sha256;
+        
+        type HmacSha256 = Hmac<Sha256>;
+        
+        let message = format!("{}{}{}{}", timestamp, method.as_str(), path, body);
+        let mut mac = HmacSha256::new_from_slice(self.config.api_secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(message.as_bytes());
+        
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    async fn make_public_request<T>(&self, endpoint: &str) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let url = format!("{}/api/v3/brokerage/market{}", self.config.api_url, endpoint);
+        let response = self.client.get(&url).send().await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Coinbase", response).await.into());
+        }
+        
+        Ok(response.json().await?)
+    }
+
+    async fn make_signed_request<T>(&self, method: reqwest::Method, endpoint: &str, query: &str, body: Option<String>) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        // The signature covers the path only; query parameters are not part of the prehash
+        let path = format!("/api/v3/brokerage{}", endpoint);
+        let body = body.unwrap_or_default();
+        let timestamp = Utc::now().timestamp();
+        let signature = self.create_signature(timestamp, &method, &path, &body);
+        
+        let url = if query.is_empty() {
+            format!("{}{}", self.config.api_url, path)
+        } else {
+            format!("{}{}?{}", self.config.api_url, path, query)
+        };
+        
+        let mut request = self.client
+            .request(method, &url)
+            .header("CB-ACCESS-KEY", &self.config.api_key)
+            .header("CB-ACCESS-SIGN", signature)
+            .header("CB-ACCESS-TIMESTAMP", timestamp.to_string());
+        if !body.is_empty() {
+            request = request.header("Content-Type", "application/json").body(body);
+        }
+        
+        let response = request.send().await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Coinbase", response).await.into());
+        }
+        
+        Ok(response.json().await?)
+    }
+
+    fn order_to_trade(&self, order: CoinbaseOrder) -> Result<Trade> {
+        let pair = self.pair_from_symbol(&order.product_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown Coinbase product: {}", order.product_id))?;
+        
+        let parse = |value: &Option<String>| -> Result<Decimal> {
+            match value.as_deref() {
+                Some(value) if !value.is_empty() => Ok(Decimal::from_str(value)?),
+                _ => Ok(Decimal::ZERO),
+            }
+        };
+        let parse_time = |value: &Option<String>| value.as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc));
+        
+        let status = match order.status.as_str() {
+            "FILLED" => TradeStatus::Executed,
+            "CANCELLED" | "EXPIRED" => TradeStatus::Cancelled,
+            "FAILED" => TradeStatus::Failed,
+            _ => TradeStatus::Pending,
+        };
+        
+        let side = if order.side == "BUY" { TradeSide::Buy } else { TradeSide::Sell };
+        
+        let executed_at = match status {
+            TradeStatus::Executed => parse_time(&order.last_fill_time).or_else(|| Some(Utc::now())),
+            _ => None,
+        };
+        
+        Ok(Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            fee_asset: Some(pair.quote.clone()),
+            pair,
+            side,
+            amount: parse(&order.filled_size)?,
+            price: parse(&order.average_filled_price)?,
+            fee_amount: parse(&order.total_fees)?,
+            status,
+            created_at: parse_time(&order.created_time).unwrap_or_else(Utc::now),
+            executed_at,
+            order_id: Some(order.order_id),
+            client_order_id: None,
+            tx_hash: None,
+            simulated: false,
+            failure_reason: None,
+        })
+    }
+
+    async fn place_order(&self, pair: &TradingPair, side: TradeSide, amount: Decimal, price: Option<Decimal>) -> Result<Trade> {
+        let base_size = amount.normalize().to_string();
+        let request = CoinbaseOrderRequest {
+            client_order_id: uuid::Uuid::new_v4().to_string(),
+            product_id: self.convert_symbol(pair),
+            side: match side {
+                TradeSide::Buy => "BUY".to_string(),
+                TradeSide::Sell => "SELL".to_string(),
+            },
+            order_configuration: match price {
+                Some(price) => CoinbaseOrderConfiguration::Limit {
+                    base_size,
+                    limit_price: price.normalize().to_string(),
+                },
+                None => CoinbaseOrderConfiguration::Market { base_size },
+            },
+        };
+        
+        let response: CoinbaseCreateOrderResponse = self
+            .make_signed_request(reqwest::Method::POST, "/orders", "", Some(serde_json::to_string(&request)?))
+            .await?;
+        
+        let order_id = match (response.success, response.success_response) {
+            (true, Some(success)) => success.order_id,
+            _ => return Err(BotError::rejected("Coinbase", None, response.error_response.unwrap_or_default().to_string()).into()),
+        };
+        
+        let mut trade = self.get_order_status(&order_id).await?;
+        if trade.amount.is_zero() {
+            trade.amount = amount;
+        }
+        Ok(trade)
+    }
+}
+
+#[async_trait]
+impl Exchange for CoinbaseExchange {
+    fn name(&self) -> &str {
+        "coinbase"
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        let product_id = self.convert_symbol(pair);
+        let ticker: CoinbaseTicker = self
+            .make_public_request(&format!("/products/{}/ticker?limit=1", product_id))
+            .await?;
+        
+        Ok(Price {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bid: Decimal::from_str(&ticker.best_bid)?,
+            ask: Decimal::from_str(&ticker.best_ask)?,
+            timestamp: ticker.trades.first()
+                .and_then(|trade| chrono::DateTime::parse_from_rfc3339(&trade.time).ok())
+                .map(|t| t.with_timezone(&Utc))
+                .unwrap_or_else(Utc::now),
+            volume_24h: None,
+            fetch_latency: None,
+        })
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        let product_id = self.convert_symbol(pair);
+        let response: CoinbaseProductBookResponse = self
+            .make_public_request(&format!("/product_book?product_id={}&limit={}", product_id, depth))
+            .await?;
+        
+        let to_levels = |levels: &[CoinbaseBookLevel]| -> Result<Vec<OrderBookLevel>> {
+            levels.iter()
+                .map(|level| Ok(OrderBookLevel {
+                    price: Decimal::from_str(&level.price)?,
+                    quantity: Decimal::from_str(&level.size)?,
+                }))
+                .collect()
+        };
+        
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bids: to_levels(&response.pricebook.bids)?,
+            asks: to_levels(&response.pricebook.asks)?,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        let mut balances = HashMap::new();
+        let mut cursor = String::new();
+        
+        loop {
+            let query = if cursor.is_empty() {
+                "limit=250".to_string()
+            } else {
+                format!("limit=250&cursor={}", cursor)
+            };
+            let response: CoinbaseAccountsResponse = self
+                .make_signed_request(reqwest::Method::GET, "/accounts", &query, None)
+                .await?;
+            
+            for account in response.accounts {
+                let free = Decimal::from_str(&account.available_balance.value)?;
+                let locked = match &account.hold {
+                    Some(hold) => Decimal::from_str(&hold.value)?,
+                    None => Decimal::ZERO,
+                };
+                let total = free + locked;
+                
+                if total > Decimal::ZERO {
+                    balances.insert(account.currency.clone(), Balance {
+                        asset: account.currency,
+                        free,
+                        locked,
+                        total,
+                        usd_value: Decimal::ZERO,
+                    });
+                }
+            }
+            
+            if !response.has_next || response.cursor.is_empty() {
+                break;
+            }
+            cursor = response.cursor;
+        }
+        
+        Ok(balances)
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Buy, amount, price).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Sell, amount, price).await?)
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        let response: CoinbaseOrderResponse = self
+            .make_signed_request(reqwest::Method::GET, &format!("/orders/historical/{}", order_id), "", None)
+            .await?;
+        
+        Ok(self.order_to_trade(response.order)?)
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> BotResult<()> {
+        let body = serde_json::json!({ "order_ids": [order_id] }).to_string();
+        let _: serde_json::Value = self
+            .make_signed_request(reqwest::Method::POST, "/orders/batch_cancel", "", Some(body))
+            .await?;
+        Ok(())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.config.trading_pairs.contains(&pair.symbol)
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        Ok(self.config.configured_pairs())
+    }
+
+    async fn get_trading_fees(&self, _pair: &TradingPair) -> BotResult<TradingFees> {
+        Ok(TradingFees {
+            maker_fee: Decimal::from_str("0.004")?,
+            taker_fee: Decimal::from_str("0.006")?,
+        })
+    }
+}
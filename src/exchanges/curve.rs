@@ -0,0 +1,536 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use ethers::prelude::*;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use crate::blockchain::failover::{self, RpcProvider};
+use crate::blockchain::gas::{GasOracle, GasPolicy};
+use crate::blockchain::multicall::TokenReader;
+use crate::blockchain::simulate;
+use crate::config::{ExchangeConfig, PoolConfig};
+use crate::errors::BotResult;
+use crate::exchanges::erc20::{self, SignerClient, TokenApprovals, ERC20};
+use crate::exchanges::univ2::{UniV2Params, UniswapV2Router};
+use crate::exchanges::{synthesize_ladder, Exchange, TradingFees};
+use crate::models::{Balance, OrderBook, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::utils;
+
+const CURVE_3POOL: &str = "0xbEbc44782C7dB0a1A60Cb6fe97d0b483032FF1C7";
+
+// Typical gas used by a stableswap exchange, used when estimate_gas is unavailable
+const DEFAULT_SWAP_GAS: u64 = 200_000;
+
+// Curve pool fees are expressed with 10 decimals
+const FEE_DENOMINATOR: u64 = 10_000_000_000;
+
+abigen!(
+    CurvePool,
+    r#"[
+        function get_dy(int128 i, int128 j, uint256 dx) external view returns (uint256)
+        function exchange(int128 i, int128 j, uint256 dx, uint256 min_dy) external
+        function fee() external view returns (uint256)
+        function coins(uint256 i) external view returns (address)
+    ]"#
+);
+
+pub struct CurveExchange {
+    config: ExchangeConfig,
+    provider: Arc<RpcProvider>,
+    tokens: TokenReader,
+    wallet: Option<LocalWallet>,
+    signer: Option<Arc<SignerClient>>,
+    max_slippage: Decimal,
+    pools: Vec<CurvePoolInfo>,
+    coin_addresses: Mutex<HashMap<(Address, i128), Address>>,
+    // Every pool is its own spender
+    approvals: HashMap<Address, TokenApprovals>,
+    submitted_swaps: Mutex<HashMap<String, SubmittedSwap>>,
+    gas_oracle: Arc<GasOracle>,
+}
+
+#[derive(Debug, Clone)]
+struct CurvePoolInfo {
+    address: Address,
+    coins: HashMap<String, i128>,
+}
+
+// A pair resolved to a pool and the coin indices of its base and quote tokens
+#[derive(Debug, Clone, Copy)]
+struct PoolRoute {
+    pool: Address,
+    base_index: i128,
+    quote_index: i128,
+}
+
+#[derive(Debug, Clone)]
+struct SubmittedSwap {
+    trade: Trade,
+    input_token: Address,
+    output_token: Address,
+}
+
+impl CurveExchange {
+    pub async fn new(config: ExchangeConfig, max_slippage: Decimal, gas_policy: GasPolicy) -> Result<Self> {
+        let provider = failover::rpc_provider(&[config.api_url.clone()], failover::DEFAULT_MAX_BLOCK_LAG, &config.http())?;
+        let provider = Arc::new(provider);
+        
+        let wallet = if !config.api_secret.is_empty() {
+            let chain_id = provider.get_chainid().await?.as_u64();
+            Some(config.api_secret.parse::<LocalWallet>()?.with_chain_id(chain_id))
+        } else {
+            None
+        };
+        
+        let signer = wallet.clone()
+            .map(|wallet| Arc::new(SignerMiddleware::new(provider.clone(), wallet)));
+        
+        let pool_configs = if config.pools.is_empty() {
+            vec![Self::default_3pool()]
+        } else {
+            config.pools.clone()
+        };
+        
+        let pools = pool_configs.into_iter()
+            .map(|pool| Ok(CurvePoolInfo {
+                address: pool.address.parse()?,
+                coins: pool.coins.into_iter()
+                    .map(|(symbol, index)| (symbol.to_uppercase(), index as i128))
+                    .collect(),
+            }))
+            .collect::<Result<Vec<_>>>()?;
+        
+        let gas_oracle = Arc::new(GasOracle::new(provider.clone(), gas_policy));
+        let approvals = pools.iter()
+            .map(|pool| (pool.address, TokenApprovals::new(pool.address, config.infinite_approval).with_gas_oracle(gas_oracle.clone())))
+            .collect();
+        
+        Ok(Self {
+            config,
+            tokens: TokenReader::new(provider.clone()),
+            provider,
+            wallet,
+            signer,
+            max_slippage,
+            pools,
+            coin_addresses: Mutex::new(HashMap::new()),
+            approvals,
+            submitted_swaps: Mutex::new(HashMap::new()),
+            gas_oracle,
+        })
+    }
+
+    fn default_3pool() -> PoolConfig {
+        PoolConfig {
+            address: CURVE_3POOL.to_string(),
+            coins: HashMap::from([
+                ("DAI".to_string(), 0),
+                ("USDC".to_string(), 1),
+                ("USDT".to_string(), 2),
+            ]),
+        }
+    }
+
+    fn find_route(&self, pair: &TradingPair) -> Option<PoolRoute> {
+        self.pools.iter().find_map(|pool| {
+            Some(PoolRoute {
+                pool: pool.address,
+                base_index: *pool.coins.get(&pair.base.to_uppercase())?,
+                quote_index: *pool.coins.get(&pair.quote.to_uppercase())?,
+            })
+        })
+    }
+
+    fn route(&self, pair: &TradingPair) -> Result<PoolRoute> {
+        self.find_route(pair)
+            .ok_or_else(|| anyhow::anyhow!("No configured Curve pool holds {}", pair.symbol))
+    }
+
+    async fn coin_address(&self, pool: Address, index: i128) -> Result<Address> {
+        if let Some(address) = self.coin_addresses.lock().unwrap().get(&(pool, index)) {
+            return Ok(*address);
+        }
+        
+        let contract = CurvePool::new(pool, self.provider.clone());
+        let address = contract.coins(U256::from(index as u64)).call().await?;
+        self.coin_addresses.lock().unwrap().insert((pool, index), address);
+        Ok(address)
+    }
+
+    async fn get_token_decimals(&self, token_address: Address) -> Result<u8> {
+        Ok(self.tokens.decimals(token_address).await?)
+    }
+
+    async fn get_dy(&self, pool: Address, i: i128, j: i128, dx: U256) -> Result<U256> {
+        let contract = CurvePool::new(pool, self.provider.clone());
+        Ok(contract.get_dy(i, j, dx).call().await?)
+    }
+
+    // Average sell and buy prices for a base quantity; get_dy only quotes exact input, so the buy side
+    // spends the sell proceeds and measures how much base they buy back
+    async fn quote_prices(&self, pair: &TradingPair, quantity: Decimal) -> Result<(Decimal, Decimal)> {
+        let route = self.route(pair)?;
+        let base_address = self.coin_address(route.pool, route.base_index).await?;
+        let quote_address = self.coin_address(route.pool, route.quote_index).await?;
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        
+        let base_units = utils::decimal_to_u256(quantity, base_decimals)?;
+        let proceeds_units = self.get_dy(route.pool, route.base_index, route.quote_index, base_units).await?;
+        let bought_units = self.get_dy(route.pool, route.quote_index, route.base_index, proceeds_units).await?;
+        
+        let proceeds = utils::u256_to_decimal(proceeds_units, quote_decimals)?;
+        let bought = utils::u256_to_decimal(bought_units, base_decimals)?;
+        
+        if bought.is_zero() {
+            anyhow::bail!("Curve returned an empty quote for {}", pair.symbol);
+        }
+        
+        Ok((proceeds / quantity, proceeds / bought))
+    }
+
+    fn apply_slippage(&self, amount: U256) -> U256 {
+        let slippage_bps = utils::decimal_to_u256(self.max_slippage * Decimal::from(10_000), 0).unwrap_or_default();
+        let bps = U256::from(10_000);
+        
+        amount * (bps - slippage_bps.min(bps)) / bps
+    }
+
+    async fn execute_swap(&self, pair: &TradingPair, side: TradeSide, amount: Decimal, price: Option<Decimal>) -> Result<Trade> {
+        let client = self.signer.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Curve connector is in read-only mode: no wallet configured"))?;
+        
+        let route = self.route(pair)?;
+        let base_address = self.coin_address(route.pool, route.base_index).await?;
+        let quote_address = self.coin_address(route.pool, route.quote_index).await?;
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        
+        let (i, j, input_token, output_token, amount_in) = match side {
+            TradeSide::Buy => {
+                let price = match price {
+                    Some(price) => price,
+                    None => self.quote_prices(pair, amount).await?.1,
+                };
+                let amount_in = utils::decimal_to_u256(amount * price, quote_decimals)?;
+                (route.quote_index, route.base_index, quote_address, base_address, amount_in)
+            },
+            TradeSide::Sell => {
+                let amount_in = utils::decimal_to_u256(amount, base_decimals)?;
+                (route.base_index, route.quote_index, base_address, quote_address, amount_in)
+            },
+        };
+        
+        let expected_out = self.get_dy(route.pool, i, j, amount_in).await?;
+        let min_dy = self.apply_slippage(expected_out);
+        
+        let approvals = self.approvals.get(&route.pool)
+            .ok_or_else(|| anyhow::anyhow!("No approvals tracked for pool {:?}", route.pool))?;
+        let fees = self.gas_oracle.fees().await?;
+        approvals.ensure(client, input_token, amount_in).await?;
+        
+        let pool = CurvePool::new(route.pool, client.clone());
+        let mut call = pool.exchange(i, j, amount_in, min_dy);
+        fees.apply(&mut call.tx);
+        simulate::simulate(client, &call.tx).await?;
+        let pending_tx = call.send().await?;
+        let tx_hash = format!("{:?}", pending_tx.tx_hash());
+        
+        approvals.consume(input_token, amount_in);
+        
+        let (base_amount, quote_amount) = match side {
+            TradeSide::Buy => (expected_out, amount_in),
+            TradeSide::Sell => (amount_in, expected_out),
+        };
+        let base_amount = utils::u256_to_decimal(base_amount, base_decimals)?;
+        let quote_amount = utils::u256_to_decimal(quote_amount, quote_decimals)?;
+        
+        tracing::info!("Submitted Curve swap {} for {} {} on pool {:?}", tx_hash, amount, pair.symbol, route.pool);
+        
+        let trade = Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            side,
+            amount,
+            price: price.unwrap_or(quote_amount / base_amount),
+            fee_amount: Decimal::ZERO,
+            fee_asset: None,
+            status: TradeStatus::Pending,
+            created_at: Utc::now(),
+            executed_at: None,
+            order_id: Some(tx_hash.clone()),
+            client_order_id: None,
+            tx_hash: Some(tx_hash.clone()),
+            simulated: false,
+            failure_reason: None,
+        };
+        
+        self.submitted_swaps.lock().unwrap().insert(tx_hash, SubmittedSwap {
+            trade: trade.clone(),
+            input_token,
+            output_token,
+        });
+        
+        Ok(trade)
+    }
+
+    // Curve stable pools hold no ETH, so gas is converted to the quote token through the Uniswap V2 router
+    async fn native_to_quote(&self, amount: Decimal, pair: &TradingPair) -> Result<Decimal> {
+        let route = self.route(pair)?;
+        let quote_address = self.coin_address(route.pool, route.quote_index).await?;
+        let weth_address = erc20::token_address(erc20::ETHEREUM_CHAIN_ID, "WETH")
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: WETH"))?;
+        
+        if quote_address == weth_address {
+            return Ok(amount);
+        }
+        
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        let router = UniswapV2Router::new(UniV2Params::uniswap()?.router, self.provider.clone());
+        let amounts_out = router.get_amounts_out(U256::exp10(18), vec![weth_address, quote_address]).call().await?;
+        let native_price = utils::u256_to_decimal(amounts_out.last().copied().unwrap_or_default(), quote_decimals)?;
+        
+        Ok(amount * native_price)
+    }
+
+    async fn estimate_swap_gas(&self, pair: &TradingPair, amount: Decimal) -> Result<U256> {
+        let client = self.signer.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Curve connector is in read-only mode: no wallet configured"))?;
+        
+        let route = self.route(pair)?;
+        let base_address = self.coin_address(route.pool, route.base_index).await?;
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let amount_in = utils::decimal_to_u256(amount, base_decimals)?;
+        
+        let pool = CurvePool::new(route.pool, client.clone());
+        let gas = pool.exchange(route.base_index, route.quote_index, amount_in, U256::zero())
+            .estimate_gas()
+            .await?;
+        Ok(gas)
+    }
+}
+
+#[async_trait]
+impl Exchange for CurveExchange {
+    fn name(&self) -> &str {
+        "curve"
+    }
+
+    fn chain_id(&self) -> Option<u64> {
+        Some(erc20::ETHEREUM_CHAIN_ID)
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        let (bid, ask) = self.quote_prices(pair, Decimal::ONE).await?;
+        
+        Ok(Price {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bid,
+            ask,
+            timestamp: Utc::now(),
+            volume_24h: None,
+            fetch_latency: None,
+        })
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        let (bids, asks) = synthesize_ladder(self.config.max_trade_amount, depth, |quantity| async move {
+            match self.quote_prices(pair, quantity).await {
+                Ok((bid, ask)) => Ok((Some(bid * quantity), Some(ask * quantity))),
+                Err(e) => {
+                    tracing::debug!("Curve quote for {} {} failed: {}", quantity, pair.symbol, e);
+                    Ok((None, None))
+                }
+            }
+        }).await?;
+        
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bids,
+            asks,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        let mut balances = HashMap::new();
+        
+        if let Some(wallet) = &self.wallet {
+            let eth_balance = self.provider.get_balance(wallet.address(), None).await?;
+            let eth_balance = utils::u256_to_decimal(eth_balance, 18)?;
+            
+            if eth_balance > Decimal::ZERO {
+                balances.insert("ETH".to_string(), Balance {
+                    asset: "ETH".to_string(),
+                    free: eth_balance,
+                    locked: Decimal::ZERO,
+                    total: eth_balance,
+                    usd_value: Decimal::ZERO,
+                });
+            }
+            
+            for pool in &self.pools {
+                for (symbol, index) in &pool.coins {
+                    if balances.contains_key(symbol) {
+                        continue;
+                    }
+                    
+                    let token_address = self.coin_address(pool.address, *index).await?;
+                    let token = ERC20::new(token_address, self.provider.clone());
+                    if let Ok(balance) = token.balance_of(wallet.address()).call().await {
+                        let decimals = self.get_token_decimals(token_address).await?;
+                        let balance_decimal = utils::u256_to_decimal(balance, decimals)?;
+                        
+                        if balance_decimal > Decimal::ZERO {
+                            balances.insert(symbol.clone(), Balance {
+                                asset: symbol.clone(),
+                                free: balance_decimal,
+                                locked: Decimal::ZERO,
+                                total: balance_decimal,
+                                usd_value: Decimal::ZERO,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        
+        Ok(balances)
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.execute_swap(pair, TradeSide::Buy, amount, price).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.execute_swap(pair, TradeSide::Sell, amount, price).await?)
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        let tx_hash: H256 = order_id.parse()
+            .map_err(|_| anyhow::anyhow!("Invalid transaction hash: {}", order_id))?;
+        
+        let swap = self.submitted_swaps.lock().unwrap().get(order_id).cloned()
+            .ok_or_else(|| anyhow::anyhow!("Unknown Curve swap: {}", order_id))?;
+        let mut trade = swap.trade.clone();
+        
+        let receipt = match self.provider.get_transaction_receipt(tx_hash).await? {
+            Some(receipt) => receipt,
+            None => return Ok(trade),
+        };
+        
+        let block_number = receipt.block_number
+            .ok_or_else(|| anyhow::anyhow!("Receipt for {} has no block number", order_id))?;
+        let current_block = self.provider.get_block_number().await?;
+        let confirmations = current_block.saturating_sub(block_number).as_u64() + 1;
+        
+        if confirmations < self.config.confirmations {
+            return Ok(trade);
+        }
+        
+        let executed_at = self.provider.get_block(block_number).await?
+            .and_then(|block| chrono::DateTime::from_timestamp(block.timestamp.as_u64() as i64, 0))
+            .unwrap_or_else(Utc::now);
+        trade.executed_at = Some(executed_at);
+        
+        if receipt.status != Some(U64::from(1)) {
+            trade.status = TradeStatus::Failed;
+            trade.failure_reason = simulate::replay_revert_reason(&self.provider, receipt.transaction_hash).await;
+            tracing::warn!("Swap {} reverted: {}", order_id, trade.failure_reason.as_deref().unwrap_or("unknown reason"));
+            self.submitted_swaps.lock().unwrap().remove(order_id);
+            return Ok(trade);
+        }
+        
+        let owner = receipt.from;
+        let amount_in = erc20::sum_transfers(&receipt.logs, swap.input_token, Some(owner), None);
+        let amount_out = erc20::sum_transfers(&receipt.logs, swap.output_token, None, Some(owner));
+        
+        let input_decimals = self.get_token_decimals(swap.input_token).await?;
+        let output_decimals = self.get_token_decimals(swap.output_token).await?;
+        let amount_in = utils::u256_to_decimal(amount_in, input_decimals)?;
+        let amount_out = utils::u256_to_decimal(amount_out, output_decimals)?;
+        
+        let (base_amount, quote_amount) = match trade.side {
+            TradeSide::Buy => (amount_out, amount_in),
+            TradeSide::Sell => (amount_in, amount_out),
+        };
+        
+        if base_amount > Decimal::ZERO {
+            trade.amount = base_amount;
+            trade.price = quote_amount / base_amount;
+        }
+        
+        // The pool fee is already reflected in the realized price, so only gas is recorded as a fee
+        if let (Some(gas_used), Some(gas_price)) = (receipt.gas_used, receipt.effective_gas_price) {
+            let gas_native = utils::u256_to_decimal(gas_used * gas_price, 18)?;
+            trade.fee_amount = self.native_to_quote(gas_native, &trade.pair).await?;
+            trade.fee_asset = Some(trade.pair.quote.clone());
+        }
+        trade.status = TradeStatus::Executed;
+        
+        self.submitted_swaps.lock().unwrap().remove(order_id);
+        
+        Ok(trade)
+    }
+
+    async fn cancel_order(&self, _order_id: &str) -> BotResult<()> {
+        Err(anyhow::anyhow!("Curve transactions cannot be cancelled").into())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.find_route(pair).is_some()
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        let pairs = self.config.trading_pairs.iter()
+            .filter_map(|symbol| self.parse_trading_pair(symbol))
+            .filter(|pair| self.supports_pair(pair))
+            .collect();
+        
+        Ok(pairs)
+    }
+
+    async fn get_trading_fees(&self, pair: &TradingPair) -> BotResult<TradingFees> {
+        let route = self.route(pair)?;
+        let pool = CurvePool::new(route.pool, self.provider.clone());
+        let fee = Decimal::from_str(&pool.fee().call().await?.to_string())? / Decimal::from(FEE_DENOMINATOR);
+        
+        Ok(TradingFees {
+            maker_fee: fee,
+            taker_fee: fee,
+        })
+    }
+
+    async fn get_execution_cost(&self, pair: &TradingPair, amount: Decimal) -> BotResult<Decimal> {
+        let gas_units = match self.estimate_swap_gas(pair, amount).await {
+            Ok(gas_units) => gas_units,
+            Err(e) => {
+                tracing::debug!("Gas estimation failed for {}, using default: {}", pair.symbol, e);
+                U256::from(DEFAULT_SWAP_GAS)
+            }
+        };
+        
+        let gas_price = self.gas_oracle.fees().await?.expected_price();
+        let cost_native = utils::u256_to_decimal(gas_units * gas_price, 18)?;
+        
+        Ok(self.native_to_quote(cost_native, pair).await?)
+    }
+}
+
+impl CurveExchange {
+    fn parse_trading_pair(&self, pair_str: &str) -> Option<TradingPair> {
+        let parts: Vec<&str> = pair_str.split('/').collect();
+        if parts.len() == 2 {
+            Some(TradingPair::new(parts[0], parts[1]))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use anyhow::Result;
+use ethers::prelude::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::blockchain::failover::RpcProvider;
+use crate::blockchain::gas::GasOracle;
+use crate::blockchain::nonce::TransactionManager;
+use crate::blockchain::simulate;
+use crate::errors::BotError;
+
+pub type SignerClient = SignerMiddleware<Arc<RpcProvider>, LocalWallet>;
+
+// ERC20 Token ABI
+abigen!(
+    ERC20,
+    r#"[
+        function balanceOf(address owner) external view returns (uint256)
+        function decimals() external view returns (uint8)
+        function symbol() external view returns (string)
+        function allowance(address owner, address spender) external view returns (uint256)
+        function approve(address spender, uint256 amount) external returns (bool)
+        event Transfer(address indexed from, address indexed to, uint256 value)
+    ]"#
+);
+
+// Gas for a plain token transfer out of the wallet, used to price DEX withdrawals
+pub const TRANSFER_GAS: u64 = 65_000;
+
+pub const ETHEREUM_CHAIN_ID: u64 = 1;
+pub const BSC_CHAIN_ID: u64 = 56;
+pub const POLYGON_CHAIN_ID: u64 = 137;
+
+// Token addresses differ per chain, so lookups are keyed by chain id
+pub fn token_address(chain_id: u64, symbol: &str) -> Option<Address> {
+    let address = match (chain_id, symbol.to_uppercase().as_str()) {
+        (ETHEREUM_CHAIN_ID, "USDC") => "0xA0b86a33E6441e5C46EE5F395f4c0C2D45C41B1A",
+        (ETHEREUM_CHAIN_ID, "USDT") => "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+        (ETHEREUM_CHAIN_ID, "DAI") => "0x6B175474E89094C44Da98b954EedeAC495271d0F",
+        (ETHEREUM_CHAIN_ID, "WETH") => "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+        (ETHEREUM_CHAIN_ID, "WBTC") => "0x2260FAC5E5542a773Aa44fBCfeDf7C193bc2C599",
+        (BSC_CHAIN_ID, "WBNB") => "0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c",
+        (BSC_CHAIN_ID, "BUSD") => "0xe9e7CEA3DedcA5984780Bafc599bD69ADd087D56",
+        (BSC_CHAIN_ID, "USDT") => "0x55d398326f99059fF775485246999027B3197955",
+        (BSC_CHAIN_ID, "USDC") => "0x8AC76a51cc950d9822D68b83fE1Ad5B32Cd580d",
+        (BSC_CHAIN_ID, "BTCB") => "0x7130d2A12B9BCbFAE4f2634d864A1Ee1Ce3Ead9c",
+        (BSC_CHAIN_ID, "ETH") => "0x2170Ed0880ac9A755fd29B2688956BD959F933F8",
+        (BSC_CHAIN_ID, "CAKE") => "0x0E09FaBB73Bd3Ade0a17ECC321fD13a19e81cE82",
+        (POLYGON_CHAIN_ID, "WMATIC") => "0x0d500B1d8E8eF31E21C99d1Db9A6444d3ADf1270",
+        (POLYGON_CHAIN_ID, "USDC") => "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174",
+        (POLYGON_CHAIN_ID, "USDT") => "0xc2132D05D31c914a87C65611C10748AEb04B58e8",
+        (POLYGON_CHAIN_ID, "DAI") => "0x8f3Cf7ad23Cd3CaDbD9735AFf958023239c6A063",
+        (POLYGON_CHAIN_ID, "WETH") => "0x7ceB23fD6bC0adD59E62ac25578270cFf1b9f619",
+        (POLYGON_CHAIN_ID, "WBTC") => "0x1BFD67037B42Cf73acF2047067bd4F2C47D9BfD6",
+        _ => return None,
+    };
+    address.parse().ok()
+}
+
+// Native gas token and its wrapped ERC20 for each supported chain
+pub fn native_symbols(chain_id: u64) -> Option<(&'static str, &'static str)> {
+    match chain_id {
+        ETHEREUM_CHAIN_ID => Some(("ETH", "WETH")),
+        BSC_CHAIN_ID => Some(("BNB", "WBNB")),
+        POLYGON_CHAIN_ID => Some(("MATIC", "WMATIC")),
+        _ => None,
+    }
+}
+
+pub fn sum_transfers(logs: &[Log], token: Address, from: Option<Address>, to: Option<Address>) -> U256 {
+    logs.iter()
+        .filter(|log| log.address == token)
+        .filter_map(|log| ethers::contract::parse_log::<TransferFilter>(log.clone()).ok())
+        .filter(|transfer| from.map_or(true, |from| transfer.from == from))
+        .filter(|transfer| to.map_or(true, |to| transfer.to == to))
+        .fold(U256::zero(), |acc, transfer| acc + transfer.value)
+}
+
+// Allowances granted to a single spender (router); each router needs its own approvals
+pub struct TokenApprovals {
+    spender: Address,
+    infinite: bool,
+    gas: Option<Arc<GasOracle>>,
+    transactions: Option<Arc<TransactionManager>>,
+    allowances: Mutex<HashMap<Address, U256>>,
+}
+
+impl TokenApprovals {
+    pub fn new(spender: Address, infinite: bool) -> Self {
+        Self {
+            spender,
+            infinite,
+            gas: None,
+            transactions: None,
+            allowances: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_gas_oracle(mut self, gas: Arc<GasOracle>) -> Self {
+        self.gas = Some(gas);
+        self
+    }
+
+    // Approvals then share the wallet's nonce sequence and get replaced if they stall
+    pub fn with_transaction_manager(mut self, transactions: Arc<TransactionManager>) -> Self {
+        self.transactions = Some(transactions);
+        self
+    }
+
+    pub fn spender(&self) -> Address {
+        self.spender
+    }
+
+    // Tokens like USDT revert when changing a non-zero allowance to another non-zero value
+    fn requires_allowance_reset(token: Address) -> bool {
+        token_address(ETHEREUM_CHAIN_ID, "USDT") == Some(token)
+    }
+
+    pub async fn ensure(&self, client: &Arc<SignerClient>, token_address: Address, amount: U256) -> Result<()> {
+        let cached = self.allowances.lock().unwrap().get(&token_address).copied();
+        if cached.map_or(false, |allowance| allowance >= amount) {
+            return Ok(());
+        }
+        
+        let token = ERC20::new(token_address, client.clone());
+        let current = token.allowance(client.address(), self.spender).call().await?;
+        
+        if current >= amount {
+            self.allowances.lock().unwrap().insert(token_address, current);
+            return Ok(());
+        }
+        
+        if !current.is_zero() && Self::requires_allowance_reset(token_address) {
+            self.send_approval(&token, U256::zero()).await?;
+        }
+        
+        let approval_amount = if self.infinite { U256::MAX } else { amount };
+        self.send_approval(&token, approval_amount).await?;
+        
+        self.allowances.lock().unwrap().insert(token_address, approval_amount);
+        Ok(())
+    }
+
+    async fn send_approval(&self, token: &ERC20<SignerClient>, amount: U256) -> Result<()> {
+        let mut call = token.approve(self.spender, amount);
+        
+        let receipt = if let Some(transactions) = &self.transactions {
+            let tx_hash = transactions.send(call.tx).await?;
+            tracing::info!("Submitted approval {:?} for token {:?} to spender {:?}", tx_hash, token.address(), self.spender);
+            transactions.confirm(tx_hash).await?
+        } else {
+            if let Some(gas) = &self.gas {
+                gas.fees().await?.apply(&mut call.tx);
+            }
+            simulate::simulate(&token.client(), &call.tx).await?;
+            let pending_tx = call.send().await?;
+            let tx_hash = pending_tx.tx_hash();
+            
+            tracing::info!("Submitted approval {:?} for token {:?} to spender {:?}", tx_hash, token.address(), self.spender);
+            
+            pending_tx.await?
+                .ok_or_else(|| anyhow::anyhow!("Approval {:?} was dropped from the mempool", tx_hash))?
+        };
+        let tx_hash = receipt.transaction_hash;
+        
+        if receipt.status != Some(U64::from(1)) {
+            return Err(BotError::chain(format!("Approval {:?} reverted", tx_hash)).into());
+        }
+        
+        Ok(())
+    }
+
+    // Seeds the cache with allowances read elsewhere, e.g. in a batched wallet snapshot
+    pub fn prime(&self, allowances: &HashMap<Address, U256>) {
+        self.allowances.lock().unwrap().extend(allowances);
+    }
+
+    pub fn consume(&self, token_address: Address, amount: U256) {
+        let mut allowances = self.allowances.lock().unwrap();
+        if let Some(allowance) = allowances.get_mut(&token_address) {
+            if *allowance != U256::MAX {
+                *allowance = allowance.saturating_sub(amount);
+            }
+        }
+    }
+}
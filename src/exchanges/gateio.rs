@@ -0,0 +1,432 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use crate::config::ExchangeConfig;
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::{Exchange, TradingFees};
+use crate::models::{Balance, OrderBook, OrderBookLevel, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::utils::{self, RateLimiter};
+
+// Depth used to price market buys, which Gate sizes in the quote currency
+const MARKET_BUY_BOOK_DEPTH: usize = 50;
+
+pub struct GateIoExchange {
+    config: ExchangeConfig,
+    client: Client,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    fee_cache: RwLock<HashMap<String, TradingFees>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GateTicker {
+    highest_bid: String,
+    lowest_ask: String,
+    base_volume: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GateOrderBook {
+    bids: Vec<[String; 2]>,
+    asks: Vec<[String; 2]>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GateAccount {
+    currency: String,
+    available: String,
+    locked: String,
+}
+
+#[derive(Debug, Serialize)]
+struct GateOrderRequest {
+    currency_pair: String,
+    side: String,
+    #[serde(rename = "type")]
+    order_type: String,
+    amount: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    price: Option<String>,
+    time_in_force: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GateOrder {
+    id: String,
+    currency_pair: String,
+    status: String,
+    side: String,
+    amount: String,
+    #[serde(default)]
+    price: String,
+    #[serde(default)]
+    left: String,
+    #[serde(default)]
+    avg_deal_price: String,
+    #[serde(default)]
+    fee: String,
+    #[serde(default)]
+    fee_currency: String,
+    #[serde(default)]
+    finish_as: String,
+    #[serde(default)]
+    create_time_ms: String,
+    #[serde(default)]
+    update_time_ms: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GateFee {
+    maker_fee: String,
+    taker_fee: String,
+}
+
+impl GateIoExchange {
+    pub fn new(config: ExchangeConfig) -> Result<Self> {
+        Ok(Self {
+            rate_limiter: utils::request_rate_limiter("gateio", config.rate_limit, None),
+            client: utils::http_client_with(&config.http())?,
+            config,
+            fee_cache: RwLock::new(HashMap::new()),
+        })
+    }
+
+    fn convert_symbol(&self, pair: &TradingPair) -> String {
+        format!("{}_{}", pair.base, pair.quote)
+    }
+
+    fn pair_from_symbol(&self, symbol: &str) -> Option<TradingPair> {
+        self.config.configured_pairs().into_iter()
+            .find(|pair| self.convert_symbol(pair) == symbol)
+    }
+
+    // Order lookups need the currency pair as well, so both are encoded in the order id we hand out
+    fn split_order_id(order_id: &str) -> Result<(&str, &str)> {
+        order_id.split_once(':')
+            .ok_or_else(|| anyhow::anyhow!("Invalid Gate.io order id: {}", order_id))
+    }
+
+    fn create_signature(&self, method: &reqwest::Method, path: &str, query: &str, body: &str, timestamp: &str) -> String {
+        use hmac::{Hmac, Mac};
+        use sha2::{Digest, Sha512};
+        
+        type HmacSha512 = Hmac<Sha512>;
+        
+        let body_hash = hex::encode(Sha512::digest(body.as_bytes()));
+        let message = format!("{}\n{}\n{}\n{}\n{}", method.as_str(), path, query, body_hash, timestamp);
+        
+        let mut mac = HmacSha512::new_from_slice(self.config.api_secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(message.as_bytes());
+        
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    async fn make_public_request<T>(&self, path: &str, query: &str) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let url = format!("{}{}?{}", self.config.api_url, path, query);
+        let response = self.client.get(&url).send().await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Gate.io", response).await.into());
+        }
+        
+        Ok(response.json().await?)
+    }
+
+    async fn make_signed_request<T>(&self, method: reqwest::Method, path: &str, query: &str, body: Option<String>) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let timestamp = Utc::now().timestamp().to_string();
+        let body = body.unwrap_or_default();
+        let signature = self.create_signature(&method, path, query, &body, &timestamp);
+        
+        let url = if query.is_empty() {
+            format!("{}{}", self.config.api_url, path)
+        } else {
+            format!("{}{}?{}", self.config.api_url, path, query)
+        };
+        
+        let mut request = self.client
+            .request(method, &url)
+            .header("KEY", &self.config.api_key)
+            .header("Timestamp", &timestamp)
+            .header("SIGN", signature)
+            .header("Accept", "application/json");
+        if !body.is_empty() {
+            request = request.header("Content-Type", "application/json").body(body);
+        }
+        
+        let response = request.send().await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Gate.io", response).await.into());
+        }
+        
+        Ok(response.json().await?)
+    }
+
+    fn order_to_trade(&self, order: GateOrder) -> Result<Trade> {
+        let pair = self.pair_from_symbol(&order.currency_pair)
+            .ok_or_else(|| anyhow::anyhow!("Unknown Gate.io currency pair: {}", order.currency_pair))?;
+        
+        let parse = |value: &str| -> Result<Decimal> {
+            if value.is_empty() {
+                Ok(Decimal::ZERO)
+            } else {
+                Ok(Decimal::from_str(value)?)
+            }
+        };
+        let parse_time = |value: &str| value.parse::<i64>().ok()
+            .and_then(chrono::DateTime::from_timestamp_millis);
+        
+        let side = if order.side == "buy" { TradeSide::Buy } else { TradeSide::Sell };
+        let amount = parse(&order.amount)?;
+        let filled = amount - parse(&order.left)?;
+        let price = if filled > Decimal::ZERO { parse(&order.avg_deal_price)? } else { parse(&order.price)? };
+        
+        // Immediate-or-cancel orders that filled partially finish as "ioc" with the remainder cancelled
+        let status = match order.status.as_str() {
+            "closed" => TradeStatus::Executed,
+            "cancelled" if order.finish_as == "ioc" && filled > Decimal::ZERO => TradeStatus::Executed,
+            "cancelled" => TradeStatus::Cancelled,
+            _ => TradeStatus::Pending,
+        };
+        
+        let executed_at = match status {
+            TradeStatus::Executed => parse_time(&order.update_time_ms).or_else(|| Some(Utc::now())),
+            _ => None,
+        };
+        
+        Ok(Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            pair,
+            side,
+            amount: if filled > Decimal::ZERO { filled } else { amount },
+            price,
+            fee_amount: parse(&order.fee)?,
+            fee_asset: (!order.fee_currency.is_empty()).then(|| order.fee_currency.clone()),
+            status,
+            created_at: parse_time(&order.create_time_ms).unwrap_or_else(Utc::now),
+            executed_at,
+            order_id: Some(format!("{}:{}", order.currency_pair, order.id)),
+            client_order_id: None,
+            tx_hash: None,
+            simulated: false,
+            failure_reason: None,
+        })
+    }
+
+    async fn place_order(&self, pair: &TradingPair, side: TradeSide, amount: Decimal, price: Option<Decimal>) -> Result<Trade> {
+        let symbol = self.convert_symbol(pair);
+        
+        // Gate sizes market buys in the quote currency, so a base-sized market buy becomes an
+        // immediate-or-cancel limit at the worst book level needed to fill it
+        let (order_type, price, time_in_force) = match (price, &side) {
+            (Some(price), _) => ("limit", Some(price), "gtc"),
+            (None, TradeSide::Buy) => {
+                let book = self.get_order_book(pair, MARKET_BUY_BOOK_DEPTH).await?;
+                let mut remaining = amount;
+                let mut worst_price = None;
+                for level in &book.asks {
+                    worst_price = Some(level.price);
+                    remaining -= level.quantity.min(remaining);
+                    if remaining.is_zero() {
+                        break;
+                    }
+                }
+                if !remaining.is_zero() {
+                    anyhow::bail!("Not enough Gate.io depth to buy {} {}", amount, pair.base);
+                }
+                ("limit", worst_price, "ioc")
+            },
+            (None, TradeSide::Sell) => ("market", None, "ioc"),
+        };
+        
+        let request = GateOrderRequest {
+            currency_pair: symbol,
+            side: match side {
+                TradeSide::Buy => "buy".to_string(),
+                TradeSide::Sell => "sell".to_string(),
+            },
+            order_type: order_type.to_string(),
+            amount: amount.normalize().to_string(),
+            price: price.map(|p| p.normalize().to_string()),
+            time_in_force: time_in_force.to_string(),
+        };
+        
+        let order: GateOrder = self
+            .make_signed_request(reqwest::Method::POST, "/api/v4/spot/orders", "", Some(serde_json::to_string(&request)?))
+            .await?;
+        
+        self.order_to_trade(order)
+    }
+}
+
+#[async_trait]
+impl Exchange for GateIoExchange {
+    fn name(&self) -> &str {
+        "gateio"
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        let tickers: Vec<GateTicker> = self
+            .make_public_request("/api/v4/spot/tickers", &format!("currency_pair={}", self.convert_symbol(pair)))
+            .await?;
+        let ticker = tickers.into_iter().next()
+            .ok_or_else(|| anyhow::anyhow!("Gate.io returned no ticker for {}", pair.symbol))?;
+        
+        Ok(Price {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bid: Decimal::from_str(&ticker.highest_bid)?,
+            ask: Decimal::from_str(&ticker.lowest_ask)?,
+            timestamp: Utc::now(),
+            volume_24h: Decimal::from_str(&ticker.base_volume).ok(),
+            fetch_latency: None,
+        })
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        let book: GateOrderBook = self
+            .make_public_request(
+                "/api/v4/spot/order_book",
+                &format!("currency_pair={}&limit={}", self.convert_symbol(pair), depth),
+            )
+            .await?;
+        
+        let to_levels = |levels: &[[String; 2]]| -> Result<Vec<OrderBookLevel>> {
+            levels.iter()
+                .map(|level| Ok(OrderBookLevel {
+                    price: Decimal::from_str(&level[0])?,
+                    quantity: Decimal::from_str(&level[1])?,
+                }))
+                .collect()
+        };
+        
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bids: to_levels(&book.bids)?,
+            asks: to_levels(&book.asks)?,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        let accounts: Vec<GateAccount> = self
+            .make_signed_request(reqwest::Method::GET, "/api/v4/spot/accounts", "", None)
+            .await?;
+        
+        let mut balances = HashMap::new();
+        
+        for account in accounts {
+            let free = Decimal::from_str(&account.available).unwrap_or_default();
+            let locked = Decimal::from_str(&account.locked).unwrap_or_default();
+            let total = free + locked;
+            
+            if total > Decimal::ZERO {
+                balances.insert(account.currency.clone(), Balance {
+                    asset: account.currency,
+                    free,
+                    locked,
+                    total,
+                    usd_value: Decimal::ZERO,
+                });
+            }
+        }
+        
+        Ok(balances)
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Buy, amount, price).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Sell, amount, price).await?)
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        let (symbol, id) = Self::split_order_id(order_id)?;
+        
+        let order: GateOrder = self
+            .make_signed_request(
+                reqwest::Method::GET,
+                &format!("/api/v4/spot/orders/{}", id),
+                &format!("currency_pair={}", symbol),
+                None,
+            )
+            .await?;
+        
+        Ok(self.order_to_trade(order)?)
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> BotResult<()> {
+        let (symbol, id) = Self::split_order_id(order_id)?;
+        
+        let _: serde_json::Value = self
+            .make_signed_request(
+                reqwest::Method::DELETE,
+                &format!("/api/v4/spot/orders/{}", id),
+                &format!("currency_pair={}", symbol),
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.config.trading_pairs.contains(&pair.symbol)
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        Ok(self.config.configured_pairs())
+    }
+
+    async fn get_trading_fees(&self, pair: &TradingPair) -> BotResult<TradingFees> {
+        // Without credentials (data-only venues) fall back to the default spot tier
+        if self.config.api_key.is_empty() {
+            return Ok(TradingFees {
+                maker_fee: Decimal::new(2, 3),
+                taker_fee: Decimal::new(2, 3),
+            });
+        }
+        
+        let symbol = self.convert_symbol(pair);
+        if let Some(fees) = self.fee_cache.read().unwrap().get(&symbol) {
+            return Ok(fees.clone());
+        }
+        
+        let rate: GateFee = self
+            .make_signed_request(reqwest::Method::GET, "/api/v4/wallet/fee", &format!("currency_pair={}", symbol), None)
+            .await?;
+        
+        let fees = TradingFees {
+            maker_fee: Decimal::from_str(&rate.maker_fee)?,
+            taker_fee: Decimal::from_str(&rate.taker_fee)?,
+        };
+        
+        self.fee_cache.write().unwrap().insert(symbol, fees.clone());
+        Ok(fees)
+    }
+}
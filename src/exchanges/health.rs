@@ -0,0 +1,239 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::config::HealthConfig;
+use crate::errors::BotError;
+
+// Weight of the newest sample in the latency moving average
+const LATENCY_SMOOTHING: f64 = 0.2;
+// Recent samples kept per exchange for the percentile and maximum
+const LATENCY_WINDOW: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HealthState {
+    Healthy,
+    // Skipped for price discovery and opportunity detection until the cooldown ends
+    Cooldown,
+    // Cooldown has ended; the next call decides between recovery and a longer cooldown
+    Probing,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExchangeHealth {
+    pub exchange: String,
+    pub state: HealthState,
+    pub consecutive_failures: u32,
+    pub total_failures: u64,
+    pub total_calls: u64,
+    pub average_latency: Option<Duration>,
+    pub p95_latency: Option<Duration>,
+    pub max_latency: Option<Duration>,
+    pub order_latency: Option<Duration>,
+    pub last_error: Option<String>,
+    pub cooldown_remaining: Option<Duration>,
+}
+
+#[derive(Debug, Default)]
+struct HealthRecord {
+    consecutive_failures: u32,
+    total_failures: u64,
+    total_calls: u64,
+    average_latency_secs: Option<f64>,
+    recent_latencies: VecDeque<f64>,
+    // Order placements only; quotes served from a stream cache would drag the overall average below what a leg takes
+    order_latency_secs: Option<f64>,
+    last_error: Option<String>,
+    cooldown_until: Option<Instant>,
+    // Consecutive cooldowns without a recovery in between, drives the backoff
+    cooldowns: u32,
+}
+
+impl HealthRecord {
+    fn state(&self, now: Instant) -> HealthState {
+        match self.cooldown_until {
+            Some(until) if now < until => HealthState::Cooldown,
+            Some(_) => HealthState::Probing,
+            None => HealthState::Healthy,
+        }
+    }
+
+    fn p95_latency(&self) -> Option<Duration> {
+        let mut samples: Vec<f64> = self.recent_latencies.iter().copied().collect();
+        samples.sort_by(|a, b| a.total_cmp(b));
+        let index = (samples.len() * 95).div_ceil(100).checked_sub(1)?;
+        samples.get(index).copied().map(Duration::from_secs_f64)
+    }
+}
+
+fn smooth(average: Option<f64>, sample: f64) -> f64 {
+    match average {
+        Some(average) => average + LATENCY_SMOOTHING * (sample - average),
+        None => sample,
+    }
+}
+
+pub struct HealthTracker {
+    config: RwLock<HealthConfig>,
+    records: Mutex<HashMap<String, HealthRecord>>,
+}
+
+impl HealthTracker {
+    pub fn new(config: HealthConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            records: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Keeps the records, so exchanges already cooling down finish their current cooldown
+    pub fn set_config(&self, config: HealthConfig) {
+        *self.config.write().unwrap() = config;
+    }
+
+    fn cooldown(&self, cooldowns: u32) -> Duration {
+        let config = self.config.read().unwrap();
+        let base = config.cooldown_seconds.max(1);
+        let backoff = base.saturating_mul(1_u64 << cooldowns.min(16));
+        Duration::from_secs(backoff.min(config.max_cooldown_seconds.max(base)))
+    }
+
+    pub fn is_available(&self, exchange: &str) -> bool {
+        let records = self.records.lock().unwrap();
+        records.get(exchange)
+            .map(|record| record.state(Instant::now()) != HealthState::Cooldown)
+            .unwrap_or(true)
+    }
+
+    pub fn record_success(&self, exchange: &str, operation: &str, latency: Duration) {
+        let mut records = self.records.lock().unwrap();
+        let record = records.entry(exchange.to_string()).or_default();
+        
+        record.total_calls += 1;
+        let sample = latency.as_secs_f64();
+        record.average_latency_secs = Some(smooth(record.average_latency_secs, sample));
+        if operation.starts_with("place_") {
+            record.order_latency_secs = Some(smooth(record.order_latency_secs, sample));
+        }
+        if record.recent_latencies.len() == LATENCY_WINDOW {
+            record.recent_latencies.pop_front();
+        }
+        record.recent_latencies.push_back(sample);
+        
+        if record.cooldown_until.is_some() {
+            info!("{} recovered after {} consecutive failures", exchange, record.consecutive_failures);
+        }
+        record.consecutive_failures = 0;
+        record.cooldown_until = None;
+        record.cooldowns = 0;
+    }
+
+    pub fn record_failure(&self, exchange: &str, error: &anyhow::Error) {
+        let now = Instant::now();
+        let mut records = self.records.lock().unwrap();
+        let record = records.entry(exchange.to_string()).or_default();
+        
+        record.total_calls += 1;
+        record.total_failures += 1;
+        record.last_error = Some(error.to_string());
+        
+        // Failures of calls that still go out during a cooldown (e.g. polling an open order) do not extend it
+        let state = record.state(now);
+        match BotError::find(error) {
+            // The venue answered and only refused this one request
+            Some(BotError::InsufficientBalance { .. } | BotError::InvalidSymbol { .. }) => return,
+            // Asked to back off: waiting for the failure threshold would only spend more of the limit
+            Some(BotError::RateLimited { retry_after, .. }) if state != HealthState::Cooldown => {
+                let cooldown = retry_after.unwrap_or_else(|| self.cooldown(record.cooldowns));
+                record.consecutive_failures += 1;
+                record.cooldown_until = Some(now + cooldown);
+                record.cooldowns += 1;
+                warn!("{} disabled for {:?} after hitting its rate limit: {}", exchange, cooldown, error);
+                return;
+            },
+            // Rejected credentials do not recover by themselves, so this goes straight to the longest cooldown
+            Some(BotError::AuthFailed { .. }) if state != HealthState::Cooldown => {
+                let cooldown = self.cooldown(u32::MAX);
+                record.consecutive_failures += 1;
+                record.cooldown_until = Some(now + cooldown);
+                record.cooldowns += 1;
+                warn!("{} disabled for {:?} after rejecting the API credentials: {}", exchange, cooldown, error);
+                return;
+            },
+            _ => {},
+        }
+        
+        record.consecutive_failures += 1;
+        let failure_threshold = self.config.read().unwrap().failure_threshold;
+        if state != HealthState::Cooldown && record.consecutive_failures >= failure_threshold {
+            let cooldown = self.cooldown(record.cooldowns);
+            record.cooldown_until = Some(now + cooldown);
+            record.cooldowns += 1;
+            warn!("{} disabled for {:?} after {} consecutive failures (last: {})",
+                  exchange, cooldown, record.consecutive_failures, error);
+        }
+    }
+
+    // What placing an order on `exchange` is expected to take, None until one has been placed there
+    pub fn order_latency(&self, exchange: &str) -> Option<Duration> {
+        let records = self.records.lock().unwrap();
+        records.get(exchange)?.order_latency_secs.map(Duration::from_secs_f64)
+    }
+
+    pub fn snapshot(&self) -> Vec<ExchangeHealth> {
+        let now = Instant::now();
+        let records = self.records.lock().unwrap();
+        
+        let mut health: Vec<_> = records.iter()
+            .map(|(exchange, record)| ExchangeHealth {
+                exchange: exchange.clone(),
+                state: record.state(now),
+                consecutive_failures: record.consecutive_failures,
+                total_failures: record.total_failures,
+                total_calls: record.total_calls,
+                average_latency: record.average_latency_secs.map(Duration::from_secs_f64),
+                p95_latency: record.p95_latency(),
+                max_latency: record.recent_latencies.iter().copied().reduce(f64::max).map(Duration::from_secs_f64),
+                order_latency: record.order_latency_secs.map(Duration::from_secs_f64),
+                last_error: record.last_error.clone(),
+                cooldown_remaining: record.cooldown_until
+                    .filter(|until| *until > now)
+                    .map(|until| until - now),
+            })
+            .collect();
+        health.sort_by(|a, b| a.exchange.cmp(&b.exchange));
+        health
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Refused requests leave the venue healthy; a rate limit or bad credentials bench it at once
+    #[test]
+    fn benches_venues_by_error_kind() {
+        let balance = BotError::InsufficientBalance { exchange: "alpha".to_string(), message: "Insufficient funds".to_string() };
+        let symbol = BotError::InvalidSymbol { exchange: "alpha".to_string(), message: "Invalid symbol.".to_string() };
+        let auth = BotError::AuthFailed { exchange: "gamma".to_string(), message: "Unauthorized".to_string() };
+        let health = HealthTracker::new(HealthConfig {
+            failure_threshold: 3,
+            cooldown_seconds: 10,
+            max_cooldown_seconds: 600,
+        });
+        for _ in 0..5 {
+            health.record_failure("alpha", &balance.clone().into());
+            health.record_failure("alpha", &symbol.clone().into());
+        }
+        assert!(health.is_available("alpha"), "refused orders put alpha in cooldown");
+        health.record_failure("beta", &BotError::RateLimited { exchange: "beta".to_string(), retry_after: Some(Duration::from_secs(30)), message: String::new() }.into());
+        health.record_failure("gamma", &auth.into());
+        let snapshot = health.snapshot();
+        let remaining = |exchange: &str| snapshot.iter().find(|health| health.exchange == exchange).and_then(|health| health.cooldown_remaining);
+        assert!(remaining("beta").map_or(false, |remaining| remaining > Duration::from_secs(25) && remaining <= Duration::from_secs(30)),
+               "a 30s rate limit left beta cooling for {:?}", remaining("beta"));
+        assert!(remaining("gamma").map_or(false, |remaining| remaining > Duration::from_secs(590)),
+               "rejected credentials left gamma cooling for {:?}", remaining("gamma"));
+    }
+}
@@ -0,0 +1,609 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use ethers::abi::{self, Token};
+use ethers::prelude::*;
+use ethers::utils::keccak256;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use crate::config::ExchangeConfig;
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::{Exchange, HedgeVenue, TradingFees};
+use crate::models::{Balance, OrderBook, OrderBookLevel, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::utils::{self, RateLimiter};
+
+// L1 actions are signed against a fixed EIP-712 domain, independent of the settlement chain
+const SIGNING_CHAIN_ID: u64 = 1337;
+// Perp prices are limited to 5 significant figures and 6 decimals minus the asset's size decimals
+const PRICE_SIGNIFICANT_FIGURES: u32 = 5;
+const MAX_PRICE_DECIMALS: u32 = 6;
+const COLLATERAL_ASSET: &str = "USDC";
+
+pub struct HyperliquidExchange {
+    config: ExchangeConfig,
+    client: Client,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    user: Option<Address>,
+    wallet: Option<LocalWallet>,
+    max_slippage: Decimal,
+    // Coin name to (asset index, size decimals)
+    assets: HashMap<String, (u32, u32)>,
+    fee_cache: RwLock<Option<TradingFees>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum Action {
+    Order { orders: Vec<OrderWire>, grouping: String },
+    Cancel { cancels: Vec<CancelWire> },
+}
+
+// Field order matters: the action is msgpack-encoded before hashing
+#[derive(Debug, Serialize)]
+struct OrderWire {
+    a: u32,
+    b: bool,
+    p: String,
+    s: String,
+    r: bool,
+    t: OrderTypeWire,
+}
+
+#[derive(Debug, Serialize)]
+struct OrderTypeWire {
+    limit: LimitWire,
+}
+
+#[derive(Debug, Serialize)]
+struct LimitWire {
+    tif: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CancelWire {
+    a: u32,
+    o: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Meta {
+    universe: Vec<AssetMeta>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AssetMeta {
+    name: String,
+    sz_decimals: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct L2Book {
+    levels: Vec<Vec<L2Level>>,
+    time: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct L2Level {
+    px: String,
+    sz: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClearinghouseState {
+    margin_summary: MarginSummary,
+    withdrawable: String,
+    asset_positions: Vec<AssetPosition>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarginSummary {
+    account_value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetPosition {
+    position: PositionData,
+}
+
+#[derive(Debug, Deserialize)]
+struct PositionData {
+    coin: String,
+    szi: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderStatusResponse {
+    status: String,
+    #[serde(default)]
+    order: Option<OrderStatusEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderStatusEntry {
+    order: OrderDetails,
+    status: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OrderDetails {
+    coin: String,
+    side: String,
+    limit_px: String,
+    orig_sz: String,
+    oid: u64,
+    timestamp: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Fill {
+    oid: u64,
+    px: String,
+    sz: String,
+    fee: String,
+    #[serde(rename = "feeToken", default)]
+    fee_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserFees {
+    user_cross_rate: String,
+    user_add_rate: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExchangeResponse {
+    status: String,
+    response: serde_json::Value,
+}
+
+impl HyperliquidExchange {
+    // api_key holds the account address, api_secret the key of an API wallet authorised to trade for it
+    pub async fn new(config: ExchangeConfig, max_slippage: Decimal) -> Result<Self> {
+        let user = if !config.api_key.is_empty() {
+            Some(Address::from_str(&config.api_key)
+                .map_err(|e| anyhow::anyhow!("Hyperliquid api_key must be the account address: {}", e))?)
+        } else {
+            None
+        };
+        let wallet = if !config.api_secret.is_empty() {
+            Some(config.api_secret.parse::<LocalWallet>()?)
+        } else {
+            None
+        };
+        
+        let mut exchange = Self {
+            rate_limiter: utils::request_rate_limiter("hyperliquid", config.rate_limit, None),
+            client: utils::http_client_with(&config.http())?,
+            config,
+            user,
+            wallet,
+            max_slippage,
+            assets: HashMap::new(),
+            fee_cache: RwLock::new(None),
+        };
+        
+        let meta: Meta = exchange.info(json!({ "type": "meta" })).await?;
+        exchange.assets = meta.universe.into_iter()
+            .enumerate()
+            .map(|(index, asset)| (asset.name, (index as u32, asset.sz_decimals)))
+            .collect();
+        
+        Ok(exchange)
+    }
+
+    // Perps are margined in USDC, so any configured quote is treated as USD and the base is the perp coin
+    fn asset(&self, pair: &TradingPair) -> Result<(u32, u32)> {
+        self.assets.get(&pair.base)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Hyperliquid has no perp market for {}", pair.base))
+    }
+
+    fn pair_from_coin(&self, coin: &str) -> TradingPair {
+        self.config.trading_pairs.iter()
+            .filter_map(|symbol| symbol.split_once('/'))
+            .find(|(base, _)| base.eq_ignore_ascii_case(coin))
+            .map(|(base, quote)| TradingPair::new(base, quote))
+            .unwrap_or_else(|| TradingPair::new(coin, COLLATERAL_ASSET))
+    }
+
+    // Order ids are handed out as "COIN:oid" so cancels know which asset to address
+    fn split_order_id(order_id: &str) -> Result<(&str, u64)> {
+        let (coin, oid) = order_id.split_once(':')
+            .ok_or_else(|| anyhow::anyhow!("Invalid Hyperliquid order id: {}", order_id))?;
+        Ok((coin, oid.parse()?))
+    }
+
+    fn user(&self) -> Result<Address> {
+        self.user.ok_or_else(|| anyhow::anyhow!("Hyperliquid account address (api_key) is not configured"))
+    }
+
+    fn is_testnet(&self) -> bool {
+        self.config.api_url.contains("testnet")
+    }
+
+    async fn info<T>(&self, request: serde_json::Value) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let url = format!("{}/info", self.config.api_url);
+        let response = self.client.post(&url).json(&request).send().await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Hyperliquid", response).await.into());
+        }
+        
+        Ok(response.json().await?)
+    }
+
+    fn sign_action(&self, action: &Action, nonce: u64) -> Result<Signature> {
+        let wallet = self.wallet.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Hyperliquid connector is in read-only mode: no API wallet configured"))?;
+        
+        let mut encoded = rmp_serde::to_vec_named(action)?;
+        encoded.extend_from_slice(&nonce.to_be_bytes());
+        // No vault address
+        encoded.push(0);
+        let connection_id = keccak256(encoded);
+        
+        let domain_separator = keccak256(abi::encode(&[
+            Token::FixedBytes(keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)").to_vec()),
+            Token::FixedBytes(keccak256("Exchange").to_vec()),
+            Token::FixedBytes(keccak256("1").to_vec()),
+            Token::Uint(U256::from(SIGNING_CHAIN_ID)),
+            Token::Address(Address::zero()),
+        ]));
+        let source = if self.is_testnet() { "b" } else { "a" };
+        let struct_hash = keccak256(abi::encode(&[
+            Token::FixedBytes(keccak256("Agent(string source,bytes32 connectionId)").to_vec()),
+            Token::FixedBytes(keccak256(source).to_vec()),
+            Token::FixedBytes(connection_id.to_vec()),
+        ]));
+        
+        let mut digest = vec![0x19, 0x01];
+        digest.extend_from_slice(&domain_separator);
+        digest.extend_from_slice(&struct_hash);
+        
+        Ok(wallet.sign_hash(H256::from(keccak256(digest)))?)
+    }
+
+    async fn exchange_action(&self, action: Action) -> Result<serde_json::Value> {
+        let nonce = Utc::now().timestamp_millis() as u64;
+        let signature = self.sign_action(&action, nonce)?;
+        
+        let body = json!({
+            "action": action,
+            "nonce": nonce,
+            "signature": {
+                "r": format!("{:#x}", signature.r),
+                "s": format!("{:#x}", signature.s),
+                "v": signature.v,
+            },
+            "vaultAddress": null,
+        });
+        
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let url = format!("{}/exchange", self.config.api_url);
+        let response = self.client.post(&url).json(&body).send().await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Hyperliquid", response).await.into());
+        }
+        
+        let response: ExchangeResponse = response.json().await?;
+        if response.status != "ok" {
+            return Err(BotError::rejected("Hyperliquid", None, response.response.to_string()).into());
+        }
+        
+        Ok(response.response)
+    }
+
+    fn format_price(price: Decimal, sz_decimals: u32) -> String {
+        price.round_sf(PRICE_SIGNIFICANT_FIGURES)
+            .unwrap_or(price)
+            .round_dp(MAX_PRICE_DECIMALS.saturating_sub(sz_decimals))
+            .normalize()
+            .to_string()
+    }
+
+    async fn mid_price(&self, coin: &str) -> Result<Decimal> {
+        let mids: HashMap<String, String> = self.info(json!({ "type": "allMids" })).await?;
+        let mid = mids.get(coin)
+            .ok_or_else(|| anyhow::anyhow!("Hyperliquid returned no mid price for {}", coin))?;
+        Ok(Decimal::from_str(mid)?)
+    }
+
+    // Filled size, average fill price and fees for an order, aggregated from the account's fills
+    async fn order_fills(&self, oid: u64) -> Result<(Decimal, Decimal, Decimal, Option<String>)> {
+        let fills: Vec<Fill> = self.info(json!({ "type": "userFills", "user": self.user()? })).await?;
+        
+        let mut filled = Decimal::ZERO;
+        let mut notional = Decimal::ZERO;
+        let mut fees = Decimal::ZERO;
+        let mut fee_asset = None;
+        
+        for fill in fills.iter().filter(|fill| fill.oid == oid) {
+            let size = Decimal::from_str(&fill.sz)?;
+            filled += size;
+            notional += size * Decimal::from_str(&fill.px)?;
+            fees += Decimal::from_str(&fill.fee)?;
+            fee_asset = fee_asset.or_else(|| fill.fee_token.clone());
+        }
+        
+        let average_price = if filled > Decimal::ZERO { notional / filled } else { Decimal::ZERO };
+        Ok((filled, average_price, fees, fee_asset))
+    }
+
+    // Market orders are sent as IOC limits at the mid price pushed out by the slippage budget
+    async fn place_order(&self, pair: &TradingPair, side: TradeSide, amount: Decimal, price: Option<Decimal>) -> Result<Trade> {
+        let (asset, sz_decimals) = self.asset(pair)?;
+        let is_buy = matches!(side, TradeSide::Buy);
+        
+        let (limit_price, tif) = match price {
+            Some(price) => (price, "Gtc"),
+            None => {
+                let mid = self.mid_price(&pair.base).await?;
+                let limit = if is_buy {
+                    mid * (Decimal::ONE + self.max_slippage)
+                } else {
+                    mid * (Decimal::ONE - self.max_slippage)
+                };
+                (limit, "Ioc")
+            }
+        };
+        
+        let size = amount.round_dp(sz_decimals);
+        if size <= Decimal::ZERO {
+            anyhow::bail!("Order size {} rounds to zero for {}", amount, pair.base);
+        }
+        
+        let action = Action::Order {
+            orders: vec![OrderWire {
+                a: asset,
+                b: is_buy,
+                p: Self::format_price(limit_price, sz_decimals),
+                s: size.normalize().to_string(),
+                r: false,
+                t: OrderTypeWire { limit: LimitWire { tif: tif.to_string() } },
+            }],
+            grouping: "na".to_string(),
+        };
+        
+        let response = self.exchange_action(action).await?;
+        let status = response["data"]["statuses"].get(0)
+            .ok_or_else(|| anyhow::anyhow!("Hyperliquid returned no order status"))?;
+        
+        if let Some(error) = status.get("error") {
+            return Err(BotError::rejected("Hyperliquid", None, error.to_string()).into());
+        }
+        let oid = status["filled"]["oid"].as_u64()
+            .or_else(|| status["resting"]["oid"].as_u64())
+            .ok_or_else(|| anyhow::anyhow!("Unexpected Hyperliquid order status: {}", status))?;
+        
+        Ok(self.get_order_status(&format!("{}:{}", pair.base, oid)).await?)
+    }
+}
+
+#[async_trait]
+impl Exchange for HyperliquidExchange {
+    fn name(&self) -> &str {
+        "hyperliquid"
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        let book = self.get_order_book(pair, 1).await?;
+        let bid = book.bids.first()
+            .ok_or_else(|| anyhow::anyhow!("Hyperliquid book for {} has no bids", pair.base))?;
+        let ask = book.asks.first()
+            .ok_or_else(|| anyhow::anyhow!("Hyperliquid book for {} has no asks", pair.base))?;
+        
+        Ok(Price {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bid: bid.price,
+            ask: ask.price,
+            timestamp: book.timestamp,
+            volume_24h: None,
+            fetch_latency: None,
+        })
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        self.asset(pair)?;
+        let book: L2Book = self.info(json!({ "type": "l2Book", "coin": pair.base })).await?;
+        if book.levels.len() != 2 {
+            return Err(anyhow::anyhow!("Unexpected Hyperliquid book shape for {}", pair.base).into());
+        }
+        
+        let to_levels = |levels: &[L2Level]| -> Result<Vec<OrderBookLevel>> {
+            levels.iter()
+                .take(depth)
+                .map(|level| Ok(OrderBookLevel {
+                    price: Decimal::from_str(&level.px)?,
+                    quantity: Decimal::from_str(&level.sz)?,
+                }))
+                .collect()
+        };
+        
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bids: to_levels(&book.levels[0])?,
+            asks: to_levels(&book.levels[1])?,
+            timestamp: book.time.and_then(chrono::DateTime::from_timestamp_millis).unwrap_or_else(Utc::now),
+        })
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        let state: ClearinghouseState = self.info(json!({ "type": "clearinghouseState", "user": self.user()? })).await?;
+        
+        let total = Decimal::from_str(&state.margin_summary.account_value)?;
+        let free = Decimal::from_str(&state.withdrawable)?;
+        
+        let mut balances = HashMap::new();
+        balances.insert(COLLATERAL_ASSET.to_string(), Balance {
+            asset: COLLATERAL_ASSET.to_string(),
+            free,
+            locked: total - free,
+            total,
+            usd_value: total,
+        });
+        
+        Ok(balances)
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Buy, amount, price).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Sell, amount, price).await?)
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        let (_, oid) = Self::split_order_id(order_id)?;
+        
+        let response: OrderStatusResponse = self.info(json!({ "type": "orderStatus", "user": self.user, "oid": oid })).await?;
+        let entry = response.order
+            .ok_or_else(|| anyhow::anyhow!("Hyperliquid order {} not found: {}", order_id, response.status))?;
+        let order = entry.order;
+        
+        let status = match entry.status.as_str() {
+            "filled" => TradeStatus::Executed,
+            "open" | "triggered" => TradeStatus::Pending,
+            "rejected" => TradeStatus::Failed,
+            _ => TradeStatus::Cancelled,
+        };
+        
+        // IOC orders that only partially fill end up canceled, so the fills are the source of truth
+        let (filled, average_price, fee_amount, fee_asset) = self.order_fills(oid).await?;
+        
+        let executed_at = match status {
+            TradeStatus::Executed => Some(Utc::now()),
+            _ => None,
+        };
+        
+        Ok(Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            pair: self.pair_from_coin(&order.coin),
+            side: if order.side == "B" { TradeSide::Buy } else { TradeSide::Sell },
+            amount: if filled > Decimal::ZERO { filled } else { Decimal::from_str(&order.orig_sz)? },
+            price: if filled > Decimal::ZERO { average_price } else { Decimal::from_str(&order.limit_px)? },
+            fee_amount,
+            fee_asset: fee_asset.or_else(|| Some(COLLATERAL_ASSET.to_string())),
+            status,
+            created_at: chrono::DateTime::from_timestamp_millis(order.timestamp).unwrap_or_else(Utc::now),
+            executed_at,
+            order_id: Some(format!("{}:{}", order.coin, order.oid)),
+            client_order_id: None,
+            tx_hash: None,
+            simulated: false,
+            failure_reason: None,
+        })
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> BotResult<()> {
+        let (coin, oid) = Self::split_order_id(order_id)?;
+        let (asset, _) = self.assets.get(coin)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Hyperliquid has no perp market for {}", coin))?;
+        
+        let response = self.exchange_action(Action::Cancel {
+            cancels: vec![CancelWire { a: asset, o: oid }],
+        }).await?;
+        
+        if let Some(error) = response["data"]["statuses"].get(0).and_then(|status| status.get("error")) {
+            return Err(BotError::rejected("Hyperliquid", None, error.to_string()));
+        }
+        Ok(())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.config.trading_pairs.contains(&pair.symbol) && self.assets.contains_key(&pair.base)
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        Ok(self.config.trading_pairs.iter()
+            .filter_map(|symbol| symbol.split_once('/'))
+            .map(|(base, quote)| TradingPair::new(base, quote))
+            .filter(|pair| self.assets.contains_key(&pair.base))
+            .collect())
+    }
+
+    // Fee rates are per account rather than per market
+    async fn get_trading_fees(&self, _pair: &TradingPair) -> BotResult<TradingFees> {
+        if let Some(fees) = self.fee_cache.read().unwrap().as_ref() {
+            return Ok(fees.clone());
+        }
+        
+        // Base tier for data-only use without an account
+        if self.user.is_none() {
+            return Ok(TradingFees {
+                maker_fee: Decimal::new(15, 5),
+                taker_fee: Decimal::new(45, 5),
+            });
+        }
+        
+        let user_fees: UserFees = self.info(json!({ "type": "userFees", "user": self.user()? })).await?;
+        let fees = TradingFees {
+            maker_fee: Decimal::from_str(&user_fees.user_add_rate)?,
+            taker_fee: Decimal::from_str(&user_fees.user_cross_rate)?,
+        };
+        
+        *self.fee_cache.write().unwrap() = Some(fees.clone());
+        Ok(fees)
+    }
+
+    fn hedge_venue(&self) -> Option<&dyn HedgeVenue> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl HedgeVenue for HyperliquidExchange {
+    async fn hedge(&self, pair: &TradingPair, size: Decimal, side: TradeSide) -> Result<Trade> {
+        let trade = self.place_order(pair, side, size, None).await?;
+        match trade.status {
+            TradeStatus::Executed => Ok(trade),
+            // A partially filled IOC still leaves a position open that has to be tracked
+            TradeStatus::Cancelled => {
+                let order_id = trade.order_id.as_deref().unwrap_or_default();
+                let (_, oid) = Self::split_order_id(order_id)?;
+                let (filled, _, _, _) = self.order_fills(oid).await?;
+                if filled.is_zero() {
+                    anyhow::bail!("Hedge order for {} {} did not fill", size, pair.base);
+                }
+                Ok(trade)
+            },
+            _ => anyhow::bail!("Hedge order for {} {} ended as {:?}", size, pair.base, trade.status),
+        }
+    }
+
+    async fn get_position(&self, pair: &TradingPair) -> Result<Decimal> {
+        let state: ClearinghouseState = self.info(json!({ "type": "clearinghouseState", "user": self.user()? })).await?;
+        
+        state.asset_positions.iter()
+            .find(|position| position.position.coin == pair.base)
+            .map(|position| Ok(Decimal::from_str(&position.position.szi)?))
+            .unwrap_or(Ok(Decimal::ZERO))
+    }
+}
@@ -0,0 +1,401 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::config::ExchangeConfig;
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::{Exchange, TradingFees};
+use crate::models::{Balance, OrderBook, OrderBookLevel, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::utils::{self, RateLimiter};
+
+// Kraken still uses its legacy asset codes on the wire
+const ASSET_ALIASES: &[(&str, &str)] = &[
+    ("XBT", "BTC"),
+    ("XXBT", "BTC"),
+    ("XETH", "ETH"),
+    ("XXDG", "DOGE"),
+    ("XDG", "DOGE"),
+    ("XLTC", "LTC"),
+    ("XXRP", "XRP"),
+    ("XXLM", "XLM"),
+    ("ZUSD", "USD"),
+    ("ZEUR", "EUR"),
+    ("ZGBP", "GBP"),
+    ("ZJPY", "JPY"),
+    ("ZCAD", "CAD"),
+];
+
+pub struct KrakenExchange {
+    config: ExchangeConfig,
+    client: Client,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    last_nonce: AtomicU64,
+}
+
+#[derive(Debug, Deserialize)]
+struct KrakenResponse<T> {
+    error: Vec<String>,
+    result: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KrakenTicker {
+    // [price, whole lot volume, lot volume]
+    a: Vec<String>,
+    b: Vec<String>,
+    // [today, last 24 hours]
+    v: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KrakenOrderBook {
+    bids: Vec<(String, String, serde_json::Value)>,
+    asks: Vec<(String, String, serde_json::Value)>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KrakenAddOrderResult {
+    txid: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KrakenOrder {
+    status: String,
+    vol: String,
+    vol_exec: String,
+    cost: String,
+    fee: String,
+    price: String,
+    opentm: f64,
+    closetm: Option<f64>,
+    descr: KrakenOrderDescription,
+}
+
+#[derive(Debug, Deserialize)]
+struct KrakenOrderDescription {
+    pair: String,
+    #[serde(rename = "type")]
+    side: String,
+    price: String,
+}
+
+impl KrakenExchange {
+    pub fn new(config: ExchangeConfig) -> Result<Self> {
+        Ok(Self {
+            rate_limiter: utils::request_rate_limiter("kraken", config.rate_limit, None),
+            client: utils::http_client_with(&config.http())?,
+            config,
+            last_nonce: AtomicU64::new(0),
+        })
+    }
+
+    pub fn normalize_asset(asset: &str) -> String {
+        ASSET_ALIASES.iter()
+            .find(|(kraken, _)| *kraken == asset)
+            .map(|(_, symbol)| symbol.to_string())
+            .unwrap_or_else(|| asset.to_string())
+    }
+
+    fn kraken_asset(symbol: &str) -> &str {
+        match symbol {
+            "BTC" => "XBT",
+            "DOGE" => "XDG",
+            other => other,
+        }
+    }
+
+    fn convert_symbol(&self, pair: &TradingPair) -> String {
+        format!("{}{}", Self::kraken_asset(&pair.base), Self::kraken_asset(&pair.quote))
+    }
+
+    fn pair_from_symbol(&self, symbol: &str) -> Option<TradingPair> {
+        self.config.configured_pairs().into_iter()
+            .find(|pair| self.convert_symbol(pair) == symbol)
+    }
+
+    // Private requests must carry a strictly increasing nonce
+    fn next_nonce(&self) -> u64 {
+        let now = Utc::now().timestamp_micros() as u64;
+        let mut last = self.last_nonce.load(Ordering::SeqCst);
+        loop {
+            let nonce = now.max(last + 1);
+            match self.last_nonce.compare_exchange(last, nonce, Ordering::SeqCst, Ordering::SeqCst) {
+                Ok(_) => return nonce,
+                Err(current) => last = current,
+            }
+        }
+    }
+
+    fn create_signature(&self, path: &str, nonce: u64, post_data: &str) -> Result<String> {
+        use base64::Engine;
+        use hmac::{Hmac, Mac};
+        use sha2::{Digest, Sha256, Sha512};
+        
+        type HmacSha512 = Hmac<Sha512>;
+        
+        let secret = base64::engine::general_purpose::STANDARD.decode(&self.config.api_secret)
+            .map_err(|e| anyhow::anyhow!("Kraken API secret is not valid base64: {}", e))?;
+        
+        let mut sha256 = Sha256::new();
+        sha256.update(nonce.to_string().as_bytes());
+        sha256.update(post_data.as_bytes());
+        let digest = sha256.finalize();
+        
+        let mut mac = HmacSha512::new_from_slice(&secret)
+            .expect("HMAC can take key of any size");
+        mac.update(path.as_bytes());
+        mac.update(&digest);
+        
+        Ok(base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes()))
+    }
+
+    fn unwrap_response<T>(response: KrakenResponse<T>) -> Result<T> {
+        if !response.error.is_empty() {
+            return Err(BotError::rejected("Kraken", None, response.error.join(", ")).into());
+        }
+        
+        response.result.ok_or_else(|| anyhow::anyhow!("Kraken API returned no result"))
+    }
+
+    async fn make_public_request<T>(&self, endpoint: &str, params: &[(&str, String)]) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let url = format!("{}/0/public/{}", self.config.api_url, endpoint);
+        let response: KrakenResponse<T> = self.client.get(&url).query(params).send().await?.json().await?;
+        Self::unwrap_response(response)
+    }
+
+    async fn make_private_request<T>(&self, endpoint: &str, params: &HashMap<String, String>) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let path = format!("/0/private/{}", endpoint);
+        let nonce = self.next_nonce();
+        
+        let mut body_params = params.clone();
+        body_params.insert("nonce".to_string(), nonce.to_string());
+        let post_data = serde_urlencoded::to_string(&body_params)?;
+        let signature = self.create_signature(&path, nonce, &post_data)?;
+        
+        let response = self.client
+            .post(format!("{}{}", self.config.api_url, path))
+            .header("API-Key", &self.config.api_key)
+            .header("API-Sign", signature)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(post_data)
+            .send()
+            .await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("Kraken", response).await.into());
+        }
+        
+        Self::unwrap_response(response.json().await?)
+    }
+
+    fn order_to_trade(&self, order_id: &str, order: KrakenOrder) -> Result<Trade> {
+        let pair = self.pair_from_symbol(&order.descr.pair)
+            .ok_or_else(|| anyhow::anyhow!("Unknown Kraken pair: {}", order.descr.pair))?;
+        
+        let volume = Decimal::from_str(&order.vol)?;
+        let executed = Decimal::from_str(&order.vol_exec)?;
+        let cost = Decimal::from_str(&order.cost)?;
+        
+        let price = if executed > Decimal::ZERO {
+            cost / executed
+        } else {
+            Decimal::from_str(&order.price)
+                .or_else(|_| Decimal::from_str(&order.descr.price))
+                .unwrap_or_default()
+        };
+        
+        let status = match order.status.as_str() {
+            "closed" => TradeStatus::Executed,
+            "canceled" | "expired" => TradeStatus::Cancelled,
+            _ => TradeStatus::Pending,
+        };
+        
+        let side = if order.descr.side == "buy" { TradeSide::Buy } else { TradeSide::Sell };
+        
+        let executed_at = match status {
+            TradeStatus::Executed => order.closetm
+                .and_then(|t| chrono::DateTime::from_timestamp_millis((t * 1000.0) as i64)),
+            _ => None,
+        };
+        
+        Ok(Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            fee_asset: Some(pair.quote.clone()),
+            pair,
+            side,
+            amount: if executed > Decimal::ZERO { executed } else { volume },
+            price,
+            fee_amount: Decimal::from_str(&order.fee)?,
+            status,
+            created_at: chrono::DateTime::from_timestamp_millis((order.opentm * 1000.0) as i64).unwrap_or_else(Utc::now),
+            executed_at,
+            order_id: Some(order_id.to_string()),
+            client_order_id: None,
+            tx_hash: None,
+            simulated: false,
+            failure_reason: None,
+        })
+    }
+
+    async fn place_order(&self, pair: &TradingPair, side: TradeSide, amount: Decimal, price: Option<Decimal>) -> Result<Trade> {
+        let mut params = HashMap::new();
+        params.insert("pair".to_string(), self.convert_symbol(pair));
+        params.insert("type".to_string(), match side {
+            TradeSide::Buy => "buy".to_string(),
+            TradeSide::Sell => "sell".to_string(),
+        });
+        params.insert("ordertype".to_string(), if price.is_some() { "limit".to_string() } else { "market".to_string() });
+        params.insert("volume".to_string(), amount.normalize().to_string());
+        if let Some(price) = price {
+            params.insert("price".to_string(), price.normalize().to_string());
+        }
+        
+        let result: KrakenAddOrderResult = self.make_private_request("AddOrder", &params).await?;
+        let order_id = result.txid.into_iter().next()
+            .ok_or_else(|| anyhow::anyhow!("Kraken did not return an order id"))?;
+        
+        Ok(self.get_order_status(&order_id).await?)
+    }
+}
+
+#[async_trait]
+impl Exchange for KrakenExchange {
+    fn name(&self) -> &str {
+        "kraken"
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        let result: HashMap<String, KrakenTicker> = self
+            .make_public_request("Ticker", &[("pair", self.convert_symbol(pair))])
+            .await?;
+        
+        // Kraken keys the result by its canonical pair name, which may differ from the one requested
+        let ticker = result.into_values().next()
+            .ok_or_else(|| anyhow::anyhow!("Kraken returned no ticker for {}", pair.symbol))?;
+        
+        let first = |values: &[String]| values.first().cloned().unwrap_or_default();
+        
+        Ok(Price {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bid: Decimal::from_str(&first(&ticker.b))?,
+            ask: Decimal::from_str(&first(&ticker.a))?,
+            timestamp: Utc::now(),
+            volume_24h: ticker.v.get(1).and_then(|v| Decimal::from_str(v).ok()),
+            fetch_latency: None,
+        })
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        let result: HashMap<String, KrakenOrderBook> = self
+            .make_public_request("Depth", &[("pair", self.convert_symbol(pair)), ("count", depth.to_string())])
+            .await?;
+        
+        let order_book = result.into_values().next()
+            .ok_or_else(|| anyhow::anyhow!("Kraken returned no order book for {}", pair.symbol))?;
+        
+        let to_levels = |levels: &[(String, String, serde_json::Value)]| -> Result<Vec<OrderBookLevel>> {
+            levels.iter()
+                .map(|(price, quantity, _)| Ok(OrderBookLevel {
+                    price: Decimal::from_str(price)?,
+                    quantity: Decimal::from_str(quantity)?,
+                }))
+                .collect()
+        };
+        
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bids: to_levels(&order_book.bids)?,
+            asks: to_levels(&order_book.asks)?,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        let result: HashMap<String, String> = self.make_private_request("Balance", &HashMap::new()).await?;
+        
+        let mut balances = HashMap::new();
+        
+        for (asset, amount) in result {
+            let total = Decimal::from_str(&amount)?;
+            if total > Decimal::ZERO {
+                let asset = Self::normalize_asset(&asset);
+                balances.insert(asset.clone(), Balance {
+                    asset,
+                    free: total,
+                    locked: Decimal::ZERO,
+                    total,
+                    usd_value: Decimal::ZERO,
+                });
+            }
+        }
+        
+        Ok(balances)
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Buy, amount, price).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Sell, amount, price).await?)
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        let mut params = HashMap::new();
+        params.insert("txid".to_string(), order_id.to_string());
+        
+        let mut result: HashMap<String, KrakenOrder> = self.make_private_request("QueryOrders", &params).await?;
+        let order = result.remove(order_id)
+            .ok_or_else(|| anyhow::anyhow!("Kraken order not found: {}", order_id))?;
+        
+        Ok(self.order_to_trade(order_id, order)?)
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> BotResult<()> {
+        let mut params = HashMap::new();
+        params.insert("txid".to_string(), order_id.to_string());
+        
+        let _: serde_json::Value = self.make_private_request("CancelOrder", &params).await?;
+        Ok(())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.config.trading_pairs.contains(&pair.symbol)
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        Ok(self.config.configured_pairs())
+    }
+
+    async fn get_trading_fees(&self, _pair: &TradingPair) -> BotResult<TradingFees> {
+        Ok(TradingFees {
+            maker_fee: Decimal::from_str("0.0016")?,
+            taker_fee: Decimal::from_str("0.0026")?,
+        })
+    }
+}
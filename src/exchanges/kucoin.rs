@@ -0,0 +1,354 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::config::ExchangeConfig;
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::{Exchange, TradingFees};
+use crate::models::{Balance, OrderBook, OrderBookLevel, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::utils::{self, RateLimiter};
+
+const SUCCESS_CODE: &str = "200000";
+
+pub struct KucoinExchange {
+    config: ExchangeConfig,
+    client: Client,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KucoinResponse<T> {
+    code: String,
+    #[serde(default)]
+    msg: String,
+    data: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KucoinTicker {
+    best_bid: String,
+    best_ask: String,
+    time: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KucoinOrderBook {
+    bids: Vec<[String; 2]>,
+    asks: Vec<[String; 2]>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KucoinAccount {
+    currency: String,
+    balance: String,
+    available: String,
+    holds: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct KucoinOrderRequest {
+    client_oid: String,
+    side: String,
+    symbol: String,
+    #[serde(rename = "type")]
+    order_type: String,
+    size: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    price: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct KucoinOrder {
+    symbol: String,
+    side: String,
+    size: String,
+    price: String,
+    deal_size: String,
+    deal_funds: String,
+    fee: String,
+    fee_currency: String,
+    is_active: bool,
+    cancel_exist: bool,
+    created_at: i64,
+}
+
+impl KucoinExchange {
+    pub fn new(config: ExchangeConfig) -> Result<Self> {
+        Ok(Self {
+            rate_limiter: utils::request_rate_limiter("kucoin", config.rate_limit, None),
+            client: utils::http_client_with(&config.http())?,
+            config,
+        })
+    }
+
+    fn convert_symbol(&self, pair: &TradingPair) -> String {
+        format!("{}-{}", pair.base, pair.quote)
+    }
+
+    fn pair_from_symbol(&self, symbol: &str) -> Option<TradingPair> {
+        self.config.configured_pairs().into_iter()
+            .find(|pair| self.convert_symbol(pair) == symbol)
+    }
+
+    fn sign(&self, message: &str) -> String {
+        use base64::Engine;
+        use hmac::{Hmac, Mac};
+        use sha2::Sha256;
+        
+        type HmacSha256 = Hmac<Sha256>;
+        
+        let mut mac = HmacSha256::new_from_slice(self.config.api_secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(message.as_bytes());
+        
+        base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes())
+    }
+
+    fn unwrap_response<T>(response: KucoinResponse<T>) -> Result<T> {
+        if response.code != SUCCESS_CODE {
+            return Err(BotError::rejected("KuCoin", Some(response.code), response.msg).into());
+        }
+        response.data.ok_or_else(|| anyhow::anyhow!("KuCoin API returned no data"))
+    }
+
+    async fn make_public_request<T>(&self, endpoint: &str) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let url = format!("{}{}", self.config.api_url, endpoint);
+        let response: KucoinResponse<T> = self.client.get(&url).send().await?.json().await?;
+        Self::unwrap_response(response)
+    }
+
+    async fn make_signed_request<T>(&self, method: reqwest::Method, endpoint: &str, body: Option<String>) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let passphrase = self.config.api_passphrase.as_deref()
+            .ok_or_else(|| anyhow::anyhow!("KuCoin requires api_passphrase to be configured"))?;
+        
+        let body = body.unwrap_or_default();
+        let timestamp = Utc::now().timestamp_millis().to_string();
+        let signature = self.sign(&format!("{}{}{}{}", timestamp, method.as_str(), endpoint, body));
+        
+        // Key version 2 expects the passphrase itself to be signed with the API secret
+        let mut request = self.client
+            .request(method, format!("{}{}", self.config.api_url, endpoint))
+            .header("KC-API-KEY", &self.config.api_key)
+            .header("KC-API-SIGN", signature)
+            .header("KC-API-TIMESTAMP", timestamp)
+            .header("KC-API-PASSPHRASE", self.sign(passphrase))
+            .header("KC-API-KEY-VERSION", "2");
+        if !body.is_empty() {
+            request = request.header("Content-Type", "application/json").body(body);
+        }
+        
+        let response = request.send().await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("KuCoin", response).await.into());
+        }
+        
+        Self::unwrap_response(response.json().await?)
+    }
+
+    fn order_to_trade(&self, client_oid: &str, order: KucoinOrder) -> Result<Trade> {
+        let pair = self.pair_from_symbol(&order.symbol)
+            .ok_or_else(|| anyhow::anyhow!("Unknown KuCoin symbol: {}", order.symbol))?;
+        
+        let size = Decimal::from_str(&order.size).unwrap_or_default();
+        let deal_size = Decimal::from_str(&order.deal_size)?;
+        let deal_funds = Decimal::from_str(&order.deal_funds)?;
+        
+        let price = if deal_size > Decimal::ZERO {
+            deal_funds / deal_size
+        } else {
+            Decimal::from_str(&order.price).unwrap_or_default()
+        };
+        
+        let status = if order.is_active {
+            TradeStatus::Pending
+        } else if order.cancel_exist {
+            TradeStatus::Cancelled
+        } else {
+            TradeStatus::Executed
+        };
+        
+        let side = if order.side == "buy" { TradeSide::Buy } else { TradeSide::Sell };
+        
+        let executed_at = match status {
+            TradeStatus::Executed => Some(Utc::now()),
+            _ => None,
+        };
+        
+        Ok(Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            pair,
+            side,
+            amount: if deal_size > Decimal::ZERO { deal_size } else { size },
+            price,
+            fee_amount: Decimal::from_str(&order.fee).unwrap_or_default(),
+            fee_asset: (!order.fee_currency.is_empty()).then(|| order.fee_currency.clone()),
+            status,
+            created_at: chrono::DateTime::from_timestamp_millis(order.created_at).unwrap_or_else(Utc::now),
+            executed_at,
+            order_id: Some(client_oid.to_string()),
+            client_order_id: None,
+            tx_hash: None,
+            simulated: false,
+            failure_reason: None,
+        })
+    }
+
+    // KuCoin requires a caller-supplied clientOid; it doubles as the order id we hand out
+    async fn place_order(&self, pair: &TradingPair, side: TradeSide, amount: Decimal, price: Option<Decimal>) -> Result<Trade> {
+        let client_oid = uuid::Uuid::new_v4().to_string();
+        let request = KucoinOrderRequest {
+            client_oid: client_oid.clone(),
+            side: match side {
+                TradeSide::Buy => "buy".to_string(),
+                TradeSide::Sell => "sell".to_string(),
+            },
+            symbol: self.convert_symbol(pair),
+            order_type: if price.is_some() { "limit".to_string() } else { "market".to_string() },
+            size: amount.normalize().to_string(),
+            price: price.map(|p| p.normalize().to_string()),
+        };
+        
+        let _: serde_json::Value = self
+            .make_signed_request(reqwest::Method::POST, "/api/v1/orders", Some(serde_json::to_string(&request)?))
+            .await?;
+        
+        Ok(self.get_order_status(&client_oid).await?)
+    }
+}
+
+#[async_trait]
+impl Exchange for KucoinExchange {
+    fn name(&self) -> &str {
+        "kucoin"
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        let ticker: KucoinTicker = self
+            .make_public_request(&format!("/api/v1/market/orderbook/level1?symbol={}", self.convert_symbol(pair)))
+            .await?;
+        
+        Ok(Price {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bid: Decimal::from_str(&ticker.best_bid)?,
+            ask: Decimal::from_str(&ticker.best_ask)?,
+            timestamp: ticker.time.and_then(chrono::DateTime::from_timestamp_millis).unwrap_or_else(Utc::now),
+            volume_24h: None,
+            fetch_latency: None,
+        })
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        // Public partial books only come in 20 and 100 level variants
+        let levels = if depth <= 20 { 20 } else { 100 };
+        let book: KucoinOrderBook = self
+            .make_public_request(&format!("/api/v1/market/orderbook/level2_{}?symbol={}", levels, self.convert_symbol(pair)))
+            .await?;
+        
+        let to_levels = |levels: &[[String; 2]]| -> Result<Vec<OrderBookLevel>> {
+            levels.iter()
+                .take(depth)
+                .map(|level| Ok(OrderBookLevel {
+                    price: Decimal::from_str(&level[0])?,
+                    quantity: Decimal::from_str(&level[1])?,
+                }))
+                .collect()
+        };
+        
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bids: to_levels(&book.bids)?,
+            asks: to_levels(&book.asks)?,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        let accounts: Vec<KucoinAccount> = self
+            .make_signed_request(reqwest::Method::GET, "/api/v1/accounts?type=trade", None)
+            .await?;
+        
+        let mut balances = HashMap::new();
+        
+        for account in accounts {
+            let total = Decimal::from_str(&account.balance).unwrap_or_default();
+            
+            if total > Decimal::ZERO {
+                balances.insert(account.currency.clone(), Balance {
+                    asset: account.currency,
+                    free: Decimal::from_str(&account.available).unwrap_or_default(),
+                    locked: Decimal::from_str(&account.holds).unwrap_or_default(),
+                    total,
+                    usd_value: Decimal::ZERO,
+                });
+            }
+        }
+        
+        Ok(balances)
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Buy, amount, price).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Sell, amount, price).await?)
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        let order: KucoinOrder = self
+            .make_signed_request(reqwest::Method::GET, &format!("/api/v1/order/client-order/{}", order_id), None)
+            .await?;
+        
+        Ok(self.order_to_trade(order_id, order)?)
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> BotResult<()> {
+        let _: serde_json::Value = self
+            .make_signed_request(reqwest::Method::DELETE, &format!("/api/v1/order/client-order/{}", order_id), None)
+            .await?;
+        Ok(())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.config.trading_pairs.contains(&pair.symbol)
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        Ok(self.config.configured_pairs())
+    }
+
+    async fn get_trading_fees(&self, _pair: &TradingPair) -> BotResult<TradingFees> {
+        Ok(TradingFees {
+            maker_fee: Decimal::from_str("0.001")?,
+            taker_fee: Decimal::from_str("0.001")?,
+        })
+    }
+}
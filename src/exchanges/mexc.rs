@@ -0,0 +1,420 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use crate::config::ExchangeConfig;
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::binance::sign_query;
+use crate::exchanges::{Exchange, TradingFees};
+use crate::models::{Balance, OrderBook, OrderBookLevel, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::utils::{self, RateLimiter};
+
+// MEXC throttles aggressively, so stay well under its published limits unless configured otherwise
+const DEFAULT_RATE_LIMIT: u32 = 10;
+
+pub struct MexcExchange {
+    config: ExchangeConfig,
+    client: Client,
+    fee_cache: RwLock<HashMap<String, TradingFees>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MexcBookTicker {
+    bid_price: String,
+    ask_price: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MexcOrderBook {
+    bids: Vec<[String; 2]>,
+    asks: Vec<[String; 2]>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MexcBalance {
+    asset: String,
+    free: String,
+    locked: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MexcAccountInfo {
+    balances: Vec<MexcBalance>,
+}
+
+#[derive(Debug, Serialize)]
+struct MexcOrderRequest {
+    symbol: String,
+    side: String,
+    #[serde(rename = "type")]
+    order_type: String,
+    quantity: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    price: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MexcOrderAck {
+    symbol: String,
+    order_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MexcOrder {
+    symbol: String,
+    order_id: String,
+    price: String,
+    orig_qty: String,
+    executed_qty: String,
+    cummulative_quote_qty: String,
+    status: String,
+    side: String,
+    #[serde(default)]
+    time: Option<i64>,
+    #[serde(default)]
+    update_time: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MexcTrade {
+    commission: String,
+    commission_asset: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MexcFeeResponse {
+    data: MexcFeeRate,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MexcFeeRate {
+    maker_commission: Decimal,
+    taker_commission: Decimal,
+}
+
+impl MexcExchange {
+    pub fn new(config: ExchangeConfig) -> Result<Self> {
+        Ok(Self {
+            rate_limiter: utils::request_rate_limiter("mexc", config.rate_limit, Some(DEFAULT_RATE_LIMIT)),
+            client: utils::http_client_with(&config.http())?,
+            config,
+            fee_cache: RwLock::new(HashMap::new()),
+        })
+    }
+
+    fn convert_symbol(&self, pair: &TradingPair) -> String {
+        format!("{}{}", pair.base, pair.quote)
+    }
+
+    fn pair_from_symbol(&self, symbol: &str) -> Option<TradingPair> {
+        self.config.configured_pairs().into_iter()
+            .find(|pair| self.convert_symbol(pair) == symbol)
+    }
+
+    // MEXC order ids are only unique per symbol, so both are encoded in the order id we hand out
+    fn split_order_id(order_id: &str) -> Result<(&str, &str)> {
+        order_id.split_once(':')
+            .ok_or_else(|| anyhow::anyhow!("Invalid MEXC order id: {}", order_id))
+    }
+
+    async fn make_public_request<T>(&self, endpoint: &str, query: &str) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let url = format!("{}{}?{}", self.config.api_url, endpoint, query);
+        let response = self.client.get(&url).send().await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("MEXC", response).await.into());
+        }
+        
+        Ok(response.json().await?)
+    }
+
+    async fn make_signed_request<T>(&self, method: reqwest::Method, endpoint: &str, params: &HashMap<String, String>) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let mut query_params = params.clone();
+        query_params.insert("timestamp".to_string(), Utc::now().timestamp_millis().to_string());
+        
+        let query_string = serde_urlencoded::to_string(&query_params)?;
+        let signature = sign_query(&self.config.api_secret, &query_string);
+        
+        let url = format!("{}{}?{}&signature={}", self.config.api_url, endpoint, query_string, signature);
+        
+        let response = self.client
+            .request(method, &url)
+            .header("X-MEXC-APIKEY", &self.config.api_key)
+            .header("Content-Type", "application/json")
+            .send()
+            .await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("MEXC", response).await.into());
+        }
+        
+        Ok(response.json().await?)
+    }
+
+    fn order_to_trade(&self, order: MexcOrder) -> Result<Trade> {
+        let pair = self.pair_from_symbol(&order.symbol)
+            .ok_or_else(|| anyhow::anyhow!("Unknown MEXC symbol: {}", order.symbol))?;
+        
+        let executed_qty = Decimal::from_str(&order.executed_qty)?;
+        let quote_qty = Decimal::from_str(&order.cummulative_quote_qty).unwrap_or_default();
+        
+        let price = if executed_qty > Decimal::ZERO && quote_qty > Decimal::ZERO {
+            quote_qty / executed_qty
+        } else {
+            Decimal::from_str(&order.price).unwrap_or_default()
+        };
+        
+        let status = match order.status.as_str() {
+            "FILLED" => TradeStatus::Executed,
+            "CANCELED" | "PARTIALLY_CANCELED" => TradeStatus::Cancelled,
+            _ => TradeStatus::Pending,
+        };
+        
+        let side = if order.side == "BUY" { TradeSide::Buy } else { TradeSide::Sell };
+        
+        let executed_at = match status {
+            TradeStatus::Executed => Some(order.update_time
+                .and_then(chrono::DateTime::from_timestamp_millis)
+                .unwrap_or_else(Utc::now)),
+            _ => None,
+        };
+        
+        Ok(Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            pair,
+            side,
+            amount: if executed_qty > Decimal::ZERO { executed_qty } else { Decimal::from_str(&order.orig_qty)? },
+            price,
+            fee_amount: Decimal::ZERO,
+            fee_asset: None,
+            status,
+            created_at: order.time
+                .and_then(chrono::DateTime::from_timestamp_millis)
+                .unwrap_or_else(Utc::now),
+            executed_at,
+            order_id: Some(format!("{}:{}", order.symbol, order.order_id)),
+            client_order_id: None,
+            tx_hash: None,
+            simulated: false,
+            failure_reason: None,
+        })
+    }
+
+    // Commission is only reported on the fills
+    async fn order_fees(&self, symbol: &str, id: &str) -> Result<(Decimal, Option<String>)> {
+        let mut params = HashMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+        params.insert("orderId".to_string(), id.to_string());
+        
+        let trades: Vec<MexcTrade> = self
+            .make_signed_request(reqwest::Method::GET, "/api/v3/myTrades", &params)
+            .await?;
+        
+        let mut fee_amount = Decimal::ZERO;
+        for trade in &trades {
+            fee_amount += Decimal::from_str(&trade.commission)?;
+        }
+        
+        Ok((fee_amount, trades.first().map(|trade| trade.commission_asset.clone())))
+    }
+
+    async fn place_order(&self, pair: &TradingPair, side: TradeSide, amount: Decimal, price: Option<Decimal>) -> Result<Trade> {
+        let request = MexcOrderRequest {
+            symbol: self.convert_symbol(pair),
+            side: match side {
+                TradeSide::Buy => "BUY".to_string(),
+                TradeSide::Sell => "SELL".to_string(),
+            },
+            order_type: if price.is_some() { "LIMIT".to_string() } else { "MARKET".to_string() },
+            quantity: amount.normalize().to_string(),
+            price: price.map(|p| p.normalize().to_string()),
+        };
+        
+        let params: HashMap<String, String> = serde_urlencoded::from_str(&serde_urlencoded::to_string(&request)?)?;
+        let ack: MexcOrderAck = self
+            .make_signed_request(reqwest::Method::POST, "/api/v3/order", &params)
+            .await?;
+        
+        Ok(self.get_order_status(&format!("{}:{}", ack.symbol, ack.order_id)).await?)
+    }
+}
+
+#[async_trait]
+impl Exchange for MexcExchange {
+    fn name(&self) -> &str {
+        "mexc"
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        let ticker: MexcBookTicker = self
+            .make_public_request("/api/v3/ticker/bookTicker", &format!("symbol={}", self.convert_symbol(pair)))
+            .await?;
+        
+        Ok(Price {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bid: Decimal::from_str(&ticker.bid_price)?,
+            ask: Decimal::from_str(&ticker.ask_price)?,
+            timestamp: Utc::now(),
+            volume_24h: None,
+            fetch_latency: None,
+        })
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        let book: MexcOrderBook = self
+            .make_public_request("/api/v3/depth", &format!("symbol={}&limit={}", self.convert_symbol(pair), depth))
+            .await?;
+        
+        let to_levels = |levels: &[[String; 2]]| -> Result<Vec<OrderBookLevel>> {
+            levels.iter()
+                .map(|level| Ok(OrderBookLevel {
+                    price: Decimal::from_str(&level[0])?,
+                    quantity: Decimal::from_str(&level[1])?,
+                }))
+                .collect()
+        };
+        
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bids: to_levels(&book.bids)?,
+            asks: to_levels(&book.asks)?,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        let account_info: MexcAccountInfo = self
+            .make_signed_request(reqwest::Method::GET, "/api/v3/account", &HashMap::new())
+            .await?;
+        
+        let mut balances = HashMap::new();
+        
+        for balance in account_info.balances {
+            let free = Decimal::from_str(&balance.free).unwrap_or_default();
+            let locked = Decimal::from_str(&balance.locked).unwrap_or_default();
+            let total = free + locked;
+            
+            if total > Decimal::ZERO {
+                balances.insert(balance.asset.clone(), Balance {
+                    asset: balance.asset,
+                    free,
+                    locked,
+                    total,
+                    usd_value: Decimal::ZERO,
+                });
+            }
+        }
+        
+        Ok(balances)
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Buy, amount, price).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Sell, amount, price).await?)
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        let (symbol, id) = Self::split_order_id(order_id)?;
+        
+        let mut params = HashMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+        params.insert("orderId".to_string(), id.to_string());
+        
+        let order: MexcOrder = self
+            .make_signed_request(reqwest::Method::GET, "/api/v3/order", &params)
+            .await?;
+        
+        let mut trade = self.order_to_trade(order)?;
+        if matches!(trade.status, TradeStatus::Executed) {
+            let (fee_amount, fee_asset) = self.order_fees(symbol, id).await?;
+            trade.fee_amount = fee_amount;
+            trade.fee_asset = fee_asset;
+        }
+        
+        Ok(trade)
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> BotResult<()> {
+        let (symbol, id) = Self::split_order_id(order_id)?;
+        
+        let mut params = HashMap::new();
+        params.insert("symbol".to_string(), symbol.to_string());
+        params.insert("orderId".to_string(), id.to_string());
+        
+        let _: serde_json::Value = self
+            .make_signed_request(reqwest::Method::DELETE, "/api/v3/order", &params)
+            .await?;
+        Ok(())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.config.trading_pairs.contains(&pair.symbol)
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        Ok(self.config.configured_pairs())
+    }
+
+    async fn get_trading_fees(&self, pair: &TradingPair) -> BotResult<TradingFees> {
+        // tradeFee is a signed endpoint, so data-only venues use the published default rates
+        if self.config.api_key.is_empty() {
+            return Ok(TradingFees {
+                maker_fee: Decimal::ZERO,
+                taker_fee: Decimal::new(5, 4),
+            });
+        }
+        
+        let symbol = self.convert_symbol(pair);
+        if let Some(fees) = self.fee_cache.read().unwrap().get(&symbol) {
+            return Ok(fees.clone());
+        }
+        
+        let mut params = HashMap::new();
+        params.insert("symbol".to_string(), symbol.clone());
+        
+        let response: MexcFeeResponse = self
+            .make_signed_request(reqwest::Method::GET, "/api/v3/tradeFee", &params)
+            .await?;
+        
+        let fees = TradingFees {
+            maker_fee: response.data.maker_commission,
+            taker_fee: response.data.taker_commission,
+        };
+        
+        self.fee_cache.write().unwrap().insert(symbol, fees.clone());
+        Ok(fees)
+    }
+}
@@ -0,0 +1,512 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::config::TimeInForce;
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::{Exchange, OrderAmount, OrderRequest, OutcomeUnknown, TokenProbe, TradingFees, TransferStatus};
+use crate::models::{Balance, OpenOrder, OrderBook, OrderBookLevel, PoolReserves, Price, Trade, TradeSide, TradeStatus, TradingPair};
+
+#[derive(Debug, Clone, Copy)]
+pub enum FillBehavior {
+    Instant,
+    // Fills the given fraction of the requested amount
+    Partial(Decimal),
+    Reject,
+    // Orders stay open with nothing filled until cancelled
+    Rest,
+    // Fills the given fraction at once and leaves the rest open until cancelled
+    PartialRest(Decimal),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MockCall {
+    GetPrice,
+    GetOrderBook,
+    GetBalances,
+    PlaceOrder,
+    GetOrderStatus,
+    GetOpenOrders,
+    CancelOrder,
+}
+
+struct MockState {
+    prices: HashMap<String, Price>,
+    order_books: HashMap<String, OrderBook>,
+    // Set to stand in for an AMM pool, which sizing prefers over the book
+    pools: HashMap<String, PoolReserves>,
+    balances: HashMap<String, Decimal>,
+    fees: TradingFees,
+    execution_cost: Decimal,
+    fill_behavior: FillBehavior,
+    // Used by the next orders in turn before falling back to `fill_behavior`
+    scripted_fills: VecDeque<FillBehavior>,
+    failures: HashMap<MockCall, u32>,
+    // What the injected failures are raised as; untyped when not set
+    failure_errors: HashMap<MockCall, BotError>,
+    latency: Duration,
+    // How old get_price reports each pair's quote as, by symbol
+    quote_ages: HashMap<String, chrono::Duration>,
+    // Assets not listed can be deposited and withdrawn
+    transfer_statuses: HashMap<String, TransferStatus>,
+    // Set to stand in for an on-chain venue, whose base tokens get probed
+    chain_id: Option<u64>,
+    // Probe results by base asset; assets not listed probe clean
+    token_probes: HashMap<String, TokenProbe>,
+    orders: HashMap<String, Trade>,
+    next_order_id: u64,
+    // Base already filled on orders that are still open
+    resting_fills: HashMap<String, Decimal>,
+    // Client id -> order id, when the mock accepts client ids
+    client_order_ids: Option<HashMap<String, String>>,
+    // Placements that fail with an unknown outcome: lost requests never arrive, lost replies are carried out
+    lost_requests: u32,
+    lost_replies: u32,
+}
+
+impl MockState {
+    // A cancelled order that filled in part reports what it filled as executed, as Binance does
+    fn cancel(&mut self, order_id: &str) -> Option<Trade> {
+        let filled = self.resting_fills.remove(order_id);
+        let order = self.orders.get_mut(order_id)?;
+        if matches!(order.status, TradeStatus::Pending) {
+            match filled {
+                Some(filled) => {
+                    order.status = TradeStatus::Executed;
+                    order.amount = filled;
+                },
+                None => order.status = TradeStatus::Cancelled,
+            }
+        }
+        Some(order.clone())
+    }
+}
+
+// Scriptable in-memory exchange for exercising the engine without API keys
+pub struct MockExchange {
+    name: String,
+    state: Mutex<MockState>,
+}
+
+impl MockExchange {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            state: Mutex::new(MockState {
+                prices: HashMap::new(),
+                order_books: HashMap::new(),
+                pools: HashMap::new(),
+                balances: HashMap::new(),
+                fees: TradingFees {
+                    maker_fee: Decimal::ZERO,
+                    taker_fee: Decimal::ZERO,
+                },
+                execution_cost: Decimal::ZERO,
+                fill_behavior: FillBehavior::Instant,
+                scripted_fills: VecDeque::new(),
+                failures: HashMap::new(),
+                failure_errors: HashMap::new(),
+                latency: Duration::ZERO,
+                quote_ages: HashMap::new(),
+                transfer_statuses: HashMap::new(),
+                chain_id: None,
+                token_probes: HashMap::new(),
+                orders: HashMap::new(),
+                next_order_id: 1,
+                resting_fills: HashMap::new(),
+                client_order_ids: None,
+                lost_requests: 0,
+                lost_replies: 0,
+            }),
+        }
+    }
+
+    pub fn set_price(&self, pair: &TradingPair, bid: Decimal, ask: Decimal) {
+        self.state.lock().unwrap().prices.insert(pair.symbol.clone(), Price {
+            exchange: self.name.clone(),
+            pair: pair.clone(),
+            bid,
+            ask,
+            timestamp: Utc::now(),
+            volume_24h: None,
+            fetch_latency: None,
+        });
+    }
+
+    // Stands in for a venue reporting its own, older, quote time
+    pub fn backdate_price(&self, pair: &TradingPair, age: chrono::Duration) {
+        self.state.lock().unwrap().quote_ages.insert(pair.symbol.clone(), age);
+    }
+
+    pub fn set_order_book(&self, pair: &TradingPair, bids: Vec<(Decimal, Decimal)>, asks: Vec<(Decimal, Decimal)>) {
+        let to_levels = |levels: Vec<(Decimal, Decimal)>| levels.into_iter()
+            .map(|(price, quantity)| OrderBookLevel { price, quantity })
+            .collect();
+        
+        self.state.lock().unwrap().order_books.insert(pair.symbol.clone(), OrderBook {
+            exchange: self.name.clone(),
+            pair: pair.clone(),
+            bids: to_levels(bids),
+            asks: to_levels(asks),
+            timestamp: Utc::now(),
+        });
+    }
+
+    pub fn set_pool(&self, pair: &TradingPair, base: Decimal, quote: Decimal) {
+        self.state.lock().unwrap().pools.insert(pair.symbol.clone(), PoolReserves { base, quote });
+    }
+
+    pub fn set_balance(&self, asset: &str, amount: Decimal) {
+        self.state.lock().unwrap().balances.insert(asset.to_string(), amount);
+    }
+
+    pub fn set_fees(&self, maker_fee: Decimal, taker_fee: Decimal) {
+        self.state.lock().unwrap().fees = TradingFees { maker_fee, taker_fee };
+    }
+
+    pub fn set_execution_cost(&self, cost: Decimal) {
+        self.state.lock().unwrap().execution_cost = cost;
+    }
+
+    pub fn set_fill_behavior(&self, fill_behavior: FillBehavior) {
+        self.state.lock().unwrap().fill_behavior = fill_behavior;
+    }
+
+    pub fn script_fills(&self, fills: Vec<FillBehavior>) {
+        self.state.lock().unwrap().scripted_fills.extend(fills);
+    }
+
+    pub fn set_latency(&self, latency: Duration) {
+        self.state.lock().unwrap().latency = latency;
+    }
+
+    pub fn set_transfer_status(&self, asset: &str, deposits_enabled: bool, withdrawals_enabled: bool) {
+        self.state.lock().unwrap().transfer_statuses.insert(asset.to_string(), TransferStatus {
+            deposits_enabled,
+            withdrawals_enabled,
+        });
+    }
+
+    pub fn set_chain_id(&self, chain_id: u64) {
+        self.state.lock().unwrap().chain_id = Some(chain_id);
+    }
+
+    pub fn set_token_probe(&self, asset: &str, probe: TokenProbe) {
+        self.state.lock().unwrap().token_probes.insert(asset.to_string(), probe);
+    }
+
+    // The next `count` calls of the given kind return an error
+    pub fn fail_next(&self, call: MockCall, count: u32) {
+        self.state.lock().unwrap().failures.insert(call, count);
+    }
+
+    // The next `count` calls of the given kind fail with `error`, as a venue that sorted its response would
+    pub fn reject_next(&self, call: MockCall, count: u32, error: BotError) {
+        let mut state = self.state.lock().unwrap();
+        state.failures.insert(call, count);
+        state.failure_errors.insert(call, error);
+    }
+
+    pub fn accept_client_order_ids(&self) {
+        self.state.lock().unwrap().client_order_ids.get_or_insert_with(HashMap::new);
+    }
+
+    // The next `count` placements fail as if the connection dropped before the order was sent
+    pub fn lose_next_requests(&self, count: u32) {
+        self.state.lock().unwrap().lost_requests = count;
+    }
+
+    // The next `count` placements are carried out, but fail as if the reply was lost
+    pub fn lose_next_replies(&self, count: u32) {
+        self.state.lock().unwrap().lost_replies = count;
+    }
+
+    pub fn order_count(&self) -> usize {
+        self.state.lock().unwrap().orders.len()
+    }
+
+    fn quote_to_base(&self, pair: &TradingPair, quote_amount: Decimal) -> Result<Decimal> {
+        let state = self.state.lock().unwrap();
+        let ask = state.prices.get(&pair.symbol)
+            .map(|quote| quote.ask)
+            .ok_or_else(|| anyhow::anyhow!("No mock price for {} on {}", pair.symbol, self.name))?;
+        Ok(quote_amount / (ask * (Decimal::ONE + state.fees.taker_fee)))
+    }
+
+    async fn enter(&self, call: MockCall) -> Result<()> {
+        let latency = self.state.lock().unwrap().latency;
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+        
+        let mut state = self.state.lock().unwrap();
+        if let Some(remaining) = state.failures.get_mut(&call) {
+            if *remaining > 0 {
+                *remaining -= 1;
+                if let Some(error) = state.failure_errors.get(&call) {
+                    return Err(error.clone().into());
+                }
+                anyhow::bail!("Injected {:?} failure on {}", call, self.name);
+            }
+        }
+        
+        Ok(())
+    }
+
+    async fn place_order(&self, pair: &TradingPair, side: TradeSide, amount: Decimal, price: Option<Decimal>) -> Result<Trade> {
+        self.enter(MockCall::PlaceOrder).await?;
+        
+        let mut state = self.state.lock().unwrap();
+        let market_price = state.prices.get(&pair.symbol)
+            .map(|quote| match side {
+                TradeSide::Buy => quote.ask,
+                TradeSide::Sell => quote.bid,
+            })
+            .ok_or_else(|| anyhow::anyhow!("No mock price for {} on {}", pair.symbol, self.name))?;
+        // A marketable limit fills at the market, as it would on a book
+        let fill_price = match (price, &side) {
+            (Some(limit), TradeSide::Buy) => limit.min(market_price),
+            (Some(limit), TradeSide::Sell) => limit.max(market_price),
+            (None, _) => market_price,
+        };
+        
+        let fill_behavior = state.scripted_fills.pop_front().unwrap_or(state.fill_behavior);
+        let (status, filled) = match fill_behavior {
+            FillBehavior::Instant => (TradeStatus::Executed, amount),
+            FillBehavior::Partial(fraction) => (TradeStatus::Executed, amount * fraction),
+            FillBehavior::Reject => (TradeStatus::Failed, Decimal::ZERO),
+            FillBehavior::Rest => (TradeStatus::Pending, Decimal::ZERO),
+            FillBehavior::PartialRest(fraction) => (TradeStatus::Pending, amount * fraction),
+        };
+        
+        if filled > Decimal::ZERO {
+            let notional = filled * fill_price;
+            let fee = notional * state.fees.taker_fee;
+            let (base_delta, quote_delta) = match side {
+                TradeSide::Buy => (filled, -(notional + fee)),
+                TradeSide::Sell => (-filled, notional - fee),
+            };
+            *state.balances.entry(pair.base.clone()).or_default() += base_delta;
+            *state.balances.entry(pair.quote.clone()).or_default() += quote_delta;
+        }
+        
+        let order_id = format!("{}-{}", self.name, state.next_order_id);
+        state.next_order_id += 1;
+        if matches!(status, TradeStatus::Pending) && filled > Decimal::ZERO {
+            state.resting_fills.insert(order_id.clone(), filled);
+        }
+        
+        let now = Utc::now();
+        let trade = Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name.clone(),
+            pair: pair.clone(),
+            side,
+            amount: if matches!(status, TradeStatus::Executed) { filled } else { amount },
+            price: fill_price,
+            fee_amount: filled * fill_price * state.fees.taker_fee,
+            fee_asset: Some(pair.quote.clone()),
+            status,
+            created_at: now,
+            executed_at: Some(now),
+            order_id: Some(order_id.clone()),
+            client_order_id: None,
+            tx_hash: None,
+            simulated: false,
+            failure_reason: None,
+        };
+        
+        state.orders.insert(order_id, trade.clone());
+        Ok(trade)
+    }
+}
+
+#[async_trait]
+impl Exchange for MockExchange {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        self.enter(MockCall::GetPrice).await?;
+        
+        let state = self.state.lock().unwrap();
+        let mut price = state.prices.get(&pair.symbol).cloned()
+            .ok_or_else(|| anyhow::anyhow!("No mock price for {} on {}", pair.symbol, self.name))?;
+        price.timestamp = Utc::now() - state.quote_ages.get(&pair.symbol).copied().unwrap_or_else(chrono::Duration::zero);
+        Ok(price)
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        self.enter(MockCall::GetOrderBook).await?;
+        
+        let mut order_book = self.state.lock().unwrap().order_books.get(&pair.symbol).cloned()
+            .ok_or_else(|| anyhow::anyhow!("No mock order book for {} on {}", pair.symbol, self.name))?;
+        order_book.bids.truncate(depth);
+        order_book.asks.truncate(depth);
+        order_book.timestamp = Utc::now();
+        Ok(order_book)
+    }
+
+    async fn get_pool_reserves(&self, pair: &TradingPair) -> BotResult<Option<PoolReserves>> {
+        Ok(self.state.lock().unwrap().pools.get(&pair.symbol).copied())
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        self.enter(MockCall::GetBalances).await?;
+        
+        Ok(self.state.lock().unwrap().balances.iter()
+            .map(|(asset, amount)| (asset.clone(), Balance {
+                asset: asset.clone(),
+                free: *amount,
+                locked: Decimal::ZERO,
+                total: *amount,
+                usd_value: Decimal::ZERO,
+            }))
+            .collect())
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Buy, amount, price).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Sell, amount, price).await?)
+    }
+
+    fn supports_quote_orders(&self) -> bool {
+        true
+    }
+
+    // Fills at the ask for as much base as `quote_amount` pays for, taker fee included
+    async fn place_market_buy_quote(&self, pair: &TradingPair, quote_amount: Decimal) -> BotResult<Trade> {
+        let amount = self.quote_to_base(pair, quote_amount)?;
+        Ok(self.place_order(pair, TradeSide::Buy, amount, None).await?)
+    }
+
+    // Emulates time in force like venues without it, and remembers client ids once they are accepted
+    async fn submit_order(&self, request: &OrderRequest) -> BotResult<Trade> {
+        if request.options.post_only {
+            return Err(anyhow::anyhow!("{} does not support post-only orders", self.name).into());
+        }
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.lost_requests > 0 {
+                state.lost_requests -= 1;
+                return Err(anyhow::Error::new(OutcomeUnknown).context(format!("{} placement request was lost", self.name)).into());
+            }
+        }
+        
+        let amount = match request.amount {
+            OrderAmount::Base(amount) => amount,
+            OrderAmount::Quote(spend) => self.quote_to_base(&request.pair, spend)?,
+        };
+        let mut trade = self.place_order(&request.pair, request.side.clone(), amount, request.price).await?;
+        
+        let mut state = self.state.lock().unwrap();
+        let order_id = trade.order_id.clone().unwrap_or_default();
+        if let (Some(ids), Some(client_id)) = (state.client_order_ids.as_mut(), &request.options.client_order_id) {
+            ids.insert(client_id.clone(), order_id.clone());
+            trade.client_order_id = Some(client_id.clone());
+            if let Some(order) = state.orders.get_mut(&order_id) {
+                order.client_order_id = Some(client_id.clone());
+            }
+        }
+        if state.lost_replies > 0 {
+            state.lost_replies -= 1;
+            return Err(anyhow::Error::new(OutcomeUnknown).context(format!("{} placement reply was lost", self.name)).into());
+        }
+        
+        let immediate = request.options.time_in_force != TimeInForce::Gtc;
+        if request.price.is_none() || !immediate || !matches!(trade.status, TradeStatus::Pending) {
+            return Ok(trade);
+        }
+        Ok(state.cancel(&order_id).unwrap_or(trade))
+    }
+
+    fn supports_client_order_ids(&self) -> bool {
+        self.state.lock().unwrap().client_order_ids.is_some()
+    }
+
+    async fn get_order_by_client_id(&self, _pair: &TradingPair, client_order_id: &str) -> BotResult<Option<Trade>> {
+        self.enter(MockCall::GetOrderStatus).await?;
+        
+        let state = self.state.lock().unwrap();
+        let ids = state.client_order_ids.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("{} does not accept client order ids", self.name))?;
+        Ok(ids.get(client_order_id).and_then(|order_id| state.orders.get(order_id)).cloned())
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        self.enter(MockCall::GetOrderStatus).await?;
+        
+        self.state.lock().unwrap().orders.get(order_id).cloned()
+            .ok_or_else(|| anyhow::anyhow!("Unknown mock order: {}", order_id).into())
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> BotResult<()> {
+        self.enter(MockCall::CancelOrder).await?;
+        
+        self.state.lock().unwrap().cancel(order_id)
+            .map(|_| ())
+            .ok_or_else(|| anyhow::anyhow!("Unknown mock order: {}", order_id).into())
+    }
+
+    async fn get_open_orders(&self, pair: Option<&TradingPair>) -> BotResult<Vec<OpenOrder>> {
+        self.enter(MockCall::GetOpenOrders).await?;
+        
+        let state = self.state.lock().unwrap();
+        Ok(state.orders.iter()
+            .filter(|(_, order)| matches!(order.status, TradeStatus::Pending))
+            .filter(|(_, order)| pair.map_or(true, |pair| order.pair == *pair))
+            .map(|(order_id, order)| OpenOrder {
+                exchange: self.name.clone(),
+                order_id: order_id.clone(),
+                pair: order.pair.symbol.clone(),
+                side: order.side.clone(),
+                price: order.price,
+                filled: state.resting_fills.get(order_id).copied().unwrap_or_default(),
+                amount: order.amount,
+                created_at: order.created_at,
+            })
+            .collect())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.state.lock().unwrap().prices.contains_key(&pair.symbol)
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        Ok(self.state.lock().unwrap().prices.values()
+            .map(|price| price.pair.clone())
+            .collect())
+    }
+
+    async fn get_trading_fees(&self, _pair: &TradingPair) -> BotResult<TradingFees> {
+        Ok(self.state.lock().unwrap().fees.clone())
+    }
+
+    async fn get_transfer_status(&self, asset: &str) -> BotResult<TransferStatus> {
+        Ok(self.state.lock().unwrap().transfer_statuses.get(asset).copied().unwrap_or(TransferStatus {
+            deposits_enabled: true,
+            withdrawals_enabled: true,
+        }))
+    }
+
+    async fn get_execution_cost(&self, _pair: &TradingPair, _amount: Decimal) -> BotResult<Decimal> {
+        Ok(self.state.lock().unwrap().execution_cost)
+    }
+
+    fn chain_id(&self) -> Option<u64> {
+        self.state.lock().unwrap().chain_id
+    }
+
+    async fn probe_token(&self, pair: &TradingPair, _native_amount: Decimal) -> BotResult<Option<TokenProbe>> {
+        Ok(Some(self.state.lock().unwrap().token_probes.get(&pair.base).cloned().unwrap_or_default()))
+    }
+}
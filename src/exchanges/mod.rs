@@ -1,35 +1,262 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 pub mod binance;
-pub mod uniswap;
+pub mod bitfinex;
+pub mod bybit;
+pub mod coinbase;
+pub mod curve;
+pub mod erc20;
+pub mod gateio;
+pub mod health;
+pub mod hyperliquid;
+pub mod kraken;
+pub mod kucoin;
+pub mod mexc;
+#[cfg(test)]
+pub mod mock;
+pub mod okx;
+pub mod oneinch;
+pub mod uniswap_v3;
+pub mod univ2;
+pub mod zerox;
 
-use crate::models::{Price, OrderBook, TradingPair, Balance, Trade};
+use crate::blockchain::atomic::EncodedSwap;
+use crate::blockchain::blocks::HeadReceiver;
+use crate::config::{ExchangeMode, HealthConfig, TimeInForce, TimeoutConfig};
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::health::{ExchangeHealth, HealthTracker};
+use crate::models::{Price, OrderBook, OrderBookLevel, PoolReserves, TradingPair, Balance, Trade, TradeSide, TradeStatus, OpenOrder};
 
 #[async_trait]
 pub trait Exchange: Send + Sync {
     fn name(&self) -> &str;
     
-    async fn get_price(&self, pair: &TradingPair) -> Result<Price>;
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price>;
     
-    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> Result<OrderBook>;
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook>;
     
-    async fn get_balances(&self) -> Result<HashMap<String, Balance>>;
+    // Reserves of the single pool a swap of `pair` goes through, for sizing against the exact curve instead of
+    // the sampled book; venues without one, or routing through several pools, return None
+    async fn get_pool_reserves(&self, _pair: &TradingPair) -> BotResult<Option<PoolReserves>> {
+        Ok(None)
+    }
+    
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>>;
+    
+    async fn place_buy_order(&self, pair: &TradingPair, amount: rust_decimal::Decimal, price: Option<rust_decimal::Decimal>) -> BotResult<Trade>;
+    
+    async fn place_sell_order(&self, pair: &TradingPair, amount: rust_decimal::Decimal, price: Option<rust_decimal::Decimal>) -> BotResult<Trade>;
+    
+    // Venues with native time-in-force, post-only or client ids override this. Elsewhere an unfilled IOC/FOK
+    // limit is cancelled right after placement (so FOK may partially fill), and on-chain swaps ignore the
+    // options since they fill or revert within their slippage bound anyway
+    async fn submit_order(&self, request: &OrderRequest) -> BotResult<Trade> {
+        let on_chain = self.chain_id().is_some();
+        if request.options.post_only && !on_chain {
+            return Err(anyhow::anyhow!("{} does not support post-only orders", self.name()).into());
+        }
+        
+        let amount = match (request.amount, &request.side) {
+            (OrderAmount::Base(amount), _) => amount,
+            (OrderAmount::Quote(spend), TradeSide::Buy) => return self.place_market_buy_quote(&request.pair, spend).await,
+            (OrderAmount::Quote(_), TradeSide::Sell) => return Err(anyhow::anyhow!("{} sells are sized in {}", self.name(), request.pair.base).into()),
+        };
+        let trade = match request.side {
+            TradeSide::Buy => self.place_buy_order(&request.pair, amount, request.price).await?,
+            TradeSide::Sell => self.place_sell_order(&request.pair, amount, request.price).await?,
+        };
+        let immediate = request.options.time_in_force != TimeInForce::Gtc;
+        if on_chain || request.price.is_none() || !immediate || !matches!(trade.status, TradeStatus::Pending) {
+            return Ok(trade);
+        }
+        let Some(order_id) = trade.order_id.clone() else {
+            return Ok(trade);
+        };
+        
+        self.cancel_order(&order_id).await?;
+        self.get_order_status(&order_id).await
+    }
+    
+    // Venues that accept our own order ids override both of these; `submit_order` must then send
+    // `options.client_order_id` with the order
+    fn supports_client_order_ids(&self) -> bool {
+        false
+    }
+    
+    // None when the venue has no order under that id, i.e. the placement never reached it
+    async fn get_order_by_client_id(&self, _pair: &TradingPair, _client_order_id: &str) -> BotResult<Option<Trade>> {
+        Err(anyhow::anyhow!("{} cannot look orders up by client id", self.name()).into())
+    }
     
-    async fn place_buy_order(&self, pair: &TradingPair, amount: rust_decimal::Decimal, price: Option<rust_decimal::Decimal>) -> Result<Trade>;
+    // Venues that can size a market buy by the quote spent override both of these
+    fn supports_quote_orders(&self) -> bool {
+        false
+    }
     
-    async fn place_sell_order(&self, pair: &TradingPair, amount: rust_decimal::Decimal, price: Option<rust_decimal::Decimal>) -> Result<Trade>;
+    // Market buy spending `quote_amount` of the pair's quote; the returned trade's amount is the base bought
+    async fn place_market_buy_quote(&self, pair: &TradingPair, _quote_amount: rust_decimal::Decimal) -> BotResult<Trade> {
+        Err(anyhow::anyhow!("{} cannot size {} buys in {}", self.name(), pair.symbol, pair.quote).into())
+    }
     
-    async fn get_order_status(&self, order_id: &str) -> Result<Trade>;
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade>;
     
-    async fn cancel_order(&self, order_id: &str) -> Result<()>;
+    async fn cancel_order(&self, order_id: &str) -> BotResult<()>;
+    
+    // Resting orders on `pair`, or on every symbol of the account when none is given
+    async fn get_open_orders(&self, _pair: Option<&TradingPair>) -> BotResult<Vec<OpenOrder>> {
+        Err(anyhow::anyhow!("{} does not support listing open orders", self.name()).into())
+    }
     
     fn supports_pair(&self, pair: &TradingPair) -> bool;
     
-    async fn get_supported_pairs(&self) -> Result<Vec<TradingPair>>;
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>>;
+    
+    async fn get_trading_fees(&self, pair: &TradingPair) -> BotResult<TradingFees>;
     
-    async fn get_trading_fees(&self, pair: &TradingPair) -> Result<TradingFees>;
+    // Order size and price constraints; venues without any (e.g. AMMs) keep the default
+    async fn get_symbol_rules(&self, _pair: &TradingPair) -> BotResult<SymbolRules> {
+        Ok(SymbolRules::unconstrained())
+    }
+    
+    // Chain id of on-chain venues; centralized exchanges have none
+    fn chain_id(&self) -> Option<u64> {
+        None
+    }
+    
+    // Exchanges with streaming market data push every price update into `sender`
+    fn subscribe_prices(&self, _sender: tokio::sync::mpsc::Sender<Price>) {}
+    
+    // Exchanges with an account stream push order and balance changes into `sender` as they happen
+    fn subscribe_account(&self, _sender: tokio::sync::mpsc::Sender<AccountEvent>) {}
+    
+    // On-chain venues can serve quotes from a per-block cache while their chain's head is followed
+    fn watch_chain_head(&self, _heads: HeadReceiver) {}
+    
+    // Fixed cost of executing one leg (e.g. gas), denominated in the pair's quote currency
+    async fn get_execution_cost(&self, _pair: &TradingPair, _amount: rust_decimal::Decimal) -> BotResult<rust_decimal::Decimal> {
+        Ok(rust_decimal::Decimal::ZERO)
+    }
+    
+    // Where transfers of `asset` onto this venue should be sent
+    async fn get_deposit_address(&self, asset: &str) -> BotResult<String> {
+        Err(anyhow::anyhow!("{} does not accept deposits of {}", self.name(), asset).into())
+    }
+    
+    async fn get_withdrawal_limits(&self, asset: &str) -> BotResult<WithdrawalLimits> {
+        Err(anyhow::anyhow!("{} does not support withdrawals of {}", self.name(), asset).into())
+    }
+    
+    // Venues that do not report it are taken to allow both
+    async fn get_transfer_status(&self, _asset: &str) -> BotResult<TransferStatus> {
+        Ok(TransferStatus {
+            deposits_enabled: true,
+            withdrawals_enabled: true,
+        })
+    }
+    
+    // Round trip of a small native-sized buy and sell of `pair.base` against `pair.quote`, simulated without
+    // sending anything; venues that cannot simulate return None and their tokens are traded unchecked
+    async fn probe_token(&self, _pair: &TradingPair, _native_amount: rust_decimal::Decimal) -> BotResult<Option<TokenProbe>> {
+        Ok(None)
+    }
+    
+    // In units of `asset`; the default reads it from the venue's withdrawal limits
+    async fn get_withdrawal_fee(&self, asset: &str) -> BotResult<rust_decimal::Decimal> {
+        Ok(self.get_withdrawal_limits(asset).await?.fee)
+    }
+    
+    // Returns the venue's withdrawal id
+    async fn withdraw(&self, asset: &str, _amount: rust_decimal::Decimal, _address: &str) -> BotResult<String> {
+        Err(anyhow::anyhow!("{} does not support withdrawals of {}", self.name(), asset).into())
+    }
+    
+    // Venues that can open offsetting derivative positions expose them here
+    fn hedge_venue(&self) -> Option<&dyn HedgeVenue> {
+        None
+    }
+    
+    // On-chain venues whose swaps can be bundled into one executor contract transaction
+    fn atomic_venue(&self) -> Option<&dyn AtomicSwapVenue> {
+        None
+    }
+}
+
+#[async_trait]
+pub trait HedgeVenue: Send + Sync {
+    // Opens a position of `size` base units on `side` at market; the returned trade carries the filled size
+    async fn hedge(&self, pair: &TradingPair, size: rust_decimal::Decimal, side: TradeSide) -> Result<Trade>;
+    
+    // Signed position size in base units, negative when short
+    async fn get_position(&self, pair: &TradingPair) -> Result<rust_decimal::Decimal>;
+}
+
+#[async_trait]
+pub trait AtomicSwapVenue: Send + Sync {
+    fn token_address(&self, symbol: &str) -> Option<ethers::types::Address>;
+    
+    async fn token_decimals(&self, token: ethers::types::Address) -> Result<u8>;
+    
+    // Exact-input swap paying out to `recipient`, with the connector's slippage limit as the minimum output
+    async fn encode_swap(
+        &self,
+        token_in: ethers::types::Address,
+        token_out: ethers::types::Address,
+        amount_in: ethers::types::U256,
+        recipient: ethers::types::Address,
+    ) -> Result<EncodedSwap>;
+}
+
+// Limit-order behaviour; market orders only use the client id
+#[derive(Debug, Clone, Default)]
+pub struct OrderOptions {
+    pub time_in_force: TimeInForce,
+    // Rejected rather than executed if it would take liquidity
+    pub post_only: bool,
+    pub client_order_id: Option<String>,
+}
+
+// Without a price the order is a market order; only market buys can be sized in quote
+#[derive(Debug, Clone)]
+pub struct OrderRequest {
+    pub pair: TradingPair,
+    pub side: TradeSide,
+    pub amount: OrderAmount,
+    pub price: Option<rust_decimal::Decimal>,
+    pub options: OrderOptions,
+}
+
+// What a market order is sized in: a base amount to trade, or a quote amount for a buy to spend
+#[derive(Debug, Clone, Copy)]
+pub enum OrderAmount {
+    Base(rust_decimal::Decimal),
+    Quote(rust_decimal::Decimal),
+}
+
+// Marks a failed request that may still have been carried out, e.g. an order placement that timed out
+// or got a 5xx, as opposed to one the venue definitely rejected
+#[derive(Debug)]
+pub struct OutcomeUnknown;
+
+impl std::fmt::Display for OutcomeUnknown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the venue did not say whether the request was carried out")
+    }
+}
+
+impl std::error::Error for OutcomeUnknown {}
+
+pub fn outcome_unknown(error: &anyhow::Error) -> bool {
+    BotError::causes(error).into_iter().any(|cause| {
+        cause.is::<OutcomeUnknown>()
+            || cause.is::<tokio::time::error::Elapsed>()
+            // Transport failures carry no status: the request may have been sent before the connection broke
+            || cause.downcast_ref::<reqwest::Error>().map_or(false, |e| e.status().is_none() && !e.is_builder())
+    })
 }
 
 #[derive(Debug, Clone)]
@@ -38,17 +265,306 @@ pub struct TradingFees {
     pub taker_fee: rust_decimal::Decimal,
 }
 
+#[derive(Debug, Clone)]
+pub enum AccountEvent {
+    // Sent on every (re)connect, after the venue has re-read the orders it may have missed
+    Connected { exchange: String },
+    Disconnected { exchange: String },
+    // Latest state of one of the account's orders, with the fee accumulated over its fills
+    Order(Trade),
+    // Free amounts of the assets whose balance changed
+    Balances { exchange: String, free: HashMap<String, rust_decimal::Decimal> },
+}
+
+// Both in units of the withdrawn asset
+#[derive(Debug, Clone)]
+pub struct WithdrawalLimits {
+    pub fee: rust_decimal::Decimal,
+    pub minimum: rust_decimal::Decimal,
+}
+
+// Whether `asset` can currently be moved onto and off the venue
+#[derive(Debug, Clone, Copy)]
+pub struct TransferStatus {
+    pub deposits_enabled: bool,
+    pub withdrawals_enabled: bool,
+}
+
+// Taxes are the fraction of the quoted amount that did not arrive, zero for a plain ERC20
+#[derive(Debug, Clone, Default)]
+pub struct TokenProbe {
+    pub buy_tax: rust_decimal::Decimal,
+    pub sell_tax: rust_decimal::Decimal,
+    // Revert reasons of legs that could not be simulated at all; a failing sell is the honeypot case
+    pub buy_revert: Option<String>,
+    pub sell_revert: Option<String>,
+    // EIP-1967 implementation the token delegates to, if it is a proxy
+    pub implementation: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SymbolRules {
+    // Zero step or tick means any precision is accepted
+    pub quantity_step: rust_decimal::Decimal,
+    pub price_tick: rust_decimal::Decimal,
+    pub min_quantity: rust_decimal::Decimal,
+    pub max_quantity: Option<rust_decimal::Decimal>,
+    pub min_notional: rust_decimal::Decimal,
+}
+
+impl SymbolRules {
+    pub fn unconstrained() -> Self {
+        Self {
+            quantity_step: rust_decimal::Decimal::ZERO,
+            price_tick: rust_decimal::Decimal::ZERO,
+            min_quantity: rust_decimal::Decimal::ZERO,
+            max_quantity: None,
+            min_notional: rust_decimal::Decimal::ZERO,
+        }
+    }
+    
+    // Always rounds down so an order never asks for more than was sized (or is held)
+    pub fn round_quantity(&self, quantity: rust_decimal::Decimal) -> rust_decimal::Decimal {
+        let quantity = match self.max_quantity {
+            Some(max_quantity) => quantity.min(max_quantity),
+            None => quantity,
+        };
+        crate::utils::round_down_to_step(quantity, self.quantity_step)
+    }
+    
+    // Rounds against ourselves: buys never bid above, sells never offer below the requested price
+    pub fn round_price(&self, price: rust_decimal::Decimal, side: &TradeSide) -> rust_decimal::Decimal {
+        match side {
+            TradeSide::Buy => crate::utils::round_down_to_step(price, self.price_tick),
+            TradeSide::Sell => crate::utils::round_up_to_step(price, self.price_tick),
+        }
+    }
+    
+    pub fn check_order(&self, quantity: rust_decimal::Decimal, price: rust_decimal::Decimal) -> Result<()> {
+        if quantity <= rust_decimal::Decimal::ZERO || quantity < self.min_quantity {
+            anyhow::bail!("Quantity {} is below the minimum of {}", quantity, self.min_quantity);
+        }
+        if quantity * price < self.min_notional {
+            anyhow::bail!("Notional {} is below the minimum of {}", quantity * price, self.min_notional);
+        }
+        Ok(())
+    }
+}
+
+// Bids and asks for a venue that quotes sizes rather than publishing a book. Each of `depth` levels
+// covers an equal slice of `max_size`, priced at the marginal cost of that slice. `quote` gives the
+// total proceeds of selling and cost of buying a cumulative size, either side None when it cannot be
+// quoted; the ladder ends at the first size neither side can be quoted for.
+pub async fn synthesize_ladder<F, Fut>(max_size: rust_decimal::Decimal, depth: usize, mut quote: F) -> Result<(Vec<OrderBookLevel>, Vec<OrderBookLevel>)>
+where
+    F: FnMut(rust_decimal::Decimal) -> Fut,
+    Fut: Future<Output = Result<(Option<rust_decimal::Decimal>, Option<rust_decimal::Decimal>)>>,
+{
+    let mut bids = Vec::new();
+    let mut asks = Vec::new();
+    if depth == 0 {
+        return Ok((bids, asks));
+    }
+    
+    let step = max_size / rust_decimal::Decimal::from(depth);
+    let mut previous_proceeds = rust_decimal::Decimal::ZERO;
+    let mut previous_cost = rust_decimal::Decimal::ZERO;
+    
+    for i in 1..=depth {
+        let (proceeds, cost) = quote(step * rust_decimal::Decimal::from(i)).await?;
+        if proceeds.is_none() && cost.is_none() {
+            break;
+        }
+        
+        if let Some(proceeds) = proceeds {
+            bids.push(OrderBookLevel {
+                price: (proceeds - previous_proceeds) / step,
+                quantity: step,
+            });
+            previous_proceeds = proceeds;
+        }
+        if let Some(cost) = cost {
+            asks.push(OrderBookLevel {
+                price: (cost - previous_cost) / step,
+                quantity: step,
+            });
+            previous_cost = cost;
+        }
+    }
+    
+    Ok((bids, asks))
+}
+
+// One venue's mapping between the asset symbols its API uses and the canonical symbols prices are
+// compared under. Assets without an alias are already canonical.
+#[derive(Debug, Clone, Default)]
+pub struct AssetAliases {
+    to_canonical: HashMap<String, String>,
+    to_native: HashMap<String, String>,
+}
+
+impl AssetAliases {
+    pub fn new(aliases: &HashMap<String, String>) -> Result<Self> {
+        let mut to_canonical = HashMap::new();
+        let mut to_native = HashMap::new();
+        
+        for (native, canonical) in aliases {
+            let native = native.to_uppercase();
+            let canonical = canonical.to_uppercase();
+            if let Some(other) = to_native.insert(canonical.clone(), native.clone()) {
+                anyhow::bail!("Assets {} and {} are both aliased to {}", other, native, canonical);
+            }
+            to_canonical.insert(native, canonical);
+        }
+        
+        // A canonical symbol that is itself aliased would make the mapping depend on how often it is applied
+        if let Some(canonical) = to_native.keys().find(|canonical| to_canonical.contains_key(*canonical)) {
+            anyhow::bail!("Asset {} is used both as an alias and as a canonical symbol", canonical);
+        }
+        
+        Ok(Self { to_canonical, to_native })
+    }
+    
+    pub fn canonical<'a>(&'a self, asset: &'a str) -> &'a str {
+        self.to_canonical.get(asset).map(String::as_str).unwrap_or(asset)
+    }
+    
+    pub fn native<'a>(&'a self, asset: &'a str) -> &'a str {
+        self.to_native.get(asset).map(String::as_str).unwrap_or(asset)
+    }
+    
+    pub fn canonical_pair(&self, pair: &TradingPair) -> TradingPair {
+        TradingPair::new(self.canonical(&pair.base), self.canonical(&pair.quote))
+    }
+    
+    pub fn native_pair(&self, pair: &TradingPair) -> TradingPair {
+        TradingPair::new(self.native(&pair.base), self.native(&pair.quote))
+    }
+}
+
 pub struct ExchangeManager {
     exchanges: HashMap<String, Box<dyn Exchange>>,
+    data_only: HashSet<String>,
+    aliases: HashMap<String, AssetAliases>,
+    fee_overrides: HashMap<String, TradingFees>,
+    timeouts: TimeoutConfig,
+    health: Arc<HealthTracker>,
 }
 
 impl ExchangeManager {
     pub fn new() -> Self {
         Self {
             exchanges: HashMap::new(),
+            data_only: HashSet::new(),
+            aliases: HashMap::new(),
+            fee_overrides: HashMap::new(),
+            timeouts: TimeoutConfig::default(),
+            health: Arc::new(HealthTracker::new(HealthConfig::default())),
         }
     }
     
+    pub fn set_timeouts(&mut self, timeouts: TimeoutConfig) {
+        self.timeouts = timeouts;
+    }
+    
+    pub fn timeouts(&self) -> &TimeoutConfig {
+        &self.timeouts
+    }
+    
+    pub fn set_health_config(&mut self, config: HealthConfig) {
+        self.health.set_config(config);
+    }
+    
+    // False while the exchange is cooling down after repeated failures
+    pub fn is_available(&self, name: &str) -> bool {
+        self.health.is_available(name)
+    }
+    
+    pub fn health(&self) -> Vec<ExchangeHealth> {
+        self.health.snapshot()
+    }
+    
+    // For readers outside the bot, such as the status server
+    pub fn health_tracker(&self) -> Arc<HealthTracker> {
+        self.health.clone()
+    }
+    
+    // Runs one exchange call under `budget`. Errors and timeouts are charged to that exchange
+    // instead of stalling or failing everything else.
+    pub async fn call<T, E, F>(&self, exchange: &str, operation: &str, budget: Duration, future: F) -> Result<T>
+    where
+        F: Future<Output = std::result::Result<T, E>>,
+        E: Into<anyhow::Error>,
+    {
+        let started = Instant::now();
+        let result = match tokio::time::timeout(budget, future).await {
+            Ok(result) => result.map_err(Into::into),
+            Err(elapsed) => Err(anyhow::Error::new(elapsed).context(BotError::NetworkTimeout {
+                exchange: exchange.to_string(),
+                message: format!("{} after {:?}", operation, budget),
+            })),
+        };
+        
+        match &result {
+            Ok(_) => self.health.record_success(exchange, operation, started.elapsed()),
+            Err(e) => self.health.record_failure(exchange, e),
+        }
+        
+        result
+    }
+    
+    // Stamps the quote with how long fetching it took
+    pub async fn fetch_price(&self, exchange: &dyn Exchange, pair: &TradingPair, budget: Duration) -> Result<Price> {
+        let started = Instant::now();
+        let mut price = self.call(exchange.name(), "get_price", budget, exchange.get_price(pair)).await?;
+        price.fetch_latency = Some(started.elapsed());
+        Ok(price)
+    }
+    
+    pub fn set_mode(&mut self, name: &str, mode: ExchangeMode) {
+        match mode {
+            ExchangeMode::Full => self.data_only.remove(name),
+            ExchangeMode::DataOnly => self.data_only.insert(name.to_string()),
+        };
+    }
+    
+    pub fn set_aliases(&mut self, name: &str, aliases: AssetAliases) {
+        self.aliases.insert(name.to_string(), aliases);
+    }
+    
+    pub fn set_fee_override(&mut self, name: &str, fees: TradingFees) {
+        self.fee_overrides.insert(name.to_string(), fees);
+    }
+    
+    // Configured fee overrides win over whatever the venue reports; `pair` is the venue's native pair
+    pub async fn trading_fees(&self, exchange: &dyn Exchange, pair: &TradingPair) -> Result<TradingFees> {
+        if let Some(fees) = self.fee_overrides.get(exchange.name()) {
+            return Ok(fees.clone());
+        }
+        self.call(exchange.name(), "get_trading_fees", self.timeouts.account(), exchange.get_trading_fees(pair)).await
+    }
+    
+    // The pair as `exchange` itself names it, for anything sent to that venue
+    pub fn native_pair(&self, exchange: &str, pair: &TradingPair) -> TradingPair {
+        match self.aliases.get(exchange) {
+            Some(aliases) => aliases.native_pair(pair),
+            None => pair.clone(),
+        }
+    }
+    
+    // The pair under the symbols shared by every venue, for comparing prices across them
+    pub fn canonical_pair(&self, exchange: &str, pair: &TradingPair) -> TradingPair {
+        match self.aliases.get(exchange) {
+            Some(aliases) => aliases.canonical_pair(pair),
+            None => pair.clone(),
+        }
+    }
+    
+    // Data-only venues still feed prices and opportunity detection but must never receive orders
+    pub fn can_execute(&self, name: &str) -> bool {
+        self.exchanges.contains_key(name) && !self.data_only.contains(name)
+    }
+    
     pub fn add_exchange(&mut self, exchange: Box<dyn Exchange>) {
         let name = exchange.name().to_string();
         self.exchanges.insert(name, exchange);
@@ -62,16 +578,26 @@ impl ExchangeManager {
         self.exchanges.values().map(|e| e.as_ref()).collect()
     }
     
+    // `pair` is canonical; each venue is asked under its native symbols and the prices come back canonical
     pub async fn get_all_prices(&self, pair: &TradingPair) -> Result<Vec<Price>> {
+        let fetches = self.exchanges.values()
+            .map(|exchange| (exchange, self.native_pair(exchange.name(), pair)))
+            .filter(|(exchange, native)| exchange.supports_pair(native) && self.is_available(exchange.name()))
+            .map(|(exchange, native)| async move {
+                let result = self.fetch_price(exchange.as_ref(), &native, self.timeouts.price()).await;
+                (exchange.name(), result)
+            });
+        
         let mut prices = Vec::new();
         
-        for exchange in self.exchanges.values() {
-            if exchange.supports_pair(pair) {
-                match exchange.get_price(pair).await {
-                    Ok(price) => prices.push(price),
-                    Err(err) => {
-                        tracing::warn!("Failed to get price from {}: {}", exchange.name(), err);
-                    }
+        for (name, result) in futures::future::join_all(fetches).await {
+            match result {
+                Ok(mut price) => {
+                    price.pair = pair.clone();
+                    prices.push(price);
+                },
+                Err(err) => {
+                    tracing::warn!("Failed to get price from {} for {}: {}", name, pair.symbol, err);
                 }
             }
         }
@@ -88,4 +614,49 @@ impl ExchangeManager {
         let prices = self.get_all_prices(pair).await?;
         Ok(prices.into_iter().max_by(|a, b| a.bid.cmp(&b.bid)))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Aliases apply per venue: WBTC is BTC on the DEX but stays its own market on the CEX
+    #[test]
+    fn applies_aliases_per_venue() -> Result<()> {
+        let dex_aliases: HashMap<String, String> = [("weth", "ETH"), ("WBTC", "btc")].into_iter()
+            .map(|(native, canonical)| (native.to_string(), canonical.to_string()))
+            .collect();
+        let mut manager = ExchangeManager::new();
+        manager.set_aliases("dex", AssetAliases::new(&dex_aliases)?);
+        let canonical = manager.canonical_pair("dex", &TradingPair::new("WETH", "USDC"));
+        assert!(canonical.symbol == "ETH/USDC",
+               "dex WETH/USDC was compared as {}", canonical.symbol);
+        let native = manager.native_pair("dex", &TradingPair::new("BTC", "USDC"));
+        assert!(native.symbol == "WBTC/USDC",
+               "BTC/USDC was sent to the dex as {}", native.symbol);
+        
+        let cex = manager.canonical_pair("cex", &TradingPair::new("WBTC", "USDT"));
+        assert!(cex.symbol == "WBTC/USDT",
+               "cex WBTC/USDT was merged into {}", cex.symbol);
+        let cex = manager.native_pair("cex", &TradingPair::new("ETH", "USDT"));
+        assert!(cex.symbol == "ETH/USDT",
+               "ETH/USDT was sent to the cex as {}", cex.symbol);
+        
+        let again = manager.canonical_pair("dex", &canonical);
+        assert!(again == canonical,
+               "canonicalizing twice turned {} into {}", canonical.symbol, again.symbol);
+        
+        let invalid = [
+            ("two assets on one canonical", [("WETH", "ETH"), ("STETH", "ETH")]),
+            ("alias chain", [("WETH", "ETH"), ("ETH", "ETH2")]),
+        ];
+        for (name, aliases) in invalid {
+            let aliases: HashMap<String, String> = aliases.into_iter()
+                .map(|(native, canonical)| (native.to_string(), canonical.to_string()))
+                .collect();
+            assert!(AssetAliases::new(&aliases).is_err(), "{} was accepted", name);
+        }
+        
+        Ok(())
+    }
+}
@@ -0,0 +1,427 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use crate::config::ExchangeConfig;
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::{Exchange, TradingFees};
+use crate::models::{Balance, OrderBook, OrderBookLevel, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::utils::{self, RateLimiter};
+
+pub struct OkxExchange {
+    config: ExchangeConfig,
+    client: Client,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    fee_cache: RwLock<HashMap<String, TradingFees>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OkxResponse<T> {
+    code: String,
+    msg: String,
+    #[serde(default = "Vec::new")]
+    data: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OkxTicker {
+    bid_px: String,
+    ask_px: String,
+    vol_24h: String,
+    ts: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OkxOrderBook {
+    // [price, size, deprecated, order count]
+    bids: Vec<Vec<String>>,
+    asks: Vec<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OkxAccountBalance {
+    details: Vec<OkxBalanceDetail>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OkxBalanceDetail {
+    ccy: String,
+    avail_bal: String,
+    frozen_bal: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OkxOrderRequest {
+    inst_id: String,
+    td_mode: String,
+    side: String,
+    ord_type: String,
+    sz: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    px: Option<String>,
+    // Market buys are sized in the quote currency unless told otherwise
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tgt_ccy: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OkxOrderAck {
+    ord_id: String,
+    s_code: String,
+    s_msg: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OkxOrder {
+    inst_id: String,
+    ord_id: String,
+    side: String,
+    state: String,
+    sz: String,
+    px: String,
+    acc_fill_sz: String,
+    avg_px: String,
+    fee: String,
+    fee_ccy: String,
+    c_time: String,
+    fill_time: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OkxFeeRate {
+    maker: String,
+    taker: String,
+}
+
+impl OkxExchange {
+    pub fn new(config: ExchangeConfig) -> Result<Self> {
+        Ok(Self {
+            rate_limiter: utils::request_rate_limiter("okx", config.rate_limit, None),
+            client: utils::http_client_with(&config.http())?,
+            config,
+            fee_cache: RwLock::new(HashMap::new()),
+        })
+    }
+
+    fn convert_symbol(&self, pair: &TradingPair) -> String {
+        format!("{}-{}", pair.base, pair.quote)
+    }
+
+    fn pair_from_symbol(&self, symbol: &str) -> Option<TradingPair> {
+        self.config.configured_pairs().into_iter()
+            .find(|pair| self.convert_symbol(pair) == symbol)
+    }
+
+    // Order ids are only addressable together with their instrument, so both are encoded in the id we hand out
+    fn split_order_id(order_id: &str) -> Result<(&str, &str)> {
+        order_id.split_once(':')
+            .ok_or_else(|| anyhow::anyhow!("Invalid OKX order id: {}", order_id))
+    }
+
+    fn create_signature(&self, timestamp: &str, method: &reqwest::Method, request_path: &str, body: &str) -> String {
+        use base64::Engine;
+        use hmac::{Hmac, Mac};
+        use sha2::Sha256;
+        
+        type HmacSha256 = Hmac<Sha256>;
+        
+        let message = format!("{}{}{}{}", timestamp, method.as_str(), request_path, body);
+        let mut mac = HmacSha256::new_from_slice(self.config.api_secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(message.as_bytes());
+        
+        base64::engine::general_purpose::STANDARD.encode(mac.finalize().into_bytes())
+    }
+
+    fn unwrap_response<T>(response: OkxResponse<T>) -> Result<Vec<T>> {
+        if response.code != "0" {
+            return Err(BotError::rejected("OKX", Some(response.code), response.msg).into());
+        }
+        Ok(response.data)
+    }
+
+    async fn make_public_request<T>(&self, request_path: &str) -> Result<Vec<T>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let url = format!("{}{}", self.config.api_url, request_path);
+        let response: OkxResponse<T> = self.client.get(&url).send().await?.json().await?;
+        Self::unwrap_response(response)
+    }
+
+    async fn make_signed_request<T>(&self, method: reqwest::Method, request_path: &str, body: Option<String>) -> Result<Vec<T>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let passphrase = self.config.api_passphrase.as_deref()
+            .ok_or_else(|| anyhow::anyhow!("OKX requires api_passphrase to be configured"))?;
+        
+        let body = body.unwrap_or_default();
+        let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();
+        let signature = self.create_signature(&timestamp, &method, request_path, &body);
+        
+        let mut request = self.client
+            .request(method, format!("{}{}", self.config.api_url, request_path))
+            .header("OK-ACCESS-KEY", &self.config.api_key)
+            .header("OK-ACCESS-SIGN", signature)
+            .header("OK-ACCESS-TIMESTAMP", timestamp)
+            .header("OK-ACCESS-PASSPHRASE", passphrase);
+        if !body.is_empty() {
+            request = request.header("Content-Type", "application/json").body(body);
+        }
+        
+        let response = request.send().await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("OKX", response).await.into());
+        }
+        
+        Self::unwrap_response(response.json().await?)
+    }
+
+    fn order_to_trade(&self, order: OkxOrder) -> Result<Trade> {
+        let pair = self.pair_from_symbol(&order.inst_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown OKX instrument: {}", order.inst_id))?;
+        
+        let parse = |value: &str| -> Result<Decimal> {
+            if value.is_empty() {
+                Ok(Decimal::ZERO)
+            } else {
+                Ok(Decimal::from_str(value)?)
+            }
+        };
+        let parse_time = |value: &str| value.parse::<i64>().ok()
+            .and_then(chrono::DateTime::from_timestamp_millis);
+        
+        let filled = parse(&order.acc_fill_sz)?;
+        let price = if filled > Decimal::ZERO { parse(&order.avg_px)? } else { parse(&order.px)? };
+        
+        let status = match order.state.as_str() {
+            "filled" => TradeStatus::Executed,
+            "canceled" | "mmp_canceled" => TradeStatus::Cancelled,
+            _ => TradeStatus::Pending,
+        };
+        
+        let side = if order.side == "buy" { TradeSide::Buy } else { TradeSide::Sell };
+        
+        let executed_at = match status {
+            TradeStatus::Executed => parse_time(&order.fill_time).or_else(|| Some(Utc::now())),
+            _ => None,
+        };
+        
+        Ok(Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            pair,
+            side,
+            amount: if filled > Decimal::ZERO { filled } else { parse(&order.sz)? },
+            price,
+            // OKX reports fees as negative amounts
+            fee_amount: parse(&order.fee)?.abs(),
+            fee_asset: (!order.fee_ccy.is_empty()).then(|| order.fee_ccy.clone()),
+            status,
+            created_at: parse_time(&order.c_time).unwrap_or_else(Utc::now),
+            executed_at,
+            order_id: Some(format!("{}:{}", order.inst_id, order.ord_id)),
+            client_order_id: None,
+            tx_hash: None,
+            simulated: false,
+            failure_reason: None,
+        })
+    }
+
+    async fn place_order(&self, pair: &TradingPair, side: TradeSide, amount: Decimal, price: Option<Decimal>) -> Result<Trade> {
+        let inst_id = self.convert_symbol(pair);
+        let request = OkxOrderRequest {
+            inst_id: inst_id.clone(),
+            td_mode: "cash".to_string(),
+            side: match side {
+                TradeSide::Buy => "buy".to_string(),
+                TradeSide::Sell => "sell".to_string(),
+            },
+            ord_type: if price.is_some() { "limit".to_string() } else { "market".to_string() },
+            sz: amount.normalize().to_string(),
+            px: price.map(|p| p.normalize().to_string()),
+            tgt_ccy: price.is_none().then(|| "base_ccy".to_string()),
+        };
+        
+        let acks: Vec<OkxOrderAck> = self
+            .make_signed_request(reqwest::Method::POST, "/api/v5/trade/order", Some(serde_json::to_string(&request)?))
+            .await?;
+        let ack = acks.into_iter().next()
+            .ok_or_else(|| anyhow::anyhow!("OKX did not acknowledge the order"))?;
+        
+        if ack.s_code != "0" {
+            return Err(BotError::rejected("OKX", Some(ack.s_code), ack.s_msg).into());
+        }
+        
+        Ok(self.get_order_status(&format!("{}:{}", inst_id, ack.ord_id)).await?)
+    }
+}
+
+#[async_trait]
+impl Exchange for OkxExchange {
+    fn name(&self) -> &str {
+        "okx"
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        let tickers: Vec<OkxTicker> = self
+            .make_public_request(&format!("/api/v5/market/ticker?instId={}", self.convert_symbol(pair)))
+            .await?;
+        let ticker = tickers.into_iter().next()
+            .ok_or_else(|| anyhow::anyhow!("OKX returned no ticker for {}", pair.symbol))?;
+        
+        Ok(Price {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bid: Decimal::from_str(&ticker.bid_px)?,
+            ask: Decimal::from_str(&ticker.ask_px)?,
+            timestamp: ticker.ts.parse().ok().and_then(chrono::DateTime::from_timestamp_millis).unwrap_or_else(Utc::now),
+            volume_24h: Decimal::from_str(&ticker.vol_24h).ok(),
+            fetch_latency: None,
+        })
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        let books: Vec<OkxOrderBook> = self
+            .make_public_request(&format!("/api/v5/market/books?instId={}&sz={}", self.convert_symbol(pair), depth))
+            .await?;
+        let book = books.into_iter().next()
+            .ok_or_else(|| anyhow::anyhow!("OKX returned no order book for {}", pair.symbol))?;
+        
+        let to_levels = |levels: &[Vec<String>]| -> Result<Vec<OrderBookLevel>> {
+            levels.iter()
+                .map(|level| match (level.first(), level.get(1)) {
+                    (Some(price), Some(quantity)) => Ok(OrderBookLevel {
+                        price: Decimal::from_str(price)?,
+                        quantity: Decimal::from_str(quantity)?,
+                    _ => anyhow::bail!("Malformed OKX order book level: {:?}", level),
+                    _ => return Err(anyhow::anyhow!("Malformed OKX order book level: {:?}", level).into()),
+                })
+                .collect()
+        };
+        
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bids: to_levels(&book.bids)?,
+            asks: to_levels(&book.asks)?,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        let accounts: Vec<OkxAccountBalance> = self
+            .make_signed_request(reqwest::Method::GET, "/api/v5/account/balance", None)
+            .await?;
+        
+        let mut balances = HashMap::new();
+        
+        for detail in accounts.into_iter().flat_map(|account| account.details) {
+            let free = Decimal::from_str(&detail.avail_bal).unwrap_or_default();
+            let locked = Decimal::from_str(&detail.frozen_bal).unwrap_or_default();
+            let total = free + locked;
+            
+            if total > Decimal::ZERO {
+                balances.insert(detail.ccy.clone(), Balance {
+                    asset: detail.ccy,
+                    free,
+                    locked,
+                    total,
+                    usd_value: Decimal::ZERO,
+                });
+            }
+        }
+        
+        Ok(balances)
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Buy, amount, price).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.place_order(pair, TradeSide::Sell, amount, price).await?)
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        let (inst_id, ord_id) = Self::split_order_id(order_id)?;
+        
+        let orders: Vec<OkxOrder> = self
+            .make_signed_request(reqwest::Method::GET, &format!("/api/v5/trade/order?instId={}&ordId={}", inst_id, ord_id), None)
+            .await?;
+        let order = orders.into_iter().next()
+            .ok_or_else(|| anyhow::anyhow!("OKX order not found: {}", order_id))?;
+        
+        Ok(self.order_to_trade(order)?)
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> BotResult<()> {
+        let (inst_id, ord_id) = Self::split_order_id(order_id)?;
+        let body = serde_json::json!({ "instId": inst_id, "ordId": ord_id }).to_string();
+        
+        let _: Vec<serde_json::Value> = self
+            .make_signed_request(reqwest::Method::POST, "/api/v5/trade/cancel-order", Some(body))
+            .await?;
+        Ok(())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.config.trading_pairs.contains(&pair.symbol)
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        Ok(self.config.configured_pairs())
+    }
+
+    async fn get_trading_fees(&self, pair: &TradingPair) -> BotResult<TradingFees> {
+        // Regular-user spot rates for data-only venues, which cannot query the account fee endpoint
+        if self.config.api_key.is_empty() {
+            return Ok(TradingFees {
+                maker_fee: Decimal::new(8, 4),
+                taker_fee: Decimal::new(1, 3),
+            });
+        }
+        
+        let inst_id = self.convert_symbol(pair);
+        if let Some(fees) = self.fee_cache.read().unwrap().get(&inst_id) {
+            return Ok(fees.clone());
+        }
+        
+        let rates: Vec<OkxFeeRate> = self
+            .make_signed_request(reqwest::Method::GET, &format!("/api/v5/account/trade-fee?instType=SPOT&instId={}", inst_id), None)
+            .await?;
+        let rate = rates.into_iter().next()
+            .ok_or_else(|| anyhow::anyhow!("OKX returned no fee rate for {}", pair.symbol))?;
+        
+        // Negative rates are charged to the user, positive rates are rebates
+        let fees = TradingFees {
+            maker_fee: -Decimal::from_str(&rate.maker)?,
+            taker_fee: -Decimal::from_str(&rate.taker)?,
+        };
+        
+        self.fee_cache.write().unwrap().insert(inst_id, fees.clone());
+        Ok(fees)
+    }
+}
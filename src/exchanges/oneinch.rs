@@ -0,0 +1,522 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use ethers::prelude::*;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::blockchain::failover::{self, RpcProvider};
+use crate::blockchain::gas::GasOracle;
+use crate::blockchain::simulate;
+use crate::config::{ChainConfig, ExchangeConfig};
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::erc20::{self, SignerClient, TokenApprovals, ERC20};
+use crate::exchanges::{synthesize_ladder, Exchange, TradingFees};
+use crate::models::{Balance, OrderBook, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::utils::{self, RateLimiter};
+
+// Used when the quote response does not include a gas estimate
+const DEFAULT_SWAP_GAS: u64 = 250_000;
+
+pub struct OneInchExchange {
+    config: ExchangeConfig,
+    client: Client,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    chain_id: u64,
+    native_symbol: &'static str,
+    wrapped_native_symbol: &'static str,
+    provider: Arc<RpcProvider>,
+    wallet: Option<LocalWallet>,
+    signer: Option<Arc<SignerClient>>,
+    gas_oracle: Arc<GasOracle>,
+    max_slippage: Decimal,
+    approvals: TokenApprovals,
+    submitted_swaps: Mutex<HashMap<String, SubmittedSwap>>,
+}
+
+#[derive(Debug, Clone)]
+struct SubmittedSwap {
+    trade: Trade,
+    input_token: Address,
+    output_token: Address,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QuoteResponse {
+    dst_amount: String,
+    #[serde(default)]
+    gas: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SwapResponse {
+    dst_amount: String,
+    tx: SwapTransaction,
+}
+
+#[derive(Debug, Deserialize)]
+struct SwapTransaction {
+    to: Address,
+    data: Bytes,
+    value: String,
+    gas: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpenderResponse {
+    address: Address,
+}
+
+impl OneInchExchange {
+    // The 1inch API key comes from the exchange entry; chain id, RPC, signer and gas from the chain it trades on
+    pub async fn new(config: ExchangeConfig, chain: &ChainConfig, max_slippage: Decimal) -> Result<Self> {
+        let chain_id = chain.chain_id;
+        if let Some(configured) = config.chain_id.filter(|configured| *configured != chain_id) {
+            anyhow::bail!("1inch is configured for chain id {}, but the chain config is {}", configured, chain_id);
+        }
+        let (native_symbol, wrapped_native_symbol) = erc20::native_symbols(chain_id)
+            .ok_or_else(|| anyhow::anyhow!("Unsupported 1inch chain id: {}", chain_id))?;
+        
+        let provider = Arc::new(failover::rpc_provider(&chain.rpc_urls, chain.max_block_lag, &chain.http())?);
+        
+        let wallet = if !chain.private_key.is_empty() {
+            Some(chain.private_key.parse::<LocalWallet>()?.with_chain_id(chain_id))
+        } else {
+            None
+        };
+        
+        let signer = wallet.clone()
+            .map(|wallet| Arc::new(SignerMiddleware::new(provider.clone(), wallet)));
+        
+        let gas_oracle = Arc::new(GasOracle::new(provider.clone(), chain.gas_policy()));
+        
+        let mut exchange = Self {
+            rate_limiter: utils::request_rate_limiter("1inch", config.rate_limit, None),
+            client: utils::http_client_with(&config.http())?,
+            config,
+            chain_id,
+            native_symbol,
+            wrapped_native_symbol,
+            provider,
+            wallet,
+            signer,
+            gas_oracle,
+            max_slippage,
+            approvals: TokenApprovals::new(Address::zero(), false),
+            submitted_swaps: Mutex::new(HashMap::new()),
+        };
+        
+        let spender: SpenderResponse = exchange.make_request("/approve/spender", &[]).await?;
+        exchange.approvals = TokenApprovals::new(spender.address, exchange.config.infinite_approval)
+            .with_gas_oracle(exchange.gas_oracle.clone());
+        
+        Ok(exchange)
+    }
+
+    fn get_token_address(&self, symbol: &str) -> Option<Address> {
+        erc20::token_address(self.chain_id, symbol)
+    }
+
+    fn pair_addresses(&self, pair: &TradingPair) -> Result<(Address, Address)> {
+        let base_address = self.get_token_address(&pair.base)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.base))?;
+        let quote_address = self.get_token_address(&pair.quote)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.quote))?;
+        Ok((base_address, quote_address))
+    }
+
+    async fn get_token_decimals(&self, token_address: Address) -> Result<u8> {
+        let token = ERC20::new(token_address, self.provider.clone());
+        let decimals = token.decimals().call().await?;
+        Ok(decimals)
+    }
+
+    async fn make_request<T>(&self, endpoint: &str, params: &[(&str, String)]) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let url = format!("{}/{}{}", self.config.api_url.trim_end_matches('/'), self.chain_id, endpoint);
+        
+        let response = self.client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.config.api_key))
+            .query(params)
+            .send()
+            .await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("1inch", response).await.into());
+        }
+        
+        Ok(response.json().await?)
+    }
+
+    async fn quote(&self, token_in: Address, token_out: Address, amount_in: U256) -> Result<QuoteResponse> {
+        let params = [
+            ("src", format!("{:?}", token_in)),
+            ("dst", format!("{:?}", token_out)),
+            ("amount", amount_in.to_string()),
+            ("includeGas", "true".to_string()),
+        ];
+        
+        self.make_request("/quote", &params).await
+    }
+
+    async fn quote_amount(&self, token_in: Address, token_out: Address, amount_in: U256) -> Result<U256> {
+        let quote = self.quote(token_in, token_out, amount_in).await?;
+        Ok(U256::from_dec_str(&quote.dst_amount)?)
+    }
+
+    // Average sell and buy prices for a base quantity; 1inch only quotes exact input, so the buy side
+    // spends the sell proceeds and measures how much base they buy back
+    async fn quote_prices(&self, pair: &TradingPair, quantity: Decimal) -> Result<(Decimal, Decimal)> {
+        let (base_address, quote_address) = self.pair_addresses(pair)?;
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        
+        let base_units = utils::decimal_to_u256(quantity, base_decimals)?;
+        let proceeds_units = self.quote_amount(base_address, quote_address, base_units).await?;
+        let bought_units = self.quote_amount(quote_address, base_address, proceeds_units).await?;
+        
+        let proceeds = utils::u256_to_decimal(proceeds_units, quote_decimals)?;
+        let bought = utils::u256_to_decimal(bought_units, base_decimals)?;
+        
+        if bought.is_zero() {
+            anyhow::bail!("1inch returned an empty quote for {}", pair.symbol);
+        }
+        
+        Ok((proceeds / quantity, proceeds / bought))
+    }
+
+    async fn native_to_quote(&self, amount: Decimal, pair: &TradingPair) -> Result<Decimal> {
+        let native_address = self.get_token_address(self.wrapped_native_symbol)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", self.wrapped_native_symbol))?;
+        let quote_address = self.get_token_address(&pair.quote)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.quote))?;
+        
+        if quote_address == native_address {
+            return Ok(amount);
+        }
+        
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        let amount_out = self.quote_amount(native_address, quote_address, U256::exp10(18)).await?;
+        
+        Ok(amount * utils::u256_to_decimal(amount_out, quote_decimals)?)
+    }
+
+    async fn execute_swap(&self, pair: &TradingPair, side: TradeSide, amount: Decimal, price: Option<Decimal>) -> Result<Trade> {
+        let client = self.signer.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("1inch connector is in read-only mode: no wallet configured"))?;
+        
+        let (base_address, quote_address) = self.pair_addresses(pair)?;
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        
+        let (input_token, output_token, amount_in) = match side {
+            TradeSide::Buy => {
+                let price = match price {
+                    Some(price) => price,
+                    None => self.quote_prices(pair, amount).await?.1,
+                };
+                (quote_address, base_address, utils::decimal_to_u256(amount * price, quote_decimals)?)
+            },
+            TradeSide::Sell => (base_address, quote_address, utils::decimal_to_u256(amount, base_decimals)?),
+        };
+        
+        let fees = self.gas_oracle.fees().await?;
+        self.approvals.ensure(client, input_token, amount_in).await?;
+        
+        let slippage_percent = (self.max_slippage * Decimal::from(100)).normalize();
+        let params = [
+            ("src", format!("{:?}", input_token)),
+            ("dst", format!("{:?}", output_token)),
+            ("amount", amount_in.to_string()),
+            ("from", format!("{:?}", client.address())),
+            ("slippage", slippage_percent.to_string()),
+            ("disableEstimate", "true".to_string()),
+        ];
+        let swap: SwapResponse = self.make_request("/swap", &params).await?;
+        let expected_out = U256::from_dec_str(&swap.dst_amount)?;
+        
+        let mut tx = TransactionRequest::new()
+            .to(swap.tx.to)
+            .data(swap.tx.data)
+            .value(U256::from_dec_str(&swap.tx.value)?)
+            .gas(swap.tx.gas.max(DEFAULT_SWAP_GAS))
+            .into();
+        fees.apply(&mut tx);
+        simulate::simulate(client, &tx).await?;
+        
+        let pending_tx = client.send_transaction(tx, None).await?;
+        let tx_hash = format!("{:?}", pending_tx.tx_hash());
+        
+        self.approvals.consume(input_token, amount_in);
+        
+        let (base_amount, quote_amount) = match side {
+            TradeSide::Buy => (expected_out, amount_in),
+            TradeSide::Sell => (amount_in, expected_out),
+        };
+        let base_amount = utils::u256_to_decimal(base_amount, base_decimals)?;
+        let quote_amount = utils::u256_to_decimal(quote_amount, quote_decimals)?;
+        
+        tracing::info!("Submitted 1inch swap {} for {} {}", tx_hash, amount, pair.symbol);
+        
+        let trade = Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            side,
+            amount,
+            price: price.unwrap_or(quote_amount / base_amount),
+            fee_amount: Decimal::ZERO,
+            fee_asset: None,
+            status: TradeStatus::Pending,
+            created_at: Utc::now(),
+            executed_at: None,
+            order_id: Some(tx_hash.clone()),
+            client_order_id: None,
+            tx_hash: Some(tx_hash.clone()),
+            simulated: false,
+            failure_reason: None,
+        };
+        
+        self.submitted_swaps.lock().unwrap().insert(tx_hash, SubmittedSwap {
+            trade: trade.clone(),
+            input_token,
+            output_token,
+        });
+        
+        Ok(trade)
+    }
+}
+
+#[async_trait]
+impl Exchange for OneInchExchange {
+    fn name(&self) -> &str {
+        "1inch"
+    }
+
+    fn chain_id(&self) -> Option<u64> {
+        Some(self.chain_id)
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        let (bid, ask) = self.quote_prices(pair, Decimal::ONE).await?;
+        
+        Ok(Price {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bid,
+            ask,
+            timestamp: Utc::now(),
+            volume_24h: None,
+            fetch_latency: None,
+        })
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        let (bids, asks) = synthesize_ladder(self.config.max_trade_amount, depth, |quantity| async move {
+            match self.quote_prices(pair, quantity).await {
+                Ok((bid, ask)) => Ok((Some(bid * quantity), Some(ask * quantity))),
+                Err(e) => {
+                    tracing::debug!("1inch quote for {} {} failed: {}", quantity, pair.symbol, e);
+                    Ok((None, None))
+                }
+            }
+        }).await?;
+        
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bids,
+            asks,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        let mut balances = HashMap::new();
+        
+        if let Some(wallet) = &self.wallet {
+            let native_balance = self.provider.get_balance(wallet.address(), None).await?;
+            let native_balance = utils::u256_to_decimal(native_balance, 18)?;
+            
+            if native_balance > Decimal::ZERO {
+                balances.insert(self.native_symbol.to_string(), Balance {
+                    asset: self.native_symbol.to_string(),
+                    free: native_balance,
+                    locked: Decimal::ZERO,
+                    total: native_balance,
+                    usd_value: Decimal::ZERO,
+                });
+            }
+            
+            for pair_str in &self.config.trading_pairs {
+                if let Some(pair) = self.parse_trading_pair(pair_str) {
+                    for symbol in [&pair.base, &pair.quote] {
+                        if let Some(token_address) = self.get_token_address(symbol) {
+                            let token = ERC20::new(token_address, self.provider.clone());
+                            if let Ok(balance) = token.balance_of(wallet.address()).call().await {
+                                let decimals = self.get_token_decimals(token_address).await?;
+                                let balance_decimal = utils::u256_to_decimal(balance, decimals)?;
+                                
+                                if balance_decimal > Decimal::ZERO {
+                                    balances.insert(symbol.clone(), Balance {
+                                        asset: symbol.clone(),
+                                        free: balance_decimal,
+                                        locked: Decimal::ZERO,
+                                        total: balance_decimal,
+                                        usd_value: Decimal::ZERO,
+                                    });
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        
+        Ok(balances)
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.execute_swap(pair, TradeSide::Buy, amount, price).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.execute_swap(pair, TradeSide::Sell, amount, price).await?)
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        let tx_hash: H256 = order_id.parse()
+            .map_err(|_| anyhow::anyhow!("Invalid transaction hash: {}", order_id))?;
+        
+        let swap = self.submitted_swaps.lock().unwrap().get(order_id).cloned()
+            .ok_or_else(|| anyhow::anyhow!("Unknown 1inch swap: {}", order_id))?;
+        let mut trade = swap.trade.clone();
+        
+        let receipt = match self.provider.get_transaction_receipt(tx_hash).await? {
+            Some(receipt) => receipt,
+            None => return Ok(trade),
+        };
+        
+        let block_number = receipt.block_number
+            .ok_or_else(|| anyhow::anyhow!("Receipt for {} has no block number", order_id))?;
+        let current_block = self.provider.get_block_number().await?;
+        let confirmations = current_block.saturating_sub(block_number).as_u64() + 1;
+        
+        if confirmations < self.config.confirmations {
+            return Ok(trade);
+        }
+        
+        let executed_at = self.provider.get_block(block_number).await?
+            .and_then(|block| chrono::DateTime::from_timestamp(block.timestamp.as_u64() as i64, 0))
+            .unwrap_or_else(Utc::now);
+        trade.executed_at = Some(executed_at);
+        
+        if receipt.status != Some(U64::from(1)) {
+            trade.status = TradeStatus::Failed;
+            trade.failure_reason = simulate::replay_revert_reason(&self.provider, receipt.transaction_hash).await;
+            tracing::warn!("Swap {} reverted: {}", order_id, trade.failure_reason.as_deref().unwrap_or("unknown reason"));
+            self.submitted_swaps.lock().unwrap().remove(order_id);
+            return Ok(trade);
+        }
+        
+        let owner = receipt.from;
+        let amount_in = erc20::sum_transfers(&receipt.logs, swap.input_token, Some(owner), None);
+        let amount_out = erc20::sum_transfers(&receipt.logs, swap.output_token, None, Some(owner));
+        
+        let input_decimals = self.get_token_decimals(swap.input_token).await?;
+        let output_decimals = self.get_token_decimals(swap.output_token).await?;
+        let amount_in = utils::u256_to_decimal(amount_in, input_decimals)?;
+        let amount_out = utils::u256_to_decimal(amount_out, output_decimals)?;
+        
+        let (base_amount, quote_amount) = match trade.side {
+            TradeSide::Buy => (amount_out, amount_in),
+            TradeSide::Sell => (amount_in, amount_out),
+        };
+        
+        if base_amount > Decimal::ZERO {
+            trade.amount = base_amount;
+            trade.price = quote_amount / base_amount;
+        }
+        
+        // Aggregator and pool fees are already reflected in the realized price, so only gas is recorded
+        if let (Some(gas_used), Some(gas_price)) = (receipt.gas_used, receipt.effective_gas_price) {
+            let gas_native = utils::u256_to_decimal(gas_used * gas_price, 18)?;
+            trade.fee_amount = self.native_to_quote(gas_native, &trade.pair).await?;
+            trade.fee_asset = Some(trade.pair.quote.clone());
+        }
+        trade.status = TradeStatus::Executed;
+        
+        self.submitted_swaps.lock().unwrap().remove(order_id);
+        
+        Ok(trade)
+    }
+
+    async fn cancel_order(&self, _order_id: &str) -> BotResult<()> {
+        Err(anyhow::anyhow!("1inch transactions cannot be cancelled").into())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.get_token_address(&pair.base).is_some() && 
+        self.get_token_address(&pair.quote).is_some()
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        let pairs = self.config.trading_pairs.iter()
+            .filter_map(|symbol| self.parse_trading_pair(symbol))
+            .filter(|pair| self.supports_pair(pair))
+            .collect();
+        
+        Ok(pairs)
+    }
+
+    async fn get_trading_fees(&self, _pair: &TradingPair) -> BotResult<TradingFees> {
+        // Quotes are net of pool and aggregator fees
+        Ok(TradingFees {
+            maker_fee: Decimal::ZERO,
+            taker_fee: Decimal::ZERO,
+        })
+    }
+
+    async fn get_execution_cost(&self, pair: &TradingPair, amount: Decimal) -> BotResult<Decimal> {
+        let (base_address, quote_address) = self.pair_addresses(pair)?;
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let amount_in = utils::decimal_to_u256(amount, base_decimals)?;
+        
+        let gas_units = match self.quote(base_address, quote_address, amount_in).await {
+            Ok(quote) => quote.gas.unwrap_or(DEFAULT_SWAP_GAS),
+            Err(e) => {
+                tracing::debug!("Gas estimation failed for {}, using default: {}", pair.symbol, e);
+                DEFAULT_SWAP_GAS
+            }
+        };
+        
+        let gas_price = self.gas_oracle.fees().await?.expected_price();
+        let cost_native = utils::u256_to_decimal(U256::from(gas_units) * gas_price, 18)?;
+        
+        Ok(self.native_to_quote(cost_native, pair).await?)
+    }
+}
+
+impl OneInchExchange {
+    fn parse_trading_pair(&self, pair_str: &str) -> Option<TradingPair> {
+        let parts: Vec<&str> = pair_str.split('/').collect();
+        if parts.len() == 2 {
+            Some(TradingPair::new(parts[0], parts[1]))
+        } else {
+            None
+        }
+    }
+}
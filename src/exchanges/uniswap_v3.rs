@@ -0,0 +1,763 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use ethers::abi::AbiDecode;
+use ethers::prelude::*;
+use futures::TryFutureExt;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::blockchain::atomic::{EncodedSwap, SwapStep};
+use crate::blockchain::blocks::{self, BlockPriceCache, HeadReceiver};
+use crate::blockchain::failover::{self, RpcProvider};
+use crate::blockchain::gas::GasOracle;
+use crate::blockchain::multicall::TokenReader;
+use crate::blockchain::nonce::{TransactionManager, TxStatus};
+use crate::blockchain::simulate;
+use crate::blockchain::BlockchainManager;
+use crate::config::{ChainConfig, ExchangeConfig};
+use crate::errors::BotResult;
+use crate::exchanges::erc20::{self, SignerClient, TokenApprovals};
+use crate::exchanges::{synthesize_ladder, AtomicSwapVenue, Exchange, OrderAmount, TradingFees};
+use crate::models::{Balance, OrderBook, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::utils;
+
+const UNISWAP_V3_QUOTER_V2: &str = "0x61fFE014bA17989E743c5F6cB21bF9697530B21e";
+const UNISWAP_V3_SWAP_ROUTER: &str = "0xE592427A0AEce92De3Edee1F18E0157C05861564";
+
+// Standard pool fee tiers in hundredths of a basis point
+const FEE_TIERS: [u32; 3] = [500, 3000, 10000];
+
+// Typical gas used by a single-pool V3 swap, used when the quoter gas estimate is unavailable
+const DEFAULT_SWAP_GAS: u64 = 180_000;
+
+abigen!(
+    QuoterV2,
+    r#"[
+        struct QuoteExactInputSingleParams { address tokenIn; address tokenOut; uint256 amountIn; uint24 fee; uint160 sqrtPriceLimitX96; }
+        struct QuoteExactOutputSingleParams { address tokenIn; address tokenOut; uint256 amount; uint24 fee; uint160 sqrtPriceLimitX96; }
+        function quoteExactInputSingle(QuoteExactInputSingleParams memory params) external returns (uint256 amountOut, uint160 sqrtPriceX96After, uint32 initializedTicksCrossed, uint256 gasEstimate)
+        function quoteExactOutputSingle(QuoteExactOutputSingleParams memory params) external returns (uint256 amountIn, uint160 sqrtPriceX96After, uint32 initializedTicksCrossed, uint256 gasEstimate)
+    ]"#
+);
+
+abigen!(
+    UniswapV3SwapRouter,
+    r#"[
+        struct ExactInputSingleParams { address tokenIn; address tokenOut; uint24 fee; address recipient; uint256 deadline; uint256 amountIn; uint256 amountOutMinimum; uint160 sqrtPriceLimitX96; }
+        function exactInputSingle(ExactInputSingleParams calldata params) external payable returns (uint256 amountOut)
+    ]"#
+);
+
+pub struct UniswapV3Exchange {
+    config: ExchangeConfig,
+    provider: Arc<RpcProvider>,
+    wallet: Option<LocalWallet>,
+    signer: Option<Arc<SignerClient>>,
+    max_slippage: Decimal,
+    quoter: Address,
+    router: Address,
+    submitted_swaps: Mutex<HashMap<String, SubmittedSwap>>,
+    // Fee tier of the pool last selected for each pair
+    selected_tiers: Mutex<HashMap<String, u32>>,
+    approvals: TokenApprovals,
+    tokens: TokenReader,
+    gas_oracle: Arc<GasOracle>,
+    // Present whenever a wallet is, so swaps and approvals never race for a nonce
+    transactions: Option<Arc<TransactionManager>>,
+    block_prices: BlockPriceCache,
+}
+
+#[derive(Debug, Clone)]
+struct SubmittedSwap {
+    trade: Trade,
+    input_token: Address,
+    output_token: Address,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TierQuote {
+    fee_tier: u32,
+    amount: U256,
+    gas_estimate: U256,
+}
+
+impl UniswapV3Exchange {
+    // Gas is priced by the chain's policy; the exchange entry's RPC URL is tried first and the chain's
+    // endpoints, while it is enabled, are its failovers
+    pub async fn new(config: ExchangeConfig, max_slippage: Decimal, chain: Option<&ChainConfig>, blockchain: &BlockchainManager) -> Result<Self> {
+        let gas_policy = chain.map(|chain| chain.gas_policy()).unwrap_or_default();
+        let enabled_chain = chain.filter(|chain| chain.enabled);
+        let mut rpc_urls = vec![config.api_url.clone()];
+        for url in enabled_chain.map(|chain| chain.rpc_urls.as_slice()).unwrap_or_default() {
+            if !rpc_urls.contains(url) {
+                rpc_urls.push(url.clone());
+            }
+        }
+        let max_block_lag = enabled_chain.map_or(failover::DEFAULT_MAX_BLOCK_LAG, |chain| chain.max_block_lag);
+        // The exchange entry's proxy and timeouts cover the chain's failovers too
+        let provider = Arc::new(failover::rpc_provider(&rpc_urls, max_block_lag, &config.http())?);
+        
+        let wallet = if !config.api_secret.is_empty() {
+            let chain_id = provider.get_chainid().await?.as_u64();
+            Some(config.api_secret.parse::<LocalWallet>()?.with_chain_id(chain_id))
+        } else {
+            None
+        };
+        
+        let signer = wallet.clone()
+            .map(|wallet| Arc::new(SignerMiddleware::new(provider.clone(), wallet)));
+        
+        let router: Address = UNISWAP_V3_SWAP_ROUTER.parse()?;
+        let gas_oracle = Arc::new(GasOracle::new(provider.clone(), gas_policy));
+        let transactions = signer.clone()
+            .map(|signer| blockchain.transaction_manager(signer, gas_oracle.clone()))
+            .transpose()?;
+        
+        let mut approvals = TokenApprovals::new(router, config.infinite_approval)
+            .with_gas_oracle(gas_oracle.clone());
+        if let Some(transactions) = &transactions {
+            approvals = approvals.with_transaction_manager(transactions.clone());
+        }
+        let tokens = TokenReader::new(provider.clone());
+        
+        Ok(Self {
+            config,
+            provider,
+            wallet,
+            signer,
+            max_slippage,
+            quoter: UNISWAP_V3_QUOTER_V2.parse()?,
+            router,
+            submitted_swaps: Mutex::new(HashMap::new()),
+            selected_tiers: Mutex::new(HashMap::new()),
+            approvals,
+            tokens,
+            gas_oracle,
+            transactions,
+            block_prices: BlockPriceCache::default(),
+        })
+    }
+
+    fn get_token_address(&self, symbol: &str) -> Option<Address> {
+        erc20::token_address(erc20::ETHEREUM_CHAIN_ID, symbol)
+    }
+
+    fn pair_addresses(&self, pair: &TradingPair) -> Result<(Address, Address)> {
+        let base_address = self.get_token_address(&pair.base)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.base))?;
+        let quote_address = self.get_token_address(&pair.quote)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.quote))?;
+        Ok((base_address, quote_address))
+    }
+
+    async fn get_token_decimals(&self, token_address: Address) -> Result<u8> {
+        Ok(self.tokens.decimals(token_address).await?)
+    }
+
+    fn tier_fee(fee_tier: u32) -> Decimal {
+        Decimal::from(fee_tier) / Decimal::from(1_000_000)
+    }
+
+    // Quotes every fee tier and keeps the one paying out the most; tiers without a pool revert and are skipped
+    async fn quote_exact_input(&self, token_in: Address, token_out: Address, amount_in: U256) -> Result<TierQuote> {
+        let quoter = QuoterV2::new(self.quoter, self.provider.clone());
+        
+        let quotes = FEE_TIERS.iter().map(|&fee_tier| {
+            let quoter = quoter.clone();
+            async move {
+                let params = QuoteExactInputSingleParams {
+                    token_in,
+                    token_out,
+                    amount_in,
+                    fee: fee_tier,
+                    sqrt_price_limit_x96: U256::zero(),
+                };
+                let (amount_out, _, _, gas_estimate) = quoter.quote_exact_input_single(params).call().await?;
+                Ok::<_, anyhow::Error>(TierQuote { fee_tier, amount: amount_out, gas_estimate })
+            }
+        });
+        
+        futures::future::join_all(quotes).await
+            .into_iter()
+            .filter_map(|quote| quote.ok())
+            .filter(|quote| !quote.amount.is_zero())
+            .max_by_key(|quote| quote.amount)
+            .ok_or_else(|| anyhow::anyhow!("No Uniswap V3 pool quotes {:?} -> {:?}", token_in, token_out))
+    }
+
+    // Quotes every fee tier and keeps the one requiring the least input
+    async fn quote_exact_output(&self, token_in: Address, token_out: Address, amount_out: U256) -> Result<TierQuote> {
+        let quoter = QuoterV2::new(self.quoter, self.provider.clone());
+        
+        let quotes = FEE_TIERS.iter().map(|&fee_tier| {
+            let quoter = quoter.clone();
+            async move {
+                let params = QuoteExactOutputSingleParams {
+                    token_in,
+                    token_out,
+                    amount: amount_out,
+                    fee: fee_tier,
+                    sqrt_price_limit_x96: U256::zero(),
+                };
+                let (amount_in, _, _, gas_estimate) = quoter.quote_exact_output_single(params).call().await?;
+                Ok::<_, anyhow::Error>(TierQuote { fee_tier, amount: amount_in, gas_estimate })
+            }
+        });
+        
+        futures::future::join_all(quotes).await
+            .into_iter()
+            .filter_map(|quote| quote.ok())
+            .filter(|quote| !quote.amount.is_zero())
+            .min_by_key(|quote| quote.amount)
+            .ok_or_else(|| anyhow::anyhow!("No Uniswap V3 pool quotes {:?} -> {:?}", token_in, token_out))
+    }
+
+    async fn native_to_quote(&self, amount: Decimal, pair: &TradingPair) -> Result<Decimal> {
+        self.native_to_token(amount, &pair.quote).await
+    }
+
+    async fn native_to_token(&self, amount: Decimal, symbol: &str) -> Result<Decimal> {
+        let weth_address = self.get_token_address("WETH")
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: WETH"))?;
+        let token_address = self.get_token_address(symbol)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", symbol))?;
+        
+        if token_address == weth_address {
+            return Ok(amount);
+        }
+        
+        let token_decimals = self.get_token_decimals(token_address).await?;
+        let quote = self.quote_exact_input(weth_address, token_address, U256::exp10(18)).await?;
+        let native_price = utils::u256_to_decimal(quote.amount, token_decimals)?;
+        
+        Ok(amount * native_price)
+    }
+
+    fn apply_slippage(&self, amount: U256) -> U256 {
+        let slippage_bps = utils::decimal_to_u256(self.max_slippage * Decimal::from(10_000), 0).unwrap_or_default();
+        let bps = U256::from(10_000);
+        
+        amount * (bps - slippage_bps.min(bps)) / bps
+    }
+
+    async fn execute_swap(&self, pair: &TradingPair, side: TradeSide, amount: OrderAmount, price: Option<Decimal>) -> Result<Trade> {
+        let (client, transactions) = self.signer.as_ref().zip(self.transactions.as_ref())
+            .ok_or_else(|| anyhow::anyhow!("Uniswap V3 connector is in read-only mode: no wallet configured"))?;
+        
+        let (base_address, quote_address) = self.pair_addresses(pair)?;
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        
+        let (input_token, output_token) = match side {
+            TradeSide::Buy => (quote_address, base_address),
+            TradeSide::Sell => (base_address, quote_address),
+        };
+        
+        // A quote-sized buy spends exactly that amount; a base-sized one is quoted for its exact output
+        let amount_in = match (&side, amount, price) {
+            (TradeSide::Buy, OrderAmount::Quote(spend), _) => utils::decimal_to_u256(spend, quote_decimals)?,
+            (TradeSide::Buy, OrderAmount::Base(amount), Some(price)) => utils::decimal_to_u256(amount * price, quote_decimals)?,
+            (TradeSide::Buy, OrderAmount::Base(amount), None) => {
+                let amount_out = utils::decimal_to_u256(amount, base_decimals)?;
+                self.quote_exact_output(input_token, output_token, amount_out).await?.amount
+            },
+            (TradeSide::Sell, OrderAmount::Base(amount), _) => utils::decimal_to_u256(amount, base_decimals)?,
+            (TradeSide::Sell, OrderAmount::Quote(_), _) => anyhow::bail!("Uniswap V3 sells are sized in {}", pair.base),
+        };
+        
+        let quote = self.quote_exact_input(input_token, output_token, amount_in).await?;
+        let amount_out_min = self.apply_slippage(quote.amount);
+        self.selected_tiers.lock().unwrap().insert(pair.symbol.clone(), quote.fee_tier);
+        
+        // Surfaces a fee cap breach before any approval is sent
+        self.gas_oracle.fees().await?;
+        self.approvals.ensure(client, input_token, amount_in).await?;
+        
+        let deadline = U256::from(Utc::now().timestamp() as u64 + self.config.swap_deadline_seconds);
+        
+        let router = UniswapV3SwapRouter::new(self.router, client.clone());
+        let params = ExactInputSingleParams {
+            token_in: input_token,
+            token_out: output_token,
+            fee: quote.fee_tier,
+            recipient: client.address(),
+            deadline,
+            amount_in,
+            amount_out_minimum: amount_out_min,
+            sqrt_price_limit_x96: U256::zero(),
+        };
+        
+        let call = router.exact_input_single(params);
+        let notional = match side {
+            TradeSide::Buy => utils::u256_to_decimal(amount_in, quote_decimals)?,
+            TradeSide::Sell => utils::u256_to_decimal(quote.amount, quote_decimals)?,
+        };
+        let private = transactions.private_relay().map_or(false, |relay| relay.should_route(notional));
+        let tx_hash = if private {
+            transactions.send_private(call.tx).await?
+        } else {
+            transactions.send(call.tx).await?
+        };
+        let tx_hash = format!("{:?}", tx_hash);
+        
+        self.approvals.consume(input_token, amount_in);
+        
+        let (base_amount, quote_amount) = match side {
+            TradeSide::Buy => (quote.amount, amount_in),
+            TradeSide::Sell => (amount_in, quote.amount),
+        };
+        let base_amount = utils::u256_to_decimal(base_amount, base_decimals)?;
+        let quote_amount = utils::u256_to_decimal(quote_amount, quote_decimals)?;
+        // Until the receipt settles it, a quote-sized buy reports the base it was quoted for
+        let amount = match amount {
+            OrderAmount::Base(amount) => amount,
+            OrderAmount::Quote(_) => base_amount,
+        };
+        
+        tracing::info!("Submitted Uniswap V3 swap {} for {} {} in the {} tier{}", tx_hash, amount, pair.symbol, quote.fee_tier,
+                       if private { " via private relay" } else { "" });
+        
+        let trade = Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            side,
+            amount,
+            price: price.unwrap_or(quote_amount / base_amount),
+            fee_amount: Decimal::ZERO,
+            fee_asset: None,
+            status: TradeStatus::Pending,
+            created_at: Utc::now(),
+            executed_at: None,
+            order_id: Some(tx_hash.clone()),
+            client_order_id: None,
+            tx_hash: Some(tx_hash.clone()),
+            simulated: false,
+            failure_reason: None,
+        };
+        
+        self.submitted_swaps.lock().unwrap().insert(tx_hash, SubmittedSwap {
+            trade: trade.clone(),
+            input_token,
+            output_token,
+        });
+        
+        Ok(trade)
+    }
+
+    async fn recover_submitted_swap(&self, tx_hash: H256) -> Result<SubmittedSwap> {
+        let tx = self.provider.get_transaction(tx_hash).await?
+            .ok_or_else(|| anyhow::anyhow!("Transaction not found: {:?}", tx_hash))?;
+        
+        let call = ExactInputSingleCall::decode(&tx.input)
+            .map_err(|_| anyhow::anyhow!("Transaction {:?} is not a Uniswap V3 swap", tx_hash))?;
+        let input_token = call.params.token_in;
+        let output_token = call.params.token_out;
+        
+        let (pair, side) = self.config.trading_pairs.iter()
+            .filter_map(|symbol| self.parse_trading_pair(symbol))
+            .find_map(|pair| {
+                let (base, quote) = self.pair_addresses(&pair).ok()?;
+                if input_token == base && output_token == quote {
+                    Some((pair, TradeSide::Sell))
+                } else if input_token == quote && output_token == base {
+                    Some((pair, TradeSide::Buy))
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| anyhow::anyhow!("Swap {:?} does not match a configured pair", tx_hash))?;
+        
+        let trade = Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            pair,
+            side,
+            amount: Decimal::ZERO,
+            price: Decimal::ZERO,
+            fee_amount: Decimal::ZERO,
+            fee_asset: None,
+            status: TradeStatus::Pending,
+            created_at: Utc::now(),
+            executed_at: None,
+            order_id: Some(format!("{:?}", tx_hash)),
+            client_order_id: None,
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            simulated: false,
+            failure_reason: None,
+        };
+        
+        Ok(SubmittedSwap {
+            trade,
+            input_token,
+            output_token,
+        })
+    }
+}
+
+#[async_trait]
+impl Exchange for UniswapV3Exchange {
+    fn name(&self) -> &str {
+        "uniswap_v3"
+    }
+
+    fn chain_id(&self) -> Option<u64> {
+        Some(erc20::ETHEREUM_CHAIN_ID)
+    }
+
+    fn watch_chain_head(&self, heads: HeadReceiver) {
+        self.block_prices.attach(heads);
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        if let Some(price) = self.block_prices.get(&pair.symbol) {
+            return Ok(price);
+        }
+        let head = self.block_prices.head();
+        
+        let (base_address, quote_address) = self.pair_addresses(pair)?;
+        
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        
+        let one_unit = U256::exp10(base_decimals as usize);
+        
+        let (sell_quote, buy_quote, quoted_at) = tokio::try_join!(
+            self.quote_exact_input(base_address, quote_address, one_unit),
+            self.quote_exact_output(quote_address, base_address, one_unit),
+            blocks::quote_time(head, &*self.provider).err_into::<anyhow::Error>(),
+        )?;
+        
+        self.selected_tiers.lock().unwrap().insert(pair.symbol.clone(), sell_quote.fee_tier);
+        
+        Ok(self.block_prices.store(head, Price {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bid: utils::u256_to_decimal(sell_quote.amount, quote_decimals)?,
+            ask: utils::u256_to_decimal(buy_quote.amount, quote_decimals)?,
+            timestamp: quoted_at,
+            volume_24h: None,
+            fetch_latency: None,
+        }))
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        let (base_address, quote_address) = self.pair_addresses(pair)?;
+        
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        
+        // Each size is quoted on the best tier for it
+        let (bids, asks) = synthesize_ladder(self.config.max_trade_amount, depth, |quantity| async move {
+            let quantity_units = utils::decimal_to_u256(quantity, base_decimals)?;
+            let (sell_quote, buy_quote) = tokio::join!(
+                self.quote_exact_input(base_address, quote_address, quantity_units),
+                self.quote_exact_output(quote_address, base_address, quantity_units),
+            );
+            
+            let proceeds = sell_quote.ok().map(|quote| utils::u256_to_decimal(quote.amount, quote_decimals)).transpose()?;
+            let cost = buy_quote.ok().map(|quote| utils::u256_to_decimal(quote.amount, quote_decimals)).transpose()?;
+            Ok((proceeds, cost))
+        }).await?;
+        
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bids,
+            asks,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        let mut balances = HashMap::new();
+        
+        if let Some(wallet) = &self.wallet {
+            let mut tokens: Vec<(String, Address)> = Vec::new();
+            for pair in self.config.trading_pairs.iter().filter_map(|pair| self.parse_trading_pair(pair)) {
+                for symbol in [pair.base, pair.quote] {
+                    if let Some(token_address) = self.get_token_address(&symbol) {
+                        if !tokens.iter().any(|(known, _)| *known == symbol) {
+                            tokens.push((symbol, token_address));
+                        }
+                    }
+                }
+            }
+            
+            let addresses: Vec<Address> = tokens.iter().map(|(_, address)| *address).collect();
+            let snapshot = self.tokens.snapshot(wallet.address(), &addresses, Some(self.router)).await?;
+            self.approvals.prime(&snapshot.allowances);
+            
+            let native_balance = utils::u256_to_decimal(snapshot.native, 18)?;
+            if native_balance > Decimal::ZERO {
+                balances.insert("ETH".to_string(), Balance {
+                    asset: "ETH".to_string(),
+                    free: native_balance,
+                    locked: Decimal::ZERO,
+                    total: native_balance,
+                    usd_value: Decimal::ZERO,
+                });
+            }
+            
+            for (symbol, token_address) in tokens {
+                if let Some(on_chain) = snapshot.symbols.get(&token_address) {
+                    if !on_chain.eq_ignore_ascii_case(&symbol) {
+                        tracing::debug!("{} token {:?} is configured as {} but reports symbol {}", self.name(), token_address, symbol, on_chain);
+                    }
+                }
+                let (Some(balance), Some(decimals)) = (snapshot.balances.get(&token_address), snapshot.decimals.get(&token_address)) else {
+                    continue;
+                };
+                let balance_decimal = utils::u256_to_decimal(*balance, *decimals)?;
+                
+                if balance_decimal > Decimal::ZERO {
+                    balances.insert(symbol.clone(), Balance {
+                        asset: symbol,
+                        free: balance_decimal,
+                        locked: Decimal::ZERO,
+                        total: balance_decimal,
+                        usd_value: Decimal::ZERO,
+                    });
+                }
+            }
+        }
+        
+        Ok(balances)
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.execute_swap(pair, TradeSide::Buy, OrderAmount::Base(amount), price).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.execute_swap(pair, TradeSide::Sell, OrderAmount::Base(amount), price).await?)
+    }
+
+    fn supports_quote_orders(&self) -> bool {
+        true
+    }
+
+    async fn place_market_buy_quote(&self, pair: &TradingPair, quote_amount: Decimal) -> BotResult<Trade> {
+        Ok(self.execute_swap(pair, TradeSide::Buy, OrderAmount::Quote(quote_amount), None).await?)
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        let tx_hash: H256 = order_id.parse()
+            .map_err(|_| anyhow::anyhow!("Invalid transaction hash: {}", order_id))?;
+        
+        let known_swap = self.submitted_swaps.lock().unwrap().get(order_id).cloned();
+        let swap = match known_swap {
+            Some(swap) => swap,
+            None => self.recover_submitted_swap(tx_hash).await?,
+        };
+        let mut trade = swap.trade.clone();
+        
+        // Also rebroadcasts the swap with a higher fee if it has stalled
+        let status = match &self.transactions {
+            Some(transactions) => transactions.poll(tx_hash).await?,
+            None => match self.provider.get_transaction_receipt(tx_hash).await? {
+                Some(receipt) => TxStatus::Mined(receipt),
+                None => TxStatus::Pending,
+            },
+        };
+        let receipt = match status {
+            TxStatus::Mined(receipt) => receipt,
+            TxStatus::Pending => return Ok(trade),
+            TxStatus::Abandoned(reason) => {
+                tracing::warn!("Giving up on Uniswap V3 swap: {}", reason);
+                trade.status = TradeStatus::Failed;
+                trade.failure_reason = Some(reason);
+                self.submitted_swaps.lock().unwrap().remove(order_id);
+                return Ok(trade);
+            },
+        };
+        
+        let block_number = receipt.block_number
+            .ok_or_else(|| anyhow::anyhow!("Receipt for {} has no block number", order_id))?;
+        let current_block = self.provider.get_block_number().await?;
+        let confirmations = current_block.saturating_sub(block_number).as_u64() + 1;
+        
+        if confirmations < self.config.confirmations {
+            return Ok(trade);
+        }
+        
+        let executed_at = self.provider.get_block(block_number).await?
+            .and_then(|block| chrono::DateTime::from_timestamp(block.timestamp.as_u64() as i64, 0))
+            .unwrap_or_else(Utc::now);
+        trade.executed_at = Some(executed_at);
+        
+        if receipt.status != Some(U64::from(1)) {
+            trade.status = TradeStatus::Failed;
+            trade.failure_reason = simulate::replay_revert_reason(&self.provider, receipt.transaction_hash).await;
+            tracing::warn!("Swap {} reverted: {}", order_id, trade.failure_reason.as_deref().unwrap_or("unknown reason"));
+            self.submitted_swaps.lock().unwrap().remove(order_id);
+            return Ok(trade);
+        }
+        
+        let owner = receipt.from;
+        let amount_in = erc20::sum_transfers(&receipt.logs, swap.input_token, Some(owner), None);
+        let amount_out = erc20::sum_transfers(&receipt.logs, swap.output_token, None, Some(owner));
+        
+        let input_decimals = self.get_token_decimals(swap.input_token).await?;
+        let output_decimals = self.get_token_decimals(swap.output_token).await?;
+        let amount_in = utils::u256_to_decimal(amount_in, input_decimals)?;
+        let amount_out = utils::u256_to_decimal(amount_out, output_decimals)?;
+        
+        let (base_amount, quote_amount) = match trade.side {
+            TradeSide::Buy => (amount_out, amount_in),
+            TradeSide::Sell => (amount_in, amount_out),
+        };
+        
+        if base_amount > Decimal::ZERO {
+            trade.amount = base_amount;
+            trade.price = quote_amount / base_amount;
+        }
+        
+        // The pool fee is already reflected in the realized price, so only gas is recorded as a fee
+        if let (Some(gas_used), Some(gas_price)) = (receipt.gas_used, receipt.effective_gas_price) {
+            let gas_native = utils::u256_to_decimal(gas_used * gas_price, 18)?;
+            trade.fee_amount = self.native_to_quote(gas_native, &trade.pair).await?;
+            trade.fee_asset = Some(trade.pair.quote.clone());
+        }
+        trade.status = TradeStatus::Executed;
+        
+        self.submitted_swaps.lock().unwrap().remove(order_id);
+        
+        Ok(trade)
+    }
+
+    async fn cancel_order(&self, _order_id: &str) -> BotResult<()> {
+        Err(anyhow::anyhow!("Uniswap V3 transactions cannot be cancelled").into())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.get_token_address(&pair.base).is_some() && 
+        self.get_token_address(&pair.quote).is_some()
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        let pairs = self.config.trading_pairs.iter()
+            .filter_map(|symbol| self.parse_trading_pair(symbol))
+            .filter(|pair| self.supports_pair(pair))
+            .collect();
+        
+        Ok(pairs)
+    }
+
+    async fn get_trading_fees(&self, pair: &TradingPair) -> BotResult<TradingFees> {
+        let cached = self.selected_tiers.lock().unwrap().get(&pair.symbol).copied();
+        let fee_tier = match cached {
+            Some(fee_tier) => fee_tier,
+            None => {
+                let (base_address, quote_address) = self.pair_addresses(pair)?;
+                let base_decimals = self.get_token_decimals(base_address).await?;
+                let quote = self.quote_exact_input(base_address, quote_address, U256::exp10(base_decimals as usize)).await?;
+                self.selected_tiers.lock().unwrap().insert(pair.symbol.clone(), quote.fee_tier);
+                quote.fee_tier
+            }
+        };
+        
+        Ok(TradingFees {
+            maker_fee: Self::tier_fee(fee_tier),
+            taker_fee: Self::tier_fee(fee_tier),
+        })
+    }
+
+    async fn get_execution_cost(&self, pair: &TradingPair, amount: Decimal) -> BotResult<Decimal> {
+        let (base_address, quote_address) = self.pair_addresses(pair)?;
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let amount_in = utils::decimal_to_u256(amount, base_decimals)?;
+        
+        let gas_units = match self.quote_exact_input(base_address, quote_address, amount_in).await {
+            Ok(quote) if !quote.gas_estimate.is_zero() => quote.gas_estimate,
+            Ok(_) => U256::from(DEFAULT_SWAP_GAS),
+            Err(e) => {
+                tracing::debug!("Gas estimation failed for {}, using default: {}", pair.symbol, e);
+                U256::from(DEFAULT_SWAP_GAS)
+            }
+        };
+        
+        let gas_price = self.gas_oracle.fees().await?.expected_price();
+        let cost_native = utils::u256_to_decimal(gas_units * gas_price, 18)?;
+        
+        Ok(self.native_to_quote(cost_native, pair).await?)
+    }
+
+    // Moving tokens off a DEX is a wallet transfer, so the fee is its gas priced in the token
+    async fn get_withdrawal_fee(&self, asset: &str) -> BotResult<Decimal> {
+        let gas_price = self.gas_oracle.fees().await?.expected_price();
+        let cost_native = utils::u256_to_decimal(U256::from(erc20::TRANSFER_GAS) * gas_price, 18)?;
+        
+        Ok(self.native_to_token(cost_native, asset).await?)
+    }
+
+    // Tokens are held by the connector's own wallet
+    async fn get_deposit_address(&self, asset: &str) -> BotResult<String> {
+        let wallet = self.wallet.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("{} has no wallet configured", self.name()))?;
+        self.get_token_address(asset)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", asset))?;
+        Ok(format!("{:?}", wallet.address()))
+    }
+
+    fn atomic_venue(&self) -> Option<&dyn AtomicSwapVenue> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl AtomicSwapVenue for UniswapV3Exchange {
+    fn token_address(&self, symbol: &str) -> Option<Address> {
+        self.get_token_address(symbol)
+    }
+
+    async fn token_decimals(&self, token: Address) -> Result<u8> {
+        self.get_token_decimals(token).await
+    }
+
+    async fn encode_swap(&self, token_in: Address, token_out: Address, amount_in: U256, recipient: Address) -> Result<EncodedSwap> {
+        let quote = self.quote_exact_input(token_in, token_out, amount_in).await?;
+        let min_amount_out = self.apply_slippage(quote.amount);
+        
+        let router = UniswapV3SwapRouter::new(self.router, self.provider.clone());
+        let params = ExactInputSingleParams {
+            token_in,
+            token_out,
+            fee: quote.fee_tier,
+            recipient,
+            deadline: U256::from(Utc::now().timestamp() as u64 + self.config.swap_deadline_seconds),
+            amount_in,
+            amount_out_minimum: min_amount_out,
+            sqrt_price_limit_x96: U256::zero(),
+        };
+        let data = router.exact_input_single(params).calldata()
+            .ok_or_else(|| anyhow::anyhow!("Failed to encode Uniswap V3 swap"))?;
+        
+        Ok(EncodedSwap {
+            step: SwapStep {
+                target: self.router,
+                token_in,
+                token_out,
+                amount_in,
+                min_amount_out,
+                data,
+            },
+            expected_out: quote.amount,
+        })
+    }
+}
+
+impl UniswapV3Exchange {
+    fn parse_trading_pair(&self, pair_str: &str) -> Option<TradingPair> {
+        let parts: Vec<&str> = pair_str.split('/').collect();
+        if parts.len() == 2 {
+            Some(TradingPair::new(parts[0], parts[1]))
+        } else {
+            None
+        }
+    }
+}
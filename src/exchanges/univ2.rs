@@ -0,0 +1,1113 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use ethers::abi::AbiDecode;
+use ethers::contract::multicall_contract::{Call3Value, Multicall3};
+use ethers::contract::MULTICALL_ADDRESS;
+use ethers::prelude::*;
+use ethers::providers::{spoof, RawCall};
+use futures::TryFutureExt;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use crate::blockchain::atomic::{EncodedSwap, SwapStep};
+use crate::blockchain::blocks::{self, BlockPriceCache, HeadReceiver};
+use crate::blockchain::failover::{self, RpcProvider};
+use crate::blockchain::gas::{GasFees, GasOracle, GasPolicy};
+use crate::blockchain::multicall::TokenReader;
+use crate::blockchain::simulate;
+use crate::config::{ChainConfig, ExchangeConfig};
+use crate::errors::BotResult;
+use crate::exchanges::erc20::{self, SignerClient, TokenApprovals, ERC20};
+use crate::exchanges::{AtomicSwapVenue, Exchange, OrderAmount, TokenProbe, TradingFees};
+use crate::models::{Balance, OrderBook, OrderBookLevel, PoolReserves, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::utils;
+
+const UNISWAP_V2_ROUTER: &str = "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D";
+const UNISWAP_V2_FACTORY: &str = "0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f";
+const SUSHISWAP_ROUTER: &str = "0xd9e1cE17f2641f24aE83637ab66a2cca9C378B9F";
+const SUSHISWAP_FACTORY: &str = "0xC0AEe478e3658e2610c5F7A4A2E1777cE9e4f2Ac";
+const PANCAKESWAP_ROUTER: &str = "0x10ED43C718714eb63d5aA57B78B54704E256024E";
+const PANCAKESWAP_FACTORY: &str = "0xcA143Ce32Fe78f1f7019d7d551a6402fC5350c73";
+const QUICKSWAP_ROUTER: &str = "0xa5E0829CaCEd8fFDD4De3c43696c57F7D7A678ff";
+const QUICKSWAP_FACTORY: &str = "0x5757371414417b8C6CAad45bAeF941aBc7d3Ab32";
+
+// Typical gas used by a two-hop V2 swap, used when estimate_gas is unavailable
+const DEFAULT_SWAP_GAS: u64 = 150_000;
+const FEE_DENOMINATOR: u64 = 10_000;
+// keccak256("eip1967.proxy.implementation") - 1
+const EIP1967_IMPLEMENTATION_SLOT: &str = "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+// Taxes are measured in parts per million of the quoted amount
+const TAX_DENOMINATOR: u64 = 1_000_000;
+
+// Deployment parameters that distinguish one Uniswap V2 fork from another
+#[derive(Debug, Clone)]
+pub struct UniV2Params {
+    pub name: String,
+    pub router: Address,
+    pub factory: Address,
+    pub fee: Decimal,
+    pub chain_id: u64,
+    pub native_symbol: String,
+    pub wrapped_native_symbol: String,
+    // Route swaps between two non-native tokens through the wrapped native token
+    pub route_via_wrapped_native: bool,
+}
+
+impl UniV2Params {
+    pub fn uniswap() -> Result<Self> {
+        Ok(Self {
+            name: "uniswap".to_string(),
+            router: UNISWAP_V2_ROUTER.parse()?,
+            factory: UNISWAP_V2_FACTORY.parse()?,
+            fee: Decimal::from_str("0.003")?,
+            chain_id: erc20::ETHEREUM_CHAIN_ID,
+            native_symbol: "ETH".to_string(),
+            wrapped_native_symbol: "WETH".to_string(),
+            route_via_wrapped_native: false,
+        })
+    }
+
+    pub fn sushiswap() -> Result<Self> {
+        Ok(Self {
+            name: "sushiswap".to_string(),
+            router: SUSHISWAP_ROUTER.parse()?,
+            factory: SUSHISWAP_FACTORY.parse()?,
+            fee: Decimal::from_str("0.003")?,
+            chain_id: erc20::ETHEREUM_CHAIN_ID,
+            native_symbol: "ETH".to_string(),
+            wrapped_native_symbol: "WETH".to_string(),
+            route_via_wrapped_native: false,
+        })
+    }
+
+    pub fn pancakeswap() -> Result<Self> {
+        Ok(Self {
+            name: "pancakeswap".to_string(),
+            router: PANCAKESWAP_ROUTER.parse()?,
+            factory: PANCAKESWAP_FACTORY.parse()?,
+            fee: Decimal::from_str("0.0025")?,
+            chain_id: erc20::BSC_CHAIN_ID,
+            native_symbol: "BNB".to_string(),
+            wrapped_native_symbol: "WBNB".to_string(),
+            route_via_wrapped_native: true,
+        })
+    }
+
+    pub fn quickswap() -> Result<Self> {
+        Ok(Self {
+            name: "quickswap".to_string(),
+            router: QUICKSWAP_ROUTER.parse()?,
+            factory: QUICKSWAP_FACTORY.parse()?,
+            fee: Decimal::from_str("0.003")?,
+            chain_id: erc20::POLYGON_CHAIN_ID,
+            native_symbol: "MATIC".to_string(),
+            wrapped_native_symbol: "WMATIC".to_string(),
+            route_via_wrapped_native: false,
+        })
+    }
+
+    fn fee_bps(&self) -> U256 {
+        utils::decimal_to_u256(self.fee * Decimal::from(FEE_DENOMINATOR), 0).unwrap_or_default()
+    }
+}
+
+// Output of a single hop, the same integer math as UniswapV2Library.getAmountOut
+fn amount_out(amount_in: U256, reserve_in: U256, reserve_out: U256, fee_bps: U256) -> U256 {
+    if amount_in.is_zero() || reserve_in.is_zero() || reserve_out.is_zero() {
+        return U256::zero();
+    }
+    let amount_in_with_fee = amount_in * (U256::from(FEE_DENOMINATOR) - fee_bps);
+    amount_in_with_fee * reserve_out / (reserve_in * U256::from(FEE_DENOMINATOR) + amount_in_with_fee)
+}
+
+fn amount_in(amount_out: U256, reserve_in: U256, reserve_out: U256, fee_bps: U256) -> Result<U256> {
+    if amount_out >= reserve_out {
+        anyhow::bail!("Pool holds only {} of the {} requested", reserve_out, amount_out);
+    }
+    Ok(reserve_in * amount_out * U256::from(FEE_DENOMINATOR)
+        / ((reserve_out - amount_out) * (U256::from(FEE_DENOMINATOR) - fee_bps))
+        + U256::one())
+}
+
+// Reserves are (reserve_in, reserve_out) per hop of the path
+fn amounts_out(amount_in: U256, reserves: &[(U256, U256)], fee_bps: U256) -> Vec<U256> {
+    let mut amounts = vec![amount_in];
+    for &(reserve_in, reserve_out) in reserves {
+        let next = amount_out(amounts[amounts.len() - 1], reserve_in, reserve_out, fee_bps);
+        amounts.push(next);
+    }
+    amounts
+}
+
+fn amounts_in(amount_out: U256, reserves: &[(U256, U256)], fee_bps: U256) -> Result<Vec<U256>> {
+    let mut amounts = vec![amount_out];
+    for &(reserve_in, reserve_out) in reserves.iter().rev() {
+        let previous = amount_in(amounts[0], reserve_in, reserve_out, fee_bps)?;
+        amounts.insert(0, previous);
+    }
+    Ok(amounts)
+}
+
+fn less_tax(amount: U256, tax: Decimal) -> U256 {
+    let kept = utils::decimal_to_u256((Decimal::ONE - tax.min(Decimal::ONE)) * Decimal::from(TAX_DENOMINATOR), 0).unwrap_or_default();
+    amount * kept / U256::from(TAX_DENOMINATOR)
+}
+
+// Share of `expected` that did not arrive, as a fraction
+fn shortfall(expected: U256, received: U256) -> Decimal {
+    if expected.is_zero() || received >= expected {
+        return Decimal::ZERO;
+    }
+    let ppm = (expected - received) * U256::from(TAX_DENOMINATOR) / expected;
+    Decimal::new(ppm.low_u64() as i64, 6)
+}
+
+pub struct UniV2StyleExchange {
+    params: UniV2Params,
+    config: ExchangeConfig,
+    provider: Arc<RpcProvider>,
+    wallet: Option<LocalWallet>,
+    signer: Option<Arc<SignerClient>>,
+    max_slippage: Decimal,
+    submitted_swaps: Mutex<HashMap<String, SubmittedSwap>>,
+    approvals: TokenApprovals,
+    tokens: TokenReader,
+    gas_limit: Option<U256>,
+    gas_oracle: Arc<GasOracle>,
+    block_prices: BlockPriceCache,
+    // Pool addresses never change, so lookups are kept for the life of the connector
+    pair_addresses: Mutex<HashMap<(Address, Address), Address>>,
+    // Raw pool reserves with the block they were read at
+    reserves: Mutex<HashMap<Address, (u64, (U256, U256))>>,
+    // Transfer tax of fee-on-transfer tokens found by probe_token; swaps through them use the router's
+    // SupportingFeeOnTransferTokens variants
+    transfer_taxes: Mutex<HashMap<Address, Decimal>>,
+}
+
+#[derive(Debug, Clone)]
+struct SubmittedSwap {
+    trade: Trade,
+    input_token: Address,
+    output_token: Address,
+}
+
+abigen!(
+    UniswapV2Router,
+    r#"[
+        function getAmountsOut(uint amountIn, address[] calldata path) external view returns (uint[] memory amounts)
+        function getAmountsIn(uint amountOut, address[] calldata path) external view returns (uint[] memory amounts)
+        function swapExactTokensForTokens(uint amountIn, uint amountOutMin, address[] calldata path, address to, uint deadline) external returns (uint[] memory amounts)
+        function swapTokensForExactTokens(uint amountOut, uint amountInMax, address[] calldata path, address to, uint deadline) external returns (uint[] memory amounts)
+        function swapExactTokensForTokensSupportingFeeOnTransferTokens(uint amountIn, uint amountOutMin, address[] calldata path, address to, uint deadline) external
+        function swapExactETHForTokensSupportingFeeOnTransferTokens(uint amountOutMin, address[] calldata path, address to, uint deadline) external payable
+    ]"#
+);
+
+abigen!(
+    UniswapV2Factory,
+    r#"[
+        function getPair(address tokenA, address tokenB) external view returns (address pair)
+    ]"#
+);
+
+abigen!(
+    UniswapV2Pair,
+    r#"[
+        function getReserves() external view returns (uint112 reserve0, uint112 reserve1, uint32 blockTimestampLast)
+    ]"#
+);
+
+impl UniV2StyleExchange {
+    pub async fn new(config: ExchangeConfig, max_slippage: Decimal, params: UniV2Params) -> Result<Self> {
+        let provider = failover::rpc_provider(&[config.api_url.clone()], failover::DEFAULT_MAX_BLOCK_LAG, &config.http())?;
+        let private_key = config.api_secret.clone();
+        Self::connect(config, provider, &private_key, None, GasPolicy::default(), max_slippage, params).await
+    }
+
+    // RPC URL, private key and gas settings come from the chain config rather than the exchange entry
+    pub async fn with_chain(config: ExchangeConfig, chain: &ChainConfig, max_slippage: Decimal, params: UniV2Params) -> Result<Self> {
+        if chain.chain_id != params.chain_id {
+            anyhow::bail!("{} requires chain id {}, but {} is chain id {}", params.name, params.chain_id, chain.name, chain.chain_id);
+        }
+        
+        let gas_limit = U256::from(chain.max_gas_limit);
+        let provider = failover::rpc_provider(&chain.rpc_urls, chain.max_block_lag, &chain.http())?;
+        Self::connect(config, provider, &chain.private_key, Some(gas_limit), chain.gas_policy(), max_slippage, params).await
+    }
+
+    async fn connect(
+        config: ExchangeConfig,
+        provider: RpcProvider,
+        private_key: &str,
+        gas_limit: Option<U256>,
+        gas_policy: GasPolicy,
+        max_slippage: Decimal,
+        params: UniV2Params,
+    ) -> Result<Self> {
+        let provider = Arc::new(provider);
+        
+        // Initialize wallet if private key is provided
+        let wallet = if !private_key.is_empty() {
+            let chain_id = provider.get_chainid().await?.as_u64();
+            Some(private_key.parse::<LocalWallet>()?.with_chain_id(chain_id))
+        } else {
+            None
+        };
+        
+        let signer = wallet.clone()
+            .map(|wallet| Arc::new(SignerMiddleware::new(provider.clone(), wallet)));
+        
+        tracing::debug!("{} using router {:?} and factory {:?}", params.name, params.router, params.factory);
+        
+        let gas_oracle = Arc::new(GasOracle::new(provider.clone(), gas_policy));
+        let approvals = TokenApprovals::new(params.router, config.infinite_approval)
+            .with_gas_oracle(gas_oracle.clone());
+        let tokens = TokenReader::new(provider.clone());
+        
+        Ok(Self {
+            params,
+            config,
+            provider,
+            wallet,
+            signer,
+            max_slippage,
+            submitted_swaps: Mutex::new(HashMap::new()),
+            approvals,
+            tokens,
+            gas_limit,
+            gas_oracle,
+            block_prices: BlockPriceCache::default(),
+            pair_addresses: Mutex::new(HashMap::new()),
+            reserves: Mutex::new(HashMap::new()),
+            transfer_taxes: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn get_token_address(&self, symbol: &str) -> Option<Address> {
+        erc20::token_address(self.params.chain_id, symbol)
+    }
+
+    fn swap_path(&self, token_in: Address, token_out: Address) -> Vec<Address> {
+        if self.params.route_via_wrapped_native {
+            if let Some(wrapped_native) = self.get_token_address(&self.params.wrapped_native_symbol) {
+                if token_in != wrapped_native && token_out != wrapped_native {
+                    return vec![token_in, wrapped_native, token_out];
+                }
+            }
+        }
+        vec![token_in, token_out]
+    }
+
+    async fn gas_price(&self) -> Result<U256> {
+        Ok(self.gas_oracle.fees().await?.expected_price())
+    }
+
+    async fn get_token_decimals(&self, token_address: Address) -> Result<u8> {
+        Ok(self.tokens.decimals(token_address).await?)
+    }
+
+    async fn pair_address(&self, token_a: Address, token_b: Address) -> Result<Address> {
+        let key = if token_a < token_b { (token_a, token_b) } else { (token_b, token_a) };
+        if let Some(pair) = self.pair_addresses.lock().unwrap().get(&key) {
+            return Ok(*pair);
+        }
+        
+        let factory = UniswapV2Factory::new(self.params.factory, self.provider.clone());
+        let pair = factory.get_pair(key.0, key.1).call().await?;
+        if pair.is_zero() {
+            anyhow::bail!("{} has no pool for {:?}/{:?}", self.params.name, key.0, key.1);
+        }
+        
+        self.pair_addresses.lock().unwrap().insert(key, pair);
+        Ok(pair)
+    }
+
+    // Reserves oriented as (token_in, token_out); reads are shared until the next block while a head subscription is live
+    async fn reserves(&self, token_in: Address, token_out: Address) -> Result<(U256, U256)> {
+        let pair = self.pair_address(token_in, token_out).await?;
+        let head = self.block_prices.head();
+        
+        let cached = head.and_then(|head| self.reserves.lock().unwrap().get(&pair)
+            .filter(|(block, _)| *block == head.number)
+            .map(|(_, reserves)| *reserves));
+        let (reserve0, reserve1) = match cached {
+            Some(reserves) => reserves,
+            None => {
+                let (reserve0, reserve1, _) = UniswapV2Pair::new(pair, self.provider.clone()).get_reserves().call().await?;
+                let reserves = (U256::from(reserve0), U256::from(reserve1));
+                if let Some(head) = head {
+                    self.reserves.lock().unwrap().insert(pair, (head.number, reserves));
+                }
+                reserves
+            }
+        };
+        
+        // token0 is the lower address
+        if token_in < token_out {
+            Ok((reserve0, reserve1))
+        } else {
+            Ok((reserve1, reserve0))
+        }
+    }
+
+    async fn path_reserves(&self, path: &[Address]) -> Result<Vec<(U256, U256)>> {
+        let mut reserves = Vec::with_capacity(path.len().saturating_sub(1));
+        for hop in path.windows(2) {
+            reserves.push(self.reserves(hop[0], hop[1]).await?);
+        }
+        Ok(reserves)
+    }
+
+    async fn get_amounts_out(&self, amount_in: U256, path: Vec<Address>) -> Result<Vec<U256>> {
+        let reserves = self.path_reserves(&path).await?;
+        let amounts = amounts_out(amount_in, &reserves, self.params.fee_bps());
+        
+        if self.config.verify_quotes {
+            let router = UniswapV2Router::new(self.params.router, self.provider.clone());
+            self.verify_quote(&amounts, router.get_amounts_out(amount_in, path).call().await);
+        }
+        Ok(amounts)
+    }
+
+    async fn get_amounts_in(&self, amount_out: U256, path: Vec<Address>) -> Result<Vec<U256>> {
+        let reserves = self.path_reserves(&path).await?;
+        let amounts = amounts_in(amount_out, &reserves, self.params.fee_bps())?;
+        
+        if self.config.verify_quotes {
+            let router = UniswapV2Router::new(self.params.router, self.provider.clone());
+            self.verify_quote(&amounts, router.get_amounts_in(amount_out, path).call().await);
+        }
+        Ok(amounts)
+    }
+
+    fn verify_quote<E: std::fmt::Display>(&self, local: &[U256], quoted: std::result::Result<Vec<U256>, E>) {
+        match quoted {
+            Ok(quoted) if quoted != local => {
+                tracing::warn!("{} local quote {:?} differs from router quote {:?}", self.params.name, local, quoted);
+            },
+            Ok(_) => {},
+            Err(e) => tracing::debug!("{} router cross-check failed: {}", self.params.name, e),
+        }
+    }
+
+    async fn estimate_swap_gas(&self, pair: &TradingPair, amount: Decimal) -> Result<U256> {
+        let client = self.signer.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("{} connector is in read-only mode: no wallet configured", self.params.name))?;
+        
+        let base_address = self.get_token_address(&pair.base)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.base))?;
+        let quote_address = self.get_token_address(&pair.quote)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.quote))?;
+        
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let amount_in = utils::decimal_to_u256(amount, base_decimals)?;
+        let deadline = U256::from(Utc::now().timestamp() as u64 + self.config.swap_deadline_seconds);
+        
+        let router = UniswapV2Router::new(self.params.router, client.clone());
+        
+        let gas = router
+            .swap_exact_tokens_for_tokens(amount_in, U256::zero(), self.swap_path(base_address, quote_address), client.address(), deadline)
+            .estimate_gas()
+            .await?;
+        Ok(gas)
+    }
+
+    async fn native_to_quote(&self, amount: Decimal, pair: &TradingPair) -> Result<Decimal> {
+        self.native_to_token(amount, &pair.quote).await
+    }
+
+    async fn native_to_token(&self, amount: Decimal, symbol: &str) -> Result<Decimal> {
+        let native_address = self.get_token_address(&self.params.wrapped_native_symbol)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", self.params.wrapped_native_symbol))?;
+        let token_address = self.get_token_address(symbol)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", symbol))?;
+        
+        if token_address == native_address {
+            return Ok(amount);
+        }
+        
+        let token_decimals = self.get_token_decimals(token_address).await?;
+        let amounts_out = self.get_amounts_out(U256::exp10(18), self.swap_path(native_address, token_address)).await?;
+        let native_price = utils::u256_to_decimal(amounts_out.last().copied().unwrap_or_default(), token_decimals)?;
+        
+        Ok(amount * native_price)
+    }
+
+    fn apply_slippage(&self, amount: U256) -> U256 {
+        let slippage_bps = utils::decimal_to_u256(self.max_slippage * Decimal::from(10_000), 0).unwrap_or_default();
+        let bps = U256::from(10_000);
+        
+        amount * (bps - slippage_bps.min(bps)) / bps
+    }
+
+    async fn execute_swap(&self, pair: &TradingPair, side: TradeSide, amount: OrderAmount, price: Option<Decimal>) -> Result<Trade> {
+        let client = self.signer.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("{} connector is in read-only mode: no wallet configured", self.params.name))?;
+        
+        let base_address = self.get_token_address(&pair.base)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.base))?;
+        let quote_address = self.get_token_address(&pair.quote)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.quote))?;
+        
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        
+        let input_token = match side {
+            TradeSide::Buy => quote_address,
+            TradeSide::Sell => base_address,
+        };
+        let output_token = match side {
+            TradeSide::Buy => base_address,
+            TradeSide::Sell => quote_address,
+        };
+        
+        let (path, amount_in) = match side {
+            TradeSide::Buy => {
+                let path = self.swap_path(quote_address, base_address);
+                // A quote-sized buy is an exact-input swap of that amount; a base-sized one is quoted for its exact output
+                let amount_in = match (amount, price) {
+                    (OrderAmount::Quote(spend), _) => utils::decimal_to_u256(spend, quote_decimals)?,
+                    (OrderAmount::Base(amount), Some(price)) => utils::decimal_to_u256(amount * price, quote_decimals)?,
+                    (OrderAmount::Base(amount), None) => {
+                        let amount_out = utils::decimal_to_u256(amount, base_decimals)?;
+                        let amounts_in = self.get_amounts_in(amount_out, path.clone()).await?;
+                        *amounts_in.first()
+                            .ok_or_else(|| anyhow::anyhow!("Invalid amounts returned from {}", self.params.name))?
+                    }
+                };
+                (path, amount_in)
+            },
+            TradeSide::Sell => {
+                let OrderAmount::Base(amount) = amount else {
+                    anyhow::bail!("{} sells are sized in {}", self.params.name, pair.base);
+                };
+                (self.swap_path(base_address, quote_address), utils::decimal_to_u256(amount, base_decimals)?)
+            },
+        };
+        
+        let amounts_out = self.get_amounts_out(amount_in, path.clone()).await?;
+        let expected_out = *amounts_out.last()
+            .ok_or_else(|| anyhow::anyhow!("Invalid amounts returned from {}", self.params.name))?;
+        let transfer_tax = self.transfer_tax(&path);
+        let amount_out_min = self.apply_slippage(less_tax(expected_out, transfer_tax));
+        
+        // Priced before the approval so a fee above the cap aborts without sending anything
+        let fees = self.gas_oracle.fees().await?;
+        self.approvals.ensure(client, input_token, amount_in).await?;
+        
+        let deadline = U256::from(Utc::now().timestamp() as u64 + self.config.swap_deadline_seconds);
+        
+        let router = UniswapV2Router::new(self.params.router, client.clone());
+        
+        let tx_hash = if transfer_tax.is_zero() {
+            let call = router.swap_exact_tokens_for_tokens(amount_in, amount_out_min, path, client.address(), deadline);
+            self.send_swap(client, call, &fees).await?
+        } else {
+            let call = router.swap_exact_tokens_for_tokens_supporting_fee_on_transfer_tokens(amount_in, amount_out_min, path, client.address(), deadline);
+            self.send_swap(client, call, &fees).await?
+        };
+        
+        self.approvals.consume(input_token, amount_in);
+        
+        let (base_amount, quote_amount) = match side {
+            TradeSide::Buy => (expected_out, amount_in),
+            TradeSide::Sell => (amount_in, expected_out),
+        };
+        let base_amount = utils::u256_to_decimal(base_amount, base_decimals)?;
+        let quote_amount = utils::u256_to_decimal(quote_amount, quote_decimals)?;
+        // Until the receipt settles it, a quote-sized buy reports the base it was quoted for
+        let amount = match amount {
+            OrderAmount::Base(amount) => amount,
+            OrderAmount::Quote(_) => base_amount,
+        };
+        
+        tracing::info!("Submitted {} swap {} for {} {}", self.params.name, tx_hash, amount, pair.symbol);
+        
+        let trade = Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            side,
+            amount,
+            price: price.unwrap_or(quote_amount / base_amount),
+            fee_amount: Decimal::ZERO,
+            fee_asset: None,
+            status: TradeStatus::Pending,
+            created_at: Utc::now(),
+            executed_at: None,
+            order_id: Some(tx_hash.clone()),
+            client_order_id: None,
+            tx_hash: Some(tx_hash.clone()),
+            simulated: false,
+            failure_reason: None,
+        };
+        
+        self.submitted_swaps.lock().unwrap().insert(tx_hash, SubmittedSwap {
+            trade: trade.clone(),
+            input_token,
+            output_token,
+        });
+        
+        Ok(trade)
+    }
+
+    async fn send_swap<D: ethers::abi::Detokenize>(&self, client: &SignerClient, mut call: ContractCall<SignerClient, D>, fees: &GasFees) -> Result<String> {
+        if let Some(gas_limit) = self.gas_limit {
+            call = call.gas(gas_limit);
+        }
+        fees.apply(&mut call.tx);
+        simulate::simulate(client, &call.tx).await?;
+        let pending_tx = call.send().await?;
+        Ok(format!("{:?}", pending_tx.tx_hash()))
+    }
+
+    // Largest tax of the tokens along the path, zero when none of them charges one
+    fn transfer_tax(&self, path: &[Address]) -> Decimal {
+        let taxes = self.transfer_taxes.lock().unwrap();
+        path.iter().filter_map(|token| taxes.get(token)).copied().max().unwrap_or(Decimal::ZERO)
+    }
+
+    async fn proxy_implementation(&self, token: Address) -> Result<Option<Address>> {
+        let slot = self.provider.get_storage_at(token, H256::from_str(EIP1967_IMPLEMENTATION_SLOT)?, None).await?;
+        let implementation = Address::from_slice(&slot.as_bytes()[12..]);
+        Ok((!implementation.is_zero()).then_some(implementation))
+    }
+
+    // One eth_call into Multicall3 with a spoofed native balance for the caller: buys `pair.base` with native
+    // through the pair's own route, then sells half the quoted amount back to wrapped native. Multicall3 holds
+    // stray tokens people sent it, so what arrived is read as the change in its balance around each swap
+    async fn probe_round_trip(&self, pair: &TradingPair, native_amount: Decimal) -> Result<TokenProbe> {
+        let wrapped = self.get_token_address(&self.params.wrapped_native_symbol)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", self.params.wrapped_native_symbol))?;
+        let base = self.get_token_address(&pair.base)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.base))?;
+        let quote = self.get_token_address(&pair.quote)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.quote))?;
+        
+        let implementation = self.proxy_implementation(base).await?.map(|address| format!("{:?}", address));
+        if base == wrapped {
+            return Ok(TokenProbe { implementation, ..TokenProbe::default() });
+        }
+        
+        // Starts at the last wrapped native hop of the traded route, or puts one in front of it
+        let route = self.swap_path(quote, base);
+        let buy_path: Vec<Address> = match route.iter().rposition(|token| *token == wrapped) {
+            Some(start) => route[start..].to_vec(),
+            None => std::iter::once(wrapped).chain(route).collect(),
+        };
+        let sell_path: Vec<Address> = buy_path.iter().rev().copied().collect();
+        
+        let value = utils::decimal_to_u256(native_amount, 18)?;
+        let router = UniswapV2Router::new(self.params.router, self.provider.clone());
+        let quoted = router.get_amounts_out(value, buy_path.clone()).call().await?;
+        let expected_bought = quoted.last().copied().unwrap_or_default();
+        let sell_amount = expected_bought / 2;
+        let deadline = U256::from(Utc::now().timestamp() as u64 + self.config.swap_deadline_seconds);
+        
+        let holder = MULTICALL_ADDRESS;
+        let token = ERC20::new(base, self.provider.clone());
+        let wrapped_token = ERC20::new(wrapped, self.provider.clone());
+        let calls = vec![
+            (base, U256::zero(), token.balance_of(holder).calldata()),
+            (self.params.router, value, router.swap_exact_eth_for_tokens_supporting_fee_on_transfer_tokens(U256::zero(), buy_path, holder, deadline).calldata()),
+            (base, U256::zero(), token.balance_of(holder).calldata()),
+            (base, U256::zero(), token.approve(self.params.router, sell_amount).calldata()),
+            (self.params.router, U256::zero(), router.get_amounts_out(sell_amount, sell_path.clone()).calldata()),
+            (wrapped, U256::zero(), wrapped_token.balance_of(holder).calldata()),
+            (self.params.router, U256::zero(), router.swap_exact_tokens_for_tokens_supporting_fee_on_transfer_tokens(sell_amount, U256::zero(), sell_path, holder, deadline).calldata()),
+            (wrapped, U256::zero(), wrapped_token.balance_of(holder).calldata()),
+        ];
+        let calls = calls.into_iter()
+            .map(|(target, value, data)| Ok(Call3Value {
+                target,
+                allow_failure: true,
+                value,
+                call_data: data.ok_or_else(|| anyhow::anyhow!("Failed to encode {} token probe", self.params.name))?,
+            }))
+            .collect::<Result<Vec<_>>>()?;
+        
+        let caller = self.wallet.as_ref().map(|wallet| wallet.address()).unwrap_or_default();
+        let multicall = Multicall3::new(MULTICALL_ADDRESS, self.provider.clone());
+        let results = multicall.aggregate_3_value(calls).value(value).from(caller)
+            .call_raw()
+            .state(&spoof::balance(caller, value * 2))
+            .await?;
+        if results.len() != 8 {
+            anyhow::bail!("{} token probe returned {} results", self.params.name, results.len());
+        }
+        
+        let amount = |index: usize| U256::decode(&results[index].return_data);
+        let revert = |index: usize| (!results[index].success)
+            .then(|| simulate::decode_revert(&results[index].return_data).to_string());
+        
+        let mut probe = TokenProbe { implementation, buy_revert: revert(1), ..TokenProbe::default() };
+        if probe.buy_revert.is_some() {
+            return Ok(probe);
+        }
+        probe.buy_tax = shortfall(expected_bought, amount(2)?.saturating_sub(amount(0)?));
+        
+        // Nothing was sold when the buy tax left less than half the quoted amount, so the sell is not judged then
+        let sold = probe.buy_tax < Decimal::new(5, 1);
+        probe.sell_revert = revert(6).filter(|_| sold);
+        if !results[6].success {
+            return Ok(probe);
+        }
+        let expected_sold = Vec::<U256>::decode(&results[4].return_data)?.last().copied().unwrap_or_default();
+        probe.sell_tax = shortfall(expected_sold, amount(7)?.saturating_sub(amount(5)?));
+        Ok(probe)
+    }
+
+    async fn recover_submitted_swap(&self, tx_hash: H256) -> Result<SubmittedSwap> {
+        let tx = self.provider.get_transaction(tx_hash).await?
+            .ok_or_else(|| anyhow::anyhow!("Transaction not found: {:?}", tx_hash))?;
+        
+        let path = SwapExactTokensForTokensCall::decode(&tx.input).map(|call| call.path)
+            .or_else(|_| SwapExactTokensForTokensSupportingFeeOnTransferTokensCall::decode(&tx.input).map(|call| call.path))
+            .map_err(|_| anyhow::anyhow!("Transaction {:?} is not a {} swap", tx_hash, self.params.name))?;
+        
+        let input_token = *path.first()
+            .ok_or_else(|| anyhow::anyhow!("Empty swap path in {:?}", tx_hash))?;
+        let output_token = *path.last()
+            .ok_or_else(|| anyhow::anyhow!("Empty swap path in {:?}", tx_hash))?;
+        
+        let (pair, side) = self.config.trading_pairs.iter()
+            .filter_map(|symbol| self.parse_trading_pair(symbol))
+            .find_map(|pair| {
+                let base = self.get_token_address(&pair.base)?;
+                let quote = self.get_token_address(&pair.quote)?;
+                if input_token == base && output_token == quote {
+                    Some((pair, TradeSide::Sell))
+                } else if input_token == quote && output_token == base {
+                    Some((pair, TradeSide::Buy))
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| anyhow::anyhow!("Swap {:?} does not match a configured pair", tx_hash))?;
+        
+        let trade = Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            pair,
+            side,
+            amount: Decimal::ZERO,
+            price: Decimal::ZERO,
+            fee_amount: Decimal::ZERO,
+            fee_asset: None,
+            status: TradeStatus::Pending,
+            created_at: Utc::now(),
+            executed_at: None,
+            order_id: Some(format!("{:?}", tx_hash)),
+            client_order_id: None,
+            tx_hash: Some(format!("{:?}", tx_hash)),
+            simulated: false,
+            failure_reason: None,
+        };
+        
+        Ok(SubmittedSwap {
+            trade,
+            input_token,
+            output_token,
+        })
+    }
+}
+
+#[async_trait]
+impl Exchange for UniV2StyleExchange {
+    fn name(&self) -> &str {
+        &self.params.name
+    }
+
+    fn chain_id(&self) -> Option<u64> {
+        Some(self.params.chain_id)
+    }
+
+    fn watch_chain_head(&self, heads: HeadReceiver) {
+        self.block_prices.attach(heads);
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        if let Some(price) = self.block_prices.get(&pair.symbol) {
+            return Ok(price);
+        }
+        let head = self.block_prices.head();
+        
+        let base_address = self.get_token_address(&pair.base)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.base))?;
+        let quote_address = self.get_token_address(&pair.quote)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.quote))?;
+        
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        
+        let one_unit = U256::exp10(base_decimals as usize);
+        
+        let path = self.swap_path(base_address, quote_address);
+        let (amounts_out, quoted_at) = tokio::try_join!(
+            self.get_amounts_out(one_unit, path.clone()),
+            blocks::quote_time(head, &*self.provider).err_into::<anyhow::Error>(),
+        )?;
+        
+        if amounts_out.len() < 2 {
+            return Err(anyhow::anyhow!("Invalid amounts returned from {}", self.params.name).into());
+        }
+        
+        let quote_amount = amounts_out[amounts_out.len() - 1];
+        let ask_price = utils::u256_to_decimal(quote_amount, quote_decimals)?;
+        
+        let bid_price = ask_price * (Decimal::ONE - self.params.fee);
+        
+        Ok(self.block_prices.store(head, Price {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bid: bid_price,
+            ask: ask_price,
+            timestamp: quoted_at,
+            volume_24h: None,
+            fetch_latency: None,
+        }))
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        let base_address = self.get_token_address(&pair.base)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.base))?;
+        let quote_address = self.get_token_address(&pair.quote)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.quote))?;
+        
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        let path = self.swap_path(base_address, quote_address);
+        
+        // One reserves read prices every level
+        let reserves = self.path_reserves(&path).await?;
+        let fee_bps = self.params.fee_bps();
+        
+        let mut asks = Vec::new();
+        let mut bids = Vec::new();
+        
+        for i in 1..=depth {
+            let quantity = Decimal::from(i) * Decimal::from(100);
+            let quantity_wei = utils::decimal_to_u256(quantity, base_decimals)?;
+            
+            let amounts = amounts_out(quantity_wei, &reserves, fee_bps);
+            let quote_amount = utils::u256_to_decimal(amounts[amounts.len() - 1], quote_decimals)?;
+            if quote_amount.is_zero() {
+                break;
+            }
+            let price = quote_amount / quantity;
+            
+            asks.push(OrderBookLevel {
+                price,
+                quantity,
+            });
+            
+            bids.push(OrderBookLevel {
+                price: price * (Decimal::ONE - self.params.fee),
+                quantity,
+            });
+        }
+        
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bids,
+            asks,
+            timestamp: Utc::now(),
+        })
+    }
+
+    // The pool fee is left out; it is charged as the taker fee
+    async fn get_pool_reserves(&self, pair: &TradingPair) -> BotResult<Option<PoolReserves>> {
+        let base_address = self.get_token_address(&pair.base)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.base))?;
+        let quote_address = self.get_token_address(&pair.quote)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.quote))?;
+        
+        if self.swap_path(base_address, quote_address).len() != 2 {
+            return Ok(None);
+        }
+        
+        let (base_reserve, quote_reserve) = self.reserves(base_address, quote_address).await?;
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        
+        Ok(Some(PoolReserves {
+            base: utils::u256_to_decimal(base_reserve, base_decimals)?,
+            quote: utils::u256_to_decimal(quote_reserve, quote_decimals)?,
+        }))
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        let mut balances = HashMap::new();
+        
+        if let Some(wallet) = &self.wallet {
+            let mut tokens: Vec<(String, Address)> = Vec::new();
+            for pair in self.config.trading_pairs.iter().filter_map(|pair| self.parse_trading_pair(pair)) {
+                for symbol in [pair.base, pair.quote] {
+                    if let Some(token_address) = self.get_token_address(&symbol) {
+                        if !tokens.iter().any(|(known, _)| *known == symbol) {
+                            tokens.push((symbol, token_address));
+                        }
+                    }
+                }
+            }
+            
+            let addresses: Vec<Address> = tokens.iter().map(|(_, address)| *address).collect();
+            let snapshot = self.tokens.snapshot(wallet.address(), &addresses, Some(self.params.router)).await?;
+            self.approvals.prime(&snapshot.allowances);
+            
+            let native_balance = utils::u256_to_decimal(snapshot.native, 18)?; // native tokens have 18 decimals
+            if native_balance > Decimal::ZERO {
+                balances.insert(self.params.native_symbol.clone(), Balance {
+                    asset: self.params.native_symbol.clone(),
+                    free: native_balance,
+                    locked: Decimal::ZERO,
+                    total: native_balance,
+                    usd_value: Decimal::ZERO,
+                });
+            }
+            
+            for (symbol, token_address) in tokens {
+                if let Some(on_chain) = snapshot.symbols.get(&token_address) {
+                    if !on_chain.eq_ignore_ascii_case(&symbol) {
+                        tracing::debug!("{} token {:?} is configured as {} but reports symbol {}", self.params.name, token_address, symbol, on_chain);
+                    }
+                }
+                let (Some(balance), Some(decimals)) = (snapshot.balances.get(&token_address), snapshot.decimals.get(&token_address)) else {
+                    continue;
+                };
+                let balance_decimal = utils::u256_to_decimal(*balance, *decimals)?;
+                
+                if balance_decimal > Decimal::ZERO {
+                    balances.insert(symbol.clone(), Balance {
+                        asset: symbol,
+                        free: balance_decimal,
+                        locked: Decimal::ZERO,
+                        total: balance_decimal,
+                        usd_value: Decimal::ZERO,
+                    });
+                }
+            }
+        }
+        
+        Ok(balances)
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.execute_swap(pair, TradeSide::Buy, OrderAmount::Base(amount), price).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.execute_swap(pair, TradeSide::Sell, OrderAmount::Base(amount), price).await?)
+    }
+
+    fn supports_quote_orders(&self) -> bool {
+        true
+    }
+
+    async fn place_market_buy_quote(&self, pair: &TradingPair, quote_amount: Decimal) -> BotResult<Trade> {
+        Ok(self.execute_swap(pair, TradeSide::Buy, OrderAmount::Quote(quote_amount), None).await?)
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        let tx_hash: H256 = order_id.parse()
+            .map_err(|_| anyhow::anyhow!("Invalid transaction hash: {}", order_id))?;
+        
+        let known_swap = self.submitted_swaps.lock().unwrap().get(order_id).cloned();
+        let swap = match known_swap {
+            Some(swap) => swap,
+            None => self.recover_submitted_swap(tx_hash).await?,
+        };
+        let mut trade = swap.trade.clone();
+        
+        let receipt = match self.provider.get_transaction_receipt(tx_hash).await? {
+            Some(receipt) => receipt,
+            None => return Ok(trade),
+        };
+        
+        let block_number = receipt.block_number
+            .ok_or_else(|| anyhow::anyhow!("Receipt for {} has no block number", order_id))?;
+        let current_block = self.provider.get_block_number().await?;
+        let confirmations = current_block.saturating_sub(block_number).as_u64() + 1;
+        
+        if confirmations < self.config.confirmations {
+            return Ok(trade);
+        }
+        
+        let executed_at = self.provider.get_block(block_number).await?
+            .and_then(|block| chrono::DateTime::from_timestamp(block.timestamp.as_u64() as i64, 0))
+            .unwrap_or_else(Utc::now);
+        trade.executed_at = Some(executed_at);
+        
+        if receipt.status != Some(U64::from(1)) {
+            trade.status = TradeStatus::Failed;
+            trade.failure_reason = simulate::replay_revert_reason(&self.provider, receipt.transaction_hash).await;
+            tracing::warn!("Swap {} reverted: {}", order_id, trade.failure_reason.as_deref().unwrap_or("unknown reason"));
+            self.submitted_swaps.lock().unwrap().remove(order_id);
+            return Ok(trade);
+        }
+        
+        let owner = receipt.from;
+        let amount_in = erc20::sum_transfers(&receipt.logs, swap.input_token, Some(owner), None);
+        let amount_out = erc20::sum_transfers(&receipt.logs, swap.output_token, None, Some(owner));
+        
+        let input_decimals = self.get_token_decimals(swap.input_token).await?;
+        let output_decimals = self.get_token_decimals(swap.output_token).await?;
+        let amount_in = utils::u256_to_decimal(amount_in, input_decimals)?;
+        let amount_out = utils::u256_to_decimal(amount_out, output_decimals)?;
+        
+        let (base_amount, quote_amount) = match trade.side {
+            TradeSide::Buy => (amount_out, amount_in),
+            TradeSide::Sell => (amount_in, amount_out),
+        };
+        
+        if base_amount > Decimal::ZERO {
+            trade.amount = base_amount;
+            trade.price = quote_amount / base_amount;
+        }
+        
+        // The pool fee is already reflected in the realized price, so only gas is recorded as a fee
+        if let (Some(gas_used), Some(gas_price)) = (receipt.gas_used, receipt.effective_gas_price) {
+            let gas_native = utils::u256_to_decimal(gas_used * gas_price, 18)?;
+            trade.fee_amount = self.native_to_quote(gas_native, &trade.pair).await?;
+            trade.fee_asset = Some(trade.pair.quote.clone());
+        }
+        trade.status = TradeStatus::Executed;
+        
+        self.submitted_swaps.lock().unwrap().remove(order_id);
+        
+        Ok(trade)
+    }
+
+    async fn cancel_order(&self, order_id: &str) -> BotResult<()> {
+        Err(anyhow::anyhow!("{} transactions cannot be cancelled", self.params.name).into())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.get_token_address(&pair.base).is_some() && 
+        self.get_token_address(&pair.quote).is_some()
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        let pairs = self.config.trading_pairs.iter()
+            .filter_map(|symbol| self.parse_trading_pair(symbol))
+            .filter(|pair| self.supports_pair(pair))
+            .collect();
+        
+        Ok(pairs)
+    }
+
+    async fn get_trading_fees(&self, _pair: &TradingPair) -> BotResult<TradingFees> {
+        Ok(TradingFees {
+            maker_fee: self.params.fee,
+            taker_fee: self.params.fee,
+        })
+    }
+
+    async fn get_execution_cost(&self, pair: &TradingPair, amount: Decimal) -> BotResult<Decimal> {
+        let gas_units = match self.estimate_swap_gas(pair, amount).await {
+            Ok(gas_units) => gas_units,
+            Err(e) => {
+                tracing::debug!("Gas estimation failed for {}, using default: {}", pair.symbol, e);
+                U256::from(DEFAULT_SWAP_GAS)
+            }
+        };
+        let gas_units = match self.gas_limit {
+            Some(gas_limit) => gas_units.min(gas_limit),
+            None => gas_units,
+        };
+        
+        let gas_price = self.gas_price().await?;
+        let cost_native = utils::u256_to_decimal(gas_units * gas_price, 18)?;
+        
+        Ok(self.native_to_quote(cost_native, pair).await?)
+    }
+
+    // A withdrawal from the wallet costs one token transfer
+    async fn get_withdrawal_fee(&self, asset: &str) -> BotResult<Decimal> {
+        let gas_price = self.gas_price().await?;
+        let cost_native = utils::u256_to_decimal(U256::from(erc20::TRANSFER_GAS) * gas_price, 18)?;
+        
+        Ok(self.native_to_token(cost_native, asset).await?)
+    }
+
+    // Fee-on-transfer tokens are remembered so swaps through them switch router variants
+    async fn probe_token(&self, pair: &TradingPair, native_amount: Decimal) -> BotResult<Option<TokenProbe>> {
+        let probe = self.probe_round_trip(pair, native_amount).await?;
+        let tax = probe.buy_tax.max(probe.sell_tax);
+        if let Some(base) = self.get_token_address(&pair.base).filter(|_| !tax.is_zero()) {
+            self.transfer_taxes.lock().unwrap().insert(base, tax);
+        }
+        Ok(Some(probe))
+    }
+
+    // Tokens are held by the connector's own wallet
+    async fn get_deposit_address(&self, asset: &str) -> BotResult<String> {
+        let wallet = self.wallet.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("{} has no wallet configured", self.name()))?;
+        self.get_token_address(asset)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", asset))?;
+        Ok(format!("{:?}", wallet.address()))
+    }
+
+    fn atomic_venue(&self) -> Option<&dyn AtomicSwapVenue> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl AtomicSwapVenue for UniV2StyleExchange {
+    fn token_address(&self, symbol: &str) -> Option<Address> {
+        self.get_token_address(symbol)
+    }
+
+    async fn token_decimals(&self, token: Address) -> Result<u8> {
+        self.get_token_decimals(token).await
+    }
+
+    async fn encode_swap(&self, token_in: Address, token_out: Address, amount_in: U256, recipient: Address) -> Result<EncodedSwap> {
+        let path = self.swap_path(token_in, token_out);
+        let amounts_out = self.get_amounts_out(amount_in, path.clone()).await?;
+        let expected_out = *amounts_out.last()
+            .ok_or_else(|| anyhow::anyhow!("Invalid amounts returned from {}", self.params.name))?;
+        let transfer_tax = self.transfer_tax(&path);
+        let min_amount_out = self.apply_slippage(less_tax(expected_out, transfer_tax));
+        
+        let deadline = U256::from(Utc::now().timestamp() as u64 + self.config.swap_deadline_seconds);
+        let router = UniswapV2Router::new(self.params.router, self.provider.clone());
+        let data = if transfer_tax.is_zero() {
+            router.swap_exact_tokens_for_tokens(amount_in, min_amount_out, path, recipient, deadline).calldata()
+        } else {
+            router.swap_exact_tokens_for_tokens_supporting_fee_on_transfer_tokens(amount_in, min_amount_out, path, recipient, deadline).calldata()
+        }
+            .ok_or_else(|| anyhow::anyhow!("Failed to encode {} swap", self.params.name))?;
+        
+        Ok(EncodedSwap {
+            step: SwapStep {
+                target: self.params.router,
+                token_in,
+                token_out,
+                amount_in,
+                min_amount_out,
+                data,
+            },
+            expected_out,
+        })
+    }
+}
+
+impl UniV2StyleExchange {
+    fn parse_trading_pair(&self, pair_str: &str) -> Option<TradingPair> {
+        let parts: Vec<&str> = pair_str.split('/').collect();
+        if parts.len() == 2 {
+            Some(TradingPair::new(parts[0], parts[1]))
+        } else {
+            None
+        }
+    }
+}
\ No newline at end of file
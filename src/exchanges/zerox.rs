@@ -0,0 +1,509 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use ethers::prelude::*;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::blockchain::failover::{self, RpcProvider};
+use crate::blockchain::gas::GasOracle;
+use crate::blockchain::multicall::TokenReader;
+use crate::blockchain::simulate;
+use crate::config::{ChainConfig, ExchangeConfig};
+use crate::errors::{BotError, BotResult};
+use crate::exchanges::erc20::{self, SignerClient, TokenApprovals, ERC20};
+use crate::exchanges::{synthesize_ladder, Exchange, TradingFees};
+use crate::models::{Balance, OrderBook, Price, Trade, TradingPair, TradeSide, TradeStatus};
+use crate::utils::{self, RateLimiter};
+
+// Used when the quote response does not include a gas estimate
+const DEFAULT_SWAP_GAS: u64 = 250_000;
+
+pub struct ZeroExExchange {
+    config: ExchangeConfig,
+    client: Client,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    chain_id: u64,
+    native_symbol: &'static str,
+    wrapped_native_symbol: &'static str,
+    provider: Arc<RpcProvider>,
+    tokens: TokenReader,
+    wallet: Option<LocalWallet>,
+    signer: Option<Arc<SignerClient>>,
+    gas_oracle: Arc<GasOracle>,
+    max_slippage: Decimal,
+    // Quotes name their own allowance target, so approvals are tracked per target
+    approvals: Mutex<HashMap<Address, Arc<TokenApprovals>>>,
+    submitted_swaps: Mutex<HashMap<String, SubmittedSwap>>,
+}
+
+#[derive(Debug, Clone)]
+struct SubmittedSwap {
+    trade: Trade,
+    input_token: Address,
+    output_token: Address,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QuoteResponse {
+    to: Address,
+    data: Bytes,
+    value: String,
+    #[serde(default)]
+    gas: Option<String>,
+    buy_amount: String,
+    sell_amount: String,
+    allowance_target: Address,
+}
+
+enum QuoteAmount {
+    Sell(U256),
+    Buy(U256),
+}
+
+impl ZeroExExchange {
+    // The 0x API key comes from the exchange entry; chain id, RPC, signer and gas from the chain it trades on
+    pub fn new(config: ExchangeConfig, chain: &ChainConfig, max_slippage: Decimal) -> Result<Self> {
+        let chain_id = chain.chain_id;
+        if let Some(configured) = config.chain_id.filter(|configured| *configured != chain_id) {
+            anyhow::bail!("0x is configured for chain id {}, but the chain config is {}", configured, chain_id);
+        }
+        let (native_symbol, wrapped_native_symbol) = erc20::native_symbols(chain_id)
+            .ok_or_else(|| anyhow::anyhow!("Unsupported 0x chain id: {}", chain_id))?;
+        
+        let provider = Arc::new(failover::rpc_provider(&chain.rpc_urls, chain.max_block_lag, &chain.http())?);
+        
+        let wallet = if !chain.private_key.is_empty() {
+            Some(chain.private_key.parse::<LocalWallet>()?.with_chain_id(chain_id))
+        } else {
+            None
+        };
+        
+        let signer = wallet.clone()
+            .map(|wallet| Arc::new(SignerMiddleware::new(provider.clone(), wallet)));
+        let gas_oracle = Arc::new(GasOracle::new(provider.clone(), chain.gas_policy()));
+        
+        Ok(Self {
+            rate_limiter: utils::request_rate_limiter("0x", config.rate_limit, None),
+            client: utils::http_client_with(&config.http())?,
+            config,
+            chain_id,
+            native_symbol,
+            wrapped_native_symbol,
+            tokens: TokenReader::new(provider.clone()),
+            provider,
+            wallet,
+            signer,
+            gas_oracle,
+            max_slippage,
+            approvals: Mutex::new(HashMap::new()),
+            submitted_swaps: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn get_token_address(&self, symbol: &str) -> Option<Address> {
+        erc20::token_address(self.chain_id, symbol)
+    }
+
+    fn pair_addresses(&self, pair: &TradingPair) -> Result<(Address, Address)> {
+        let base_address = self.get_token_address(&pair.base)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.base))?;
+        let quote_address = self.get_token_address(&pair.quote)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.quote))?;
+        Ok((base_address, quote_address))
+    }
+
+    async fn get_token_decimals(&self, token_address: Address) -> Result<u8> {
+        Ok(self.tokens.decimals(token_address).await?)
+    }
+
+    fn approvals_for(&self, allowance_target: Address) -> Arc<TokenApprovals> {
+        self.approvals.lock().unwrap()
+            .entry(allowance_target)
+            .or_insert_with(|| Arc::new(
+                TokenApprovals::new(allowance_target, self.config.infinite_approval)
+                    .with_gas_oracle(self.gas_oracle.clone())
+            ))
+            .clone()
+    }
+
+    async fn quote(&self, sell_token: Address, buy_token: Address, amount: QuoteAmount) -> Result<QuoteResponse> {
+        let mut params = vec![
+            ("sellToken", format!("{:?}", sell_token)),
+            ("buyToken", format!("{:?}", buy_token)),
+            ("slippagePercentage", self.max_slippage.normalize().to_string()),
+        ];
+        match amount {
+            QuoteAmount::Sell(amount) => params.push(("sellAmount", amount.to_string())),
+            QuoteAmount::Buy(amount) => params.push(("buyAmount", amount.to_string())),
+        }
+        if let Some(wallet) = &self.wallet {
+            params.push(("takerAddress", format!("{:?}", wallet.address())));
+        }
+        
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(1).await;
+        }
+        
+        let url = format!("{}/swap/v1/quote", self.config.api_url.trim_end_matches('/'));
+        let response = self.client
+            .get(&url)
+            .header("0x-api-key", &self.config.api_key)
+            .query(&params)
+            .send()
+            .await?;
+        
+        if !response.status().is_success() {
+            return Err(BotError::from_response("0x", response).await.into());
+        }
+        
+        Ok(response.json().await?)
+    }
+
+    // Average sell and buy prices for a base quantity
+    async fn quote_prices(&self, pair: &TradingPair, quantity: Decimal) -> Result<(Decimal, Decimal)> {
+        let (base_address, quote_address) = self.pair_addresses(pair)?;
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        
+        let base_units = utils::decimal_to_u256(quantity, base_decimals)?;
+        let (sell_quote, buy_quote) = tokio::try_join!(
+            self.quote(base_address, quote_address, QuoteAmount::Sell(base_units)),
+            self.quote(quote_address, base_address, QuoteAmount::Buy(base_units)),
+        )?;
+        
+        let proceeds = utils::u256_to_decimal(U256::from_dec_str(&sell_quote.buy_amount)?, quote_decimals)?;
+        let cost = utils::u256_to_decimal(U256::from_dec_str(&buy_quote.sell_amount)?, quote_decimals)?;
+        
+        Ok((proceeds / quantity, cost / quantity))
+    }
+
+    async fn native_to_quote(&self, amount: Decimal, pair: &TradingPair) -> Result<Decimal> {
+        let native_address = self.get_token_address(self.wrapped_native_symbol)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", self.wrapped_native_symbol))?;
+        let quote_address = self.get_token_address(&pair.quote)
+            .ok_or_else(|| anyhow::anyhow!("Token not supported: {}", pair.quote))?;
+        
+        if quote_address == native_address {
+            return Ok(amount);
+        }
+        
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        let quote = self.quote(native_address, quote_address, QuoteAmount::Sell(U256::exp10(18))).await?;
+        let native_price = utils::u256_to_decimal(U256::from_dec_str(&quote.buy_amount)?, quote_decimals)?;
+        
+        Ok(amount * native_price)
+    }
+
+    async fn execute_swap(&self, pair: &TradingPair, side: TradeSide, amount: Decimal, price: Option<Decimal>) -> Result<Trade> {
+        let client = self.signer.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("0x connector is in read-only mode: no wallet configured"))?;
+        
+        let (base_address, quote_address) = self.pair_addresses(pair)?;
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let quote_decimals = self.get_token_decimals(quote_address).await?;
+        let base_units = utils::decimal_to_u256(amount, base_decimals)?;
+        
+        let (input_token, output_token, quote) = match side {
+            TradeSide::Buy => (quote_address, base_address, self.quote(quote_address, base_address, QuoteAmount::Buy(base_units)).await?),
+            TradeSide::Sell => (base_address, quote_address, self.quote(base_address, quote_address, QuoteAmount::Sell(base_units)).await?),
+        };
+        
+        let sell_amount = U256::from_dec_str(&quote.sell_amount)?;
+        let buy_amount = U256::from_dec_str(&quote.buy_amount)?;
+        let (base_amount, quote_amount) = match side {
+            TradeSide::Buy => (buy_amount, sell_amount),
+            TradeSide::Sell => (sell_amount, buy_amount),
+        };
+        let quoted_price = utils::u256_to_decimal(quote_amount, quote_decimals)?
+            / utils::u256_to_decimal(base_amount, base_decimals)?;
+        
+        // Firm quotes expire quickly, so reject one that has moved past the slippage budget
+        if let Some(price) = price {
+            let degraded = match side {
+                TradeSide::Buy => quoted_price > price * (Decimal::ONE + self.max_slippage),
+                TradeSide::Sell => quoted_price < price * (Decimal::ONE - self.max_slippage),
+            };
+            if degraded {
+                anyhow::bail!("0x quote for {} degraded to {} from {}", pair.symbol, quoted_price, price);
+            }
+        }
+        
+        let fees = self.gas_oracle.fees().await?;
+        self.approvals_for(quote.allowance_target).ensure(client, input_token, sell_amount).await?;
+        
+        let gas = match &quote.gas {
+            Some(gas) => U256::from_dec_str(gas)?,
+            None => U256::from(DEFAULT_SWAP_GAS),
+        };
+        let mut tx = TransactionRequest::new()
+            .to(quote.to)
+            .data(quote.data)
+            .value(U256::from_dec_str(&quote.value)?)
+            .gas(gas)
+            .into();
+        fees.apply(&mut tx);
+        simulate::simulate(client, &tx).await?;
+        
+        let pending_tx = client.send_transaction(tx, None).await?;
+        let tx_hash = format!("{:?}", pending_tx.tx_hash());
+        
+        self.approvals_for(quote.allowance_target).consume(input_token, sell_amount);
+        
+        tracing::info!("Submitted 0x swap {} for {} {}", tx_hash, amount, pair.symbol);
+        
+        let trade = Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            side,
+            amount,
+            price: quoted_price,
+            fee_amount: Decimal::ZERO,
+            fee_asset: None,
+            status: TradeStatus::Pending,
+            created_at: Utc::now(),
+            executed_at: None,
+            order_id: Some(tx_hash.clone()),
+            client_order_id: None,
+            tx_hash: Some(tx_hash.clone()),
+            simulated: false,
+            failure_reason: None,
+        };
+        
+        self.submitted_swaps.lock().unwrap().insert(tx_hash, SubmittedSwap {
+            trade: trade.clone(),
+            input_token,
+            output_token,
+        });
+        
+        Ok(trade)
+    }
+}
+
+#[async_trait]
+impl Exchange for ZeroExExchange {
+    fn name(&self) -> &str {
+        "0x"
+    }
+
+    fn chain_id(&self) -> Option<u64> {
+        Some(self.chain_id)
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        let (bid, ask) = self.quote_prices(pair, Decimal::ONE).await?;
+        
+        Ok(Price {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bid,
+            ask,
+            timestamp: Utc::now(),
+            volume_24h: None,
+            fetch_latency: None,
+        })
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        let (bids, asks) = synthesize_ladder(self.config.max_trade_amount, depth, |quantity| async move {
+            match self.quote_prices(pair, quantity).await {
+                Ok((bid, ask)) => Ok((Some(bid * quantity), Some(ask * quantity))),
+                Err(e) => {
+                    tracing::debug!("0x quote for {} {} failed: {}", quantity, pair.symbol, e);
+                    Ok((None, None))
+                }
+            }
+        }).await?;
+        
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: pair.clone(),
+            bids,
+            asks,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        let mut balances = HashMap::new();
+        
+        if let Some(wallet) = &self.wallet {
+            let native_balance = self.provider.get_balance(wallet.address(), None).await?;
+            let native_balance = utils::u256_to_decimal(native_balance, 18)?;
+            
+            if native_balance > Decimal::ZERO {
+                balances.insert(self.native_symbol.to_string(), Balance {
+                    asset: self.native_symbol.to_string(),
+                    free: native_balance,
+                    locked: Decimal::ZERO,
+                    total: native_balance,
+                    usd_value: Decimal::ZERO,
+                });
+            }
+            
+            for pair_str in &self.config.trading_pairs {
+                if let Some(pair) = self.parse_trading_pair(pair_str) {
+                    for symbol in [&pair.base, &pair.quote] {
+                        if let Some(token_address) = self.get_token_address(symbol) {
+                            let token = ERC20::new(token_address, self.provider.clone());
+                            if let Ok(balance) = token.balance_of(wallet.address()).call().await {
+                                let decimals = self.get_token_decimals(token_address).await?;
+                                let balance_decimal = utils::u256_to_decimal(balance, decimals)?;
+                                
+                                if balance_decimal > Decimal::ZERO {
+                                    balances.insert(symbol.clone(), Balance {
+                                        asset: symbol.clone(),
+                                        free: balance_decimal,
+                                        locked: Decimal::ZERO,
+                                        total: balance_decimal,
+                                        usd_value: Decimal::ZERO,
+                                    });
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        
+        Ok(balances)
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.execute_swap(pair, TradeSide::Buy, amount, price).await?)
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        Ok(self.execute_swap(pair, TradeSide::Sell, amount, price).await?)
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        let tx_hash: H256 = order_id.parse()
+            .map_err(|_| anyhow::anyhow!("Invalid transaction hash: {}", order_id))?;
+        
+        let swap = self.submitted_swaps.lock().unwrap().get(order_id).cloned()
+            .ok_or_else(|| anyhow::anyhow!("Unknown 0x swap: {}", order_id))?;
+        let mut trade = swap.trade.clone();
+        
+        let receipt = match self.provider.get_transaction_receipt(tx_hash).await? {
+            Some(receipt) => receipt,
+            None => return Ok(trade),
+        };
+        
+        let block_number = receipt.block_number
+            .ok_or_else(|| anyhow::anyhow!("Receipt for {} has no block number", order_id))?;
+        let current_block = self.provider.get_block_number().await?;
+        let confirmations = current_block.saturating_sub(block_number).as_u64() + 1;
+        
+        if confirmations < self.config.confirmations {
+            return Ok(trade);
+        }
+        
+        let executed_at = self.provider.get_block(block_number).await?
+            .and_then(|block| chrono::DateTime::from_timestamp(block.timestamp.as_u64() as i64, 0))
+            .unwrap_or_else(Utc::now);
+        trade.executed_at = Some(executed_at);
+        
+        if receipt.status != Some(U64::from(1)) {
+            trade.status = TradeStatus::Failed;
+            trade.failure_reason = simulate::replay_revert_reason(&self.provider, receipt.transaction_hash).await;
+            tracing::warn!("Swap {} reverted: {}", order_id, trade.failure_reason.as_deref().unwrap_or("unknown reason"));
+            self.submitted_swaps.lock().unwrap().remove(order_id);
+            return Ok(trade);
+        }
+        
+        let owner = receipt.from;
+        let amount_in = erc20::sum_transfers(&receipt.logs, swap.input_token, Some(owner), None);
+        let amount_out = erc20::sum_transfers(&receipt.logs, swap.output_token, None, Some(owner));
+        
+        let input_decimals = self.get_token_decimals(swap.input_token).await?;
+        let output_decimals = self.get_token_decimals(swap.output_token).await?;
+        let amount_in = utils::u256_to_decimal(amount_in, input_decimals)?;
+        let amount_out = utils::u256_to_decimal(amount_out, output_decimals)?;
+        
+        let (base_amount, quote_amount) = match trade.side {
+            TradeSide::Buy => (amount_out, amount_in),
+            TradeSide::Sell => (amount_in, amount_out),
+        };
+        
+        if base_amount > Decimal::ZERO {
+            trade.amount = base_amount;
+            trade.price = quote_amount / base_amount;
+        }
+        
+        // Liquidity source fees are already reflected in the realized price, so only gas is recorded
+        if let (Some(gas_used), Some(gas_price)) = (receipt.gas_used, receipt.effective_gas_price) {
+            let gas_native = utils::u256_to_decimal(gas_used * gas_price, 18)?;
+            trade.fee_amount = self.native_to_quote(gas_native, &trade.pair).await?;
+            trade.fee_asset = Some(trade.pair.quote.clone());
+        }
+        trade.status = TradeStatus::Executed;
+        
+        self.submitted_swaps.lock().unwrap().remove(order_id);
+        
+        Ok(trade)
+    }
+
+    async fn cancel_order(&self, _order_id: &str) -> BotResult<()> {
+        Err(anyhow::anyhow!("0x transactions cannot be cancelled").into())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.get_token_address(&pair.base).is_some() && 
+        self.get_token_address(&pair.quote).is_some()
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        let pairs = self.config.trading_pairs.iter()
+            .filter_map(|symbol| self.parse_trading_pair(symbol))
+            .filter(|pair| self.supports_pair(pair))
+            .collect();
+        
+        Ok(pairs)
+    }
+
+    async fn get_trading_fees(&self, _pair: &TradingPair) -> BotResult<TradingFees> {
+        // Quotes are net of liquidity source fees
+        Ok(TradingFees {
+            maker_fee: Decimal::ZERO,
+            taker_fee: Decimal::ZERO,
+        })
+    }
+
+    async fn get_execution_cost(&self, pair: &TradingPair, amount: Decimal) -> BotResult<Decimal> {
+        let (base_address, quote_address) = self.pair_addresses(pair)?;
+        let base_decimals = self.get_token_decimals(base_address).await?;
+        let amount_in = utils::decimal_to_u256(amount, base_decimals)?;
+        
+        let gas_units = match self.quote(base_address, quote_address, QuoteAmount::Sell(amount_in)).await {
+            Ok(QuoteResponse { gas: Some(gas), .. }) => U256::from_dec_str(&gas).map_err(anyhow::Error::from)?,
+            Ok(_) => U256::from(DEFAULT_SWAP_GAS),
+            Err(e) => {
+                tracing::debug!("Gas estimation failed for {}, using default: {}", pair.symbol, e);
+                U256::from(DEFAULT_SWAP_GAS)
+            }
+        };
+        
+        let gas_price = self.gas_oracle.fees().await?.expected_price();
+        let cost_native = utils::u256_to_decimal(gas_units * gas_price, 18)?;
+        
+        Ok(self.native_to_quote(cost_native, pair).await?)
+    }
+}
+
+impl ZeroExExchange {
+    fn parse_trading_pair(&self, pair_str: &str) -> Option<TradingPair> {
+        let parts: Vec<&str> = pair_str.split('/').collect();
+        if parts.len() == 2 {
+            Some(TradingPair::new(parts[0], parts[1]))
+        } else {
+            None
+        }
+    }
+}
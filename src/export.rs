@@ -0,0 +1,161 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::stream::{BoxStream, StreamExt};
+use serde::Serialize;
+use std::io::Write;
+
+use crate::database::{self, Database};
+use crate::models::{ArbitrageOpportunity, Price, Trade};
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum ExportKind {
+    Opportunities,
+    Trades,
+    Prices,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    // One JSON object per line
+    Json,
+}
+
+// A record with a fixed CSV column order; decimals are written as stored, never in scientific notation
+trait ExportRow: Serialize {
+    const COLUMNS: &'static [&'static str];
+    
+    fn fields(&self) -> Vec<String>;
+}
+
+impl ExportRow for ArbitrageOpportunity {
+    const COLUMNS: &'static [&'static str] = &[
+        "id", "timestamp", "pair", "buy_exchange", "sell_exchange", "buy_pair", "sell_pair",
+        "buy_price", "sell_price", "sell_quote_rate", "max_trade_size", "gross_profit", "buy_fee_cost",
+        "sell_fee_cost", "buy_fee_rate", "sell_fee_rate", "gas_cost", "transfer_cost", "profit_amount", "adjusted_profit_amount",
+        "marginal_profit", "profit_percentage", "status",
+    ];
+    
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.timestamp.to_rfc3339(),
+            self.pair.symbol.clone(),
+            self.buy_exchange.clone(),
+            self.sell_exchange.clone(),
+            self.buy_pair.symbol.clone(),
+            self.sell_pair.symbol.clone(),
+            self.buy_price.to_string(),
+            self.sell_price.to_string(),
+            self.sell_quote_rate.to_string(),
+            self.max_trade_size.to_string(),
+            self.gross_profit.to_string(),
+            self.buy_fee_cost.to_string(),
+            self.sell_fee_cost.to_string(),
+            self.buy_fee_rate.to_string(),
+            self.sell_fee_rate.to_string(),
+            self.gas_cost.to_string(),
+            self.transfer_cost.to_string(),
+            self.profit_amount.to_string(),
+            self.adjusted_profit_amount.to_string(),
+            self.marginal_profit.to_string(),
+            self.profit_percentage.to_string(),
+            database::status_to_str(&self.status).to_string(),
+        ]
+    }
+}
+
+// opportunity_id joins each trade to the opportunities export
+impl ExportRow for Trade {
+    const COLUMNS: &'static [&'static str] = &[
+        "id", "opportunity_id", "created_at", "executed_at", "exchange", "pair", "side", "amount",
+        "price", "fee_amount", "fee_asset", "status", "order_id", "client_order_id", "tx_hash", "simulated",
+        "failure_reason",
+    ];
+    
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.opportunity_id.to_string(),
+            self.created_at.to_rfc3339(),
+            self.executed_at.map(|time| time.to_rfc3339()).unwrap_or_default(),
+            self.exchange.clone(),
+            self.pair.symbol.clone(),
+            database::side_to_str(&self.side).to_string(),
+            self.amount.to_string(),
+            self.price.to_string(),
+            self.fee_amount.to_string(),
+            self.fee_asset.clone().unwrap_or_default(),
+            database::trade_status_to_str(&self.status).to_string(),
+            self.order_id.clone().unwrap_or_default(),
+            self.client_order_id.clone().unwrap_or_default(),
+            self.tx_hash.clone().unwrap_or_default(),
+            self.simulated.to_string(),
+            self.failure_reason.clone().unwrap_or_default(),
+        ]
+    }
+}
+
+impl ExportRow for Price {
+    const COLUMNS: &'static [&'static str] = &["timestamp", "exchange", "pair", "bid", "ask", "volume_24h"];
+    
+    fn fields(&self) -> Vec<String> {
+        vec![
+            self.timestamp.to_rfc3339(),
+            self.exchange.clone(),
+            self.pair.symbol.clone(),
+            self.bid.to_string(),
+            self.ask.to_string(),
+            self.volume_24h.map(|volume| volume.to_string()).unwrap_or_default(),
+        ]
+    }
+}
+
+// Writes every `kind` record in [start, end) to `out` and returns how many there were
+pub async fn export<W: Write>(
+    database: &Database,
+    kind: ExportKind,
+    format: ExportFormat,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    out: &mut W,
+) -> Result<u64> {
+    match kind {
+        ExportKind::Opportunities => write_rows(database.stream_opportunities_between(start, end), format, out).await,
+        ExportKind::Trades => write_rows(database.stream_trades_between(start, end), format, out).await,
+        ExportKind::Prices => write_rows(database.stream_price_history(start, end), format, out).await,
+    }
+}
+
+async fn write_rows<T: ExportRow, W: Write>(mut rows: BoxStream<'_, Result<T>>, format: ExportFormat, out: &mut W) -> Result<u64> {
+    if let ExportFormat::Csv = format {
+        writeln!(out, "{}", T::COLUMNS.join(","))?;
+    }
+    
+    let mut count = 0;
+    while let Some(row) = rows.next().await {
+        let row = row?;
+        match format {
+            ExportFormat::Csv => {
+                let fields: Vec<String> = row.fields().iter().map(|field| csv_field(field)).collect();
+                writeln!(out, "{}", fields.join(","))?;
+            },
+            ExportFormat::Json => {
+                serde_json::to_writer(&mut *out, &row)?;
+                writeln!(out)?;
+            }
+        }
+        count += 1;
+    }
+    
+    out.flush()?;
+    Ok(count)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
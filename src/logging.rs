@@ -0,0 +1,58 @@
+use anyhow::Result;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer, Registry};
+
+use crate::config::{LogFormat, LogRotation, LoggingConfig};
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+// The returned guard flushes the log file when dropped, so it must live until the process exits
+pub fn init(config: &LoggingConfig) -> Result<Option<WorkerGuard>> {
+    let filter = match std::env::var(EnvFilter::DEFAULT_ENV) {
+        Ok(_) => EnvFilter::from_default_env(),
+        Err(_) => EnvFilter::try_new(config.filter())?,
+    };
+    
+    let mut layers = vec![format_layer(config.format, std::io::stdout, true)];
+    let mut guard = None;
+    if let Some(file) = &config.file {
+        let rotation = match file.rotation {
+            LogRotation::Hourly => Rotation::HOURLY,
+            LogRotation::Daily => Rotation::DAILY,
+            LogRotation::Never => Rotation::NEVER,
+        };
+        let (writer, file_guard) = tracing_appender::non_blocking(RollingFileAppender::new(rotation, &file.directory, &file.prefix));
+        layers.push(format_layer(config.format, writer, false));
+        guard = Some(file_guard);
+    }
+    
+    tracing_subscriber::registry()
+        .with(layers)
+        .with(filter)
+        .try_init()?;
+    
+    Ok(guard)
+}
+
+// JSON lines carry the fields of the current span and every span around it, so a trade's
+// opportunity_id, pair and route show up on everything logged while executing it
+pub fn format_layer<W>(format: LogFormat, writer: W, ansi: bool) -> BoxedLayer
+where
+    W: for<'writer> fmt::MakeWriter<'writer> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Text => fmt::layer()
+            .with_writer(writer)
+            .with_ansi(ansi)
+            .boxed(),
+        LogFormat::Json => fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_writer(writer)
+            .boxed(),
+    }
+}
@@ -1,16 +1,44 @@
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use tracing::{info, warn, error};
 
 mod config;
+mod control;
+mod cooldown;
+mod crosschain;
+mod equivalence;
+mod errors;
+mod export;
 mod exchanges;
+mod logging;
 mod blockchain;
 mod arbitrage;
+mod balances;
+mod backtest;
 mod models;
 mod database;
+mod notifications;
+mod orders;
+mod paper;
+mod pnl;
+mod profit;
+mod rebalance;
+mod recorder;
+mod report;
+mod risk;
+mod sanity;
+mod scoring;
+mod sizing;
+mod spreads;
+#[cfg(test)]
+mod scenario;
+mod token_safety;
 mod utils;
+mod valuation;
+mod soak;
+mod status;
 
-use crate::config::Config;
+use crate::config::{Config, LogFormat, LoggingConfig};
 use crate::arbitrage::ArbitrageBot;
 
 #[derive(Parser)]
@@ -19,6 +47,9 @@ use crate::arbitrage::ArbitrageBot;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    // Overrides logging.format in the config
+    #[arg(long, global = true, value_enum)]
+    log_format: Option<LogFormat>,
 }
 
 #[derive(Subcommand)]
@@ -32,20 +63,230 @@ enum Commands {
     Scan {
         #[arg(short, long)]
         pair: Option<String>,
+        #[arg(long, value_enum, default_value = "table")]
+        output: OutputFormat,
+        // Rescan every `interval` seconds until Ctrl-C
+        #[arg(long, default_value = "false")]
+        watch: bool,
+        #[arg(long, default_value = "2")]
+        interval: u64,
     },
     InitDb,
-    Config,
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+    // Without an action, prints the parsed config.toml
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
+    },
+    Pnl {
+        #[arg(long)]
+        from: Option<String>,
+        #[arg(long)]
+        to: Option<String>,
+        #[arg(long, default_value = "false")]
+        simulated: bool,
+    },
+    // Defaults to the last 30 days, like pnl
+    Stats {
+        #[command(subcommand)]
+        report: Option<StatsReport>,
+        #[arg(long, global = true)]
+        from: Option<String>,
+        #[arg(long, global = true)]
+        to: Option<String>,
+        #[arg(long, global = true, default_value = "false")]
+        json: bool,
+    },
+    // Regenerates a daily summary from the database into reports.directory; defaults to yesterday
+    Report {
+        #[arg(long)]
+        date: Option<String>,
+        #[arg(long, default_value = "false")]
+        simulated: bool,
+        // Also send it through the notification channels
+        #[arg(long, default_value = "false")]
+        notify: bool,
+    },
+    // Dates are YYYY-MM-DD in UTC, both days included
+    Export {
+        #[arg(value_enum)]
+        what: export::ExportKind,
+        #[arg(long)]
+        from: String,
+        #[arg(long)]
+        to: String,
+        #[arg(long, value_enum, default_value = "csv")]
+        format: export::ExportFormat,
+        #[arg(long)]
+        out: std::path::PathBuf,
+    },
+    Backtest {
+        #[arg(long)]
+        from: String,
+        #[arg(long)]
+        to: String,
+        #[arg(short, long)]
+        config: Option<String>,
+        #[arg(long, default_value = "500")]
+        latency_ms: u64,
+        #[arg(long, default_value = "0.001")]
+        taker_fee: rust_decimal::Decimal,
+    },
+    NotifyTest {
+        #[arg(short, long)]
+        config: Option<String>,
+    },
+    Rebalance {
+        #[arg(short, long)]
+        config: Option<String>,
+        #[arg(long, default_value = "false")]
+        execute: bool,
+    },
+    CrossChain {
+        #[arg(short, long)]
+        config: Option<String>,
+        // Bridge the best route's base tokens, which must already be held on its source chain
+        #[arg(long, default_value = "false")]
+        bridge: bool,
+    },
+    Balances {
+        #[arg(short, long)]
+        config: Option<String>,
+        #[arg(long)]
+        exchange: Option<String>,
+        #[arg(long, default_value = "false")]
+        json: bool,
+        // Include assets with a zero balance
+        #[arg(long, default_value = "false")]
+        all: bool,
+    },
+    // Works alongside a running bot: orders are read from and cancelled on the venues themselves
+    Orders {
+        #[command(subcommand)]
+        action: OrdersAction,
+    },
+    Halt,
+    Resume,
+    // Lets the running bot execute an opportunity held for being above max_profit_threshold
+    Approve {
+        id: String,
+    },
+    Status,
+    #[command(hide = true)]
+    Soak {
+        #[arg(long, default_value = "200")]
+        pairs: usize,
+        #[arg(long, default_value = "4")]
+        venues: usize,
+        #[arg(long, default_value = "60")]
+        minutes: u64,
+        #[arg(long, default_value = "250")]
+        tick_ms: u64,
+        #[arg(long, default_value = "0.0005")]
+        volatility: f64,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    // Writes a commented example covering every setting
+    Init {
+        #[arg(long, default_value = "config.toml")]
+        path: String,
+        #[arg(long, default_value = "false")]
+        force: bool,
+    },
+    // Lists every problem in the config instead of stopping at the first
+    Check {
+        #[arg(long, default_value = "config.toml")]
+        path: String,
+    },
+}
+
+// Without a report, `stats` summarizes the opportunities found
+#[derive(Subcommand)]
+enum StatsReport {
+    // Net spread history per route
+    Spreads {
+        #[arg(short, long)]
+        pair: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DbAction {
+    // Applies pending migrations; with --dry-run only lists them
+    Migrate {
+        #[arg(long, default_value = "false")]
+        dry_run: bool,
+    },
+    // Prints the schema version the database is at
+    Version,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Table,
+    Json,
+}
+
+#[derive(Subcommand)]
+enum OrdersAction {
+    List {
+        #[arg(short, long)]
+        config: Option<String>,
+        #[arg(long)]
+        exchange: Option<String>,
+        #[arg(long, default_value = "false")]
+        json: bool,
+    },
+    Cancel {
+        #[arg(short, long)]
+        config: Option<String>,
+        #[arg(long)]
+        exchange: String,
+        #[arg(long)]
+        id: String,
+        #[arg(long, default_value = "false")]
+        json: bool,
+    },
+    CancelAll {
+        #[arg(short, long)]
+        config: Option<String>,
+        #[arg(long)]
+        exchange: Option<String>,
+        #[arg(long, default_value = "false")]
+        json: bool,
+    },
+}
+
+// Read before the command runs, which loads and reports on the config itself; other commands'
+// --config paths are not consulted
+fn logging_config(command: &Commands) -> LoggingConfig {
+    let path = match command {
+        Commands::Start { config: Some(path), .. } => path.as_str(),
+        _ => "config.toml",
+    };
+    if !std::path::Path::new(path).exists() {
+        return LoggingConfig::default();
+    }
+    Config::load(path).map(|config| config.logging).unwrap_or_default()
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-        .init();
-
     dotenv::dotenv().ok();
 
     let cli = Cli::parse();
+    
+    let mut logging = logging_config(&cli.command);
+    if let Some(format) = cli.log_format {
+        logging.format = format;
+    }
+    let _log_guard = logging::init(&logging)?;
 
     match cli.command {
         Commands::Start { config, dry_run } => {
@@ -55,24 +296,29 @@ async fn main() -> Result<()> {
             
             let mut bot = ArbitrageBot::new(config).await?;
             bot.set_dry_run(dry_run);
+            bot.set_config_path(&config_path);
             
             info!("Bot initialized, starting main loop...");
             bot.run().await?;
         },
-        Commands::Scan { pair } => {
+        Commands::Scan { pair, output, watch, interval } => {
             info!("Scanning for arbitrage opportunities");
             let config = Config::load("config.toml")?;
             let bot = ArbitrageBot::new(config).await?;
             
-            match pair {
-                Some(trading_pair) => {
-                    info!("Scanning pair: {}", trading_pair);
-                    bot.scan_pair(&trading_pair).await?;
-                },
-                None => {
-                    info!("Scanning all configured pairs");
-                    bot.scan_all().await?;
-                }
+            if watch {
+                return watch_scan(&bot, pair.as_deref(), output, std::time::Duration::from_secs(interval.max(1))).await;
+            }
+            
+            let report = scan_report(&bot, pair.as_deref()).await?;
+            match output {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+                OutputFormat::Table => print_scan_report(&report),
+            }
+            
+            let unpriced = report.unpriced();
+            if !unpriced.is_empty() {
+                anyhow::bail!("Not priced on at least two venues: {}", unpriced.join(", "));
             }
         },
         Commands::InitDb => {
@@ -81,12 +327,685 @@ async fn main() -> Result<()> {
             database::init_database(&config.database_url).await?;
             info!("Database initialized successfully");
         },
-        Commands::Config => {
+        Commands::Db { action } => {
+            let config = Config::load("config.toml")?;
+            let database = database::Database::new(&config.database_url).await?;
+            
+            match action {
+                DbAction::Migrate { dry_run: true } => {
+                    let pending = database.pending_migrations().await?;
+                    if pending.is_empty() {
+                        println!("No pending migrations");
+                    }
+                    for migration in pending {
+                        println!("Pending: {} {}", migration.version, migration.name);
+                    }
+                },
+                DbAction::Migrate { dry_run: false } => {
+                    let applied = database.migrate().await?;
+                    if applied.is_empty() {
+                        println!("No pending migrations");
+                    }
+                    for migration in applied {
+                        println!("Applied: {} {}", migration.version, migration.name);
+                    }
+                },
+                DbAction::Version => match database.schema_version().await? {
+                    Some(version) => println!("Schema version {} ({:?})", version, database.backend()),
+                    None => println!("No migrations applied ({:?})", database.backend()),
+                },
+            }
+        },
+        Commands::Config { action: None } => {
             info!("Checking configuration");
             let config = Config::load("config.toml")?;
             println!("{:#?}", config);
+        },
+        Commands::Config { action: Some(ConfigAction::Init { path, force }) } => {
+            if std::path::Path::new(&path).exists() && !force {
+                anyhow::bail!("{} already exists; pass --force to overwrite it", path);
+            }
+            std::fs::write(&path, crate::config::EXAMPLE_CONFIG)?;
+            println!("Wrote example config to {}", path);
+        },
+        Commands::Config { action: Some(ConfigAction::Check { path }) } => {
+            let config = Config::read(&path)?;
+            let problems = config.problems();
+            
+            if !problems.is_empty() {
+                for problem in &problems {
+                    println!("  - {}", problem);
+                }
+                anyhow::bail!("{} has {} problems", path, problems.len());
+            }
+            println!("{} is valid", path);
+        },
+        Commands::Pnl { from, to, simulated } => {
+            let config = Config::load("config.toml")?;
+            let database = database::Database::new(&config.database_url).await?;
+            
+            let (start, end) = date_range(from.as_deref(), to.as_deref())?;
+            let summary = database.get_pnl_summary(start, end, simulated).await?;
+            if simulated {
+                println!("Showing simulated dry-run trades only");
+            }
+            
+            print_pnl_summary(&summary);
+        },
+        Commands::Stats { report: Some(StatsReport::Spreads { pair }), from, to, json } => {
+            let config = Config::load("config.toml")?;
+            let database = database::Database::new(&config.database_url).await?;
+            
+            let (start, end) = date_range(from.as_deref(), to.as_deref())?;
+            let mut buckets = database.get_spread_buckets(start, end).await?;
+            if let Some(pair) = &pair {
+                buckets.retain(|bucket| bucket.pair.eq_ignore_ascii_case(pair));
+            }
+            let window = chrono::Duration::hours(config.trading.spread_history.window_hours as i64);
+            let stats = spreads::summarize(&buckets, end.min(chrono::Utc::now()) - window);
+            
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                println!("Net spreads from {} to {}", start.date_naive(), (end - chrono::Duration::days(1)).date_naive());
+                print_spread_stats(&stats, config.trading.spread_history.window_hours);
+            }
+        },
+        Commands::Stats { report: None, from, to, json } => {
+            let config = Config::load("config.toml")?;
+            let database = database::Database::new(&config.database_url).await?;
+            
+            let (start, end) = date_range(from.as_deref(), to.as_deref())?;
+            let stats = database.get_opportunity_stats(start, end).await?;
+            
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                println!("Opportunities from {} to {}", start.date_naive(), (end - chrono::Duration::days(1)).date_naive());
+                print_opportunity_stats(&stats);
+            }
+        },
+        Commands::Report { date, simulated, notify } => {
+            let config = Config::load("config.toml")?;
+            let database = database::Database::new(&config.database_url).await?;
+            
+            let offset = config.reports.offset()?;
+            let date = match date {
+                Some(date) => parse_date(&date)?,
+                None => report::local_date(chrono::Utc::now(), offset) - chrono::Duration::days(1),
+            };
+            let daily = report::daily(&database, date, offset, simulated).await?;
+            let paths = report::write(&daily, std::path::Path::new(&config.reports.directory))?;
+            
+            print!("{}", report::markdown(&daily));
+            println!();
+            for path in &paths {
+                println!("Written to {}", path.display());
+            }
+            
+            if notify {
+                let notifications = notifications::NotificationService::new(config.notifications.as_ref());
+                if !notifications.is_enabled() {
+                    anyhow::bail!("No notification channels configured");
+                }
+                for (channel, result) in notifications.send_daily_summary(&daily).await {
+                    match result {
+                        Ok(()) => println!("{}: summary sent", channel),
+                        Err(e) => println!("{}: failed - {}", channel, e),
+                    }
+                }
+            }
+        },
+        Commands::Export { what, from, to, format, out } => {
+            let config = Config::load("config.toml")?;
+            let database = database::Database::new(&config.database_url).await?;
+            
+            let start = parse_date(&from)?.and_hms_opt(0, 0, 0).unwrap().and_utc();
+            let end = (parse_date(&to)? + chrono::Duration::days(1)).and_hms_opt(0, 0, 0).unwrap().and_utc();
+            
+            let file = std::fs::File::create(&out)
+                .map_err(|e| anyhow::anyhow!("Cannot create {}: {}", out.display(), e))?;
+            let mut writer = std::io::BufWriter::new(file);
+            let count = export::export(&database, what, format, start, end, &mut writer).await?;
+            println!("Exported {} records to {}", count, out.display());
+        },
+        Commands::Backtest { from, to, config, latency_ms, taker_fee } => {
+            let config_path = config.unwrap_or_else(|| "config.toml".to_string());
+            let config = Config::load(&config_path)?;
+            
+            let backtest_config = backtest::BacktestConfig {
+                from: parse_date(&from)?.and_hms_opt(0, 0, 0).unwrap().and_utc(),
+                to: (parse_date(&to)? + chrono::Duration::days(1)).and_hms_opt(0, 0, 0).unwrap().and_utc(),
+                latency: std::time::Duration::from_millis(latency_ms),
+                taker_fee,
+            };
+            
+            info!("Backtesting {} to {} with {}ms latency", from, to, latency_ms);
+            let report = backtest::run(config, backtest_config).await?;
+            
+            println!("Ticks replayed: {}", report.ticks);
+            println!("Opportunities:  {}", report.opportunities);
+            println!("Executed:       {}", report.executed);
+            println!("Missed:         {}", report.missed);
+            println!("Hit rate:       {:.2}%", report.hit_rate());
+            println!("Total PnL:      {:.4}", report.total_pnl);
+            println!("Max drawdown:   {:.4}", report.max_drawdown);
+        },
+        Commands::NotifyTest { config } => {
+            let config_path = config.unwrap_or_else(|| "config.toml".to_string());
+            let config = Config::load(&config_path)?;
+            let notifications = notifications::NotificationService::new(config.notifications.as_ref());
+            
+            if !notifications.is_enabled() {
+                anyhow::bail!("No notification channels configured");
+            }
+            
+            let mut failures = 0;
+            for (channel, result) in notifications.send_test().await {
+                match result {
+                    Ok(()) => println!("{}: test message sent", channel),
+                    Err(e) => {
+                        println!("{}: failed - {}", channel, e);
+                        failures += 1;
+                    }
+                }
+            }
+            
+            if failures > 0 {
+                anyhow::bail!("{} notification channel(s) failed", failures);
+            }
+        },
+        Commands::Resume => {
+            let config = Config::load("config.toml")?;
+            let database = database::Database::new(&config.database_url).await?;
+            
+            match database.clear_trading_halts(chrono::Utc::now()).await? {
+                0 => println!("No daily loss halt is active"),
+                cleared => println!("Lifted {} daily loss halt(s)", cleared),
+            }
+            
+            match control::send_command(&config.control.listen_addr, "resume").await {
+                Ok(reply) => println!("Kill switch: {}", reply),
+                Err(e) => warn!("Could not reach the running bot: {}", e),
+            }
+        },
+        Commands::Approve { id } => {
+            let config = Config::load("config.toml")?;
+            let database = database::Database::new(&config.database_url).await?;
+            let id = uuid::Uuid::parse_str(&id).map_err(|e| anyhow::anyhow!("{} is not an opportunity id: {}", id, e))?;
+            
+            if database.approve_opportunity(id, chrono::Utc::now()).await? {
+                println!("Approved opportunity {}; the running bot executes it on its next cycle if it is still there", id);
+            } else {
+                match database.get_opportunity(id).await? {
+                    None => anyhow::bail!("No opportunity {}", id),
+                    Some(opportunity) if !matches!(opportunity.status, models::OpportunityStatus::Active) => {
+                        anyhow::bail!("Opportunity {} is no longer active ({:?})", id, opportunity.status)
+                    },
+                    Some(opportunity) if opportunity.approved_at.is_some() => println!("Opportunity {} was already approved", id),
+                    Some(_) => println!("Opportunity {} does not need approval", id),
+                }
+            }
+        },
+        Commands::CrossChain { config, bridge } => {
+            let config_path = config.unwrap_or_else(|| "config.toml".to_string());
+            let config = Config::load(&config_path)?;
+            let bot = ArbitrageBot::new(config).await?;
+            
+            let routes = bot.scan_cross_chain().await?;
+            if routes.is_empty() {
+                println!("No profitable cross-chain routes found");
+            }
+            for route in &routes {
+                print_cross_chain_plan(route);
+            }
+            
+            if let (true, Some(route)) = (bridge, routes.first()) {
+                let tx_hash = bot.execute_cross_chain_bridge(route).await?;
+                println!("Submitted bridge deposit of {} {} from {} to {}: {}", route.amount, route.pair.base, route.source_chain, route.target_chain, tx_hash);
+            }
+        },
+        Commands::Rebalance { config, execute } => {
+            let config_path = config.unwrap_or_else(|| "config.toml".to_string());
+            let config = Config::load(&config_path)?;
+            let bot = ArbitrageBot::new(config).await?;
+            
+            let plan = bot.plan_rebalance().await?;
+            print_rebalance_plan(&plan);
+            
+            if plan.transfers.is_empty() {
+                return Ok(());
+            }
+            if !execute {
+                println!();
+                println!("Run with --execute to submit these withdrawals");
+                return Ok(());
+            }
+            
+            println!();
+            for (transfer, result) in bot.execute_rebalance(&plan).await {
+                match result {
+                    Ok(id) => println!("{} {} {} -> {}: submitted ({})", transfer.amount, transfer.asset, transfer.from, transfer.to, id),
+                    Err(e) => println!("{} {} {} -> {}: failed - {}", transfer.amount, transfer.asset, transfer.from, transfer.to, e),
+                }
+            }
+        },
+        Commands::Balances { config, exchange, json, all } => {
+            let config_path = config.unwrap_or_else(|| "config.toml".to_string());
+            let config = Config::load(&config_path)?;
+            let bot = ArbitrageBot::new(config).await?;
+            
+            let mut report = bot.balance_report(exchange.as_deref()).await?;
+            if !all {
+                report.hide_zero_balances();
+            }
+            
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print_balance_report(&report);
+            }
+        },
+        Commands::Orders { action } => match action {
+            OrdersAction::List { config, exchange, json } => {
+                let config = Config::load(&config.unwrap_or_else(|| "config.toml".to_string()))?;
+                let bot = ArbitrageBot::new(config).await?;
+                let report = bot.list_open_orders(exchange.as_deref()).await?;
+                
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                } else {
+                    print_open_orders(&report);
+                }
+            },
+            OrdersAction::Cancel { config, exchange, id, json } => {
+                let config = Config::load(&config.unwrap_or_else(|| "config.toml".to_string()))?;
+                let bot = ArbitrageBot::new(config).await?;
+                let result = bot.cancel_exchange_order(&exchange, &id).await;
+                
+                if json {
+                    let cancellation = models::OrderCancellation {
+                        exchange: exchange.clone(),
+                        order_id: id.clone(),
+                        error: result.as_ref().err().map(|e| e.to_string()),
+                    };
+                    println!("{}", serde_json::to_string_pretty(&cancellation)?);
+                }
+                result.map_err(|e| anyhow::anyhow!("Failed to cancel order {} on {}: {}", id, exchange, e))?;
+                if !json {
+                    println!("Cancelled order {} on {}", id, exchange);
+                }
+            },
+            OrdersAction::CancelAll { config, exchange, json } => {
+                let config = Config::load(&config.unwrap_or_else(|| "config.toml".to_string()))?;
+                let bot = ArbitrageBot::new(config).await?;
+                let report = bot.cancel_all_orders(exchange.as_deref()).await?;
+                
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                } else {
+                    print_cancellation_report(&report);
+                }
+                
+                let failed = report.cancellations.iter().filter(|cancellation| cancellation.error.is_some()).count();
+                if failed > 0 || !report.failures.is_empty() {
+                    anyhow::bail!("{} orders could not be cancelled and {} exchanges could not be listed", failed, report.failures.len());
+                }
+            },
+        },
+        Commands::Halt => {
+            let config = Config::load("config.toml")?;
+            let reply = control::send_command(&config.control.listen_addr, "halt").await?;
+            println!("Kill switch: {}", reply);
+        },
+        Commands::Status => {
+            let config = Config::load("config.toml")?;
+            let reply = control::send_command(&config.control.listen_addr, "status").await?;
+            println!("Bot is {}", reply);
+        },
+        Commands::Soak { pairs, venues, minutes, tick_ms, volatility } => {
+            info!("Starting soak test: {} pairs x {} venues for {} minutes", pairs, venues, minutes);
+            let soak_config = soak::SoakConfig {
+                pairs,
+                venues,
+                duration: std::time::Duration::from_secs(minutes * 60),
+                tick_interval: std::time::Duration::from_millis(tick_ms),
+                volatility,
+                ..Default::default()
+            };
+            
+            let report = soak::run(soak_config).await?;
+            println!("{:#?}", report);
+            
+            if !report.violations.is_empty() {
+                for violation in &report.violations {
+                    error!("Soak invariant violated: {}", violation);
+                }
+                anyhow::bail!("Soak test failed with {} violations", report.violations.len());
+            }
         }
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+fn parse_date(value: &str) -> Result<chrono::NaiveDate> {
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("Invalid date (expected YYYY-MM-DD): {}", value))
+}
+
+// Whole UTC days from `from` through `to`, defaulting to the last 30 days
+fn date_range(from: Option<&str>, to: Option<&str>) -> Result<(chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)> {
+    let today = chrono::Utc::now().date_naive();
+    let from = match from {
+        Some(date) => parse_date(date)?,
+        None => today - chrono::Duration::days(30),
+    };
+    let to = match to {
+        Some(date) => parse_date(date)?,
+        None => today,
+    };
+    
+    let start = from.and_hms_opt(0, 0, 0).unwrap().and_utc();
+    let end = (to + chrono::Duration::days(1)).and_hms_opt(0, 0, 0).unwrap().and_utc();
+    Ok((start, end))
+}
+
+fn print_opportunity_stats(stats: &models::OpportunityStats) {
+    let percent = |value: Option<f64>| value.map_or("-".to_string(), |value| format!("{:.4}%", value));
+    
+    println!("Total:      {}", stats.total);
+    println!("Executed:   {}", stats.executed);
+    println!("Expired:    {}", stats.expired);
+    println!("Failed:     {}", stats.failed);
+    println!("Active:     {}", stats.active);
+    println!("Improvements: {}", stats.updates);
+    println!("Executed vs expired: {}", stats.execution_rate().map_or("-".to_string(), |rate| format!("{:.1}%", rate * 100.0)));
+    println!("Average profit: {}, best: {}", percent(stats.average_profit_percentage), percent(stats.max_profit_percentage));
+    println!("Average lifetime: {}", stats.average_lifetime_seconds.map_or("-".to_string(), |seconds| format!("{:.1}s", seconds)));
+    
+    let sections = [("Day", &stats.by_day), ("Pair", &stats.by_pair), ("Route", &stats.by_route)];
+    for (label, rows) in sections {
+        println!();
+        println!("{:<32} {:>10} {:>10} {:>12}", label, "Found", "Executed", "Avg profit");
+        for row in rows {
+            println!("{:<32} {:>10} {:>10} {:>11.4}%", row.key, row.count, row.executed, row.average_profit_percentage);
+        }
+    }
+    
+    println!();
+    println!("{:<32} {:>10}", "Profit", "Found");
+    for bucket in &stats.profit_distribution {
+        let range = match (bucket.from, bucket.to) {
+            (None, Some(to)) => format!("< {}%", to),
+            (Some(from), Some(to)) => format!("{}% - {}%", from, to),
+            (Some(from), None) => format!(">= {}%", from),
+            (None, None) => "all".to_string(),
+        };
+        println!("{:<32} {:>10}", range, bucket.count);
+    }
+}
+
+fn print_spread_stats(stats: &[models::RouteSpreadStats], window_hours: u64) {
+    if stats.is_empty() {
+        println!("No spreads recorded");
+        return;
+    }
+    
+    let percent = |value: Option<f64>| value.map_or("-".to_string(), |value| format!("{:.4}%", value));
+    let recent = format!("Last {}h", window_hours);
+    println!("{:<12} {:<24} {:>9} {:>10} {:>10} {:>10} {:>10} {:>9} {:>15}",
+             "Pair", "Route", "Samples", "Mean", "Stddev", recent, "Stddev", "Above", "Best hour");
+    for route in stats {
+        let best_hour = match (route.best_hour, route.best_hour_mean) {
+            (Some(hour), Some(mean)) => format!("{:02}:00 {:.3}%", hour, mean),
+            _ => "-".to_string(),
+        };
+        println!("{:<12} {:<24} {:>9} {:>9.4}% {:>9.4}% {:>10} {:>10} {:>8.1}% {:>15}",
+                 route.pair, format!("{} -> {}", route.buy_exchange, route.sell_exchange), route.samples,
+                 route.mean, route.stddev, percent(route.recent_mean), percent(route.recent_stddev),
+                 route.above_threshold_percentage, best_hour);
+    }
+}
+
+fn print_cross_chain_plan(route: &models::CrossChainArbitrage) {
+    let quote = &route.pair.quote;
+    println!("{} via {} -> {} (~{} min)", route.pair.symbol, route.source_chain, route.target_chain, route.estimated_time_minutes);
+    println!("  1. Swap on {} ({}): buy {:.8} {} at {}", route.buy_exchange, route.source_chain, route.amount, route.pair.base, route.buy_price);
+    println!("       swap fee {:.2} {}, gas {:.2} {}", route.buy_swap_fee, quote, route.buy_gas_cost, quote);
+    println!("  2. Bridge {} ({}) to {}", route.pair.base, route.token_address, route.target_chain);
+    println!("       bridge fee {:.2} {}", route.bridge_fees, quote);
+    println!("  3. Swap on {} ({}): sell at {}", route.sell_exchange, route.target_chain, route.sell_price);
+    println!("       swap fee {:.2} {}, gas {:.2} {}", route.sell_swap_fee, quote, route.sell_gas_cost, quote);
+    println!("  Estimated profit: {:.2} {}", route.profit_estimate, quote);
+    println!();
+}
+
+fn print_balance_report(report: &models::BalanceReport) {
+    let mut exchanges: Vec<_> = report.exchanges.iter().collect();
+    exchanges.sort_by(|a, b| a.0.cmp(b.0));
+    let sections = exchanges.into_iter()
+        .map(|(name, balances)| (name.as_str(), balances))
+        .chain(std::iter::once(("Total", &report.totals)));
+    
+    for (label, balances) in sections {
+        let mut balances: Vec<_> = balances.values().collect();
+        balances.sort_by(|a, b| b.usd_value.cmp(&a.usd_value).then_with(|| a.asset.cmp(&b.asset)));
+        
+        println!("{}", label);
+        println!("{:<10} {:>20} {:>20} {:>20} {:>14}", "Asset", "Free", "Locked", "Total", "USD");
+        for balance in balances {
+            println!("{:<10} {:>20.8} {:>20.8} {:>20.8} {:>14.2}",
+                     balance.asset, balance.free, balance.locked, balance.total, balance.usd_value);
+        }
+        println!();
+    }
+    println!("Portfolio value: ${:.2}", report.total_value_usd);
+    
+    let mut failures: Vec<_> = report.failures.iter().collect();
+    failures.sort();
+    for (exchange, error) in failures {
+        println!("{}: failed to fetch balances - {}", exchange, error);
+    }
+}
+
+async fn scan_report(bot: &ArbitrageBot, pair: Option<&str>) -> Result<models::ScanReport> {
+    match pair {
+        Some(trading_pair) => {
+            info!("Scanning pair: {}", trading_pair);
+            Ok(models::ScanReport { pairs: vec![bot.scan_pair(trading_pair).await?] })
+        },
+        None => {
+            info!("Scanning all configured pairs");
+            Ok(bot.scan_all().await)
+        }
+    }
+}
+
+// Redraws the table after every scan; JSON output becomes one report per line instead
+async fn watch_scan(bot: &ArbitrageBot, pair: Option<&str>, output: OutputFormat, interval: std::time::Duration) -> Result<()> {
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    
+    loop {
+        let started = std::time::Instant::now();
+        let report = tokio::select! {
+            _ = &mut ctrl_c => break,
+            report = scan_report(bot, pair) => report?,
+        };
+        let elapsed = started.elapsed();
+        
+        match output {
+            OutputFormat::Json => println!("{}", serde_json::to_string(&report)?),
+            OutputFormat::Table => {
+                print!("\x1b[2J\x1b[H");
+                println!("{} - scanned in {} ms, every {}s; Ctrl-C to stop\n",
+                         chrono::Utc::now().format("%H:%M:%S"), elapsed.as_millis(), interval.as_secs());
+                print_scan_report(&report);
+            }
+        }
+        
+        tokio::select! {
+            _ = &mut ctrl_c => break,
+            _ = tokio::time::sleep(interval.saturating_sub(elapsed)) => {},
+        }
+    }
+    Ok(())
+}
+
+fn print_scan_report(report: &models::ScanReport) {
+    // Routes clearing the pair's threshold are shown in green on a terminal
+    let color = std::io::IsTerminal::is_terminal(&std::io::stdout());
+    let highlight = |line: String, hit: bool| if hit && color { format!("\x1b[1;32m{}\x1b[0m", line) } else { line };
+    
+    for scan in &report.pairs {
+        println!("{}", scan.pair);
+        if let Some(error) = &scan.error {
+            println!("  error: {}", error);
+        }
+        
+        println!("  {:<14} {:>18} {:>18} {:>10}", "Exchange", "Bid", "Ask", "Spread");
+        for quote in &scan.quotes {
+            println!("  {:<14} {:>18} {:>18} {:>9.4}%",
+                     quote.price.exchange, quote.price.bid, quote.price.ask, quote.spread_percentage);
+        }
+        if let Some(route) = &scan.best_route {
+            let net = route.net_percentage.map_or("?".to_string(), |net| format!("{:.4}%", net));
+            let clears = route.net_percentage.map_or(false, |net| net >= scan.min_profit_threshold);
+            println!("{}", highlight(
+                format!("  Best route: buy {} -> sell {}, {:.4}% gross, {} net of fees (threshold {}%)",
+                        route.buy_exchange, route.sell_exchange, route.gross_percentage, net, scan.min_profit_threshold),
+                clears,
+            ));
+        }
+        
+        for opportunity in &scan.opportunities {
+            println!("{}", highlight(
+                format!("  Buy on {} at {}, sell on {} at {}: {:.4}% net, {} {} on up to {}",
+                        opportunity.buy_exchange, opportunity.buy_price,
+                        opportunity.sell_exchange, opportunity.native_sell_price(),
+                        opportunity.profit_percentage, opportunity.profit_amount, opportunity.pair.quote,
+                        opportunity.max_trade_size),
+                true,
+            ));
+        }
+        println!();
+    }
+}
+
+fn print_open_orders(report: &models::OpenOrdersReport) {
+    if report.orders.is_empty() {
+        println!("No open orders");
+    } else {
+        let now = chrono::Utc::now();
+        println!("{:<12} {:<28} {:<10} {:<5} {:>16} {:>30} {:>10}", "Exchange", "Id", "Pair", "Side", "Price", "Filled/Total", "Age");
+        for order in &report.orders {
+            let side = match order.side {
+                models::TradeSide::Buy => "BUY",
+                models::TradeSide::Sell => "SELL",
+            };
+            println!("{:<12} {:<28} {:<10} {:<5} {:>16} {:>30} {:>10}",
+                     order.exchange, order.order_id, order.pair, side, order.price,
+                     format!("{}/{}", order.filled, order.amount), format_age(now - order.created_at));
+        }
+    }
+    
+    let mut failures: Vec<_> = report.failures.iter().collect();
+    failures.sort();
+    for (exchange, error) in failures {
+        println!("{}: failed to list open orders - {}", exchange, error);
+    }
+}
+
+fn print_cancellation_report(report: &models::CancellationReport) {
+    if report.cancellations.is_empty() && report.failures.is_empty() {
+        println!("No open orders to cancel");
+    }
+    for cancellation in &report.cancellations {
+        match &cancellation.error {
+            None => println!("Cancelled order {} on {}", cancellation.order_id, cancellation.exchange),
+            Some(e) => println!("Failed to cancel order {} on {} - {}", cancellation.order_id, cancellation.exchange, e),
+        }
+    }
+    
+    let mut failures: Vec<_> = report.failures.iter().collect();
+    failures.sort();
+    for (exchange, error) in failures {
+        println!("{}: failed to list open orders, nothing cancelled - {}", exchange, error);
+    }
+}
+
+fn format_age(age: chrono::Duration) -> String {
+    let seconds = age.num_seconds().max(0);
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m{}s", seconds / 60, seconds % 60),
+        3600..=86399 => format!("{}h{}m", seconds / 3600, seconds % 3600 / 60),
+        _ => format!("{}d{}h", seconds / 86400, seconds % 86400 / 3600),
+    }
+}
+
+fn print_rebalance_plan(plan: &rebalance::RebalancePlan) {
+    if !plan.flows.is_empty() {
+        println!("Net flows from recent trades:");
+        println!("{:<16} {:<10} {:>20}", "Exchange", "Asset", "Change");
+        for ((exchange, asset), change) in &plan.flows {
+            println!("{:<16} {:<10} {:>20.8}", exchange, asset, change);
+        }
+        println!();
+    }
+    
+    if plan.transfers.is_empty() {
+        println!("No transfers needed");
+    } else {
+        println!("Proposed transfers:");
+        for transfer in &plan.transfers {
+            println!("{} {} from {} to {} (fee {}, address {})",
+                     transfer.amount, transfer.asset, transfer.from, transfer.to, transfer.fee, transfer.address);
+        }
+    }
+    
+    for reason in &plan.skipped {
+        println!("Skipped: {}", reason);
+    }
+}
+
+fn print_pnl_summary(summary: &pnl::PnlSummary) {
+    println!("PnL from {} to {}", summary.from.date_naive(), summary.to.date_naive());
+    
+    let sections = [
+        ("Day", summary.by_day.iter().map(|(day, bucket)| (day.to_string(), bucket)).collect::<Vec<_>>()),
+        ("Pair", summary.by_pair.iter().map(|(pair, bucket)| (pair.clone(), bucket)).collect()),
+        ("Route", summary.by_route.iter().map(|(route, bucket)| (route.clone(), bucket)).collect()),
+    ];
+    
+    for (label, rows) in sections {
+        println!();
+        println!("{:<28} {:>8} {:>16} {:>16}", label, "Trades", "Fees", "Realized");
+        for (key, bucket) in rows {
+            println!("{:<28} {:>8} {:>16.4} {:>16.4}", key, bucket.opportunities, bucket.fees, bucket.realized);
+        }
+    }
+    
+    println!();
+    println!("Total fees:     {:.4}", summary.total_fees);
+    println!("Total realized: {:.4}", summary.total_realized);
+    
+    if !summary.inventory.is_empty() {
+        println!();
+        println!("Unrealized inventory changes (not included in realized PnL):");
+        println!("{:<12} {:>16} {:>16} {:>16}", "Asset", "Quantity", "Cost basis", "Unrealized");
+        for (asset, inventory) in &summary.inventory {
+            println!("{:<12} {:>16.6} {:>16.4} {:>16.4}",
+                     asset, inventory.quantity, inventory.cost_basis, inventory.unrealized());
+        }
+    }
+    
+    if !summary.unpriced_fees.is_empty() {
+        println!();
+        println!("Fees paid in other assets (not included above):");
+        for (asset, amount) in &summary.unpriced_fees {
+            println!("{:<12} {:>16.8}", asset, amount);
+        }
+    }
+}
@@ -1,8 +1,8 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, DurationRound, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TradingPair {
     pub base: String,
     pub quote: String,
@@ -27,6 +27,63 @@ pub struct Price {
     pub ask: Decimal,
     pub timestamp: DateTime<Utc>,
     pub volume_24h: Option<Decimal>,
+    // Round trip of the request that fetched the quote; None for streamed and recorded quotes
+    #[serde(default)]
+    pub fetch_latency: Option<std::time::Duration>,
+}
+
+impl Price {
+    pub fn mid(&self) -> Decimal {
+        (self.bid + self.ask) / Decimal::TWO
+    }
+}
+
+// Mid prices of one minute of recorded history, kept after the raw prices are pruned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceCandle {
+    pub exchange: String,
+    pub pair: TradingPair,
+    pub minute: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub samples: i64,
+}
+
+impl PriceCandle {
+    fn open(price: &Price, minute: DateTime<Utc>) -> Self {
+        let mid = price.mid();
+        Self {
+            exchange: price.exchange.clone(),
+            pair: price.pair.clone(),
+            minute,
+            open: mid,
+            high: mid,
+            low: mid,
+            close: mid,
+            samples: 1,
+        }
+    }
+    
+    fn update(&mut self, price: &Price) {
+        let mid = price.mid();
+        self.high = self.high.max(mid);
+        self.low = self.low.min(mid);
+        self.close = mid;
+        self.samples += 1;
+    }
+    
+    // Prices must arrive sorted by exchange, pair and time; each minute becomes one candle
+    pub fn roll_up(candles: &mut Vec<Self>, price: &Price) {
+        let minute = price.timestamp.duration_trunc(chrono::Duration::minutes(1)).unwrap_or(price.timestamp);
+        match candles.last_mut() {
+            Some(candle) if candle.minute == minute && candle.exchange == price.exchange && candle.pair == price.pair => {
+                candle.update(price);
+            },
+            _ => candles.push(Self::open(price, minute)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +95,63 @@ pub struct OrderBook {
     pub timestamp: DateTime<Utc>,
 }
 
+impl OrderBook {
+    // Volume-weighted average price for filling `quantity` against the opposite side of the book.
+    // Returns None when the book cannot fill the full quantity.
+    pub fn effective_price(&self, side: &TradeSide, quantity: Decimal) -> Option<Decimal> {
+        if quantity <= Decimal::ZERO {
+            return None;
+        }
+        
+        let levels = match side {
+            TradeSide::Buy => &self.asks,
+            TradeSide::Sell => &self.bids,
+        };
+        
+        let mut remaining = quantity;
+        let mut cost = Decimal::ZERO;
+        
+        for level in levels {
+            let fill = remaining.min(level.quantity);
+            cost += fill * level.price;
+            remaining -= fill;
+            
+            if remaining.is_zero() {
+                return Some(cost / quantity);
+            }
+        }
+        
+        None
+    }
+    
+    // The book with prices restated in another quote, one unit of this book's quote being worth `rate`
+    pub fn converted(&self, rate: Decimal) -> OrderBook {
+        let convert = |levels: &[OrderBookLevel]| levels.iter()
+            .map(|level| OrderBookLevel { price: level.price * rate, quantity: level.quantity })
+            .collect();
+        OrderBook {
+            exchange: self.exchange.clone(),
+            pair: self.pair.clone(),
+            bids: convert(&self.bids),
+            asks: convert(&self.asks),
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+// Constant-product pool depth for a pair, in whole base and quote units
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PoolReserves {
+    pub base: Decimal,
+    pub quote: Decimal,
+}
+
+impl PoolReserves {
+    pub fn converted(&self, rate: Decimal) -> PoolReserves {
+        PoolReserves { base: self.base, quote: self.quote * rate }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderBookLevel {
     pub price: Decimal,
@@ -47,16 +161,53 @@ pub struct OrderBookLevel {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArbitrageOpportunity {
     pub id: uuid::Uuid,
+    // Canonical pair the venues were compared under; each leg trades its venue's native pair
     pub pair: TradingPair,
     pub buy_exchange: String,
     pub sell_exchange: String,
+    pub buy_pair: TradingPair,
+    pub sell_pair: TradingPair,
+    // Prices and profit are in the buy leg's quote; a sell leg quoted in an equivalent asset is
+    // restated at this rate, which is 1 when both legs share a quote
+    pub sell_quote_rate: Decimal,
     pub buy_price: Decimal,
     pub sell_price: Decimal,
     pub profit_percentage: Decimal,
     pub profit_amount: Decimal,
     pub max_trade_size: Decimal,
+    // Quote-asset breakdown of profit_amount: gross spread less each leg's taker fee and gas
+    #[serde(default)]
+    pub gross_profit: Decimal,
+    #[serde(default)]
+    pub buy_fee_cost: Decimal,
+    #[serde(default)]
+    pub sell_fee_cost: Decimal,
+    // Taker fee rates (fractions) the legs were priced with
+    #[serde(default)]
+    pub buy_fee_rate: Decimal,
+    #[serde(default)]
+    pub sell_fee_rate: Decimal,
+    pub gas_cost: Decimal,
+    // profit_amount is before transfers; the adjusted figure also pays the amortized withdrawal fees
+    pub transfer_cost: Decimal,
+    pub adjusted_profit_amount: Decimal,
+    // Per further unit of base at max_trade_size; positive when a cap rather than the books bounded the size
+    #[serde(default)]
+    pub marginal_profit: Decimal,
     pub timestamp: DateTime<Utc>,
     pub status: OpportunityStatus,
+    // Above trading.max_profit_threshold; not executed until approved
+    #[serde(default)]
+    pub requires_approval: bool,
+    #[serde(default)]
+    pub approved_at: Option<DateTime<Utc>>,
+}
+
+impl ArbitrageOpportunity {
+    // The sell price as the sell venue itself quotes it
+    pub fn native_sell_price(&self) -> Decimal {
+        self.sell_price / self.sell_quote_rate
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -76,13 +227,23 @@ pub struct Trade {
     pub side: TradeSide,
     pub amount: Decimal,
     pub price: Decimal,
+    pub fee_amount: Decimal,
+    pub fee_asset: Option<String>,
     pub status: TradeStatus,
     pub created_at: DateTime<Utc>,
     pub executed_at: Option<DateTime<Utc>>,
+    pub order_id: Option<String>,
+    // Our own id for the order, derived from the opportunity and leg so a retried placement is recognised
+    #[serde(default)]
+    pub client_order_id: Option<String>,
     pub tx_hash: Option<String>,
+    pub simulated: bool,
+    // Decoded revert reason for on-chain swaps that failed
+    #[serde(default)]
+    pub failure_reason: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TradeSide {
     Buy,
     Sell,
@@ -96,6 +257,220 @@ pub enum TradeStatus {
     Cancelled,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HedgePosition {
+    pub id: uuid::Uuid,
+    pub opportunity_id: uuid::Uuid,
+    pub exchange: String,
+    pub pair: TradingPair,
+    pub side: TradeSide,
+    pub size: Decimal,
+    pub entry_price: Decimal,
+    pub order_id: Option<String>,
+    pub status: HedgeStatus,
+    pub opened_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum HedgeStatus {
+    Open,
+    Closed,
+}
+
+// Trading stays halted until `halted_until` passes or the halt is cleared from the CLI
+// Consecutive failed executions of one route; it is skipped while `cooled_until` lies ahead
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteFailure {
+    pub route: String,
+    pub consecutive_failures: u32,
+    pub cooled_until: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Finished executions of one route, counted from the opportunities table
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteFillCount {
+    pub pair: String,
+    pub buy_exchange: String,
+    pub sell_exchange: String,
+    pub executed: i64,
+    pub failed: i64,
+}
+
+// Errors of one component over a period, with the most recent message
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorCount {
+    pub context: String,
+    pub count: i64,
+    pub last_message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteResult {
+    pub route: String,
+    pub opportunities: usize,
+    pub realized: Decimal,
+}
+
+// One day of trading at the configured UTC offset; amounts are in quote currency, portfolio values in USD
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyReport {
+    pub date: NaiveDate,
+    pub utc_offset: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub simulated: bool,
+    pub opportunities_seen: i64,
+    pub opportunities_executed: i64,
+    pub realized_pnl: Decimal,
+    pub fees: Decimal,
+    // As estimated for the executed opportunities
+    pub gas_spent: Decimal,
+    pub top_route: Option<RouteResult>,
+    pub halts: Vec<TradingHalt>,
+    pub errors: Vec<ErrorCount>,
+    // Latest snapshots before the end and the start of the day
+    pub portfolio_value: Option<Decimal>,
+    pub previous_portfolio_value: Option<Decimal>,
+}
+
+impl DailyReport {
+    pub fn portfolio_change(&self) -> Option<Decimal> {
+        Some(self.portfolio_value? - self.previous_portfolio_value?)
+    }
+    
+    // Plain text for notification channels
+    pub fn summary(&self) -> String {
+        let mut lines = vec![
+            format!("Opportunities: {} seen, {} executed", self.opportunities_seen, self.opportunities_executed),
+            format!("Realized PnL: {:.2}", self.realized_pnl),
+            format!("Fees: {:.2}, gas: {:.2}", self.fees, self.gas_spent),
+        ];
+        if let Some(top) = &self.top_route {
+            lines.push(format!("Top route: {} ({} trades, {:.2})", top.route, top.opportunities, top.realized));
+        }
+        match (self.portfolio_value, self.portfolio_change()) {
+            (Some(value), Some(change)) => lines.push(format!("Portfolio: ${:.2} ({:+.2} vs yesterday)", value, change)),
+            (Some(value), None) => lines.push(format!("Portfolio: ${:.2}", value)),
+            _ => {},
+        }
+        for halt in &self.halts {
+            lines.push(format!("Halted at {}: {}", halt.halted_at.format("%H:%M UTC"), halt.reason));
+        }
+        for error in &self.errors {
+            lines.push(format!("Errors in {}: {} (last: {})", error.context, error.count, error.last_message));
+        }
+        if self.simulated {
+            lines.push("Simulated dry-run trades only".to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+// Net spread of one route in one scan cycle, in percent after both legs' taker fees
+#[derive(Debug, Clone)]
+pub struct SpreadSample {
+    pub pair: String,
+    pub buy_exchange: String,
+    pub sell_exchange: String,
+    pub net_spread: Decimal,
+    // Against the pair's min_profit_threshold at the time
+    pub above_threshold: bool,
+    pub timestamp: DateTime<Utc>,
+}
+
+// One route's recorded spreads within one UTC hour, as sums so buckets can be merged
+#[derive(Debug, Clone)]
+pub struct SpreadBucket {
+    pub pair: String,
+    pub buy_exchange: String,
+    pub sell_exchange: String,
+    pub hour: DateTime<Utc>,
+    pub samples: i64,
+    pub net_sum: f64,
+    pub net_sum_squares: f64,
+    pub above_threshold: i64,
+}
+
+// Spreads are net percentages. The recent figures cover the rolling alert window up to the end of the range
+#[derive(Debug, Clone, Serialize)]
+pub struct RouteSpreadStats {
+    pub pair: String,
+    pub buy_exchange: String,
+    pub sell_exchange: String,
+    pub samples: i64,
+    pub mean: f64,
+    pub stddev: f64,
+    pub recent_mean: Option<f64>,
+    pub recent_stddev: Option<f64>,
+    pub above_threshold_percentage: f64,
+    // UTC hour of day with the widest mean spread
+    pub best_hour: Option<u32>,
+    pub best_hour_mean: Option<f64>,
+}
+
+// Why a route's spread jumped, judged against the venues outside the route
+#[derive(Debug, Clone, Serialize)]
+pub enum SpreadCause {
+    // One leg's mid is this far (percent) off the other venues' median while the other leg agrees with it
+    BadQuote { exchange: String, deviation_percentage: f64 },
+    // Both legs agree with the other venues, or both moved: the market itself moved
+    Dislocation,
+    // No other venue quotes the pair to compare against
+    Unconfirmed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpreadAlert {
+    pub pair: String,
+    pub buy_exchange: String,
+    pub sell_exchange: String,
+    pub net_spread: f64,
+    pub mean: f64,
+    pub stddev: f64,
+    pub samples: u64,
+    pub window_hours: u64,
+    pub cause: SpreadCause,
+}
+
+impl SpreadAlert {
+    pub fn deviations(&self) -> f64 {
+        (self.net_spread - self.mean) / self.stddev
+    }
+    
+    pub fn diagnosis(&self) -> String {
+        match &self.cause {
+            SpreadCause::BadQuote { exchange, deviation_percentage } => format!(
+                "{}'s mid is {:+.3}% off the other venues while the other leg agrees with them: most likely bad data from {}, not free money",
+                exchange, deviation_percentage, exchange,
+            ),
+            SpreadCause::Dislocation => "Both legs agree with the other venues: the market itself moved, so this may be a real opportunity".to_string(),
+            SpreadCause::Unconfirmed => format!("No other venue quotes {} to check against: treat it as suspect data until confirmed", self.pair),
+        }
+    }
+}
+
+// A token that failed its safety probe on a chain; `exchange` is the venue the probe ran on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlacklistedToken {
+    pub chain_id: u64,
+    pub token: String,
+    pub exchange: String,
+    pub reason: String,
+    pub blacklisted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradingHalt {
+    pub id: uuid::Uuid,
+    pub reason: String,
+    pub halted_at: DateTime<Utc>,
+    pub halted_until: DateTime<Utc>,
+    pub cleared_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Portfolio {
     pub total_value_usd: Decimal,
@@ -112,6 +487,167 @@ pub struct Balance {
     pub usd_value: Decimal,
 }
 
+// Holdings per exchange and summed per asset; exchanges whose balances could not be read are listed
+// in `failures` with the error instead
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BalanceReport {
+    pub exchanges: std::collections::HashMap<String, std::collections::HashMap<String, Balance>>,
+    pub totals: std::collections::HashMap<String, Balance>,
+    pub total_value_usd: Decimal,
+    pub failures: std::collections::HashMap<String, String>,
+}
+
+impl BalanceReport {
+    pub fn hide_zero_balances(&mut self) {
+        for balances in self.exchanges.values_mut() {
+            balances.retain(|_, balance| !balance.total.is_zero());
+        }
+        self.totals.retain(|_, balance| !balance.total.is_zero());
+    }
+}
+
+// Opportunities detected over a date range. Percentages are profit_percentage values; lifetimes run
+// from detection until the opportunity was executed, expired or failed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OpportunityStats {
+    pub total: i64,
+    pub active: i64,
+    pub executed: i64,
+    pub expired: i64,
+    pub failed: i64,
+    pub average_profit_percentage: Option<f64>,
+    pub max_profit_percentage: Option<f64>,
+    // From first detection to closing
+    pub average_lifetime_seconds: Option<f64>,
+    // Improvements on a route folded into its active row
+    pub updates: i64,
+    pub by_day: Vec<OpportunityCount>,
+    pub by_pair: Vec<OpportunityCount>,
+    pub by_route: Vec<OpportunityCount>,
+    pub profit_distribution: Vec<ProfitBucket>,
+}
+
+impl OpportunityStats {
+    // Share of the opportunities that ran their course which were executed rather than left to expire
+    pub fn execution_rate(&self) -> Option<f64> {
+        let settled = self.executed + self.expired;
+        (settled > 0).then(|| self.executed as f64 / settled as f64)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OpportunityCount {
+    pub key: String,
+    pub count: i64,
+    pub executed: i64,
+    pub average_profit_percentage: f64,
+}
+
+// Profit percentages in [from, to); an open bound extends without limit
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfitBucket {
+    pub from: Option<f64>,
+    pub to: Option<f64>,
+    pub count: i64,
+}
+
+// A venue's quote with its own bid/ask spread, in percent of the bid
+#[derive(Debug, Clone, Serialize)]
+pub struct VenueQuote {
+    #[serde(flatten)]
+    pub price: Price,
+    pub spread_percentage: Decimal,
+}
+
+impl VenueQuote {
+    pub fn new(price: Price) -> Self {
+        let spread_percentage = if price.bid > Decimal::ZERO {
+            (price.ask - price.bid) / price.bid * Decimal::ONE_HUNDRED
+        } else {
+            Decimal::ZERO
+        };
+        Self { price, spread_percentage }
+    }
+}
+
+// Buying at the lowest ask and selling at the highest bid on another venue. Margins are in percent;
+// the net one takes both taker fees at the top of the book and is None when a fee lookup failed
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanRoute {
+    pub buy_exchange: String,
+    pub sell_exchange: String,
+    pub gross_percentage: Decimal,
+    pub net_percentage: Option<Decimal>,
+}
+
+// One pair across every venue quoting it; the opportunities are the routes that clear the pair's
+// min_profit_threshold once sized against the order books
+#[derive(Debug, Clone, Serialize)]
+pub struct PairScan {
+    pub pair: String,
+    pub quotes: Vec<VenueQuote>,
+    pub best_route: Option<ScanRoute>,
+    pub min_profit_threshold: Decimal,
+    pub opportunities: Vec<ArbitrageOpportunity>,
+    pub error: Option<String>,
+}
+
+impl PairScan {
+    // Arbitrage needs a price from at least two venues
+    pub fn is_priced(&self) -> bool {
+        self.error.is_none() && self.quotes.len() >= 2
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanReport {
+    pub pairs: Vec<PairScan>,
+}
+
+impl ScanReport {
+    pub fn unpriced(&self) -> Vec<&str> {
+        self.pairs.iter()
+            .filter(|scan| !scan.is_priced())
+            .map(|scan| scan.pair.as_str())
+            .collect()
+    }
+}
+
+// A resting order as the venue reports it. `pair` is the canonical symbol for configured pairs and
+// the venue's own symbol for anything else on the account
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenOrder {
+    pub exchange: String,
+    pub order_id: String,
+    pub pair: String,
+    pub side: TradeSide,
+    pub price: Decimal,
+    pub filled: Decimal,
+    pub amount: Decimal,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OpenOrdersReport {
+    pub orders: Vec<OpenOrder>,
+    pub failures: std::collections::HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderCancellation {
+    pub exchange: String,
+    pub order_id: String,
+    // None when the order was cancelled
+    pub error: Option<String>,
+}
+
+// Exchanges whose open orders could not be listed are in `failures`; none of their orders were touched
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CancellationReport {
+    pub cancellations: Vec<OrderCancellation>,
+    pub failures: std::collections::HashMap<String, String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SmartContractCall {
     pub contract_address: String,
@@ -124,11 +660,83 @@ pub struct SmartContractCall {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CrossChainArbitrage {
+    pub id: uuid::Uuid,
+    pub pair: TradingPair,
+    // Buy the base on `buy_exchange`, bridge it from source to target chain, sell on `sell_exchange`
+    pub buy_exchange: String,
+    pub sell_exchange: String,
     pub source_chain: String,
     pub target_chain: String,
+    // The bridged base token on the source chain
     pub token_address: String,
+    pub buy_price: Decimal,
+    pub sell_price: Decimal,
+    // Base units bought on the source chain
     pub amount: Decimal,
-    pub profit_estimate: Decimal,
+    // Every fee below is in the pair's quote currency
+    pub buy_swap_fee: Decimal,
+    pub buy_gas_cost: Decimal,
     pub bridge_fees: Decimal,
+    pub sell_swap_fee: Decimal,
+    pub sell_gas_cost: Decimal,
+    pub profit_estimate: Decimal,
     pub estimated_time_minutes: u32,
-}
\ No newline at end of file
+    pub timestamp: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn book(bids: &[(&str, &str)], asks: &[(&str, &str)]) -> OrderBook {
+        let levels = |levels: &[(&str, &str)]| levels.iter()
+            .map(|(price, quantity)| OrderBookLevel {
+                price: Decimal::from_str(price).unwrap(),
+                quantity: Decimal::from_str(quantity).unwrap(),
+            })
+            .collect();
+        OrderBook {
+            exchange: "test".to_string(),
+            pair: TradingPair::new("ETH", "USDT"),
+            bids: levels(bids),
+            asks: levels(asks),
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn effective_price_walks_the_book() {
+        let book = book(&[("999", "1"), ("998", "3")], &[("1000", "1"), ("1002", "3")]);
+        
+        assert_eq!(book.effective_price(&TradeSide::Buy, Decimal::from(2)), Some(Decimal::from(1001)));
+        assert_eq!(book.effective_price(&TradeSide::Sell, Decimal::from(4)), Some(Decimal::from_str("998.25").unwrap()));
+        assert_eq!(book.effective_price(&TradeSide::Buy, Decimal::from(4)), Some(Decimal::from_str("1001.5").unwrap()));
+    }
+
+    #[test]
+    fn effective_price_of_an_empty_book() {
+        let book = book(&[], &[]);
+        
+        assert_eq!(book.effective_price(&TradeSide::Buy, Decimal::ONE), None);
+        assert_eq!(book.effective_price(&TradeSide::Sell, Decimal::ONE), None);
+    }
+
+    #[test]
+    fn effective_price_beyond_the_book() {
+        let book = book(&[("999", "1")], &[("1000", "1"), ("1001", "2")]);
+        
+        assert_eq!(book.effective_price(&TradeSide::Buy, Decimal::from_str("3.0001").unwrap()), None);
+        assert_eq!(book.effective_price(&TradeSide::Sell, Decimal::from(2)), None);
+        // The other side's depth does not count
+        assert_eq!(book.effective_price(&TradeSide::Sell, Decimal::from(3)), None);
+    }
+
+    #[test]
+    fn effective_price_of_no_quantity() {
+        let book = book(&[("999", "1")], &[("1000", "1")]);
+        
+        assert_eq!(book.effective_price(&TradeSide::Buy, Decimal::ZERO), None);
+        assert_eq!(book.effective_price(&TradeSide::Sell, Decimal::from(-1)), None);
+    }
+}
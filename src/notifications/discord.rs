@@ -0,0 +1,86 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Serialize;
+
+use crate::config::DiscordConfig;
+use crate::notifications::{NotificationEvent, NotificationLevel, Notifier};
+
+const COLOR_SUCCESS: u32 = 0x2ecc71;
+const COLOR_FAILURE: u32 = 0xe74c3c;
+const COLOR_INFO: u32 = 0x3498db;
+const COLOR_CRITICAL: u32 = 0x8b0000;
+
+pub struct DiscordNotifier {
+    config: DiscordConfig,
+    client: Client,
+}
+
+#[derive(Debug, Serialize)]
+struct WebhookRequest {
+    username: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    embeds: Vec<Embed>,
+}
+
+#[derive(Debug, Serialize)]
+struct Embed {
+    title: String,
+    description: String,
+    color: u32,
+    timestamp: String,
+}
+
+impl DiscordNotifier {
+    pub fn new(config: DiscordConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for DiscordNotifier {
+    fn name(&self) -> &str {
+        "discord"
+    }
+
+    async fn send(&self, event: &NotificationEvent) -> Result<()> {
+        let color = match event.level() {
+            NotificationLevel::Success => COLOR_SUCCESS,
+            NotificationLevel::Failure => COLOR_FAILURE,
+            NotificationLevel::Info => COLOR_INFO,
+            NotificationLevel::Critical => COLOR_CRITICAL,
+        };
+        // Critical events ping everyone in the channel rather than waiting to be noticed
+        let content = matches!(event.level(), NotificationLevel::Critical).then(|| "@here".to_string());
+        
+        let request = WebhookRequest {
+            username: "DeFi Arbitrage Bot".to_string(),
+            content,
+            embeds: vec![Embed {
+                title: event.title(),
+                description: event.body(),
+                color,
+                timestamp: chrono::Utc::now().to_rfc3339(),
+            }],
+        };
+        
+        // The webhook URL carries its token, so it is stripped from any request error
+        let response = self.client
+            .post(&self.config.webhook_url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| e.without_url())?;
+        
+        if !response.status().is_success() {
+            let error_text = response.text().await.map_err(|e| e.without_url())?;
+            anyhow::bail!("Discord webhook error: {}", error_text);
+        }
+        
+        Ok(())
+    }
+}
@@ -0,0 +1,396 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+pub mod discord;
+pub mod telegram;
+
+use crate::config::NotificationConfig;
+use crate::models::{ArbitrageOpportunity, DailyReport, SpreadAlert, SpreadCause, TradingHalt};
+
+#[derive(Debug, Clone, Copy)]
+pub enum NotificationLevel {
+    Success,
+    Failure,
+    Info,
+    // Needs someone to look now: delivered, where the channel can, with a ping
+    Critical,
+}
+
+#[derive(Debug, Clone)]
+pub enum NotificationEvent {
+    OpportunityFound(ArbitrageOpportunity),
+    TradeExecuted(ArbitrageOpportunity),
+    TradeFailed {
+        opportunity: ArbitrageOpportunity,
+        reason: String,
+    },
+    BotStarted {
+        dry_run: bool,
+    },
+    BotStopped,
+    Test,
+    RepeatedError {
+        context: String,
+        message: String,
+        count: u32,
+    },
+    TradingHalted(TradingHalt),
+    KillSwitch {
+        engaged: bool,
+    },
+    ControlAction {
+        action: String,
+        outcome: String,
+    },
+    StrandedInventory {
+        opportunity: ArbitrageOpportunity,
+        outcome: String,
+    },
+    ApprovalNeeded(ArbitrageOpportunity),
+    SpreadAnomaly(SpreadAlert),
+    DailySummary(DailyReport),
+}
+
+impl NotificationEvent {
+    pub fn level(&self) -> NotificationLevel {
+        match self {
+            NotificationEvent::OpportunityFound(_) | NotificationEvent::TradeExecuted(_) => NotificationLevel::Success,
+            NotificationEvent::TradeFailed { .. }
+            | NotificationEvent::RepeatedError { .. }
+            | NotificationEvent::TradingHalted(_)
+            | NotificationEvent::KillSwitch { engaged: true } => NotificationLevel::Failure,
+            NotificationEvent::BotStarted { .. }
+            | NotificationEvent::BotStopped
+            | NotificationEvent::Test
+            | NotificationEvent::KillSwitch { engaged: false }
+            | NotificationEvent::ControlAction { .. }
+            | NotificationEvent::DailySummary(_) => NotificationLevel::Info,
+            NotificationEvent::StrandedInventory { .. } | NotificationEvent::ApprovalNeeded(_) => NotificationLevel::Critical,
+            NotificationEvent::SpreadAnomaly(alert) => match alert.cause {
+                SpreadCause::BadQuote { .. } => NotificationLevel::Failure,
+                SpreadCause::Dislocation | SpreadCause::Unconfirmed => NotificationLevel::Info,
+            },
+        }
+    }
+    
+    pub fn title(&self) -> String {
+        match self {
+            NotificationEvent::OpportunityFound(opportunity) => {
+                format!("Opportunity found: {}", opportunity.pair.symbol)
+            },
+            NotificationEvent::TradeExecuted(opportunity) => {
+                format!("Trade executed: {}", opportunity.pair.symbol)
+            },
+            NotificationEvent::TradeFailed { opportunity, .. } => {
+                format!("Trade failed: {}", opportunity.pair.symbol)
+            },
+            NotificationEvent::BotStarted { .. } => "Bot started".to_string(),
+            NotificationEvent::BotStopped => "Bot stopped".to_string(),
+            NotificationEvent::Test => "Test notification".to_string(),
+            NotificationEvent::RepeatedError { context, .. } => {
+                format!("Repeated error: {}", context)
+            },
+            NotificationEvent::TradingHalted(_) => "Trading halted".to_string(),
+            NotificationEvent::KillSwitch { engaged: true } => "Kill switch engaged".to_string(),
+            NotificationEvent::KillSwitch { engaged: false } => "Kill switch released".to_string(),
+            NotificationEvent::ControlAction { action, .. } => format!("Control: {}", action),
+            NotificationEvent::StrandedInventory { opportunity, .. } => {
+                format!("Sell leg failed: {} left on {}", opportunity.pair.base, opportunity.buy_exchange)
+            },
+            NotificationEvent::ApprovalNeeded(opportunity) => {
+                format!("Suspicious spread: {} {:.2}%", opportunity.pair.symbol, opportunity.profit_percentage)
+            },
+            NotificationEvent::SpreadAnomaly(alert) => {
+                format!("Spread anomaly: {} {} -> {}", alert.pair, alert.buy_exchange, alert.sell_exchange)
+            },
+            NotificationEvent::DailySummary(report) => format!("Daily summary for {}", report.date),
+        }
+    }
+    
+    pub fn body(&self) -> String {
+        match self {
+            NotificationEvent::OpportunityFound(opportunity) | NotificationEvent::TradeExecuted(opportunity) => {
+                format!(
+                    "Buy {} on {} at {}\nSell {} on {} at {}\nSize: {}\nProfit: {:.2}% (${:.2})",
+                    opportunity.buy_pair.symbol,
+                    opportunity.buy_exchange,
+                    opportunity.buy_price,
+                    opportunity.sell_pair.symbol,
+                    opportunity.sell_exchange,
+                    opportunity.sell_price,
+                    opportunity.max_trade_size,
+                    opportunity.profit_percentage,
+                    opportunity.profit_amount,
+                )
+            },
+            NotificationEvent::TradeFailed { opportunity, reason } => {
+                format!(
+                    "Buy {} on {} / sell {} on {}\nSize: {}\nReason: {}",
+                    opportunity.buy_pair.symbol,
+                    opportunity.buy_exchange,
+                    opportunity.sell_pair.symbol,
+                    opportunity.sell_exchange,
+                    opportunity.max_trade_size,
+                    reason,
+                )
+            },
+            NotificationEvent::BotStarted { dry_run } => {
+                if *dry_run {
+                    "Running in dry-run mode".to_string()
+                } else {
+                    "Running in live trading mode".to_string()
+                }
+            },
+            NotificationEvent::BotStopped => "Main loop has exited".to_string(),
+            NotificationEvent::Test => "Notifications are configured correctly".to_string(),
+            NotificationEvent::RepeatedError { message, count, .. } => {
+                format!("Failed {} times in a row: {}", count, message)
+            },
+            NotificationEvent::TradingHalted(halt) => {
+                format!("{}\nHalted until {} (run `resume` to lift it earlier)",
+                        halt.reason, halt.halted_until.format("%Y-%m-%d %H:%M UTC"))
+            },
+            NotificationEvent::KillSwitch { engaged: true } => {
+                "Scanning continues, no new trades will be executed and open orders are being cancelled".to_string()
+            },
+            NotificationEvent::KillSwitch { engaged: false } => "Trade execution has resumed".to_string(),
+            NotificationEvent::ControlAction { outcome, .. } => outcome.clone(),
+            NotificationEvent::StrandedInventory { opportunity, outcome } => {
+                format!(
+                    "Bought {} on {} but the sell on {} failed\nOpportunity: {}\n{}",
+                    opportunity.buy_pair.symbol,
+                    opportunity.buy_exchange,
+                    opportunity.sell_exchange,
+                    opportunity.id,
+                    outcome,
+                )
+            },
+            NotificationEvent::ApprovalNeeded(opportunity) => {
+                format!(
+                    "Buy {} on {} at {}\nSell {} on {} at {}\nSize: {}\nA spread this wide usually means a halt, delisting or suspended transfers. \
+                     It will not be executed unless approved with `approve {}`",
+                    opportunity.buy_pair.symbol,
+                    opportunity.buy_exchange,
+                    opportunity.buy_price,
+                    opportunity.sell_pair.symbol,
+                    opportunity.sell_exchange,
+                    opportunity.sell_price,
+                    opportunity.max_trade_size,
+                    opportunity.id,
+                )
+            },
+            NotificationEvent::SpreadAnomaly(alert) => {
+                format!(
+                    "Net spread {:.3}% is {:.1} standard deviations above its {}h mean of {:.3}% ({} samples)\n{}",
+                    alert.net_spread,
+                    alert.deviations(),
+                    alert.window_hours,
+                    alert.mean,
+                    alert.samples,
+                    alert.diagnosis(),
+                )
+            },
+            NotificationEvent::DailySummary(report) => report.summary(),
+        }
+    }
+    
+    pub fn to_text(&self) -> String {
+        format!("{}\n{}", self.title(), self.body())
+    }
+}
+
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    fn name(&self) -> &str;
+    
+    async fn send(&self, event: &NotificationEvent) -> Result<()>;
+}
+
+pub struct NotificationService {
+    notifiers: Arc<Vec<Box<dyn Notifier>>>,
+    notify_threshold: Decimal,
+    error_repeat_threshold: u32,
+    dedup_window: chrono::Duration,
+    last_sent: Mutex<HashMap<String, DateTime<Utc>>>,
+    error_counts: Mutex<HashMap<String, u32>>,
+}
+
+impl NotificationService {
+    pub fn new(config: Option<&NotificationConfig>) -> Self {
+        let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+        
+        if let Some(config) = config {
+            if let Some(telegram) = &config.telegram {
+                notifiers.push(Box::new(telegram::TelegramNotifier::new(telegram.clone())));
+            }
+            if let Some(discord) = &config.discord {
+                notifiers.push(Box::new(discord::DiscordNotifier::new(discord.clone())));
+            }
+        }
+        
+        Self {
+            notifiers: Arc::new(notifiers),
+            notify_threshold: config.map(|c| c.notify_threshold).unwrap_or_default(),
+            error_repeat_threshold: config.map(|c| c.error_repeat_threshold).unwrap_or(u32::MAX),
+            dedup_window: chrono::Duration::seconds(config.map(|c| c.dedup_window_seconds).unwrap_or(300) as i64),
+            last_sent: Mutex::new(HashMap::new()),
+            error_counts: Mutex::new(HashMap::new()),
+        }
+    }
+    
+    pub fn is_enabled(&self) -> bool {
+        !self.notifiers.is_empty()
+    }
+    
+    pub fn opportunity_found(&self, opportunity: &ArbitrageOpportunity) {
+        if opportunity.profit_percentage < self.notify_threshold {
+            return;
+        }
+        
+        let key = format!("opportunity:{}-{}-{}",
+                          opportunity.pair.symbol, opportunity.buy_exchange, opportunity.sell_exchange);
+        if self.should_send(&key) {
+            self.dispatch(NotificationEvent::OpportunityFound(opportunity.clone()));
+        }
+    }
+    
+    pub fn trade_executed(&self, opportunity: &ArbitrageOpportunity) {
+        self.dispatch(NotificationEvent::TradeExecuted(opportunity.clone()));
+    }
+    
+    pub fn trade_failed(&self, opportunity: &ArbitrageOpportunity, error: &anyhow::Error) {
+        self.dispatch(NotificationEvent::TradeFailed {
+            opportunity: opportunity.clone(),
+            reason: error.to_string(),
+        });
+    }
+    
+    pub fn stranded_inventory(&self, opportunity: &ArbitrageOpportunity, outcome: &str) {
+        self.dispatch(NotificationEvent::StrandedInventory {
+            opportunity: opportunity.clone(),
+            outcome: outcome.to_string(),
+        });
+    }
+    
+    // Deduplicated per route like opportunity_found, but regardless of notify_threshold
+    pub fn approval_needed(&self, opportunity: &ArbitrageOpportunity) {
+        let key = format!("approval:{}-{}-{}",
+                          opportunity.pair.symbol, opportunity.buy_exchange, opportunity.sell_exchange);
+        if self.should_send(&key) {
+            self.dispatch(NotificationEvent::ApprovalNeeded(opportunity.clone()));
+        }
+    }
+    
+    pub fn spread_anomaly(&self, alert: &SpreadAlert) {
+        let key = format!("spread:{}-{}-{}", alert.pair, alert.buy_exchange, alert.sell_exchange);
+        if self.should_send(&key) {
+            self.dispatch(NotificationEvent::SpreadAnomaly(alert.clone()));
+        }
+    }
+    
+    pub fn daily_summary(&self, report: &DailyReport) {
+        self.dispatch(NotificationEvent::DailySummary(report.clone()));
+    }
+    
+    // Awaited, for the `report` command, which exits right after
+    pub async fn send_daily_summary(&self, report: &DailyReport) -> Vec<(String, Result<()>)> {
+        let event = NotificationEvent::DailySummary(report.clone());
+        let mut results = Vec::new();
+        for notifier in self.notifiers.iter() {
+            results.push((notifier.name().to_string(), notifier.send(&event).await));
+        }
+        results
+    }
+    
+    pub fn trading_halted(&self, halt: &TradingHalt) {
+        self.dispatch(NotificationEvent::TradingHalted(halt.clone()));
+    }
+    
+    pub fn kill_switch(&self, engaged: bool) {
+        self.dispatch(NotificationEvent::KillSwitch { engaged });
+    }
+    
+    pub fn control_action(&self, action: &str, outcome: &str) {
+        self.dispatch(NotificationEvent::ControlAction {
+            action: action.to_string(),
+            outcome: outcome.to_string(),
+        });
+    }
+    
+    pub fn bot_started(&self, dry_run: bool) {
+        self.dispatch(NotificationEvent::BotStarted { dry_run });
+    }
+    
+    // Awaited rather than spawned so the message goes out before the process exits
+    pub async fn bot_stopped(&self) {
+        for notifier in self.notifiers.iter() {
+            if let Err(e) = notifier.send(&NotificationEvent::BotStopped).await {
+                warn!("Failed to send {} notification: {}", notifier.name(), e);
+            }
+        }
+    }
+    
+    pub async fn send_test(&self) -> Vec<(String, Result<()>)> {
+        let mut results = Vec::new();
+        for notifier in self.notifiers.iter() {
+            let result = notifier.send(&NotificationEvent::Test).await;
+            results.push((notifier.name().to_string(), result));
+        }
+        results
+    }
+    
+    pub fn record_error(&self, context: &str, error: &anyhow::Error) {
+        let count = {
+            let mut error_counts = self.error_counts.lock().unwrap();
+            let count = error_counts.entry(context.to_string()).or_insert(0);
+            *count += 1;
+            *count
+        };
+        
+        if count >= self.error_repeat_threshold && self.should_send(&format!("error:{}", context)) {
+            self.dispatch(NotificationEvent::RepeatedError {
+                context: context.to_string(),
+                message: error.to_string(),
+                count,
+            });
+        }
+    }
+    
+    pub fn clear_error(&self, context: &str) {
+        self.error_counts.lock().unwrap().remove(context);
+    }
+    
+    fn should_send(&self, key: &str) -> bool {
+        let now = Utc::now();
+        let mut last_sent = self.last_sent.lock().unwrap();
+        
+        match last_sent.get(key) {
+            Some(sent_at) if now.signed_duration_since(*sent_at) < self.dedup_window => false,
+            _ => {
+                last_sent.insert(key.to_string(), now);
+                true
+            }
+        }
+    }
+    
+    // Delivery runs in the background so a slow or failing channel never stalls the trading loop
+    fn dispatch(&self, event: NotificationEvent) {
+        if self.notifiers.is_empty() {
+            return;
+        }
+        
+        let notifiers = self.notifiers.clone();
+        tokio::spawn(async move {
+            for notifier in notifiers.iter() {
+                if let Err(e) = notifier.send(&event).await {
+                    warn!("Failed to send {} notification: {}", notifier.name(), e);
+                }
+            }
+        });
+    }
+}
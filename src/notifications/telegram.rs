@@ -0,0 +1,58 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Serialize;
+
+use crate::config::TelegramConfig;
+use crate::notifications::{NotificationEvent, Notifier};
+
+pub struct TelegramNotifier {
+    config: TelegramConfig,
+    client: Client,
+}
+
+#[derive(Debug, Serialize)]
+struct SendMessageRequest<'a> {
+    chat_id: &'a str,
+    text: String,
+    disable_web_page_preview: bool,
+}
+
+impl TelegramNotifier {
+    pub fn new(config: TelegramConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    fn name(&self) -> &str {
+        "telegram"
+    }
+
+    // The bot token is part of the URL, so it is stripped from any request error
+    async fn send(&self, event: &NotificationEvent) -> Result<()> {
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", self.config.bot_token);
+        
+        let response = self.client
+            .post(&url)
+            .json(&SendMessageRequest {
+                chat_id: &self.config.chat_id,
+                text: event.to_text(),
+                disable_web_page_preview: true,
+            })
+            .send()
+            .await
+            .map_err(|e| e.without_url())?;
+        
+        if !response.status().is_success() {
+            let error_text = response.text().await.map_err(|e| e.without_url())?;
+            anyhow::bail!("Telegram API error: {}", error_text);
+        }
+        
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, Notify};
+use tracing::{debug, info, warn};
+
+use crate::balances::BalanceManager;
+use crate::exchanges::AccountEvent;
+use crate::models::Trade;
+
+// Streamed updates nobody waited for (orders placed elsewhere on the account) are dropped after this
+const UPDATE_RETENTION: Duration = Duration::from_secs(300);
+
+// Latest streamed state of each order, held until the execution waiting on it takes it. Updates are
+// kept even when nobody is waiting yet, since a fill is often streamed before the placement reply.
+#[derive(Default)]
+pub struct OrderTracker {
+    updates: Mutex<HashMap<(String, String), (Instant, Trade)>>,
+    streaming: Mutex<HashSet<String>>,
+    // Bumped on every reconnect so waiters re-read order state over REST
+    connections: Mutex<HashMap<String, u64>>,
+    changed: Notify,
+}
+
+impl OrderTracker {
+    pub fn is_streaming(&self, exchange: &str) -> bool {
+        self.streaming.lock().unwrap().contains(exchange)
+    }
+
+    pub fn record(&self, trade: Trade) {
+        let Some(order_id) = trade.order_id.clone() else {
+            return;
+        };
+        let now = Instant::now();
+
+        let mut updates = self.updates.lock().unwrap();
+        updates.retain(|_, (received_at, _)| now.duration_since(*received_at) < UPDATE_RETENTION);
+        updates.insert((trade.exchange.clone(), order_id), (now, trade));
+        drop(updates);
+        self.changed.notify_waiters();
+    }
+
+    pub fn connected(&self, exchange: &str) {
+        self.streaming.lock().unwrap().insert(exchange.to_string());
+        *self.connections.lock().unwrap().entry(exchange.to_string()).or_default() += 1;
+        self.changed.notify_waiters();
+    }
+
+    pub fn disconnected(&self, exchange: &str) {
+        self.streaming.lock().unwrap().remove(exchange);
+        self.changed.notify_waiters();
+    }
+
+    fn connection(&self, exchange: &str) -> u64 {
+        self.connections.lock().unwrap().get(exchange).copied().unwrap_or_default()
+    }
+
+    fn take(&self, exchange: &str, order_id: &str) -> Option<Trade> {
+        self.updates.lock().unwrap()
+            .remove(&(exchange.to_string(), order_id.to_string()))
+            .map(|(_, trade)| trade)
+    }
+
+    // Resolves with the next streamed update of the order, or None once `timeout` passes, the stream
+    // drops, or it reconnects; in all of those the caller should poll the order over REST
+    pub async fn wait_for_update(&self, exchange: &str, order_id: &str, timeout: Duration) -> Option<Trade> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        let connection = self.connection(exchange);
+        let was_streaming = self.is_streaming(exchange);
+
+        loop {
+            // Registered before checking so an update recorded in between still wakes this waiter
+            let changed = self.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+
+            if let Some(trade) = self.take(exchange, order_id) {
+                return Some(trade);
+            }
+            if self.connection(exchange) != connection || (was_streaming && !self.is_streaming(exchange)) {
+                return None;
+            }
+            if tokio::time::timeout_at(deadline, changed).await.is_err() {
+                return None;
+            }
+        }
+    }
+}
+
+// Applies account stream events until every exchange has dropped its sender
+pub async fn track_account_events(mut events: mpsc::Receiver<AccountEvent>, tracker: Arc<OrderTracker>, balances: Arc<BalanceManager>) {
+    while let Some(event) = events.recv().await {
+        match event {
+            AccountEvent::Connected { exchange } => {
+                info!("Account stream for {} connected", exchange);
+                // Whatever changed while disconnected is unknown, so the next lookup refetches
+                balances.invalidate(&exchange);
+                tracker.connected(&exchange);
+            },
+            AccountEvent::Disconnected { exchange } => {
+                warn!("Account stream for {} disconnected, falling back to polling", exchange);
+                tracker.disconnected(&exchange);
+            },
+            AccountEvent::Order(trade) => {
+                debug!("Streamed {:?} update for order {:?} on {}", trade.status, trade.order_id, trade.exchange);
+                tracker.record(trade);
+            },
+            AccountEvent::Balances { exchange, free } => balances.apply_update(&exchange, free),
+        }
+    }
+}
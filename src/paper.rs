@@ -0,0 +1,122 @@
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+use crate::exchanges::TradingFees;
+use crate::models::{OrderBook, Trade, TradeSide, TradeStatus, TradingPair};
+
+// Simulated balance ledger used in dry-run mode, keyed by exchange and then asset
+#[derive(Debug, Default)]
+pub struct PaperPortfolio {
+    balances: HashMap<String, HashMap<String, Decimal>>,
+    realized_pnl: Decimal,
+    simulated_opportunities: u64,
+}
+
+impl PaperPortfolio {
+    pub fn is_seeded(&self, exchange: &str) -> bool {
+        self.balances.contains_key(exchange)
+    }
+
+    pub fn seed(&mut self, exchange: &str, balances: HashMap<String, Decimal>) {
+        self.balances.insert(exchange.to_string(), balances);
+    }
+
+    pub fn balance(&self, exchange: &str, asset: &str) -> Decimal {
+        self.balances.get(exchange)
+            .and_then(|balances| balances.get(asset))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn balances(&self) -> &HashMap<String, HashMap<String, Decimal>> {
+        &self.balances
+    }
+
+    pub fn realized_pnl(&self) -> Decimal {
+        self.realized_pnl
+    }
+
+    pub fn simulated_opportunities(&self) -> u64 {
+        self.simulated_opportunities
+    }
+
+    // Fills against the order book at its volume-weighted price, charging the taker fee in the quote asset
+    pub fn simulate_fill(
+        &mut self,
+        exchange: &str,
+        book: &OrderBook,
+        side: TradeSide,
+        quantity: Decimal,
+        fees: &TradingFees,
+    ) -> Result<Trade> {
+        let pair = &book.pair;
+        let price = book.effective_price(&side, quantity)
+            .ok_or_else(|| anyhow::anyhow!("Order book on {} too thin to fill {} {}", exchange, quantity, pair.base))?;
+        
+        let notional = quantity * price;
+        let fee = notional * fees.taker_fee;
+        
+        match side {
+            TradeSide::Buy => {
+                let available = self.balance(exchange, &pair.quote);
+                if available < notional + fee {
+                    anyhow::bail!("Insufficient simulated {} on {}: have {}, need {}",
+                                  pair.quote, exchange, available, notional + fee);
+                }
+                self.adjust(exchange, &pair.quote, -(notional + fee));
+                self.adjust(exchange, &pair.base, quantity);
+            },
+            TradeSide::Sell => {
+                let available = self.balance(exchange, &pair.base);
+                if available < quantity {
+                    anyhow::bail!("Insufficient simulated {} on {}: have {}, need {}",
+                                  pair.base, exchange, available, quantity);
+                }
+                self.adjust(exchange, &pair.base, -quantity);
+                self.adjust(exchange, &pair.quote, notional - fee);
+            },
+        }
+        
+        Ok(simulated_trade(exchange, pair, side, quantity, price, fee))
+    }
+
+    pub fn record_round_trip(&mut self, buy: &Trade, sell: &Trade) -> Decimal {
+        let matched = buy.amount.min(sell.amount);
+        let pnl = matched * (sell.price - buy.price) - buy.fee_amount - sell.fee_amount;
+        
+        self.realized_pnl += pnl;
+        self.simulated_opportunities += 1;
+        pnl
+    }
+
+    fn adjust(&mut self, exchange: &str, asset: &str, delta: Decimal) {
+        *self.balances.entry(exchange.to_string()).or_default()
+            .entry(asset.to_string()).or_default() += delta;
+    }
+}
+
+fn simulated_trade(exchange: &str, pair: &TradingPair, side: TradeSide, amount: Decimal, price: Decimal, fee: Decimal) -> Trade {
+    let now = Utc::now();
+
+    Trade {
+        id: uuid::Uuid::new_v4(),
+        opportunity_id: uuid::Uuid::nil(),
+        exchange: exchange.to_string(),
+        pair: pair.clone(),
+        side,
+        amount,
+        price,
+        fee_amount: fee,
+        fee_asset: Some(pair.quote.clone()),
+        status: TradeStatus::Executed,
+        created_at: now,
+        executed_at: Some(now),
+        order_id: None,
+        client_order_id: None,
+        tx_hash: None,
+        simulated: true,
+        failure_reason: None,
+    }
+}
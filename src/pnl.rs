@@ -0,0 +1,173 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::models::{Trade, TradeSide, TradeStatus};
+
+#[derive(Debug, Clone)]
+pub struct OpportunityPnl {
+    pub opportunity_id: uuid::Uuid,
+    pub date: NaiveDate,
+    pub pair: String,
+    pub route: String,
+    pub matched_quantity: Decimal,
+    pub buy_cost: Decimal,
+    pub sell_proceeds: Decimal,
+    pub fees: Decimal,
+    pub realized: Decimal,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PnlBucket {
+    pub opportunities: usize,
+    pub fees: Decimal,
+    pub realized: Decimal,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InventoryChange {
+    pub quantity: Decimal,
+    pub cost_basis: Decimal,
+    pub last_price: Decimal,
+}
+
+impl InventoryChange {
+    // Marked at the most recent fill price; positive when inventory was accumulated
+    pub fn unrealized(&self) -> Decimal {
+        self.quantity * self.last_price - self.cost_basis
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PnlSummary {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub opportunities: Vec<OpportunityPnl>,
+    pub by_day: BTreeMap<NaiveDate, PnlBucket>,
+    pub by_pair: BTreeMap<String, PnlBucket>,
+    pub by_route: BTreeMap<String, PnlBucket>,
+    pub inventory: BTreeMap<String, InventoryChange>,
+    pub unpriced_fees: BTreeMap<String, Decimal>,
+    pub total_fees: Decimal,
+    pub total_realized: Decimal,
+}
+
+#[derive(Default)]
+struct LegTotals {
+    quantity: Decimal,
+    notional: Decimal,
+    exchange: Option<String>,
+}
+
+impl LegTotals {
+    fn add(&mut self, trade: &Trade) {
+        self.quantity += trade.amount;
+        self.notional += trade.amount * trade.price;
+        self.exchange.get_or_insert_with(|| trade.exchange.clone());
+    }
+
+    fn average_price(&self) -> Decimal {
+        if self.quantity > Decimal::ZERO {
+            self.notional / self.quantity
+        } else {
+            Decimal::ZERO
+        }
+    }
+}
+
+pub fn summarize(from: DateTime<Utc>, to: DateTime<Utc>, trades: &[Trade]) -> PnlSummary {
+    let mut grouped: HashMap<uuid::Uuid, Vec<&Trade>> = HashMap::new();
+    for trade in trades.iter().filter(|trade| matches!(trade.status, TradeStatus::Executed)) {
+        grouped.entry(trade.opportunity_id).or_default().push(trade);
+    }
+
+    let mut summary = PnlSummary {
+        from,
+        to,
+        opportunities: Vec::new(),
+        by_day: BTreeMap::new(),
+        by_pair: BTreeMap::new(),
+        by_route: BTreeMap::new(),
+        inventory: BTreeMap::new(),
+        unpriced_fees: BTreeMap::new(),
+        total_fees: Decimal::ZERO,
+        total_realized: Decimal::ZERO,
+    };
+
+    for (opportunity_id, legs) in grouped {
+        let pair = legs[0].pair.clone();
+        let date = legs.iter().map(|trade| trade.executed_at.unwrap_or(trade.created_at)).min()
+            .unwrap_or(from)
+            .date_naive();
+        
+        let mut bought = LegTotals::default();
+        let mut sold = LegTotals::default();
+        let mut fees = Decimal::ZERO;
+        
+        for trade in &legs {
+            match trade.side {
+                TradeSide::Buy => bought.add(trade),
+                TradeSide::Sell => sold.add(trade),
+            }
+            
+            match trade.fee_asset.as_deref() {
+                None => fees += trade.fee_amount,
+                Some(asset) if asset == pair.quote => fees += trade.fee_amount,
+                Some(asset) if asset == pair.base => fees += trade.fee_amount * trade.price,
+                Some(asset) => *summary.unpriced_fees.entry(asset.to_string()).or_default() += trade.fee_amount,
+            }
+        }
+        
+        // Only the quantity that was both bought and sold counts as captured spread
+        let matched = bought.quantity.min(sold.quantity);
+        let buy_cost = matched * bought.average_price();
+        let sell_proceeds = matched * sold.average_price();
+        let realized = sell_proceeds - buy_cost - fees;
+        
+        let residual = bought.quantity - sold.quantity;
+        if residual != Decimal::ZERO {
+            let price = if residual > Decimal::ZERO {
+                bought.average_price()
+            } else {
+                sold.average_price()
+            };
+            let inventory = summary.inventory.entry(pair.base.clone()).or_default();
+            inventory.quantity += residual;
+            inventory.cost_basis += residual * price;
+            inventory.last_price = price;
+        }
+        
+        let route = format!(
+            "{} -> {}",
+            bought.exchange.as_deref().unwrap_or("-"),
+            sold.exchange.as_deref().unwrap_or("-"),
+        );
+        
+        for bucket in [
+            summary.by_day.entry(date).or_default(),
+            summary.by_pair.entry(pair.symbol.clone()).or_default(),
+            summary.by_route.entry(route.clone()).or_default(),
+        ] {
+            bucket.opportunities += 1;
+            bucket.fees += fees;
+            bucket.realized += realized;
+        }
+        
+        summary.total_fees += fees;
+        summary.total_realized += realized;
+        summary.opportunities.push(OpportunityPnl {
+            opportunity_id,
+            date,
+            pair: pair.symbol.clone(),
+            route,
+            matched_quantity: matched,
+            buy_cost,
+            sell_proceeds,
+            fees,
+            realized,
+        });
+    }
+
+    summary.opportunities.sort_by_key(|opportunity| opportunity.date);
+    summary
+}
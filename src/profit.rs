@@ -0,0 +1,180 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const BPS_PER_UNIT: i64 = 10_000;
+const BPS_PER_PERCENT: i64 = 100;
+
+// Rates in basis points. Venues report fees as fractions and the config speaks percent; both are
+// converted at the boundary so the profit math never mixes the two.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Bps(Decimal);
+
+impl Bps {
+    pub const ZERO: Bps = Bps(Decimal::ZERO);
+
+    pub fn from_fraction(fraction: Decimal) -> Self {
+        Bps(fraction * Decimal::from(BPS_PER_UNIT))
+    }
+
+    pub fn from_percent(percent: Decimal) -> Self {
+        Bps(percent * Decimal::from(BPS_PER_PERCENT))
+    }
+
+    pub fn as_fraction(self) -> Decimal {
+        self.0 / Decimal::from(BPS_PER_UNIT)
+    }
+
+    pub fn as_percent(self) -> Decimal {
+        self.0 / Decimal::from(BPS_PER_PERCENT)
+    }
+
+    // The share of `notional` this rate amounts to
+    pub fn of(self, notional: Decimal) -> Decimal {
+        notional * self.as_fraction()
+    }
+
+    // `amount` as a rate on `notional`; an empty notional earns nothing
+    pub fn ratio(amount: Decimal, notional: Decimal) -> Self {
+        if notional <= Decimal::ZERO {
+            return Bps::ZERO;
+        }
+        Bps::from_fraction(amount / notional)
+    }
+}
+
+impl fmt::Display for Bps {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1}bps", self.0)
+    }
+}
+
+// One side of a round trip: the average fill price and the taker fee charged on that leg's notional
+#[derive(Debug, Clone, Copy)]
+pub struct Leg {
+    pub price: Decimal,
+    pub fee: Bps,
+}
+
+impl Leg {
+    pub fn new(price: Decimal, fee: Bps) -> Self {
+        Self { price, fee }
+    }
+}
+
+// Margin per unit of base after both legs' fees, for screening quotes before sizing
+pub fn unit_margin(buy: Leg, sell: Leg) -> Bps {
+    ProfitBreakdown::compute(Decimal::ONE, buy, sell, Decimal::ZERO, Decimal::ZERO).net_margin()
+}
+
+// All amounts are in the pair's quote asset
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfitBreakdown {
+    pub size: Decimal,
+    pub buy_notional: Decimal,
+    pub sell_notional: Decimal,
+    pub gross: Decimal,
+    pub buy_fee: Decimal,
+    pub sell_fee: Decimal,
+    pub gas_cost: Decimal,
+    pub transfer_cost: Decimal,
+    pub net: Decimal,
+}
+
+impl ProfitBreakdown {
+    pub fn compute(size: Decimal, buy: Leg, sell: Leg, gas_cost: Decimal, transfer_cost: Decimal) -> Self {
+        let buy_notional = size * buy.price;
+        let sell_notional = size * sell.price;
+        let gross = sell_notional - buy_notional;
+        let buy_fee = buy.fee.of(buy_notional);
+        let sell_fee = sell.fee.of(sell_notional);
+        
+        Self {
+            size,
+            buy_notional,
+            sell_notional,
+            gross,
+            buy_fee,
+            sell_fee,
+            gas_cost,
+            transfer_cost,
+            net: gross - buy_fee - sell_fee - gas_cost - transfer_cost,
+        }
+    }
+
+    // Net before the amortized transfer cost, which only has to be earned back eventually
+    pub fn net_before_transfers(&self) -> Decimal {
+        self.net + self.transfer_cost
+    }
+
+    // Margins are measured against the capital committed on the buy leg
+    pub fn gross_margin(&self) -> Bps {
+        Bps::ratio(self.gross, self.buy_notional)
+    }
+
+    pub fn net_margin(&self) -> Bps {
+        Bps::ratio(self.net, self.buy_notional)
+    }
+}
+
+impl fmt::Display for ProfitBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "gross {:.4} ({}) - fees {:.4} buy / {:.4} sell - gas {:.4} - transfers {:.4} = net {:.4} ({})",
+               self.gross, self.gross_margin(), self.buy_fee, self.sell_fee,
+               self.gas_cost, self.transfer_cost, self.net, self.net_margin())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    // Worked examples for the profit math, including the fee-units mix-up it replaced
+    #[test]
+    fn works_through_fee_examples() {
+        assert!(Bps::from_fraction(dec("0.001")) == Bps::from_percent(dec("0.1")),
+               "0.001 as a fraction and 0.1% are not the same rate");
+        assert!(Bps::from_percent(dec("0.5")).as_fraction() == dec("0.005"),
+               "0.5% did not convert to a 0.005 fraction");
+        
+        // 2 ETH bought at 1000 with a 10bps fee, sold at 1010 with a 30bps fee, $1.50 gas and $0.50 transfers
+        let breakdown = ProfitBreakdown::compute(
+            dec("2"),
+            Leg::new(dec("1000"), Bps::from_fraction(dec("0.001"))),
+            Leg::new(dec("1010"), Bps::from_fraction(dec("0.003"))),
+            dec("1.5"),
+            dec("0.5"),
+        );
+        let expected = [
+            ("gross", breakdown.gross, dec("20")),
+            ("buy fee", breakdown.buy_fee, dec("2")),
+            ("sell fee", breakdown.sell_fee, dec("6.06")),
+            ("net", breakdown.net, dec("9.94")),
+            ("net before transfers", breakdown.net_before_transfers(), dec("10.44")),
+            ("net margin (percent)", breakdown.net_margin().as_percent(), dec("0.497")),
+        ];
+        for (name, actual, wanted) in expected {
+            assert!(actual == wanted, "{} was {}, expected {}", name, actual, wanted);
+        }
+        
+        // The sell fee is charged on the sell leg's own notional, not on the buy leg's
+        let wide = ProfitBreakdown::compute(
+            Decimal::ONE,
+            Leg::new(dec("100"), Bps::ZERO),
+            Leg::new(dec("200"), Bps::from_percent(Decimal::ONE)),
+            Decimal::ZERO,
+            Decimal::ZERO,
+        );
+        assert!(wide.sell_fee == dec("2"),
+               "a 1% fee on a 200 sell leg came to {}", wide.sell_fee);
+        
+        let margin = unit_margin(Leg::new(dec("1000"), Bps::from_percent(dec("0.1"))), Leg::new(dec("1002"), Bps::from_percent(dec("0.1"))));
+        assert!(margin < Bps::ZERO,
+               "a 0.2% spread under 0.2% of fees screened as {}", margin);
+    }
+}
@@ -0,0 +1,164 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use tracing::warn;
+
+use crate::config::RebalancingConfig;
+use crate::database::Database;
+use crate::exchanges::ExchangeManager;
+use crate::models::{Trade, TradeSide, TradeStatus};
+
+#[derive(Debug, Clone)]
+pub struct Transfer {
+    pub asset: String,
+    pub from: String,
+    pub to: String,
+    // Debited from the source; the destination receives it less the withdrawal fee
+    pub amount: Decimal,
+    pub fee: Decimal,
+    pub address: String,
+}
+
+#[derive(Debug, Default)]
+pub struct RebalancePlan {
+    // Net change per (exchange, asset) from trades executed within the flow window
+    pub flows: BTreeMap<(String, String), Decimal>,
+    pub transfers: Vec<Transfer>,
+    // Imbalances that cannot be fixed right now, with the reason
+    pub skipped: Vec<String>,
+}
+
+// Two-leg arbitrage accumulates base on the buy venue and quote on the sell venue
+pub fn net_flows(trades: &[Trade]) -> BTreeMap<(String, String), Decimal> {
+    let mut flows: BTreeMap<(String, String), Decimal> = BTreeMap::new();
+
+    for trade in trades.iter().filter(|trade| matches!(trade.status, TradeStatus::Executed) && !trade.simulated) {
+        let notional = trade.amount * trade.price;
+        let (base, quote) = match trade.side {
+            TradeSide::Buy => (trade.amount, -notional),
+            TradeSide::Sell => (-trade.amount, notional),
+        };
+        
+        *flows.entry((trade.exchange.clone(), trade.pair.base.clone())).or_default() += base;
+        *flows.entry((trade.exchange.clone(), trade.pair.quote.clone())).or_default() += quote;
+        if let Some(fee_asset) = &trade.fee_asset {
+            *flows.entry((trade.exchange.clone(), fee_asset.clone())).or_default() -= trade.fee_amount;
+        }
+    }
+
+    flows
+}
+
+// Aims for an even split of every asset across the executable venues that trade it, moving at
+// most one transfer per asset per plan from the fullest venue to the emptiest
+pub struct Rebalancer {
+    config: RebalancingConfig,
+}
+
+impl Rebalancer {
+    pub fn new(config: RebalancingConfig) -> Self {
+        Self { config }
+    }
+
+    pub async fn plan(&self, manager: &ExchangeManager, database: &Database) -> Result<RebalancePlan> {
+        let now = Utc::now();
+        let trades = database
+            .get_trades_between(now - Duration::hours(self.config.flow_window_hours as i64), now, false)
+            .await?;
+        let mut plan = RebalancePlan {
+            flows: net_flows(&trades),
+            ..Default::default()
+        };
+        
+        let mut holdings: BTreeMap<String, BTreeMap<String, Decimal>> = BTreeMap::new();
+        for exchange in manager.get_all_exchanges() {
+            if !manager.can_execute(exchange.name()) {
+                continue;
+            }
+            
+            let pairs = exchange.get_supported_pairs().await?;
+            let balances = match manager.call(
+                exchange.name(), "get_balances", manager.timeouts().account(),
+                exchange.get_balances(),
+            ).await {
+                Ok(balances) => balances,
+                Err(e) => {
+                    plan.skipped.push(format!("{}: balances unavailable: {}", exchange.name(), e));
+                    continue;
+                }
+            };
+            
+            for pair in pairs {
+                for asset in [&pair.base, &pair.quote] {
+                    let free = balances.get(asset).map(|balance| balance.free).unwrap_or_default();
+                    holdings.entry(asset.clone()).or_default().insert(exchange.name().to_string(), free);
+                }
+            }
+        }
+        
+        for (asset, venues) in &holdings {
+            let total: Decimal = venues.values().sum();
+            if venues.len() < 2 || total <= Decimal::ZERO {
+                continue;
+            }
+            
+            let target = total / Decimal::from(venues.len());
+            let (from, fullest) = venues.iter().max_by_key(|(_, free)| **free).unwrap();
+            let (to, emptiest) = venues.iter().min_by_key(|(_, free)| **free).unwrap();
+            if (*fullest - target) / total < self.config.imbalance_threshold {
+                continue;
+            }
+            
+            let amount = (*fullest - target).min(target - *emptiest);
+            match self.propose(manager, asset, from, to, amount).await {
+                Ok(transfer) => plan.transfers.push(transfer),
+                Err(e) => plan.skipped.push(format!("{} {} -> {}: {}", asset, from, to, e)),
+            }
+        }
+        
+        Ok(plan)
+    }
+
+    async fn propose(&self, manager: &ExchangeManager, asset: &str, from: &str, to: &str, amount: Decimal) -> Result<Transfer> {
+        let source = manager.get_exchange(from)
+            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", from))?;
+        let destination = manager.get_exchange(to)
+            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", to))?;
+        let timeout = manager.timeouts().account();
+        
+        let limits = manager.call(from, "get_withdrawal_limits", timeout, source.get_withdrawal_limits(asset)).await?;
+        if amount < limits.minimum {
+            anyhow::bail!("{} is below the withdrawal minimum of {}", amount, limits.minimum);
+        }
+        if limits.fee > amount * self.config.max_fee_ratio {
+            anyhow::bail!("withdrawal fee {} exceeds {}% of {}", limits.fee, self.config.max_fee_ratio * Decimal::from(100), amount);
+        }
+        
+        let address = manager.call(to, "get_deposit_address", timeout, destination.get_deposit_address(asset)).await?;
+        
+        Ok(Transfer {
+            asset: asset.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+            amount,
+            fee: limits.fee,
+            address,
+        })
+    }
+
+    // Returns the source venue's withdrawal id
+    pub async fn execute(&self, manager: &ExchangeManager, transfer: &Transfer) -> Result<String> {
+        let source = manager.get_exchange(&transfer.from)
+            .ok_or_else(|| anyhow::anyhow!("Exchange not found: {}", transfer.from))?;
+        
+        let result = manager.call(
+            &transfer.from, "withdraw", manager.timeouts().order(),
+            source.withdraw(&transfer.asset, transfer.amount, &transfer.address),
+        ).await;
+        if let Err(e) = &result {
+            warn!("Withdrawal of {} {} from {} to {} failed: {}", transfer.amount, transfer.asset, transfer.from, transfer.to, e);
+        }
+        result
+    }
+}
@@ -0,0 +1,78 @@
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use crate::database::Database;
+use crate::models::{OrderBook, Price};
+
+const RECORDER_CHANNEL_CAPACITY: usize = 4096;
+// Most snapshots written per round trip to the database
+const RECORDER_BATCH_SIZE: usize = 500;
+
+enum Snapshot {
+    Price(Price),
+    OrderBook(OrderBook),
+}
+
+// Writes market data snapshots in the background so recording never slows down a scan
+pub struct MarketRecorder {
+    sender: mpsc::Sender<Snapshot>,
+    writer: JoinHandle<()>,
+}
+
+impl MarketRecorder {
+    pub fn spawn(database: Database) -> Self {
+        let (sender, mut receiver) = mpsc::channel(RECORDER_CHANNEL_CAPACITY);
+        
+        // Whatever queued up while the previous batch was being written goes out as the next one
+        let writer = tokio::spawn(async move {
+            while let Some(snapshot) = receiver.recv().await {
+                let mut prices = Vec::new();
+                let mut order_books = Vec::new();
+                let mut next = Some(snapshot);
+                while let Some(snapshot) = next {
+                    match snapshot {
+                        Snapshot::Price(price) => prices.push(price),
+                        Snapshot::OrderBook(order_book) => order_books.push(order_book),
+                    }
+                    next = if prices.len() + order_books.len() < RECORDER_BATCH_SIZE {
+                        receiver.try_recv().ok()
+                    } else {
+                        None
+                    };
+                }
+                
+                if let Err(e) = database.save_price_history(&prices).await {
+                    warn!("Failed to record {} prices: {}", prices.len(), e);
+                }
+                for order_book in &order_books {
+                    if let Err(e) = database.save_order_book_snapshot(order_book).await {
+                        warn!("Failed to record order book snapshot: {}", e);
+                    }
+                }
+            }
+        });
+        
+        Self { sender, writer }
+    }
+
+    pub fn record_price(&self, price: &Price) {
+        if self.sender.try_send(Snapshot::Price(price.clone())).is_err() {
+            warn!("Market data recorder is falling behind, dropping price snapshot");
+        }
+    }
+
+    pub fn record_order_book(&self, order_book: &OrderBook) {
+        if self.sender.try_send(Snapshot::OrderBook(order_book.clone())).is_err() {
+            warn!("Market data recorder is falling behind, dropping order book snapshot");
+        }
+    }
+
+    // Closes the queue and waits for the snapshots already in it to be written
+    pub async fn flush(self) {
+        drop(self.sender);
+        if let Err(e) = self.writer.await {
+            warn!("Market data recorder stopped abnormally: {}", e);
+        }
+    }
+}
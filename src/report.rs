@@ -0,0 +1,240 @@
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use std::path::{Path, PathBuf};
+
+use crate::database::Database;
+use crate::models::{DailyReport, RouteResult};
+
+// Midnight to midnight at `offset`
+pub fn day_range(date: NaiveDate, offset: FixedOffset) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = (date.and_hms_opt(0, 0, 0).unwrap() - offset).and_utc();
+    (start, start + chrono::Duration::days(1))
+}
+
+pub fn local_date(now: DateTime<Utc>, offset: FixedOffset) -> NaiveDate {
+    now.with_timezone(&offset).date_naive()
+}
+
+// Everything comes from the database, so a past day can be regenerated as it was sent
+pub async fn daily(database: &Database, date: NaiveDate, offset: FixedOffset, simulated: bool) -> Result<DailyReport> {
+    let (from, to) = day_range(date, offset);
+    let stats = database.get_opportunity_stats(from, to).await?;
+    let pnl = database.get_pnl_summary(from, to, simulated).await?;
+
+    let top_route = pnl.by_route.iter()
+        .max_by(|a, b| a.1.realized.cmp(&b.1.realized))
+        .map(|(route, bucket)| RouteResult {
+            route: route.clone(),
+            opportunities: bucket.opportunities,
+            realized: bucket.realized,
+        });
+
+    Ok(DailyReport {
+        date,
+        utc_offset: offset.to_string(),
+        from,
+        to,
+        simulated,
+        opportunities_seen: stats.total,
+        opportunities_executed: stats.executed,
+        realized_pnl: pnl.total_realized,
+        fees: pnl.total_fees,
+        gas_spent: database.get_executed_gas_cost(from, to).await?,
+        top_route,
+        halts: database.get_trading_halts_between(from, to).await?,
+        errors: database.get_error_counts(from, to).await?,
+        portfolio_value: database.get_portfolio_value_before(to).await?,
+        previous_portfolio_value: database.get_portfolio_value_before(from).await?,
+    })
+}
+
+pub fn markdown(report: &DailyReport) -> String {
+    let money = |value: Option<rust_decimal::Decimal>| value.map_or("-".to_string(), |value| format!("${:.2}", value));
+    let top_route = report.top_route.as_ref()
+        .map_or("-".to_string(), |top| format!("{} ({} trades, {:.2})", top.route, top.opportunities, top.realized));
+
+    let mut lines = vec![format!("# Daily summary for {} (UTC{})", report.date, report.utc_offset), String::new()];
+    if report.simulated {
+        lines.extend(["Simulated dry-run trades only.".to_string(), String::new()]);
+    }
+    lines.extend([
+        "| | |".to_string(),
+        "|---|---|".to_string(),
+        format!("| Opportunities seen | {} |", report.opportunities_seen),
+        format!("| Opportunities executed | {} |", report.opportunities_executed),
+        format!("| Realized PnL | {:.2} |", report.realized_pnl),
+        format!("| Fees | {:.2} |", report.fees),
+        format!("| Gas | {:.2} |", report.gas_spent),
+        format!("| Top route | {} |", top_route),
+        format!("| Portfolio value | {} |", money(report.portfolio_value)),
+        format!("| Previous day | {} |", money(report.previous_portfolio_value)),
+        format!("| Change | {} |", report.portfolio_change().map_or("-".to_string(), |change| format!("{:+.2}", change))),
+        String::new(),
+        "## Risk halts".to_string(),
+        String::new(),
+    ]);
+    if report.halts.is_empty() {
+        lines.push("None".to_string());
+    }
+    for halt in &report.halts {
+        lines.push(format!("- {} until {}: {}", halt.halted_at.format("%Y-%m-%d %H:%M UTC"),
+                           halt.halted_until.format("%Y-%m-%d %H:%M UTC"), halt.reason));
+    }
+
+    lines.extend([String::new(), "## Errors".to_string(), String::new()]);
+    if report.errors.is_empty() {
+        lines.push("None".to_string());
+    } else {
+        lines.extend(["| Component | Count | Last error |".to_string(), "|---|---|---|".to_string()]);
+        for error in &report.errors {
+            lines.push(format!("| {} | {} | {} |", error.context, error.count, error.last_message.replace('|', "\\|").replace('\n', " ")));
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
+
+// Writes <date>.json and <date>.md, replacing the files of an earlier run for the same day
+pub fn write(report: &DailyReport, directory: &Path) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(directory)
+        .map_err(|e| anyhow::anyhow!("Cannot create {}: {}", directory.display(), e))?;
+
+    let json = directory.join(format!("{}.json", report.date));
+    std::fs::write(&json, serde_json::to_string_pretty(report)?)
+        .map_err(|e| anyhow::anyhow!("Cannot write {}: {}", json.display(), e))?;
+    let markdown_path = directory.join(format!("{}.md", report.date));
+    std::fs::write(&markdown_path, markdown(report))
+        .map_err(|e| anyhow::anyhow!("Cannot write {}: {}", markdown_path.display(), e))?;
+
+    Ok(vec![json, markdown_path])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use std::collections::HashMap;
+    use std::str::FromStr;
+
+    use crate::database::tests::stats_opportunity;
+    use crate::models::{OpportunityStatus, Portfolio, Trade, TradeSide, TradeStatus, TradingHalt, TradingPair};
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    // One day at UTC+2 with an executed and an active opportunity, a halt, errors and portfolio snapshots, each
+    // mirrored just outside the day
+    #[tokio::test]
+    async fn reports_one_local_day() -> Result<()> {
+        let database = Database::new("sqlite::memory:").await?;
+        database.migrate().await?;
+        
+        let offset: chrono::FixedOffset = "+02:00".parse()?;
+        let date = chrono::NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let at = |value: &str| chrono::DateTime::parse_from_rfc3339(value).map(|t| t.with_timezone(&chrono::Utc));
+        let (from, to) = day_range(date, offset);
+        assert!(from == at("2026-03-09T22:00:00Z")? && to == at("2026-03-10T22:00:00Z")?,
+               "2026-03-10 at +02:00 ran from {} to {}", from, to);
+        assert!(local_date(at("2026-03-10T23:00:00Z")?, offset) == date.succ_opt().unwrap(),
+               "23:00 UTC was not the next day at +02:00");
+        
+        let eth = TradingPair::new("ETH", "USDT");
+        let btc = TradingPair::new("BTC", "USDT");
+        let mut executed = stats_opportunity(&eth, "alpha", "beta", "3", chrono::Duration::zero());
+        executed.timestamp = at("2026-03-10T08:00:00Z")?;
+        executed.gas_cost = dec("1.5");
+        let mut active = stats_opportunity(&eth, "beta", "alpha", "0.6", chrono::Duration::zero());
+        active.timestamp = at("2026-03-10T09:00:00Z")?;
+        let mut outside = stats_opportunity(&btc, "alpha", "beta", "1", chrono::Duration::zero());
+        outside.timestamp = at("2026-03-09T21:00:00Z")?;
+        outside.gas_cost = dec("10");
+        for opportunity in [&executed, &active, &outside] {
+            database.save_opportunity(opportunity).await?;
+        }
+        for opportunity in [&mut executed, &mut outside] {
+            opportunity.status = OpportunityStatus::Executed;
+            database.update_opportunity_status(opportunity).await?;
+        }
+        
+        // Bought at 100 and sold at 103 with 0.1 USDT fees on each leg
+        for (exchange, side, price) in [("alpha", TradeSide::Buy, "100"), ("beta", TradeSide::Sell, "103")] {
+            database.save_trade(&Trade {
+                id: uuid::Uuid::new_v4(),
+                opportunity_id: executed.id,
+                exchange: exchange.to_string(),
+                pair: eth.clone(),
+                side,
+                amount: dec("1"),
+                price: dec(price),
+                fee_amount: dec("0.1"),
+                fee_asset: Some("USDT".to_string()),
+                status: TradeStatus::Executed,
+                created_at: at("2026-03-10T08:00:01Z")?,
+                executed_at: Some(at("2026-03-10T08:00:02Z")?),
+                order_id: None,
+                client_order_id: None,
+                tx_hash: None,
+                simulated: false,
+                failure_reason: None,
+            }).await?;
+        }
+        
+        for (halted_at, reason) in [("2026-03-10T05:00:00Z", "Daily loss limit reached"), ("2026-03-11T01:00:00Z", "Tomorrow's halt")] {
+            database.save_trading_halt(&TradingHalt {
+                id: uuid::Uuid::new_v4(),
+                reason: reason.to_string(),
+                halted_at: at(halted_at)?,
+                halted_until: at(halted_at)? + chrono::Duration::hours(1),
+                cleared_at: None,
+            }).await?;
+        }
+        for (context, message, time) in [
+            ("execution", "timeout", "2026-03-10T03:10:00Z"),
+            ("execution", "nonce too low", "2026-03-10T03:40:00Z"),
+            ("main loop", "database is locked", "2026-03-10T10:00:00Z"),
+            ("execution", "after the day", "2026-03-10T22:30:00Z"),
+        ] {
+            database.record_error(context, message, at(time)?).await?;
+        }
+        for (value, time) in [("1000", "2026-03-09T20:00:00Z"), ("1100", "2026-03-10T21:00:00Z"), ("5000", "2026-03-10T23:00:00Z")] {
+            database.save_portfolio_snapshot(&Portfolio {
+                total_value_usd: dec(value),
+                balances: HashMap::new(),
+                updated_at: at(time)?,
+            }).await?;
+        }
+        
+        let daily = daily(&database, date, offset, false).await?;
+        assert!(daily.opportunities_seen == 2 && daily.opportunities_executed == 1 && daily.gas_spent == dec("1.5"),
+               "counted {} opportunities seen, {} executed and {} gas", daily.opportunities_seen, daily.opportunities_executed, daily.gas_spent);
+        assert!(daily.realized_pnl == dec("2.8") && daily.fees == dec("0.2"),
+               "realized {} after {} fees, expected 2.8 after 0.2", daily.realized_pnl, daily.fees);
+        assert!(daily.top_route.as_ref().map_or(false, |top| top.route == "alpha -> beta" && top.opportunities == 1),
+               "top route was {:?}", daily.top_route);
+        assert!(daily.halts.len() == 1 && daily.halts[0].reason == "Daily loss limit reached",
+               "reported halts {:?}", daily.halts);
+        let errors: Vec<_> = daily.errors.iter().map(|error| (error.context.as_str(), error.count, error.last_message.as_str())).collect();
+        assert!(errors == [("execution", 2, "nonce too low"), ("main loop", 1, "database is locked")],
+               "reported errors {:?}", errors);
+        assert!(daily.portfolio_value == Some(dec("1100")) && daily.previous_portfolio_value == Some(dec("1000"))
+                  && daily.portfolio_change() == Some(dec("100")),
+               "portfolio went from {:?} to {:?}", daily.previous_portfolio_value, daily.portfolio_value);
+        assert!(daily.summary().contains("$1100.00 (+100.00 vs yesterday)"),
+               "summary read {:?}", daily.summary());
+        
+        // Written twice, as a regeneration does, the files hold the latest run
+        let directory = std::env::temp_dir().join(format!("daily-report-{}", uuid::Uuid::new_v4()));
+        write(&daily, &directory)?;
+        let paths = write(&daily, &directory)?;
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(directory.join("2026-03-10.json"))?)?;
+        let markdown = std::fs::read_to_string(directory.join("2026-03-10.md"))?;
+        assert!(paths.len() == 2 && json["date"] == "2026-03-10" && json["utc_offset"] == "+02:00",
+               "wrote {:?} with {}", paths, json);
+        assert!(markdown.contains("| Realized PnL | 2.80 |") && markdown.contains("| execution | 2 | nonce too low |"),
+               "markdown read {:?}", markdown);
+        std::fs::remove_dir_all(&directory)?;
+        
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::config::RiskManagement;
+use crate::models::Portfolio;
+
+// Pure limit arithmetic plus the book of USD committed to executions that have not finished yet.
+// Looking up prices, balances and realized PnL is left to the caller.
+pub struct RiskManager {
+    limits: RiskManagement,
+    in_flight: Mutex<HashMap<uuid::Uuid, Decimal>>,
+}
+
+impl RiskManager {
+    pub fn new(limits: RiskManagement) -> Self {
+        Self {
+            limits,
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Reservations for executions already under way are kept
+    pub fn set_limits(&mut self, limits: RiskManagement) {
+        self.limits = limits;
+    }
+
+    // Largest size whose notional stays within `position_size_limit`, given the USD value of one unit
+    pub fn cap_size(&self, size: Decimal, unit_value_usd: Decimal) -> Decimal {
+        if unit_value_usd <= Decimal::ZERO {
+            return size;
+        }
+        size.min(self.limits.position_size_limit / unit_value_usd)
+    }
+
+    pub fn in_flight_usd(&self) -> Decimal {
+        self.in_flight.lock().unwrap().values().copied().sum()
+    }
+
+    pub fn check_exposure(&self, trade_usd: Decimal, held_usd: Decimal) -> Result<()> {
+        let committed = self.in_flight_usd() + held_usd + trade_usd;
+        if committed > self.limits.max_portfolio_exposure {
+            anyhow::bail!("Exposure of ${:.2} would exceed the ${:.2} limit (${:.2} in flight, ${:.2} held)",
+                          committed, self.limits.max_portfolio_exposure, self.in_flight_usd(), held_usd);
+        }
+        Ok(())
+    }
+
+    pub fn reserve(&self, opportunity_id: uuid::Uuid, usd: Decimal) {
+        self.in_flight.lock().unwrap().insert(opportunity_id, usd);
+    }
+
+    pub fn release(&self, opportunity_id: uuid::Uuid) {
+        self.in_flight.lock().unwrap().remove(&opportunity_id);
+    }
+
+    // `stop_loss_percentage` of the portfolio may be lost per UTC day before trading halts
+    pub fn daily_loss_limit(&self, portfolio: &Portfolio) -> Decimal {
+        portfolio.total_value_usd * self.limits.stop_loss_percentage / Decimal::from(100)
+    }
+
+    pub fn loss_limit_breached(&self, portfolio: &Portfolio, realized_pnl: Decimal) -> bool {
+        let limit = self.daily_loss_limit(portfolio);
+        limit > Decimal::ZERO && -realized_pnl >= limit
+    }
+}
+
+pub fn start_of_day(now: DateTime<Utc>) -> DateTime<Utc> {
+    now.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc()
+}
+
+pub fn end_of_day(now: DateTime<Utc>) -> DateTime<Utc> {
+    start_of_day(now) + Duration::days(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    // Each risk limit against a $10,000 mock portfolio: $100 per trade, $1,000 total exposure and a
+    // 5% ($500) daily loss limit
+    #[test]
+    fn enforces_each_limit() {
+        let risk = RiskManager::new(RiskManagement {
+            max_portfolio_exposure: dec("1000"),
+            stop_loss_percentage: dec("5"),
+            position_size_limit: dec("100"),
+        });
+        let portfolio = Portfolio {
+            total_value_usd: dec("10000"),
+            balances: std::collections::HashMap::new(),
+            updated_at: chrono::Utc::now(),
+        };
+        let capped = risk.cap_size(dec("10"), dec("50"));
+        assert!(capped == dec("2"), "10 units at $50 capped to {}, expected 2", capped);
+        let uncapped = risk.cap_size(dec("1"), dec("50"));
+        assert!(uncapped == dec("1"), "1 unit at $50 changed to {}", uncapped);
+        
+        assert!(risk.check_exposure(dec("100"), dec("0")).is_ok(),
+               "$100 trade with nothing committed was refused");
+        risk.reserve(uuid::Uuid::new_v4(), dec("950"));
+        assert!(risk.check_exposure(dec("100"), dec("0")).is_err(),
+               "$100 trade on top of $950 in flight was allowed");
+        assert!(risk.in_flight_usd() == dec("950"),
+               "in-flight exposure is {}, expected 950", risk.in_flight_usd());
+        
+        let released = RiskManager::new(RiskManagement {
+            max_portfolio_exposure: dec("1000"),
+            stop_loss_percentage: dec("5"),
+            position_size_limit: dec("100"),
+        });
+        let id = uuid::Uuid::new_v4();
+        released.reserve(id, dec("950"));
+        released.release(id);
+        assert!(released.check_exposure(dec("100"), dec("0")).is_ok(),
+               "exposure was not freed after release");
+        assert!(released.check_exposure(dec("100"), dec("950")).is_err(),
+               "$100 trade on top of $950 held inventory was allowed");
+        
+        let limit = risk.daily_loss_limit(&portfolio);
+        assert!(limit == dec("500"), "daily loss limit is {}, expected 500", limit);
+        assert!(!risk.loss_limit_breached(&portfolio, dec("-499.99")),
+               "halted below the daily loss limit");
+        assert!(risk.loss_limit_breached(&portfolio, dec("-500")),
+               "did not halt at the daily loss limit");
+        assert!(!risk.loss_limit_breached(&portfolio, dec("250")),
+               "halted on a profitable day");
+    }
+}
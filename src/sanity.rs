@@ -0,0 +1,168 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::config::PriceSanityConfig;
+use crate::models::Price;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rejection {
+    NonPositive,
+    Crossed,
+    Stale { age_seconds: i64 },
+    OffMedian { median: Decimal, deviation: Decimal },
+    Jump { previous: Decimal, deviation: Decimal },
+}
+
+impl Rejection {
+    // Stable label for counting rejections by cause
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Rejection::NonPositive => "non_positive",
+            Rejection::Crossed => "crossed",
+            Rejection::Stale { .. } => "stale",
+            Rejection::OffMedian { .. } => "off_median",
+            Rejection::Jump { .. } => "jump",
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::NonPositive => write!(f, "non-positive bid or ask"),
+            Rejection::Crossed => write!(f, "bid at or above ask"),
+            Rejection::Stale { age_seconds } => write!(f, "quote is {}s old", age_seconds),
+            Rejection::OffMedian { median, deviation } => {
+                write!(f, "{:.2}% away from the cross-venue median {}", deviation * Decimal::from(100), median)
+            },
+            Rejection::Jump { previous, deviation } => {
+                write!(f, "{:.2}% away from the venue's last accepted mid {}", deviation * Decimal::from(100), previous)
+            },
+        }
+    }
+}
+
+// Screens every fetched or streamed price before it can reach the opportunity scanner
+pub struct PriceSanity {
+    config: PriceSanityConfig,
+    // (exchange, pair symbol) to the last mid price that passed
+    last_accepted: HashMap<(String, String), (Decimal, DateTime<Utc>)>,
+}
+
+impl PriceSanity {
+    pub fn new(config: PriceSanityConfig) -> Self {
+        Self {
+            config,
+            last_accepted: HashMap::new(),
+        }
+    }
+
+    pub fn set_config(&mut self, config: PriceSanityConfig) {
+        self.config = config;
+    }
+
+    // `others` are the current quotes for the same pair on other venues
+    pub fn check<'a>(&mut self, price: &Price, others: impl IntoIterator<Item = &'a Price>) -> Result<(), Rejection> {
+        if price.bid <= Decimal::ZERO || price.ask <= Decimal::ZERO {
+            return Err(Rejection::NonPositive);
+        }
+        if price.bid >= price.ask {
+            return Err(Rejection::Crossed);
+        }
+        
+        let now = Utc::now();
+        let age_seconds = now.signed_duration_since(price.timestamp).num_seconds();
+        if age_seconds > self.config.max_age_seconds as i64 {
+            return Err(Rejection::Stale { age_seconds });
+        }
+        
+        let mid = (price.bid + price.ask) / Decimal::TWO;
+        
+        // A median of one venue is just that venue's opinion, so it takes two others to outvote a quote
+        let mut other_mids: Vec<Decimal> = others.into_iter()
+            .filter(|other| other.exchange != price.exchange)
+            .filter(|other| now.signed_duration_since(other.timestamp).num_seconds() <= self.config.max_age_seconds as i64)
+            .map(|other| (other.bid + other.ask) / Decimal::TWO)
+            .collect();
+        if other_mids.len() >= 2 {
+            other_mids.sort();
+            let middle = other_mids.len() / 2;
+            let median = if other_mids.len() % 2 == 0 {
+                (other_mids[middle - 1] + other_mids[middle]) / Decimal::TWO
+            } else {
+                other_mids[middle]
+            };
+            
+            let deviation = ((mid - median) / median).abs();
+            if deviation > self.config.max_median_deviation {
+                return Err(Rejection::OffMedian { median, deviation });
+            }
+        }
+        
+        // Only a recent reference counts, otherwise a genuine move would lock the venue out for good
+        let key = (price.exchange.clone(), price.pair.symbol.clone());
+        if let Some((previous, accepted_at)) = self.last_accepted.get(&key) {
+            let reference_age = now.signed_duration_since(*accepted_at).num_seconds();
+            let deviation = ((mid - previous) / previous).abs();
+            if reference_age <= self.config.max_age_seconds as i64 && deviation > self.config.max_jump {
+                return Err(Rejection::Jump { previous: *previous, deviation });
+            }
+        }
+        
+        self.last_accepted.insert(key, (mid, now));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use crate::models::TradingPair;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    // One glitched tick among sane venues must be turned away before it can look like an opportunity
+    #[test]
+    fn rejects_glitched_ticks() {
+        let pair = TradingPair::new("ETH", "USDT");
+        let quote = |exchange: &str, bid: &str, ask: &str, age_seconds: i64| Price {
+            exchange: exchange.to_string(),
+            pair: pair.clone(),
+            bid: dec(bid),
+            ask: dec(ask),
+            timestamp: chrono::Utc::now() - chrono::Duration::seconds(age_seconds),
+            volume_24h: None,
+            fetch_latency: None,
+        };
+        let mut sanity = PriceSanity::new(PriceSanityConfig::default());
+        let venues = [quote("alpha", "999", "1000", 0), quote("beta", "1001", "1002", 0), quote("gamma", "1000", "1001", 0)];
+        for price in &venues {
+            let result = sanity.check(price, &venues);
+            assert!(result.is_ok(), "sane {} quote rejected: {:?}", price.exchange, result);
+        }
+        
+        let cases = [
+            ("glitched ask", quote("delta", "0.00009", "0.0001", 0), "off_median"),
+            ("crossed book", quote("delta", "1001", "1000", 0), "crossed"),
+            ("zero bid", quote("delta", "0", "1000", 0), "non_positive"),
+            ("stale quote", quote("delta", "999", "1000", 120), "stale"),
+        ];
+        for (name, price, expected) in cases {
+            let result = sanity.check(&price, &venues);
+            assert!(matches!(&result, Err(rejection) if rejection.kind() == expected),
+                   "{} was not rejected as {}: {:?}", name, expected, result);
+        }
+        
+        // With no other venues to compare against, the venue's own history still catches the spike
+        let mut alone = PriceSanity::new(PriceSanityConfig::default());
+        let _ = alone.check(&quote("alpha", "999", "1000", 0), []);
+        let spike = alone.check(&quote("alpha", "1499", "1500", 0), []);
+        assert!(matches!(&spike, Err(rejection) if rejection.kind() == "jump"),
+               "a 50% jump on a lone venue was not rejected: {:?}", spike);
+    }
+}
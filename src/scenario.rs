@@ -0,0 +1,1490 @@
+use anyhow::Result;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::arbitrage::ArbitrageBot;
+use crate::blockchain::BlockchainManager;
+use crate::config::{Config, LegOrderType, TimeInForce};
+use crate::database::Database;
+use crate::errors::BotError;
+use crate::export::{self, ExportFormat, ExportKind};
+use crate::exchanges::binance;
+use crate::exchanges::mock::{FillBehavior, MockCall, MockExchange};
+use crate::exchanges::{Exchange, ExchangeManager, OrderAmount, OrderOptions, OrderRequest, OutcomeUnknown, TokenProbe};
+use crate::models::{ArbitrageOpportunity, OpportunityStatus, Price, Trade, TradeSide, TradeStatus, TradingPair};
+
+pub(crate) const SCENARIO_CONFIG: &str = r#"
+database_url = "sqlite::memory:"
+
+[exchanges.alpha]
+name = "alpha"
+api_key = ""
+api_secret = ""
+api_url = "http://localhost"
+enabled = true
+trading_pairs = ["ETH/USDT"]
+min_trade_amount = "0"
+max_trade_amount = "10"
+
+[exchanges.beta]
+name = "beta"
+api_key = ""
+api_secret = ""
+api_url = "http://localhost"
+enabled = true
+trading_pairs = ["ETH/USDT"]
+min_trade_amount = "0"
+max_trade_amount = "10"
+
+[blockchain.ethereum]
+rpc_url = "http://localhost:8545"
+chain_id = 1
+private_key = ""
+gas_price_gwei = 0
+max_gas_limit = 0
+enabled = false
+
+[blockchain.bsc]
+rpc_url = "http://localhost:8545"
+chain_id = 56
+private_key = ""
+gas_price_gwei = 0
+max_gas_limit = 0
+enabled = false
+
+[blockchain.polygon]
+rpc_url = "http://localhost:8545"
+chain_id = 137
+private_key = ""
+gas_price_gwei = 0
+max_gas_limit = 0
+enabled = false
+
+[trading]
+min_profit_threshold = "0.5"
+max_slippage = "0.005"
+check_interval_seconds = 1
+max_concurrent_trades = 1
+
+[trading.risk_management]
+max_portfolio_exposure = "100000"
+stop_loss_percentage = "5"
+position_size_limit = "100000"
+"#;
+
+fn dec(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+// Two mock venues with a known 2% spread: alpha sells ETH at 1000/1001, beta buys at 1020/1019.
+// With 0.1% fees on each leg the whole 10 ETH allowed by config is worth taking.
+#[tokio::test]
+async fn mock_cycle() -> Result<()> {
+    let pair = TradingPair::new("ETH", "USDT");
+
+    let alpha = MockExchange::new("alpha");
+    alpha.set_price(&pair, dec("999"), dec("1000"));
+    alpha.set_order_book(&pair, vec![(dec("999"), dec("5"))], vec![(dec("1000"), dec("5")), (dec("1001"), dec("5"))]);
+    alpha.set_fees(dec("0.001"), dec("0.001"));
+    alpha.set_balance("USDT", dec("100000"));
+
+    let beta = MockExchange::new("beta");
+    beta.set_price(&pair, dec("1020"), dec("1021"));
+    beta.set_order_book(&pair, vec![(dec("1020"), dec("5")), (dec("1019"), dec("5"))], vec![(dec("1021"), dec("5"))]);
+    beta.set_fees(dec("0.001"), dec("0.001"));
+    beta.set_balance("ETH", dec("100"));
+
+    let mut exchange_manager = ExchangeManager::new();
+    exchange_manager.add_exchange(Box::new(alpha));
+    exchange_manager.add_exchange(Box::new(beta));
+
+    let config: Config = toml::from_str(SCENARIO_CONFIG)?;
+    let database = Database::new(&config.database_url).await?;
+    database.migrate().await?;
+    let blockchain_manager = BlockchainManager::new(&config.blockchain).await?;
+
+    let mut bot = ArbitrageBot::with_components(config, exchange_manager, blockchain_manager, database);
+    bot.run_cycle().await?;
+
+    let stats = bot.stats();
+    assert!(stats.opportunities_found == 1,
+           "expected 1 opportunity, found {}", stats.opportunities_found);
+    assert!(bot.database().count_opportunities().await? == 1,
+           "expected exactly 1 persisted opportunity");
+
+    let now = chrono::Utc::now();
+    let trades = bot.database()
+        .get_trades_between(now - chrono::Duration::hours(1), now + chrono::Duration::hours(1), false)
+        .await?;
+    assert!(trades.len() == 2, "expected 2 trades, found {}", trades.len());
+
+    if let Some(buy) = trades.iter().find(|trade| matches!(trade.side, TradeSide::Buy)) {
+        assert!(buy.exchange == "alpha", "buy leg went to {}", buy.exchange);
+        assert!(buy.amount == dec("10"), "buy leg sized {}", buy.amount);
+        assert!(matches!(buy.status, TradeStatus::Executed), "buy leg is {:?}", buy.status);
+        
+        match bot.database().get_opportunity(buy.opportunity_id).await? {
+            Some(opportunity) => {
+                assert!(opportunity.buy_exchange == "alpha" && opportunity.sell_exchange == "beta",
+                       "route was {} -> {}", opportunity.buy_exchange, opportunity.sell_exchange);
+                assert!(opportunity.max_trade_size == dec("10"),
+                       "opportunity sized {}", opportunity.max_trade_size);
+                assert!(opportunity.buy_price == dec("1000.5") && opportunity.sell_price == dec("1019.5"),
+                       "effective prices {} / {}", opportunity.buy_price, opportunity.sell_price);
+                assert!(matches!(opportunity.status, OpportunityStatus::Executed),
+                       "opportunity is {:?}", opportunity.status);
+            },
+            None => panic!("buy leg references a missing opportunity"),
+        }
+    } else {
+        panic!("no buy leg recorded");
+    }
+
+    if let Some(sell) = trades.iter().find(|trade| matches!(trade.side, TradeSide::Sell)) {
+        assert!(sell.exchange == "beta", "sell leg went to {}", sell.exchange);
+        assert!(sell.amount == dec("10"), "sell leg sized {}", sell.amount);
+    } else {
+        panic!("no sell leg recorded");
+    }
+
+    let alpha_balances = bot.exchange_manager().get_exchange("alpha").unwrap().get_balances().await?;
+    let alpha_eth = alpha_balances.get("ETH").map(|balance| balance.free).unwrap_or_default();
+    assert!(alpha_eth == dec("10"), "alpha ETH balance is {}", alpha_eth);
+
+    Ok(())
+}
+
+// Holdings are summed across venues and valued at one price per asset; a venue that fails is reported
+// on its own and the rest of the report still comes back
+#[tokio::test]
+async fn balance_report() -> Result<()> {
+    let pair = TradingPair::new("ETH", "USDT");
+    
+    let alpha = MockExchange::new("alpha");
+    alpha.set_price(&pair, dec("2000"), dec("2002"));
+    alpha.set_balance("ETH", dec("2"));
+    alpha.set_balance("USDT", dec("1000"));
+    alpha.set_balance("BTC", dec("0"));
+    
+    let beta = MockExchange::new("beta");
+    beta.set_price(&pair, dec("2000"), dec("2002"));
+    beta.set_balance("ETH", dec("1"));
+    beta.set_balance("USDT", dec("500"));
+    
+    let gamma = MockExchange::new("gamma");
+    gamma.set_balance("ETH", dec("5"));
+    gamma.fail_next(MockCall::GetBalances, 1);
+    
+    let mut exchange_manager = ExchangeManager::new();
+    exchange_manager.add_exchange(Box::new(alpha));
+    exchange_manager.add_exchange(Box::new(beta));
+    exchange_manager.add_exchange(Box::new(gamma));
+    
+    let config: Config = toml::from_str(SCENARIO_CONFIG)?;
+    let database = Database::new(&config.database_url).await?;
+    let blockchain_manager = BlockchainManager::new(&config.blockchain).await?;
+    let bot = ArbitrageBot::with_components(config, exchange_manager, blockchain_manager, database);
+    let mut report = bot.balance_report(None).await?;
+    let eth = report.totals.get("ETH");
+    assert!(eth.map_or(false, |eth| eth.total == dec("3") && eth.usd_value == dec("6003")),
+           "ETH totals came to {:?}", eth);
+    assert!(report.total_value_usd == dec("7503"),
+           "portfolio value was {}, expected 7503", report.total_value_usd);
+    let alpha_eth = report.exchanges.get("alpha").and_then(|balances| balances.get("ETH"));
+    assert!(alpha_eth.map_or(false, |eth| eth.usd_value == dec("4002")),
+           "alpha's ETH was valued as {:?}", alpha_eth);
+    assert!(report.failures.contains_key("gamma") && !report.exchanges.contains_key("gamma"),
+           "gamma's failure was not reported on its own: {:?}", report.failures);
+    
+    assert!(report.totals.contains_key("BTC"), "a zero balance was dropped before hiding");
+    report.hide_zero_balances();
+    assert!(!report.totals.contains_key("BTC") && !report.exchanges["alpha"].contains_key("BTC"),
+           "a zero BTC balance was still shown");
+    
+    let single = bot.balance_report(Some("beta")).await?;
+    assert!(single.exchanges.len() == 1 && single.totals.get("USDT").map_or(false, |usdt| usdt.total == dec("500")),
+           "the beta-only report held {:?}", single.exchanges.keys().collect::<Vec<_>>());
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn orders() -> Result<()> {
+    let pair = TradingPair::new("ETH", "USDT");
+    
+    let alpha = MockExchange::new("alpha");
+    alpha.set_price(&pair, dec("2000"), dec("2002"));
+    alpha.set_fill_behavior(FillBehavior::Rest);
+    let resting_buy = alpha.place_buy_order(&pair, dec("0.5"), Some(dec("1990"))).await?;
+    let resting_sell = alpha.place_sell_order(&pair, dec("0.25"), Some(dec("2010"))).await?;
+    alpha.set_fill_behavior(FillBehavior::Instant);
+    alpha.place_buy_order(&pair, dec("0.1"), None).await?;
+    
+    let beta = MockExchange::new("beta");
+    beta.fail_next(MockCall::GetOpenOrders, 1);
+    
+    let mut exchange_manager = ExchangeManager::new();
+    exchange_manager.add_exchange(Box::new(alpha));
+    exchange_manager.add_exchange(Box::new(beta));
+    
+    let config: Config = toml::from_str(SCENARIO_CONFIG)?;
+    let database = Database::new(&config.database_url).await?;
+    let blockchain_manager = BlockchainManager::new(&config.blockchain).await?;
+    let bot = ArbitrageBot::with_components(config, exchange_manager, blockchain_manager, database);
+    let report = bot.list_open_orders(None).await?;
+    assert!(report.orders.len() == 2,
+           "expected the two resting orders, listed {:?}", report.orders.iter().map(|order| &order.order_id).collect::<Vec<_>>());
+    let buy = report.orders.iter().find(|order| Some(&order.order_id) == resting_buy.order_id.as_ref());
+    assert!(buy.map_or(false, |buy| buy.amount == dec("0.5") && buy.filled.is_zero() && buy.price == dec("1990") && buy.pair == "ETH/USDT"),
+           "the resting buy was listed as {:?}", buy);
+    assert!(report.failures.contains_key("beta"),
+           "beta's listing failure was not reported: {:?}", report.failures);
+    
+    let sell_id = resting_sell.order_id.clone().unwrap_or_default();
+    bot.cancel_exchange_order("alpha", &sell_id).await?;
+    let report = bot.list_open_orders(Some("alpha")).await?;
+    assert!(report.orders.len() == 1 && report.orders.iter().all(|order| order.order_id != sell_id),
+           "the cancelled sell was still open: {:?}", report.orders);
+    assert!(bot.cancel_exchange_order("alpha", "alpha-999").await.is_err(),
+           "cancelling an unknown order succeeded");
+    assert!(bot.list_open_orders(Some("gamma")).await.is_err(),
+           "listing orders on an unknown exchange succeeded");
+    
+    let cancelled = bot.cancel_all_orders(None).await?;
+    assert!(cancelled.cancellations.len() == 1 && cancelled.cancellations.iter().all(|cancellation| cancellation.error.is_none()),
+           "cancel-all reported {:?}", cancelled.cancellations);
+    let report = bot.list_open_orders(None).await?;
+    assert!(report.orders.is_empty(),
+           "orders were still open after cancel-all: {:?}", report.orders);
+    
+    Ok(())
+}
+
+// The venues of `run` plus a BTC/USDT market only alpha quotes, which cannot be arbitraged
+#[tokio::test]
+async fn scan() -> Result<()> {
+    let eth = TradingPair::new("ETH", "USDT");
+    let btc = TradingPair::new("BTC", "USDT");
+    
+    let alpha = MockExchange::new("alpha");
+    alpha.set_price(&eth, dec("999"), dec("1000"));
+    alpha.set_order_book(&eth, vec![(dec("999"), dec("5"))], vec![(dec("1000"), dec("5")), (dec("1001"), dec("5"))]);
+    alpha.set_fees(dec("0.001"), dec("0.001"));
+    alpha.set_price(&btc, dec("60000"), dec("60010"));
+    
+    let beta = MockExchange::new("beta");
+    beta.set_price(&eth, dec("1020"), dec("1021"));
+    beta.set_order_book(&eth, vec![(dec("1020"), dec("5")), (dec("1019"), dec("5"))], vec![(dec("1021"), dec("5"))]);
+    beta.set_fees(dec("0.001"), dec("0.001"));
+    
+    let mut exchange_manager = ExchangeManager::new();
+    exchange_manager.add_exchange(Box::new(alpha));
+    exchange_manager.add_exchange(Box::new(beta));
+    
+    let mut config: Config = toml::from_str(SCENARIO_CONFIG)?;
+    if let Some(alpha) = config.exchanges.get_mut("alpha") {
+        alpha.trading_pairs.push("BTC/USDT".to_string());
+    }
+    let database = Database::new(&config.database_url).await?;
+    let blockchain_manager = BlockchainManager::new(&config.blockchain).await?;
+    let bot = ArbitrageBot::with_components(config, exchange_manager, blockchain_manager, database);
+    let report = bot.scan_all().await;
+    let pairs: Vec<&str> = report.pairs.iter().map(|scan| scan.pair.as_str()).collect();
+    assert!(pairs == ["BTC/USDT", "ETH/USDT"], "scanned {:?}", pairs);
+    assert!(report.unpriced() == ["BTC/USDT"],
+           "unpriced pairs were {:?}", report.unpriced());
+    
+    if let Some(scan) = report.pairs.iter().find(|scan| scan.pair == "ETH/USDT") {
+        assert!(scan.quotes.len() == 2, "ETH/USDT had {} quotes", scan.quotes.len());
+        let route = scan.best_route.as_ref();
+        assert!(route.map_or(false, |route| route.buy_exchange == "alpha" && route.gross_percentage == dec("2")),
+               "the best route was {:?}", route);
+        // Two 0.1% taker fees leave about 1.8% of the 2% spread
+        let net = route.and_then(|route| route.net_percentage);
+        assert!(net.map_or(false, |net| net > dec("1.75") && net < dec("1.85") && net >= scan.min_profit_threshold),
+               "the best route netted {:?} against a {}% threshold", net, scan.min_profit_threshold);
+        let alpha_spread = scan.quotes.iter().find(|quote| quote.price.exchange == "alpha").map(|quote| quote.spread_percentage);
+        assert!(alpha_spread.map_or(false, |spread| spread.round_dp(4) == dec("0.1001")),
+               "alpha's own spread was {:?}", alpha_spread);
+        assert!(scan.opportunities.len() == 1 && scan.opportunities.iter().all(|opportunity| opportunity.buy_exchange == "alpha"),
+               "found {} opportunities", scan.opportunities.len());
+    } else {
+        panic!("ETH/USDT was not scanned");
+    }
+    
+    let json = serde_json::to_value(&report)?;
+    let quote = &json["pairs"][1]["quotes"][0];
+    assert!(quote.get("bid").is_some() && quote.get("spread_percentage").is_some(),
+           "a serialized quote lacked its price or spread: {}", quote);
+    
+    assert!(bot.scan_pair("ETHUSDT").await.is_err(), "an unsplit pair symbol was scanned");
+    
+    Ok(())
+}
+
+// Exports what one executed opportunity of `run` leaves behind, plus a failed trade and a price
+// small enough to tempt scientific notation
+#[tokio::test]
+async fn export_history() -> Result<()> {
+    let pair = TradingPair::new("ETH", "USDT");
+    
+    let alpha = MockExchange::new("alpha");
+    alpha.set_price(&pair, dec("999"), dec("1000"));
+    alpha.set_order_book(&pair, vec![(dec("999"), dec("5"))], vec![(dec("1000"), dec("5")), (dec("1001"), dec("5"))]);
+    alpha.set_fees(dec("0.001"), dec("0.001"));
+    alpha.set_balance("USDT", dec("100000"));
+    
+    let beta = MockExchange::new("beta");
+    beta.set_price(&pair, dec("1020"), dec("1021"));
+    beta.set_order_book(&pair, vec![(dec("1020"), dec("5")), (dec("1019"), dec("5"))], vec![(dec("1021"), dec("5"))]);
+    beta.set_fees(dec("0.001"), dec("0.001"));
+    beta.set_balance("ETH", dec("100"));
+    
+    let mut exchange_manager = ExchangeManager::new();
+    exchange_manager.add_exchange(Box::new(alpha));
+    exchange_manager.add_exchange(Box::new(beta));
+    
+    let config: Config = toml::from_str(SCENARIO_CONFIG)?;
+    let database = Database::new(&config.database_url).await?;
+    database.migrate().await?;
+    let blockchain_manager = BlockchainManager::new(&config.blockchain).await?;
+    let mut bot = ArbitrageBot::with_components(config, exchange_manager, blockchain_manager, database);
+    bot.run_cycle().await?;
+    
+    let now = chrono::Utc::now();
+    let opportunity_id = bot.database().get_trades_between(now - chrono::Duration::hours(1), now + chrono::Duration::hours(1), false)
+        .await?
+        .first()
+        .map(|trade| trade.opportunity_id)
+        .unwrap_or_default();
+    bot.database().save_trade(&Trade {
+        id: uuid::Uuid::new_v4(),
+        opportunity_id,
+        exchange: "beta".to_string(),
+        pair: pair.clone(),
+        side: TradeSide::Sell,
+        amount: dec("0.5"),
+        price: dec("1020"),
+        fee_amount: dec("0.00000001"),
+        fee_asset: None,
+        status: TradeStatus::Failed,
+        created_at: now,
+        executed_at: None,
+        order_id: None,
+        client_order_id: None,
+        tx_hash: None,
+        simulated: true,
+        failure_reason: Some("rejected: \"insufficient\" balance, retry".to_string()),
+    }).await?;
+    bot.database().save_price_history(&[Price {
+        exchange: "alpha".to_string(),
+        pair: pair.clone(),
+        bid: dec("0.00000012"),
+        ask: dec("0.00000013"),
+        timestamp: now,
+        volume_24h: None,
+        fetch_latency: None,
+    }]).await?;
+    
+    let start = now - chrono::Duration::hours(1);
+    let end = now + chrono::Duration::hours(1);
+    
+    let mut csv = Vec::new();
+    let count = export::export(bot.database(), ExportKind::Trades, ExportFormat::Csv, start, end, &mut csv).await?;
+    let csv = String::from_utf8(csv)?;
+    let lines: Vec<&str> = csv.lines().collect();
+    assert!(count == 3 && lines.len() == 4, "exported {} trades in {} lines", count, lines.len());
+    assert!(lines.first().map_or(false, |header| header.starts_with("id,opportunity_id,created_at,")),
+           "trade header was {:?}", lines.first());
+    assert!(lines.iter().skip(1).all(|line| line.contains(&opportunity_id.to_string())),
+           "a trade row lacked its opportunity id");
+    assert!(csv.contains(",0.00000001,") && csv.contains("\"rejected: \"\"insufficient\"\" balance, retry\""),
+           "the failed trade was written as {:?}", lines.last());
+    
+    let mut csv = Vec::new();
+    export::export(bot.database(), ExportKind::Prices, ExportFormat::Csv, start, end, &mut csv).await?;
+    let csv = String::from_utf8(csv)?;
+    assert!(csv.contains(",alpha,ETH/USDT,0.00000012,0.00000013,") && !csv.contains("e-"),
+           "price snapshot exported as {:?}", csv.lines().nth(1));
+    
+    let mut json = Vec::new();
+    let count = export::export(bot.database(), ExportKind::Opportunities, ExportFormat::Json, start, end, &mut json).await?;
+    let lines: Vec<serde_json::Value> = String::from_utf8(json)?.lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert!(count == 1 && lines.len() == 1, "exported {} opportunities as {} lines", count, lines.len());
+    assert!(lines.first().map_or(false, |line| line["id"] == serde_json::json!(opportunity_id.to_string())),
+           "the exported opportunity did not match its trades");
+    
+    let mut empty = Vec::new();
+    let count = export::export(bot.database(), ExportKind::Trades, ExportFormat::Json, end, end + chrono::Duration::hours(1), &mut empty).await?;
+    assert!(count == 0 && empty.is_empty(), "an empty range exported {} trades", count);
+    
+    Ok(())
+}
+
+struct SharedBuffer(Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl std::io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+// The mock scenario's executable spread, logged as JSON: every line written while executing it
+// carries the opportunity's id, pair and route
+#[tokio::test]
+async fn logging() -> Result<()> {
+    use tracing_subscriber::layer::SubscriberExt;
+    
+    let pair = TradingPair::new("ETH", "USDT");
+    let alpha = MockExchange::new("alpha");
+    alpha.set_price(&pair, dec("999"), dec("1000"));
+    alpha.set_order_book(&pair, vec![(dec("999"), dec("5"))], vec![(dec("1000"), dec("5")), (dec("1001"), dec("5"))]);
+    alpha.set_fees(dec("0.001"), dec("0.001"));
+    alpha.set_balance("USDT", dec("100000"));
+    let beta = MockExchange::new("beta");
+    beta.set_price(&pair, dec("1020"), dec("1021"));
+    beta.set_order_book(&pair, vec![(dec("1020"), dec("5")), (dec("1019"), dec("5"))], vec![(dec("1021"), dec("5"))]);
+    beta.set_fees(dec("0.001"), dec("0.001"));
+    beta.set_balance("ETH", dec("100"));
+    
+    let mut exchange_manager = ExchangeManager::new();
+    exchange_manager.add_exchange(Box::new(alpha));
+    exchange_manager.add_exchange(Box::new(beta));
+    let config: Config = toml::from_str(SCENARIO_CONFIG)?;
+    let database = Database::new(&config.database_url).await?;
+    database.migrate().await?;
+    let blockchain_manager = BlockchainManager::new(&config.blockchain).await?;
+    let mut bot = ArbitrageBot::with_components(config, exchange_manager, blockchain_manager, database);
+    
+    // Installed for this thread only, which is where the cycle below runs
+    let buffer = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let writer = buffer.clone();
+    let subscriber = tracing_subscriber::registry()
+        .with(crate::logging::format_layer(crate::config::LogFormat::Json, move || SharedBuffer(writer.clone()), false))
+        .with(tracing_subscriber::EnvFilter::try_new(logging.filter())?);
+    let guard = tracing::subscriber::set_default(subscriber);
+    bot.run_cycle().await?;
+    drop(guard);
+    
+    let output = String::from_utf8(buffer.lock().unwrap().clone())?;
+    let lines: Vec<serde_json::Value> = output.lines().map(serde_json::from_str).collect::<Result<_, _>>()?;
+    let opportunity_span = |line: &serde_json::Value| line["spans"].as_array()
+        .and_then(|spans| spans.iter().find(|span| span["name"] == "opportunity"))
+        .cloned();
+    let in_opportunity: Vec<(&serde_json::Value, serde_json::Value)> = lines.iter()
+        .filter_map(|line| opportunity_span(line).map(|span| (line, span)))
+        .collect();
+    
+    let now = chrono::Utc::now();
+    let trades = bot.database().get_trades_between(now - chrono::Duration::hours(1), now + chrono::Duration::hours(1), false).await?;
+    let opportunity_id = trades.first().map(|trade| trade.opportunity_id.to_string()).unwrap_or_default();
+    
+    assert!(in_opportunity.iter().any(|(line, _)| line["fields"]["message"].as_str().map_or(false, |message| message.starts_with("Trade execution completed"))),
+           "no execution log line was inside the opportunity span among {} lines", lines.len());
+    assert!(!opportunity_id.is_empty() && in_opportunity.iter().all(|(_, span)| span["opportunity_id"] == serde_json::json!(opportunity_id)
+              && span["pair"] == "ETH/USDT" && span["route"] == "alpha->beta"),
+           "execution lines carried {:?}", in_opportunity.first().map(|(_, span)| span));
+    
+    Ok(())
+}
+
+// With the kill switch engaged the mock spread stays active, so /status and /opportunities have
+// something to show; a zero tick age makes /healthz fail
+#[tokio::test]
+async fn status() -> Result<()> {
+    let pair = TradingPair::new("ETH", "USDT");
+    let alpha = MockExchange::new("alpha");
+    alpha.set_price(&pair, dec("999"), dec("1000"));
+    alpha.set_order_book(&pair, vec![(dec("999"), dec("5"))], vec![(dec("1000"), dec("5")), (dec("1001"), dec("5"))]);
+    alpha.set_fees(dec("0.001"), dec("0.001"));
+    alpha.set_balance("USDT", dec("100000"));
+    let beta = MockExchange::new("beta");
+    beta.set_price(&pair, dec("1020"), dec("1021"));
+    beta.set_order_book(&pair, vec![(dec("1020"), dec("5")), (dec("1019"), dec("5"))], vec![(dec("1021"), dec("5"))]);
+    beta.set_fees(dec("0.001"), dec("0.001"));
+    beta.set_balance("ETH", dec("100"));
+    
+    let mut exchange_manager = ExchangeManager::new();
+    exchange_manager.add_exchange(Box::new(alpha));
+    exchange_manager.add_exchange(Box::new(beta));
+    let config: Config = toml::from_str(SCENARIO_CONFIG)?;
+    let database = Database::new(&config.database_url).await?;
+    database.migrate().await?;
+    let blockchain_manager = BlockchainManager::new(&config.blockchain).await?;
+    let mut bot = ArbitrageBot::with_components(config, exchange_manager, blockchain_manager, database);
+    bot.set_dry_run(true);
+    
+    let state = bot.status_state();
+    state.kill_switch.send_replace(true);
+    state.heartbeat.tick();
+    bot.run_cycle().await?;
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(crate::status::serve(listener, state.clone()));
+    let stale_listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let stale_address = stale_listener.local_addr()?;
+    tokio::spawn(crate::status::serve(stale_listener, crate::status::StatusState { max_tick_age: Duration::ZERO, ..state.clone() }));
+    
+    let client = reqwest::Client::new();
+    let healthz = client.get(format!("http://{}/healthz", address)).send().await?;
+    assert!(healthz.status() == reqwest::StatusCode::OK, "/healthz answered {}", healthz.status());
+    let stale = client.get(format!("http://{}/healthz", stale_address)).send().await?;
+    assert!(stale.status() == reqwest::StatusCode::SERVICE_UNAVAILABLE,
+           "/healthz with a stale tick answered {}", stale.status());
+    
+    let status: serde_json::Value = client.get(format!("http://{}/status", address)).send().await?.json().await?;
+    assert!(status["dry_run"] == true && status["halted"] == true && status["active_opportunities"] == 1,
+           "/status reported {}", status);
+    assert!(status["last_scan"].is_string() && status["last_tick"].is_string(),
+           "/status had no scan or tick time: {}", status);
+    let exchanges: Vec<&str> = status["exchanges"].as_array()
+        .map(|exchanges| exchanges.iter().filter_map(|exchange| exchange["exchange"].as_str()).collect())
+        .unwrap_or_default();
+    assert!(exchanges == ["alpha", "beta"], "/status listed exchanges {:?}", exchanges);
+    
+    let opportunities: HashMap<String, ArbitrageOpportunity> = client.get(format!("http://{}/opportunities", address))
+        .send().await?
+        .json().await?;
+    let opportunity = opportunities.values().next();
+    assert!(opportunities.len() == 1 && opportunity.map_or(false, |opportunity| opportunity.buy_exchange == "alpha"
+              && opportunity.sell_exchange == "beta"),
+           "/opportunities returned {:?}", opportunities.keys().collect::<Vec<_>>());
+    
+    Ok(())
+}
+
+// Sends one request to the control API, answering the command it produces in place of the main loop
+async fn control_call(
+    bot: &mut ArbitrageBot,
+    commands: &mut tokio::sync::mpsc::Receiver<crate::control::ControlRequest>,
+    request: reqwest::RequestBuilder,
+) -> Result<(reqwest::StatusCode, String)> {
+    let mut response = tokio::spawn(request.send());
+    let response = loop {
+        tokio::select! {
+            Some(control) = commands.recv() => {
+                let outcome = bot.apply_control(control.command).map_err(|e| e.to_string());
+                let _ = control.reply.send(outcome);
+            },
+            response = &mut response => break response??,
+        }
+    };
+    Ok((response.status(), response.text().await?))
+}
+
+#[tokio::test]
+async fn control() -> Result<()> {
+    let config: Config = toml::from_str(SCENARIO_CONFIG)?;
+    let database = Database::new(&config.database_url).await?;
+    database.migrate().await?;
+    let blockchain_manager = BlockchainManager::new(&config.blockchain).await?;
+    let mut bot = ArbitrageBot::with_components(config, ExchangeManager::new(), blockchain_manager, database);
+    
+    let (commands_tx, mut commands) = tokio::sync::mpsc::channel(crate::control::CONTROL_CHANNEL_CAPACITY);
+    let state = bot.status_state();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(crate::status::serve(listener, crate::status::StatusState {
+        control: Some(crate::control::ControlHandle { token: "scenario-token".to_string(), commands: commands_tx }),
+        ..state.clone()
+    }));
+    let read_only_listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let read_only_address = read_only_listener.local_addr()?;
+    tokio::spawn(crate::status::serve(read_only_listener, state.clone()));
+    
+    let client = reqwest::Client::new();
+    let url = |path: &str| format!("http://{}{}", address, path);
+    let (status, _) = control_call(&mut bot, &mut commands, client.post(url("/control/pause"))).await?;
+    assert!(status == reqwest::StatusCode::UNAUTHORIZED, "a pause without a token answered {}", status);
+    let (status, _) = control_call(&mut bot, &mut commands, client.post(url("/control/pause")).bearer_auth("scenario-tokem")).await?;
+    assert!(status == reqwest::StatusCode::UNAUTHORIZED, "a pause with the wrong token answered {}", status);
+    assert!(!state.settings.borrow().paused, "an unauthorized pause was applied");
+    
+    let (status, body) = control_call(&mut bot, &mut commands, client.post(url("/control/pause")).bearer_auth("scenario-token")).await?;
+    assert!(status == reqwest::StatusCode::OK && state.settings.borrow().paused,
+           "pause answered {} {:?} without pausing", status, body);
+    let report: serde_json::Value = client.get(url("/status")).send().await?.json().await?;
+    assert!(report["paused"] == true, "/status after a pause reported {}", report);
+    let (status, body) = control_call(&mut bot, &mut commands, client.post(url("/control/resume")).bearer_auth("scenario-token")).await?;
+    assert!(status == reqwest::StatusCode::OK && !state.settings.borrow().paused,
+           "resume answered {} {:?} without resuming", status, body);
+    
+    let (status, _) = control_call(&mut bot, &mut commands, client.post(url("/control/dry-run")).bearer_auth("scenario-token").body("on")).await?;
+    assert!(status == reqwest::StatusCode::OK && state.settings.borrow().dry_run,
+           "dry-run on answered {} without enabling it", status);
+    let (status, _) = control_call(&mut bot, &mut commands, client.post(url("/control/dry-run")).bearer_auth("scenario-token").body("maybe")).await?;
+    assert!(status == reqwest::StatusCode::BAD_REQUEST && state.settings.borrow().dry_run,
+           "dry-run maybe answered {}", status);
+    
+    let (status, body) = control_call(&mut bot, &mut commands, client.patch(url("/control/trading")).bearer_auth("scenario-token")
+        .json(&serde_json::json!({ "min_profit_threshold": "0.8", "max_concurrent_trades": 2 }))).await?;
+    let settings = state.settings.borrow().clone();
+    assert!(status == reqwest::StatusCode::OK && settings.min_profit_threshold == dec("0.8") && settings.max_concurrent_trades == 2,
+           "the trading patch answered {} {:?} and left {:?}", status, body, settings);
+    let (status, _) = control_call(&mut bot, &mut commands, client.patch(url("/control/trading")).bearer_auth("scenario-token")
+        .json(&serde_json::json!({ "max_concurrent_trades": 0 }))).await?;
+    assert!(status == reqwest::StatusCode::BAD_REQUEST && state.settings.borrow().max_concurrent_trades == 2,
+           "an invalid trading patch answered {}", status);
+    
+    let status = client.post(format!("http://{}/control/pause", read_only_address)).bearer_auth("scenario-token").send().await?.status();
+    assert!(status == reqwest::StatusCode::NOT_FOUND, "the control API was served without a token configured: {}", status);
+    
+    Ok(())
+}
+
+// Order and balance events from an account stream reach waiting executions and the balance cache
+// without a REST round trip
+#[tokio::test]
+async fn user_stream() -> Result<()> {
+    let exchange_config: crate::config::ExchangeConfig = toml::from_str(r#"
+        name = "binance"
+        api_url = "http://localhost"
+        enabled = true
+        trading_pairs = ["ETH/USDT"]
+        min_trade_amount = "0"
+        max_trade_amount = "10"
+    "#)?;
+    let exchange = binance::BinanceExchange::new(exchange_config)?;
+    let filled = exchange.user_stream_message(
+        r#"{"e":"executionReport","E":1,"s":"ETHUSDT","S":"BUY","o":"LIMIT","q":"2.00000000","p":"1000.00000000","X":"FILLED","i":42,"z":"2.00000000","Z":"1999.00000000","n":"1.0","N":"USDT","T":1700000000000}"#,
+        &mut HashMap::new(),
+    );
+    let Ok(binance::UserStreamMessage::Event(crate::exchanges::AccountEvent::Order(early))) = filled else {
+        panic!("a filled execution report was not streamed as an order");
+    };
+    
+    // A fill streamed before anyone waits for it is still handed over
+    let tracker = Arc::new(crate::orders::OrderTracker::default());
+    let balance_manager = Arc::new(crate::balances::BalanceManager::new(Duration::from_secs(60), HashMap::new()));
+    let (events, receiver) = tokio::sync::mpsc::channel(16);
+    tokio::spawn(crate::orders::track_account_events(receiver, tracker.clone(), balance_manager.clone()));
+    
+    events.send(crate::exchanges::AccountEvent::Connected { exchange: "binance".to_string() }).await?;
+    events.send(crate::exchanges::AccountEvent::Order(early)).await?;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(tracker.is_streaming("binance"), "a connected account stream was not marked as streaming");
+    let started = Instant::now();
+    let update = tracker.wait_for_update("binance", "ETHUSDT:42", Duration::from_secs(5)).await;
+    assert!(update.is_some() && started.elapsed() < Duration::from_secs(1),
+           "an already streamed fill took {:?} to reach its waiter", started.elapsed());
+    
+    // A reconnect releases waiters at once so they re-read their orders over REST
+    let waiter = {
+        let tracker = tracker.clone();
+        tokio::spawn(async move {
+            let started = Instant::now();
+            let update = tracker.wait_for_update("binance", "ETHUSDT:43", Duration::from_secs(5)).await;
+            (update.is_none(), started.elapsed())
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    events.send(crate::exchanges::AccountEvent::Connected { exchange: "binance".to_string() }).await?;
+    let (released, waited) = waiter.await?;
+    assert!(released && waited < Duration::from_secs(1),
+           "a reconnect left a waiter blocked for {:?}", waited);
+    
+    let unstreamed = tracker.wait_for_update("alpha", "alpha-1", Duration::from_millis(100)).await;
+    assert!(unstreamed.is_none(), "an exchange without a stream produced an update");
+    
+    // Streamed balances patch the cached snapshot, which then answers without asking the venue
+    let alpha = MockExchange::new("alpha");
+    alpha.set_balance("ETH", dec("1"));
+    alpha.set_balance("USDT", dec("500"));
+    let mut exchange_manager = ExchangeManager::new();
+    exchange_manager.add_exchange(Box::new(alpha));
+    let venue = exchange_manager.get_exchange("alpha").ok_or_else(|| anyhow::anyhow!("alpha is not registered"))?;
+    balance_manager.free_balance(&exchange_manager, venue, "ETH").await?;
+    let mut free = HashMap::new();
+    free.insert("ETH".to_string(), dec("4"));
+    events.send(crate::exchanges::AccountEvent::Balances { exchange: "alpha".to_string(), free }).await?;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let eth = balance_manager.free_balance(&exchange_manager, venue, "ETH").await?;
+    let usdt = balance_manager.free_balance(&exchange_manager, venue, "USDT").await?;
+    assert!(eth == dec("4") && usdt == dec("500"),
+           "after a streamed update the cache held {} ETH and {} USDT", eth, usdt);
+    
+    events.send(crate::exchanges::AccountEvent::Disconnected { exchange: "binance".to_string() }).await?;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!tracker.is_streaming("binance"), "a dropped account stream was still marked as streaming");
+    
+    Ok(())
+}
+
+// A configured override replaces the venue's own rates; other venues still report theirs
+#[tokio::test]
+async fn fee_tiers() -> Result<()> {
+    let pair = TradingPair::new("ETH", "USDT");
+    
+    let alpha = MockExchange::new("alpha");
+    alpha.set_fees(dec("0.001"), dec("0.001"));
+    let beta = MockExchange::new("beta");
+    beta.set_fees(dec("0.002"), dec("0.002"));
+    let mut manager = ExchangeManager::new();
+    manager.add_exchange(Box::new(alpha));
+    manager.add_exchange(Box::new(beta));
+    manager.set_fee_override("alpha", crate::exchanges::TradingFees { maker_fee: dec("-0.0001"), taker_fee: dec("0.0004") });
+    
+    let overridden = manager.trading_fees(manager.get_exchange("alpha").expect("alpha registered"), &pair).await?;
+    assert!(overridden.taker_fee == dec("0.0004") && overridden.maker_fee == dec("-0.0001"),
+           "alpha's override was not applied: {:?}", overridden);
+    let reported = manager.trading_fees(manager.get_exchange("beta").expect("beta registered"), &pair).await?;
+    assert!(reported.taker_fee == dec("0.002"), "beta without an override reported {}", reported.taker_fee);
+    
+    Ok(())
+}
+
+// The run() venues with quote-sized buys: alpha spends what 10 ETH cost at the 1000.5 effective price,
+// gets whatever base that buys at its 1000 ask after fees, and beta sells exactly that
+#[tokio::test]
+async fn quote_buys() -> Result<()> {
+    let pair = TradingPair::new("ETH", "USDT");
+    
+    let alpha = MockExchange::new("alpha");
+    alpha.set_price(&pair, dec("999"), dec("1000"));
+    alpha.set_order_book(&pair, vec![(dec("999"), dec("5"))], vec![(dec("1000"), dec("5")), (dec("1001"), dec("5"))]);
+    alpha.set_fees(dec("0.001"), dec("0.001"));
+    alpha.set_balance("USDT", dec("100000"));
+    
+    let beta = MockExchange::new("beta");
+    beta.set_price(&pair, dec("1020"), dec("1021"));
+    beta.set_order_book(&pair, vec![(dec("1020"), dec("5")), (dec("1019"), dec("5"))], vec![(dec("1021"), dec("5"))]);
+    beta.set_fees(dec("0.001"), dec("0.001"));
+    beta.set_balance("ETH", dec("100"));
+    
+    let mut exchange_manager = ExchangeManager::new();
+    exchange_manager.add_exchange(Box::new(alpha));
+    exchange_manager.add_exchange(Box::new(beta));
+    
+    let mut config: Config = toml::from_str(SCENARIO_CONFIG)?;
+    config.trading.quote_sized_buys = true;
+    config.trading.order_type = LegOrderType::Market;
+    let database = Database::new(&config.database_url).await?;
+    database.migrate().await?;
+    let blockchain_manager = BlockchainManager::new(&config.blockchain).await?;
+    
+    let mut bot = ArbitrageBot::with_components(config, exchange_manager, blockchain_manager, database);
+    bot.run_cycle().await?;
+    
+    let now = chrono::Utc::now();
+    let trades = bot.database()
+        .get_trades_between(now - chrono::Duration::hours(1), now + chrono::Duration::hours(1), false)
+        .await?;
+    let buy = trades.iter().find(|trade| matches!(trade.side, TradeSide::Buy));
+    let sell = trades.iter().find(|trade| matches!(trade.side, TradeSide::Sell));
+    let (Some(buy), Some(sell)) = (buy, sell) else {
+        panic!("expected a buy and a sell leg, found {} trades", trades.len());
+    };
+    
+    let bought = dec("10005") / dec("1001");
+    assert!((buy.amount - bought).abs() < dec("0.000001"),
+           "the quote-sized buy reported {} rather than the {} ETH it bought", buy.amount, bought);
+    assert!(sell.amount == buy.amount,
+           "the sell leg sold {} after buying {}", sell.amount, buy.amount);
+    
+    let alpha_balances = bot.exchange_manager().get_exchange("alpha").unwrap().get_balances().await?;
+    let spent = dec("100000") - alpha_balances.get("USDT").map(|balance| balance.free).unwrap_or_default();
+    assert!((spent - dec("10005")).abs() < dec("0.000001"),
+           "alpha spent {} USDT instead of 10005", spent);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn time_in_force() -> Result<()> {
+    let pair = TradingPair::new("ETH", "USDT");
+    let request = |time_in_force, post_only| OrderRequest {
+        pair: pair.clone(),
+        side: TradeSide::Buy,
+        amount: OrderAmount::Base(dec("0.5")),
+        price: Some(dec("1990")),
+        options: OrderOptions { time_in_force, post_only, client_order_id: None },
+    };
+    
+    // A venue without native time in force: the IOC limit is cancelled instead of left resting
+    let alpha = MockExchange::new("alpha");
+    alpha.set_price(&pair, dec("2000"), dec("2002"));
+    alpha.set_fill_behavior(FillBehavior::Rest);
+    
+    let ioc = alpha.submit_order(&request(TimeInForce::Ioc, false)).await?;
+    assert!(matches!(ioc.status, TradeStatus::Cancelled), "an unfilled IOC limit ended as {:?}", ioc.status);
+    let gtc = alpha.submit_order(&request(TimeInForce::Gtc, false)).await?;
+    assert!(matches!(gtc.status, TradeStatus::Pending), "a GTC limit ended as {:?}", gtc.status);
+    let open = alpha.get_open_orders(Some(&pair)).await?;
+    assert!(open.len() == 1 && Some(&open[0].order_id) == gtc.order_id.as_ref(),
+           "expected only the GTC order resting, found {:?}", open.iter().map(|order| &order.order_id).collect::<Vec<_>>());
+    assert!(alpha.submit_order(&request(TimeInForce::Gtc, true)).await.is_err(),
+           "a venue without post-only accepted a post-only order");
+    
+    // A marketable limit fills at the market rather than at its limit
+    alpha.set_fill_behavior(FillBehavior::Instant);
+    let mut marketable = request(TimeInForce::Ioc, false);
+    marketable.price = Some(dec("2010"));
+    let filled = alpha.submit_order(&marketable).await?;
+    assert!(matches!(filled.status, TradeStatus::Executed) && filled.price == dec("2002"),
+           "a marketable IOC limit ended as {:?} at {}", filled.status, filled.price);
+    
+    // Legs default to IOC limits; a [pairs] entry can switch one pair to market orders
+    let config: Config = toml::from_str(&format!("{}\n[pairs.\"ETH/USDT\"]\norder_type = \"market\"\ntime_in_force = \"fok\"\n", SCENARIO_CONFIG))?;
+    let defaults = config.pair_settings("BTC/USDT");
+    assert!(defaults.order_type == LegOrderType::Limit && defaults.time_in_force == TimeInForce::Ioc,
+           "legs defaulted to {:?} / {:?}", defaults.order_type, defaults.time_in_force);
+    let overridden = config.pair_settings("ETH/USDT");
+    assert!(overridden.order_type == LegOrderType::Market && overridden.time_in_force == TimeInForce::Fok,
+           "the ETH/USDT override gave {:?} / {:?}", overridden.order_type, overridden.time_in_force);
+    
+    Ok(())
+}
+
+// The run() venues and config, scripted by `script` and `configure` before one cycle runs
+async fn scripted_cycle(configure: impl FnOnce(&mut Config), script: impl FnOnce(&MockExchange, &MockExchange)) -> Result<ArbitrageBot> {
+    let mut bot = scripted_bot(configure, script, None).await?;
+    bot.run_cycle().await?;
+    Ok(bot)
+}
+
+// A restarted bot is given the database of the one it replaces
+async fn scripted_bot(
+    configure: impl FnOnce(&mut Config),
+    script: impl FnOnce(&MockExchange, &MockExchange),
+    database: Option<Database>,
+) -> Result<ArbitrageBot> {
+    let pair = TradingPair::new("ETH", "USDT");
+    
+    let alpha = MockExchange::new("alpha");
+    alpha.set_price(&pair, dec("999"), dec("1000"));
+    alpha.set_order_book(&pair, vec![(dec("999"), dec("5"))], vec![(dec("1000"), dec("5")), (dec("1001"), dec("5"))]);
+    alpha.set_fees(dec("0.001"), dec("0.001"));
+    alpha.set_balance("USDT", dec("100000"));
+    
+    let beta = MockExchange::new("beta");
+    beta.set_price(&pair, dec("1020"), dec("1021"));
+    beta.set_order_book(&pair, vec![(dec("1020"), dec("5")), (dec("1019"), dec("5"))], vec![(dec("1021"), dec("5"))]);
+    beta.set_fees(dec("0.001"), dec("0.001"));
+    beta.set_balance("ETH", dec("100"));
+    script(&alpha, &beta);
+    
+    let mut exchange_manager = ExchangeManager::new();
+    exchange_manager.add_exchange(Box::new(alpha));
+    exchange_manager.add_exchange(Box::new(beta));
+    
+    let mut config: Config = toml::from_str(SCENARIO_CONFIG)?;
+    configure(&mut config);
+    let database = match database {
+        Some(database) => database,
+        None => {
+            let database = Database::new(&config.database_url).await?;
+            database.migrate().await?;
+            database
+        }
+    };
+    let blockchain_manager = BlockchainManager::new(&config.blockchain).await?;
+    
+    Ok(ArbitrageBot::with_components(config, exchange_manager, blockchain_manager, database))
+}
+
+// Recorded trades of the cycle and the ETH `venue` ended up with
+async fn cycle_outcome(bot: &ArbitrageBot, venue: &str) -> Result<(Vec<Trade>, Decimal)> {
+    let now = chrono::Utc::now();
+    let mut trades = bot.database()
+        .get_trades_between(now - chrono::Duration::hours(1), now + chrono::Duration::hours(1), false)
+        .await?;
+    trades.sort_by_key(|trade| trade.client_order_id.clone());
+    let balances = bot.exchange_manager().get_exchange(venue).unwrap().get_balances().await?;
+    Ok((trades, balances.get("ETH").map(|balance| balance.free).unwrap_or_default()))
+}
+
+// One line per recorded trade, for assertion messages
+fn describe(trades: &[Trade]) -> Vec<String> {
+    trades.iter()
+        .map(|trade| format!("{:?} {} on {} {:?}", trade.side, trade.amount, trade.exchange, trade.status))
+        .collect()
+}
+
+// What one side of a cycle filled on `venue`, and over how many orders
+fn leg(trades: &[Trade], venue: &str, side: TradeSide) -> (Decimal, usize) {
+    let orders: Vec<_> = trades.iter().filter(|trade| trade.exchange == venue && trade.side == side).collect();
+    let filled = orders.iter()
+        .filter(|trade| matches!(trade.status, TradeStatus::Executed))
+        .map(|trade| trade.amount)
+        .sum();
+    (filled, orders.len())
+}
+
+// Buy orders placed, filled or not
+fn buys(trades: &[Trade]) -> usize {
+    trades.iter().filter(|trade| trade.side == TradeSide::Buy).count()
+}
+
+#[tokio::test]
+async fn client_order_ids() -> Result<()> {
+    let opportunity_id = uuid::Uuid::new_v4();
+    let buy_id = crate::arbitrage::client_order_id(opportunity_id, "b");
+    assert!(buy_id == crate::arbitrage::client_order_id(opportunity_id, "b") && buy_id.len() <= 36,
+           "client id {:?} is not stable or too long for Binance", buy_id);
+    assert!(buy_id != crate::arbitrage::client_order_id(opportunity_id, "s"),
+           "both legs got the same client id");
+    
+    // Only failures that may have been carried out count as ambiguous
+    let manager = ExchangeManager::new();
+    let timed_out = manager.call("alpha", "place_buy_order", Duration::from_millis(10), async {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        Ok(())
+    }).await.unwrap_err();
+    assert!(crate::exchanges::outcome_unknown(&timed_out), "a timed-out placement was treated as rejected");
+    assert!(crate::exchanges::outcome_unknown(&anyhow::Error::new(OutcomeUnknown).context("Binance API error: 503")),
+           "a 5xx placement was treated as rejected");
+    assert!(!crate::exchanges::outcome_unknown(&anyhow::anyhow!("Binance API error: insufficient balance")),
+           "a rejected placement was treated as ambiguous");
+    
+    // The reply is lost but the order went through: the lookup finds it and nothing is placed twice
+    let bot = scripted_cycle(|_| {}, |alpha, _| {
+        alpha.accept_client_order_ids();
+        alpha.lose_next_replies(1);
+    }).await?;
+    let (trades, eth) = cycle_outcome(&bot, "alpha").await?;
+    assert!(eth == dec("10"), "after a lost reply alpha holds {} ETH instead of one order's 10", eth);
+    let buy = trades.iter().find(|trade| matches!(trade.side, TradeSide::Buy));
+    assert!(buy.map_or(false, |buy| buy.client_order_id.as_deref() == Some(crate::arbitrage::client_order_id(buy.opportunity_id, "b").as_str())),
+           "the recovered buy was recorded as {:?}", buy.map(|buy| &buy.client_order_id));
+    assert!(trades.iter().any(|trade| matches!(trade.side, TradeSide::Sell)),
+           "the sell leg did not follow the recovered buy");
+    
+    // The request never arrived: the lookup finds nothing and the order is placed again, once
+    let bot = scripted_cycle(|_| {}, |alpha, _| {
+        alpha.accept_client_order_ids();
+        alpha.lose_next_requests(1);
+    }).await?;
+    let (trades, eth) = cycle_outcome(&bot, "alpha").await?;
+    assert!(eth == dec("10") && trades.len() == 2,
+           "after a lost request alpha holds {} ETH over {} recorded trades", eth, trades.len());
+    
+    // Without client ids the outcome cannot be checked, so the leg fails rather than risk a second order
+    let bot = scripted_cycle(|_| {}, |alpha, _| alpha.lose_next_replies(1)).await?;
+    let (trades, eth) = cycle_outcome(&bot, "alpha").await?;
+    assert!(eth == dec("10") && trades.is_empty(),
+           "without client ids alpha holds {} ETH and {} trades were recorded", eth, trades.len());
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn order_timeouts() -> Result<()> {
+    // Orders rest until the 50ms leg timeout cancels them; client ids tell the attempts apart
+    let resting = |config: &mut Config| {
+        config.trading.time_in_force = TimeInForce::Gtc;
+        config.trading.order_timeout_ms = 50;
+    };
+    
+    // A buy that never fills is cancelled at the timeout and requoted; the requote fills in full
+    let bot = scripted_cycle(resting, |alpha, beta| {
+        alpha.accept_client_order_ids();
+        beta.accept_client_order_ids();
+        alpha.script_fills(vec![FillBehavior::Rest]);
+    }).await?;
+    let (trades, eth) = cycle_outcome(&bot, "alpha").await?;
+    assert!(leg(&trades, "alpha", TradeSide::Buy) == (dec("10"), 2) && leg(&trades, "beta", TradeSide::Sell) == (dec("10"), 1) && eth == dec("10"),
+           "slow buy: alpha holds {} ETH after {:?}", eth, describe(&trades));
+    assert!(matches!(trades.first().map(|trade| &trade.status), Some(TradeStatus::Cancelled))
+              && trades.get(1).and_then(|trade| trade.client_order_id.as_deref()).map_or(false, |id| id.ends_with("-b2")),
+           "slow buy: the timed-out order was not recorded as cancelled and requoted: {:?}", describe(&trades));
+    
+    // A buy that fills 40% and then stalls keeps those 4 ETH through two fruitless requotes, and only they are sold
+    let bot = scripted_cycle(resting, |alpha, beta| {
+        alpha.accept_client_order_ids();
+        beta.accept_client_order_ids();
+        alpha.script_fills(vec![FillBehavior::PartialRest(dec("0.4"))]);
+        alpha.set_fill_behavior(FillBehavior::Rest);
+    }).await?;
+    let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+    let (_, beta_eth) = cycle_outcome(&bot, "beta").await?;
+    assert!(leg(&trades, "alpha", TradeSide::Buy) == (dec("4"), 3),
+           "partial buy: expected 4 ETH over the order and two requotes, got {:?}", describe(&trades));
+    assert!(leg(&trades, "beta", TradeSide::Sell) == (dec("4"), 1) && beta_eth == dec("96"),
+           "partial buy: the sell leg was not sized to the 4 ETH bought: {:?}, beta holds {} ETH", describe(&trades), beta_eth);
+    
+    // A sell that fills half and stalls is requoted twice; the unsold half is left over rather than sold twice
+    let bot = scripted_cycle(resting, |alpha, beta| {
+        alpha.accept_client_order_ids();
+        beta.accept_client_order_ids();
+        beta.script_fills(vec![FillBehavior::PartialRest(dec("0.5"))]);
+        beta.set_fill_behavior(FillBehavior::Rest);
+    }).await?;
+    let (trades, beta_eth) = cycle_outcome(&bot, "beta").await?;
+    assert!(leg(&trades, "alpha", TradeSide::Buy) == (dec("10"), 1) && leg(&trades, "beta", TradeSide::Sell) == (dec("5"), 3) && beta_eth == dec("95"),
+           "partial sell: beta holds {} ETH after {:?}", beta_eth, describe(&trades));
+    
+    // An IOC buy that fills half comes back at once; the remainder is requoted without waiting
+    let bot = scripted_cycle(|_| {}, |alpha, beta| {
+        alpha.accept_client_order_ids();
+        beta.accept_client_order_ids();
+        alpha.script_fills(vec![FillBehavior::Partial(dec("0.5"))]);
+    }).await?;
+    let (trades, beta_eth) = cycle_outcome(&bot, "beta").await?;
+    assert!(leg(&trades, "alpha", TradeSide::Buy) == (dec("10"), 2) && leg(&trades, "beta", TradeSide::Sell) == (dec("10"), 1) && beta_eth == dec("90"),
+           "IOC partial buy: beta holds {} ETH after {:?}", beta_eth, describe(&trades));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn unwind() -> Result<()> {
+    let unwinding = |config: &mut Config| {
+        config.trading.unwind_on_leg_failure = true;
+        config.trading.time_in_force = TimeInForce::Gtc;
+        config.trading.order_timeout_ms = 50;
+    };
+    // Executed sells back on alpha, where the ETH was bought
+    let unwound = |trades: &[Trade]| leg(trades, "alpha", TradeSide::Sell).0;
+    let scripted = |fills: Vec<FillBehavior>, fallback: FillBehavior| move |alpha: &MockExchange, beta: &MockExchange| {
+        alpha.accept_client_order_ids();
+        beta.accept_client_order_ids();
+        beta.script_fills(fills);
+        beta.set_fill_behavior(fallback);
+    };
+    
+    // The sell is rejected outright: all 10 ETH go back on alpha, under the same opportunity and at a loss
+    let bot = scripted_cycle(unwinding, scripted(Vec::new(), FillBehavior::Reject)).await?;
+    let (trades, alpha_eth) = cycle_outcome(&bot, "alpha").await?;
+    assert!(unwound(&trades) == dec("10") && alpha_eth.is_zero(),
+           "rejected sell: alpha holds {} ETH after {:?}", alpha_eth, describe(&trades));
+    assert!(trades.iter().all(|trade| trade.opportunity_id == trades[0].opportunity_id)
+              && trades.iter().any(|trade| trade.client_order_id.as_deref().map_or(false, |id| id.ends_with("-u"))),
+           "rejected sell: the unwind was not recorded under the opportunity: {:?}", describe(&trades));
+    let now = chrono::Utc::now();
+    let pnl = crate::pnl::summarize(now - chrono::Duration::hours(1), now, &trades);
+    assert!(pnl.total_realized < Decimal::ZERO && pnl.inventory.values().all(|change| change.quantity.is_zero()),
+           "rejected sell: PnL shows {} realized and {:?} inventory instead of the unwind loss", pnl.total_realized, pnl.inventory);
+    
+    // The sell rests through every requote: cancelled at each timeout, then unwound in full
+    let bot = scripted_cycle(unwinding, scripted(Vec::new(), FillBehavior::Rest)).await?;
+    let (trades, alpha_eth) = cycle_outcome(&bot, "alpha").await?;
+    let (_, beta_eth) = cycle_outcome(&bot, "beta").await?;
+    assert!(unwound(&trades) == dec("10") && alpha_eth.is_zero() && beta_eth == dec("100"),
+           "timed-out sell: alpha holds {} and beta {} ETH after {:?}", alpha_eth, beta_eth, describe(&trades));
+    
+    // Half the sell fills before it stalls: only the unsold half is unwound
+    let bot = scripted_cycle(unwinding, scripted(vec![FillBehavior::PartialRest(dec("0.5"))], FillBehavior::Rest)).await?;
+    let (trades, alpha_eth) = cycle_outcome(&bot, "alpha").await?;
+    let (_, beta_eth) = cycle_outcome(&bot, "beta").await?;
+    assert!(unwound(&trades) == dec("5") && alpha_eth == dec("5") && beta_eth == dec("95"),
+           "partially filled sell: alpha holds {} and beta {} ETH after {:?}", alpha_eth, beta_eth, describe(&trades));
+    
+    // beta will not cancel the resting sell: it may still fill, so none of it is unwound and it stays open
+    let bot = scripted_cycle(unwinding, |alpha, beta| {
+        scripted(Vec::new(), FillBehavior::Rest)(alpha, beta);
+        beta.reject_next(MockCall::CancelOrder, 1, BotError::rejected("beta", None, "Order is being processed"));
+    }).await?;
+    let (trades, alpha_eth) = cycle_outcome(&bot, "alpha").await?;
+    let open = bot.list_open_orders(Some("beta")).await?.orders;
+    assert!(unwound(&trades).is_zero() && alpha_eth == dec("10") && open.len() == 1,
+           "uncancellable sell: alpha holds {} ETH with {} orders open on beta after {:?}", alpha_eth, open.len(), describe(&trades));
+    
+    // beta sold but its reply was lost and it has no client ids to look the order up by: unwinding as well
+    // would sell the ETH twice, so the sell is left unresolved
+    let bot = scripted_cycle(unwinding, |_, beta| beta.lose_next_replies(1)).await?;
+    let (trades, alpha_eth) = cycle_outcome(&bot, "alpha").await?;
+    let (_, beta_eth) = cycle_outcome(&bot, "beta").await?;
+    assert!(unwound(&trades).is_zero() && alpha_eth == dec("10") && beta_eth == dec("90"),
+           "lost sell reply: alpha holds {} and beta {} ETH after {:?}", alpha_eth, beta_eth, describe(&trades));
+    
+    // Accumulated assets are kept where they were bought
+    let accumulating = |config: &mut Config| {
+        unwinding(config);
+        config.trading.accumulate_assets = vec!["eth".to_string()];
+    };
+    let bot = scripted_cycle(accumulating, scripted(Vec::new(), FillBehavior::Reject)).await?;
+    let (trades, alpha_eth) = cycle_outcome(&bot, "alpha").await?;
+    assert!(unwound(&trades).is_zero() && alpha_eth == dec("10"),
+           "accumulated asset: alpha holds {} ETH after {:?}", alpha_eth, describe(&trades));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn route_cooldown() -> Result<()> {
+    let route = "ETH/USDT-alpha-beta";
+    // beta rejects every sell, so each cycle re-detects the route and fails it again
+    let configure = |config: &mut Config| {
+        config.trading.route_cooldown.failure_threshold = 2;
+        config.trading.route_cooldown.cooldown_seconds = 60;
+    };
+    let rejecting = |_: &MockExchange, beta: &MockExchange| beta.set_fill_behavior(FillBehavior::Reject);
+    
+    let mut bot = scripted_bot(configure, rejecting, None).await?;
+    for _ in 0..4 {
+        bot.run_cycle().await?;
+    }
+    let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+    assert!(buys(&trades) == 2,
+           "the route was attempted {} times over 4 cycles instead of cooling down after 2 failures", buys(&trades));
+    
+    let failures = bot.database().get_route_failures().await?;
+    let persisted = failures.iter().find(|failure| failure.route == route);
+    assert!(persisted.map_or(false, |failure| {
+              failure.consecutive_failures == 2 && failure.cooled_until.map_or(false, |until| until > chrono::Utc::now() + chrono::Duration::seconds(50))
+          }),
+           "the route's failures were persisted as {:?}", failures);
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(crate::status::serve(listener, bot.status_state()));
+    let status: serde_json::Value = reqwest::get(format!("http://{}/status", address)).await?.json().await?;
+    let cooled = &status["cooled_routes"];
+    assert!(cooled[0]["route"] == route && cooled[0]["consecutive_failures"] == 2 && cooled[0]["cooldown_remaining_seconds"].as_f64().map_or(false, |remaining| remaining > 50.0),
+           "/status listed cooled routes {}", cooled);
+    
+    // A restart keeps the route benched: nothing executes even though beta would now fill
+    let mut restarted = scripted_bot(configure, |_, _| {}, Some(bot.database().clone())).await?;
+    restarted.restore_route_cooldowns().await?;
+    restarted.run_cycle().await?;
+    let (trades, _) = cycle_outcome(&restarted, "alpha").await?;
+    assert!(buys(&trades) == 2,
+           "after a restart the cooling route was attempted again ({} buys)", buys(&trades));
+    
+    // Once the cooldown has passed the route trades again, and a success forgets its failures
+    let mut lifted = scripted_bot(configure, |_, _| {}, Some(bot.database().clone())).await?;
+    let mut expired = persisted.cloned().unwrap_or(crate::models::RouteFailure {
+        route: route.to_string(),
+        consecutive_failures: 2,
+        cooled_until: None,
+        last_error: None,
+        updated_at: chrono::Utc::now(),
+    });
+    expired.cooled_until = Some(chrono::Utc::now() - chrono::Duration::seconds(1));
+    bot.database().save_route_failure(&expired).await?;
+    lifted.restore_route_cooldowns().await?;
+    lifted.run_cycle().await?;
+    let (trades, _) = cycle_outcome(&lifted, "alpha").await?;
+    let failures = lifted.database().get_route_failures().await?;
+    assert!(buys(&trades) == 3 && failures.is_empty(),
+           "after the cooldown the route made {} buys in total and left failures {:?}", buys(&trades), failures);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn latency_filter() -> Result<()> {
+    let pair = TradingPair::new("ETH", "USDT");
+    
+    // Fresh quotes from quick venues pass both checks
+    let bot = scripted_cycle(|config| {
+        config.trading.max_quote_age_ms = Some(1000);
+        config.trading.max_execution_latency_ms = Some(500);
+    }, |_, _| {}).await?;
+    let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+    assert!(bot.stats().opportunities_skipped == 0 && buys(&trades) == 1,
+           "fresh quotes were skipped {:?} and made {} buys", bot.stats().skips_by_reason, buys(&trades));
+    
+    // A venue reporting a quote older than the limit takes the opportunity with it, even while the
+    // scanner's own freshness window (twice the check interval) would still use the quote
+    let bot = scripted_cycle(|config| config.trading.max_quote_age_ms = Some(1000), |alpha, _| {
+        alpha.backdate_price(&pair, chrono::Duration::milliseconds(1500));
+    }).await?;
+    let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+    assert!(bot.stats().skips_by_reason.get("stale_quote") == Some(&1) && bot.stats().opportunities_found == 0 && trades.is_empty(),
+           "a 1.5s old quote was skipped {:?}, found {} opportunities and made {} trades", bot.stats().skips_by_reason, bot.stats().opportunities_found, trades.len());
+    
+    // Two venues each taking 40ms to answer blow a 50ms execution budget
+    let bot = scripted_cycle(|config| config.trading.max_execution_latency_ms = Some(50), |alpha, beta| {
+        alpha.set_latency(Duration::from_millis(40));
+        beta.set_latency(Duration::from_millis(40));
+    }).await?;
+    let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+    assert!(bot.stats().skips_by_reason.get("latency_budget") == Some(&1) && trades.is_empty(),
+           "slow venues were skipped {:?} and made {} trades", bot.stats().skips_by_reason, trades.len());
+    
+    // One leg alone over the budget is enough, whatever is known about the other
+    let slow_leg = scripted_cycle(|config| config.trading.max_execution_latency_ms = Some(50), |alpha, _| {
+        alpha.set_latency(Duration::from_millis(60));
+    }).await?;
+    let (trades, _) = cycle_outcome(&slow_leg, "alpha").await?;
+    assert!(slow_leg.stats().skips_by_reason.get("latency_budget") == Some(&1) && trades.is_empty(),
+           "a single slow leg was skipped {:?} and made {} trades", slow_leg.stats().skips_by_reason, trades.len());
+    
+    let health = bot.exchange_manager().health();
+    let alpha = health.iter().find(|health| health.exchange == "alpha");
+    assert!(alpha.map_or(false, |alpha| {
+              alpha.p95_latency.map_or(false, |p95| p95 >= Duration::from_millis(40))
+                  && alpha.max_latency >= alpha.p95_latency && alpha.order_latency.is_none()
+          }),
+           "alpha's latency stats were {:?}", alpha);
+    
+    // Once orders have gone through, their latency is what the budget is held against
+    let bot = scripted_cycle(|_| {}, |_, _| {}).await?;
+    let order_latency = bot.exchange_manager().health_tracker().order_latency("alpha");
+    assert!(order_latency.is_some(),
+           "an executed buy left no order latency estimate for alpha");
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(crate::status::serve(listener, bot.status_state()));
+    let status: serde_json::Value = reqwest::get(format!("http://{}/status", address)).await?.json().await?;
+    let exchanges = status["exchanges"].as_array().cloned().unwrap_or_default();
+    assert!(exchanges.iter().any(|exchange| exchange["exchange"] == "alpha" && exchange["p95_latency_ms"].is_number() && exchange["order_latency_ms"].is_number()),
+           "/status reported exchanges {:?}", exchanges);
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn spread_cap() -> Result<()> {
+    // The scripted route nets about 1.8%, past a 1% cap: it is recorded but held
+    let configure = |config: &mut Config| config.trading.max_profit_threshold = Some(dec("1"));
+    let mut bot = scripted_cycle(configure, |_, _| {}).await?;
+    let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+    let held = bot.database().get_opportunities_with_status(&OpportunityStatus::Active).await?;
+    assert!(trades.is_empty() && held.len() == 1 && held[0].requires_approval && held[0].approved_at.is_none(),
+           "a capped spread made {} trades and left active opportunities {:?}", trades.len(), held);
+    
+    bot.run_cycle().await?;
+    let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+    assert!(trades.is_empty(), "an unapproved opportunity made {} trades on a later cycle", trades.len());
+    
+    // Approval goes through the database, as the CLI does, and is picked up by the next cycle
+    let id = held[0].id;
+    let approved = bot.database().approve_opportunity(id, chrono::Utc::now()).await?;
+    let approved_twice = bot.database().approve_opportunity(id, chrono::Utc::now()).await?;
+    assert!(approved && !approved_twice,
+           "approving returned {} the first time and {} the second", approved, approved_twice);
+    bot.run_cycle().await?;
+    let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+    assert!(buys(&trades) == 1,
+           "the approved opportunity made {} buys", buys(&trades));
+    
+    // Below the cap nothing waits for approval
+    let bot = scripted_cycle(|config| config.trading.max_profit_threshold = Some(dec("5")), |_, _| {}).await?;
+    let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+    assert!(buys(&trades) == 1, "an uncapped spread made {} buys", buys(&trades));
+    
+    // Suspended transfers on either side block execution but keep the opportunity
+    for (venue, deposits, withdrawals) in [("alpha", true, false), ("beta", false, true)] {
+        let bot = scripted_cycle(|_| {}, |alpha, beta| {
+            let exchange = if venue == "alpha" { alpha } else { beta };
+            exchange.set_transfer_status("ETH", deposits, withdrawals);
+        }).await?;
+        let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+        let active = bot.database().get_opportunities_with_status(&OpportunityStatus::Active).await?;
+        assert!(trades.is_empty() && active.len() == 1,
+               "with ETH deposits {} and withdrawals {} on {}, {} trades were made and {} opportunities left active", deposits, withdrawals, venue, trades.len(), active.len());
+    }
+    
+    // Suspensions that do not stop inventory moving from the buy to the sell venue do not block it
+    let bot = scripted_cycle(|_| {}, |alpha, beta| {
+        alpha.set_transfer_status("ETH", false, true);
+        beta.set_transfer_status("ETH", true, false);
+    }).await?;
+    let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+    assert!(buys(&trades) == 1,
+           "suspensions unrelated to moving ETH from alpha to beta left {} buys", buys(&trades));
+    
+    Ok(())
+}
+
+#[tokio::test]
+async fn token_safety() -> Result<()> {
+    let on_chain = |probe: Option<TokenProbe>| move |alpha: &MockExchange, _: &MockExchange| {
+        alpha.set_chain_id(1);
+        if let Some(probe) = probe {
+            alpha.set_token_probe("ETH", probe);
+        }
+    };
+    let honeypot = TokenProbe {
+        sell_revert: Some("TRANSFER_FAILED".to_string()),
+        implementation: Some("0x00000000000000000000000000000000000000aa".to_string()),
+        ..TokenProbe::default()
+    };
+    
+    // A token that round-trips cleanly, or with a transfer tax under the limit, trades as usual
+    for (label, probe) in [("a clean token", None), ("a 0.5% tax", Some(TokenProbe { buy_tax: dec("0.005"), ..TokenProbe::default() }))] {
+        let bot = scripted_cycle(|_| {}, on_chain(probe)).await?;
+        let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+        assert!(buys(&trades) == 1, "{} made {} buys", label, buys(&trades));
+    }
+    
+    // Failing tokens are blacklisted and never become opportunities
+    let failing = [
+        ("a sell that reverts", honeypot.clone()),
+        ("a 5% sell tax", TokenProbe { sell_tax: dec("0.05"), ..TokenProbe::default() }),
+        ("a buy that reverts", TokenProbe { buy_revert: Some("reverted without a reason".to_string()), ..TokenProbe::default() }),
+    ];
+    for (label, probe) in failing {
+        let bot = scripted_cycle(|_| {}, on_chain(Some(probe))).await?;
+        let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+        let active = bot.database().get_opportunities_with_status(&OpportunityStatus::Active).await?;
+        let blacklisted = bot.database().get_blacklisted_tokens().await?;
+        assert!(trades.is_empty() && active.is_empty(),
+               "{} made {} trades and {} active opportunities", label, trades.len(), active.len());
+        assert!(blacklisted.len() == 1 && blacklisted[0].token == "ETH" && blacklisted[0].chain_id == 1 && blacklisted[0].exchange == "alpha",
+               "{} left the blacklist {:?}", label, blacklisted);
+    }
+    
+    // The blacklist survives a restart: the restarted venue would now probe clean, but is not probed again
+    let bot = scripted_cycle(|_| {}, on_chain(Some(honeypot.clone()))).await?;
+    let reason = bot.database().get_blacklisted_tokens().await?.first().map(|token| token.reason.clone()).unwrap_or_default();
+    assert!(reason.contains("selling it reverts") && reason.contains("TRANSFER_FAILED") && reason.contains("proxy to"),
+           "the honeypot was blacklisted with reason {:?}", reason);
+    let mut restarted = scripted_bot(|_| {}, on_chain(None), Some(bot.database().clone())).await?;
+    restarted.restore_token_blacklist().await?;
+    restarted.run_cycle().await?;
+    let (trades, _) = cycle_outcome(&restarted, "alpha").await?;
+    assert!(trades.is_empty(), "a blacklisted token made {} trades after a restart", trades.len());
+    
+    // Trusting the token overrides both the probe and the blacklist
+    let trust = |config: &mut Config| config.trading.token_safety.trusted_tokens = vec!["eth".to_string()];
+    let mut trusted = scripted_bot(trust, on_chain(Some(honeypot.clone())), Some(bot.database().clone())).await?;
+    trusted.restore_token_blacklist().await?;
+    trusted.run_cycle().await?;
+    let (trades, _) = cycle_outcome(&trusted, "alpha").await?;
+    assert!(buys(&trades) == 1, "a trusted, blacklisted token made {} buys", buys(&trades));
+    
+    // Venues off chain are never probed
+    let bot = scripted_cycle(|_| {}, |alpha, _| alpha.set_token_probe("ETH", TokenProbe { sell_tax: dec("0.5"), ..TokenProbe::default() })).await?;
+    let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+    assert!(buys(&trades) == 1, "a centralized venue's token made {} buys", buys(&trades));
+    
+    Ok(())
+}
+
+// Executions land in the fill counts the scorer reads for the route
+#[tokio::test]
+async fn opportunity_scoring() -> Result<()> {
+    let bot = scripted_cycle(|_| {}, |_, _| {}).await?;
+    let counts = bot.database().get_route_fill_counts(chrono::Utc::now() - chrono::Duration::days(1)).await?;
+    assert!(counts.len() == 1 && counts[0].pair == "ETH/USDT" && counts[0].buy_exchange == "alpha"
+              && counts[0].sell_exchange == "beta" && counts[0].executed == 1 && counts[0].failed == 0,
+           "route fill counts after one execution: {:?}", counts);
+    
+    Ok(())
+}
+
+// Two routes buying with the same alpha USDT run in one batch. Each leg waits on the venue, so
+// without reservations made one at a time both would size against the whole balance.
+#[tokio::test]
+async fn batch_reservations() -> Result<()> {
+    let btc = TradingPair::new("BTC", "USDT");
+    let configure = |config: &mut Config| {
+        config.trading.max_concurrent_trades = 2;
+        for exchange in config.exchanges.values_mut() {
+            exchange.trading_pairs.push("BTC/USDT".to_string());
+        }
+    };
+    let bot = scripted_cycle(configure, |alpha, beta| {
+        alpha.set_balance("USDT", dec("15000"));
+        alpha.set_price(&btc, dec("9990"), dec("10000"));
+        alpha.set_order_book(&btc, vec![(dec("9990"), dec("1"))], vec![(dec("10000"), dec("1"))]);
+        alpha.set_latency(Duration::from_millis(5));
+        beta.set_price(&btc, dec("10200"), dec("10210"));
+        beta.set_order_book(&btc, vec![(dec("10200"), dec("1"))], vec![(dec("10210"), dec("1"))]);
+        beta.set_balance("BTC", dec("10"));
+        beta.set_latency(Duration::from_millis(5));
+    }).await?;
+    
+    let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+    let bought: Vec<&str> = trades.iter()
+        .filter(|trade| trade.side == TradeSide::Buy)
+        .map(|trade| trade.pair.symbol.as_str())
+        .collect();
+    assert!(bought.len() == 2, "the batch bought {:?}", bought);
+    let balances = bot.exchange_manager().get_exchange("alpha").unwrap().get_balances().await?;
+    let usdt = balances.get("USDT").map(|balance| balance.free).unwrap_or_default();
+    assert!(usdt >= Decimal::ZERO, "the batch spent more USDT than alpha held, leaving {}", usdt);
+    
+    // With one slot, a better-ranked route that fails its reservation leaves the slot to the next one
+    let bot = scripted_cycle(|config| {
+        configure(config);
+        config.trading.max_concurrent_trades = 1;
+    }, |alpha, beta| {
+        alpha.set_price(&btc, dec("9990"), dec("10000"));
+        alpha.set_order_book(&btc, vec![(dec("9990"), dec("1"))], vec![(dec("10000"), dec("1"))]);
+        alpha.set_transfer_status("BTC", true, false);
+        beta.set_price(&btc, dec("11000"), dec("11010"));
+        beta.set_order_book(&btc, vec![(dec("11000"), dec("1"))], vec![(dec("11010"), dec("1"))]);
+        beta.set_balance("BTC", dec("10"));
+    }).await?;
+    let (trades, _) = cycle_outcome(&bot, "alpha").await?;
+    let bought: Vec<&str> = trades.iter()
+        .filter(|trade| trade.side == TradeSide::Buy)
+        .map(|trade| trade.pair.symbol.as_str())
+        .collect();
+    assert!(bought == ["ETH/USDT"], "with BTC withdrawals suspended the single slot bought {:?}", bought);
+    
+    Ok(())
+}
+
+// Through a cycle: alpha becomes a 1000 pool and beta's bids net 1018.98 then 1017.981, so the size lands in
+// beta's second level where 1.001 * QB / (B - y)^2 = 1017.981, and the chosen size is persisted with its margin
+#[tokio::test]
+async fn size_solver() -> Result<()> {
+    let close = |actual: Decimal, expected: f64| Decimal::from_f64(expected)
+        .map_or(false, |expected| (actual - expected).abs() < dec("0.000001"));
+    let pair = TradingPair::new("ETH", "USDT");
+    let bot = scripted_cycle(
+        |config| {
+            config.trading.max_slippage = dec("0.05");
+            if let Some(alpha) = config.exchanges.get_mut("alpha") {
+                alpha.max_trade_amount = dec("100");
+            }
+        },
+        |alpha, _| alpha.set_pool(&pair, dec("1000"), dec("1000000")),
+    ).await?;
+    let expected = 1e3 - (1.001 * 1e9f64 / (1019.0 * 0.999)).sqrt();
+    let (trades, _) = cycle_outcome(&bot, "beta").await?;
+    match trades.first() {
+        Some(trade) => {
+            let opportunity = bot.database().get_opportunity(trade.opportunity_id).await?;
+            assert!(opportunity.as_ref().map_or(false, |opportunity| close(opportunity.max_trade_size, expected)
+                      && opportunity.marginal_profit.abs() < dec("0.0001")),
+                   "the cycle sized its opportunity as {:?}, expected {:.6}", opportunity.map(|o| (o.max_trade_size, o.marginal_profit)), expected);
+        },
+        None => panic!("the pool route traded nothing"),
+    }
+    
+    Ok(())
+}
+
+// One cycle records both directions, the profitable one above the 0.5% threshold
+#[tokio::test]
+async fn spread_history() -> Result<()> {
+    let bot = scripted_cycle(|_| {}, |_, _| {}).await?;
+    let now = chrono::Utc::now();
+    let cycle = bot.database().get_spread_buckets(now - chrono::Duration::hours(1), now + chrono::Duration::hours(1)).await?;
+    let forward = cycle.iter().find(|bucket| bucket.buy_exchange == "alpha");
+    let backward = cycle.iter().find(|bucket| bucket.buy_exchange == "beta");
+    assert!(cycle.len() == 2 && forward.map_or(false, |bucket| bucket.samples == 1 && bucket.above_threshold == 1 && bucket.net_sum > 0.5)
+              && backward.map_or(false, |bucket| bucket.samples == 1 && bucket.above_threshold == 0 && bucket.net_sum < 0.0),
+           "the cycle recorded {:?}", cycle);
+    
+    Ok(())
+}
+
+// Execution: a refused balance neither cools the route nor halts, bad credentials engage the kill switch,
+// and any other rejection counts against the route
+#[tokio::test]
+async fn error_taxonomy() -> Result<()> {
+    let configure = |config: &mut Config| config.trading.route_cooldown.failure_threshold = 1;
+    for (name, error, expect_cooldown, expect_halt) in [
+        ("insufficient balance", BotError::InsufficientBalance { exchange: "alpha".to_string(), message: "Insufficient funds".to_string() }, false, false),
+        ("bad credentials", BotError::AuthFailed { exchange: "alpha".to_string(), message: "Unauthorized".to_string() }, false, true),
+        ("other rejection", BotError::rejected("alpha", None, "Order does not exist."), true, false),
+    ] {
+        let mut bot = scripted_bot(configure, |alpha, _| alpha.reject_next(MockCall::PlaceOrder, 1, error), None).await?;
+        bot.run_cycle().await?;
+        let failures = bot.database().get_route_failures().await?;
+        assert!(failures.is_empty() != expect_cooldown && bot.kill_switch_engaged() == expect_halt,
+               "{}: route failures {:?}, kill switch engaged: {}", name, failures, bot.kill_switch_engaged());
+    }
+    
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use rust_decimal::Decimal;
+use std::fmt;
+use std::time::Duration;
+
+use crate::config::OpportunityScoringConfig;
+
+// What one opportunity's score is built from
+#[derive(Debug, Clone)]
+pub struct ScoreInputs {
+    // Adjusted profit at the opportunity's full size
+    pub profit_usd: Decimal,
+    // Finished executions of the route on record
+    pub executed: u64,
+    pub failed: u64,
+    // Of the older of the two quotes; None when either is no longer cached, which scores as stale
+    pub quote_age: Option<Duration>,
+    // Both legs at the venues' measured order latency; None until either venue has been measured
+    pub latency: Option<Duration>,
+}
+
+#[derive(Debug, Clone)]
+pub struct OpportunityScore {
+    pub inputs: ScoreInputs,
+    pub profit: Decimal,
+    pub reliability: Decimal,
+    pub freshness: Decimal,
+    pub speed: Decimal,
+    pub total: Decimal,
+}
+
+impl fmt::Display for OpportunityScore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inputs = &self.inputs;
+        let millis = |duration: Option<Duration>, missing: &str| duration
+            .map(|duration| format!("{}ms", duration.as_millis()))
+            .unwrap_or_else(|| missing.to_string());
+        write!(f, "score {}: profit ${} ({}), fills {}/{} ({}), quote age {} ({}), latency {} ({})",
+               self.total.round_dp(3), inputs.profit_usd.round_dp(2), self.profit.round_dp(3),
+               inputs.executed, inputs.executed + inputs.failed, self.reliability.round_dp(3),
+               millis(inputs.quote_age, "unknown"), self.freshness.round_dp(3),
+               millis(inputs.latency, "unmeasured"), self.speed.round_dp(3))
+    }
+}
+
+// Share of `limit` left after `value`, floored at zero
+fn remaining(value: Duration, limit: Duration) -> Decimal {
+    if limit.is_zero() {
+        return Decimal::ZERO;
+    }
+    let millis = |duration: Duration| Decimal::from(duration.as_millis().min(u64::MAX as u128) as u64);
+    let used = millis(value) / millis(limit).max(Decimal::ONE);
+    (Decimal::ONE - used).max(Decimal::ZERO)
+}
+
+// Best first. Profit is scaled against the best candidate, so scores only compare within one batch. Untried
+// routes and unmeasured venues start from an even 0.5, which a successful history then raises
+pub fn rank<T>(
+    candidates: Vec<(T, ScoreInputs)>,
+    config: &OpportunityScoringConfig,
+    quote_horizon: Duration,
+    latency_budget: Duration,
+) -> Vec<(T, OpportunityScore)> {
+    let half = Decimal::new(5, 1);
+    let best_profit = candidates.iter()
+        .map(|(_, inputs)| inputs.profit_usd)
+        .max()
+        .unwrap_or_default();
+
+    let mut ranked: Vec<_> = candidates.into_iter()
+        .map(|(candidate, inputs)| {
+            let profit = if best_profit > Decimal::ZERO {
+                (inputs.profit_usd / best_profit).max(Decimal::ZERO)
+            } else {
+                Decimal::ZERO
+            };
+            let reliability = Decimal::from(inputs.executed + 1) / Decimal::from(inputs.executed + inputs.failed + 2);
+            let freshness = inputs.quote_age.map_or(Decimal::ZERO, |age| remaining(age, quote_horizon));
+            let speed = inputs.latency.map_or(half, |latency| remaining(latency, latency_budget));
+            let total = config.profit_weight * profit
+                + config.reliability_weight * reliability
+                + config.freshness_weight * freshness
+                + config.latency_weight * speed;
+
+            (candidate, OpportunityScore { inputs, profit, reliability, freshness, speed, total })
+        })
+        .collect();
+
+    ranked.sort_by(|(_, a), (_, b)| b.total.cmp(&a.total).then(b.inputs.profit_usd.cmp(&a.inputs.profit_usd)));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn ranks_by_weighted_components() {
+        let config = OpportunityScoringConfig::default();
+        let (horizon, budget) = (Duration::from_secs(2), Duration::from_secs(2));
+        let inputs = |profit_usd: &str, executed: u64, failed: u64, age_ms: u64, latency_ms: Option<u64>| ScoreInputs {
+            profit_usd: dec(profit_usd),
+            executed,
+            failed,
+            quote_age: Some(Duration::from_millis(age_ms)),
+            latency: latency_ms.map(Duration::from_millis),
+        };
+        let order = |ranked: &[(&str, OpportunityScore)]| ranked.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+        
+        // 0.3% of $50k beats 2% of $200, even on a route with a weaker record
+        let ranked = rank(vec![
+            ("2% of $200", inputs("4", 3, 0, 100, Some(200))),
+            ("0.3% of $50k", inputs("150", 1, 1, 100, Some(200))),
+        ], &config, horizon, budget);
+        assert!(order(&ranked) == ["0.3% of $50k", "2% of $200"], "deep edge ranked {:?}", order(&ranked));
+        
+        // With profit level, a route that fills beats one that keeps failing, and fresh quotes beat old ones
+        let ranked = rank(vec![
+            ("failing", inputs("100", 0, 6, 100, None)),
+            ("filling", inputs("100", 6, 0, 100, None)),
+        ], &config, horizon, budget);
+        assert!(order(&ranked) == ["filling", "failing"], "reliability ranked {:?}", order(&ranked));
+        let ranked = rank(vec![
+            ("stale", inputs("100", 0, 0, 1900, Some(200))),
+            ("slow", inputs("100", 0, 0, 100, Some(1900))),
+            ("fresh", inputs("100", 0, 0, 100, Some(200))),
+        ], &config, horizon, budget);
+        assert!(order(&ranked).first() == Some(&"fresh"), "freshness and latency ranked {:?}", order(&ranked));
+        
+        // Components stay within 0..1 and the log line names each of them
+        let (_, score) = &ranked[0];
+        let unit = |value: Decimal| value >= Decimal::ZERO && value <= Decimal::ONE;
+        assert!([score.profit, score.reliability, score.freshness, score.speed].into_iter().all(unit),
+               "score components out of range: {:?}", score);
+        let explained = score.to_string();
+        assert!(["profit $100", "fills 0/0", "quote age 100ms", "latency 200ms"].iter().all(|part| explained.contains(part)),
+               "score explained as {:?}", explained);
+        
+        // Zero weights leave only what is weighted: here reliability alone, so the weaker route sinks despite its profit
+        let reliability_only = OpportunityScoringConfig {
+            profit_weight: Decimal::ZERO,
+            freshness_weight: Decimal::ZERO,
+            latency_weight: Decimal::ZERO,
+            ..config.clone()
+        };
+        let ranked = rank(vec![
+            ("profitable", inputs("1000", 0, 4, 100, None)),
+            ("reliable", inputs("10", 4, 0, 100, None)),
+        ], &reliability_only, horizon, budget);
+        assert!(order(&ranked) == ["reliable", "profitable"], "reliability weight alone ranked {:?}", order(&ranked));
+        
+    }
+}
@@ -0,0 +1,279 @@
+use rust_decimal::Decimal;
+
+use crate::models::{OrderBook, OrderBookLevel, PoolReserves, TradeSide};
+use crate::profit::{Bps, Leg, ProfitBreakdown};
+
+// Halvings of the size range, which pins a size far below any venue's step size
+const BISECTIONS: usize = 64;
+
+// Where one leg fills: walking a venue's order book, or along a constant-product pool's curve
+#[derive(Debug, Clone, Copy)]
+pub enum Liquidity<'a> {
+    Book(&'a OrderBook),
+    Pool(PoolReserves),
+}
+
+fn levels<'a>(book: &'a OrderBook, side: &TradeSide) -> &'a [OrderBookLevel] {
+    match side {
+        TradeSide::Buy => &book.asks,
+        TradeSide::Sell => &book.bids,
+    }
+}
+
+impl Liquidity<'_> {
+    // Average price of filling `size` base; None beyond the available depth
+    pub fn average_price(&self, side: &TradeSide, size: Decimal) -> Option<Decimal> {
+        match self {
+            Liquidity::Book(book) => book.effective_price(side, size),
+            Liquidity::Pool(_) if size <= Decimal::ZERO => None,
+            Liquidity::Pool(pool) => match side {
+                TradeSide::Buy => (size < pool.base).then(|| pool.quote / (pool.base - size)),
+                TradeSide::Sell => Some(pool.quote / (pool.base + size)),
+            },
+        }
+    }
+
+    // Price of the next unit once `size` has filled
+    pub fn marginal_price(&self, side: &TradeSide, size: Decimal) -> Option<Decimal> {
+        match self {
+            Liquidity::Book(book) => {
+                let mut cumulative = Decimal::ZERO;
+                levels(book, side).iter()
+                    .find(|level| {
+                        cumulative += level.quantity;
+                        cumulative > size
+                    })
+                    .map(|level| level.price)
+            },
+            Liquidity::Pool(pool) => match side {
+                TradeSide::Buy => (size < pool.base).then(|| {
+                    let remaining = pool.base - size;
+                    pool.quote / remaining * (pool.base / remaining)
+                }),
+                TradeSide::Sell => {
+                    let reserve = pool.base + size;
+                    Some(pool.quote / reserve * (pool.base / reserve))
+                },
+            },
+        }
+    }
+
+    // Most that fills within `max_slippage` of `reference`: the levels priced inside it on a book, the size
+    // whose average price reaches it on a pool
+    pub fn depth_within(&self, side: &TradeSide, reference: Decimal, max_slippage: Decimal) -> Decimal {
+        let limit = match side {
+            TradeSide::Buy => reference * (Decimal::ONE + max_slippage),
+            TradeSide::Sell => reference * (Decimal::ONE - max_slippage),
+        };
+
+        match (self, side) {
+            (Liquidity::Book(book), TradeSide::Buy) => book.asks.iter()
+                .take_while(|ask| ask.price <= limit)
+                .map(|ask| ask.quantity)
+                .sum(),
+            (Liquidity::Book(book), TradeSide::Sell) => book.bids.iter()
+                .take_while(|bid| bid.price >= limit)
+                .map(|bid| bid.quantity)
+                .sum(),
+            (Liquidity::Pool(_), TradeSide::Buy) if limit <= Decimal::ZERO => Decimal::ZERO,
+            (Liquidity::Pool(pool), TradeSide::Buy) => (pool.base - pool.quote / limit).max(Decimal::ZERO),
+            (Liquidity::Pool(_), TradeSide::Sell) if limit <= Decimal::ZERO => Decimal::MAX,
+            (Liquidity::Pool(pool), TradeSide::Sell) => (pool.quote / limit - pool.base).max(Decimal::ZERO),
+        }
+    }
+
+    // Sizes where the fill moves onto the next level; a pool's price moves smoothly and has none
+    fn breakpoints(&self, side: &TradeSide) -> Vec<Decimal> {
+        match self {
+            Liquidity::Book(book) => levels(book, side).iter()
+                .scan(Decimal::ZERO, |cumulative, level| {
+                    *cumulative += level.quantity;
+                    Some(*cumulative)
+                })
+                .collect(),
+            Liquidity::Pool(_) => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct OptimalSize {
+    pub size: Decimal,
+    pub buy_price: Decimal,
+    pub sell_price: Decimal,
+    // After fees and the fixed cost
+    pub net_profit: Decimal,
+    // What one more unit of base would add, in quote; still positive when a cap rather than the books bounds the size
+    pub marginal_profit: Decimal,
+}
+
+// Buying on one venue and selling on another, both priced in the buy venue's quote
+#[derive(Debug, Clone, Copy)]
+pub struct Route<'a> {
+    pub buy: Liquidity<'a>,
+    pub sell: Liquidity<'a>,
+    pub buy_fee: Bps,
+    pub sell_fee: Bps,
+}
+
+impl Route<'_> {
+    // Fees only; None beyond either side's depth
+    pub fn breakdown(&self, size: Decimal) -> Option<ProfitBreakdown> {
+        let buy_price = self.buy.average_price(&TradeSide::Buy, size)?;
+        let sell_price = self.sell.average_price(&TradeSide::Sell, size)?;
+        Some(ProfitBreakdown::compute(size, Leg::new(buy_price, self.buy_fee), Leg::new(sell_price, self.sell_fee), Decimal::ZERO, Decimal::ZERO))
+    }
+
+    pub fn marginal_profit(&self, size: Decimal) -> Option<Decimal> {
+        let buy_price = self.buy.marginal_price(&TradeSide::Buy, size)?;
+        let sell_price = self.sell.marginal_price(&TradeSide::Sell, size)?;
+        Some(sell_price - self.sell_fee.of(sell_price) - buy_price - self.buy_fee.of(buy_price))
+    }
+
+    fn sized(&self, size: Decimal, fixed_cost: Decimal) -> Option<OptimalSize> {
+        let breakdown = self.breakdown(size)?;
+        Some(OptimalSize {
+            size,
+            buy_price: breakdown.buy_notional / size,
+            sell_price: breakdown.sell_notional / size,
+            net_profit: breakdown.net - fixed_cost,
+            marginal_profit: self.marginal_profit(size).unwrap_or(Decimal::ZERO),
+        })
+    }
+
+    // Each unit bought costs at least as much as the last and each unit sold fetches at most as much, so the
+    // margin falls with size and profit is concave in it. The best size is where the marginal profit turns
+    // negative, or the largest one allowed by `max_size` and `min_margin`; None when no size pays for `fixed_cost`
+    pub fn solve(&self, max_size: Decimal, fixed_cost: Decimal, min_margin: Bps) -> Option<OptimalSize> {
+        let clears_margin = |size: Decimal| self.breakdown(size).map_or(false, |breakdown| breakdown.net_margin() > min_margin);
+        let limit = if clears_margin(max_size) { max_size } else { largest(max_size, clears_margin) };
+        if limit <= Decimal::ZERO {
+            return None;
+        }
+
+        let turn = largest(limit, |size| self.marginal_profit(size).map_or(false, |marginal| marginal > Decimal::ZERO));
+
+        // On a book the marginal profit steps down at level boundaries, which the bisection only approaches
+        let mut candidates = self.buy.breakpoints(&TradeSide::Buy);
+        candidates.extend(self.sell.breakpoints(&TradeSide::Sell));
+        candidates.retain(|size| *size < limit);
+        candidates.extend([turn, limit]);
+
+        candidates.into_iter()
+            .filter(|size| *size > Decimal::ZERO)
+            .filter_map(|size| self.sized(size, fixed_cost))
+            .max_by(|a, b| a.net_profit.cmp(&b.net_profit))
+            .filter(|best| best.net_profit > Decimal::ZERO)
+    }
+}
+
+// Largest size in (0, `upper`] that `holds` for, given it holds up to some point and not beyond; zero when it
+// fails everywhere
+fn largest(upper: Decimal, holds: impl Fn(Decimal) -> bool) -> Decimal {
+    let mut low = Decimal::ZERO;
+    let mut high = upper;
+    for _ in 0..BISECTIONS {
+        let middle = (low + high) / Decimal::TWO;
+        if middle == low || middle == high {
+            break;
+        }
+        if holds(middle) {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    low
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::prelude::FromPrimitive;
+    use std::str::FromStr;
+
+    use crate::models::TradingPair;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    fn synthetic_book(bids: &[(&str, &str)], asks: &[(&str, &str)]) -> OrderBook {
+        let levels = |levels: &[(&str, &str)]| levels.iter()
+            .map(|(price, quantity)| OrderBookLevel { price: dec(price), quantity: dec(quantity) })
+            .collect();
+        OrderBook {
+            exchange: "synthetic".to_string(),
+            pair: TradingPair::new("ETH", "USDT"),
+            bids: levels(bids),
+            asks: levels(asks),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    // Synthetic books and pools whose best size is known in closed form
+    #[test]
+    fn solves_closed_form_optima() {
+        let close = |actual: Decimal, expected: f64| Decimal::from_f64(expected)
+            .map_or(false, |expected| (actual - expected).abs() < dec("0.000001"));
+        let no_fee = Bps::ZERO;
+        
+        // Levels pay 4, 1 and then -3 per unit, so the optimum takes the first two: 2 ETH for 5 USDT
+        let books = synthetic_book(&[("104", "1"), ("102", "1"), ("100", "1")], &[("100", "1"), ("101", "1"), ("103", "1")]);
+        let book_route = Route { buy: Liquidity::Book(&books), sell: Liquidity::Book(&books), buy_fee: no_fee, sell_fee: no_fee };
+        let solved = book_route.solve(dec("3"), Decimal::ZERO, Bps::ZERO);
+        assert!(solved.map_or(false, |solved| solved.size == dec("2") && solved.net_profit == dec("5") && solved.marginal_profit == dec("-3")),
+               "book optimum solved as {:?}", solved);
+        
+        // A cap inside the second level binds with that level's 1 per unit still on the table
+        let capped = book_route.solve(dec("1.5"), Decimal::ZERO, Bps::ZERO);
+        assert!(capped.map_or(false, |capped| capped.size == dec("1.5") && close(capped.net_profit, 4.5) && capped.marginal_profit == dec("1")),
+               "capped book optimum solved as {:?}", capped);
+        
+        // Fixed gas comes off every size alike: 1 leaves the same size, 6 leaves nothing worth doing
+        let gassed = book_route.solve(dec("3"), dec("1"), Bps::ZERO);
+        assert!(gassed.map_or(false, |gassed| gassed.size == dec("2") && gassed.net_profit == dec("4")),
+               "book optimum after 1 USDT gas solved as {:?}", gassed);
+        let unprofitable = book_route.solve(dec("3"), dec("6"), Bps::ZERO);
+        assert!(unprofitable.is_none(), "6 USDT gas against a 5 USDT edge solved as {:?}", unprofitable);
+        
+        // A 3% minimum margin stops inside the second level, where (4 + t) / (100 + 101t) falls to 0.03
+        let margined = book_route.solve(dec("3"), Decimal::ZERO, Bps::from_percent(dec("3")));
+        assert!(margined.map_or(false, |margined| close(margined.size, 1.0 + 1.0 / 2.03)),
+               "3% margin floor solved as {:?}", margined);
+        
+        // Two pools at 1000 and 1210: marginal prices meet at y = (v * Ba - u * Bb) / (u + v), u = sqrt(Qa * Ba), v = sqrt(Qb * Bb)
+        let pools = Route {
+            buy: Liquidity::Pool(PoolReserves { base: dec("1000"), quote: dec("1000000") }),
+            sell: Liquidity::Pool(PoolReserves { base: dec("1000"), quote: dec("1210000") }),
+            buy_fee: no_fee,
+            sell_fee: no_fee,
+        };
+        let (u, v) = ((1e6f64 * 1e3).sqrt(), (1.21e6f64 * 1e3).sqrt());
+        let expected = (v * 1e3 - u * 1e3) / (u + v);
+        let solved = pools.solve(dec("500"), Decimal::ZERO, Bps::ZERO);
+        assert!(solved.map_or(false, |solved| close(solved.size, expected) && solved.marginal_profit.abs() < dec("0.0001")),
+               "pool optimum solved as {:?}, expected {:.6}", solved, expected);
+        
+        // Fees move the optimum in: buying pays 1.001 and selling keeps 0.997 of each marginal price
+        let fees = Route { buy_fee: Bps::from_fraction(dec("0.001")), sell_fee: Bps::from_fraction(dec("0.003")), ..pools };
+        let (u, v) = ((1.001 * 1e6f64 * 1e3).sqrt(), (0.997 * 1.21e6f64 * 1e3).sqrt());
+        let expected = (v * 1e3 - u * 1e3) / (u + v);
+        let solved = fees.solve(dec("500"), Decimal::ZERO, Bps::ZERO);
+        assert!(solved.map_or(false, |solved| close(solved.size, expected)),
+               "pool optimum with fees solved as {:?}, expected {:.6}", solved, expected);
+        
+        // Against a flat 110 bid the pool is bought until its marginal price QB / (B - y)^2 reaches 110
+        let flat = synthetic_book(&[("110", "100")], &[]);
+        let mixed = Route {
+            buy: Liquidity::Pool(PoolReserves { base: dec("1000"), quote: dec("100000") }),
+            sell: Liquidity::Book(&flat),
+            buy_fee: no_fee,
+            sell_fee: no_fee,
+        };
+        let expected = 1e3 - (1e5f64 * 1e3 / 110.0).sqrt();
+        let solved = mixed.solve(dec("100"), Decimal::ZERO, Bps::ZERO);
+        assert!(solved.map_or(false, |solved| close(solved.size, expected)),
+               "pool against a book solved as {:?}, expected {:.6}", solved, expected);
+    }
+}
@@ -0,0 +1,414 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use rand::Rng;
+use rust_decimal::prelude::*;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::arbitrage::ArbitrageBot;
+use crate::blockchain::BlockchainManager;
+use crate::config::Config;
+use crate::database::Database;
+use crate::errors::BotResult;
+use crate::exchanges::{Exchange, ExchangeManager, TradingFees};
+use crate::models::{Balance, OrderBook, OrderBookLevel, Price, Trade, TradeSide, TradeStatus, TradingPair};
+
+#[derive(Debug, Clone)]
+pub struct SoakConfig {
+    pub pairs: usize,
+    pub venues: usize,
+    pub duration: Duration,
+    pub tick_interval: Duration,
+    pub volatility: f64,
+    pub dislocation: f64,
+    pub max_cycle_duration: Duration,
+    pub max_memory_growth: f64,
+}
+
+impl Default for SoakConfig {
+    fn default() -> Self {
+        Self {
+            pairs: 200,
+            venues: 4,
+            duration: Duration::from_secs(3600),
+            tick_interval: Duration::from_millis(250),
+            volatility: 0.0005,
+            dislocation: 0.004,
+            max_cycle_duration: Duration::from_secs(10),
+            max_memory_growth: 0.25,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SoakReport {
+    pub cycles: u64,
+    pub ticks: u64,
+    pub min_cycle: Duration,
+    pub avg_cycle: Duration,
+    pub p99_cycle: Duration,
+    pub max_cycle: Duration,
+    pub opportunities_found: u64,
+    pub opportunities_persisted: u64,
+    pub database_rows: i64,
+    pub opportunity_updates: i64,
+    pub active_opportunities: usize,
+    pub rss_after_warmup_kb: Option<u64>,
+    pub rss_final_kb: Option<u64>,
+    pub violations: Vec<String>,
+}
+
+// A uniform draw from -spread..spread; a spread of zero or less leaves the price where it is
+fn jitter(rng: &mut impl Rng, spread: f64) -> f64 {
+    if spread > 0.0 {
+        rng.gen_range(-spread..spread)
+    } else {
+        0.0
+    }
+}
+
+struct SyntheticMarket {
+    mids: RwLock<HashMap<String, f64>>,
+}
+
+impl SyntheticMarket {
+    fn new(pairs: &[TradingPair]) -> Self {
+        let mut rng = rand::thread_rng();
+        let mids = pairs.iter()
+            .map(|pair| (pair.symbol.clone(), rng.gen_range(0.5..5000.0)))
+            .collect();
+        
+        Self {
+            mids: RwLock::new(mids),
+        }
+    }
+
+    fn step(&self, volatility: f64) {
+        let mut rng = rand::thread_rng();
+        let mut mids = self.mids.write().unwrap();
+        
+        for mid in mids.values_mut() {
+            *mid *= 1.0 + jitter(&mut rng, volatility);
+        }
+    }
+
+    fn mid(&self, symbol: &str) -> Option<f64> {
+        self.mids.read().unwrap().get(symbol).copied()
+    }
+}
+
+struct SyntheticExchange {
+    name: String,
+    market: Arc<SyntheticMarket>,
+    pairs: Vec<TradingPair>,
+    dislocation: f64,
+}
+
+impl SyntheticExchange {
+    fn quote(&self, pair: &TradingPair) -> Result<(Decimal, Decimal)> {
+        let mid = self.market.mid(&pair.symbol)
+            .ok_or_else(|| anyhow::anyhow!("Unknown synthetic pair: {}", pair.symbol))?;
+        let mid = mid * (1.0 + jitter(&mut rand::thread_rng(), self.dislocation));
+        
+        let bid = Decimal::from_f64(mid * 0.9995).unwrap_or_default().round_dp(8);
+        let ask = Decimal::from_f64(mid * 1.0005).unwrap_or_default().round_dp(8);
+        Ok((bid, ask))
+    }
+
+    fn filled_trade(&self, pair: &TradingPair, side: TradeSide, amount: Decimal, price: Decimal) -> Trade {
+        Trade {
+            id: uuid::Uuid::new_v4(),
+            opportunity_id: uuid::Uuid::nil(),
+            exchange: self.name.clone(),
+            pair: pair.clone(),
+            side,
+            amount,
+            price,
+            fee_amount: Decimal::ZERO,
+            fee_asset: None,
+            status: TradeStatus::Executed,
+            created_at: Utc::now(),
+            executed_at: Some(Utc::now()),
+            order_id: None,
+            client_order_id: None,
+            tx_hash: None,
+            simulated: false,
+            failure_reason: None,
+        }
+    }
+}
+
+#[async_trait]
+impl Exchange for SyntheticExchange {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn get_price(&self, pair: &TradingPair) -> BotResult<Price> {
+        let (bid, ask) = self.quote(pair)?;
+        
+        Ok(Price {
+            exchange: self.name.clone(),
+            pair: pair.clone(),
+            bid,
+            ask,
+            timestamp: Utc::now(),
+            volume_24h: None,
+            fetch_latency: None,
+        })
+    }
+
+    async fn get_order_book(&self, pair: &TradingPair, depth: usize) -> BotResult<OrderBook> {
+        let (bid, ask) = self.quote(pair)?;
+        let step = Decimal::from_str("0.0002")?;
+        
+        let bids = (0..depth)
+            .map(|i| OrderBookLevel {
+                price: bid * (Decimal::ONE - step * Decimal::from(i)),
+                quantity: Decimal::ONE,
+            })
+            .collect();
+        let asks = (0..depth)
+            .map(|i| OrderBookLevel {
+                price: ask * (Decimal::ONE + step * Decimal::from(i)),
+                quantity: Decimal::ONE,
+            })
+            .collect();
+        
+        Ok(OrderBook {
+            exchange: self.name.clone(),
+            pair: pair.clone(),
+            bids,
+            asks,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_balances(&self) -> BotResult<HashMap<String, Balance>> {
+        Ok(HashMap::new())
+    }
+
+    async fn place_buy_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        let price = match price {
+            Some(price) => price,
+            None => self.quote(pair)?.1,
+        };
+        Ok(self.filled_trade(pair, TradeSide::Buy, amount, price))
+    }
+
+    async fn place_sell_order(&self, pair: &TradingPair, amount: Decimal, price: Option<Decimal>) -> BotResult<Trade> {
+        let price = match price {
+            Some(price) => price,
+            None => self.quote(pair)?.0,
+        };
+        Ok(self.filled_trade(pair, TradeSide::Sell, amount, price))
+    }
+
+    async fn get_order_status(&self, order_id: &str) -> BotResult<Trade> {
+        Err(anyhow::anyhow!("Synthetic exchange does not track orders: {}", order_id).into())
+    }
+
+    async fn cancel_order(&self, _order_id: &str) -> BotResult<()> {
+        Ok(())
+    }
+
+    fn supports_pair(&self, pair: &TradingPair) -> bool {
+        self.pairs.iter().any(|p| p.symbol == pair.symbol)
+    }
+
+    async fn get_supported_pairs(&self) -> BotResult<Vec<TradingPair>> {
+        Ok(self.pairs.clone())
+    }
+
+    async fn get_trading_fees(&self, _pair: &TradingPair) -> BotResult<TradingFees> {
+        Ok(TradingFees {
+            maker_fee: Decimal::from_str("0.001")?,
+            taker_fee: Decimal::from_str("0.001")?,
+        })
+    }
+}
+
+fn build_config(soak: &SoakConfig, pairs: &[TradingPair]) -> Result<Config> {
+    let pair_list = pairs.iter()
+        .map(|pair| format!("\"{}\"", pair.symbol))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut toml = String::from("database_url = \"sqlite::memory:\"\n\n");
+
+    for venue in 0..soak.venues {
+        toml.push_str(&format!(
+            "[exchanges.synthetic{venue}]\n\
+             name = \"synthetic{venue}\"\n\
+             api_key = \"\"\n\
+             api_secret = \"\"\n\
+             api_url = \"http://localhost\"\n\
+             enabled = true\n\
+             trading_pairs = [{pair_list}]\n\
+             min_trade_amount = \"0\"\n\
+             max_trade_amount = \"10\"\n\n"
+        ));
+    }
+
+    toml.push_str(
+        "[trading]\n\
+         min_profit_threshold = \"0.1\"\n\
+         max_slippage = \"0.005\"\n\
+         check_interval_seconds = 1\n\
+         max_concurrent_trades = 3\n\n\
+         [trading.risk_management]\n\
+         max_portfolio_exposure = \"1000\"\n\
+         stop_loss_percentage = \"5\"\n\
+         position_size_limit = \"100\"\n"
+    );
+
+    // Dry-run fills draw on a simulated ledger, so give every venue enough inventory to never run dry
+    for venue in 0..soak.venues {
+        toml.push_str(&format!("\n[trading.paper_balances.synthetic{venue}]\nUSDT = \"1000000000\"\n"));
+        for pair in pairs {
+            toml.push_str(&format!("{} = \"1000000\"\n", pair.base));
+        }
+    }
+
+    Ok(toml::from_str(&toml)?)
+}
+
+fn resident_memory_kb() -> Option<u64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    Some(pages * 4)
+}
+
+pub async fn run(soak: SoakConfig) -> Result<SoakReport> {
+    let pairs: Vec<TradingPair> = (0..soak.pairs)
+        .map(|i| TradingPair::new(&format!("SYN{}", i), "USDT"))
+        .collect();
+
+    let config = build_config(&soak, &pairs)?;
+    let market = Arc::new(SyntheticMarket::new(&pairs));
+
+    let mut exchange_manager = ExchangeManager::new();
+    for venue in 0..soak.venues {
+        exchange_manager.add_exchange(Box::new(SyntheticExchange {
+            name: format!("synthetic{}", venue),
+            market: market.clone(),
+            pairs: pairs.clone(),
+            dislocation: soak.dislocation,
+        }));
+    }
+
+    let database = Database::new(&config.database_url).await?;
+    database.migrate().await?;
+    let blockchain_manager = BlockchainManager::new(&config.blockchain).await?;
+
+    let mut bot = ArbitrageBot::with_components(config, exchange_manager, blockchain_manager, database);
+    bot.set_dry_run(true);
+
+    let walker_market = market.clone();
+    let tick_interval = soak.tick_interval;
+    let volatility = soak.volatility;
+    let ticks = Arc::new(std::sync::atomic::AtomicU64::new(0));
+    let walker_ticks = ticks.clone();
+    let walker = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(tick_interval);
+        loop {
+            interval.tick().await;
+            walker_market.step(volatility);
+            walker_ticks.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        }
+    });
+
+    let started = Instant::now();
+    let warmup = soak.duration / 10;
+    let mut cycle_times = Vec::new();
+    let mut report = SoakReport::default();
+
+    while started.elapsed() < soak.duration {
+        let cycle_start = Instant::now();
+        
+        match tokio::time::timeout(soak.max_cycle_duration * 3, bot.run_cycle()).await {
+            Ok(Ok(())) => {},
+            Ok(Err(e)) => report.violations.push(format!("cycle {} failed: {}", cycle_times.len(), e)),
+            Err(_) => {
+                report.violations.push(format!(
+                    "cycle {} did not finish within {:?} (possible deadlock)",
+                    cycle_times.len(),
+                    soak.max_cycle_duration * 3
+                ));
+                break;
+            }
+        }
+        
+        let elapsed = cycle_start.elapsed();
+        cycle_times.push(elapsed);
+        
+        if report.rss_after_warmup_kb.is_none() && started.elapsed() >= warmup {
+            report.rss_after_warmup_kb = resident_memory_kb();
+        }
+        
+        if cycle_times.len() % 100 == 0 {
+            info!("Soak progress: {} cycles, {:?} elapsed, last cycle {:?}",
+                  cycle_times.len(), started.elapsed(), elapsed);
+        }
+    }
+
+    walker.abort();
+
+    report.rss_final_kb = resident_memory_kb();
+    report.ticks = ticks.load(std::sync::atomic::Ordering::Relaxed);
+    report.cycles = cycle_times.len() as u64;
+
+    if !cycle_times.is_empty() {
+        let total: Duration = cycle_times.iter().sum();
+        report.avg_cycle = total / cycle_times.len() as u32;
+        
+        cycle_times.sort();
+        report.min_cycle = cycle_times[0];
+        report.max_cycle = cycle_times[cycle_times.len() - 1];
+        report.p99_cycle = cycle_times[(cycle_times.len() - 1) * 99 / 100];
+    }
+
+    let stats = bot.stats();
+    report.opportunities_found = stats.opportunities_found;
+    report.opportunities_persisted = stats.opportunities_persisted;
+    report.database_rows = bot.database().count_opportunities().await?;
+    report.opportunity_updates = bot.database().count_opportunity_updates().await?;
+    report.active_opportunities = bot.active_opportunity_count();
+
+    if report.p99_cycle > soak.max_cycle_duration {
+        report.violations.push(format!(
+            "p99 cycle time {:?} exceeds bound {:?}", report.p99_cycle, soak.max_cycle_duration
+        ));
+    }
+
+    // Every save either started a row or updated the active one on its route
+    if report.database_rows + report.opportunity_updates != report.opportunities_persisted as i64 {
+        report.violations.push(format!(
+            "database has {} opportunity rows and {} updates but {} were persisted",
+            report.database_rows, report.opportunity_updates, report.opportunities_persisted
+        ));
+    }
+
+    let max_routes = soak.pairs * soak.venues * soak.venues.saturating_sub(1);
+    if report.active_opportunities > max_routes {
+        report.violations.push(format!(
+            "{} active opportunities exceeds the {} possible routes",
+            report.active_opportunities, max_routes
+        ));
+    }
+
+    if let (Some(baseline), Some(final_rss)) = (report.rss_after_warmup_kb, report.rss_final_kb) {
+        let limit = baseline as f64 * (1.0 + soak.max_memory_growth);
+        if final_rss as f64 > limit {
+            report.violations.push(format!(
+                "resident memory grew from {} kB to {} kB (limit {:.0} kB)",
+                baseline, final_rss, limit
+            ));
+        }
+    }
+
+    Ok(report)
+}
@@ -0,0 +1,291 @@
+use chrono::{DateTime, DurationRound, Timelike, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Mutex, RwLock};
+
+use crate::config::SpreadHistoryConfig;
+use crate::models::{RouteSpreadStats, SpreadBucket, SpreadCause};
+
+// Pair, buy exchange, sell exchange
+pub type RouteKey = (String, String, String);
+
+// Running sums of net spreads, which merge exactly across hours and survive downsampling
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Moments {
+    pub samples: u64,
+    pub sum: f64,
+    pub sum_squares: f64,
+}
+
+impl Moments {
+    pub fn add(&mut self, net_spread: f64) {
+        self.samples += 1;
+        self.sum += net_spread;
+        self.sum_squares += net_spread * net_spread;
+    }
+
+    pub fn merge(&mut self, other: &Moments) {
+        self.samples += other.samples;
+        self.sum += other.sum;
+        self.sum_squares += other.sum_squares;
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        self.sum / self.samples as f64
+    }
+
+    // Sample standard deviation; rounding can leave the variance slightly negative for a flat spread
+    pub fn stddev(&self) -> f64 {
+        if self.samples < 2 {
+            return 0.0;
+        }
+        let n = self.samples as f64;
+        ((self.sum_squares - self.sum * self.sum / n) / (n - 1.0)).max(0.0).sqrt()
+    }
+}
+
+impl From<&SpreadBucket> for Moments {
+    fn from(bucket: &SpreadBucket) -> Self {
+        Self {
+            samples: bucket.samples.max(0) as u64,
+            sum: bucket.net_sum,
+            sum_squares: bucket.net_sum_squares,
+        }
+    }
+}
+
+// Rolling per-route spread statistics over the alert window, kept as hourly sums
+pub struct SpreadTracker {
+    config: RwLock<SpreadHistoryConfig>,
+    routes: Mutex<HashMap<RouteKey, VecDeque<(DateTime<Utc>, Moments)>>>,
+}
+
+impl SpreadTracker {
+    pub fn new(config: SpreadHistoryConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            routes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn set_config(&self, config: SpreadHistoryConfig) {
+        *self.config.write().unwrap() = config;
+    }
+
+    pub fn config(&self) -> SpreadHistoryConfig {
+        self.config.read().unwrap().clone()
+    }
+
+    // Buckets must be in hour order, as the database returns them
+    pub fn restore(&self, buckets: &[SpreadBucket]) {
+        let mut routes = self.routes.lock().unwrap();
+        for bucket in buckets {
+            let key = (bucket.pair.clone(), bucket.buy_exchange.clone(), bucket.sell_exchange.clone());
+            let hours = routes.entry(key).or_default();
+            match hours.back_mut() {
+                Some((hour, moments)) if *hour == bucket.hour => moments.merge(&Moments::from(bucket)),
+                _ => hours.push_back((bucket.hour, Moments::from(bucket))),
+            }
+        }
+    }
+
+    // Adds the sample and returns the window it was judged against when it lies more than `alert_deviations`
+    // standard deviations above the mean, once the window holds `min_samples`
+    pub fn observe(&self, route: &RouteKey, net_spread: f64, now: DateTime<Utc>) -> Option<Moments> {
+        let config = self.config.read().unwrap();
+        let window_start = now - chrono::Duration::hours(config.window_hours as i64);
+        let hour = now.duration_trunc(chrono::Duration::hours(1)).unwrap_or(now);
+
+        let mut routes = self.routes.lock().unwrap();
+        let hours = routes.entry(route.clone()).or_default();
+        while hours.front().map_or(false, |(start, _)| *start + chrono::Duration::hours(1) <= window_start) {
+            hours.pop_front();
+        }
+
+        let mut baseline = Moments::default();
+        for (_, moments) in hours.iter() {
+            baseline.merge(moments);
+        }
+
+        match hours.back_mut() {
+            Some((start, moments)) if *start == hour => moments.add(net_spread),
+            _ => {
+                let mut moments = Moments::default();
+                moments.add(net_spread);
+                hours.push_back((hour, moments));
+            },
+        }
+
+        let deviations = config.alert_deviations.to_f64().unwrap_or(f64::MAX);
+        let stddev = baseline.stddev();
+        (baseline.samples >= config.min_samples && stddev > 0.0 && net_spread > baseline.mean() + deviations * stddev)
+            .then_some(baseline)
+    }
+}
+
+// Compares both legs' mids with the median of `others`, the venues outside the route. A leg that accounts for at
+// least half of the `excess` (percent above the mean) on its own is off; only one leg being off points at its data
+// rather than the market
+pub fn diagnose(buy: (&str, Option<f64>), sell: (&str, Option<f64>), others: &[f64], excess: f64) -> SpreadCause {
+    if others.is_empty() {
+        return SpreadCause::Unconfirmed;
+    }
+    let mut others = others.to_vec();
+    others.sort_by(f64::total_cmp);
+    let middle = others.len() / 2;
+    let median = if others.len() % 2 == 0 { (others[middle - 1] + others[middle]) / 2.0 } else { others[middle] };
+
+    let deviation = |mid: Option<f64>| mid.map(|mid| (mid - median) / median * 100.0);
+    let off = |deviation: Option<f64>| deviation.map_or(false, |deviation| deviation.abs() >= excess / 2.0);
+    let (buy_deviation, sell_deviation) = (deviation(buy.1), deviation(sell.1));
+    match (off(buy_deviation), off(sell_deviation)) {
+        (true, false) => SpreadCause::BadQuote { exchange: buy.0.to_string(), deviation_percentage: buy_deviation.unwrap_or_default() },
+        (false, true) => SpreadCause::BadQuote { exchange: sell.0.to_string(), deviation_percentage: sell_deviation.unwrap_or_default() },
+        _ => SpreadCause::Dislocation,
+    }
+}
+
+// Per-route report over the buckets, widest mean spread first. The recent figures cover buckets from
+// `recent_since` on
+pub fn summarize(buckets: &[SpreadBucket], recent_since: DateTime<Utc>) -> Vec<RouteSpreadStats> {
+    #[derive(Default)]
+    struct Totals {
+        all: Moments,
+        recent: Moments,
+        above_threshold: i64,
+        by_hour: BTreeMap<u32, Moments>,
+    }
+
+    let mut routes: HashMap<RouteKey, Totals> = HashMap::new();
+    for bucket in buckets {
+        let key = (bucket.pair.clone(), bucket.buy_exchange.clone(), bucket.sell_exchange.clone());
+        let totals = routes.entry(key).or_default();
+        let moments = Moments::from(bucket);
+        totals.all.merge(&moments);
+        if bucket.hour >= recent_since {
+            totals.recent.merge(&moments);
+        }
+        totals.above_threshold += bucket.above_threshold;
+        totals.by_hour.entry(bucket.hour.hour()).or_default().merge(&moments);
+    }
+
+    let mut stats: Vec<RouteSpreadStats> = routes.into_iter()
+        .filter(|(_, totals)| totals.all.samples > 0)
+        .map(|((pair, buy_exchange, sell_exchange), totals)| {
+            let best_hour = totals.by_hour.iter()
+                .filter(|(_, moments)| moments.samples > 0)
+                .max_by(|a, b| a.1.mean().total_cmp(&b.1.mean()));
+            let recent = (totals.recent.samples > 0).then_some(totals.recent);
+
+            RouteSpreadStats {
+                pair,
+                buy_exchange,
+                sell_exchange,
+                samples: totals.all.samples as i64,
+                mean: totals.all.mean(),
+                stddev: totals.all.stddev(),
+                recent_mean: recent.map(|moments| moments.mean()),
+                recent_stddev: recent.map(|moments| moments.stddev()),
+                above_threshold_percentage: totals.above_threshold as f64 / totals.all.samples as f64 * 100.0,
+                best_hour: best_hour.map(|(hour, _)| *hour),
+                best_hour_mean: best_hour.map(|(_, moments)| moments.mean()),
+            }
+        })
+        .collect();
+
+    stats.sort_by(|a, b| b.mean.total_cmp(&a.mean).then_with(|| a.pair.cmp(&b.pair)));
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(actual: f64, expected: f64) -> bool {
+        (actual - expected).abs() < 1e-9
+    }
+
+    fn spread_bucket(route: (&str, &str), hour: chrono::DateTime<chrono::Utc>, spreads: &[f64], above_threshold: i64) -> SpreadBucket {
+        SpreadBucket {
+            pair: "ETH/USDT".to_string(),
+            buy_exchange: route.0.to_string(),
+            sell_exchange: route.1.to_string(),
+            hour,
+            samples: spreads.len() as i64,
+            net_sum: spreads.iter().sum(),
+            net_sum_squares: spreads.iter().map(|spread| spread * spread).sum(),
+            above_threshold,
+        }
+    }
+
+    // 60 samples alternating 0.1% and 0.3% put the mean at 0.2% and the stddev just over 0.1%
+    #[test]
+    fn alerts_on_spikes_against_the_rolling_window() {
+        let tracker = SpreadTracker::new(SpreadHistoryConfig { min_samples: 50, ..SpreadHistoryConfig::default() });
+        let start = chrono::Utc::now().duration_trunc(chrono::Duration::hours(1)).unwrap() - chrono::Duration::hours(30);
+        let route = ("ETH/USDT".to_string(), "alpha".to_string(), "beta".to_string());
+        let quiet = (0..60).filter_map(|i| tracker.observe(&route, if i % 2 == 0 { 0.1 } else { 0.3 }, start + chrono::Duration::seconds(i))).count();
+        assert!(quiet == 0, "{} alerts fired on an ordinary spread", quiet);
+        let alert = tracker.observe(&route, 1.0, start + chrono::Duration::minutes(5));
+        assert!(alert.map_or(false, |baseline| baseline.samples == 60 && close(baseline.mean(), 0.2)),
+               "a 1% spike against a 0.2% mean alerted with {:?}", alert);
+        let modest = tracker.observe(&route, 0.5, start + chrono::Duration::minutes(6));
+        assert!(modest.is_none(), "a 0.5% spread alerted with {:?}", modest);
+        // A day later the window has emptied and has too few samples to judge by
+        let expired = tracker.observe(&route, 5.0, start + chrono::Duration::hours(26));
+        assert!(expired.is_none(), "a spike against an expired window alerted with {:?}", expired);
+        
+        let young = ("ETH/USDT".to_string(), "beta".to_string(), "alpha".to_string());
+        for i in 0..10 {
+            tracker.observe(&young, if i % 2 == 0 { 0.1 } else { 0.3 }, start + chrono::Duration::seconds(i));
+        }
+        let gated = tracker.observe(&young, 5.0, start + chrono::Duration::minutes(1));
+        assert!(gated.is_none(), "a route with 10 samples alerted with {:?}", gated);
+    }
+
+    // alpha -> beta: 0.1 and 0.3 at 03:00, 0.5 and 0.7 at 14:00, three of them above the threshold
+    #[test]
+    fn summarizes_routes_and_hours() {
+        let day = chrono::NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+        let at = |hour: u32| day.and_hms_opt(hour, 0, 0).unwrap().and_utc();
+        let buckets = vec![
+            spread_bucket(("alpha", "beta"), at(3), &[0.1, 0.3], 1),
+            spread_bucket(("beta", "alpha"), at(3), &[-0.1, 0.1], 0),
+            spread_bucket(("alpha", "beta"), at(14), &[0.5, 0.7], 2),
+        ];
+        let stats = summarize(&buckets, at(14));
+        match stats.as_slice() {
+            [widest, narrowest] => {
+                assert!(widest.buy_exchange == "alpha" && widest.samples == 4 && close(widest.mean, 0.4)
+                          && close(widest.stddev, (0.2f64 / 3.0).sqrt()) && close(widest.above_threshold_percentage, 75.0),
+                       "alpha -> beta summarized as {:?}", widest);
+                assert!(widest.best_hour == Some(14) && widest.best_hour_mean.map_or(false, |mean| close(mean, 0.6))
+                          && widest.recent_mean.map_or(false, |mean| close(mean, 0.6))
+                          && widest.recent_stddev.map_or(false, |stddev| close(stddev, 0.02f64.sqrt())),
+                       "alpha -> beta hours summarized as {:?}", widest);
+                assert!(narrowest.buy_exchange == "beta" && close(narrowest.mean, 0.0) && narrowest.recent_mean.is_none()
+                          && narrowest.best_hour == Some(3) && close(narrowest.above_threshold_percentage, 0.0),
+                       "beta -> alpha summarized as {:?}", narrowest);
+            },
+            _ => panic!("summarized {} routes instead of 2", stats.len()),
+        }
+    }
+
+    // Other venues at 999-1001: one leg 3% off is bad data, both legs off or nobody to ask is not
+    #[test]
+    fn diagnoses_bad_quotes() {
+        let others = [1000.0, 1001.0, 999.0];
+        match diagnose(("alpha", Some(1000.2)), ("beta", Some(1030.0)), &others, 2.5) {
+            SpreadCause::BadQuote { exchange, deviation_percentage } => assert!(exchange == "beta" && close(deviation_percentage, 3.0),
+                                                                                 "blamed {} at {:.3}%", exchange, deviation_percentage),
+            cause => panic!("a lone 3% outlier was diagnosed as {:?}", cause),
+        }
+        let both = diagnose(("alpha", Some(970.0)), ("beta", Some(1030.0)), &others, 5.0);
+        assert!(matches!(both, SpreadCause::Dislocation), "both legs moving was diagnosed as {:?}", both);
+        let alone = diagnose(("alpha", Some(1000.0)), ("beta", Some(1030.0)), &[], 2.5);
+        assert!(matches!(alone, SpreadCause::Unconfirmed), "a route without witnesses was diagnosed as {:?}", alone);
+    }
+}
@@ -0,0 +1,224 @@
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::{get, patch, post};
+use axum::Router;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tracing::warn;
+
+use crate::control::{ControlCommand, ControlHandle, RuntimeSettings, TradingAdjustment};
+use crate::cooldown::RouteCooldowns;
+use crate::database::Database;
+use crate::exchanges::health::{HealthState, HealthTracker};
+use crate::models::ArbitrageOpportunity;
+
+// Written by the main loop, read by the status server
+#[derive(Debug, Default)]
+pub struct Heartbeat {
+    last_tick: Mutex<Option<DateTime<Utc>>>,
+    last_scan: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl Heartbeat {
+    pub fn tick(&self) {
+        *self.last_tick.lock().unwrap() = Some(Utc::now());
+    }
+    
+    pub fn scanned(&self) {
+        *self.last_scan.lock().unwrap() = Some(Utc::now());
+    }
+    
+    pub fn last_tick(&self) -> Option<DateTime<Utc>> {
+        *self.last_tick.lock().unwrap()
+    }
+    
+    pub fn last_scan(&self) -> Option<DateTime<Utc>> {
+        *self.last_scan.lock().unwrap()
+    }
+}
+
+#[derive(Clone)]
+pub struct StatusState {
+    pub started_at: Instant,
+    pub settings: watch::Receiver<RuntimeSettings>,
+    pub max_tick_age: Duration,
+    pub heartbeat: Arc<Heartbeat>,
+    pub kill_switch: Arc<watch::Sender<bool>>,
+    pub health: Arc<HealthTracker>,
+    pub active_opportunities: Arc<RwLock<HashMap<String, ArbitrageOpportunity>>>,
+    pub route_cooldowns: Arc<RouteCooldowns>,
+    pub database: Database,
+    // Set when control.api_token is configured, which mounts the /control endpoints
+    pub control: Option<ControlHandle>,
+}
+
+#[derive(Debug, Serialize)]
+struct StatusReport {
+    uptime_seconds: u64,
+    #[serde(flatten)]
+    settings: RuntimeSettings,
+    halted: bool,
+    last_tick: Option<DateTime<Utc>>,
+    last_scan: Option<DateTime<Utc>>,
+    active_opportunities: usize,
+    exchanges: Vec<ExchangeStatus>,
+    cooled_routes: Vec<RouteStatus>,
+}
+
+#[derive(Debug, Serialize)]
+struct ExchangeStatus {
+    exchange: String,
+    state: &'static str,
+    consecutive_failures: u32,
+    total_failures: u64,
+    total_calls: u64,
+    average_latency_ms: Option<f64>,
+    p95_latency_ms: Option<f64>,
+    max_latency_ms: Option<f64>,
+    order_latency_ms: Option<f64>,
+    last_error: Option<String>,
+    cooldown_remaining_seconds: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct RouteStatus {
+    route: String,
+    consecutive_failures: u32,
+    cooled_until: Option<DateTime<Utc>>,
+    cooldown_remaining_seconds: Option<f64>,
+    last_error: Option<String>,
+}
+
+pub fn router(state: StatusState) -> Router {
+    let mut router = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/status", get(status))
+        .route("/opportunities", get(opportunities));
+    if state.control.is_some() {
+        router = router
+            .route("/control/pause", post(pause))
+            .route("/control/resume", post(resume))
+            .route("/control/dry-run", post(dry_run))
+            .route("/control/trading", patch(trading));
+    }
+    router.with_state(state)
+}
+
+pub async fn serve(listener: TcpListener, state: StatusState) {
+    if let Err(e) = axum::serve(listener, router(state)).await {
+        warn!("Status server stopped: {}", e);
+    }
+}
+
+// 200 while the main loop is ticking and the database answers, 503 with the reason otherwise
+async fn healthz(State(state): State<StatusState>) -> Response {
+    let stale = match state.heartbeat.last_tick() {
+        Some(last_tick) => (Utc::now() - last_tick).to_std().unwrap_or_default() > state.max_tick_age,
+        None => state.started_at.elapsed() > state.max_tick_age,
+    };
+    if stale {
+        return (StatusCode::SERVICE_UNAVAILABLE, "main loop has not ticked recently\n").into_response();
+    }
+    if let Err(e) = state.database.ping().await {
+        return (StatusCode::SERVICE_UNAVAILABLE, format!("database unreachable: {}\n", e)).into_response();
+    }
+    
+    (StatusCode::OK, "ok\n").into_response()
+}
+
+fn millis(latency: Duration) -> f64 {
+    latency.as_secs_f64() * 1000.0
+}
+
+async fn status(State(state): State<StatusState>) -> Json<StatusReport> {
+    let exchanges = state.health.snapshot().into_iter()
+        .map(|health| ExchangeStatus {
+            exchange: health.exchange,
+            state: match health.state {
+                HealthState::Healthy => "healthy",
+                HealthState::Cooldown => "cooldown",
+                HealthState::Probing => "probing",
+            },
+            consecutive_failures: health.consecutive_failures,
+            total_failures: health.total_failures,
+            total_calls: health.total_calls,
+            average_latency_ms: health.average_latency.map(millis),
+            p95_latency_ms: health.p95_latency.map(millis),
+            max_latency_ms: health.max_latency.map(millis),
+            order_latency_ms: health.order_latency.map(millis),
+            last_error: health.last_error,
+            cooldown_remaining_seconds: health.cooldown_remaining.map(|remaining| remaining.as_secs_f64()),
+        })
+        .collect();
+    let now = Utc::now();
+    let cooled_routes = state.route_cooldowns.cooling(now).into_iter()
+        .map(|failure| RouteStatus {
+            cooldown_remaining_seconds: failure.cooled_until
+                .and_then(|until| (until - now).to_std().ok())
+                .map(|remaining| remaining.as_secs_f64()),
+            route: failure.route,
+            consecutive_failures: failure.consecutive_failures,
+            cooled_until: failure.cooled_until,
+            last_error: failure.last_error,
+        })
+        .collect();
+    
+    Json(StatusReport {
+        uptime_seconds: state.started_at.elapsed().as_secs(),
+        settings: state.settings.borrow().clone(),
+        halted: *state.kill_switch.borrow(),
+        last_tick: state.heartbeat.last_tick(),
+        last_scan: state.heartbeat.last_scan(),
+        active_opportunities: state.active_opportunities.read().unwrap().len(),
+        exchanges,
+        cooled_routes,
+    })
+}
+
+async fn opportunities(State(state): State<StatusState>) -> Json<HashMap<String, ArbitrageOpportunity>> {
+    Json(state.active_opportunities.read().unwrap().clone())
+}
+
+async fn pause(State(state): State<StatusState>, headers: HeaderMap) -> Response {
+    control(&state, &headers, ControlCommand::Pause).await
+}
+
+async fn resume(State(state): State<StatusState>, headers: HeaderMap) -> Response {
+    control(&state, &headers, ControlCommand::Resume).await
+}
+
+async fn dry_run(State(state): State<StatusState>, headers: HeaderMap, body: String) -> Response {
+    let enabled = match body.trim() {
+        "on" => true,
+        "off" => false,
+        other => return (StatusCode::BAD_REQUEST, format!("expected on or off, got {:?}\n", other)).into_response(),
+    };
+    control(&state, &headers, ControlCommand::DryRun(enabled)).await
+}
+
+async fn trading(State(state): State<StatusState>, headers: HeaderMap, Json(adjustment): Json<TradingAdjustment>) -> Response {
+    control(&state, &headers, ControlCommand::Trading(adjustment)).await
+}
+
+// Waits for the main loop to apply the command, so the reply reflects what actually changed
+async fn control(state: &StatusState, headers: &HeaderMap, command: ControlCommand) -> Response {
+    let Some(handle) = &state.control else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let authorization = headers.get(header::AUTHORIZATION).and_then(|value| value.to_str().ok());
+    if !handle.authorized(authorization) {
+        warn!("Rejected unauthorized control request: {}", command);
+        return (StatusCode::UNAUTHORIZED, "missing or invalid bearer token\n").into_response();
+    }
+    
+    match handle.send(command).await {
+        Ok(outcome) => (StatusCode::OK, format!("{}\n", outcome)).into_response(),
+        Err(reason) => (StatusCode::BAD_REQUEST, format!("{}\n", reason)).into_response(),
+    }
+}
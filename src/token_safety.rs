@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::config::TokenSafetyConfig;
+use crate::exchanges::TokenProbe;
+use crate::models::BlacklistedToken;
+
+// A proxy can be pointed at new code at any time, so a pass for a proxied token is only good for this long
+const PROXY_RECHECK_INTERVAL: Duration = Duration::from_secs(3600);
+
+enum Verdict {
+    Passed { checked_at: Instant, proxied: bool },
+    Blacklisted(BlacklistedToken),
+}
+
+pub enum TokenStatus {
+    Safe,
+    Blacklisted(String),
+    // Not probed yet, or a proxied token whose pass has run out
+    Unknown,
+}
+
+// Verdicts of the pre-trade token probe per (chain id, symbol). Passes live in memory only, so tokens are
+// probed again after a restart and connectors relearn their transfer taxes; blacklistings are persisted
+pub struct TokenSafety {
+    config: RwLock<TokenSafetyConfig>,
+    verdicts: Mutex<HashMap<(u64, String), Verdict>>,
+}
+
+impl TokenSafety {
+    pub fn new(config: TokenSafetyConfig) -> Self {
+        Self {
+            config: RwLock::new(config),
+            verdicts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn set_config(&self, config: TokenSafetyConfig) {
+        *self.config.write().unwrap() = config;
+    }
+
+    pub fn restore(&self, tokens: Vec<BlacklistedToken>) {
+        let mut verdicts = self.verdicts.lock().unwrap();
+        for token in tokens {
+            verdicts.insert((token.chain_id, token.token.to_uppercase()), Verdict::Blacklisted(token));
+        }
+    }
+
+    pub fn probe_amount(&self) -> Decimal {
+        self.config.read().unwrap().probe_amount
+    }
+
+    // Trusted tokens pass even when blacklisted, so adding one to the list lifts a blacklisting
+    pub fn status(&self, chain_id: u64, token: &str) -> TokenStatus {
+        {
+            let config = self.config.read().unwrap();
+            if !config.enabled || config.is_trusted(token) {
+                return TokenStatus::Safe;
+            }
+        }
+
+        match self.verdicts.lock().unwrap().get(&(chain_id, token.to_uppercase())) {
+            Some(Verdict::Blacklisted(blacklisted)) => TokenStatus::Blacklisted(blacklisted.reason.clone()),
+            Some(Verdict::Passed { checked_at, proxied }) if !*proxied || checked_at.elapsed() < PROXY_RECHECK_INTERVAL => TokenStatus::Safe,
+            _ => TokenStatus::Unknown,
+        }
+    }
+
+    // Returns the blacklisting for persisting when the token failed. Venues that cannot simulate report
+    // no probe, and their tokens pass unchecked
+    pub fn record(&self, chain_id: u64, token: &str, exchange: &str, probe: Option<&TokenProbe>, now: DateTime<Utc>) -> Option<BlacklistedToken> {
+        let key = (chain_id, token.to_uppercase());
+        let reason = probe.and_then(|probe| self.failure(probe));
+        let mut verdicts = self.verdicts.lock().unwrap();
+
+        let Some(reason) = reason else {
+            let proxied = probe.map_or(false, |probe| probe.implementation.is_some());
+            if let Some(probe) = probe.filter(|probe| !probe.buy_tax.is_zero() || !probe.sell_tax.is_zero()) {
+                info!("{} on chain {} charges {}% on buys and {}% on sells; trading it as a fee-on-transfer token",
+                      token, chain_id, probe.buy_tax * Decimal::from(100), probe.sell_tax * Decimal::from(100));
+            }
+            verdicts.insert(key, Verdict::Passed { checked_at: Instant::now(), proxied });
+            return None;
+        };
+
+        let blacklisted = BlacklistedToken {
+            chain_id,
+            token: token.to_string(),
+            exchange: exchange.to_string(),
+            reason,
+            blacklisted_at: now,
+        };
+        warn!("Blacklisted {} on chain {} after probing it on {}: {}", token, chain_id, exchange, blacklisted.reason);
+        verdicts.insert(key, Verdict::Blacklisted(blacklisted.clone()));
+        Some(blacklisted)
+    }
+
+    fn failure(&self, probe: &TokenProbe) -> Option<String> {
+        let max_transfer_tax = self.config.read().unwrap().max_transfer_tax;
+        let behind = probe.implementation.as_ref()
+            .map(|implementation| format!(" (proxy to {})", implementation))
+            .unwrap_or_default();
+
+        if let Some(reason) = &probe.buy_revert {
+            return Some(format!("buying it reverts{}: {}", behind, reason));
+        }
+        if let Some(reason) = &probe.sell_revert {
+            return Some(format!("selling it reverts{}: {}", behind, reason));
+        }
+        if probe.buy_tax > max_transfer_tax || probe.sell_tax > max_transfer_tax {
+            return Some(format!("transfer tax of {}% on buys and {}% on sells{} exceeds {}%",
+                                probe.buy_tax * Decimal::from(100), probe.sell_tax * Decimal::from(100),
+                                behind, max_transfer_tax * Decimal::from(100)));
+        }
+        None
+    }
+}
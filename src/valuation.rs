@@ -0,0 +1,183 @@
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tracing::{debug, warn};
+
+use crate::config::ValuationConfig;
+use crate::exchanges::ExchangeManager;
+use crate::exchanges::Exchange;
+use crate::models::{Balance, BalanceReport, Portfolio, TradingPair};
+
+// Prices held assets in USD from their stable-quoted pairs on every enabled exchange
+pub struct ValuationService {
+    stable_quotes: Vec<String>,
+}
+
+impl ValuationService {
+    pub fn new(config: &ValuationConfig) -> Self {
+        Self {
+            stable_quotes: config.stable_quotes.iter().map(|quote| quote.to_uppercase()).collect(),
+        }
+    }
+
+    fn is_stable(&self, asset: &str) -> bool {
+        asset == "USD" || self.stable_quotes.iter().any(|quote| quote == asset)
+    }
+
+    // Median of the mids across venues, so a single stale or bad quote cannot move the valuation
+    pub async fn usd_price(&self, manager: &ExchangeManager, asset: &str) -> Option<Decimal> {
+        if self.is_stable(asset) {
+            return Some(Decimal::ONE);
+        }
+        
+        let mut mids = Vec::new();
+        for quote in &self.stable_quotes {
+            let pair = TradingPair::new(asset, quote);
+            match manager.get_all_prices(&pair).await {
+                Ok(prices) => mids.extend(prices.iter()
+                    .filter(|price| price.bid > Decimal::ZERO && price.ask > Decimal::ZERO)
+                    .map(|price| (price.bid + price.ask) / Decimal::from(2))),
+                Err(e) => debug!("No {} quotes for valuation: {}", pair.symbol, e),
+            }
+        }
+        
+        median(mids)
+    }
+
+    // Fills in `usd_value` and returns the total; assets without a USD quote are valued at zero
+    pub async fn value_balances(&self, manager: &ExchangeManager, balances: &mut HashMap<String, Balance>) -> Decimal {
+        let mut prices: HashMap<String, Option<Decimal>> = HashMap::new();
+        let mut total = Decimal::ZERO;
+        
+        for balance in balances.values_mut() {
+            if balance.total.is_zero() {
+                balance.usd_value = Decimal::ZERO;
+                continue;
+            }
+            
+            let price = match prices.get(&balance.asset) {
+                Some(price) => *price,
+                None => {
+                    let price = self.usd_price(manager, &balance.asset).await;
+                    prices.insert(balance.asset.clone(), price);
+                    price
+                }
+            };
+            
+            match price {
+                Some(price) => {
+                    balance.usd_value = balance.total * price;
+                    total += balance.usd_value;
+                },
+                None => {
+                    balance.usd_value = Decimal::ZERO;
+                    debug!("No USD price for {}, left out of the portfolio value", balance.asset);
+                }
+            }
+        }
+        
+        total
+    }
+
+    // Balances are summed per asset across every exchange that has account access
+    pub async fn value_portfolio(&self, manager: &ExchangeManager) -> Result<Portfolio> {
+        let mut balances: HashMap<String, Balance> = HashMap::new();
+        
+        for exchange in manager.get_all_exchanges() {
+            if !manager.can_execute(exchange.name()) {
+                continue;
+            }
+            
+            let result = manager.call(
+                exchange.name(), "get_balances", manager.timeouts().account(),
+                exchange.get_balances(),
+            ).await;
+            let exchange_balances = match result {
+                Ok(exchange_balances) => exchange_balances,
+                Err(e) => {
+                    warn!("Failed to fetch {} balances for valuation: {}", exchange.name(), e);
+                    continue;
+                }
+            };
+            
+            for (asset, balance) in &exchange_balances {
+                accumulate(&mut balances, asset, balance);
+            }
+        }
+        
+        let total_value_usd = self.value_balances(manager, &mut balances).await;
+        
+        Ok(Portfolio {
+            total_value_usd,
+            balances,
+            updated_at: Utc::now(),
+        })
+    }
+
+    // Like `value_portfolio` but keeps each exchange's holdings apart. The exchanges are queried
+    // concurrently and one that fails is recorded in the report rather than failing it.
+    pub async fn balance_report(&self, manager: &ExchangeManager, exchanges: &[&dyn Exchange]) -> BalanceReport {
+        let fetches = exchanges.iter().map(|exchange| async move {
+            let result = manager.call(
+                exchange.name(), "get_balances", manager.timeouts().account(),
+                exchange.get_balances(),
+            ).await;
+            (exchange.name().to_string(), result)
+        });
+        
+        let mut report = BalanceReport::default();
+        for (name, result) in futures::future::join_all(fetches).await {
+            match result {
+                Ok(balances) => {
+                    for (asset, balance) in &balances {
+                        accumulate(&mut report.totals, asset, balance);
+                    }
+                    report.exchanges.insert(name, balances);
+                },
+                Err(e) => {
+                    report.failures.insert(name, e.to_string());
+                }
+            }
+        }
+        
+        // Each venue's share of an asset is valued at the same price as the total
+        report.total_value_usd = self.value_balances(manager, &mut report.totals).await;
+        for balances in report.exchanges.values_mut() {
+            for balance in balances.values_mut() {
+                balance.usd_value = report.totals.get(&balance.asset)
+                    .filter(|total| !total.total.is_zero())
+                    .map_or(Decimal::ZERO, |total| total.usd_value * balance.total / total.total);
+            }
+        }
+        
+        report
+    }
+}
+
+fn accumulate(totals: &mut HashMap<String, Balance>, asset: &str, balance: &Balance) {
+    let entry = totals.entry(asset.to_string()).or_insert_with(|| Balance {
+        asset: asset.to_string(),
+        free: Decimal::ZERO,
+        locked: Decimal::ZERO,
+        total: Decimal::ZERO,
+        usd_value: Decimal::ZERO,
+    });
+    entry.free += balance.free;
+    entry.locked += balance.locked;
+    entry.total += balance.total;
+}
+
+fn median(mut values: Vec<Decimal>) -> Option<Decimal> {
+    if values.is_empty() {
+        return None;
+    }
+
+    values.sort();
+    let middle = values.len() / 2;
+    if values.len() % 2 == 0 {
+        Some((values[middle - 1] + values[middle]) / Decimal::from(2))
+    } else {
+        Some(values[middle])
+    }
+}